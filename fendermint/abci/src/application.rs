@@ -7,7 +7,8 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
-use tendermint::abci::{request, response, Request, Response};
+use tendermint::abci::{request, response};
+use tendermint::{v0_37, v0_38};
 use tower::Service;
 use tower_abci::BoxError;
 
@@ -25,6 +26,10 @@ pub type AbciResult<T> = std::result::Result<T, BoxError>;
 /// Asynchronous equivalent of of [tendermint_abci::Application].
 ///
 /// See the [spec](https://github.com/tendermint/tendermint/blob/v0.37.0-rc2/spec/abci) for the expected behaviour.
+///
+/// The same trait serves both ABCI 1.0 (CometBFT 0.37) and ABCI 2.0 (CometBFT 0.38):
+/// the former calls `begin_block`, `deliver_tx` and `end_block`, the latter calls
+/// `finalize_block` instead, and adds `extend_vote` and `verify_vote_extension`.
 #[allow(unused_variables)]
 #[async_trait]
 pub trait Application {
@@ -96,6 +101,45 @@ pub trait Application {
         Ok(Default::default())
     }
 
+    /// Deliver a decided block in one go; this replaces `begin_block`, `deliver_tx` and `end_block` in ABCI 2.0.
+    ///
+    /// Unlike in ABCI 1.0 the application hash has to be returned here instead of in `commit`.
+    ///
+    /// See the [spec](https://github.com/cometbft/cometbft/blob/v0.38.x/spec/abci/abci++_methods.md#finalizeblock).
+    async fn finalize_block(
+        &self,
+        request: request::FinalizeBlock,
+    ) -> AbciResult<response::FinalizeBlock> {
+        Ok(response::FinalizeBlock {
+            events: Vec::new(),
+            tx_results: vec![Default::default(); request.txs.len()],
+            validator_updates: Vec::new(),
+            consensus_param_updates: None,
+            app_hash: Default::default(),
+        })
+    }
+
+    /// Opportunity for the application to attach data to the precommit vote of the validator.
+    ///
+    /// See the [spec](https://github.com/cometbft/cometbft/blob/v0.38.x/spec/abci/abci++_methods.md#extendvote).
+    async fn extend_vote(&self, request: request::ExtendVote) -> AbciResult<response::ExtendVote> {
+        Ok(response::ExtendVote {
+            vote_extension: Default::default(),
+        })
+    }
+
+    /// Check the vote extension attached to the precommit vote of another validator.
+    ///
+    /// Rejecting an extension means rejecting the vote, so this should only be done if it's malformed.
+    ///
+    /// See the [spec](https://github.com/cometbft/cometbft/blob/v0.38.x/spec/abci/abci++_methods.md#verifyvoteextension).
+    async fn verify_vote_extension(
+        &self,
+        request: request::VerifyVoteExtension,
+    ) -> AbciResult<response::VerifyVoteExtension> {
+        Ok(response::VerifyVoteExtension::Accept)
+    }
+
     /// Commit the current state at the current height.
     async fn commit(&self) -> AbciResult<response::Commit> {
         Ok(Default::default())
@@ -132,23 +176,17 @@ pub trait Application {
 }
 
 /// Wrapper to adapt an `Application` to a `tower::Service`.
+///
+/// It implements the service for the request types of both CometBFT 0.37 and 0.38;
+/// the server decides which protocol version is spoken.
 pub struct ApplicationService<A: Application + Sync + Send + Clone + 'static>(pub A);
 
-impl<A> Service<Request> for ApplicationService<A>
+impl<A> ApplicationService<A>
 where
     A: Application + Sync + Send + Clone + 'static,
 {
-    type Response = Response;
-    type Error = BoxError;
-    type Future = Pin<Box<dyn Future<Output = Result<Response, BoxError>> + Send + 'static>>;
-
-    /// At this level the application is always ready to receive requests.
-    /// Throttling is handled in the layers added on top of it.
-    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
-    }
-
-    fn call(&mut self, req: Request) -> Self::Future {
+    /// Take the application out to be moved into an async boxed future.
+    fn take_app(&mut self) -> A {
         // Must make sure this is a cheap clone, required so the app can be moved into the async boxed future.
         // See https://tokio.rs/blog/2021-05-14-inventing-the-service-trait
         // The alternative is to perform the operation synchronously right here,
@@ -158,7 +196,28 @@ where
 
         // Another trick to avoid any subtle bugs is the mem::replace.
         // See https://github.com/tower-rs/tower/issues/547
-        let app: A = std::mem::replace(&mut self.0, app);
+        std::mem::replace(&mut self.0, app)
+    }
+}
+
+impl<A> Service<v0_37::abci::Request> for ApplicationService<A>
+where
+    A: Application + Sync + Send + Clone + 'static,
+{
+    type Response = v0_37::abci::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, BoxError>> + Send + 'static>>;
+
+    /// At this level the application is always ready to receive requests.
+    /// Throttling is handled in the layers added on top of it.
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: v0_37::abci::Request) -> Self::Future {
+        use v0_37::abci::{Request, Response};
+
+        let app = self.take_app();
 
         // Because this is async, make sure the `Consensus` service is wrapped in a concurrency limiting Tower layer.
         let res = async move {
@@ -200,6 +259,69 @@ where
     }
 }
 
+impl<A> Service<v0_38::abci::Request> for ApplicationService<A>
+where
+    A: Application + Sync + Send + Clone + 'static,
+{
+    type Response = v0_38::abci::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, BoxError>> + Send + 'static>>;
+
+    /// At this level the application is always ready to receive requests.
+    /// Throttling is handled in the layers added on top of it.
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: v0_38::abci::Request) -> Self::Future {
+        use v0_38::abci::{Request, Response};
+
+        let app = self.take_app();
+
+        // Because this is async, make sure the `Consensus` service is wrapped in a concurrency limiting Tower layer.
+        let res = async move {
+            let res = match req {
+                Request::Echo(r) => Response::Echo(log_error(app.echo(r).await)?),
+                Request::Info(r) => Response::Info(log_error(app.info(r).await)?),
+                Request::InitChain(r) => Response::InitChain(log_error(app.init_chain(r).await)?),
+                Request::Query(r) => Response::Query(log_error(app.query(r).await)?),
+                Request::CheckTx(r) => Response::CheckTx(log_error(app.check_tx(r).await)?),
+                Request::PrepareProposal(r) => {
+                    Response::PrepareProposal(log_error(app.prepare_proposal(r).await)?)
+                }
+                Request::ProcessProposal(r) => {
+                    Response::ProcessProposal(log_error(app.process_proposal(r).await)?)
+                }
+                Request::ExtendVote(r) => {
+                    Response::ExtendVote(log_error(app.extend_vote(r).await)?)
+                }
+                Request::VerifyVoteExtension(r) => {
+                    Response::VerifyVoteExtension(log_error(app.verify_vote_extension(r).await)?)
+                }
+                Request::FinalizeBlock(r) => {
+                    Response::FinalizeBlock(log_error(app.finalize_block(r).await)?)
+                }
+                Request::Commit => Response::Commit(log_error(app.commit().await)?),
+                Request::ListSnapshots => {
+                    Response::ListSnapshots(log_error(app.list_snapshots().await)?)
+                }
+                Request::OfferSnapshot(r) => {
+                    Response::OfferSnapshot(log_error(app.offer_snapshot(r).await)?)
+                }
+                Request::LoadSnapshotChunk(r) => {
+                    Response::LoadSnapshotChunk(log_error(app.load_snapshot_chunk(r).await)?)
+                }
+                Request::ApplySnapshotChunk(r) => {
+                    Response::ApplySnapshotChunk(log_error(app.apply_snapshot_chunk(r).await)?)
+                }
+                Request::Flush => panic!("Flush should be handled by the Server!"),
+            };
+            Ok(res)
+        };
+        res.boxed()
+    }
+}
+
 fn log_error<T>(res: AbciResult<T>) -> AbciResult<T> {
     if let Err(ref e) = res {
         tracing::error!("failed to execute ABCI request: {e:#}");
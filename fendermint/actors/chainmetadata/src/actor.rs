@@ -7,12 +7,12 @@ use fil_actors_runtime::builtin::singletons::SYSTEM_ACTOR_ADDR;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::ActorDowncast;
 use fil_actors_runtime::ActorError;
-use fil_actors_runtime::Array;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::error::ExitCode;
 
 use crate::{
-    BlockHash, ConstructorParams, Method, PushBlockParams, State, CHAINMETADATA_ACTOR_NAME,
+    BlockHashEntry, ConstructorParams, GetBlockHashesParams, Method, PushBlockParams, State,
+    CHAINMETADATA_ACTOR_NAME,
 };
 
 fil_actors_runtime::wasm_trampoline!(Actor);
@@ -36,36 +36,32 @@ impl Actor {
         rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
 
         rt.transaction(|st: &mut State, rt| {
-            // load the blockhashes AMT
-            let mut blockhashes = Array::load(&st.blockhashes, rt.store()).map_err(|e| {
+            st.push_block_hash(rt.store(), params.epoch, params.block)
+                .map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to push blockhash")
+                })
+        })?;
+
+        Ok(())
+    }
+
+    fn set_lookback_len(rt: &impl Runtime, lookback_len: u64) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&SYSTEM_ACTOR_ADDR))?;
+
+        if lookback_len == 0 {
+            return Err(actor_error!(
+                illegal_argument,
+                "lookback length must be positive"
+            ));
+        }
+
+        rt.transaction(|st: &mut State, rt| {
+            st.set_lookback_len(rt.store(), lookback_len).map_err(|e| {
                 e.downcast_default(
                     ExitCode::USR_ILLEGAL_STATE,
-                    "failed to load blockhashes states",
+                    "failed to change lookback length",
                 )
-            })?;
-
-            // push the block to the AMT
-            blockhashes.set(params.epoch as u64, params.block).unwrap();
-
-            // remove the oldest block if the AMT is full (note that this assume the
-            // for_each_while iterates in order, which it seems to do)
-            if blockhashes.count() > st.lookback_len {
-                let mut first_idx = 0;
-                blockhashes
-                    .for_each_while(|i, _: &BlockHash| {
-                        first_idx = i;
-                        Ok(false)
-                    })
-                    .unwrap();
-                blockhashes.delete(first_idx).unwrap();
-            }
-
-            // save the new blockhashes AMT cid root
-            st.blockhashes = blockhashes.flush().map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save blockhashes")
-            })?;
-
-            Ok(())
+            })
         })?;
 
         Ok(())
@@ -85,6 +81,27 @@ impl Actor {
         st.get_block_hash(rt.store(), epoch)
             .map_err(|e| e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to get blockhash"))
     }
+
+    fn get_block_hashes(
+        rt: &impl Runtime,
+        params: GetBlockHashesParams,
+    ) -> Result<Vec<BlockHashEntry>, ActorError> {
+        let st: State = rt.state()?;
+
+        if params.to < params.from {
+            return Err(actor_error!(
+                illegal_argument,
+                "invalid epoch range {}..={}",
+                params.from,
+                params.to
+            ));
+        }
+
+        st.get_block_hashes(rt.store(), params.from, params.to)
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to get blockhashes")
+            })
+    }
 }
 
 impl ActorCode for Actor {
@@ -99,5 +116,7 @@ impl ActorCode for Actor {
         PushBlockHash => push_block_hash,
         LookbackLen => lookback_len,
         GetBlockHash => get_block_hash,
+        GetBlockHashes => get_block_hashes,
+        SetLookbackLen => set_lookback_len,
     }
 }
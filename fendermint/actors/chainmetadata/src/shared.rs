@@ -71,6 +71,112 @@ impl State {
             )),
         }
     }
+
+    // returns the blockhashes stored for the epochs in the inclusive range,
+    // skipping the epochs which have no blockhash (null rounds or pruned)
+    pub fn get_block_hashes<BS: Blockstore>(
+        &self,
+        store: &BS,
+        from: ChainEpoch,
+        to: ChainEpoch,
+    ) -> anyhow::Result<Vec<BlockHashEntry>> {
+        if from < 0 || to < from {
+            return Err(anyhow::anyhow!("invalid epoch range {}..={}", from, to));
+        }
+
+        let blockhashes = self.load_blockhashes(store)?;
+
+        // start iterating at the beginning of the range instead of the oldest stored epoch
+        let mut entries = Vec::new();
+        blockhashes
+            .for_each_while_ranged(Some(from as u64), None, |i, hash: &BlockHash| {
+                let epoch = i as ChainEpoch;
+                if epoch > to {
+                    return Ok(false);
+                }
+                entries.push(BlockHashEntry {
+                    epoch,
+                    block: *hash,
+                });
+                Ok(true)
+            })
+            .map_err(|e| anyhow::anyhow!("failed to iterate blockhashes, error: {}", e))?;
+
+        Ok(entries)
+    }
+
+    // pushes the blockhash of an epoch and removes the oldest ones beyond `lookback_len`
+    pub fn push_block_hash<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        epoch: ChainEpoch,
+        block: BlockHash,
+    ) -> anyhow::Result<()> {
+        let mut blockhashes = self.load_blockhashes(store)?;
+
+        blockhashes
+            .set(epoch as u64, block)
+            .map_err(|e| anyhow::anyhow!("failed to set blockhash at epoch {}: {}", epoch, e))?;
+
+        self.prune_and_flush(blockhashes)
+    }
+
+    // changes the number of blockhashes kept, removing the oldest ones if it shrinks
+    pub fn set_lookback_len<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        lookback_len: u64,
+    ) -> anyhow::Result<()> {
+        if lookback_len == 0 {
+            return Err(anyhow::anyhow!("lookback length must be positive"));
+        }
+        self.lookback_len = lookback_len;
+        let blockhashes = self.load_blockhashes(store)?;
+        self.prune_and_flush(blockhashes)
+    }
+
+    fn load_blockhashes<'a, BS: Blockstore>(
+        &self,
+        store: &'a BS,
+    ) -> anyhow::Result<Amt<BlockHash, &'a BS>> {
+        Amt::load(&self.blockhashes, store).map_err(|e| {
+            anyhow::anyhow!(
+                "failed to load blockhashes from AMT cid {}, error: {}",
+                self.blockhashes,
+                e
+            )
+        })
+    }
+
+    fn prune_and_flush<BS: Blockstore>(
+        &mut self,
+        mut blockhashes: Amt<BlockHash, BS>,
+    ) -> anyhow::Result<()> {
+        // remove the oldest blocks while the AMT is over capacity (note that this assume the
+        // for_each_while iterates in order, which it seems to do)
+        let excess = blockhashes.count().saturating_sub(self.lookback_len);
+        if excess > 0 {
+            let mut oldest = Vec::new();
+            blockhashes
+                .for_each_while(|i, _: &BlockHash| {
+                    oldest.push(i);
+                    Ok((oldest.len() as u64) < excess)
+                })
+                .map_err(|e| anyhow::anyhow!("failed to iterate blockhashes: {}", e))?;
+
+            for i in oldest {
+                blockhashes
+                    .delete(i)
+                    .map_err(|e| anyhow::anyhow!("failed to delete blockhash {}: {}", i, e))?;
+            }
+        }
+
+        self.blockhashes = blockhashes
+            .flush()
+            .map_err(|e| anyhow::anyhow!("failed to save blockhashes: {}", e))?;
+
+        Ok(())
+    }
 }
 
 pub const CHAINMETADATA_ACTOR_NAME: &str = "chainmetadata";
@@ -94,6 +200,20 @@ pub struct PushBlockParams {
     pub block: BlockHash,
 }
 
+#[derive(Default, Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct GetBlockHashesParams {
+    // the first epoch of the range, inclusive
+    pub from: ChainEpoch,
+    // the last epoch of the range, inclusive
+    pub to: ChainEpoch,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct BlockHashEntry {
+    pub epoch: ChainEpoch,
+    pub block: BlockHash,
+}
+
 #[derive(FromPrimitive)]
 #[repr(u64)]
 pub enum Method {
@@ -101,4 +221,108 @@ pub enum Method {
     PushBlockHash = frc42_dispatch::method_hash!("PushBlockHash"),
     LookbackLen = frc42_dispatch::method_hash!("LookbackLen"),
     GetBlockHash = frc42_dispatch::method_hash!("GetBlockHash"),
+    GetBlockHashes = frc42_dispatch::method_hash!("GetBlockHashes"),
+    SetLookbackLen = frc42_dispatch::method_hash!("SetLookbackLen"),
+}
+
+#[cfg(test)]
+mod tests {
+    use fvm_ipld_blockstore::MemoryBlockstore;
+    use fvm_shared::clock::ChainEpoch;
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+
+    use super::{BlockHash, BlockHashEntry, State};
+
+    /// Epochs to push, increasing but with gaps for null rounds.
+    #[derive(Debug, Clone)]
+    struct Pushes(Vec<BlockHashEntry>);
+
+    impl Arbitrary for Pushes {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let n = usize::arbitrary(g) % 100;
+            let mut epoch: ChainEpoch = 0;
+            let mut pushes = Vec::new();
+            for _ in 0..n {
+                epoch += 1 + ChainEpoch::from(u8::arbitrary(g) % 3);
+                let block: BlockHash = std::array::from_fn(|_| u8::arbitrary(g));
+                pushes.push(BlockHashEntry { epoch, block });
+            }
+            Self(pushes)
+        }
+    }
+
+    fn push_all(state: &mut State, store: &MemoryBlockstore, pushes: &[BlockHashEntry]) {
+        for p in pushes {
+            state.push_block_hash(store, p.epoch, p.block).unwrap();
+        }
+    }
+
+    fn all_hashes(state: &State, store: &MemoryBlockstore) -> Vec<BlockHashEntry> {
+        state.get_block_hashes(store, 0, ChainEpoch::MAX).unwrap()
+    }
+
+    /// The window slides over the pushed epochs, keeping the most recent ones.
+    #[quickcheck]
+    fn prop_keeps_most_recent(pushes: Pushes, lookback_len: u8) -> bool {
+        let lookback_len = u64::from(lookback_len % 20) + 1;
+        let store = MemoryBlockstore::default();
+        let mut state = State::new(&store, lookback_len).unwrap();
+
+        push_all(&mut state, &store, &pushes.0);
+
+        let keep = pushes.0.len().min(lookback_len as usize);
+        let expected = pushes.0[pushes.0.len() - keep..].to_vec();
+
+        all_hashes(&state, &store) == expected
+    }
+
+    /// Shrinking the lookback prunes the oldest hashes, growing it keeps everything.
+    #[quickcheck]
+    fn prop_set_lookback_len_prunes(pushes: Pushes, before: u8, after: u8) -> bool {
+        let before = u64::from(before % 20) + 1;
+        let after = u64::from(after % 20) + 1;
+        let store = MemoryBlockstore::default();
+        let mut state = State::new(&store, before).unwrap();
+
+        push_all(&mut state, &store, &pushes.0);
+        let kept = all_hashes(&state, &store);
+
+        state.set_lookback_len(&store, after).unwrap();
+
+        let keep = kept.len().min(after as usize);
+        let expected = kept[kept.len() - keep..].to_vec();
+
+        state.lookback_len == after && all_hashes(&state, &store) == expected
+    }
+
+    /// Range queries return exactly the stored hashes within the range.
+    #[quickcheck]
+    fn prop_range_query(pushes: Pushes, from: u8, len: u8) -> bool {
+        let store = MemoryBlockstore::default();
+        let mut state = State::new(&store, 1000).unwrap();
+
+        push_all(&mut state, &store, &pushes.0);
+
+        let from = ChainEpoch::from(from);
+        let to = from + ChainEpoch::from(len);
+        let expected = pushes
+            .0
+            .iter()
+            .filter(|p| p.epoch >= from && p.epoch <= to)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        state.get_block_hashes(&store, from, to).unwrap() == expected
+            && expected
+                .iter()
+                .all(|p| state.get_block_hash(&store, p.epoch).unwrap() == Some(p.block))
+    }
+
+    #[test]
+    fn invalid_range() {
+        let store = MemoryBlockstore::default();
+        let state = State::new(&store, 10).unwrap();
+        assert!(state.get_block_hashes(&store, 5, 4).is_err());
+    }
 }
@@ -5,11 +5,13 @@ use fil_actor_eam::{EamActor, Method};
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::ActorError;
-use fil_actors_runtime::EAM_ACTOR_ID;
+use fil_actors_runtime::{EAM_ACTOR_ID, SYSTEM_ACTOR_ID};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::{DAG_CBOR, IPLD_RAW};
 use fvm_shared::address::Address;
+use fvm_shared::event::{ActorEvent, Entry, Flags};
 use fvm_shared::{ActorID, MethodNum};
 use num_derive::FromPrimitive;
 
@@ -31,15 +33,23 @@ pub struct IPCEamActor;
 #[repr(u64)]
 pub enum ExtraMethods {
     UpdateDeployers = frc42_dispatch::method_hash!("UpdateDeployers"),
+    AddDeployers = frc42_dispatch::method_hash!("AddDeployers"),
+    RemoveDeployers = frc42_dispatch::method_hash!("RemoveDeployers"),
+    SetPermissionMode = frc42_dispatch::method_hash!("SetPermissionMode"),
+    ListDeployers = frc42_dispatch::method_hash!("ListDeployers"),
+    ChangeAdmin = frc42_dispatch::method_hash!("ChangeAdmin"),
 }
 
+/// Name of the event emitted when the deployers or the permission mode change.
+pub const PERMISSIONS_CHANGED_EVENT: &str = "eam_permissions_changed";
+
 impl IPCEamActor {
     /// Creates the actor. If the `whitelisted_deployers` is empty, that means there is no restriction
     /// for deployment, i.e any address can deploy.
     pub fn constructor(rt: &impl Runtime, args: ConstructorParams) -> Result<(), ActorError> {
         EamActor::constructor(rt)?;
 
-        let st = State::new(rt.store(), args.permission_mode)?;
+        let st = State::new(rt.store(), args.permission_mode, args.admin)?;
         rt.create(&st)?;
 
         Ok(())
@@ -78,23 +88,142 @@ impl IPCEamActor {
             )));
         };
 
-        // Check that the caller is in the allowlist.
+        // Check that the caller is in the allowlist or is the admin.
         let caller_id = rt.message().caller().id().unwrap();
-        if !state.can_deploy(rt, caller_id)? {
+        if !state.can_deploy(rt, caller_id)? && !state.is_admin(rt, caller_id) {
             return Err(ActorError::forbidden(String::from(
                 "sender not allowed to update deployers",
             )));
         }
 
         // Perform the update.
+        let params = PermissionModeParams::AllowList(deployers);
         rt.transaction(|st: &mut State, rt| {
-            st.permission_mode =
-                State::new(rt.store(), PermissionModeParams::AllowList(deployers))?.permission_mode;
+            st.permission_mode = State::permission_mode(rt.store(), params.clone())?;
             Ok(())
         })?;
 
+        Self::emit_permissions_changed(rt, "replace", &params)
+    }
+
+    fn add_deployers(rt: &impl Runtime, deployers: Vec<Address>) -> Result<(), ActorError> {
+        Self::ensure_admin(rt)?;
+
+        rt.transaction(|st: &mut State, rt| st.update_allow_list(rt.store(), &deployers, &[]))?;
+
+        Self::emit_permissions_changed(rt, "add", &PermissionModeParams::AllowList(deployers))
+    }
+
+    fn remove_deployers(rt: &impl Runtime, deployers: Vec<Address>) -> Result<(), ActorError> {
+        Self::ensure_admin(rt)?;
+
+        rt.transaction(|st: &mut State, rt| st.update_allow_list(rt.store(), &[], &deployers))?;
+
+        Self::emit_permissions_changed(rt, "remove", &PermissionModeParams::AllowList(deployers))
+    }
+
+    /// Switch between unrestricted and allow-list modes, replacing any existing allow-list.
+    fn set_permission_mode(
+        rt: &impl Runtime,
+        params: PermissionModeParams,
+    ) -> Result<(), ActorError> {
+        Self::ensure_admin(rt)?;
+
+        rt.transaction(|st: &mut State, rt| {
+            st.permission_mode = State::permission_mode(rt.store(), params.clone())?;
+            Ok(())
+        })?;
+
+        Self::emit_permissions_changed(rt, "set", &params)
+    }
+
+    fn list_deployers(rt: &impl Runtime) -> Result<PermissionModeParams, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let state: State = rt.state()?;
+        state.permission_mode_params(rt.store())
+    }
+
+    /// Hand over the administration to another account, or renounce it with `None`.
+    ///
+    /// Besides the current admin, the system actor may also call it, so that an upgrade can
+    /// install an admin on a chain which was launched without one.
+    fn change_admin(rt: &impl Runtime, admin: Option<Address>) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+
+        // The caller is guaranteed to be an ID address.
+        let caller_id = rt.message().caller().id().unwrap();
+        let state: State = rt.state()?;
+        if caller_id != SYSTEM_ACTOR_ID && !state.is_admin(rt, caller_id) {
+            return Err(ActorError::forbidden(String::from(
+                "sender not allowed to change the admin",
+            )));
+        }
+
+        rt.transaction(|st: &mut State, _| {
+            st.admin = admin;
+            Ok(())
+        })
+    }
+
+    fn ensure_admin(rt: &impl Runtime) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+
+        // The caller is guaranteed to be an ID address.
+        let caller_id = rt.message().caller().id().unwrap();
+        let state: State = rt.state()?;
+        if !state.is_admin(rt, caller_id) {
+            return Err(ActorError::forbidden(String::from(
+                "sender not allowed to manage deployers",
+            )));
+        }
         Ok(())
     }
+
+    /// Emit an event with the kind of change and the deployers involved, so that indexers
+    /// can follow changes to the permissions without polling the state.
+    fn emit_permissions_changed(
+        rt: &impl Runtime,
+        action: &str,
+        params: &PermissionModeParams,
+    ) -> Result<(), ActorError> {
+        rt.emit_event(&permissions_changed_event(action, params)?)
+    }
+}
+
+/// Create the event emitted when the permissions of the EAM change.
+pub fn permissions_changed_event(
+    action: &str,
+    params: &PermissionModeParams,
+) -> Result<ActorEvent, ActorError> {
+    let entry = |key: &str, codec: u64, value: Vec<u8>| Entry {
+        flags: Flags::FLAG_INDEXED_ALL,
+        key: key.to_owned(),
+        codec,
+        value,
+    };
+    let deployers = match params {
+        PermissionModeParams::Unrestricted => Vec::new(),
+        PermissionModeParams::AllowList(deployers) => deployers.clone(),
+    };
+    let mode = match params {
+        PermissionModeParams::Unrestricted => "unrestricted",
+        PermissionModeParams::AllowList(_) => "allowlist",
+    };
+    let deployers = fvm_ipld_encoding::to_vec(&deployers)
+        .map_err(|e| ActorError::serialization(format!("failed to encode deployers: {e}")))?;
+
+    Ok(ActorEvent {
+        entries: vec![
+            entry(
+                "$type",
+                IPLD_RAW,
+                PERMISSIONS_CHANGED_EVENT.as_bytes().to_vec(),
+            ),
+            entry("action", IPLD_RAW, action.as_bytes().to_vec()),
+            entry("mode", IPLD_RAW, mode.as_bytes().to_vec()),
+            entry("deployers", DAG_CBOR, deployers),
+        ],
+    })
 }
 
 impl ActorCode for IPCEamActor {
@@ -117,6 +246,16 @@ impl ActorCode for IPCEamActor {
             fil_actors_runtime::dispatch(rt, method, Self::constructor, params)
         } else if method == ExtraMethods::UpdateDeployers as u64 {
             fil_actors_runtime::dispatch(rt, method, Self::update_deployers, params)
+        } else if method == ExtraMethods::AddDeployers as u64 {
+            fil_actors_runtime::dispatch(rt, method, Self::add_deployers, params)
+        } else if method == ExtraMethods::RemoveDeployers as u64 {
+            fil_actors_runtime::dispatch(rt, method, Self::remove_deployers, params)
+        } else if method == ExtraMethods::SetPermissionMode as u64 {
+            fil_actors_runtime::dispatch(rt, method, Self::set_permission_mode, params)
+        } else if method == ExtraMethods::ListDeployers as u64 {
+            fil_actors_runtime::dispatch(rt, method, Self::list_deployers, params)
+        } else if method == ExtraMethods::ChangeAdmin as u64 {
+            fil_actors_runtime::dispatch(rt, method, Self::change_admin, params)
         } else {
             Self::ensure_deployer_allowed(rt)?;
             EamActor::invoke_method(rt, method, params)
@@ -127,6 +266,7 @@ impl ActorCode for IPCEamActor {
 #[derive(Debug, Serialize_tuple, Deserialize_tuple)]
 pub struct ConstructorParams {
     permission_mode: PermissionModeParams,
+    admin: Option<Address>,
 }
 
 #[cfg(test)]
@@ -149,10 +289,17 @@ mod tests {
     use fvm_shared::error::ExitCode;
     use fvm_shared::MethodNum;
 
-    use crate::state::PermissionModeParams;
-    use crate::{ConstructorParams as IPCConstructorParams, ExtraMethods, IPCEamActor, Method};
+    use crate::state::{PermissionModeParams, State};
+    use crate::{
+        permissions_changed_event, ConstructorParams as IPCConstructorParams, ExtraMethods,
+        IPCEamActor, Method,
+    };
 
     pub fn construct_and_verify(deployers: Vec<Address>) -> MockRuntime {
+        construct_with_admin(deployers, None)
+    }
+
+    pub fn construct_with_admin(deployers: Vec<Address>, admin: Option<Address>) -> MockRuntime {
         let rt = MockRuntime {
             receiver: Address::new_id(10),
             ..Default::default()
@@ -172,7 +319,11 @@ mod tests {
         let result = rt
             .call::<IPCEamActor>(
                 Method::Constructor as u64,
-                IpldBlock::serialize_cbor(&IPCConstructorParams { permission_mode }).unwrap(),
+                IpldBlock::serialize_cbor(&IPCConstructorParams {
+                    permission_mode,
+                    admin,
+                })
+                .unwrap(),
             )
             .unwrap();
         expect_empty(result);
@@ -454,6 +605,13 @@ mod tests {
         // Now add permissions for the deployer from the allowed address.
         let update_deployers_params = vec![deployer.f410];
         rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, allowed.id);
+        rt.expect_emitted_event(
+            permissions_changed_event(
+                "replace",
+                &PermissionModeParams::AllowList(update_deployers_params.clone()),
+            )
+            .unwrap(),
+        );
         let ret = rt.call::<IPCEamActor>(
             ExtraMethods::UpdateDeployers as MethodNum,
             IpldBlock::serialize_cbor(&update_deployers_params).unwrap(),
@@ -518,4 +676,273 @@ mod tests {
 
         assert_eq!(ret, expected_return);
     }
+
+    #[test]
+    fn test_admin_manages_deployers() {
+        let admin = Address::new_id(500);
+        let deployer = Address::new_id(1000);
+        let other = Address::new_id(2000);
+        let rt = construct_with_admin(vec![deployer], Some(admin));
+
+        let list = |rt: &MockRuntime| {
+            rt.expect_validate_caller_any();
+            rt.call::<IPCEamActor>(ExtraMethods::ListDeployers as MethodNum, None)
+                .unwrap()
+                .unwrap()
+                .deserialize::<PermissionModeParams>()
+                .unwrap()
+        };
+
+        // A deployer who is not the admin cannot add others.
+        rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, deployer);
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<IPCEamActor>(
+            ExtraMethods::AddDeployers as MethodNum,
+            IpldBlock::serialize_cbor(&vec![other]).unwrap(),
+        );
+        assert_eq!(ExitCode::USR_FORBIDDEN, ret.err().unwrap().exit_code());
+        rt.reset();
+
+        // The admin can add deployers.
+        rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, admin);
+        rt.expect_validate_caller_any();
+        rt.expect_emitted_event(
+            permissions_changed_event("add", &PermissionModeParams::AllowList(vec![other]))
+                .unwrap(),
+        );
+        rt.call::<IPCEamActor>(
+            ExtraMethods::AddDeployers as MethodNum,
+            IpldBlock::serialize_cbor(&vec![other]).unwrap(),
+        )
+        .unwrap();
+        rt.verify();
+
+        let PermissionModeParams::AllowList(mut deployers) = list(&rt) else {
+            panic!("expected allowlist mode");
+        };
+        deployers.sort_by_key(|a| a.id().unwrap());
+        assert_eq!(deployers, vec![deployer, other]);
+
+        // The admin can remove deployers.
+        rt.expect_validate_caller_any();
+        rt.expect_emitted_event(
+            permissions_changed_event("remove", &PermissionModeParams::AllowList(vec![deployer]))
+                .unwrap(),
+        );
+        rt.call::<IPCEamActor>(
+            ExtraMethods::RemoveDeployers as MethodNum,
+            IpldBlock::serialize_cbor(&vec![deployer]).unwrap(),
+        )
+        .unwrap();
+        rt.verify();
+        assert_eq!(list(&rt), PermissionModeParams::AllowList(vec![other]));
+
+        // The admin can lift the restrictions.
+        rt.expect_validate_caller_any();
+        rt.expect_emitted_event(
+            permissions_changed_event("set", &PermissionModeParams::Unrestricted).unwrap(),
+        );
+        rt.call::<IPCEamActor>(
+            ExtraMethods::SetPermissionMode as MethodNum,
+            IpldBlock::serialize_cbor(&PermissionModeParams::Unrestricted).unwrap(),
+        )
+        .unwrap();
+        rt.verify();
+        assert_eq!(list(&rt), PermissionModeParams::Unrestricted);
+    }
+
+    #[test]
+    fn test_non_admin_cannot_change_permissions() {
+        let admin = Address::new_id(500);
+        let deployer = Address::new_id(1000);
+        let other = Address::new_id(2000);
+
+        let calls = [
+            (
+                ExtraMethods::AddDeployers as MethodNum,
+                IpldBlock::serialize_cbor(&vec![other]).unwrap(),
+            ),
+            (
+                ExtraMethods::RemoveDeployers as MethodNum,
+                IpldBlock::serialize_cbor(&vec![deployer]).unwrap(),
+            ),
+            (
+                ExtraMethods::SetPermissionMode as MethodNum,
+                IpldBlock::serialize_cbor(&PermissionModeParams::Unrestricted).unwrap(),
+            ),
+        ];
+
+        // Neither an allowed deployer nor anyone else is the admin, and without an admin nobody is.
+        for (maybe_admin, caller) in [
+            (Some(admin), deployer),
+            (Some(admin), other),
+            (None, deployer),
+        ] {
+            let rt = construct_with_admin(vec![deployer], maybe_admin);
+
+            for (method, params) in calls.iter() {
+                rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, caller);
+                rt.expect_validate_caller_any();
+                let ret = rt.call::<IPCEamActor>(*method, params.clone());
+                assert_eq!(ExitCode::USR_FORBIDDEN, ret.err().unwrap().exit_code());
+                rt.reset();
+            }
+
+            rt.expect_validate_caller_any();
+            let list = rt
+                .call::<IPCEamActor>(ExtraMethods::ListDeployers as MethodNum, None)
+                .unwrap()
+                .unwrap()
+                .deserialize::<PermissionModeParams>()
+                .unwrap();
+            assert_eq!(list, PermissionModeParams::AllowList(vec![deployer]));
+        }
+    }
+
+    #[test]
+    fn test_change_admin() {
+        let admin = Address::new_id(500);
+        let deployer = Address::new_id(1000);
+        let other = Address::new_id(2000);
+
+        let change_admin = |rt: &MockRuntime, new_admin: Option<Address>| {
+            rt.expect_validate_caller_any();
+            let ret = rt.call::<IPCEamActor>(
+                ExtraMethods::ChangeAdmin as MethodNum,
+                IpldBlock::serialize_cbor(&new_admin).unwrap(),
+            );
+            rt.reset();
+            ret
+        };
+
+        let admin_of = |rt: &MockRuntime| rt.get_state::<State>().admin;
+
+        // Without an admin only the system actor can install one.
+        let rt = construct_with_admin(vec![deployer], None);
+        rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, deployer);
+        let ret = change_admin(&rt, Some(deployer));
+        assert_eq!(ExitCode::USR_FORBIDDEN, ret.err().unwrap().exit_code());
+
+        rt.set_caller(*SYSTEM_ACTOR_CODE_ID, SYSTEM_ACTOR_ADDR);
+        change_admin(&rt, Some(admin)).unwrap();
+        assert_eq!(admin_of(&rt), Some(admin));
+
+        // Someone other than the admin cannot take over.
+        rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, other);
+        let ret = change_admin(&rt, Some(other));
+        assert_eq!(ExitCode::USR_FORBIDDEN, ret.err().unwrap().exit_code());
+        assert_eq!(admin_of(&rt), Some(admin));
+
+        // The admin can hand over, after which it can no longer manage the deployers.
+        rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, admin);
+        change_admin(&rt, Some(other)).unwrap();
+        assert_eq!(admin_of(&rt), Some(other));
+
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<IPCEamActor>(
+            ExtraMethods::AddDeployers as MethodNum,
+            IpldBlock::serialize_cbor(&vec![admin]).unwrap(),
+        );
+        assert_eq!(ExitCode::USR_FORBIDDEN, ret.err().unwrap().exit_code());
+        rt.reset();
+
+        // The new admin can renounce the administration.
+        rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, other);
+        change_admin(&rt, None).unwrap();
+        assert_eq!(admin_of(&rt), None);
+    }
+
+    #[test]
+    fn test_create_after_admin_changes() {
+        let admin = Address::new_id(500);
+        let deployer = Address::new_id(1000);
+        let other = Address::new_id(2000);
+        let rt = construct_with_admin(vec![deployer], Some(admin));
+
+        // Only the original deployer is allowed.
+        create_external(&rt, deployer).unwrap();
+        rt.verify();
+        assert_eq!(
+            ExitCode::USR_FORBIDDEN,
+            create_external(&rt, other).unwrap_err().exit_code()
+        );
+        rt.reset();
+
+        // The admin swaps the deployers.
+        for (method, action, addr) in [
+            (ExtraMethods::AddDeployers, "add", other),
+            (ExtraMethods::RemoveDeployers, "remove", deployer),
+        ] {
+            rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, admin);
+            rt.expect_validate_caller_any();
+            rt.expect_emitted_event(
+                permissions_changed_event(action, &PermissionModeParams::AllowList(vec![addr]))
+                    .unwrap(),
+            );
+            rt.call::<IPCEamActor>(
+                method as MethodNum,
+                IpldBlock::serialize_cbor(&vec![addr]).unwrap(),
+            )
+            .unwrap();
+            rt.verify();
+        }
+
+        create_external(&rt, other).unwrap();
+        rt.verify();
+        assert_eq!(
+            ExitCode::USR_FORBIDDEN,
+            create_external(&rt, deployer).unwrap_err().exit_code()
+        );
+        rt.reset();
+    }
+
+    /// Call `CreateExternal` from an Ethereum account, expecting the contract to be created if it's allowed.
+    fn create_external(
+        rt: &MockRuntime,
+        caller: Address,
+    ) -> Result<Option<IpldBlock>, fil_actors_runtime::ActorError> {
+        let caller_id = caller.id().unwrap();
+        let mut eth_addr = EthAddress([0xca; 20]);
+        eth_addr.0[12..].copy_from_slice(&caller_id.to_be_bytes());
+        let f4_eth_addr = Address::new_delegated(10, &eth_addr.0).unwrap();
+
+        rt.set_delegated_address(caller_id, f4_eth_addr);
+        rt.set_caller(*ETHACCOUNT_ACTOR_CODE_ID, caller);
+        rt.set_origin(caller);
+        rt.expect_validate_caller_addr(vec![caller]);
+
+        let initcode = vec![0xff];
+
+        let evm_params = ConstructorParams {
+            creator: eth_addr,
+            initcode: initcode.clone().into(),
+        };
+
+        let new_eth_addr = compute_address_create(rt, &eth_addr, 0);
+        let params = Exec4Params {
+            code_cid: *EVM_ACTOR_CODE_ID,
+            constructor_params: RawBytes::serialize(evm_params).unwrap(),
+            subaddress: new_eth_addr.0[..].to_owned().into(),
+        };
+
+        let send_return = IpldBlock::serialize_cbor(&Exec4Return {
+            id_address: Address::new_id(111),
+            robust_address: Address::new_id(0),
+        })
+        .unwrap();
+
+        rt.expect_send_simple(
+            INIT_ACTOR_ADDR,
+            EXEC4_METHOD,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+            TokenAmount::from_atto(0),
+            send_return,
+            ExitCode::OK,
+        );
+
+        rt.call::<IPCEamActor>(
+            Method::CreateExternal as u64,
+            IpldBlock::serialize_cbor(&CreateExternalParams(initcode)).unwrap(),
+        )
+    }
 }
@@ -8,7 +8,9 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 use fvm_shared::ActorID;
-use serde::{Deserialize, Serialize};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
 
 pub type DeployerMap<BS> = Map2<BS, Address, ()>;
 
@@ -30,16 +32,71 @@ pub enum PermissionMode {
     AllowList(Cid), // HAMT[Address]()
 }
 
-#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone)]
+/// The state is serialized as a tuple of its fields.
+///
+/// The `admin` was added after launch, so deserialization also accepts the original
+/// layout which only has the permission mode; such states have no admin.
+#[derive(Serialize_tuple, Debug, Clone)]
 pub struct State {
     pub permission_mode: PermissionMode,
+    /// The account allowed to manage the deployers and change the permission mode after launch.
+    pub admin: Option<Address>,
+}
+
+impl<'de> Deserialize<'de> for State {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StateVisitor;
+
+        impl<'de> Visitor<'de> for StateVisitor {
+            type Value = State;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a tuple of the permission mode and an optional admin")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<State, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let permission_mode = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+
+                // Missing in states created before the admin was introduced.
+                let admin = seq.next_element::<Option<Address>>()?.flatten();
+
+                Ok(State {
+                    permission_mode,
+                    admin,
+                })
+            }
+        }
+
+        deserializer.deserialize_seq(StateVisitor)
+    }
 }
 
 impl State {
     pub fn new<BS: Blockstore>(
         store: &BS,
         args: PermissionModeParams,
+        admin: Option<Address>,
     ) -> Result<State, ActorError> {
+        let permission_mode = Self::permission_mode(store, args)?;
+        Ok(State {
+            permission_mode,
+            admin,
+        })
+    }
+
+    /// Store the deployers of an allow-list in a HAMT.
+    pub fn permission_mode<BS: Blockstore>(
+        store: &BS,
+        args: PermissionModeParams,
+    ) -> Result<PermissionMode, ActorError> {
         let permission_mode = match args {
             PermissionModeParams::Unrestricted => PermissionMode::Unrestricted,
             PermissionModeParams::AllowList(deployers) => {
@@ -50,7 +107,59 @@ impl State {
                 PermissionMode::AllowList(deployers_map.flush()?)
             }
         };
-        Ok(State { permission_mode })
+        Ok(permission_mode)
+    }
+
+    /// Check whether the caller is the admin, resolving the admin address to its ID form.
+    pub fn is_admin(&self, rt: &impl Runtime, caller: ActorID) -> bool {
+        match self.admin {
+            Some(ref admin) => rt.resolve_address(admin) == Some(caller),
+            None => false,
+        }
+    }
+
+    /// Add or remove deployers from the allow-list.
+    ///
+    /// Fails if the permission mode is unrestricted.
+    pub fn update_allow_list<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        add: &[Address],
+        remove: &[Address],
+    ) -> Result<(), ActorError> {
+        let PermissionMode::AllowList(ref cid) = self.permission_mode else {
+            return Err(ActorError::forbidden(String::from(
+                "deployers can only be updated in allowlist mode",
+            )));
+        };
+        let mut deployers = DeployerMap::load(store, cid, DEFAULT_HAMT_CONFIG, "deployers")?;
+        for d in add {
+            deployers.set(d, ())?;
+        }
+        for d in remove {
+            deployers.delete(d)?;
+        }
+        self.permission_mode = PermissionMode::AllowList(deployers.flush()?);
+        Ok(())
+    }
+
+    /// List the deployers in the form they were added, or `Unrestricted` if anyone can deploy.
+    pub fn permission_mode_params<BS: Blockstore>(
+        &self,
+        store: &BS,
+    ) -> Result<PermissionModeParams, ActorError> {
+        Ok(match &self.permission_mode {
+            PermissionMode::Unrestricted => PermissionModeParams::Unrestricted,
+            PermissionMode::AllowList(cid) => {
+                let deployers = DeployerMap::load(store, cid, DEFAULT_HAMT_CONFIG, "deployers")?;
+                let mut addresses = Vec::new();
+                deployers.for_each(|k, _| {
+                    addresses.push(k);
+                    Ok(())
+                })?;
+                PermissionModeParams::AllowList(addresses)
+            }
+        })
     }
 
     pub fn can_deploy(&self, rt: &impl Runtime, deployer: ActorID) -> Result<bool, ActorError> {
@@ -78,8 +187,10 @@ impl State {
 #[cfg(test)]
 mod tests {
     use cid::Cid;
+    use fvm_ipld_encoding::tuple::*;
+    use fvm_shared::address::Address;
 
-    use crate::state::PermissionMode;
+    use crate::state::{PermissionMode, State};
 
     #[test]
     fn test_serialization() {
@@ -95,4 +206,35 @@ mod tests {
         let dp: PermissionMode = fvm_ipld_encoding::from_slice(&v).unwrap();
         assert_eq!(dp, p)
     }
+
+    #[test]
+    fn test_state_serialization() {
+        let st = State {
+            permission_mode: PermissionMode::AllowList(Cid::default()),
+            admin: Some(Address::new_id(100)),
+        };
+        let v = fvm_ipld_encoding::to_vec(&st).unwrap();
+
+        let dst: State = fvm_ipld_encoding::from_slice(&v).unwrap();
+        assert_eq!(dst.permission_mode, st.permission_mode);
+        assert_eq!(dst.admin, st.admin);
+    }
+
+    #[test]
+    fn test_state_without_admin() {
+        /// The layout of the state before the admin was added.
+        #[derive(Serialize_tuple)]
+        struct StateV0 {
+            permission_mode: PermissionMode,
+        }
+
+        let st = StateV0 {
+            permission_mode: PermissionMode::AllowList(Cid::default()),
+        };
+        let v = fvm_ipld_encoding::to_vec(&st).unwrap();
+
+        let dst: State = fvm_ipld_encoding::from_slice(&v).unwrap();
+        assert_eq!(dst.permission_mode, st.permission_mode);
+        assert_eq!(dst.admin, None);
+    }
 }
@@ -0,0 +1,348 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fil_actors_runtime::actor_dispatch;
+use fil_actors_runtime::actor_error;
+use fil_actors_runtime::builtin::singletons::INIT_ACTOR_ADDR;
+use fil_actors_runtime::extract_send_result;
+use fil_actors_runtime::runtime::{ActorCode, Runtime};
+use fil_actors_runtime::ActorError;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::METHOD_SEND;
+
+use crate::{ConstructorParams, Method, State, VestingInfo, WithdrawParams, VESTING_ACTOR_NAME};
+
+fil_actors_runtime::wasm_trampoline!(Actor);
+
+pub struct Actor;
+
+impl Actor {
+    fn constructor(rt: &impl Runtime, params: ConstructorParams) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&INIT_ACTOR_ADDR))?;
+
+        if params.duration < params.cliff || params.cliff < 0 {
+            return Err(actor_error!(
+                illegal_argument,
+                "invalid vesting schedule: cliff {} and duration {}",
+                params.cliff,
+                params.duration
+            ));
+        }
+
+        let owner = resolve(rt, &params.owner)?;
+        let revoker = match params.revoker {
+            Some(revoker) => Some(resolve(rt, &revoker)?),
+            None => None,
+        };
+
+        let state = State {
+            owner,
+            revoker,
+            initial_balance: rt.current_balance(),
+            start_epoch: params.start_epoch,
+            cliff: params.cliff,
+            duration: params.duration,
+            revoked_at: None,
+        };
+
+        rt.create(&state)?;
+
+        Ok(())
+    }
+
+    fn withdraw(rt: &impl Runtime, params: WithdrawParams) -> Result<(), ActorError> {
+        let st: State = rt.state()?;
+        rt.validate_immediate_caller_is(std::iter::once(&st.owner))?;
+
+        if params.amount.is_negative() {
+            return Err(actor_error!(illegal_argument, "negative withdrawal amount"));
+        }
+
+        let withdrawable = st.withdrawable_amount(&rt.current_balance(), rt.curr_epoch());
+        if params.amount > withdrawable {
+            return Err(actor_error!(
+                insufficient_funds,
+                "cannot withdraw {} with only {} vested",
+                params.amount,
+                withdrawable
+            ));
+        }
+
+        extract_send_result(rt.send_simple(&params.to, METHOD_SEND, None, params.amount))?;
+
+        Ok(())
+    }
+
+    fn revoke(rt: &impl Runtime) -> Result<(), ActorError> {
+        let st: State = rt.state()?;
+
+        let revoker = match st.revoker {
+            Some(revoker) => revoker,
+            None => return Err(actor_error!(forbidden, "vesting schedule is irrevocable")),
+        };
+        rt.validate_immediate_caller_is(std::iter::once(&revoker))?;
+
+        if st.revoked_at.is_some() {
+            return Err(actor_error!(illegal_state, "vesting already revoked"));
+        }
+
+        let epoch = rt.curr_epoch();
+        let unvested = st.unvested_amount(epoch);
+
+        rt.transaction(|st: &mut State, _| {
+            st.revoked_at = Some(epoch);
+            Ok(())
+        })?;
+
+        let amount = std::cmp::min(unvested, rt.current_balance());
+        if !amount.is_zero() {
+            extract_send_result(rt.send_simple(&revoker, METHOD_SEND, None, amount))?;
+        }
+
+        Ok(())
+    }
+
+    fn get_vesting_info(rt: &impl Runtime) -> Result<VestingInfo, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        let balance: TokenAmount = rt.current_balance();
+        Ok(VestingInfo::new(&st, &balance, rt.curr_epoch()))
+    }
+}
+
+/// Resolve an address to its ID form, so it can be compared with callers.
+fn resolve(rt: &impl Runtime, addr: &Address) -> Result<Address, ActorError> {
+    rt.resolve_address(addr)
+        .map(Address::new_id)
+        .ok_or_else(|| actor_error!(illegal_argument, "failed to resolve address {}", addr))
+}
+
+impl ActorCode for Actor {
+    type Methods = Method;
+
+    fn name() -> &'static str {
+        VESTING_ACTOR_NAME
+    }
+
+    actor_dispatch! {
+        Constructor => constructor,
+        Withdraw => withdraw,
+        Revoke => revoke,
+        GetVestingInfo => get_vesting_info,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fil_actors_runtime::test_utils::{
+        expect_empty, MockRuntime, ACCOUNT_ACTOR_CODE_ID, INIT_ACTOR_CODE_ID,
+    };
+    use fil_actors_runtime::INIT_ACTOR_ADDR;
+    use fvm_ipld_encoding::ipld_block::IpldBlock;
+    use fvm_shared::address::Address;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use fvm_shared::METHOD_SEND;
+
+    use crate::{ConstructorParams, Method, State, VestingInfo, WithdrawParams};
+
+    use super::Actor;
+
+    fn owner() -> Address {
+        Address::new_id(100)
+    }
+
+    fn revoker() -> Address {
+        Address::new_id(101)
+    }
+
+    fn schedule(revoker: Option<Address>) -> ConstructorParams {
+        ConstructorParams {
+            owner: owner(),
+            revoker,
+            start_epoch: 10,
+            cliff: 20,
+            duration: 100,
+        }
+    }
+
+    /// Construct an actor holding 1000 atto, half of which vests by epoch 60.
+    fn construct(revoker: Option<Address>) -> MockRuntime {
+        let rt = MockRuntime {
+            receiver: Address::new_id(1000),
+            ..Default::default()
+        };
+        rt.set_balance(TokenAmount::from_atto(1000));
+        rt.set_caller(*INIT_ACTOR_CODE_ID, INIT_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![INIT_ACTOR_ADDR]);
+
+        let result = rt
+            .call::<Actor>(
+                Method::Constructor as u64,
+                IpldBlock::serialize_cbor(&schedule(revoker)).unwrap(),
+            )
+            .unwrap();
+        expect_empty(result);
+        rt.verify();
+        rt.reset();
+
+        rt
+    }
+
+    fn withdraw(rt: &MockRuntime, amount: u64) -> Result<(), ExitCode> {
+        let params = WithdrawParams {
+            to: owner(),
+            amount: TokenAmount::from_atto(amount),
+        };
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, owner());
+        rt.expect_validate_caller_addr(vec![owner()]);
+
+        rt.call::<Actor>(
+            Method::Withdraw as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )
+        .map(|_| ())
+        .map_err(|e| e.exit_code())
+    }
+
+    fn vesting_info(rt: &MockRuntime) -> VestingInfo {
+        rt.expect_validate_caller_any();
+        rt.call::<Actor>(Method::GetVestingInfo as u64, None)
+            .unwrap()
+            .unwrap()
+            .deserialize::<VestingInfo>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_constructor() {
+        let rt = construct(Some(revoker()));
+
+        let st: State = rt.get_state();
+        assert_eq!(st.owner, owner());
+        assert_eq!(st.revoker, Some(revoker()));
+        assert_eq!(st.initial_balance, TokenAmount::from_atto(1000));
+        assert_eq!(st.start_epoch, 10);
+        assert_eq!(st.revoked_at, None);
+    }
+
+    #[test]
+    fn test_constructor_rejects_invalid_schedule() {
+        let rt = MockRuntime::default();
+        rt.set_caller(*INIT_ACTOR_CODE_ID, INIT_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![INIT_ACTOR_ADDR]);
+
+        let mut params = schedule(None);
+        params.cliff = params.duration + 1;
+
+        let exit_code = rt
+            .call::<Actor>(
+                Method::Constructor as u64,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )
+            .unwrap_err()
+            .exit_code();
+
+        assert_eq!(exit_code, ExitCode::USR_ILLEGAL_ARGUMENT);
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let rt = construct(None);
+
+        // Nothing vests before the cliff.
+        rt.set_epoch(29);
+        assert_eq!(withdraw(&rt, 1), Err(ExitCode::USR_INSUFFICIENT_FUNDS));
+
+        rt.set_epoch(60);
+        assert_eq!(vesting_info(&rt).withdrawable, TokenAmount::from_atto(500));
+        assert_eq!(withdraw(&rt, 501), Err(ExitCode::USR_INSUFFICIENT_FUNDS));
+
+        rt.expect_send_simple(
+            owner(),
+            METHOD_SEND,
+            None,
+            TokenAmount::from_atto(500),
+            None,
+            ExitCode::OK,
+        );
+        assert_eq!(withdraw(&rt, 500), Ok(()));
+        rt.verify();
+    }
+
+    #[test]
+    fn test_withdraw_by_others_forbidden() {
+        let rt = construct(None);
+        rt.set_epoch(200);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, revoker());
+        rt.expect_validate_caller_addr(vec![owner()]);
+
+        let params = WithdrawParams {
+            to: revoker(),
+            amount: TokenAmount::from_atto(1),
+        };
+        let exit_code = rt
+            .call::<Actor>(
+                Method::Withdraw as u64,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )
+            .unwrap_err()
+            .exit_code();
+
+        assert_eq!(exit_code, ExitCode::USR_FORBIDDEN);
+    }
+
+    #[test]
+    fn test_revoke() {
+        let rt = construct(Some(revoker()));
+        rt.set_epoch(60);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, revoker());
+        rt.expect_validate_caller_addr(vec![revoker()]);
+        rt.expect_send_simple(
+            revoker(),
+            METHOD_SEND,
+            None,
+            TokenAmount::from_atto(500),
+            None,
+            ExitCode::OK,
+        );
+
+        let result = rt.call::<Actor>(Method::Revoke as u64, None).unwrap();
+        expect_empty(result);
+        rt.verify();
+
+        let st: State = rt.get_state();
+        assert_eq!(st.revoked_at, Some(60));
+
+        // What has vested so far stays with the owner, the rest is no longer locked.
+        rt.set_balance(TokenAmount::from_atto(500));
+        rt.set_epoch(200);
+        let info = vesting_info(&rt);
+        assert_eq!(info.vested, TokenAmount::from_atto(500));
+        assert_eq!(info.unvested, TokenAmount::zero());
+        assert_eq!(info.withdrawable, TokenAmount::from_atto(500));
+
+        // It can only be revoked once.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, revoker());
+        rt.expect_validate_caller_addr(vec![revoker()]);
+        let exit_code = rt
+            .call::<Actor>(Method::Revoke as u64, None)
+            .unwrap_err()
+            .exit_code();
+        assert_eq!(exit_code, ExitCode::USR_ILLEGAL_STATE);
+    }
+
+    #[test]
+    fn test_revoke_irrevocable() {
+        let rt = construct(None);
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, revoker());
+
+        let exit_code = rt
+            .call::<Actor>(Method::Revoke as u64, None)
+            .unwrap_err()
+            .exit_code();
+
+        assert_eq!(exit_code, ExitCode::USR_FORBIDDEN);
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fvm_ipld_encoding::tuple::{Deserialize_tuple, Serialize_tuple};
+use fvm_shared::{address::Address, clock::ChainEpoch, econ::TokenAmount, METHOD_CONSTRUCTOR};
+use num_derive::FromPrimitive;
+
+pub const VESTING_ACTOR_NAME: &str = "vesting";
+
+// The state holds the vesting schedule of the funds held by the actor.
+//
+// The locked amount is derived from the schedule, so any funds sent to the
+// actor on top of the initial balance are available to the owner right away.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    // the ID address of the account allowed to withdraw vested funds
+    pub owner: Address,
+    // the ID address of the account allowed to revoke the unvested funds;
+    // the schedule is irrevocable if this is missing
+    pub revoker: Option<Address>,
+    // the amount subject to the schedule
+    pub initial_balance: TokenAmount,
+    // the epoch from which the vesting is counted
+    pub start_epoch: ChainEpoch,
+    // the number of epochs after the start before anything vests
+    pub cliff: ChainEpoch,
+    // the number of epochs after the start when everything has vested
+    pub duration: ChainEpoch,
+    // the epoch at which the schedule was revoked, which freezes the vested amount
+    pub revoked_at: Option<ChainEpoch>,
+}
+
+impl State {
+    /// The amount of the initial balance which has vested by the given epoch.
+    ///
+    /// Nothing vests before the cliff; after that the amount grows linearly
+    /// from the start, so a schedule where the cliff equals the duration is a
+    /// simple timelock.
+    pub fn vested_amount(&self, epoch: ChainEpoch) -> TokenAmount {
+        let epoch = match self.revoked_at {
+            Some(revoked_at) => epoch.min(revoked_at),
+            None => epoch,
+        };
+        let elapsed = epoch - self.start_epoch;
+
+        if elapsed < self.cliff || elapsed < 0 {
+            TokenAmount::zero()
+        } else if elapsed >= self.duration {
+            self.initial_balance.clone()
+        } else {
+            let atto = self.initial_balance.atto() * elapsed / self.duration;
+            TokenAmount::from_atto(atto)
+        }
+    }
+
+    /// The amount of the initial balance which has not vested by the given epoch.
+    ///
+    /// After a revocation the unvested amount is returned to the revoker,
+    /// so there is nothing left to vest.
+    pub fn unvested_amount(&self, epoch: ChainEpoch) -> TokenAmount {
+        if self.revoked_at.is_some() {
+            TokenAmount::zero()
+        } else {
+            &self.initial_balance - self.vested_amount(epoch)
+        }
+    }
+
+    /// The part of the balance the owner can withdraw at the given epoch.
+    pub fn withdrawable_amount(&self, balance: &TokenAmount, epoch: ChainEpoch) -> TokenAmount {
+        let locked = self.unvested_amount(epoch);
+        if balance > &locked {
+            balance - locked
+        } else {
+            TokenAmount::zero()
+        }
+    }
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ConstructorParams {
+    pub owner: Address,
+    pub revoker: Option<Address>,
+    pub start_epoch: ChainEpoch,
+    pub cliff: ChainEpoch,
+    pub duration: ChainEpoch,
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct WithdrawParams {
+    pub to: Address,
+    pub amount: TokenAmount,
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple, PartialEq, Eq)]
+pub struct VestingInfo {
+    pub owner: Address,
+    pub revoker: Option<Address>,
+    pub vested: TokenAmount,
+    pub unvested: TokenAmount,
+    pub withdrawable: TokenAmount,
+    pub revoked_at: Option<ChainEpoch>,
+}
+
+impl VestingInfo {
+    pub fn new(state: &State, balance: &TokenAmount, epoch: ChainEpoch) -> Self {
+        Self {
+            owner: state.owner,
+            revoker: state.revoker,
+            vested: state.vested_amount(epoch),
+            unvested: state.unvested_amount(epoch),
+            withdrawable: state.withdrawable_amount(balance, epoch),
+            revoked_at: state.revoked_at,
+        }
+    }
+}
+
+#[derive(FromPrimitive)]
+#[repr(u64)]
+pub enum Method {
+    Constructor = METHOD_CONSTRUCTOR,
+    Withdraw = frc42_dispatch::method_hash!("Withdraw"),
+    Revoke = frc42_dispatch::method_hash!("Revoke"),
+    GetVestingInfo = frc42_dispatch::method_hash!("GetVestingInfo"),
+}
+
+#[cfg(test)]
+mod tests {
+    use fvm_shared::{address::Address, econ::TokenAmount};
+
+    use super::State;
+
+    fn state(cliff: i64, duration: i64) -> State {
+        State {
+            owner: Address::new_id(100),
+            revoker: Some(Address::new_id(101)),
+            initial_balance: TokenAmount::from_atto(1000),
+            start_epoch: 10,
+            cliff,
+            duration,
+            revoked_at: None,
+        }
+    }
+
+    #[test]
+    fn vesting_schedule() {
+        let st = state(20, 100);
+        assert_eq!(st.vested_amount(0), TokenAmount::zero());
+        assert_eq!(st.vested_amount(29), TokenAmount::zero());
+        assert_eq!(st.vested_amount(30), TokenAmount::from_atto(200));
+        assert_eq!(st.vested_amount(60), TokenAmount::from_atto(500));
+        assert_eq!(st.vested_amount(110), TokenAmount::from_atto(1000));
+        assert_eq!(st.vested_amount(1000), TokenAmount::from_atto(1000));
+        assert_eq!(st.unvested_amount(60), TokenAmount::from_atto(500));
+    }
+
+    #[test]
+    fn timelock_schedule() {
+        let st = state(50, 50);
+        assert_eq!(st.vested_amount(59), TokenAmount::zero());
+        assert_eq!(st.vested_amount(60), TokenAmount::from_atto(1000));
+    }
+
+    #[test]
+    fn withdrawable_includes_extra_funds() {
+        let st = state(0, 100);
+        let balance = TokenAmount::from_atto(1100);
+        assert_eq!(
+            st.withdrawable_amount(&balance, 60),
+            TokenAmount::from_atto(600)
+        );
+    }
+
+    #[test]
+    fn revocation_freezes_vesting() {
+        let mut st = state(0, 100);
+        st.revoked_at = Some(60);
+        assert_eq!(st.vested_amount(100), TokenAmount::from_atto(500));
+        assert_eq!(st.unvested_amount(100), TokenAmount::zero());
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use clap::{Args, Subcommand, ValueEnum};
+
+#[derive(Args, Debug)]
+pub struct DbArgs {
+    #[command(subcommand)]
+    pub command: DbCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DbCommands {
+    /// Copy all the data from one database backend to another; the node must not be running.
+    ///
+    /// Set `db.backend` in the configuration to the target afterwards to start using it.
+    Migrate(DbMigrateArgs),
+    /// Create a backup of the database at the last committed height, printing its manifest as JSON.
+    ///
+    /// By default the running node is asked to create it through its DB admin endpoint.
+    Backup(DbBackupArgs),
+    /// List the backups in the backups directory as JSON.
+    ListBackups,
+    /// Verify a backup and restore it into the data directory; the node must not be running.
+    ///
+    /// The current RocksDB directory has to be moved out of the way first.
+    Restore(DbRestoreArgs),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum DbBackend {
+    #[value(name = "rocksdb")]
+    RocksDb,
+    Redb,
+}
+
+#[derive(Args, Debug)]
+pub struct DbMigrateArgs {
+    /// The backend to copy the data from.
+    #[arg(long)]
+    pub from: DbBackend,
+
+    /// The backend to copy the data to; it must not exist yet.
+    #[arg(long)]
+    pub to: DbBackend,
+
+    /// Number of entries to copy in a single write transaction.
+    #[arg(long, default_value = "10000")]
+    pub batch_size: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct DbBackupArgs {
+    /// The URL of the DB admin endpoint of the running node.
+    #[arg(
+        long,
+        short,
+        default_value = "http://127.0.0.1:26660",
+        env = "FM_DB_ADMIN_URL"
+    )]
+    pub url: url::Url,
+
+    /// Open the database directly instead of asking the node; only possible when it isn't running.
+    #[arg(long, default_value_t = false)]
+    pub offline: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DbRestoreArgs {
+    /// Block height of the backup to restore.
+    #[arg(long)]
+    pub height: u64,
+}
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use crate::parse::parse_eth_address;
 use clap::{Args, Subcommand};
 use fvm_shared::address::Address;
 use ipc_api::subnet_id::SubnetID;
+use tendermint_rpc::Url;
 
 #[derive(Args, Debug)]
 pub struct DebugArgs {
@@ -21,6 +23,21 @@ pub enum DebugCommands {
         #[command(subcommand)]
         command: DebugIpcCommands,
     },
+    /// IPLD Resolver commands, querying the admin endpoint of a running node.
+    Resolver {
+        #[command(subcommand)]
+        command: DebugResolverCommands,
+    },
+    /// Inspect and compare FVM state trees, in a local database or through a remote node.
+    State {
+        #[command(subcommand)]
+        command: DebugStateCommands,
+    },
+    /// Re-execute committed blocks on top of the state history in the local database
+    /// and compare the results with what CometBFT recorded; the node must not be running.
+    ///
+    /// The first differing transaction is reported with its gas trace.
+    Replay(DebugReplayArgs),
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -29,6 +46,10 @@ pub enum DebugIpcCommands {
     ///
     /// This can be used to construct an upgrade to impute missing events.
     ExportTopDownEvents(Box<DebugExportTopDownEventsArgs>),
+
+    /// List the parent blocks a running node has cached for top-down finality,
+    /// with their hashes and pending messages, as JSON.
+    ParentView(DebugParentViewArgs),
 }
 
 #[derive(Args, Debug, Clone)]
@@ -65,3 +86,152 @@ pub struct DebugExportTopDownEventsArgs {
     #[arg(long)]
     pub events_file: PathBuf,
 }
+
+#[derive(Args, Debug, Clone)]
+pub struct DebugParentViewArgs {
+    /// The URL of the Tendermint node's RPC endpoint.
+    #[arg(
+        long,
+        short,
+        default_value = "http://127.0.0.1:26657",
+        env = "TENDERMINT_RPC_URL"
+    )]
+    pub url: Url,
+
+    /// An optional HTTP/S proxy through which to submit requests to the
+    /// Tendermint node's RPC endpoint.
+    #[arg(long)]
+    pub proxy_url: Option<Url>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DebugResolverCommands {
+    /// List the connected peers, with the subnets they claim to provide, as JSON.
+    Peers(DebugResolverArgs),
+
+    /// List the known providers of a subnet, as JSON.
+    Providers(DebugResolverProvidersArgs),
+
+    /// Show the statistics of the resolver, including pinned subnets and rate limit usage, as JSON.
+    Stats(DebugResolverArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DebugResolverArgs {
+    /// The URL of the IPLD Resolver admin endpoint of the node.
+    #[arg(
+        long,
+        short,
+        default_value = "http://127.0.0.1:26659",
+        env = "FM_RESOLVER_ADMIN_URL"
+    )]
+    pub url: url::Url,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DebugResolverProvidersArgs {
+    #[command(flatten)]
+    pub admin: DebugResolverArgs,
+
+    /// The subnet to list the providers of.
+    #[arg(long, short)]
+    pub subnet_id: SubnetID,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DebugStateCommands {
+    /// List the actors in the state tree with their code CID, balance and nonce, as JSON.
+    Actors(DebugStateArgs),
+
+    /// Dump the storage slots of an EVM contract, as JSON.
+    Storage(DebugStateStorageArgs),
+
+    /// Compare two state trees, listing the added, removed and changed actors
+    /// and the changed storage slots of contracts, as JSON.
+    Diff(DebugStateDiffArgs),
+}
+
+/// Where to read a state tree from.
+#[derive(Debug, Clone)]
+pub enum StateSource {
+    /// A RocksDB directory or a redb file of a node which isn't running, or a backup of it.
+    Local(PathBuf),
+    /// The CometBFT RPC endpoint of a running node, queried for IPLD data.
+    Remote(Url),
+}
+
+impl FromStr for StateSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("http://") || s.starts_with("https://") {
+            Url::from_str(s)
+                .map(Self::Remote)
+                .map_err(|e| format!("error parsing URL: {e}"))
+        } else {
+            Ok(Self::Local(PathBuf::from(s)))
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DebugStateArgs {
+    /// The URL of the Tendermint node's RPC endpoint, or the path to a local database.
+    #[arg(
+        long,
+        short,
+        default_value = "http://127.0.0.1:26657",
+        env = "TENDERMINT_RPC_URL"
+    )]
+    pub source: StateSource,
+
+    /// Block height of the state; 0 means the latest committed state.
+    #[arg(long, default_value_t = 0)]
+    pub height: u64,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DebugStateStorageArgs {
+    #[command(flatten)]
+    pub state: DebugStateArgs,
+
+    /// Address of the contract; 20 byte Ethereum address in 0x prefixed hex format
+    #[arg(long, short, value_parser = parse_eth_address)]
+    pub contract: Address,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DebugStateDiffArgs {
+    /// The URL of a Tendermint node's RPC endpoint, or the path to a local database,
+    /// to read the first state from.
+    #[arg(long)]
+    pub left: StateSource,
+
+    /// Block height of the first state; 0 means the latest committed state.
+    #[arg(long, default_value_t = 0)]
+    pub left_height: u64,
+
+    /// Where to read the second state from; the same as the first one if missing.
+    #[arg(long)]
+    pub right: Option<StateSource>,
+
+    /// Block height of the second state; 0 means the latest committed state.
+    #[arg(long, default_value_t = 0)]
+    pub right_height: u64,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DebugReplayArgs {
+    /// The first block to replay.
+    #[arg(long)]
+    pub from: u64,
+
+    /// The last block to replay; the same as the first one if missing.
+    #[arg(long)]
+    pub to: Option<u64>,
+
+    /// The URL of the Tendermint node's RPC endpoint to fetch the blocks from;
+    /// the one in the configuration if missing.
+    #[arg(long, short)]
+    pub url: Option<Url>,
+}
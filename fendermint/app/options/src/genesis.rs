@@ -7,10 +7,11 @@ use clap::{Args, Subcommand, ValueEnum};
 use ipc_api::subnet_id::SubnetID;
 
 use super::parse::{
-    parse_eth_address, parse_full_fil, parse_network_version, parse_percentage, parse_signer_addr,
-    parse_token_amount,
+    parse_bytes, parse_eth_address, parse_full_fil, parse_library_link, parse_network_version,
+    parse_percentage, parse_signer_addr, parse_token_amount,
 };
-use fendermint_vm_genesis::SignerAddr;
+use bytes::Bytes;
+use fendermint_vm_genesis::{LibraryLink, SignerAddr};
 use fvm_shared::{address::Address, econ::TokenAmount, version::NetworkVersion};
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -29,6 +30,8 @@ pub enum GenesisCommands {
     AddMultisig(GenesisAddMultisigArgs),
     /// Add a validator to the genesis file.
     AddValidator(GenesisAddValidatorArgs),
+    /// Add an EVM contract to deploy from its build artifact when the genesis is sealed.
+    AddContract(GenesisAddContractArgs),
     /// Set the EAM actor permission mode.
     SetEamPermissions(GenesisSetEAMPermissionsArgs),
     /// IPC commands.
@@ -58,6 +61,13 @@ pub struct GenesisSetEAMPermissionsArgs {
         help = "List of addresses that can deploy contract. Field is ignored if mode is unrestricted"
     )]
     pub addresses: Vec<SignerAddr>,
+
+    #[arg(
+        long,
+        value_parser = parse_signer_addr,
+        help = "Address allowed to manage the deployers and change the permission mode after launch"
+    )]
+    pub admin: Option<SignerAddr>,
 }
 
 #[derive(Args, Debug)]
@@ -100,6 +110,21 @@ pub struct GenesisAddAccountArgs {
     /// Indicate whether the account is a regular or ethereum account.
     #[arg(long, short, default_value = "regular")]
     pub kind: AccountKind,
+    /// Lock the balance in a vesting actor owned by the account, releasing it linearly
+    /// over this many block heights after the start.
+    #[arg(long, requires = "vesting_start")]
+    pub vesting_duration: Option<u64>,
+    /// Block height from which the vesting is counted.
+    #[arg(long, requires = "vesting_duration")]
+    pub vesting_start: Option<u64>,
+    /// Number of block heights after the start before anything can be withdrawn;
+    /// setting it equal to the duration makes a simple timelock.
+    #[arg(long, requires = "vesting_duration", default_value_t = 0)]
+    pub vesting_cliff: u64,
+    /// Path to the Secp256k1 public key of an account allowed to revoke the unvested balance,
+    /// which is of the same kind as the owner; the vesting is irrevocable without it.
+    #[arg(long, requires = "vesting_duration")]
+    pub vesting_revoker: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -129,6 +154,36 @@ pub struct GenesisAddValidatorArgs {
     /// The collateral staked by the validator, lending it its voting power.
     #[arg(long, short = 'v', value_parser = parse_full_fil)]
     pub power: TokenAmount,
+    /// Also fund the validator with its collateral, timelocked in a vesting actor owned by the
+    /// validator until this block height.
+    #[arg(long)]
+    pub collateral_lock: Option<u64>,
+    /// Indicate whether the owner of the locked collateral is a regular or ethereum account.
+    #[arg(long, short, default_value = "regular")]
+    pub kind: AccountKind,
+}
+
+#[derive(Args, Debug)]
+pub struct GenesisAddContractArgs {
+    /// Unique name of the contract, which later contracts can use to link it as a library.
+    #[arg(long, short)]
+    pub name: String,
+    /// Path to the Hardhat or Foundry JSON artifact, e.g. `out/Multicall3.sol/Multicall3.json`.
+    ///
+    /// Relative paths are resolved against the directory where the genesis is sealed.
+    #[arg(long, short)]
+    pub artifact: PathBuf,
+    /// ABI encoded constructor arguments in hex format, without the 0x prefix.
+    #[arg(long, short, value_parser = parse_bytes)]
+    pub constructor_args: Option<Bytes>,
+    /// Library to link as `<library>=<address>`, where the address is either 0x prefixed hex,
+    /// or the name of a contract added earlier. The library is given by its plain or fully qualified name.
+    #[arg(long, short, value_parser = parse_library_link)]
+    pub library: Vec<(String, LibraryLink)>,
+    /// Fixed address to deploy the contract at; 20 byte 0x prefixed hex.
+    /// By default an address is derived from the actor ID the contract gets.
+    #[arg(long, short, value_parser = parse_eth_address)]
+    pub eth_address: Option<Address>,
 }
 
 #[derive(Args, Debug)]
@@ -142,6 +197,10 @@ pub struct GenesisIntoTendermintArgs {
     /// Maximum block size in bytes.
     #[arg(long, default_value_t = 22020096)]
     pub block_max_bytes: u64,
+    /// Height from which validators attach vote extensions to their precommits, which is required by ABCI 2.0;
+    /// 0 disables them, which only works with ABCI 1.0.
+    #[arg(long, default_value_t = 1)]
+    pub vote_extensions_enable_height: u64,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -10,11 +10,12 @@ use fvm_shared::address::Network;
 use lazy_static::lazy_static;
 
 use self::{
-    eth::EthArgs, genesis::GenesisArgs, key::KeyArgs, materializer::MaterializerArgs, rpc::RpcArgs,
-    run::RunArgs,
+    db::DbArgs, eth::EthArgs, genesis::GenesisArgs, key::KeyArgs, materializer::MaterializerArgs,
+    rpc::RpcArgs, run::RunArgs,
 };
 
 pub mod config;
+pub mod db;
 pub mod debug;
 pub mod eth;
 pub mod genesis;
@@ -133,6 +134,8 @@ impl Options {
 pub enum Commands {
     /// Parse the configuration file and print it to the console.
     Config(ConfigArgs),
+    /// Subcommands related to the database of the application.
+    Db(DbArgs),
     /// Arbitrary commands that aid in debugging.
     Debug(DebugArgs),
     /// Run the `App`, listening to ABCI requests from Tendermint.
@@ -7,7 +7,7 @@ use bytes::Bytes;
 use cid::Cid;
 use num_traits::{FromPrimitive, Num};
 
-use fendermint_vm_genesis::SignerAddr;
+use fendermint_vm_genesis::{LibraryLink, SignerAddr};
 use fvm_shared::{
     address::{set_current_network, Address, Network},
     bigint::BigInt,
@@ -108,3 +108,19 @@ pub fn parse_eth_address(s: &str) -> Result<Address, String> {
         Err(e) => Err(format!("not a valid ethereum address: {e}")),
     }
 }
+
+/// Parse a library link in the form of `<library>=<address>`, where the address is either
+/// 20 byte 0x prefixed hex, or the name of a contract added earlier to the genesis file.
+pub fn parse_library_link(s: &str) -> Result<(String, LibraryLink), String> {
+    let (lib, link) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected <library>=<address>: {s}"))?;
+
+    let link = if link.starts_with("0x") {
+        LibraryLink::Address(parse_eth_address(link)?)
+    } else {
+        LibraryLink::Contract(link.to_string())
+    };
+
+    Ok((lib.to_string(), link))
+}
@@ -8,6 +8,7 @@ use cid::Cid;
 use clap::{Args, Subcommand, ValueEnum};
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::{address::Address, econ::TokenAmount, MethodNum};
+use ipc_api::subnet_id::SubnetID;
 use tendermint_rpc::Url;
 
 use crate::{
@@ -74,6 +75,64 @@ pub enum RpcCommands {
         #[command(flatten)]
         args: TransArgs,
     },
+    /// Manage who can deploy contracts through the EAM actor.
+    Eam {
+        #[command(subcommand)]
+        command: RpcEamCommands,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum RpcEamCommands {
+    /// Allow accounts to deploy contracts; the sender has to be the EAM admin.
+    AddDeployer {
+        /// Addresses of the accounts to allow.
+        #[arg(long, short, required = true, value_delimiter = ',', value_parser = parse_address)]
+        deployers: Vec<Address>,
+        #[command(flatten)]
+        args: TransArgs,
+    },
+    /// Stop accounts from deploying contracts; the sender has to be the EAM admin.
+    RemoveDeployer {
+        /// Addresses of the accounts to disallow.
+        #[arg(long, short, required = true, value_delimiter = ',', value_parser = parse_address)]
+        deployers: Vec<Address>,
+        #[command(flatten)]
+        args: TransArgs,
+    },
+    /// Switch between unrestricted and allow-list modes; the sender has to be the EAM admin.
+    SetMode {
+        /// Permission mode (unrestricted/allowlist) that controls who can deploy contracts.
+        #[arg(long, short)]
+        mode: EamPermissionMode,
+        /// Addresses allowed to deploy contracts in allow-list mode, replacing the current ones.
+        #[arg(long, short = 'a', value_delimiter = ',', value_parser = parse_address)]
+        deployers: Vec<Address>,
+        #[command(flatten)]
+        args: TransArgs,
+    },
+    /// Hand over the administration of the EAM to another account; the sender has to be the EAM admin.
+    ChangeAdmin {
+        /// Address of the new admin; leaving it out renounces the administration for good.
+        #[arg(long, short = 'a', value_parser = parse_address)]
+        admin: Option<Address>,
+        #[command(flatten)]
+        args: TransArgs,
+    },
+    /// List the accounts allowed to deploy contracts; print them as JSON.
+    ListDeployers {
+        /// Block height to query; 0 means latest.
+        #[arg(long, short = 'b', default_value_t = 0)]
+        height: u64,
+    },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum EamPermissionMode {
+    /// Anyone can deploy contracts.
+    Unrestricted,
+    /// Only the listed deployers can deploy contracts.
+    Allowlist,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -90,8 +149,34 @@ pub enum RpcQueryCommands {
         #[arg(long, short, value_parser = parse_address)]
         address: Address,
     },
+    /// Get the vested and unvested balances of a vesting actor; print them as JSON.
+    Vesting {
+        /// Address of the vesting actor to query.
+        #[arg(long, short, value_parser = parse_address)]
+        address: Address,
+    },
+    /// Get the block hashes kept by the chain metadata actor in an inclusive range of heights;
+    /// print them as JSON.
+    BlockHashes {
+        /// First block height of the range.
+        #[arg(long)]
+        from: i64,
+        /// Last block height of the range.
+        #[arg(long)]
+        to: i64,
+    },
     /// Get the slowly changing state parameters.
     StateParams,
+    /// Get the quorum certificate of the parent finality committed at a parent block height,
+    /// verify the votes in it, and print it as JSON.
+    FinalityCertificate {
+        /// Height of the finalized block on the parent chain.
+        #[arg(long)]
+        parent_height: u64,
+        /// The subnet the votes must have been cast in.
+        #[arg(long, short)]
+        subnet_id: SubnetID,
+    },
 }
 
 #[derive(Subcommand, Debug, Clone)]
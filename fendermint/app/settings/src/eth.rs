@@ -7,6 +7,7 @@ use ipc_provider::config::deserialize::{
 };
 use serde::Deserialize;
 use serde_with::{serde_as, DurationSeconds};
+use std::collections::HashMap;
 use std::time::Duration;
 use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin};
 
@@ -26,6 +27,7 @@ pub struct EthSettings {
     pub max_nonce_gap: u64,
     pub metrics: MetricsSettings,
     pub cors: CorsOpt,
+    pub limits: LimitOpt,
     pub tracing: TracingSettings,
 }
 
@@ -49,3 +51,37 @@ pub struct CorsOpt {
     #[serde(deserialize_with = "deserialize_cors_headers")]
     pub allowed_headers: AllowHeaders,
 }
+
+/// A token bucket: clients can burst up to the capacity, then the rate is limited to the refill.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenBucketOpt {
+    pub capacity: u32,
+    pub refill_per_sec: u32,
+}
+
+/// Limits on what clients can ask of the API; `None` means unlimited.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LimitOpt {
+    /// Token bucket of each client IP address without an API key.
+    pub per_ip: Option<TokenBucketOpt>,
+    /// Token bucket of each API key.
+    pub per_api_key: Option<TokenBucketOpt>,
+    /// The HTTP header clients send their API key in.
+    pub api_key_header: String,
+    /// API keys entitled to the `per_api_key` limits.
+    pub api_keys: Vec<String>,
+    /// Number of tokens a method call costs, unless it has a cost in `method_costs`.
+    pub default_method_cost: u32,
+    /// Number of tokens calling expensive methods costs.
+    pub method_costs: HashMap<String, u32>,
+    /// Maximum number of requests in a JSON-RPC batch.
+    pub max_batch_size: Option<usize>,
+    /// Maximum number of blocks `eth_getLogs` can query.
+    pub max_log_block_range: Option<u64>,
+    /// Maximum gas limit of `eth_call` and `eth_estimateGas`; higher limits are capped.
+    pub max_call_gas: Option<u64>,
+    /// Maximum number of filters and subscriptions a client can have installed.
+    pub max_filters: Option<usize>,
+    /// Maximum number of subscriptions a WebSocket connection can open.
+    pub max_subscriptions_per_socket: Option<usize>,
+}
@@ -99,6 +99,22 @@ pub struct AbciSettings {
     pub bound: usize,
     /// Maximum number of messages allowed in a block.
     pub block_max_msgs: usize,
+    /// The ABCI protocol version spoken with CometBFT.
+    #[serde(default)]
+    pub version: AbciVersion,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// The version of the ABCI protocol, which has to match the version of CometBFT the application is connected to.
+pub enum AbciVersion {
+    /// ABCI 1.0, used by CometBFT 0.37: blocks are executed with `BeginBlock`, `DeliverTx` and `EndBlock`,
+    /// parent finality votes are gossiped over the IPLD resolver and checkpoint signatures are sent as transactions.
+    #[default]
+    V037,
+    /// ABCI 2.0, used by CometBFT 0.38: blocks are executed with `FinalizeBlock`, and parent finality votes
+    /// and checkpoint signatures are attached to the precommits as vote extensions.
+    V038,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -131,14 +147,78 @@ impl Display for DbCompaction {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// The embedded database engine used to store the ledger and the application state.
+pub enum DbBackend {
+    /// RocksDB, stored in the `rocksdb` directory under the data directory.
+    #[default]
+    RocksDb,
+    /// redb, a pure-Rust embedded database, stored in a single `fendermint.redb` file under the data directory.
+    Redb,
+}
+
+impl Display for DbBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbBackend::RocksDb => write!(f, "rocksdb"),
+            DbBackend::Redb => write!(f, "redb"),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct DbSettings {
     /// Length of the app state history to keep in the database before pruning; 0 means unlimited.
     ///
     /// This affects how long we can go back in state queries.
     pub state_hist_size: u64,
-    /// How to compact the datastore.
+    /// How to compact the datastore; only applies to RocksDB.
     pub compaction_style: DbCompaction,
+    /// The database engine to use. Switching it requires migrating the data with `fendermint db migrate`.
+    #[serde(default)]
+    pub backend: DbBackend,
+    /// Online backups of the database; only supported with RocksDB.
+    #[serde(default)]
+    pub backup: DbBackupSettings,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DbBackupSettings {
+    /// Number of most recent backups to keep; older ones are removed after a new one is created. 0 means unlimited.
+    pub keep: usize,
+    /// Local HTTP endpoint through which `fendermint db backup` asks the running node to create a backup.
+    #[serde(default)]
+    pub admin: DbAdminSettings,
+}
+
+impl Default for DbBackupSettings {
+    fn default() -> Self {
+        Self {
+            keep: 5,
+            admin: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DbAdminSettings {
+    /// Enable the admin endpoint.
+    pub enabled: bool,
+    /// HTTP listen address of the admin endpoint.
+    pub listen: SocketAddress,
+}
+
+impl Default for DbAdminSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: SocketAddress {
+                host: "127.0.0.1".into(),
+                port: 26660,
+            },
+        }
+    }
 }
 
 /// Settings affecting how we deal with failures in trying to send transactions to the local CometBFT node.
@@ -235,6 +315,9 @@ pub struct SnapshotSettings {
     pub block_interval: BlockHeight,
     /// Number of snapshots to keep before purging old ones.
     pub hist_size: usize,
+    /// Number of delta snapshots to create on top of a full snapshot before creating
+    /// the next full one. Zero means every snapshot is a full one.
+    pub max_deltas: usize,
     /// Target chunk size, in bytes.
     pub chunk_size_bytes: usize,
     /// How long to keep a snapshot from being purged after it has been requested by a peer.
@@ -269,6 +352,8 @@ pub struct Settings {
     data_dir: PathBuf,
     /// State snapshots.
     snapshots_dir: PathBuf,
+    /// Database backups.
+    backups_dir: PathBuf,
     /// Solidity contracts.
     contracts_dir: PathBuf,
 
@@ -295,7 +380,7 @@ pub struct Settings {
 }
 
 impl Settings {
-    home_relative!(data_dir, snapshots_dir, contracts_dir);
+    home_relative!(data_dir, snapshots_dir, backups_dir, contracts_dir);
 
     /// Load the default configuration from a directory,
     /// then potential overrides specific to the run mode,
@@ -328,12 +413,14 @@ impl Settings {
                     .list_separator(",") // need to list keys explicitly below otherwise it can't pase simple `String` type
                     .with_list_parse_key("tracing.file.domain_filter")
                     .with_list_parse_key("tracing.file.events_filter")
+                    .with_list_parse_key("resolver.connection.listen_addrs")
                     .with_list_parse_key("resolver.connection.external_addresses")
                     .with_list_parse_key("resolver.discovery.static_addresses")
                     .with_list_parse_key("resolver.membership.static_subnets")
                     .with_list_parse_key("eth.cors.allowed_origins")
                     .with_list_parse_key("eth.cors.allowed_methods")
                     .with_list_parse_key("eth.cors.allowed_headers")
+                    .with_list_parse_key("eth.limits.api_keys")
                     .with_list_parse_key("eth.tracing.file.domain_filter")
                     .with_list_parse_key("eth.tracing.file.events_filter"),
             ))
@@ -372,7 +459,11 @@ impl Settings {
 
     /// Indicate whether we have configured the IPLD Resolver to run.
     pub fn resolver_enabled(&self) -> bool {
-        !self.resolver.connection.listen_addr.is_empty()
+        self.resolver
+            .connection
+            .listen_addrs
+            .iter()
+            .any(|addr| !addr.is_empty())
             && self.ipc.subnet_id != *ipc_api::subnet_id::UNDEF
     }
 }
@@ -417,6 +508,24 @@ mod tests {
     fn parse_test_config() {
         let settings = parse_config("test");
         assert!(settings.resolver_enabled());
+        assert_eq!(settings.resolver.connection.listen_addrs.len(), 2);
+    }
+
+    #[test]
+    fn parse_single_listen_addr() {
+        let settings = with_env_vars(
+            vec![(
+                "FM_RESOLVER__CONNECTION__LISTEN_ADDR",
+                "/ip4/198.51.100.2/udp/1234/quic-v1",
+            )],
+            || try_parse_config(""),
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings.resolver.connection.listen_addrs,
+            vec![multiaddr!(Ip4([198, 51, 100, 2]), Udp(1234u16), QuicV1)]
+        );
     }
 
     #[test]
@@ -437,6 +546,7 @@ mod tests {
                 ("FM_RESOLVER__NETWORK__NETWORK_NAME", "test"),
             ], || try_parse_config("")).unwrap();
 
+        assert_eq!(settings.resolver.connection.listen_addrs.len(), 2);
         assert_eq!(settings.resolver.connection.external_addresses.len(), 2);
         assert_eq!(settings.resolver.discovery.static_addresses.len(), 2);
         assert_eq!(settings.resolver.membership.static_subnets.len(), 2);
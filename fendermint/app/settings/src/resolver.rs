@@ -4,12 +4,12 @@
 use std::{path::PathBuf, time::Duration};
 
 use serde::Deserialize;
-use serde_with::{serde_as, DurationSeconds};
+use serde_with::{formats::PreferMany, serde_as, DisplayFromStr, DurationSeconds, OneOrMany};
 
 use ipc_api::subnet_id::SubnetID;
-use multiaddr::Multiaddr;
+use multiaddr::{Multiaddr, PeerId};
 
-use crate::{home_relative, IsHumanReadable};
+use crate::{home_relative, IsHumanReadable, SocketAddress};
 
 #[serde_as]
 #[derive(Debug, Clone, Deserialize)]
@@ -22,6 +22,8 @@ pub struct ResolverSettings {
     pub membership: MembershipSettings,
     pub connection: ConnectionSettings,
     pub content: ContentSettings,
+    #[serde(default)]
+    pub admin: AdminSettings,
 }
 
 /// Settings describing the subnet hierarchy, not the physical network.
@@ -77,10 +79,15 @@ pub struct MembershipSettings {
     pub max_provider_age: Duration,
 }
 
+#[serde_as]
 #[derive(Debug, Clone, Deserialize)]
 pub struct ConnectionSettings {
-    /// The address where we will listen to incoming connections.
-    pub listen_addr: Multiaddr,
+    /// The addresses where we will listen to incoming connections.
+    ///
+    /// Accepts a single address as well, under the `listen_addr` key used by earlier versions.
+    #[serde(alias = "listen_addr", default)]
+    #[serde_as(as = "OneOrMany<_, PreferMany>")]
+    pub listen_addrs: Vec<Multiaddr>,
     /// A list of known external addresses this node is reachable on.
     pub external_addresses: Vec<Multiaddr>,
     /// Maximum number of incoming connections.
@@ -92,6 +99,25 @@ pub struct ConnectionSettings {
     /// Maximum number of events in the push-based broadcast channel before a slow
     /// consumer gets an error because it's falling behind.
     pub event_buffer_capacity: u32,
+    /// Peers which are always allowed to connect, even in validator-only mode.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub allow_list: Vec<PeerId>,
+    /// Peers which are never allowed to connect.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub deny_list: Vec<PeerId>,
+    /// Only allow connections from peers whose network key belongs to a validator
+    /// in the current power table, or which are on the allow list.
+    pub validator_only: bool,
+    /// Penalty score at which a misbehaving peer gets banned.
+    pub ban_threshold: u32,
+    /// How long a banned peer is kept from connecting.
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub ban_duration: Duration,
+    /// How long it takes for penalties worth the ban threshold to be forgiven; 0 means never.
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub penalty_decay: Duration,
+    /// Maximum number of peers to keep track of penalties for.
+    pub max_penalized_peers: usize,
 }
 
 /// Configuration for [`content::Behaviour`].
@@ -108,3 +134,24 @@ pub struct ContentSettings {
     #[serde_as(as = "DurationSeconds<u64>")]
     pub rate_limit_period: Duration,
 }
+
+/// Local HTTP endpoint to inspect the state of the resolver, used by `fendermint debug resolver`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminSettings {
+    /// Enable the admin endpoint.
+    pub enabled: bool,
+    /// HTTP listen address of the admin endpoint.
+    pub listen: SocketAddress,
+}
+
+impl Default for AdminSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: SocketAddress {
+                host: "127.0.0.1".into(),
+                port: 26659,
+            },
+        }
+    }
+}
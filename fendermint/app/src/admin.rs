@@ -0,0 +1,36 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! Scaffolding shared by the local HTTP admin endpoints of the node.
+
+use std::net::ToSocketAddrs;
+
+use anyhow::anyhow;
+use axum::http::StatusCode;
+use axum::{Json, Router};
+use serde_json::Value;
+
+/// Result of the admin handlers: JSON on success, a status and a message on failure.
+pub type JsonResult = Result<Json<Value>, (StatusCode, String)>;
+
+/// Serve the routes of an admin endpoint until the server fails.
+pub async fn serve<A: ToSocketAddrs>(
+    listen_addr: A,
+    router: Router,
+    name: &str,
+) -> anyhow::Result<()> {
+    let listen_addr = listen_addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("no socket address to listen on"))?;
+
+    let server = axum::Server::try_bind(&listen_addr)?.serve(router.into_make_service());
+
+    tracing::info!(?listen_addr, "bound {name} admin API");
+    server.await?;
+    Ok(())
+}
+
+/// Report a failure of the node as an internal server error.
+pub fn internal_error(e: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}"))
+}
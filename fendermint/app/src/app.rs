@@ -13,10 +13,12 @@ use fendermint_storage::{
     Codec, Encode, KVCollection, KVRead, KVReadable, KVStore, KVWritable, KVWrite,
 };
 use fendermint_vm_core::Timestamp;
+use fendermint_vm_genesis::{Power, Validator};
 use fendermint_vm_interpreter::bytes::{
     BytesMessageApplyRes, BytesMessageCheckRes, BytesMessageQuery, BytesMessageQueryRes,
 };
-use fendermint_vm_interpreter::chain::{ChainEnv, ChainMessageApplyRet, IllegalMessage};
+use fendermint_vm_interpreter::chain::{ChainEnv, IllegalMessage};
+use fendermint_vm_interpreter::fvm::state::ipc::GatewayCaller;
 use fendermint_vm_interpreter::fvm::state::{
     empty_state_tree, CheckStateRef, FvmExecState, FvmQueryState, FvmStateParams,
     FvmUpdatableParams,
@@ -28,8 +30,12 @@ use fendermint_vm_interpreter::signed::InvalidSignature;
 use fendermint_vm_interpreter::{
     CheckInterpreter, ExecInterpreter, ProposalInterpreter, QueryInterpreter,
 };
+use fendermint_vm_message::chain::ChainMessage;
+use fendermint_vm_message::ipc::IpcMessage;
 use fendermint_vm_message::query::FvmQueryHeight;
+use fendermint_vm_message::query::{PARENT_FINALITY_CERTIFICATE_PATH, PARENT_VIEW_PATH};
 use fendermint_vm_snapshot::{SnapshotClient, SnapshotError};
+use fendermint_vm_topdown::certificate::QuorumCertificate;
 use fvm::engine::MultiEngine;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_shared::chainid::ChainID;
@@ -47,6 +53,7 @@ use crate::observe::{
     BlockCommitted, BlockProposalEvaluated, BlockProposalReceived, BlockProposalSent, Message,
     MpoolReceived,
 };
+use crate::vote_ext::{AppVoteExtension, VoteExtensions};
 use crate::AppExitCode;
 use crate::BlockHeight;
 use crate::{tmconv::*, VERSION};
@@ -83,10 +90,23 @@ pub struct AppState {
 }
 
 impl AppState {
+    #[cfg(test)]
+    pub(crate) fn new(block_height: BlockHeight, state_params: FvmStateParams) -> Self {
+        Self {
+            block_height,
+            oldest_state_height: block_height,
+            state_params,
+        }
+    }
+
     pub fn state_root(&self) -> Cid {
         self.state_params.state_root
     }
 
+    pub fn state_params(&self) -> &FvmStateParams {
+        &self.state_params
+    }
+
     pub fn chain_id(&self) -> ChainID {
         ChainID::from(self.state_params.chain_id)
     }
@@ -95,6 +115,11 @@ impl AppState {
         to_app_hash(&self.state_params)
     }
 
+    /// Last committed block height.
+    pub fn block_height(&self) -> BlockHeight {
+        self.block_height
+    }
+
     /// The state is effective at the *next* block, that is, the effects of block N are visible in the header of block N+1,
     /// so the height of the state itself as a "post-state" is one higher than the block which we executed to create it.
     pub fn state_height(&self) -> BlockHeight {
@@ -109,6 +134,8 @@ pub struct AppConfig<S: KVStore> {
     pub state_hist_namespace: S::Namespace,
     /// Size of state history to keep; 0 means unlimited.
     pub state_hist_size: u64,
+    /// Namespace to store the quorum certificates of committed parent finalities.
+    pub finality_cert_namespace: S::Namespace,
     /// Block height where we should gracefully stop the node
     pub halt_height: i64,
 }
@@ -145,6 +172,11 @@ where
     /// so that we can retrospectively execute FVM messages at past block heights
     /// in read-only mode.
     state_hist: KVCollection<S, BlockHeight, FvmStateParams>,
+    /// Quorum certificates of the committed parent finalities, indexed by parent block height.
+    ///
+    /// These are not part of the ledger; the signed votes can differ between nodes,
+    /// depending on which of them they received over gossip.
+    finality_certs: KVCollection<S, BlockHeight, QuorumCertificate>,
     /// Interpreter for block lifecycle events.
     interpreter: Arc<I>,
     /// Environment-like dependencies for the interpreter.
@@ -153,6 +185,10 @@ where
     snapshots: Option<SnapshotClient>,
     /// State accumulating changes during block execution.
     exec_state: Arc<tokio::sync::Mutex<Option<FvmExecState<SS>>>>,
+    /// State flushed by `finalize_block` in ABCI 2.0, waiting to be committed.
+    finalized_state: Arc<tokio::sync::Mutex<Option<AppState>>>,
+    /// Producing and checking vote extensions in ABCI 2.0; without it the extensions are empty.
+    vote_extensions: Option<VoteExtensions>,
     /// Projected (partial) state accumulating during transaction checks.
     check_state: CheckStateRef<SS>,
     /// How much history to keep.
@@ -167,7 +203,8 @@ where
         + Codec<AppState>
         + Encode<AppStoreKey>
         + Encode<BlockHeight>
-        + Codec<FvmStateParams>,
+        + Codec<FvmStateParams>
+        + Codec<QuorumCertificate>,
     DB: KVWritable<S> + KVReadable<S> + Clone + 'static,
     SS: Blockstore + Clone + 'static,
 {
@@ -187,15 +224,24 @@ where
             namespace: config.app_namespace,
             state_hist: KVCollection::new(config.state_hist_namespace),
             state_hist_size: config.state_hist_size,
+            finality_certs: KVCollection::new(config.finality_cert_namespace),
             interpreter: Arc::new(interpreter),
             chain_env,
             snapshots,
             exec_state: Arc::new(tokio::sync::Mutex::new(None)),
+            finalized_state: Arc::new(tokio::sync::Mutex::new(None)),
+            vote_extensions: None,
             check_state: Arc::new(tokio::sync::Mutex::new(None)),
         };
         app.init_committed_state()?;
         Ok(app)
     }
+
+    /// Exchange parent finality votes and checkpoint signatures in ABCI 2.0 vote extensions.
+    pub fn with_vote_extensions(mut self, vote_extensions: VoteExtensions) -> Self {
+        self.vote_extensions = Some(vote_extensions);
+        self
+    }
 }
 
 impl<DB, SS, S, I> App<DB, SS, S, I>
@@ -204,7 +250,8 @@ where
         + Codec<AppState>
         + Encode<AppStoreKey>
         + Encode<BlockHeight>
-        + Codec<FvmStateParams>,
+        + Codec<FvmStateParams>
+        + Codec<QuorumCertificate>,
     DB: KVWritable<S> + KVReadable<S> + 'static + Clone,
     SS: Blockstore + 'static + Clone,
 {
@@ -282,6 +329,32 @@ where
             .context("commit failed")
     }
 
+    /// Persist the quorum certificates of the parent finalities committed in the block.
+    fn put_finality_certificates(&self, certs: Vec<QuorumCertificate>) -> Result<()> {
+        if certs.is_empty() {
+            return Ok(());
+        }
+        self.db
+            .with_write(|tx| {
+                for cert in certs.iter() {
+                    self.finality_certs.put(tx, &cert.block_height, cert)?;
+                }
+                Ok(())
+            })
+            .context("failed to store finality certificates")
+    }
+
+    /// Look up the quorum certificate of the parent finality committed at a parent block height.
+    fn get_finality_certificate(
+        &self,
+        parent_height: BlockHeight,
+    ) -> Result<Option<QuorumCertificate>> {
+        let tx = self.db.read();
+        self.finality_certs
+            .get(&tx, &parent_height)
+            .context("error looking up finality certificate")
+    }
+
     /// Put the execution state during block execution. Has to be empty.
     async fn put_exec_state(&self, state: FvmExecState<SS>) {
         let mut guard = self.exec_state.lock().await;
@@ -342,6 +415,27 @@ where
         })
     }
 
+    /// The power table of the gateway in the last committed state, which the checkpoint signatures
+    /// in proposals are checked against. Empty if there is no state yet or IPC is not enabled.
+    fn committed_power_table(&self) -> Result<Vec<Validator<Power>>> {
+        let mut state = match self.new_read_only_exec_state()? {
+            Some(state) => state,
+            None => return Ok(Vec::new()),
+        };
+
+        let gateway = GatewayCaller::default();
+
+        if !gateway.enabled(&mut state)? {
+            return Ok(Vec::new());
+        }
+
+        let (_, power_table) = gateway
+            .current_power_table(&mut state)
+            .context("failed to get the current power table")?;
+
+        Ok(power_table)
+    }
+
     /// Look up a past state at a particular height Tendermint Core is looking for.
     ///
     /// A height of zero means we are looking for the latest state.
@@ -386,6 +480,147 @@ where
     }
 }
 
+impl<DB, SS, S, I> App<DB, SS, S, I>
+where
+    S: KVStore
+        + Codec<AppState>
+        + Encode<AppStoreKey>
+        + Encode<BlockHeight>
+        + Codec<FvmStateParams>
+        + Codec<QuorumCertificate>,
+    DB: KVWritable<S> + KVReadable<S> + Clone + Send + Sync + 'static,
+    SS: Blockstore + Clone + Send + Sync + 'static,
+    I: ExecInterpreter<
+        State = (ChainEnv, FvmExecState<SS>),
+        Message = Vec<u8>,
+        BeginOutput = FvmApplyRet,
+        DeliverOutput = BytesMessageApplyRes,
+        EndOutput = PowerUpdates,
+    >,
+{
+    /// Create the execution state for a new block and run the interpreter's begin step on it.
+    async fn exec_begin(
+        &self,
+        height: tendermint::block::Height,
+        hash: tendermint::Hash,
+        time: tendermint::Time,
+        proposer_address: tendermint::account::Id,
+    ) -> Result<response::BeginBlock> {
+        let block_height = height.into();
+        let block_hash = match hash {
+            tendermint::Hash::Sha256(h) => h,
+            tendermint::Hash::None => return Err(anyhow!("empty block hash")),
+        };
+
+        if self.halt_height != 0 && block_height == self.halt_height {
+            tracing::info!(
+                height = block_height,
+                "Stopping node due to reaching halt height"
+            );
+            std::process::exit(AppExitCode::Halt as i32);
+        }
+
+        let db = self.state_store_clone();
+        let state = self.committed_state()?;
+        let mut state_params = state.state_params.clone();
+
+        tracing::debug!(
+            height = block_height,
+            timestamp = time.unix_timestamp(),
+            //app_state_hash = to_app_hash(&state_params).to_string(), // should be the same as `app_hash`
+            "begin block"
+        );
+
+        state_params.timestamp = to_timestamp(time);
+
+        let state = FvmExecState::new(db, self.multi_engine.as_ref(), block_height, state_params)
+            .context("error creating new state")?
+            .with_block_hash(block_hash)
+            .with_validator_id(proposer_address);
+
+        tracing::debug!("initialized exec state");
+
+        self.put_exec_state(state).await;
+
+        let ret = self
+            .modify_exec_state(|s| self.interpreter.begin(s))
+            .await
+            .context("begin failed")?;
+
+        Ok(to_begin_block(ret))
+    }
+
+    /// Apply a transaction to the execution state of the current block.
+    async fn exec_deliver(&self, msg: Vec<u8>) -> Result<response::DeliverTx> {
+        let (result, block_hash) = self
+            .modify_exec_state(|s| async {
+                let ((env, state), res) = self.interpreter.deliver(s, msg).await?;
+                let block_hash = state.block_hash();
+                Ok(((env, state), (res, block_hash)))
+            })
+            .await
+            .context("deliver failed")?;
+
+        let response = to_deliver_tx_result(result, block_hash);
+
+        if response.code != 0.into() {
+            tracing::info!(
+                "deliver_tx failed: {:?} - {:?}",
+                response.code,
+                response.info
+            );
+        }
+
+        Ok(response)
+    }
+
+    /// Run the interpreter's end step on the execution state of the current block.
+    async fn exec_end(&self) -> Result<response::EndBlock> {
+        // TODO: Return events from epoch transitions.
+        let ret = self
+            .modify_exec_state(|s| self.interpreter.end(s))
+            .await
+            .context("end failed")?;
+
+        to_end_block(ret)
+    }
+
+    /// Flush the execution state of the block to the blockstore and return the application state
+    /// it results in, without making it the committed state yet.
+    fn flush_exec_state(&self, exec_state: FvmExecState<SS>) -> Result<AppState> {
+        let mut state = self.committed_state()?;
+        state.block_height = exec_state.block_height().try_into()?;
+        state.state_params.timestamp = exec_state.timestamp();
+
+        let (
+            state_root,
+            FvmUpdatableParams {
+                app_version,
+                base_fee,
+                circ_supply,
+                power_scale,
+            },
+            _,
+        ) = exec_state.commit().context("failed to commit FVM")?;
+
+        state.state_params.state_root = state_root;
+        state.state_params.app_version = app_version;
+        state.state_params.base_fee = base_fee;
+        state.state_params.circ_supply = circ_supply;
+        state.state_params.power_scale = power_scale;
+
+        tracing::debug!(
+            block_height = state.block_height,
+            state_root = state_root.to_string(),
+            app_hash = state.app_hash().to_string(),
+            timestamp = state.state_params.timestamp.0,
+            "commit state"
+        );
+
+        Ok(state)
+    }
+}
+
 // NOTE: The `Application` interface doesn't allow failures at the moment. The protobuf
 // of `Response` actually has an `Exception` type, so in theory we could use that, and
 // Tendermint would break up the connection. However, before the response could reach it,
@@ -398,7 +633,8 @@ where
         + Codec<AppState>
         + Encode<AppStoreKey>
         + Encode<BlockHeight>
-        + Codec<FvmStateParams>,
+        + Codec<FvmStateParams>
+        + Codec<QuorumCertificate>,
     S::Namespace: Sync + Send,
     DB: KVWritable<S> + KVReadable<S> + Clone + Send + Sync + 'static,
     SS: Blockstore + Clone + Send + Sync + 'static,
@@ -438,6 +674,7 @@ where
     }
 
     /// Called once upon genesis.
+    #[instrument(skip_all)]
     async fn init_chain(&self, request: request::InitChain) -> AbciResult<response::InitChain> {
         let genesis_bytes = Self::parse_genesis_app_bytes(&request.app_state_bytes)?;
         let genesis_hash =
@@ -493,6 +730,23 @@ where
     /// Query the application for data at the current or past height.
     #[instrument(skip(self))]
     async fn query(&self, request: request::Query) -> AbciResult<response::Query> {
+        if request.path == PARENT_FINALITY_CERTIFICATE_PATH {
+            let parent_height: BlockHeight = match fvm_ipld_encoding::from_slice(&request.data) {
+                Ok(h) => h,
+                Err(e) => return Ok(invalid_query(AppError::InvalidEncoding, e.to_string())),
+            };
+            let cert = self.get_finality_certificate(parent_height)?;
+            let state = self.committed_state()?;
+            return Ok(to_certificate_query(cert, state.block_height)?);
+        }
+
+        if request.path == PARENT_VIEW_PATH {
+            let entries =
+                atomically(|| self.chain_env.parent_finality_provider.cached_entries()).await;
+            let state = self.committed_state()?;
+            return Ok(to_parent_view_query(entries, state.block_height)?);
+        }
+
         let db = self.state_store_clone();
         let height = FvmQueryHeight::from(request.height.value());
         let (state_params, block_height) = self.state_params_at_height(height)?;
@@ -538,6 +792,7 @@ where
     }
 
     /// Check the given transaction before putting it into the local mempool.
+    #[instrument(skip_all)]
     async fn check_tx(&self, request: request::CheckTx) -> AbciResult<response::CheckTx> {
         // Keep the guard through the check, so there can be only one at a time.
         let mut guard = self.check_state.lock().await;
@@ -599,6 +854,7 @@ where
     }
 
     /// Amend which transactions to put into the next block proposal.
+    #[instrument(skip_all, fields(height = request.height.value()))]
     async fn prepare_proposal(
         &self,
         request: request::PrepareProposal,
@@ -610,12 +866,34 @@ where
         );
         let txs = request.txs.into_iter().map(|tx| tx.to_vec()).collect();
 
-        let txs = self
+        // With ABCI 2.0, collect the votes and signatures the validators attached to their precommits
+        // on the previous block, before the interpreter looks for a parent finality quorum.
+        let signatures = match (&self.vote_extensions, &request.local_last_commit) {
+            (Some(vote_extensions), Some(commit)) => {
+                let exts = commit
+                    .votes
+                    .iter()
+                    .filter(|v| !v.vote_extension.is_empty())
+                    .map(|v| v.vote_extension.as_ref());
+
+                vote_extensions.collect(&self.chain_env, exts).await
+            }
+            _ => Vec::new(),
+        };
+
+        let mut txs = self
             .interpreter
             .prepare(self.chain_env.clone(), txs)
             .await
             .context("failed to prepare proposal")?;
 
+        if !signatures.is_empty() {
+            let msg = ChainMessage::Ipc(IpcMessage::CheckpointSignatures(signatures));
+            let msg = fvm_ipld_encoding::to_vec(&msg)
+                .context("failed to encode checkpoint signatures")?;
+            txs.insert(0, msg);
+        }
+
         let txs = txs.into_iter().map(bytes::Bytes::from).collect();
         let (txs, size) = take_until_max_size(txs, request.max_tx_bytes.try_into().unwrap());
 
@@ -630,6 +908,7 @@ where
     }
 
     /// Inspect a proposal and decide whether to vote on it.
+    #[instrument(skip_all, fields(height = request.height.value()))]
     async fn process_proposal(
         &self,
         request: request::ProcessProposal,
@@ -643,9 +922,18 @@ where
         let size_txs = txs.iter().map(|tx| tx.len()).sum::<usize>();
         let num_txs = txs.len();
 
+        // Checkpoint signatures can only come from vote extensions, and they have to be checked
+        // against the ledger, so every validator comes to the same decision.
+        let mut chain_env = self.chain_env.clone();
+        if chain_env.vote_extensions {
+            chain_env.power_table = self
+                .committed_power_table()
+                .context("failed to get the committed power table")?;
+        }
+
         let accept = self
             .interpreter
-            .process(self.chain_env.clone(), txs)
+            .process(chain_env, txs)
             .await
             .context("failed to process proposal")?;
 
@@ -675,128 +963,140 @@ where
     }
 
     /// Signals the beginning of a new block, prior to any `DeliverTx` calls.
+    #[instrument(skip_all, fields(height = request.header.height.value()))]
     async fn begin_block(&self, request: request::BeginBlock) -> AbciResult<response::BeginBlock> {
-        let block_height = request.header.height.into();
-        let block_hash = match request.hash {
-            tendermint::Hash::Sha256(h) => h,
-            tendermint::Hash::None => return Err(anyhow!("empty block hash").into()),
-        };
+        let ret = self
+            .exec_begin(
+                request.header.height,
+                request.hash,
+                request.header.time,
+                request.header.proposer_address,
+            )
+            .await?;
 
-        if self.halt_height != 0 && block_height == self.halt_height {
-            tracing::info!(
-                height = block_height,
-                "Stopping node due to reaching halt height"
-            );
-            std::process::exit(AppExitCode::Halt as i32);
-        }
+        Ok(ret)
+    }
 
-        let db = self.state_store_clone();
-        let state = self.committed_state()?;
-        let mut state_params = state.state_params.clone();
+    /// Apply a transaction to the application's state.
+    #[instrument(skip_all)]
+    async fn deliver_tx(&self, request: request::DeliverTx) -> AbciResult<response::DeliverTx> {
+        let response = self.exec_deliver(request.tx.to_vec()).await?;
+        Ok(response)
+    }
+
+    /// Signals the end of a block.
+    #[instrument(skip_all, fields(height = request.height))]
+    async fn end_block(&self, request: request::EndBlock) -> AbciResult<response::EndBlock> {
+        tracing::debug!(height = request.height, "end block");
+        let response = self.exec_end().await?;
+        Ok(response)
+    }
 
+    /// Execute a whole block, as ABCI 2.0 delivers it.
+    ///
+    /// The state is flushed to the blockstore to get the application hash, but it
+    /// only becomes the committed state of the application in the following `commit`.
+    #[instrument(skip_all, fields(height = request.height.value()))]
+    async fn finalize_block(
+        &self,
+        request: request::FinalizeBlock,
+    ) -> AbciResult<response::FinalizeBlock> {
         tracing::debug!(
-            height = block_height,
-            timestamp = request.header.time.unix_timestamp(),
-            app_hash = request.header.app_hash.to_string(),
-            //app_state_hash = to_app_hash(&state_params).to_string(), // should be the same as `app_hash`
-            "begin block"
+            height = request.height.value(),
+            tx_count = request.txs.len(),
+            "finalize block"
         );
 
-        state_params.timestamp = to_timestamp(request.header.time);
+        if self.finalized_state.lock().await.is_some() {
+            return Err(anyhow!("the previously finalized block has not been committed").into());
+        }
 
-        let state = FvmExecState::new(db, self.multi_engine.as_ref(), block_height, state_params)
-            .context("error creating new state")?
-            .with_block_hash(block_hash)
-            .with_validator_id(request.header.proposer_address);
+        let begin = self
+            .exec_begin(
+                request.height,
+                request.hash,
+                request.time,
+                request.proposer_address,
+            )
+            .await?;
 
-        tracing::debug!("initialized exec state");
+        let mut txs = Vec::with_capacity(request.txs.len());
+        for tx in request.txs {
+            txs.push(self.exec_deliver(tx.to_vec()).await?);
+        }
 
-        self.put_exec_state(state).await;
+        let end = self.exec_end().await?;
 
-        let ret = self
-            .modify_exec_state(|s| self.interpreter.begin(s))
-            .await
-            .context("begin failed")?;
+        let exec_state = self.take_exec_state().await;
+        let state = self.flush_exec_state(exec_state)?;
+        let app_hash = state.app_hash();
 
-        Ok(to_begin_block(ret))
-    }
+        *self.finalized_state.lock().await = Some(state);
 
-    /// Apply a transaction to the application's state.
-    async fn deliver_tx(&self, request: request::DeliverTx) -> AbciResult<response::DeliverTx> {
-        let msg = request.tx.to_vec();
-        let (result, block_hash) = self
-            .modify_exec_state(|s| async {
-                let ((env, state), res) = self.interpreter.deliver(s, msg).await?;
-                let block_hash = state.block_hash();
-                Ok(((env, state), (res, block_hash)))
-            })
-            .await
-            .context("deliver failed")?;
+        Ok(to_finalize_block(begin, txs, end, app_hash))
+    }
 
-        let response = match result {
-            Err(e) => invalid_deliver_tx(AppError::InvalidEncoding, e.description),
-            Ok(ret) => match ret {
-                ChainMessageApplyRet::Signed(Err(InvalidSignature(d))) => {
-                    invalid_deliver_tx(AppError::InvalidSignature, d)
-                }
-                ChainMessageApplyRet::Signed(Ok(ret)) => {
-                    to_deliver_tx(ret.fvm, ret.domain_hash, block_hash)
-                }
-                ChainMessageApplyRet::Ipc(ret) => to_deliver_tx(ret, None, block_hash),
-            },
+    /// Attach our parent finality vote and checkpoint signatures to our precommit.
+    #[instrument(skip_all, fields(height = request.height.value()))]
+    async fn extend_vote(&self, request: request::ExtendVote) -> AbciResult<response::ExtendVote> {
+        let ext = match self.vote_extensions {
+            Some(ref vote_extensions) => vote_extensions
+                .extend(&self.chain_env)
+                .await
+                .context("failed to extend vote")?,
+            None => AppVoteExtension::default(),
         };
 
-        if response.code != 0.into() {
-            tracing::info!(
-                "deliver_tx failed: {:?} - {:?}",
-                response.code,
-                response.info
-            );
-        }
+        let vote_extension = if ext.is_empty() {
+            Default::default()
+        } else {
+            ext.encode()?.into()
+        };
 
-        Ok(response)
+        Ok(response::ExtendVote { vote_extension })
     }
 
-    /// Signals the end of a block.
-    async fn end_block(&self, request: request::EndBlock) -> AbciResult<response::EndBlock> {
-        tracing::debug!(height = request.height, "end block");
-
-        // TODO: Return events from epoch transitions.
-        let ret = self
-            .modify_exec_state(|s| self.interpreter.end(s))
-            .await
-            .context("end failed")?;
-
-        let r = to_end_block(ret)?;
+    /// Check the vote extension of another validator.
+    #[instrument(skip_all, fields(height = request.height.value()))]
+    async fn verify_vote_extension(
+        &self,
+        request: request::VerifyVoteExtension,
+    ) -> AbciResult<response::VerifyVoteExtension> {
+        let vote_extensions = match self.vote_extensions {
+            Some(ref vote_extensions) => vote_extensions,
+            None => return Ok(response::VerifyVoteExtension::Accept),
+        };
 
-        Ok(r)
+        match vote_extensions.verify(
+            &request.validator_address,
+            request.height.value(),
+            &request.vote_extension,
+        ) {
+            Ok(_) => Ok(response::VerifyVoteExtension::Accept),
+            Err(e) => {
+                tracing::warn!(
+                    validator = request.validator_address.to_string(),
+                    error = format!("{e:#}"),
+                    "rejecting vote extension"
+                );
+                Ok(response::VerifyVoteExtension::Reject)
+            }
+        }
     }
 
     /// Commit the current state at the current height.
+    #[instrument(skip_all)]
     async fn commit(&self) -> AbciResult<response::Commit> {
-        let exec_state = self.take_exec_state().await;
+        // With ABCI 2.0 the state has already been flushed in `finalize_block`.
+        let finalized_state = self.finalized_state.lock().await.take();
 
-        // Commit the execution state to the datastore.
-        let mut state = self.committed_state()?;
-        state.block_height = exec_state.block_height().try_into()?;
-        state.state_params.timestamp = exec_state.timestamp();
-
-        let (
-            state_root,
-            FvmUpdatableParams {
-                app_version,
-                base_fee,
-                circ_supply,
-                power_scale,
-            },
-            _,
-        ) = exec_state.commit().context("failed to commit FVM")?;
-
-        state.state_params.state_root = state_root;
-        state.state_params.app_version = app_version;
-        state.state_params.base_fee = base_fee;
-        state.state_params.circ_supply = circ_supply;
-        state.state_params.power_scale = power_scale;
+        let state = match finalized_state {
+            Some(state) => state,
+            None => {
+                let exec_state = self.take_exec_state().await;
+                self.flush_exec_state(exec_state)?
+            }
+        };
 
         let app_hash = state.app_hash();
         let block_height = state.block_height;
@@ -808,14 +1108,6 @@ where
             block_height.saturating_sub(self.state_hist_size)
         };
 
-        tracing::debug!(
-            block_height,
-            state_root = state_root.to_string(),
-            app_hash = app_hash.to_string(),
-            timestamp = state.state_params.timestamp.0,
-            "commit state"
-        );
-
         // TODO: We can defer committing changes the resolution pool to this point.
         // For example if a checkpoint is successfully executed, that's when we want to remove
         // that checkpoint from the pool, and not propose it to other validators again.
@@ -849,6 +1141,10 @@ where
             atomically(|| snapshots.notify(block_height, state.state_params.clone())).await;
         }
 
+        // Keep the evidence for the parent finalities committed in this block.
+        let certs = atomically(|| self.chain_env.parent_finality_votes.take_certificates()).await;
+        self.put_finality_certificates(certs)?;
+
         // Commit app state to the datastore.
         self.set_committed_state(state)?;
 
@@ -920,7 +1216,7 @@ where
             match from_snapshot(request).context("failed to parse snapshot") {
                 Ok(manifest) => {
                     tracing::info!(?manifest, "received snapshot offer");
-                    // We can look at the version but currently there's only one.
+                    // Full snapshots and delta snapshots streamed together with their chain are accepted.
                     match atomically_or_err(|| client.offer_snapshot(manifest.clone())).await {
                         Ok(path) => {
                             tracing::info!(
@@ -1041,3 +1337,247 @@ where
         Ok(default)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use fendermint_abci::Application;
+    use fendermint_crypto::SecretKey;
+    use fendermint_rocksdb::{RocksDb, RocksDbConfig};
+    use fendermint_vm_genesis::Genesis;
+    use fendermint_vm_interpreter::bytes::{BytesMessageInterpreter, ProposalPrepareMode};
+    use fendermint_vm_interpreter::chain::{ChainEnv, ChainMessageInterpreter, CheckpointPool};
+    use fendermint_vm_interpreter::fvm::bundle::{bundle_path, custom_actors_bundle_path};
+    use fendermint_vm_interpreter::fvm::store::memory::MemoryBlockstore;
+    use fendermint_vm_interpreter::fvm::upgrades::UpgradeScheduler;
+    use fendermint_vm_interpreter::fvm::{CheckpointSignaturePool, FvmMessageInterpreter};
+    use fendermint_vm_interpreter::genesis::{read_genesis_car, GenesisBuilder};
+    use fendermint_vm_interpreter::signed::SignedMessageInterpreter;
+    use fendermint_vm_message::chain::ChainMessage;
+    use fendermint_vm_message::conv::from_fvm;
+    use fendermint_vm_message::ipc::{CheckpointSignature, IpcMessage};
+    use fendermint_vm_message::signed::sign_secp256k1;
+    use fendermint_vm_topdown::voting::VoteTally;
+    use fendermint_vm_topdown::Toggle;
+    use ipc_api::subnet_id::SubnetID;
+    use quickcheck::Arbitrary;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    use tendermint::abci::{request, types};
+
+    use super::{App, AppConfig, AppState};
+    use crate::store::AppStore;
+    use crate::vote_ext::{AppVoteExtension, VoteExtensions};
+
+    type TestInterpreter = BytesMessageInterpreter<
+        ChainMessageInterpreter<
+            SignedMessageInterpreter<FvmMessageInterpreter<MemoryBlockstore, NeverCallClient>>,
+            MemoryBlockstore,
+        >,
+    >;
+
+    type TestApp = App<RocksDb, MemoryBlockstore, AppStore, TestInterpreter>;
+
+    #[derive(Clone)]
+    struct NeverCallClient;
+
+    #[async_trait]
+    impl tendermint_rpc::Client for NeverCallClient {
+        async fn perform<R>(&self, _request: R) -> Result<R::Output, tendermint_rpc::Error>
+        where
+            R: tendermint_rpc::SimpleRequest,
+        {
+            todo!()
+        }
+    }
+
+    /// Create an application with the genesis state committed at height 0.
+    async fn new_app(dir: &tempfile::TempDir) -> TestApp {
+        let mut genesis = Genesis::arbitrary(&mut quickcheck::Gen::new(5));
+        genesis.ipc = None;
+
+        let car_path = dir.path().join("genesis.car");
+        GenesisBuilder::new(bundle_path(), custom_actors_bundle_path(), genesis)
+            .write_to(car_path.clone())
+            .await
+            .expect("failed to write genesis");
+
+        let state_store = MemoryBlockstore::new();
+        let (_, state_params) = read_genesis_car(std::fs::read(car_path).unwrap(), &state_store)
+            .await
+            .expect("failed to read genesis");
+
+        let namespaces = ["app", "state_hist", "finality_cert"];
+        let db = RocksDb::open_cf(
+            dir.path().join("rocksdb"),
+            &RocksDbConfig::default(),
+            namespaces.iter(),
+        )
+        .expect("failed to open RocksDB");
+
+        let interpreter = FvmMessageInterpreter::new(
+            NeverCallClient,
+            None,
+            1.25,
+            1.25,
+            false,
+            UpgradeScheduler::new(),
+        );
+        let interpreter = SignedMessageInterpreter::new(interpreter);
+        let interpreter = ChainMessageInterpreter::<_, MemoryBlockstore>::new(interpreter);
+        let interpreter =
+            BytesMessageInterpreter::new(interpreter, ProposalPrepareMode::PrependOnly, false, 100);
+
+        let chain_env = ChainEnv {
+            checkpoint_pool: CheckpointPool::new(),
+            parent_finality_provider: Arc::new(Toggle::disabled()),
+            parent_finality_votes: VoteTally::empty(),
+            checkpoint_signatures: CheckpointSignaturePool::new(),
+            vote_extensions: true,
+            power_table: Vec::new(),
+        };
+
+        let app: TestApp = App::new(
+            AppConfig {
+                app_namespace: namespaces[0].to_string(),
+                state_hist_namespace: namespaces[1].to_string(),
+                state_hist_size: 0,
+                finality_cert_namespace: namespaces[2].to_string(),
+                halt_height: 0,
+            },
+            db,
+            state_store,
+            interpreter,
+            chain_env,
+            None,
+        )
+        .expect("failed to create app");
+
+        app.set_committed_state(AppState {
+            block_height: 0,
+            oldest_state_height: 0,
+            state_params,
+        })
+        .expect("failed to commit genesis");
+
+        app
+    }
+
+    fn checkpoint_signature(seed: u64) -> CheckpointSignature {
+        let sk = SecretKey::random(&mut ChaCha20Rng::seed_from_u64(seed));
+        let hash = [1u8; 32];
+        let signature = sign_secp256k1(&sk, &hash);
+        let signature = from_fvm::to_eth_signature(&signature, false).unwrap();
+        CheckpointSignature {
+            height: 1,
+            membership_proof: Vec::new(),
+            weight: 1,
+            hash,
+            signature: signature.to_vec(),
+        }
+    }
+
+    fn prepare_request(exts: Vec<Vec<u8>>) -> request::PrepareProposal {
+        let votes = exts
+            .into_iter()
+            .map(|vote_extension| types::ExtendedVoteInfo {
+                validator: types::Validator {
+                    address: [0u8; 20],
+                    power: 1u32.into(),
+                },
+                sig_info: types::BlockSignatureInfo::Flag(types::BlockIdFlag::Commit),
+                vote_extension: vote_extension.into(),
+                extension_signature: None,
+            })
+            .collect();
+
+        request::PrepareProposal {
+            max_tx_bytes: 1024 * 1024,
+            txs: Vec::new(),
+            local_last_commit: Some(types::ExtendedCommitInfo {
+                round: 0u8.into(),
+                votes,
+            }),
+            misbehavior: Vec::new(),
+            height: 2u32.into(),
+            time: tendermint::Time::from_unix_timestamp(1_700_000_000, 0).unwrap(),
+            next_validators_hash: tendermint::Hash::None,
+            proposer_address: tendermint::account::Id::new([0u8; 20]),
+        }
+    }
+
+    #[tokio::test]
+    async fn prepare_proposal_with_checkpoint_signatures() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = new_app(&dir).await;
+
+        let (sig1, sig2) = (checkpoint_signature(1), checkpoint_signature(2));
+        let ext = |sigs: Vec<CheckpointSignature>| {
+            AppVoteExtension {
+                parent_finality: None,
+                checkpoint_signatures: sigs,
+            }
+            .encode()
+            .unwrap()
+        };
+
+        let exts = vec![ext(vec![sig1.clone()]), Vec::new(), ext(vec![sig2.clone()])];
+
+        // Without vote extensions the extensions in the last commit are ignored.
+        let res = app
+            .prepare_proposal(prepare_request(exts.clone()))
+            .await
+            .unwrap();
+        assert!(res.txs.is_empty());
+
+        let app = app.with_vote_extensions(VoteExtensions::new(SubnetID::new_root(123), None));
+
+        let res = app.prepare_proposal(prepare_request(exts)).await.unwrap();
+        assert_eq!(res.txs.len(), 1);
+
+        let msg: ChainMessage = fvm_ipld_encoding::from_slice(&res.txs[0]).unwrap();
+        assert_eq!(
+            msg,
+            ChainMessage::Ipc(IpcMessage::CheckpointSignatures(vec![sig1, sig2]))
+        );
+    }
+
+    #[tokio::test]
+    async fn finalize_block_then_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = new_app(&dir).await;
+
+        let request = request::FinalizeBlock {
+            txs: Vec::new(),
+            decided_last_commit: types::CommitInfo {
+                round: 0u8.into(),
+                votes: Vec::new(),
+            },
+            misbehavior: Vec::new(),
+            hash: tendermint::Hash::Sha256([1u8; 32]),
+            height: 1u32.into(),
+            time: tendermint::Time::from_unix_timestamp(1_700_000_000, 0).unwrap(),
+            next_validators_hash: tendermint::Hash::None,
+            proposer_address: tendermint::account::Id::new([0u8; 20]),
+        };
+
+        let finalized = app.finalize_block(request.clone()).await.unwrap();
+
+        // Another block can't be finalized on top of an uncommitted one.
+        assert!(app.finalize_block(request).await.is_err());
+
+        // Nothing is committed until CometBFT says so.
+        let state = app.committed_state().unwrap();
+        assert_eq!(state.block_height, 0);
+        assert_ne!(state.app_hash(), finalized.app_hash);
+
+        let committed = app.commit().await.unwrap();
+        assert_eq!(committed.data.as_ref(), finalized.app_hash.as_bytes());
+
+        let state = app.committed_state().unwrap();
+        assert_eq!(state.block_height, 1);
+        assert_eq!(state.app_hash(), finalized.app_hash);
+        assert!(app.finalized_state.lock().await.is_none());
+    }
+}
@@ -0,0 +1,436 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! Online backups of the database using RocksDB checkpoints.
+//!
+//! Every backup is a directory named after the block height it was taken at,
+//! containing the checkpoint and a manifest describing it.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context};
+use fendermint_rocksdb::{RocksDb, RocksDbConfig};
+use fendermint_storage::{KVRead, KVReadable};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::app::{AppState, AppStoreKey};
+use crate::db::AppDb;
+use crate::{AppStore, BlockHeight};
+
+/// Prefix of the directories of finished backups.
+const BACKUP_DIR_PREFIX: &str = "backup-";
+/// Name of the manifest file in the backup directory.
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+/// Name of the directory the checkpoint is created in.
+const CHECKPOINT_DIR_NAME: &str = "rocksdb";
+
+/// The manifest of a backup.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct BackupManifest {
+    /// Last committed block height in the backup.
+    pub block_height: BlockHeight,
+    /// The app hash at the block height, which can be compared to the one in CometBFT.
+    pub app_hash: String,
+    /// Seconds since the epoch when the backup was taken.
+    pub created_at: u64,
+    /// The files in the checkpoint, to check that nothing is missing or corrupted before a restore.
+    pub files: Vec<BackupFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct BackupFile {
+    pub name: String,
+    pub size: u64,
+    /// Sha256 checksum of the contents, to detect corrupted files.
+    pub checksum: tendermint::Hash,
+}
+
+/// Create, list and restore backups in a directory, keeping only the last few of them.
+#[derive(Clone)]
+pub struct Backups {
+    backups_dir: PathBuf,
+    /// Number of backups to keep; 0 means unlimited.
+    keep: usize,
+}
+
+impl Backups {
+    pub fn new(backups_dir: PathBuf, keep: usize) -> Self {
+        Self { backups_dir, keep }
+    }
+
+    /// Create a backup of every column family of the database at the last committed height,
+    /// then remove the backups beyond the retention limit.
+    ///
+    /// This doesn't stop the application from committing blocks; the checkpoint only flushes
+    /// the memtables and hard links the files. The checkpoint is consistent across column
+    /// families, so the height and the app hash in the manifest are read from it, not the live DB.
+    pub fn create(&self, db: &AppDb, app_ns: &str) -> anyhow::Result<BackupManifest> {
+        let db = match db {
+            AppDb::RocksDb(db) => db,
+            AppDb::Redb(_) => bail!("backups are only supported with the RocksDB backend"),
+        };
+
+        std::fs::create_dir_all(&self.backups_dir).context("failed to create backups dir")?;
+
+        let temp_dir = tempfile::Builder::new()
+            .prefix(".backup-")
+            .tempdir_in(&self.backups_dir)
+            .context("failed to create temp dir")?;
+
+        let checkpoint_dir = temp_dir.path().join(CHECKPOINT_DIR_NAME);
+
+        db.create_checkpoint(&checkpoint_dir)
+            .context("failed to create checkpoint")?;
+
+        // Opening the checkpoint can add files to it, so only list them after it's closed.
+        let state = {
+            let checkpoint = RocksDb::open(&checkpoint_dir, &RocksDbConfig::default())
+                .context("failed to open checkpoint")?;
+            read_state(&checkpoint, app_ns)?
+        };
+
+        let manifest = BackupManifest {
+            block_height: state.block_height(),
+            app_hash: state.app_hash().to_string(),
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            files: list_files(&checkpoint_dir)?,
+        };
+
+        let json = serde_json::to_string_pretty(&manifest)
+            .context("failed to convert manifest to JSON")?;
+
+        std::fs::write(temp_dir.path().join(MANIFEST_FILE_NAME), json)
+            .context("failed to write manifest file")?;
+
+        let backup_dir = self.backup_dir(manifest.block_height);
+        if backup_dir.exists() {
+            bail!("backup at height {} already exists", manifest.block_height);
+        }
+
+        std::fs::rename(temp_dir.into_path(), &backup_dir)
+            .context("failed to move backup into place")?;
+
+        tracing::info!(
+            block_height = manifest.block_height,
+            path = backup_dir.to_string_lossy().into_owned(),
+            "created database backup"
+        );
+
+        self.prune()?;
+
+        Ok(manifest)
+    }
+
+    /// List the backups in ascending order of block height.
+    pub fn list(&self) -> anyhow::Result<Vec<BackupManifest>> {
+        if !self.backups_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut manifests = Vec::new();
+
+        for entry in std::fs::read_dir(&self.backups_dir)? {
+            let entry = entry?;
+            // Skip temporary directories of unfinished backups.
+            if !entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(BACKUP_DIR_PREFIX)
+            {
+                continue;
+            }
+            let manifest_path = entry.path().join(MANIFEST_FILE_NAME);
+            if !manifest_path.exists() {
+                continue;
+            }
+            manifests.push(read_manifest(&manifest_path)?);
+        }
+
+        manifests.sort_by_key(|m| m.block_height);
+
+        Ok(manifests)
+    }
+
+    /// Check that all files of a backup are present with their original contents.
+    pub fn verify(&self, block_height: BlockHeight) -> anyhow::Result<BackupManifest> {
+        let backup_dir = self.backup_dir(block_height);
+        let manifest = read_manifest(&backup_dir.join(MANIFEST_FILE_NAME))
+            .with_context(|| format!("backup at height {block_height} not found"))?;
+
+        let files = list_files(&backup_dir.join(CHECKPOINT_DIR_NAME))?;
+
+        if files != manifest.files {
+            bail!("the files of the backup at height {block_height} don't match the manifest");
+        }
+
+        Ok(manifest)
+    }
+
+    /// Verify a backup, copy it to the target directory, which must not exist,
+    /// and check that the restored database is at the height of the backup.
+    ///
+    /// The files are copied rather than linked, because RocksDB keeps appending
+    /// to some of them, which would make the backup unusable.
+    pub fn restore(
+        &self,
+        block_height: BlockHeight,
+        target_dir: &Path,
+        app_ns: &str,
+    ) -> anyhow::Result<BackupManifest> {
+        let manifest = self.verify(block_height)?;
+
+        if target_dir.exists() {
+            bail!("the target directory {target_dir:?} already exists");
+        }
+
+        std::fs::create_dir_all(target_dir).context("failed to create target dir")?;
+
+        let checkpoint_dir = self.backup_dir(block_height).join(CHECKPOINT_DIR_NAME);
+
+        for file in manifest.files.iter() {
+            std::fs::copy(checkpoint_dir.join(&file.name), target_dir.join(&file.name))
+                .with_context(|| format!("failed to copy {}", file.name))?;
+        }
+
+        let db = RocksDb::open(target_dir, &RocksDbConfig::default())
+            .context("failed to open restored database")?;
+
+        let state = read_state(&db, app_ns)?;
+
+        if state.block_height() != manifest.block_height
+            || state.app_hash().to_string() != manifest.app_hash
+        {
+            bail!(
+                "the restored database is at height {}, not at the height of the backup",
+                state.block_height()
+            );
+        }
+
+        Ok(manifest)
+    }
+
+    /// Remove the oldest backups beyond the retention limit.
+    fn prune(&self) -> anyhow::Result<()> {
+        if self.keep == 0 {
+            return Ok(());
+        }
+
+        let manifests = self.list()?;
+        let remove = manifests.len().saturating_sub(self.keep);
+
+        for manifest in manifests.into_iter().take(remove) {
+            let backup_dir = self.backup_dir(manifest.block_height);
+            std::fs::remove_dir_all(&backup_dir)
+                .with_context(|| format!("failed to remove backup {backup_dir:?}"))?;
+
+            tracing::info!(
+                block_height = manifest.block_height,
+                "removed old database backup"
+            );
+        }
+
+        Ok(())
+    }
+
+    fn backup_dir(&self, block_height: BlockHeight) -> PathBuf {
+        self.backups_dir
+            .join(format!("{BACKUP_DIR_PREFIX}{block_height:0>12}"))
+    }
+}
+
+/// Read the last committed application state.
+fn read_state(db: &RocksDb, app_ns: &str) -> anyhow::Result<AppState> {
+    let tx = KVReadable::<AppStore>::read(db);
+    let ns = app_ns.to_string();
+    KVRead::<AppStore>::get(&tx, &ns, &AppStoreKey::State)
+        .context("failed to read app state")?
+        .ok_or_else(|| anyhow!("app state not found; the database is not initialized"))
+}
+
+fn read_manifest(path: &Path) -> anyhow::Result<BackupManifest> {
+    let json = std::fs::read_to_string(path).context("failed to read manifest")?;
+    serde_json::from_str(&json).context("failed to parse manifest")
+}
+
+/// List the files in a directory with their sizes and checksums, sorted by name.
+fn list_files(dir: &Path) -> anyhow::Result<Vec<BackupFile>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).context("failed to read checkpoint dir")? {
+        let entry = entry?;
+        files.push(BackupFile {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            size: entry.metadata()?.len(),
+            checksum: file_checksum(&entry.path())
+                .with_context(|| format!("failed to compute checksum of {:?}", entry.path()))?,
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// Calculate the Sha256 checksum of a file.
+fn file_checksum(path: &Path) -> anyhow::Result<tendermint::Hash> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let _ = std::io::copy(&mut file, &mut hasher)?;
+    Ok(tendermint::Hash::Sha256(hasher.finalize().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use cid::multihash::{Code, MultihashDigest};
+    use cid::Cid;
+    use fendermint_rocksdb::{RocksDb, RocksDbConfig};
+    use fendermint_storage::{KVWritable, KVWrite};
+    use fendermint_vm_core::Timestamp;
+    use fendermint_vm_interpreter::fvm::state::FvmStateParams;
+    use fvm_ipld_encoding::DAG_CBOR;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::version::NetworkVersion;
+
+    use super::{read_state, BackupManifest, Backups, CHECKPOINT_DIR_NAME, MANIFEST_FILE_NAME};
+    use crate::app::{AppState, AppStoreKey};
+    use crate::db::AppDb;
+    use crate::AppStore;
+
+    const APP_NS: &str = "app";
+
+    fn app_state(block_height: u64) -> AppState {
+        let state_root = Cid::new_v1(
+            DAG_CBOR,
+            Code::Blake2b256.digest(&block_height.to_be_bytes()),
+        );
+        AppState::new(
+            block_height,
+            FvmStateParams {
+                state_root,
+                timestamp: Timestamp(block_height),
+                network_version: NetworkVersion::V21,
+                base_fee: TokenAmount::from_atto(100),
+                circ_supply: TokenAmount::from_whole(1000),
+                chain_id: 1234,
+                power_scale: 3,
+                app_version: 0,
+            },
+        )
+    }
+
+    fn commit(db: &RocksDb, block_height: u64) {
+        let ns = APP_NS.to_string();
+        KVWritable::<AppStore>::with_write(db, |tx| {
+            tx.put(&ns, &AppStoreKey::State, &app_state(block_height))
+        })
+        .unwrap();
+    }
+
+    fn open_db(dir: &tempfile::TempDir) -> RocksDb {
+        RocksDb::open_cf(
+            dir.path().join("rocksdb"),
+            &RocksDbConfig::default(),
+            [APP_NS].iter(),
+        )
+        .unwrap()
+    }
+
+    fn fake_backup(backups: &Backups, block_height: u64) {
+        let dir = backups.backup_dir(block_height);
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = BackupManifest {
+            block_height,
+            app_hash: String::new(),
+            created_at: 0,
+            files: Vec::new(),
+        };
+        let json = serde_json::to_string(&manifest).unwrap();
+        std::fs::write(dir.join(MANIFEST_FILE_NAME), json).unwrap();
+    }
+
+    #[test]
+    fn prune_keeps_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = Backups::new(dir.path().to_path_buf(), 2);
+
+        for h in [30, 10, 20] {
+            fake_backup(&backups, h);
+        }
+        // Unfinished backups are ignored.
+        std::fs::create_dir_all(dir.path().join(".backup-123")).unwrap();
+
+        let heights = |b: &Backups| {
+            b.list()
+                .unwrap()
+                .into_iter()
+                .map(|m| m.block_height)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(heights(&backups), vec![10, 20, 30]);
+
+        backups.prune().unwrap();
+        assert_eq!(heights(&backups), vec![20, 30]);
+    }
+
+    #[test]
+    fn create_verify_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_db(&dir);
+        let backups = Backups::new(dir.path().join("backups"), 0);
+
+        commit(&db, 10);
+        let app_db = AppDb::RocksDb(db.clone());
+        let manifest = backups.create(&app_db, APP_NS).unwrap();
+
+        // Blocks committed after the backup don't end up in it.
+        commit(&db, 11);
+
+        assert_eq!(manifest.block_height, 10);
+        assert_eq!(manifest.app_hash, app_state(10).app_hash().to_string());
+        assert!(!manifest.files.is_empty());
+        assert_eq!(backups.list().unwrap(), vec![manifest.clone()]);
+        assert_eq!(backups.verify(10).unwrap(), manifest);
+
+        let target_dir = dir.path().join("restored");
+        let restored = backups.restore(10, &target_dir, APP_NS).unwrap();
+        assert_eq!(restored, manifest);
+
+        let db = RocksDb::open(&target_dir, &RocksDbConfig::default()).unwrap();
+        let state = read_state(&db, APP_NS).unwrap();
+        assert_eq!(state.block_height(), 10);
+
+        // Restoring over an existing directory is refused.
+        assert!(backups.restore(10, &target_dir, APP_NS).is_err());
+    }
+
+    #[test]
+    fn verify_detects_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_db(&dir);
+        let backups = Backups::new(dir.path().join("backups"), 0);
+
+        commit(&db, 20);
+        let manifest = backups.create(&AppDb::RocksDb(db), APP_NS).unwrap();
+
+        let file = manifest
+            .files
+            .iter()
+            .find(|f| f.size > 0)
+            .expect("a non-empty file");
+
+        let path = backups
+            .backup_dir(20)
+            .join(CHECKPOINT_DIR_NAME)
+            .join(&file.name);
+
+        // Flip a byte without changing the size of the file.
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0] ^= 0xff;
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(backups.verify(20).is_err());
+        assert!(backups
+            .restore(20, &dir.path().join("restored"), APP_NS)
+            .is_err());
+        assert!(!dir.path().join("restored").exists());
+    }
+}
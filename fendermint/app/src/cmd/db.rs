@@ -0,0 +1,125 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::{anyhow, bail, Context};
+use fendermint_app::backup::Backups;
+use fendermint_app::db::copy_namespace;
+use fendermint_app_options::db::{
+    DbArgs, DbBackend, DbBackupArgs, DbCommands, DbMigrateArgs, DbRestoreArgs,
+};
+use fendermint_app_settings::DbBackend as SettingsDbBackend;
+
+use crate::cmd::{db_path, open_db, Namespaces};
+use crate::{cmd, settings::Settings};
+
+cmd! {
+  DbArgs(self, settings) {
+    match &self.command {
+        DbCommands::Migrate(args) => migrate(settings, args),
+        DbCommands::Backup(args) => backup(settings, args).await,
+        DbCommands::ListBackups => list_backups(settings),
+        DbCommands::Restore(args) => restore(settings, args),
+    }
+  }
+}
+
+/// Copy every namespace of the application from one backend to another.
+fn migrate(settings: Settings, args: &DbMigrateArgs) -> anyhow::Result<()> {
+    let from = to_settings_backend(args.from);
+    let to = to_settings_backend(args.to);
+
+    if from == to {
+        bail!("the source and the target backend are the same");
+    }
+
+    let from_path = db_path(&settings, from);
+    let to_path = db_path(&settings, to);
+
+    if !from_path.exists() {
+        bail!("the source database does not exist at {from_path:?}");
+    }
+    if to_path.exists() {
+        bail!("the target database already exists at {to_path:?}; remove it to migrate again");
+    }
+
+    let ns = Namespaces::default();
+    let from_db = open_db(&settings, from, &ns).context("error opening source DB")?;
+    let to_db = open_db(&settings, to, &ns).context("error opening target DB")?;
+
+    for name in ns.values() {
+        let count = copy_namespace(&from_db, &to_db, name, args.batch_size)
+            .with_context(|| format!("failed to copy namespace {name}"))?;
+
+        tracing::info!(namespace = name, count, "copied namespace");
+    }
+
+    println!(
+        "Migrated the database from {from} to {to}; set `db.backend = \"{to}\"` in the configuration to use it."
+    );
+
+    Ok(())
+}
+
+/// Create a backup, either through the admin endpoint of the node or by opening the database.
+async fn backup(settings: Settings, args: &DbBackupArgs) -> anyhow::Result<()> {
+    if args.offline {
+        let ns = Namespaces::default();
+        let db = open_db(&settings, settings.db.backend, &ns).context("error opening DB")?;
+        let manifest = backups(&settings).create(&db, &ns.app)?;
+        println!("{}", serde_json::to_string_pretty(&manifest)?);
+        return Ok(());
+    }
+
+    let url = args
+        .url
+        .join("backups")
+        .context("failed to construct DB admin URL")?;
+
+    let res = reqwest::Client::new()
+        .post(url)
+        .send()
+        .await
+        .context("failed to call the DB admin endpoint")?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let msg = res.text().await.unwrap_or_default();
+        return Err(anyhow!("DB admin endpoint returned {status}: {msg}"));
+    }
+
+    let json: serde_json::Value = res.json().await.context("failed to parse response")?;
+    println!("{}", serde_json::to_string_pretty(&json)?);
+
+    Ok(())
+}
+
+fn list_backups(settings: Settings) -> anyhow::Result<()> {
+    let manifests = backups(&settings).list()?;
+    println!("{}", serde_json::to_string_pretty(&manifests)?);
+    Ok(())
+}
+
+/// Restore a backup into the RocksDB directory of the node.
+fn restore(settings: Settings, args: &DbRestoreArgs) -> anyhow::Result<()> {
+    let ns = Namespaces::default();
+    let target_dir = db_path(&settings, SettingsDbBackend::RocksDb);
+    let manifest = backups(&settings).restore(args.height, &target_dir, &ns.app)?;
+
+    println!(
+        "Restored the backup at height {} with app hash {} to {target_dir:?}.",
+        manifest.block_height, manifest.app_hash
+    );
+
+    Ok(())
+}
+
+fn backups(settings: &Settings) -> Backups {
+    Backups::new(settings.backups_dir(), settings.db.backup.keep)
+}
+
+fn to_settings_backend(backend: DbBackend) -> SettingsDbBackend {
+    match backend {
+        DbBackend::RocksDb => SettingsDbBackend::RocksDb,
+        DbBackend::Redb => SettingsDbBackend::Redb,
+    }
+}
@@ -1,22 +1,58 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use anyhow::{anyhow, Context};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context};
+use cid::Cid;
+use fendermint_app::db::{AppBlockstore, AppDb};
+use fendermint_app::inspect::{self, RemoteBlockstore};
+use fendermint_app::replay::{compare_tx, ReplayBlock, Replayer};
 use fendermint_app_options::debug::{
-    DebugArgs, DebugCommands, DebugExportTopDownEventsArgs, DebugIpcCommands,
+    DebugArgs, DebugCommands, DebugExportTopDownEventsArgs, DebugIpcCommands, DebugParentViewArgs,
+    DebugReplayArgs, DebugResolverArgs, DebugResolverCommands, DebugStateCommands, StateSource,
+};
+use fendermint_redb::Redb;
+use fendermint_rocksdb::{RocksDb, RocksDbConfig};
+use fendermint_rpc::{client::FendermintClient, query::QueryClient};
+use fendermint_vm_interpreter::fvm::store::overlay::OverlayBlockstore;
+use fendermint_vm_interpreter::fvm::upgrades::UpgradeScheduler;
+use fendermint_vm_interpreter::{
+    bytes::{BytesMessageInterpreter, ProposalPrepareMode},
+    chain::{ChainEnv, ChainMessageInterpreter, CheckpointPool},
+    fvm::{CheckpointSignaturePool, FvmMessageInterpreter},
+    signed::SignedMessageInterpreter,
 };
-use fendermint_vm_topdown::proxy::IPCProviderProxy;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fendermint_vm_topdown::proxy::{IPCProviderProxy, IPCProviderProxyWithLatency};
+use fendermint_vm_topdown::store::ParentViewEntry;
+use fendermint_vm_topdown::voting::VoteTally;
+use fendermint_vm_topdown::{CachedFinalityProvider, Toggle};
+use fvm_ipld_blockstore::Blockstore;
 use ipc_provider::{
     config::subnet::{EVMSubnet, SubnetConfig},
     IpcProvider,
 };
 
+use serde_json::json;
+use tendermint_rpc::Client;
+
 use crate::cmd;
+use crate::cmd::run::make_ipc_provider_proxy;
+use crate::cmd::{open_db, Namespaces};
+use crate::settings::Settings;
 
 cmd! {
-  DebugArgs(self) {
+  DebugArgs(self, settings: Option<Settings>) {
     match &self.command {
         DebugCommands::Ipc { command } => command.exec(()).await,
+        DebugCommands::Resolver { command } => command.exec(()).await,
+        DebugCommands::State { command } => command.exec(()).await,
+        DebugCommands::Replay(args) => {
+            let settings = settings.ok_or_else(|| anyhow!("replay needs the settings"))?;
+            replay(settings, args).await
+        }
     }
   }
 }
@@ -28,6 +64,73 @@ cmd! {
         DebugIpcCommands::ExportTopDownEvents(args) => {
             export_topdown_events(args).await
         }
+        DebugIpcCommands::ParentView(args) => {
+            list_parent_view(args).await
+        }
+    }
+  }
+}
+
+cmd! {
+  DebugResolverCommands(self) {
+    match self {
+        DebugResolverCommands::Peers(args) => {
+            query_resolver(args, "peers", &[]).await
+        }
+        DebugResolverCommands::Providers(args) => {
+            let subnet_id = args.subnet_id.to_string();
+            query_resolver(&args.admin, "providers", &[("subnet_id", &subnet_id)]).await
+        }
+        DebugResolverCommands::Stats(args) => {
+            query_resolver(args, "stats", &[]).await
+        }
+    }
+  }
+}
+
+cmd! {
+  DebugStateCommands(self) {
+    match self {
+        DebugStateCommands::Actors(args) => {
+            let store = open_store(&args.source)?;
+            let state_root = state_root(&store, args.height).await?;
+            let actors = inspect::list_actors(&store, &state_root)?;
+            print_state_json(&state_root, actors)
+        }
+        DebugStateCommands::Storage(args) => {
+            let store = open_store(&args.state.source)?;
+            let state_root = state_root(&store, args.state.height).await?;
+            let slots = inspect::evm_storage(&store, &state_root, &args.contract)?;
+            print_state_json(&state_root, slots)
+        }
+        DebugStateCommands::Diff(args) => {
+            let left = open_store(&args.left)?;
+            let left_root = state_root(&left, args.left_height).await?;
+
+            // Compare two heights in the same store if there is no other source;
+            // a local database can only be opened once anyway.
+            let (right_root, diff) = match &args.right {
+                None => {
+                    let right_root = state_root(&left, args.right_height).await?;
+                    let diff = inspect::diff_states(&left, &left_root, &left, &right_root)?;
+                    (right_root, diff)
+                }
+                Some(source) => {
+                    let right = open_store(source)?;
+                    let right_root = state_root(&right, args.right_height).await?;
+                    let diff = inspect::diff_states(&left, &left_root, &right, &right_root)?;
+                    (right_root, diff)
+                }
+            };
+
+            let json = json!({
+                "left_state_root": left_root.to_string(),
+                "right_state_root": right_root.to_string(),
+                "diff": diff,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+            Ok(())
+        }
     }
   }
 }
@@ -68,3 +171,337 @@ async fn export_topdown_events(args: &DebugExportTopDownEventsArgs) -> anyhow::R
 
     Ok(())
 }
+
+async fn list_parent_view(args: &DebugParentViewArgs) -> anyhow::Result<()> {
+    let client = FendermintClient::new_http(args.url.clone(), args.proxy_url.clone())?;
+
+    let res = client.parent_view().await?;
+
+    let entries: Vec<ParentViewEntry> =
+        fvm_ipld_encoding::from_slice(&res.value).context("failed to decode parent view")?;
+
+    let blocks = entries
+        .into_iter()
+        .map(|e| match e.payload {
+            None => json!({ "height": e.height, "null_round": true }),
+            Some((block_hash, validator_changes, top_down_msgs)) => json!({
+                "height": e.height,
+                "block_hash": hex::encode(block_hash),
+                "validator_changes": validator_changes,
+                "top_down_msgs": top_down_msgs,
+            }),
+        })
+        .collect::<Vec<_>>();
+
+    let json = json!({ "height": res.height, "blocks": blocks });
+    println!("{}", serde_json::to_string_pretty(&json)?);
+
+    Ok(())
+}
+
+/// Query the admin endpoint of the IPLD Resolver and print the JSON response.
+async fn query_resolver(
+    args: &DebugResolverArgs,
+    path: &str,
+    query: &[(&str, &str)],
+) -> anyhow::Result<()> {
+    let url = args
+        .url
+        .join(path)
+        .context("failed to construct resolver admin URL")?;
+
+    let res = reqwest::Client::new()
+        .get(url)
+        .query(query)
+        .send()
+        .await
+        .context("failed to query the resolver admin endpoint")?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let msg = res.text().await.unwrap_or_default();
+        return Err(anyhow!("resolver admin endpoint returned {status}: {msg}"));
+    }
+
+    let json: serde_json::Value = res.json().await.context("failed to parse response")?;
+    println!("{}", serde_json::to_string_pretty(&json)?);
+
+    Ok(())
+}
+
+/// The store a state tree is read from.
+enum StateStore {
+    Local(AppDb, AppBlockstore),
+    Remote(FendermintClient, RemoteBlockstore<FendermintClient>),
+}
+
+impl Blockstore for StateStore {
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        match self {
+            StateStore::Local(_, bs) => bs.get(k),
+            StateStore::Remote(_, bs) => bs.get(k),
+        }
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+        match self {
+            StateStore::Local(_, bs) => bs.put_keyed(k, block),
+            StateStore::Remote(_, bs) => bs.put_keyed(k, block),
+        }
+    }
+}
+
+fn open_store(source: &StateSource) -> anyhow::Result<StateStore> {
+    match source {
+        StateSource::Local(path) => {
+            let ns = Namespaces::default();
+            let db = open_local_db(path, &ns)?;
+            let bs = AppBlockstore::new(db.clone(), ns.state_store)?;
+            Ok(StateStore::Local(db, bs))
+        }
+        StateSource::Remote(url) => {
+            let client = FendermintClient::new_http(url.clone(), None)?;
+            // The blocks are the same at every height, they just might not be there yet.
+            let bs = RemoteBlockstore::new(client.clone(), FvmQueryHeight::Committed);
+            Ok(StateStore::Remote(client, bs))
+        }
+    }
+}
+
+/// Look up the state root at a height; 0 means the latest committed state.
+async fn state_root(store: &StateStore, height: u64) -> anyhow::Result<Cid> {
+    match store {
+        StateStore::Local(db, _) => {
+            let ns = Namespaces::default();
+            let state_params = inspect::local_state_params(db, &ns.app, &ns.state_hist, height)?;
+            Ok(state_params.state_root)
+        }
+        StateStore::Remote(client, _) => {
+            let res = client.state_params(FvmQueryHeight::from(height)).await?;
+
+            // The node falls back to the latest state if the height is not in its history.
+            if height != 0 && res.height.value() != height {
+                bail!("state at height {height} not found; it may have been pruned");
+            }
+
+            res.value
+                .state_root
+                .ok_or_else(|| anyhow!("the node doesn't report its state root; upgrade it"))
+        }
+    }
+}
+
+/// Open the database of a node which isn't running, or a backup of it; a file is taken to be a redb database.
+fn open_local_db(path: &Path, ns: &Namespaces) -> anyhow::Result<AppDb> {
+    if !path.exists() {
+        bail!("the database does not exist at {path:?}");
+    }
+    let db = if path.is_file() {
+        AppDb::Redb(Redb::open_ns(path, ns.values().iter())?)
+    } else {
+        AppDb::RocksDb(RocksDb::open_cf(
+            path,
+            &RocksDbConfig::default(),
+            ns.values().iter(),
+        )?)
+    };
+    Ok(db)
+}
+
+fn print_state_json<T: serde::Serialize>(state_root: &Cid, value: T) -> anyhow::Result<()> {
+    let json = json!({ "state_root": state_root.to_string(), "value": value });
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+/// Replay blocks one by one, each on top of the state the previous one resulted in,
+/// stopping at the first block which doesn't match what was committed.
+async fn replay(settings: Settings, args: &DebugReplayArgs) -> anyhow::Result<()> {
+    let from = args.from;
+    let to = args.to.unwrap_or(from);
+
+    if from == 0 || to < from {
+        bail!("invalid block range: {from}..={to}");
+    }
+
+    let url = match args.url {
+        Some(ref url) => url.clone(),
+        None => settings.tendermint_rpc_url()?,
+    };
+
+    let tendermint_client =
+        tendermint_rpc::HttpClient::new(url).context("failed to create Tendermint client")?;
+
+    let ns = Namespaces::default();
+    let db = open_db(&settings, settings.db.backend, &ns).context("error opening DB")?;
+    let state_store = AppBlockstore::new(db.clone(), ns.state_store.clone())
+        .context("error creating state DB")?;
+
+    // Without a validator context the interpreter doesn't broadcast anything.
+    let interpreter = FvmMessageInterpreter::<OverlayBlockstore<AppBlockstore>, _>::new(
+        tendermint_client.clone(),
+        None,
+        settings.fvm.gas_overestimation_rate,
+        settings.fvm.gas_search_step,
+        settings.fvm.exec_in_check,
+        UpgradeScheduler::new(),
+    )
+    .with_push_chain_meta(
+        settings
+            .testing
+            .as_ref()
+            .map_or(true, |t| t.push_chain_meta),
+    );
+
+    let interpreter = SignedMessageInterpreter::new(interpreter);
+    let interpreter =
+        ChainMessageInterpreter::<_, OverlayBlockstore<AppBlockstore>>::new(interpreter);
+    let interpreter = BytesMessageInterpreter::new(
+        interpreter,
+        ProposalPrepareMode::PrependOnly,
+        false,
+        settings.abci.block_max_msgs,
+    );
+
+    // Top-down effects are fetched from the parent, rather than the parent view store of the node,
+    // which would be modified by committing finalities.
+    let parent_finality_provider = if settings.topdown_enabled() {
+        let topdown_config = settings.ipc.topdown_config()?;
+        let config = fendermint_vm_topdown::Config::new(
+            topdown_config.chain_head_delay,
+            topdown_config.polling_interval,
+            topdown_config.exponential_back_off,
+            topdown_config.exponential_retry_limit,
+        );
+        let ipc_provider = Arc::new(IPCProviderProxyWithLatency::new(make_ipc_provider_proxy(
+            &settings,
+        )?));
+        let provider = CachedFinalityProvider::uninitialized(config, ipc_provider).await?;
+        Arc::new(Toggle::enabled(provider))
+    } else {
+        Arc::new(Toggle::disabled())
+    };
+
+    let chain_env = ChainEnv {
+        checkpoint_pool: CheckpointPool::new(),
+        parent_finality_provider,
+        parent_finality_votes: VoteTally::empty(),
+        checkpoint_signatures: CheckpointSignaturePool::new(),
+        vote_extensions: false,
+        power_table: Vec::new(),
+    };
+
+    let replayer = Replayer::new(interpreter, chain_env, state_store);
+
+    // The state history is indexed by the height where the state appeared, which is
+    // one higher than the block that committed it, so this is the state before `from`.
+    let mut state_params = inspect::local_state_params(&db, &ns.app, &ns.state_hist, from)?;
+    let mut block = tendermint_client.block(to_height(from)?).await?;
+
+    for height in from..=to {
+        let results = tendermint_client.block_results(to_height(height)?).await?;
+
+        let replay_block = ReplayBlock {
+            height: block.block.header.height,
+            hash: block.block_id.hash,
+            time: block.block.header.time,
+            proposer_address: block.block.header.proposer_address,
+            txs: block.block.data.clone(),
+        };
+
+        let replayed = replayer.replay_block(state_params, &replay_block).await?;
+
+        let committed_txs = results.txs_results.unwrap_or_default();
+
+        if committed_txs.len() != replayed.txs.len() {
+            bail!(
+                "block {height} has {} transaction results in CometBFT, but {} transactions",
+                committed_txs.len(),
+                replayed.txs.len()
+            );
+        }
+
+        for (index, (committed, tx)) in committed_txs.iter().zip(replayed.txs.iter()).enumerate() {
+            if let Some(difference) = compare_tx(committed, &tx.response) {
+                let json = json!({
+                    "height": height,
+                    "tx_index": index,
+                    "difference": difference,
+                    "committed": {
+                        "code": committed.code.value(),
+                        "gas_used": committed.gas_used,
+                        "info": committed.info,
+                    },
+                    "replayed": {
+                        "code": tx.response.code.value(),
+                        "gas_used": tx.response.gas_used,
+                        "info": tx.response.info,
+                    },
+                    "gas_trace": tx.gas_trace,
+                });
+                println!("{}", serde_json::to_string_pretty(&json)?);
+                bail!("transaction {index} in block {height} diverged");
+            }
+        }
+
+        // The effect of the block is visible in the header of the next one, if it's there yet.
+        let next = if height < to {
+            Some(tendermint_client.block(to_height(height + 1)?).await?)
+        } else {
+            tendermint_client.block(to_height(height + 1)?).await.ok()
+        };
+
+        let committed_state_root =
+            inspect::local_state_params(&db, &ns.app, &ns.state_hist, height + 1)
+                .ok()
+                .map(|p| p.state_root);
+
+        let committed_app_hash = next.as_ref().map(|b| b.block.header.app_hash.clone());
+
+        let state_root_differs =
+            committed_state_root.map_or(false, |r| r != replayed.state_params.state_root);
+
+        let app_hash_differs = committed_app_hash
+            .as_ref()
+            .map_or(false, |h| *h != replayed.app_hash());
+
+        if state_root_differs || app_hash_differs {
+            let json = json!({
+                "height": height,
+                "difference": "all transaction results match, but the resulting state doesn't",
+                "committed": {
+                    "state_root": committed_state_root.map(|r| r.to_string()),
+                    "app_hash": committed_app_hash.map(|h| h.to_string()),
+                },
+                "replayed": {
+                    "state_root": replayed.state_params.state_root.to_string(),
+                    "app_hash": replayed.app_hash().to_string(),
+                },
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+            bail!("the state after block {height} diverged");
+        }
+
+        tracing::info!(
+            height,
+            txs = replayed.txs.len(),
+            state_root = replayed.state_params.state_root.to_string(),
+            "replayed block"
+        );
+
+        state_params = replayed.state_params;
+
+        match next {
+            Some(next) => block = next,
+            None => break,
+        }
+    }
+
+    println!("Replayed blocks {from} to {to}; the results match what was committed.");
+
+    Ok(())
+}
+
+fn to_height(height: u64) -> anyhow::Result<tendermint::block::Height> {
+    Ok(tendermint::block::Height::try_from(height)?)
+}
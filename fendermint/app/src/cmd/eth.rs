@@ -10,7 +10,7 @@ use tracing::info;
 use crate::{
     cmd,
     options::eth::{EthArgs, EthCommands},
-    settings::eth::EthSettings,
+    settings::eth::{EthSettings, TokenBucketOpt},
 };
 
 cmd! {
@@ -62,6 +62,26 @@ async fn run(settings: EthSettings, client: HybridClient) -> anyhow::Result<()>
         allowed_methods: settings.cors.allowed_methods,
         allowed_headers: settings.cors.allowed_headers,
     };
+    let limits = settings.limits;
+    let to_bucket = |b: TokenBucketOpt| fendermint_eth_api::TokenBucketOpt {
+        capacity: b.capacity,
+        refill_per_sec: b.refill_per_sec,
+    };
+    let rate_limit = fendermint_eth_api::RateLimitOpt {
+        per_ip: limits.per_ip.map(to_bucket),
+        per_api_key: limits.per_api_key.map(to_bucket),
+        api_key_header: limits.api_key_header,
+        api_keys: limits.api_keys.into_iter().collect(),
+        default_cost: limits.default_method_cost,
+        method_costs: limits.method_costs,
+        max_batch_size: limits.max_batch_size,
+    };
+    let method_limit = fendermint_eth_api::MethodLimitOpt {
+        max_log_block_range: limits.max_log_block_range,
+        max_call_gas: limits.max_call_gas,
+        max_filters: limits.max_filters,
+        max_subscriptions_per_socket: limits.max_subscriptions_per_socket,
+    };
     fendermint_eth_api::listen(
         settings.listen,
         client,
@@ -70,6 +90,8 @@ async fn run(settings: EthSettings, client: HybridClient) -> anyhow::Result<()>
         settings.max_nonce_gap,
         gas,
         cors,
+        rate_limit,
+        method_limit,
     )
     .await
 }
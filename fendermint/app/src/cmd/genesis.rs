@@ -3,16 +3,16 @@
 
 use anyhow::{anyhow, Context};
 use fendermint_crypto::PublicKey;
-use fvm_shared::address::Address;
+use fvm_shared::address::{Address, Payload};
 use ipc_provider::config::subnet::{EVMSubnet, SubnetConfig};
 use ipc_provider::IpcProvider;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use fendermint_vm_actor_interface::eam::EthAddress;
 use fendermint_vm_core::{chainid, Timestamp};
 use fendermint_vm_genesis::{
-    ipc, Account, Actor, ActorMeta, Collateral, Genesis, Multisig, PermissionMode, SignerAddr,
-    Validator, ValidatorKey,
+    ipc, Account, Actor, ActorMeta, Collateral, Contract, Genesis, LibraryLink, Multisig,
+    PermissionMode, SignerAddr, Validator, ValidatorKey, Vesting,
 };
 use fendermint_vm_interpreter::genesis::{GenesisAppState, GenesisBuilder};
 
@@ -30,6 +30,7 @@ cmd! {
         GenesisCommands::AddAccount(args) => args.exec(genesis_file).await,
         GenesisCommands::AddMultisig(args) => args.exec(genesis_file).await,
         GenesisCommands::AddValidator(args) => args.exec(genesis_file).await,
+        GenesisCommands::AddContract(args) => args.exec(genesis_file).await,
         GenesisCommands::IntoTendermint(args) => args.exec(genesis_file).await,
         GenesisCommands::SetEamPermissions(args) => args.exec(genesis_file).await,
         GenesisCommands::Ipc { command } => command.exec(genesis_file).await,
@@ -48,7 +49,9 @@ cmd! {
       validators: Vec::new(),
       accounts: Vec::new(),
       eam_permission_mode: PermissionMode::Unrestricted,
+      eam_admin: None,
       ipc: None,
+      contracts: Vec::new(),
     };
 
     let json = serde_json::to_string_pretty(&genesis)?;
@@ -76,6 +79,12 @@ cmd! {
   }
 }
 
+cmd! {
+  GenesisAddContractArgs(self, genesis_file: PathBuf) {
+    add_contract(&genesis_file, self)
+  }
+}
+
 cmd! {
   GenesisIntoTendermintArgs(self, genesis_file: PathBuf) {
     into_tendermint(&genesis_file, self)
@@ -103,18 +112,37 @@ cmd! {
 
 fn add_account(genesis_file: &PathBuf, args: &GenesisAddAccountArgs) -> anyhow::Result<()> {
     update_genesis(genesis_file, |mut genesis| {
-        let pk = read_public_key(&args.public_key)?;
-        let pk = pk.serialize();
-        let addr = match args.kind {
-            AccountKind::Regular => Address::new_secp256k1(&pk)?,
-            AccountKind::Ethereum => Address::from(EthAddress::new_secp256k1(&pk)?),
+        let addr = account_address(&read_public_key(&args.public_key)?, &args.kind)?;
+        let meta = match (args.vesting_duration, args.vesting_start) {
+            (Some(duration), Some(start)) => {
+                if args.vesting_cliff > duration {
+                    return Err(anyhow!("vesting cliff cannot be longer than the duration"));
+                }
+                let revoker = match args.vesting_revoker {
+                    Some(ref p) => Some(SignerAddr(account_address(
+                        &read_public_key(p)?,
+                        &args.kind,
+                    )?)),
+                    None => None,
+                };
+                ActorMeta::Vesting(Vesting {
+                    owner: SignerAddr(addr),
+                    revoker,
+                    start,
+                    cliff: args.vesting_cliff,
+                    duration,
+                })
+            }
+            _ => {
+                let meta = ActorMeta::Account(Account {
+                    owner: SignerAddr(addr),
+                });
+                if genesis.accounts.iter().any(|a| a.meta == meta) {
+                    return Err(anyhow!("account already exists in the genesis file"));
+                }
+                meta
+            }
         };
-        let meta = ActorMeta::Account(Account {
-            owner: SignerAddr(addr),
-        });
-        if genesis.accounts.iter().any(|a| a.meta == meta) {
-            return Err(anyhow!("account already exists in the genesis file"));
-        }
         let actor = Actor {
             meta,
             balance: args.balance.clone(),
@@ -167,6 +195,7 @@ fn add_multisig(genesis_file: &PathBuf, args: &GenesisAddMultisigArgs) -> anyhow
 fn add_validator(genesis_file: &PathBuf, args: &GenesisAddValidatorArgs) -> anyhow::Result<()> {
     update_genesis(genesis_file, |mut genesis| {
         let pk = read_public_key(&args.public_key)?;
+        let owner = SignerAddr(account_address(&pk, &args.kind)?);
         let vk = ValidatorKey(pk);
         if genesis.validators.iter().any(|v| v.public_key == vk) {
             return Err(anyhow!("account already exists in the genesis file"));
@@ -176,6 +205,80 @@ fn add_validator(genesis_file: &PathBuf, args: &GenesisAddValidatorArgs) -> anyh
             power: Collateral(args.power.clone()),
         };
         genesis.validators.push(validator);
+
+        if let Some(until) = args.collateral_lock {
+            let vesting = Vesting {
+                owner,
+                revoker: None,
+                start: 0,
+                cliff: until,
+                duration: until,
+            };
+            genesis.accounts.push(Actor {
+                meta: ActorMeta::Vesting(vesting),
+                balance: args.power.clone(),
+            });
+        }
+
+        Ok(genesis)
+    })
+}
+
+/// Derive the address of an account from its public key.
+fn account_address(pk: &PublicKey, kind: &AccountKind) -> anyhow::Result<Address> {
+    let pk = pk.serialize();
+    let addr = match kind {
+        AccountKind::Regular => Address::new_secp256k1(&pk)?,
+        AccountKind::Ethereum => Address::from(EthAddress::new_secp256k1(&pk)?),
+    };
+    Ok(addr)
+}
+
+fn add_contract(genesis_file: &PathBuf, args: &GenesisAddContractArgs) -> anyhow::Result<()> {
+    update_genesis(genesis_file, |mut genesis| {
+        if genesis.contracts.iter().any(|c| c.name == args.name) {
+            return Err(anyhow!("contract already exists in the genesis file"));
+        }
+        if !args.artifact.is_file() {
+            return Err(anyhow!(
+                "artifact not found: {}",
+                args.artifact.to_string_lossy()
+            ));
+        }
+        for (lib, link) in &args.library {
+            if let LibraryLink::Contract(name) = link {
+                if !genesis.contracts.iter().any(|c| c.name == *name) {
+                    return Err(anyhow!("library {lib} links to unknown contract: {name}"));
+                }
+            }
+        }
+        if let Some(addr) = args.eth_address {
+            if !matches!(addr.payload(), Payload::Delegated(_)) {
+                return Err(anyhow!("contract address cannot be an ID address"));
+            }
+            if genesis
+                .contracts
+                .iter()
+                .any(|c| c.delegated_address == Some(addr))
+            {
+                return Err(anyhow!("contract address already used in the genesis file"));
+            }
+        }
+
+        let contract = Contract {
+            name: args.name.clone(),
+            artifact: genesis_relative_path(genesis_file, &args.artifact)?,
+            constructor_args: args
+                .constructor_args
+                .as_ref()
+                .map(|bz| bz.to_vec())
+                .unwrap_or_default(),
+            libraries: args.library.iter().cloned().collect(),
+            delegated_address: args.eth_address,
+        };
+
+        genesis.contracts.push(contract);
+
         Ok(genesis)
     })
 }
@@ -210,6 +313,9 @@ fn set_eam_permissions(
             }
             _ => return Err(anyhow!("unknown eam permisison mode")),
         };
+        if args.admin.is_some() {
+            genesis.eam_admin = args.admin.clone();
+        }
         Ok(genesis)
     })
 }
@@ -259,7 +365,12 @@ fn into_tendermint(genesis_file: &PathBuf, args: &GenesisIntoTendermintArgs) ->
         // cometbft serves data in json format, convert to string to be specific
         app_state,
     };
-    let tmg_json = serde_json::to_string_pretty(&tmg)?;
+    let mut tmg_json = serde_json::to_value(&tmg)?;
+    // The consensus parameters in `tendermint-rs` predate ABCI 2.0, so the vote extension settings are added by hand.
+    tmg_json["consensus_params"]["abci"] = serde_json::json!({
+        "vote_extensions_enable_height": args.vote_extensions_enable_height.to_string()
+    });
+    let tmg_json = serde_json::to_string_pretty(&tmg_json)?;
     std::fs::write(&args.out, tmg_json)?;
     Ok(())
 }
@@ -289,6 +400,27 @@ fn set_ipc_gateway(genesis_file: &PathBuf, args: &GenesisIpcGatewayArgs) -> anyh
     })
 }
 
+/// Express a path given on the command line relative to the directory of the genesis file,
+/// which is what relative paths in the genesis file are resolved against.
+///
+/// Paths outside that directory are stored as absolute paths.
+fn genesis_relative_path(genesis_file: &Path, path: &Path) -> anyhow::Result<PathBuf> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("failed to resolve {}", path.to_string_lossy()))?;
+
+    let genesis_dir = match genesis_file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.canonicalize(),
+        _ => std::env::current_dir().and_then(|dir| dir.canonicalize()),
+    }
+    .context("failed to resolve the genesis directory")?;
+
+    match path.strip_prefix(&genesis_dir) {
+        Ok(rel) => Ok(rel.to_path_buf()),
+        Err(_) => Ok(path),
+    }
+}
+
 async fn seal_genesis(genesis_file: &PathBuf, args: &SealGenesisArgs) -> anyhow::Result<()> {
     let genesis_params = read_genesis(genesis_file)?;
 
@@ -302,6 +434,10 @@ async fn seal_genesis(genesis_file: &PathBuf, args: &SealGenesisArgs) -> anyhow:
         builder = builder.with_ipc_system_contracts(ipc_system_artifacts.clone());
     }
 
+    if let Some(genesis_dir) = genesis_file.parent() {
+        builder = builder.with_genesis_dir(genesis_dir.to_path_buf());
+    }
+
     builder.write_to(args.output_path.clone()).await
 }
 
@@ -351,7 +487,9 @@ async fn new_genesis_from_parent(
         validators: Vec::new(),
         accounts: Vec::new(),
         eam_permission_mode: PermissionMode::Unrestricted,
+        eam_admin: None,
         ipc: Some(ipc_params),
+        contracts: Vec::new(),
     };
 
     for v in genesis_info.validators {
@@ -4,11 +4,16 @@
 //! CLI command implementations.
 
 use crate::{
-    options::{Commands, Options},
+    options::{debug::DebugCommands, Commands, Options},
     settings::{utils::expand_tilde, Settings},
 };
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use fendermint_app::db::AppDb;
+use fendermint_app_settings::DbBackend;
+use fendermint_redb::Redb;
+use fendermint_rocksdb::{namespaces, RocksDb, RocksDbConfig};
+use std::path::PathBuf;
 
 use ipc_observability::config::TracingSettings;
 use ipc_observability::traces::create_temporary_subscriber;
@@ -16,6 +21,7 @@ use ipc_observability::traces::set_global_tracing_subscriber;
 use tracing::subscriber;
 
 pub mod config;
+pub mod db;
 pub mod debug;
 pub mod eth;
 pub mod genesis;
@@ -24,6 +30,18 @@ pub mod materializer;
 pub mod rpc;
 pub mod run;
 
+// Database collection names.
+namespaces! {
+    Namespaces {
+        app,
+        state_hist,
+        state_store,
+        bit_store,
+        finality_cert,
+        parent_view
+    }
+}
+
 #[async_trait]
 pub trait Cmd {
     type Settings;
@@ -68,9 +86,20 @@ macro_rules! cmd {
 pub async fn exec(opts: &Options) -> anyhow::Result<()> {
     match &opts.command {
         Commands::Config(args) => args.exec(settings(opts)?).await,
+        Commands::Db(args) => {
+            let settings = settings(opts)?;
+            let _trace_file_guard = set_global_tracing_subscriber(&TracingSettings::default());
+            args.exec(settings).await
+        }
         Commands::Debug(args) => {
+            // Only replaying blocks needs the settings, to open the database;
+            // the rest of the commands work without a configuration directory.
+            let settings = match args.command {
+                DebugCommands::Replay(_) => Some(settings(opts)?),
+                _ => None,
+            };
             let _trace_file_guard = set_global_tracing_subscriber(&TracingSettings::default());
-            args.exec(()).await
+            args.exec(settings).await
         }
         Commands::Run(args) => {
             let settings = settings(opts)?;
@@ -121,3 +150,32 @@ fn settings(opts: &Options) -> anyhow::Result<Settings> {
 
     Ok(settings)
 }
+
+/// Where the data of a database backend is stored.
+fn db_path(settings: &Settings, backend: DbBackend) -> PathBuf {
+    match backend {
+        DbBackend::RocksDb => settings.data_dir().join("rocksdb"),
+        DbBackend::Redb => settings.data_dir().join("fendermint.redb"),
+    }
+}
+
+/// Open database with all the namespaces the application uses.
+fn open_db(settings: &Settings, backend: DbBackend, ns: &Namespaces) -> anyhow::Result<AppDb> {
+    let path = db_path(settings, backend);
+    tracing::info!(
+        path = path.to_string_lossy().into_owned(),
+        backend = backend.to_string(),
+        "opening database"
+    );
+    let db = match backend {
+        DbBackend::RocksDb => {
+            let config = RocksDbConfig {
+                compaction_style: settings.db.compaction_style.to_string(),
+                ..Default::default()
+            };
+            AppDb::RocksDb(RocksDb::open_cf(path, &config, ns.values().iter())?)
+        }
+        DbBackend::Redb => AppDb::Redb(Redb::open_ns(path, ns.values().iter())?),
+    };
+    Ok(db)
+}
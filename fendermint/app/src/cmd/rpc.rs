@@ -8,6 +8,8 @@ use std::pin::Pin;
 use anyhow::Context;
 use async_trait::async_trait;
 use bytes::Bytes;
+use fendermint_actor_eam::PermissionModeParams;
+use fendermint_app::ipc::AppVote;
 use fendermint_app_options::genesis::AccountKind;
 use fendermint_crypto::{to_b64, SecretKey};
 use fendermint_rpc::client::BoundFendermintClient;
@@ -24,16 +26,18 @@ use fvm_shared::econ::TokenAmount;
 use fvm_shared::MethodNum;
 use serde::Serialize;
 use serde_json::json;
-use tendermint::abci::response::DeliverTx;
+use tendermint::abci::types::ExecTxResult;
 use tendermint::block::Height;
 use tendermint_rpc::HttpClient;
 
 use crate::cmd;
 use crate::options::rpc::{BroadcastMode, FevmArgs, RpcFevmCommands, TransArgs};
+use crate::options::rpc::{EamPermissionMode, RpcEamCommands};
 use crate::options::rpc::{RpcArgs, RpcCommands, RpcQueryCommands};
 use fendermint_rpc::message::{GasParams, SignedMessageFactory};
 use fendermint_rpc::{client::FendermintClient, query::QueryClient};
 use fendermint_vm_actor_interface::eam::{self, CreateReturn, EthAddress};
+use fendermint_vm_topdown::certificate::QuorumCertificate;
 
 use super::key::read_secret_key;
 
@@ -66,6 +70,28 @@ cmd! {
             let height = Height::try_from(height)?;
             fevm_estimate_gas(client, args, contract, method, method_args, height).await
         }
+      },
+      RpcCommands::Eam { command } => match command {
+        RpcEamCommands::AddDeployer { deployers, args } => {
+            eam_add_deployers(client, args, deployers).await
+        }
+        RpcEamCommands::RemoveDeployer { deployers, args } => {
+            eam_remove_deployers(client, args, deployers).await
+        }
+        RpcEamCommands::SetMode { mode, deployers, args } => {
+            let mode = match mode {
+                EamPermissionMode::Unrestricted => PermissionModeParams::Unrestricted,
+                EamPermissionMode::Allowlist => PermissionModeParams::AllowList(deployers),
+            };
+            eam_set_permission_mode(client, args, mode).await
+        }
+        RpcEamCommands::ChangeAdmin { admin, args } => {
+            eam_change_admin(client, args, admin).await
+        }
+        RpcEamCommands::ListDeployers { height } => {
+            let height = Height::try_from(height)?;
+            eam_list_deployers(client, height).await
+        }
       }
     }
   }
@@ -97,11 +123,58 @@ async fn query(
                 }
             }
         }
+        RpcQueryCommands::Vesting { address } => {
+            let res = client.vesting_info(&address, height).await?;
+            match res.value {
+                Some(info) => {
+                    let out = json!({
+                        "height": res.height,
+                        "owner": info.owner.to_string(),
+                        "revoker": info.revoker.map(|a| a.to_string()),
+                        "vested": info.vested.atto().to_string(),
+                        "unvested": info.unvested.atto().to_string(),
+                        "withdrawable": info.withdrawable.atto().to_string(),
+                        "revoked_at": info.revoked_at,
+                    });
+                    print_json(&out)?;
+                }
+                None => {
+                    eprintln!("actor not found")
+                }
+            }
+        }
+        RpcQueryCommands::BlockHashes { from, to } => {
+            let res = client.block_hashes(from, to, height).await?;
+            let hashes = res
+                .value
+                .into_iter()
+                .map(|e| json!({ "height": e.epoch, "hash": hex::encode(e.block) }))
+                .collect::<Vec<_>>();
+            let json = json!({ "height": res.height, "block_hashes": hashes });
+            print_json(&json)?;
+        }
         RpcQueryCommands::StateParams => {
             let res = client.state_params(height).await?;
             let json = json!({ "response": res });
             print_json(&json)?;
         }
+        RpcQueryCommands::FinalityCertificate {
+            parent_height,
+            subnet_id,
+        } => match client.parent_finality_certificate(parent_height).await? {
+            Some(bz) => {
+                let cert: QuorumCertificate = fvm_ipld_encoding::from_slice(&bz)
+                    .context("failed to decode finality certificate")?;
+                let weight = cert
+                    .verify(&subnet_id, |vote| match vote {
+                        AppVote::ParentFinality(f) => Some(f),
+                    })
+                    .context("invalid finality certificate")?;
+                let json = json!({ "certificate": cert, "weight": weight });
+                print_json(&json)?;
+            }
+            None => eprintln!("certificate not found"),
+        },
     };
     Ok(())
 }
@@ -282,6 +355,92 @@ async fn fevm_estimate_gas(
     print_json(&json)
 }
 
+/// Add deployers to the EAM allow-list through RPC and print the response to STDOUT as JSON.
+async fn eam_add_deployers(
+    client: FendermintClient,
+    args: TransArgs,
+    deployers: Vec<Address>,
+) -> anyhow::Result<()> {
+    broadcast_and_print(
+        client,
+        args,
+        |mut client, _, gas_params| {
+            Box::pin(async move { client.eam_add_deployers(deployers, gas_params).await })
+        },
+        |_| serde_json::Value::Null,
+    )
+    .await
+}
+
+/// Remove deployers from the EAM allow-list through RPC and print the response to STDOUT as JSON.
+async fn eam_remove_deployers(
+    client: FendermintClient,
+    args: TransArgs,
+    deployers: Vec<Address>,
+) -> anyhow::Result<()> {
+    broadcast_and_print(
+        client,
+        args,
+        |mut client, _, gas_params| {
+            Box::pin(async move { client.eam_remove_deployers(deployers, gas_params).await })
+        },
+        |_| serde_json::Value::Null,
+    )
+    .await
+}
+
+/// Change the EAM permission mode through RPC and print the response to STDOUT as JSON.
+async fn eam_set_permission_mode(
+    client: FendermintClient,
+    args: TransArgs,
+    mode: PermissionModeParams,
+) -> anyhow::Result<()> {
+    broadcast_and_print(
+        client,
+        args,
+        |mut client, _, gas_params| {
+            Box::pin(async move { client.eam_set_permission_mode(mode, gas_params).await })
+        },
+        |_| serde_json::Value::Null,
+    )
+    .await
+}
+
+/// Change the EAM admin through RPC and print the response to STDOUT as JSON.
+async fn eam_change_admin(
+    client: FendermintClient,
+    args: TransArgs,
+    admin: Option<Address>,
+) -> anyhow::Result<()> {
+    broadcast_and_print(
+        client,
+        args,
+        |mut client, _, gas_params| {
+            Box::pin(async move { client.eam_change_admin(admin, gas_params).await })
+        },
+        |_| serde_json::Value::Null,
+    )
+    .await
+}
+
+/// Query the EAM permission mode and print the deployers to STDOUT as JSON.
+async fn eam_list_deployers(client: FendermintClient, height: Height) -> anyhow::Result<()> {
+    let height = FvmQueryHeight::from(height.value());
+    let res = client.eam_permission_mode(height).await?;
+    let json = match res.value {
+        PermissionModeParams::Unrestricted => json!({
+            "height": res.height,
+            "mode": "unrestricted",
+        }),
+        PermissionModeParams::AllowList(deployers) => json!({
+            "height": res.height,
+            "mode": "allowlist",
+            "deployers": deployers.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+        }),
+    };
+    print_json(&json)
+}
+
 /// Print out pretty-printed JSON.
 ///
 /// People can use `jq` to turn it into compact form if they want to save the results to a `.jsonline`
@@ -349,7 +508,7 @@ impl BoundClient for TransClient {
 impl TxClient<BroadcastModeWrapper> for TransClient {
     async fn perform<F, T>(&self, msg: ChainMessage, f: F) -> anyhow::Result<BroadcastResponse<T>>
     where
-        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        F: FnOnce(&ExecTxResult) -> anyhow::Result<T> + Sync + Send,
         T: Sync + Send,
     {
         match self.broadcast_mode.0 {
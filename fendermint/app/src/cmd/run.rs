@@ -2,13 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use anyhow::{anyhow, bail, Context};
-use async_stm::atomically_or_err;
+use async_stm::{atomically, atomically_or_err, retry};
 use fendermint_abci::ApplicationService;
-use fendermint_app::ipc::{AppParentFinalityQuery, AppVote};
+use fendermint_app::backup::Backups;
+use fendermint_app::db::{AppBlockstore, AppDb};
+use fendermint_app::db_admin;
+use fendermint_app::ipc::{AppParentFinalityQuery, AppParentViewStore, AppVote};
+use fendermint_app::resolver_admin;
+use fendermint_app::vote_ext::VoteExtensions;
 use fendermint_app::{App, AppConfig, AppStore, BitswapBlockstore};
-use fendermint_app_settings::AccountKind;
+use fendermint_app_settings::{AbciVersion, AccountKind};
 use fendermint_crypto::SecretKey;
-use fendermint_rocksdb::{blockstore::NamespaceBlockstore, namespaces, RocksDb, RocksDbConfig};
 use fendermint_vm_actor_interface::eam::EthAddress;
 use fendermint_vm_interpreter::chain::ChainEnv;
 use fendermint_vm_interpreter::fvm::observe::register_metrics as register_interpreter_metrics;
@@ -16,29 +20,33 @@ use fendermint_vm_interpreter::fvm::upgrades::UpgradeScheduler;
 use fendermint_vm_interpreter::{
     bytes::{BytesMessageInterpreter, ProposalPrepareMode},
     chain::{ChainMessageInterpreter, CheckpointPool},
-    fvm::{Broadcaster, FvmMessageInterpreter, ValidatorContext},
+    fvm::{Broadcaster, CheckpointSignaturePool, FvmMessageInterpreter, ValidatorContext},
     signed::SignedMessageInterpreter,
 };
 use fendermint_vm_resolver::ipld::IpldResolver;
 use fendermint_vm_snapshot::{SnapshotManager, SnapshotParams};
 use fendermint_vm_topdown::observe::register_metrics as register_topdown_metrics;
 use fendermint_vm_topdown::proxy::{IPCProviderProxy, IPCProviderProxyWithLatency};
-use fendermint_vm_topdown::sync::launch_polling_syncer;
-use fendermint_vm_topdown::voting::{publish_vote_loop, Error as VoteError, VoteTally};
+use fendermint_vm_topdown::sync::{launch_polling_syncer, ParentFinalityStateQuery};
+use fendermint_vm_topdown::voting::{
+    publish_vote_loop, Error as VoteError, ValidatorKey, VoteTally,
+};
 use fendermint_vm_topdown::{CachedFinalityProvider, IPCParentFinality, Toggle};
 use fvm_shared::address::{current_network, Address, Network};
-use ipc_ipld_resolver::{Event as ResolverEvent, VoteRecord};
+use ipc_ipld_resolver::{Event as ResolverEvent, SignedVoteRecord};
 use ipc_observability::observe::register_metrics as register_default_metrics;
 use ipc_provider::config::subnet::{EVMSubnet, SubnetConfig};
 use ipc_provider::IpcProvider;
 use libp2p::identity::secp256k1;
 use libp2p::identity::Keypair;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast::error::RecvError;
 use tower::ServiceBuilder;
 use tracing::info;
 
 use crate::cmd::key::read_secret_key;
+use crate::cmd::{open_db, Namespaces};
 use crate::{cmd, options::run::RunArgs, settings::Settings};
 use fendermint_app::observe::register_metrics as register_consensus_metrics;
 
@@ -48,16 +56,6 @@ cmd! {
   }
 }
 
-// Database collection names.
-namespaces! {
-    Namespaces {
-        app,
-        state_hist,
-        state_store,
-        bit_store
-    }
-}
-
 /// Run the Fendermint ABCI Application.
 ///
 /// This method acts as our composition root.
@@ -110,6 +108,10 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
         libp2p::identity::Keypair::from(kp)
     });
 
+    // With ABCI 2.0 checkpoint signatures are sent as vote extensions instead of transactions.
+    let vote_extensions_enabled = settings.abci.version == AbciVersion::V038;
+    let checkpoint_signatures = CheckpointSignaturePool::new();
+
     let validator_ctx = validator.map(|(sk, addr)| {
         // For now we are using the validator key for submitting transactions.
         // This allows us to identify transactions coming from empowered validators, to give priority to protocol related transactions.
@@ -124,7 +126,13 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
         .with_max_retries(settings.broadcast.max_retries)
         .with_retry_delay(settings.broadcast.retry_delay);
 
-        ValidatorContext::new(sk, broadcaster)
+        let ctx = ValidatorContext::new(sk, broadcaster);
+
+        if vote_extensions_enabled {
+            ctx.with_signature_pool(checkpoint_signatures.clone())
+        } else {
+            ctx
+        }
     });
 
     let testing_settings = match settings.testing.as_ref() {
@@ -134,7 +142,7 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
         other => other,
     };
 
-    let interpreter = FvmMessageInterpreter::<NamespaceBlockstore, _>::new(
+    let interpreter = FvmMessageInterpreter::<AppBlockstore, _>::new(
         tendermint_client.clone(),
         validator_ctx,
         settings.fvm.gas_overestimation_rate,
@@ -145,7 +153,7 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
     .with_push_chain_meta(testing_settings.map_or(true, |t| t.push_chain_meta));
 
     let interpreter = SignedMessageInterpreter::new(interpreter);
-    let interpreter = ChainMessageInterpreter::<_, NamespaceBlockstore>::new(interpreter);
+    let interpreter = ChainMessageInterpreter::<_, AppBlockstore>::new(interpreter);
     let interpreter = BytesMessageInterpreter::new(
         interpreter,
         ProposalPrepareMode::PrependOnly,
@@ -154,11 +162,23 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
     );
 
     let ns = Namespaces::default();
-    let db = open_db(&settings, &ns).context("error opening DB")?;
+    let db = open_db(&settings, settings.db.backend, &ns).context("error opening DB")?;
+
+    if settings.db.backup.admin.enabled {
+        let listen_addr = settings.db.backup.admin.listen.clone();
+        let backups = Backups::new(settings.backups_dir(), settings.db.backup.keep);
+        let db = db.clone();
+        let app_ns = ns.app.clone();
+        tokio::spawn(async move {
+            if let Err(e) = db_admin::listen(listen_addr, db, backups, app_ns).await {
+                tracing::error!("DB admin API failed: {e:#}")
+            }
+        });
+    }
 
     // Blockstore for actors.
     let state_store =
-        NamespaceBlockstore::new(db.clone(), ns.state_store).context("error creating state DB")?;
+        AppBlockstore::new(db.clone(), ns.state_store).context("error creating state DB")?;
 
     let checkpoint_pool = CheckpointPool::new();
     let parent_finality_votes = VoteTally::empty();
@@ -185,6 +205,25 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
             .add_provided_subnet(own_subnet_id.clone())
             .context("error adding own provided subnet.")?;
 
+        if settings.resolver.connection.validator_only {
+            tracing::info!("only validators are allowed to connect to the IPLD Resolver");
+            let client = client.clone();
+            let parent_finality_votes = parent_finality_votes.clone();
+            tokio::spawn(async move {
+                dispatch_validators(client, parent_finality_votes).await;
+            });
+        }
+
+        if settings.resolver.admin.enabled {
+            let client = client.clone();
+            let listen_addr = settings.resolver.admin.listen.clone();
+            tokio::spawn(async move {
+                if let Err(e) = resolver_admin::listen(listen_addr, client).await {
+                    tracing::error!("IPLD Resolver admin API failed: {e:#}")
+                }
+            });
+        }
+
         let resolver = IpldResolver::new(
             client.clone(),
             checkpoint_pool.queue(),
@@ -192,8 +231,10 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
             own_subnet_id.clone(),
         );
 
-        if topdown_enabled {
-            if let Some(key) = validator_keypair {
+        if topdown_enabled && vote_extensions_enabled {
+            tracing::info!("parent finality votes are sent as vote extensions");
+        } else if topdown_enabled {
+            if let Some(key) = validator_keypair.clone() {
                 let parent_finality_votes = parent_finality_votes.clone();
 
                 tracing::info!("starting the parent finality vote gossip loop...");
@@ -258,8 +299,13 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
             Arc::new(IPCProviderProxyWithLatency::new(p))
         };
 
+        let parent_view_store: AppParentViewStore<_, AppStore> =
+            AppParentViewStore::new(db.clone(), ns.parent_view);
+
         let finality_provider =
-            CachedFinalityProvider::uninitialized(config.clone(), ipc_provider.clone()).await?;
+            CachedFinalityProvider::uninitialized(config.clone(), ipc_provider.clone())
+                .await?
+                .with_store(Arc::new(parent_view_store));
 
         let p = Arc::new(Toggle::enabled(finality_provider));
         (p, Some((ipc_provider, config)))
@@ -278,6 +324,7 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
                 block_interval: settings.snapshots.block_interval,
                 chunk_size: settings.snapshots.chunk_size_bytes,
                 hist_size: settings.snapshots.hist_size,
+                max_deltas: settings.snapshots.max_deltas,
                 last_access_hold: settings.snapshots.last_access_hold,
                 sync_poll_interval: settings.snapshots.sync_poll_interval,
             },
@@ -299,6 +346,7 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
             app_namespace: ns.app,
             state_hist_namespace: ns.state_hist,
             state_hist_size: settings.db.state_hist_size,
+            finality_cert_namespace: ns.finality_cert,
             halt_height: settings.halt_height,
         },
         db,
@@ -308,10 +356,22 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
             checkpoint_pool,
             parent_finality_provider: parent_finality_provider.clone(),
             parent_finality_votes: parent_finality_votes.clone(),
+            checkpoint_signatures,
+            vote_extensions: vote_extensions_enabled,
+            power_table: Vec::new(),
         },
         snapshots,
     )?;
 
+    let app = if vote_extensions_enabled {
+        app.with_vote_extensions(VoteExtensions::new(
+            settings.ipc.subnet_id.clone(),
+            validator_keypair,
+        ))
+    } else {
+        app
+    };
+
     if let Some((agent_proxy, config)) = ipc_tuple {
         let app_parent_finality_query = AppParentFinalityQuery::new(app.clone());
         tokio::spawn(async move {
@@ -329,6 +389,15 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
                 Err(e) => tracing::error!("cannot launch polling syncer: {e}"),
             }
         });
+    } else if vote_extensions_enabled
+        || (settings.resolver_enabled() && settings.resolver.connection.validator_only)
+    {
+        // Without the syncer nobody loads the initial power table, the interpreter only applies changes.
+        // Proposed checkpoint signatures are checked against it, as well as the resolver peers.
+        let app_parent_finality_query = AppParentFinalityQuery::new(app.clone());
+        tokio::spawn(async move {
+            init_power_table(app_parent_finality_query, parent_finality_votes).await;
+        });
     }
 
     // Start the metrics on a background thread.
@@ -346,65 +415,61 @@ async fn run(settings: Settings) -> anyhow::Result<()> {
 
     let service = ApplicationService(app);
 
-    // Split it into components.
-    let (consensus, mempool, snapshot, info) =
-        tower_abci::split::service(service, settings.abci.bound);
-
-    // Hand those components to the ABCI server. This is where tower layers could be added.
-    // TODO: Check out the examples about load shedding in `info` requests.
-    let server = tower_abci::v037::Server::builder()
-        .consensus(
-            // Limiting the concurrency to 1 here because the `AplicationService::poll_ready` always
-            // reports `Ready`, because it doesn't know which request it's going to get.
-            // Not limiting the concurrency to 1 can lead to transactions being applied
-            // in different order across nodes. The buffer size has to be large enough
-            // to allow all in-flight requests to not block message handling in
-            // `tower_abci::Connection::run`, which could lead to deadlocks.
-            // With ABCI++ we need to be able to handle all block transactions plus the begin/end/commit
-            // around it. With ABCI 2.0 we'll get the block as a whole, which makes this easier.
-            ServiceBuilder::new()
-                .buffer(settings.abci.block_max_msgs + 3)
-                .concurrency_limit(1)
-                .service(consensus),
-        )
-        .snapshot(snapshot)
-        .mempool(mempool)
-        .info(info)
-        .finish()
-        .context("error creating ABCI server")?;
-
-    // Run the ABCI server.
-    server
-        .listen(settings.abci.listen.to_string())
-        .await
-        .map_err(|e| anyhow!("error listening: {e}"))?;
+    // Hand the components of the service to the ABCI server of the configured protocol version.
+    // The two are identical apart from the version specific modules in `tower_abci`.
+    macro_rules! serve_abci {
+        ($version:ident) => {{
+            // Split it into components.
+            let (consensus, mempool, snapshot, info) =
+                tower_abci::$version::split::service(service, settings.abci.bound);
+
+            // This is where tower layers could be added.
+            // TODO: Check out the examples about load shedding in `info` requests.
+            let server = tower_abci::$version::Server::builder()
+                .consensus(
+                    // Limiting the concurrency to 1 here because the `AplicationService::poll_ready` always
+                    // reports `Ready`, because it doesn't know which request it's going to get.
+                    // Not limiting the concurrency to 1 can lead to transactions being applied
+                    // in different order across nodes. The buffer size has to be large enough
+                    // to allow all in-flight requests to not block message handling in
+                    // `tower_abci::Connection::run`, which could lead to deadlocks.
+                    // With ABCI++ we need to be able to handle all block transactions plus the begin/end/commit
+                    // around it. With ABCI 2.0 we get the block as a whole, so this is more than enough.
+                    ServiceBuilder::new()
+                        .buffer(settings.abci.block_max_msgs + 3)
+                        .concurrency_limit(1)
+                        .service(consensus),
+                )
+                .snapshot(snapshot)
+                .mempool(mempool)
+                .info(info)
+                .finish()
+                .context("error creating ABCI server")?;
+
+            // Run the ABCI server.
+            server
+                .listen(settings.abci.listen.to_string())
+                .await
+                .map_err(|e| anyhow!("error listening: {e}"))?;
+        }};
+    }
 
-    Ok(())
-}
+    match settings.abci.version {
+        AbciVersion::V037 => serve_abci!(v037),
+        AbciVersion::V038 => serve_abci!(v038),
+    }
 
-/// Open database with all
-fn open_db(settings: &Settings, ns: &Namespaces) -> anyhow::Result<RocksDb> {
-    let path = settings.data_dir().join("rocksdb");
-    info!(
-        path = path.to_string_lossy().into_owned(),
-        "opening database"
-    );
-    let config = RocksDbConfig {
-        compaction_style: settings.db.compaction_style.to_string(),
-        ..Default::default()
-    };
-    let db = RocksDb::open_cf(path, &config, ns.values().iter())?;
-    Ok(db)
+    Ok(())
 }
 
 fn make_resolver_service(
     settings: &Settings,
-    db: RocksDb,
-    state_store: NamespaceBlockstore,
+    db: AppDb,
+    state_store: AppBlockstore,
     bit_store_ns: String,
 ) -> anyhow::Result<ipc_ipld_resolver::Service<libipld::DefaultParams, AppVote>> {
     // Blockstore for Bitswap.
-    let bit_store = NamespaceBlockstore::new(db, bit_store_ns).context("error creating bit DB")?;
+    let bit_store = AppBlockstore::new(db, bit_store_ns).context("error creating bit DB")?;
 
     // Blockstore for Bitswap with a fallback on the actor store for reads.
     let bitswap_store = BitswapBlockstore::new(state_store, bit_store);
@@ -417,7 +482,7 @@ fn make_resolver_service(
     Ok(service)
 }
 
-fn make_ipc_provider_proxy(settings: &Settings) -> anyhow::Result<IPCProviderProxy> {
+pub(crate) fn make_ipc_provider_proxy(settings: &Settings) -> anyhow::Result<IPCProviderProxy> {
     let topdown_config = settings.ipc.topdown_config()?;
     let subnet = ipc_provider::config::Subnet {
         id: settings
@@ -465,12 +530,25 @@ fn to_resolver_config(settings: &Settings) -> anyhow::Result<ipc_ipld_resolver::
 
     let config = Config {
         connection: ConnectionConfig {
-            listen_addr: r.connection.listen_addr.clone(),
+            listen_addrs: r
+                .connection
+                .listen_addrs
+                .iter()
+                .filter(|addr| !addr.is_empty())
+                .cloned()
+                .collect(),
             external_addresses: r.connection.external_addresses.clone(),
             expected_peer_count: r.connection.expected_peer_count,
             max_incoming: r.connection.max_incoming,
             max_peers_per_query: r.connection.max_peers_per_query,
             event_buffer_capacity: r.connection.event_buffer_capacity,
+            allow_list: r.connection.allow_list.clone(),
+            deny_list: r.connection.deny_list.clone(),
+            validator_only: r.connection.validator_only,
+            ban_threshold: r.connection.ban_threshold,
+            ban_duration: r.connection.ban_duration,
+            penalty_decay: r.connection.penalty_decay,
+            max_penalized_peers: r.connection.max_penalized_peers,
         },
         network: NetworkConfig {
             local_key,
@@ -505,6 +583,60 @@ fn to_address(sk: &SecretKey, kind: &AccountKind) -> anyhow::Result<Address> {
     }
 }
 
+/// Keep the validator set of the IPLD Resolver up to date with the power table in the vote tally.
+async fn dispatch_validators(client: ipc_ipld_resolver::Client<AppVote>, votes: VoteTally) {
+    let mut current = atomically(|| votes.validators()).await;
+    loop {
+        let validators = current
+            .iter()
+            .cloned()
+            .map(libp2p::identity::PublicKey::from)
+            .collect();
+
+        if let Err(e) = client.set_validators(validators) {
+            tracing::error!(error = e.to_string(), "failed to set resolver validators");
+            return;
+        }
+
+        current = atomically(|| {
+            let validators = votes.validators()?;
+            if validators == current {
+                retry()
+            } else {
+                Ok(validators)
+            }
+        })
+        .await;
+    }
+}
+
+/// Load the power table of the current committee into the vote tally, once the application has been initialized.
+async fn init_power_table<Q>(query: Q, votes: VoteTally)
+where
+    Q: ParentFinalityStateQuery,
+{
+    loop {
+        match query.get_power_table() {
+            Ok(Some(power_table)) => {
+                let power_table = power_table
+                    .into_iter()
+                    .map(|v| (ValidatorKey::from(v.public_key.0), v.power.0))
+                    .collect::<Vec<_>>();
+
+                atomically(|| votes.set_power_table(power_table.clone())).await;
+                return;
+            }
+            Ok(None) => {
+                tracing::debug!("app not ready for query yet");
+            }
+            Err(e) => {
+                tracing::warn!(error = e.to_string(), "cannot get power table");
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
+
 async fn dispatch_resolver_events(
     mut rx: tokio::sync::broadcast::Receiver<ResolverEvent<AppVote>>,
     parent_finality_votes: VoteTally,
@@ -530,10 +662,14 @@ async fn dispatch_resolver_events(
 }
 
 async fn dispatch_vote(
-    vote: VoteRecord<AppVote>,
+    vote: SignedVoteRecord<AppVote>,
     parent_finality_votes: &VoteTally,
     topdown_enabled: bool,
 ) {
+    // Keep the signed envelope so the vote can be part of a quorum certificate.
+    let envelope = vote.envelope().clone().into_protobuf_encoding();
+    let vote = vote.into_record();
+
     match vote.content {
         AppVote::ParentFinality(f) => {
             if !topdown_enabled {
@@ -541,10 +677,11 @@ async fn dispatch_vote(
                 return;
             }
             let res = atomically_or_err(|| {
-                parent_finality_votes.add_vote(
+                parent_finality_votes.add_signed_vote(
                     vote.public_key.clone(),
                     f.height,
                     f.block_hash.clone(),
+                    envelope.clone(),
                 )
             })
             .await;
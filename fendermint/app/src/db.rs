@@ -0,0 +1,334 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! The database backends the application can run on, selected at startup,
+//! and the means to copy data from one backend to another.
+
+use std::borrow::Cow;
+
+use cid::Cid;
+use either::Either;
+use fendermint_redb::{Redb, RedbReadTx, RedbWriteTx};
+use fendermint_rocksdb::{RocksDb, RocksDbReadTx, RocksDbWriteTx};
+use fendermint_storage::{
+    Decode, Encode, KVRead, KVReadable, KVResult, KVStore, KVTransaction, KVWritable, KVWrite,
+};
+use fvm_ipld_blockstore::Blockstore;
+
+/// Dispatch to the concrete transaction type of the backend.
+macro_rules! dispatch {
+    ($this:expr, $enum:ident, $tx:ident => $e:expr) => {
+        match $this {
+            $enum::RocksDb($tx) => $e,
+            $enum::Redb($tx) => $e,
+        }
+    };
+}
+
+/// Database backing all the key-value collections and blockstores of the application.
+#[derive(Clone)]
+pub enum AppDb {
+    RocksDb(RocksDb),
+    Redb(Redb),
+}
+
+pub enum AppDbReadTx<'a> {
+    RocksDb(RocksDbReadTx<'a>),
+    Redb(RedbReadTx),
+}
+
+pub enum AppDbWriteTx<'a> {
+    RocksDb(RocksDbWriteTx<'a>),
+    Redb(RedbWriteTx),
+}
+
+impl<S> KVReadable<S> for AppDb
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    type Tx<'a>
+        = AppDbReadTx<'a>
+    where
+        Self: 'a;
+
+    fn read(&self) -> Self::Tx<'_> {
+        match self {
+            AppDb::RocksDb(db) => AppDbReadTx::RocksDb(KVReadable::<S>::read(db)),
+            AppDb::Redb(db) => AppDbReadTx::Redb(KVReadable::<S>::read(db)),
+        }
+    }
+}
+
+impl<S> KVWritable<S> for AppDb
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    type Tx<'a>
+        = AppDbWriteTx<'a>
+    where
+        Self: 'a;
+
+    fn write(&self) -> Self::Tx<'_> {
+        match self {
+            AppDb::RocksDb(db) => AppDbWriteTx::RocksDb(KVWritable::<S>::write(db)),
+            AppDb::Redb(db) => AppDbWriteTx::Redb(KVWritable::<S>::write(db)),
+        }
+    }
+}
+
+impl<'a, S> KVRead<S> for AppDbReadTx<'a>
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    fn get<K, V>(&self, ns: &S::Namespace, k: &K) -> KVResult<Option<V>>
+    where
+        S: Encode<K> + Decode<V>,
+    {
+        dispatch!(self, AppDbReadTx, tx => KVRead::<S>::get(tx, ns, k))
+    }
+
+    fn iterate<K, V>(&self, ns: &S::Namespace) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        K: 'static,
+        V: 'static,
+        S: Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        match self {
+            AppDbReadTx::RocksDb(tx) => Either::Left(KVRead::<S>::iterate(tx, ns)),
+            AppDbReadTx::Redb(tx) => Either::Right(KVRead::<S>::iterate(tx, ns)),
+        }
+    }
+
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        K: 'static,
+        V: 'static,
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        match self {
+            AppDbReadTx::RocksDb(tx) => Either::Left(KVRead::<S>::iterate_from(tx, ns, from)),
+            AppDbReadTx::Redb(tx) => Either::Right(KVRead::<S>::iterate_from(tx, ns, from)),
+        }
+    }
+}
+
+impl<'a, S> KVRead<S> for AppDbWriteTx<'a>
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    fn get<K, V>(&self, ns: &S::Namespace, k: &K) -> KVResult<Option<V>>
+    where
+        S: Encode<K> + Decode<V>,
+    {
+        dispatch!(self, AppDbWriteTx, tx => KVRead::<S>::get(tx, ns, k))
+    }
+
+    fn iterate<K, V>(&self, ns: &S::Namespace) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        K: 'static,
+        V: 'static,
+        S: Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        match self {
+            AppDbWriteTx::RocksDb(tx) => Either::Left(KVRead::<S>::iterate(tx, ns)),
+            AppDbWriteTx::Redb(tx) => Either::Right(KVRead::<S>::iterate(tx, ns)),
+        }
+    }
+
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        K: 'static,
+        V: 'static,
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        match self {
+            AppDbWriteTx::RocksDb(tx) => Either::Left(KVRead::<S>::iterate_from(tx, ns, from)),
+            AppDbWriteTx::Redb(tx) => Either::Right(KVRead::<S>::iterate_from(tx, ns, from)),
+        }
+    }
+}
+
+impl<'a, S> KVWrite<S> for AppDbWriteTx<'a>
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    fn put<K, V>(&mut self, ns: &S::Namespace, k: &K, v: &V) -> KVResult<()>
+    where
+        S: Encode<K> + Encode<V>,
+    {
+        dispatch!(self, AppDbWriteTx, tx => KVWrite::<S>::put(tx, ns, k, v))
+    }
+
+    fn delete<K>(&mut self, ns: &S::Namespace, k: &K) -> KVResult<()>
+    where
+        S: Encode<K>,
+    {
+        dispatch!(self, AppDbWriteTx, tx => KVWrite::<S>::delete(tx, ns, k))
+    }
+}
+
+impl<'a> KVTransaction for AppDbWriteTx<'a> {
+    fn commit(self) -> KVResult<()> {
+        dispatch!(self, AppDbWriteTx, tx => tx.commit())
+    }
+
+    fn rollback(self) -> KVResult<()> {
+        dispatch!(self, AppDbWriteTx, tx => tx.rollback())
+    }
+}
+
+/// A [`Blockstore`] writing to a namespace of the [`AppDb`].
+#[derive(Clone)]
+pub enum AppBlockstore {
+    RocksDb(fendermint_rocksdb::blockstore::NamespaceBlockstore),
+    Redb(fendermint_redb::blockstore::NamespaceBlockstore),
+}
+
+impl AppBlockstore {
+    pub fn new(db: AppDb, ns: String) -> anyhow::Result<Self> {
+        match db {
+            AppDb::RocksDb(db) => Ok(Self::RocksDb(
+                fendermint_rocksdb::blockstore::NamespaceBlockstore::new(db, ns)?,
+            )),
+            AppDb::Redb(db) => Ok(Self::Redb(
+                fendermint_redb::blockstore::NamespaceBlockstore::new(db, ns)?,
+            )),
+        }
+    }
+}
+
+impl Blockstore for AppBlockstore {
+    fn has(&self, k: &Cid) -> anyhow::Result<bool> {
+        dispatch!(self, AppBlockstore, bs => bs.has(k))
+    }
+
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        dispatch!(self, AppBlockstore, bs => bs.get(k))
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+        dispatch!(self, AppBlockstore, bs => bs.put_keyed(k, block))
+    }
+
+    fn put_many_keyed<D, I>(&self, blocks: I) -> anyhow::Result<()>
+    where
+        Self: Sized,
+        D: AsRef<[u8]>,
+        I: IntoIterator<Item = (Cid, D)>,
+    {
+        dispatch!(self, AppBlockstore, bs => bs.put_many_keyed(blocks))
+    }
+}
+
+/// [`KVStore`] which doesn't interpret the data, to copy it between databases as-is.
+#[derive(Clone)]
+pub struct RawStore;
+
+impl KVStore for RawStore {
+    type Repr = Vec<u8>;
+    type Namespace = String;
+}
+
+impl Encode<Vec<u8>> for RawStore {
+    fn to_repr(value: &Vec<u8>) -> KVResult<Cow<Self::Repr>> {
+        Ok(Cow::Borrowed(value))
+    }
+}
+
+impl Decode<Vec<u8>> for RawStore {
+    fn from_repr(repr: &Self::Repr) -> KVResult<Vec<u8>> {
+        Ok(repr.clone())
+    }
+}
+
+/// Copy every entry of a namespace from one database to another, committing them in batches.
+///
+/// Returns the number of entries copied.
+pub fn copy_namespace<A, B>(from: &A, to: &B, ns: &str, batch_size: usize) -> KVResult<usize>
+where
+    A: KVReadable<RawStore>,
+    B: KVWritable<RawStore>,
+{
+    let ns = ns.to_owned();
+    let src = from.read();
+    let mut entries = src.iterate::<Vec<u8>, Vec<u8>>(&ns);
+    let mut count = 0;
+
+    loop {
+        let batch = entries
+            .by_ref()
+            .take(batch_size.max(1))
+            .collect::<KVResult<Vec<_>>>()?;
+
+        if batch.is_empty() {
+            break;
+        }
+
+        to.with_write(|tx| {
+            for (k, v) in batch.iter() {
+                tx.put(&ns, k, v)?;
+            }
+            Ok(())
+        })?;
+
+        count += batch.len();
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use fendermint_redb::Redb;
+    use fendermint_storage::{KVRead, KVReadable, KVWritable, KVWrite};
+
+    use super::{copy_namespace, AppDb, RawStore};
+
+    #[test]
+    fn copy_namespace_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let ns = "data".to_string();
+
+        let open =
+            |name: &str| AppDb::Redb(Redb::open_ns(dir.path().join(name), [&ns].iter()).unwrap());
+
+        let from = open("from");
+        let to = open("to");
+
+        let entries = (0..10u8).map(|i| (vec![i], vec![i; 3])).collect::<Vec<_>>();
+
+        KVWritable::<RawStore>::with_write(&from, |tx| {
+            for (k, v) in entries.iter() {
+                KVWrite::<RawStore>::put(tx, &ns, k, v)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let count = copy_namespace(&from, &to, &ns, 3).unwrap();
+        assert_eq!(count, entries.len());
+
+        let tx = KVReadable::<RawStore>::read(&to);
+        let copied = KVRead::<RawStore>::iterate::<Vec<u8>, Vec<u8>>(&tx, &ns)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(copied, entries);
+    }
+}
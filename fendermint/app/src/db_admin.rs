@@ -0,0 +1,66 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! HTTP endpoint to create backups of the database of a running node,
+//! meant to be bound to a local address and called by `fendermint db backup`.
+
+use std::net::ToSocketAddrs;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::routing::get;
+use axum::Json;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::admin::{internal_error, serve, JsonResult};
+use crate::backup::Backups;
+use crate::db::AppDb;
+
+#[derive(Clone)]
+struct AdminState {
+    db: AppDb,
+    backups: Backups,
+    app_ns: String,
+    /// Only create one backup at a time.
+    lock: Arc<Mutex<()>>,
+}
+
+/// Serve the admin endpoint until the server fails.
+pub async fn listen<A: ToSocketAddrs>(
+    listen_addr: A,
+    db: AppDb,
+    backups: Backups,
+    app_ns: String,
+) -> anyhow::Result<()> {
+    let state = AdminState {
+        db,
+        backups,
+        app_ns,
+        lock: Default::default(),
+    };
+
+    let router = axum::Router::new()
+        .route("/backups", get(list_backups).post(create_backup))
+        .with_state(state);
+
+    serve(listen_addr, router, "DB").await
+}
+
+async fn list_backups(State(state): State<AdminState>) -> JsonResult {
+    let manifests = state.backups.list().map_err(internal_error)?;
+    Ok(Json(json!(manifests)))
+}
+
+async fn create_backup(State(state): State<AdminState>) -> JsonResult {
+    let lock = state.lock.clone();
+    let _guard = lock.lock().await;
+
+    // Taking the checkpoint involves flushing to disk, so keep it off the async threads.
+    let manifest =
+        tokio::task::spawn_blocking(move || state.backups.create(&state.db, &state.app_ns))
+            .await
+            .map_err(|e| internal_error(e.into()))?
+            .map_err(internal_error)?;
+
+    Ok(Json(json!(manifest)))
+}
@@ -0,0 +1,502 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! Inspect and compare FVM state trees, to help debugging state divergences between validators.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context};
+use cid::Cid;
+use fendermint_rpc::query::QueryClient;
+use fendermint_storage::{KVCollection, KVRead, KVReadable};
+use fendermint_vm_actor_interface::evm;
+use fendermint_vm_encoding::IsHumanReadable;
+use fendermint_vm_interpreter::fvm::state::FvmStateParams;
+use fendermint_vm_message::query::FvmQueryHeight;
+use fvm::state_tree::{ActorState, StateTree};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::ActorID;
+use serde::Serialize;
+use serde_with::serde_as;
+
+use crate::app::{AppState, AppStoreKey};
+use crate::db::AppDb;
+use crate::{AppStore, BlockHeight};
+
+type Slot = [u8; 32];
+
+/// The fields of an actor which are interesting to compare.
+#[serde_as]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ActorSummary {
+    pub id: ActorID,
+    #[serde_as(as = "Option<IsHumanReadable>")]
+    pub delegated_address: Option<Address>,
+    #[serde_as(as = "IsHumanReadable")]
+    pub code: Cid,
+    #[serde_as(as = "IsHumanReadable")]
+    pub state: Cid,
+    #[serde_as(as = "IsHumanReadable")]
+    pub balance: TokenAmount,
+    pub nonce: u64,
+}
+
+impl ActorSummary {
+    fn new(id: ActorID, actor: &ActorState) -> Self {
+        Self {
+            id,
+            delegated_address: actor.delegated_address,
+            code: actor.code,
+            state: actor.state,
+            balance: actor.balance.clone(),
+            nonce: actor.sequence,
+        }
+    }
+}
+
+/// A storage slot of an EVM contract, in 0x prefixed hex format.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageSlot {
+    pub key: String,
+    pub value: String,
+}
+
+/// A storage slot which differs between two states; a missing value means the slot is empty.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageChange {
+    pub key: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ActorChange {
+    pub left: ActorSummary,
+    pub right: ActorSummary,
+    /// Changed storage slots, if both versions of the actor are EVM contracts.
+    pub storage: Vec<StorageChange>,
+}
+
+/// The differences between two state trees, from the left one to the right one.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    pub added: Vec<ActorSummary>,
+    pub removed: Vec<ActorSummary>,
+    pub changed: Vec<ActorChange>,
+}
+
+/// List all actors in the state tree, in ascending order of their IDs.
+pub fn list_actors<BS: Blockstore>(
+    store: &BS,
+    state_root: &Cid,
+) -> anyhow::Result<Vec<ActorSummary>> {
+    let actors = load_actors(store, state_root)?;
+    Ok(actors
+        .iter()
+        .map(|(id, actor)| ActorSummary::new(*id, actor))
+        .collect())
+}
+
+/// List the non-empty storage slots of an EVM contract, in ascending order of their keys.
+pub fn evm_storage<BS: Blockstore>(
+    store: &BS,
+    state_root: &Cid,
+    address: &Address,
+) -> anyhow::Result<Vec<StorageSlot>> {
+    let state_tree = StateTree::new_from_root(store, state_root)?;
+
+    let actor = state_tree
+        .get_actor_by_address(address)?
+        .ok_or_else(|| anyhow!("actor {address} not found"))?;
+
+    let state = load_evm_state(store, &actor)?
+        .ok_or_else(|| anyhow!("actor {address} is not an EVM contract"))?;
+
+    let slots = load_storage(store, &state)?;
+
+    Ok(slots
+        .iter()
+        .map(|(k, v)| StorageSlot {
+            key: to_hex(k),
+            value: to_hex(v),
+        })
+        .collect())
+}
+
+/// Compare two state trees, which can be in different stores.
+///
+/// Actors are compared by their IDs; for EVM contracts present in both trees
+/// with different contract state, the storage slots are compared as well.
+pub fn diff_states<BS1, BS2>(
+    left_store: &BS1,
+    left_root: &Cid,
+    right_store: &BS2,
+    right_root: &Cid,
+) -> anyhow::Result<StateDiff>
+where
+    BS1: Blockstore,
+    BS2: Blockstore,
+{
+    let mut diff = StateDiff::default();
+
+    // The roots are content addressed, so there is nothing to walk.
+    if left_root == right_root {
+        return Ok(diff);
+    }
+
+    let left = load_actors(left_store, left_root).context("failed to load left state")?;
+    let mut right = load_actors(right_store, right_root).context("failed to load right state")?;
+
+    for (id, left_actor) in left {
+        match right.remove(&id) {
+            None => diff.removed.push(ActorSummary::new(id, &left_actor)),
+            Some(right_actor) if right_actor != left_actor => {
+                let storage = diff_evm_storage(left_store, &left_actor, right_store, &right_actor)
+                    .with_context(|| format!("failed to compare storage of actor {id}"))?;
+
+                diff.changed.push(ActorChange {
+                    left: ActorSummary::new(id, &left_actor),
+                    right: ActorSummary::new(id, &right_actor),
+                    storage,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    diff.added = right
+        .iter()
+        .map(|(id, actor)| ActorSummary::new(*id, actor))
+        .collect();
+
+    Ok(diff)
+}
+
+/// Find the state parameters, including the state root, in the database of a node which isn't running.
+///
+/// Height 0 means the latest committed state, otherwise the state is looked up
+/// in the history, the same way the node does for queries at a given height.
+pub fn local_state_params(
+    db: &AppDb,
+    app_ns: &str,
+    state_hist_ns: &str,
+    height: BlockHeight,
+) -> anyhow::Result<FvmStateParams> {
+    let tx = KVReadable::<AppStore>::read(db);
+
+    if height == 0 {
+        let state: AppState =
+            KVRead::<AppStore>::get(&tx, &app_ns.to_string(), &AppStoreKey::State)
+                .context("failed to read app state")?
+                .ok_or_else(|| anyhow!("app state not found; the database is not initialized"))?;
+
+        return Ok(state.state_params().clone());
+    }
+
+    let state_hist =
+        KVCollection::<AppStore, BlockHeight, FvmStateParams>::new(state_hist_ns.to_string());
+
+    state_hist
+        .get(&tx, &height)
+        .context("failed to read state history")?
+        .ok_or_else(|| anyhow!("state at height {height} not found; it may have been pruned"))
+}
+
+/// Read-only [`Blockstore`] fetching data from a node through IPLD queries.
+///
+/// It blocks the current thread while waiting for the node, so it must be used
+/// within a multi-threaded Tokio runtime.
+pub struct RemoteBlockstore<C> {
+    client: C,
+    height: FvmQueryHeight,
+    runtime: tokio::runtime::Handle,
+}
+
+impl<C> RemoteBlockstore<C> {
+    pub fn new(client: C, height: FvmQueryHeight) -> Self {
+        Self {
+            client,
+            height,
+            runtime: tokio::runtime::Handle::current(),
+        }
+    }
+}
+
+impl<C: QueryClient> Blockstore for RemoteBlockstore<C> {
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        tokio::task::block_in_place(|| self.runtime.block_on(self.client.ipld(k, self.height)))
+    }
+
+    fn put_keyed(&self, _k: &Cid, _block: &[u8]) -> anyhow::Result<()> {
+        Err(anyhow!("the remote blockstore is read-only"))
+    }
+}
+
+fn load_actors<BS: Blockstore>(
+    store: &BS,
+    state_root: &Cid,
+) -> anyhow::Result<BTreeMap<ActorID, ActorState>> {
+    let state_tree = StateTree::new_from_root(store, state_root)?;
+    let mut actors = BTreeMap::new();
+
+    state_tree.for_each(|addr, actor| {
+        actors.insert(addr.id()?, actor.clone());
+        Ok(())
+    })?;
+
+    Ok(actors)
+}
+
+/// Load the state of an actor as an EVM contract, or return `None` if it is something else.
+fn load_evm_state<BS: Blockstore>(
+    store: &BS,
+    actor: &ActorState,
+) -> anyhow::Result<Option<evm::State>> {
+    let bz = match store.get(&actor.state)? {
+        Some(bz) => bz,
+        None => return Ok(None),
+    };
+    // We don't know the code CIDs of the EVM actor without the manifest; try to decode instead.
+    Ok(fvm_ipld_encoding::from_slice::<evm::State>(&bz).ok())
+}
+
+fn load_storage<BS: Blockstore>(
+    store: &BS,
+    state: &evm::State,
+) -> anyhow::Result<BTreeMap<Slot, Slot>> {
+    let kamt = state.storage(store)?;
+    let mut slots = BTreeMap::new();
+
+    kamt.for_each(|k, v| {
+        slots.insert(to_slot(k), to_slot(v));
+        Ok(())
+    })?;
+
+    Ok(slots)
+}
+
+fn diff_evm_storage<BS1, BS2>(
+    left_store: &BS1,
+    left_actor: &ActorState,
+    right_store: &BS2,
+    right_actor: &ActorState,
+) -> anyhow::Result<Vec<StorageChange>>
+where
+    BS1: Blockstore,
+    BS2: Blockstore,
+{
+    let (left_state, right_state) = match (
+        load_evm_state(left_store, left_actor)?,
+        load_evm_state(right_store, right_actor)?,
+    ) {
+        (Some(l), Some(r)) if l.contract_state != r.contract_state => (l, r),
+        _ => return Ok(Vec::new()),
+    };
+
+    let left = load_storage(left_store, &left_state)?;
+    let mut right = load_storage(right_store, &right_state)?;
+
+    let mut changes = Vec::new();
+
+    for (k, left_value) in left {
+        let right_value = right.remove(&k);
+        if right_value != Some(left_value) {
+            changes.push(StorageChange {
+                key: to_hex(&k),
+                left: Some(to_hex(&left_value)),
+                right: right_value.as_ref().map(to_hex),
+            });
+        }
+    }
+
+    for (k, right_value) in right {
+        changes.push(StorageChange {
+            key: to_hex(&k),
+            left: None,
+            right: Some(to_hex(&right_value)),
+        });
+    }
+
+    changes.sort_by(|a, b| a.key.cmp(&b.key));
+
+    Ok(changes)
+}
+
+fn to_slot(value: &evm::uints::U256) -> Slot {
+    let mut slot = [0u8; 32];
+    value.to_big_endian(&mut slot);
+    slot
+}
+
+fn to_hex(slot: &Slot) -> String {
+    format!("0x{}", hex::encode(slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use cid::Cid;
+    use fendermint_vm_actor_interface::evm;
+    use fvm::state_tree::{ActorState, StateTree};
+    use fvm_ipld_blockstore::MemoryBlockstore;
+    use fvm_ipld_encoding::CborStore;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::state::StateTreeVersion;
+
+    use super::{diff_states, list_actors, to_hex, to_slot, ActorSummary};
+
+    fn contract(store: &MemoryBlockstore, slots: &[(u64, u64)]) -> ActorState {
+        let mut kamt = evm::StorageKamt::new_with_config(store, evm::storage_kamt_config());
+        for (k, v) in slots {
+            kamt.set((*k).into(), (*v).into()).unwrap();
+        }
+        let state = evm::State {
+            bytecode: Cid::default(),
+            bytecode_hash: vec![0; 32],
+            contract_state: kamt.flush().unwrap(),
+            nonce: 0,
+            tombstone: None,
+        };
+        let state = store
+            .put_cbor(&state, cid::multihash::Code::Blake2b256)
+            .unwrap();
+        ActorState::new(Cid::default(), state, TokenAmount::from_atto(1), 0, None)
+    }
+
+    fn account(balance: u64) -> ActorState {
+        ActorState::new(
+            Cid::default(),
+            Cid::default(),
+            TokenAmount::from_atto(balance),
+            0,
+            None,
+        )
+    }
+
+    fn state_root(store: &MemoryBlockstore, actors: Vec<(u64, ActorState)>) -> Cid {
+        let mut state_tree = StateTree::new(store, StateTreeVersion::V5).unwrap();
+        for (id, actor) in actors {
+            state_tree.set_actor(id, actor);
+        }
+        state_tree.flush().unwrap()
+    }
+
+    #[test]
+    fn diff_actors_and_storage() {
+        let store = MemoryBlockstore::new();
+
+        let left = state_root(
+            &store,
+            vec![
+                (100, account(1)),
+                (101, account(2)),
+                (102, contract(&store, &[(1, 10), (2, 20)])),
+            ],
+        );
+        let right = state_root(
+            &store,
+            vec![
+                (100, account(1)),
+                (102, contract(&store, &[(2, 21), (3, 30)])),
+                (103, account(3)),
+            ],
+        );
+
+        assert_eq!(list_actors(&store, &left).unwrap().len(), 3);
+
+        let diff = diff_states(&store, &left, &store, &right).unwrap();
+
+        let ids = |actors: &[ActorSummary]| actors.iter().map(|a| a.id).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.removed), vec![101]);
+        assert_eq!(ids(&diff.added), vec![103]);
+        assert_eq!(diff.changed.len(), 1);
+
+        let slot = |v: u64| to_hex(&to_slot(&v.into()));
+        let storage = diff.changed[0]
+            .storage
+            .iter()
+            .map(|c| (c.key.clone(), c.left.clone(), c.right.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            storage,
+            vec![
+                (slot(1), Some(slot(10)), None),
+                (slot(2), Some(slot(20)), Some(slot(21))),
+                (slot(3), None, Some(slot(30))),
+            ]
+        );
+
+        assert_eq!(
+            diff_states(&store, &left, &store, &left).unwrap(),
+            Default::default()
+        );
+    }
+
+    #[test]
+    fn diff_states_in_separate_stores() {
+        let left_store = MemoryBlockstore::new();
+        let right_store = MemoryBlockstore::new();
+
+        let left = state_root(
+            &left_store,
+            vec![
+                (100, account(1)),
+                (101, account(2)),
+                (102, contract(&left_store, &[(1, 10)])),
+                (104, account(4)),
+                (105, account(5)),
+                (106, contract(&left_store, &[(1, 10)])),
+            ],
+        );
+        let right = state_root(
+            &right_store,
+            vec![
+                (100, account(1)),
+                (101, account(7)),
+                // Same contract state, written to the other store.
+                (102, contract(&right_store, &[(1, 10)])),
+                (103, account(3)),
+                (104, contract(&right_store, &[(1, 1)])),
+                (106, contract(&right_store, &[(1, 11)])),
+            ],
+        );
+
+        let diff = diff_states(&left_store, &left, &right_store, &right).unwrap();
+
+        let ids = |actors: &[ActorSummary]| actors.iter().map(|a| a.id).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.added), vec![103]);
+        assert_eq!(ids(&diff.removed), vec![105]);
+        assert_eq!(
+            diff.changed.iter().map(|c| c.left.id).collect::<Vec<_>>(),
+            vec![101, 104, 106]
+        );
+
+        // A balance change only.
+        let change = &diff.changed[0];
+        assert_eq!(change.left.balance, TokenAmount::from_atto(2));
+        assert_eq!(change.right.balance, TokenAmount::from_atto(7));
+        assert_eq!(change.left.state, change.right.state);
+        assert!(change.storage.is_empty());
+
+        // An account replaced by a contract has no storage to compare with.
+        let change = &diff.changed[1];
+        assert_ne!(change.left.state, change.right.state);
+        assert!(change.storage.is_empty());
+
+        // The storage of each version is read from its own store.
+        let slot = |v: u64| to_hex(&to_slot(&v.into()));
+        let change = &diff.changed[2];
+        assert_eq!(change.storage.len(), 1);
+        assert_eq!(change.storage[0].key, slot(1));
+        assert_eq!(change.storage[0].left, Some(slot(10)));
+        assert_eq!(change.storage[0].right, Some(slot(11)));
+
+        // The other way around everything is reversed.
+        let diff = diff_states(&right_store, &right, &left_store, &left).unwrap();
+        assert_eq!(ids(&diff.added), vec![105]);
+        assert_eq!(ids(&diff.removed), vec![103]);
+        assert_eq!(diff.changed.len(), 3);
+        assert_eq!(diff.changed[2].storage[0].left, Some(slot(11)));
+    }
+}
@@ -4,13 +4,16 @@
 
 use crate::app::{AppState, AppStoreKey};
 use crate::{App, BlockHeight};
-use fendermint_storage::{Codec, Encode, KVReadable, KVStore, KVWritable};
+use anyhow::Context;
+use fendermint_storage::{Codec, Encode, KVCollection, KVReadable, KVStore, KVWritable};
 use fendermint_vm_genesis::{Power, Validator};
 use fendermint_vm_interpreter::fvm::state::ipc::GatewayCaller;
 use fendermint_vm_interpreter::fvm::state::{FvmExecState, FvmStateParams};
 use fendermint_vm_interpreter::fvm::store::ReadOnlyBlockstore;
+use fendermint_vm_topdown::certificate::QuorumCertificate;
+use fendermint_vm_topdown::store::{ParentViewEntry, ParentViewStore};
 use fendermint_vm_topdown::sync::ParentFinalityStateQuery;
-use fendermint_vm_topdown::IPCParentFinality;
+use fendermint_vm_topdown::{IPCParentFinality, ParentViewPayload};
 use fvm_ipld_blockstore::Blockstore;
 use std::sync::Arc;
 
@@ -40,7 +43,8 @@ where
         + Codec<AppState>
         + Encode<AppStoreKey>
         + Encode<BlockHeight>
-        + Codec<FvmStateParams>,
+        + Codec<FvmStateParams>
+        + Codec<QuorumCertificate>,
     DB: KVWritable<S> + KVReadable<S> + 'static + Clone,
     SS: Blockstore + 'static + Clone,
 {
@@ -68,7 +72,8 @@ where
         + Codec<AppState>
         + Encode<AppStoreKey>
         + Encode<BlockHeight>
-        + Codec<FvmStateParams>,
+        + Codec<FvmStateParams>
+        + Codec<QuorumCertificate>,
     DB: KVWritable<S> + KVReadable<S> + 'static + Clone,
     SS: Blockstore + 'static + Clone,
 {
@@ -87,3 +92,156 @@ where
         })
     }
 }
+
+/// Keeps the parent view cache of the top-down syncer in the application database,
+/// indexed by parent block height.
+///
+/// Like the finality certificates, this is not part of the ledger.
+pub struct AppParentViewStore<DB, S>
+where
+    S: KVStore,
+{
+    db: DB,
+    parent_view: KVCollection<S, BlockHeight, Option<ParentViewPayload>>,
+}
+
+impl<DB, S> AppParentViewStore<DB, S>
+where
+    S: KVStore + Encode<BlockHeight> + Codec<Option<ParentViewPayload>>,
+{
+    pub fn new(db: DB, ns: S::Namespace) -> Self {
+        Self {
+            db,
+            parent_view: KVCollection::new(ns),
+        }
+    }
+}
+
+impl<DB, S> AppParentViewStore<DB, S>
+where
+    S: KVStore + Codec<BlockHeight> + Codec<Option<ParentViewPayload>>,
+    S::Repr: Ord + 'static,
+    DB: KVWritable<S> + KVReadable<S>,
+{
+    /// Delete the entries for which the predicate holds.
+    fn delete_where<F>(&self, f: F) -> anyhow::Result<()>
+    where
+        F: Fn(BlockHeight) -> bool,
+    {
+        self.db
+            .with_write(|tx| {
+                let heights = self
+                    .parent_view
+                    .iterate(&*tx)
+                    .map(|r| r.map(|(h, _)| h))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for h in heights.into_iter().filter(|h| f(*h)) {
+                    self.parent_view.delete(tx, &h)?;
+                }
+                Ok(())
+            })
+            .context("failed to delete parent view")
+    }
+}
+
+impl<DB, S> ParentViewStore for AppParentViewStore<DB, S>
+where
+    S: KVStore + Codec<BlockHeight> + Codec<Option<ParentViewPayload>> + Send + Sync,
+    S::Repr: Ord + 'static,
+    S::Namespace: Send + Sync,
+    DB: KVWritable<S> + KVReadable<S> + Send + Sync,
+{
+    fn put(&self, entry: &ParentViewEntry) -> anyhow::Result<()> {
+        self.db
+            .with_write(|tx| self.parent_view.put(tx, &entry.height, &entry.payload))
+            .context("failed to store parent view")
+    }
+
+    /// Iterate the keys from the height on, relying on the binary representation of the heights
+    /// being in the same order as the numbers, which holds for the CBOR encoding of integers.
+    fn load_from(&self, height: BlockHeight) -> anyhow::Result<Vec<ParentViewEntry>> {
+        let tx = self.db.read();
+        let entries = self
+            .parent_view
+            .iterate_from(&tx, &height)
+            .map(|r| r.map(|(height, payload)| ParentViewEntry { height, payload }))
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to load parent view")?;
+
+        Ok(entries)
+    }
+
+    fn prune_below(&self, height: BlockHeight) -> anyhow::Result<()> {
+        self.delete_where(|h| h < height)
+    }
+
+    fn clear(&self) -> anyhow::Result<()> {
+        self.delete_where(|_| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fendermint_rocksdb::{RocksDb, RocksDbConfig};
+    use fendermint_vm_topdown::store::{ParentViewEntry, ParentViewStore};
+
+    use super::AppParentViewStore;
+    use crate::store::AppStore;
+    use crate::BlockHeight;
+
+    fn entry(height: BlockHeight) -> ParentViewEntry {
+        ParentViewEntry {
+            height,
+            // Every third block is a null round.
+            payload: (height % 3 != 0).then(|| (height.to_be_bytes().to_vec(), vec![], vec![])),
+        }
+    }
+
+    fn heights(entries: &[ParentViewEntry]) -> Vec<BlockHeight> {
+        entries.iter().map(|e| e.height).collect()
+    }
+
+    #[test]
+    fn parent_view_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rocksdb");
+        let ns = "parent_view".to_string();
+
+        let open = || {
+            let db = RocksDb::open_cf(path.clone(), &RocksDbConfig::default(), [&ns].iter())
+                .expect("error opening RocksDB");
+            AppParentViewStore::<_, AppStore>::new(db, ns.clone())
+        };
+
+        // Heights where the binary representation of the number gets longer.
+        let all = (20..30).chain(250..260).collect::<Vec<_>>();
+
+        let store = open();
+        for h in all.iter() {
+            store.put(&entry(*h)).unwrap();
+        }
+        store.prune_below(25).unwrap();
+        drop(store);
+
+        let store = open();
+        let loaded = store.load_from(254).unwrap();
+        assert_eq!(heights(&loaded), (254..260).collect::<Vec<_>>());
+        for e in loaded {
+            let block_hash = |e: ParentViewEntry| e.payload.map(|p| p.0);
+            assert_eq!(block_hash(entry(e.height)), block_hash(e));
+        }
+
+        let loaded = store.load_from(0).unwrap();
+        assert_eq!(
+            heights(&loaded),
+            all.iter()
+                .filter(|h| **h >= 25)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        store.clear().unwrap();
+        assert!(store.load_from(0).unwrap().is_empty());
+    }
+}
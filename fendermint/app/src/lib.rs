@@ -2,12 +2,20 @@ extern crate core;
 
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
+mod admin;
 mod app;
+pub mod backup;
+pub mod db;
+pub mod db_admin;
+pub mod inspect;
 pub mod ipc;
 pub mod metrics;
 pub mod observe;
+pub mod replay;
+pub mod resolver_admin;
 mod store;
 mod tmconv;
+pub mod vote_ext;
 
 pub use app::{App, AppConfig};
 pub use store::{AppStore, BitswapBlockstore};
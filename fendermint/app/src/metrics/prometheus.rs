@@ -5,10 +5,11 @@
 /// Metrics emitted by the Ethereum API facade.
 pub mod eth {
     // TODO - migrate these metrics to new observability architecture
-    use fendermint_eth_api::apis::RPC_METHOD_CALL_LATENCY_SECONDS;
+    use fendermint_eth_api::apis::{RPC_LIMIT_REJECTIONS, RPC_METHOD_CALL_LATENCY_SECONDS};
 
     pub fn register_metrics(registry: &prometheus::Registry) -> anyhow::Result<()> {
         registry.register(Box::new(RPC_METHOD_CALL_LATENCY_SECONDS.clone()))?;
+        registry.register(Box::new(RPC_LIMIT_REJECTIONS.clone()))?;
         Ok(())
     }
 }
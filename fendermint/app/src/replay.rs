@@ -0,0 +1,248 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! Re-execute committed blocks on top of the local state history, to find the
+//! message which makes the application hash diverge from what was committed.
+
+use anyhow::{anyhow, Context};
+use fendermint_vm_interpreter::bytes::BytesMessageApplyRes;
+use fendermint_vm_interpreter::chain::{ChainEnv, ChainMessageApplyRet};
+use fendermint_vm_interpreter::fvm::state::{FvmExecState, FvmStateParams, FvmUpdatableParams};
+use fendermint_vm_interpreter::fvm::store::overlay::OverlayBlockstore;
+use fendermint_vm_interpreter::fvm::{FvmApplyRet, PowerUpdates};
+use fendermint_vm_interpreter::ExecInterpreter;
+use fvm::engine::MultiEngine;
+use fvm::executor::ApplyRet;
+use fvm::trace::ExecutionEvent;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_shared::clock::ChainEpoch;
+use serde::Serialize;
+use tendermint::abci::response;
+use tendermint::abci::types::ExecTxResult;
+
+use crate::tmconv::{to_app_hash, to_deliver_tx_result, to_timestamp};
+
+/// A block the way CometBFT delivers it to the application.
+pub struct ReplayBlock {
+    pub height: tendermint::block::Height,
+    pub hash: tendermint::Hash,
+    pub time: tendermint::Time,
+    pub proposer_address: tendermint::account::Id,
+    pub txs: Vec<Vec<u8>>,
+}
+
+/// Total gas charged under the same name during the execution of a message.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct GasTraceEntry {
+    pub name: String,
+    pub count: u64,
+    pub gas: u64,
+}
+
+pub struct ReplayedTx {
+    pub response: response::DeliverTx,
+    /// Gas charges in the order they first appeared; empty if the message didn't reach the FVM.
+    pub gas_trace: Vec<GasTraceEntry>,
+}
+
+pub struct ReplayedBlock {
+    /// The state parameters after the block.
+    pub state_params: FvmStateParams,
+    pub txs: Vec<ReplayedTx>,
+}
+
+impl ReplayedBlock {
+    pub fn app_hash(&self) -> tendermint::hash::AppHash {
+        to_app_hash(&self.state_params)
+    }
+}
+
+/// Execute blocks with the same interpreter stack the application uses, keeping the resulting
+/// state in memory, so the state store isn't affected by the outcome.
+pub struct Replayer<I, SS> {
+    interpreter: I,
+    chain_env: ChainEnv,
+    state_store: OverlayBlockstore<SS>,
+    multi_engine: MultiEngine,
+}
+
+impl<I, SS> Replayer<I, SS>
+where
+    SS: Blockstore + Clone + Send + Sync + 'static,
+    I: ExecInterpreter<
+        State = (ChainEnv, FvmExecState<OverlayBlockstore<SS>>),
+        Message = Vec<u8>,
+        BeginOutput = FvmApplyRet,
+        DeliverOutput = BytesMessageApplyRes,
+        EndOutput = PowerUpdates,
+    >,
+{
+    pub fn new(interpreter: I, chain_env: ChainEnv, state_store: SS) -> Self {
+        Self {
+            interpreter,
+            chain_env,
+            state_store: OverlayBlockstore::new(state_store),
+            multi_engine: MultiEngine::new(1),
+        }
+    }
+
+    /// Execute a block on top of the state it was originally executed on,
+    /// going through the same begin, deliver and end steps as `App`.
+    pub async fn replay_block(
+        &self,
+        mut state_params: FvmStateParams,
+        block: &ReplayBlock,
+    ) -> anyhow::Result<ReplayedBlock> {
+        let block_hash = match block.hash {
+            tendermint::Hash::Sha256(h) => h,
+            tendermint::Hash::None => return Err(anyhow!("empty block hash")),
+        };
+
+        state_params.timestamp = to_timestamp(block.time);
+
+        let state = FvmExecState::new_traced(
+            self.state_store.clone(),
+            &self.multi_engine,
+            block.height.value() as ChainEpoch,
+            state_params.clone(),
+        )
+        .context("error creating new state")?
+        .with_block_hash(block_hash)
+        .with_validator_id(block.proposer_address);
+
+        let (mut state, _) = self
+            .interpreter
+            .begin((self.chain_env.clone(), state))
+            .await
+            .context("begin failed")?;
+
+        let mut txs = Vec::with_capacity(block.txs.len());
+
+        for tx in block.txs.iter() {
+            let ((env, exec_state), res) = self
+                .interpreter
+                .deliver(state, tx.clone())
+                .await
+                .context("deliver failed")?;
+
+            let gas_trace = apply_ret(&res).map(gas_trace).unwrap_or_default();
+            let response = to_deliver_tx_result(res, exec_state.block_hash());
+
+            txs.push(ReplayedTx {
+                response,
+                gas_trace,
+            });
+
+            state = (env, exec_state);
+        }
+
+        let ((_, exec_state), _) = self.interpreter.end(state).await.context("end failed")?;
+
+        let (
+            state_root,
+            FvmUpdatableParams {
+                app_version,
+                base_fee,
+                circ_supply,
+                power_scale,
+            },
+            _,
+        ) = exec_state.commit().context("failed to commit FVM")?;
+
+        state_params.state_root = state_root;
+        state_params.app_version = app_version;
+        state_params.base_fee = base_fee;
+        state_params.circ_supply = circ_supply;
+        state_params.power_scale = power_scale;
+
+        Ok(ReplayedBlock { state_params, txs })
+    }
+}
+
+/// Describe how a replayed transaction differs from the result CometBFT recorded, if at all.
+pub fn compare_tx(committed: &ExecTxResult, replayed: &response::DeliverTx) -> Option<String> {
+    if committed.code != replayed.code {
+        return Some(format!(
+            "exit code {} instead of {}",
+            replayed.code.value(),
+            committed.code.value()
+        ));
+    }
+    if committed.gas_used != replayed.gas_used {
+        return Some(format!(
+            "used {} gas instead of {}",
+            replayed.gas_used, committed.gas_used
+        ));
+    }
+    if committed.data != replayed.data {
+        return Some("different return data".to_string());
+    }
+    None
+}
+
+fn apply_ret(res: &BytesMessageApplyRes) -> Option<&ApplyRet> {
+    match res {
+        Ok(ChainMessageApplyRet::Signed(Ok(ret))) => Some(&ret.fvm.apply_ret),
+        Ok(ChainMessageApplyRet::Ipc(ret)) => Some(&ret.apply_ret),
+        _ => None,
+    }
+}
+
+/// Sum up the gas charges in the execution trace by name.
+fn gas_trace(ret: &ApplyRet) -> Vec<GasTraceEntry> {
+    let mut entries: Vec<GasTraceEntry> = Vec::new();
+
+    for event in ret.exec_trace.iter() {
+        if let ExecutionEvent::GasCharge(charge) = event {
+            let gas = charge.total().round_up();
+            match entries.iter_mut().find(|e| e.name == charge.name) {
+                Some(entry) => {
+                    entry.count += 1;
+                    entry.gas += gas;
+                }
+                None => entries.push(GasTraceEntry {
+                    name: charge.name.to_string(),
+                    count: 1,
+                    gas,
+                }),
+            }
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint::abci::response;
+    use tendermint::abci::types::ExecTxResult;
+
+    use super::compare_tx;
+
+    #[test]
+    fn compare_tx_results() {
+        let committed = ExecTxResult {
+            gas_used: 100,
+            data: vec![1, 2, 3].into(),
+            ..Default::default()
+        };
+
+        let replayed = |gas_used, data: Vec<u8>| response::DeliverTx {
+            gas_used,
+            data: data.into(),
+            ..Default::default()
+        };
+
+        assert_eq!(compare_tx(&committed, &replayed(100, vec![1, 2, 3])), None);
+        assert!(compare_tx(&committed, &replayed(101, vec![1, 2, 3])).is_some());
+        assert!(compare_tx(&committed, &replayed(100, vec![1, 2])).is_some());
+
+        let failed = response::DeliverTx {
+            code: 33.into(),
+            ..replayed(100, vec![1, 2, 3])
+        };
+        assert_eq!(
+            compare_tx(&committed, &failed),
+            Some("exit code 33 instead of 0".to_string())
+        );
+    }
+}
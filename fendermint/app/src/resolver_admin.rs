@@ -0,0 +1,104 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! Read-only HTTP endpoint to look into the state of the IPLD Resolver service,
+//! meant to be bound to a local address and queried with `fendermint debug resolver`.
+
+use std::net::ToSocketAddrs;
+use std::str::FromStr;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Json;
+use ipc_api::subnet_id::SubnetID;
+use ipc_ipld_resolver::{Client, PeerInfo, ServiceStats};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::admin::{internal_error, serve, JsonResult};
+use crate::ipc::AppVote;
+
+type ResolverClient = Client<AppVote>;
+
+#[derive(Deserialize)]
+struct ProvidersParams {
+    subnet_id: String,
+}
+
+/// Serve the admin endpoint until the server fails.
+pub async fn listen<A: ToSocketAddrs>(
+    listen_addr: A,
+    client: ResolverClient,
+) -> anyhow::Result<()> {
+    let router = axum::Router::new()
+        .route("/peers", get(list_peers))
+        .route("/providers", get(list_providers))
+        .route("/stats", get(stats))
+        .with_state(client);
+
+    serve(listen_addr, router, "IPLD Resolver").await
+}
+
+async fn list_peers(State(client): State<ResolverClient>) -> JsonResult {
+    let peers = client.list_peers().await.map_err(internal_error)?;
+    let peers = peers.iter().map(peer_to_json).collect::<Vec<_>>();
+    Ok(Json(json!(peers)))
+}
+
+async fn list_providers(
+    State(client): State<ResolverClient>,
+    Query(params): Query<ProvidersParams>,
+) -> JsonResult {
+    let subnet_id = SubnetID::from_str(&params.subnet_id)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid subnet ID: {e}")))?;
+
+    let providers = client
+        .list_providers(subnet_id)
+        .await
+        .map_err(internal_error)?;
+
+    let providers = providers
+        .iter()
+        .map(|peer_id| peer_id.to_string())
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!(providers)))
+}
+
+async fn stats(State(client): State<ResolverClient>) -> JsonResult {
+    let stats = client.stats().await.map_err(internal_error)?;
+    Ok(Json(stats_to_json(&stats)))
+}
+
+fn peer_to_json(peer: &PeerInfo) -> Value {
+    json!({
+        "peer_id": peer.peer_id.to_string(),
+        "address": peer.address.as_ref().map(|a| a.to_string()),
+        "routable": peer.routable,
+        "subnets": to_strings(&peer.subnets),
+        "rate_limit_used": peer.rate_limit_used,
+    })
+}
+
+fn stats_to_json(stats: &ServiceStats) -> Value {
+    json!({
+        "peer_id": stats.peer_id.to_string(),
+        "listen_addrs": to_strings(&stats.listen_addrs),
+        "external_addrs": to_strings(&stats.external_addrs),
+        "connected_peers": stats.connected_peers,
+        "routable_peers": stats.routable_peers,
+        "known_subnets": stats.known_subnets,
+        "provided_subnets": to_strings(&stats.provided_subnets),
+        "pinned_subnets": to_strings(&stats.pinned_subnets),
+        "running_queries": stats.running_queries,
+        "rate_limit": {
+            "bytes": stats.rate_limit_bytes,
+            "period_secs": stats.rate_limit_period.as_secs(),
+            "used": stats.rate_limit_used,
+        },
+    })
+}
+
+fn to_strings<T: ToString>(items: &[T]) -> Vec<String> {
+    items.iter().map(|i| i.to_string()).collect()
+}
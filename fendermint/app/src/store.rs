@@ -5,11 +5,12 @@ use ipc_ipld_resolver::missing_blocks::missing_blocks;
 use libp2p_bitswap::BitswapStore;
 use std::borrow::Cow;
 
-use fendermint_rocksdb::blockstore::NamespaceBlockstore;
 use fendermint_storage::{Codec, Decode, Encode, KVError, KVResult, KVStore};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::{de::DeserializeOwned, serde::Serialize};
 
+use crate::db::AppBlockstore;
+
 /// [`KVStore`] type we use to store historial data in the database.
 #[derive(Clone)]
 pub struct AppStore;
@@ -50,13 +51,13 @@ pub struct BitswapBlockstore {
     /// This must not be written to by Bitswap operations, because that could result
     /// in some nodes having some data that others don't, which would lead to a
     /// consensu failure. We can use read data from it, but not write to it.
-    state_store: NamespaceBlockstore,
+    state_store: AppBlockstore,
     /// The `Blockstore` implementation where Bitswap operations can write to.
-    bit_store: NamespaceBlockstore,
+    bit_store: AppBlockstore,
 }
 
 impl BitswapBlockstore {
-    pub fn new(state_store: NamespaceBlockstore, bit_store: NamespaceBlockstore) -> Self {
+    pub fn new(state_store: AppBlockstore, bit_store: AppBlockstore) -> Self {
         Self {
             state_store,
             bit_store,
@@ -4,16 +4,22 @@
 use anyhow::{anyhow, bail, Context};
 use fendermint_vm_core::Timestamp;
 use fendermint_vm_genesis::{Power, Validator};
+use fendermint_vm_interpreter::bytes::BytesMessageApplyRes;
+use fendermint_vm_interpreter::chain::ChainMessageApplyRet;
 use fendermint_vm_interpreter::fvm::{
     state::{BlockHash, FvmStateParams},
     FvmApplyRet, FvmCheckRet, FvmQueryRet, PowerUpdates,
 };
+use fendermint_vm_interpreter::signed::InvalidSignature;
 use fendermint_vm_message::signed::DomainHash;
-use fendermint_vm_snapshot::{SnapshotItem, SnapshotManifest};
+use fendermint_vm_snapshot::{SnapshotItem, SnapshotLink, SnapshotManifest};
+use fendermint_vm_topdown::certificate::QuorumCertificate;
+use fendermint_vm_topdown::store::ParentViewEntry;
 use fvm_shared::{address::Address, error::ExitCode, event::StampedEvent, ActorID};
 use prost::Message;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, num::NonZeroU32};
+use tendermint::abci::types::ExecTxResult;
 use tendermint::abci::{response, Code, Event, EventAttribute};
 
 use crate::{app::AppError, BlockHeight};
@@ -22,6 +28,9 @@ use crate::{app::AppError, BlockHeight};
 struct SnapshotMetadata {
     size: u64,
     state_params: FvmStateParams,
+    /// Chain of snapshots that a delta snapshot is streamed with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    chain: Vec<SnapshotLink>,
 }
 
 /// IPLD encoding of data types we know we must be able to encode.
@@ -160,6 +169,71 @@ pub fn to_end_block(power_table: PowerUpdates) -> anyhow::Result<response::EndBl
     })
 }
 
+/// Response to the delivery of checkpoint signatures collected from vote extensions.
+///
+/// Rejected signatures don't fail the message; they were added to the ledger by the proposer, not the signatory.
+pub fn to_checkpoint_signatures_deliver_tx(
+    accepted: usize,
+    rejected: usize,
+) -> response::DeliverTx {
+    response::DeliverTx {
+        info: format!("accepted {accepted}, rejected {rejected} checkpoint signatures"),
+        ..Default::default()
+    }
+}
+
+/// Response to the delivery of a transaction, whatever kind of message it turned out to be.
+pub fn to_deliver_tx_result(
+    result: BytesMessageApplyRes,
+    block_hash: Option<BlockHash>,
+) -> response::DeliverTx {
+    match result {
+        Err(e) => invalid_deliver_tx(AppError::InvalidEncoding, e.description),
+        Ok(ret) => match ret {
+            ChainMessageApplyRet::Signed(Err(InvalidSignature(d))) => {
+                invalid_deliver_tx(AppError::InvalidSignature, d)
+            }
+            ChainMessageApplyRet::Signed(Ok(ret)) => {
+                to_deliver_tx(ret.fvm, ret.domain_hash, block_hash)
+            }
+            ChainMessageApplyRet::Ipc(ret) => to_deliver_tx(ret, None, block_hash),
+            ChainMessageApplyRet::CheckpointSignatures { accepted, rejected } => {
+                to_checkpoint_signatures_deliver_tx(accepted, rejected)
+            }
+        },
+    }
+}
+
+/// Assemble the response to `FinalizeBlock` from the steps the block was executed in.
+pub fn to_finalize_block(
+    begin: response::BeginBlock,
+    txs: Vec<response::DeliverTx>,
+    end: response::EndBlock,
+    app_hash: tendermint::hash::AppHash,
+) -> response::FinalizeBlock {
+    let tx_results = txs
+        .into_iter()
+        .map(|tx| ExecTxResult {
+            code: tx.code,
+            data: tx.data,
+            log: tx.log,
+            info: tx.info,
+            gas_wanted: tx.gas_wanted,
+            gas_used: tx.gas_used,
+            events: tx.events,
+            codespace: tx.codespace,
+        })
+        .collect();
+
+    response::FinalizeBlock {
+        events: [begin.events, end.events].concat(),
+        tx_results,
+        validator_updates: end.validator_updates,
+        consensus_param_updates: end.consensus_param_updates,
+        app_hash,
+    }
+}
+
 /// Map the return values from cron operations.
 pub fn to_begin_block(ret: FvmApplyRet) -> response::BeginBlock {
     let events = to_events("event", ret.apply_ret.events, ret.emitters);
@@ -262,6 +336,7 @@ pub fn to_query(ret: FvmQueryRet, block_height: BlockHeight) -> anyhow::Result<r
         FvmQueryRet::Call(_) | FvmQueryRet::EstimateGas(_) => ExitCode::OK,
         FvmQueryRet::StateParams(_) => ExitCode::OK,
         FvmQueryRet::BuiltinActors(_) => ExitCode::OK,
+        FvmQueryRet::BlockHashes(_) => ExitCode::OK,
     };
 
     // The return value has a `key` field which is supposed to be set to the data matched.
@@ -282,7 +357,7 @@ pub fn to_query(ret: FvmQueryRet, block_height: BlockHeight) -> anyhow::Result<r
             // This is so there is a single representation of a call result, instead
             // of a normal delivery being one way and a query exposing `FvmApplyRet`.
             let dtx = to_deliver_tx(ret, None, None);
-            let dtx = tendermint_proto::abci::ResponseDeliverTx::from(dtx);
+            let dtx = tendermint_proto::v0_37::abci::ResponseDeliverTx::from(dtx);
             let mut buf = bytes::BytesMut::new();
             dtx.encode(&mut buf)?;
             let bz = buf.to_vec();
@@ -302,6 +377,10 @@ pub fn to_query(ret: FvmQueryRet, block_height: BlockHeight) -> anyhow::Result<r
             let v = ipld_encode!(ba);
             (Vec::new(), v)
         }
+        FvmQueryRet::BlockHashes(bh) => {
+            let v = ipld_encode!(bh);
+            (Vec::new(), v)
+        }
     };
 
     // The height here is the height of the block that was committed, not in which the app hash appeared.
@@ -319,6 +398,42 @@ pub fn to_query(ret: FvmQueryRet, block_height: BlockHeight) -> anyhow::Result<r
     Ok(res)
 }
 
+/// Response to a parent finality certificate query.
+pub fn to_certificate_query(
+    cert: Option<QuorumCertificate>,
+    block_height: BlockHeight,
+) -> anyhow::Result<response::Query> {
+    let (exit_code, value) = match cert {
+        None => (ExitCode::USR_NOT_FOUND, Vec::new()),
+        Some(cert) => (ExitCode::OK, ipld_encode!(cert)),
+    };
+
+    let height = tendermint::block::Height::try_from(block_height).context("height too big")?;
+
+    Ok(response::Query {
+        code: to_code(exit_code),
+        info: to_error_msg(exit_code).to_owned(),
+        value: value.into(),
+        height,
+        ..Default::default()
+    })
+}
+
+/// Response to a parent view query.
+pub fn to_parent_view_query(
+    entries: Vec<ParentViewEntry>,
+    block_height: BlockHeight,
+) -> anyhow::Result<response::Query> {
+    let value = ipld_encode!(entries);
+    let height = tendermint::block::Height::try_from(block_height).context("height too big")?;
+
+    Ok(response::Query {
+        value: value.into(),
+        height,
+        ..Default::default()
+    })
+}
+
 /// Project Genesis validators to Tendermint.
 pub fn to_validator_updates(
     validators: Vec<Validator<Power>>,
@@ -393,6 +508,7 @@ pub fn to_snapshot(snapshot: SnapshotItem) -> anyhow::Result<tendermint::abci::t
     let metadata = SnapshotMetadata {
         size: snapshot.manifest.size,
         state_params: snapshot.manifest.state_params,
+        chain: snapshot.manifest.chain,
     };
 
     Ok(tendermint::abci::types::Snapshot {
@@ -428,6 +544,15 @@ pub fn from_snapshot(
     let checksum = tendermint::hash::Hash::try_from(offer.snapshot.hash)
         .context("failed to parse checksum")?;
 
+    let chain_chunks: u64 = metadata.chain.iter().map(|link| link.chunks as u64).sum();
+    if !metadata.chain.is_empty() && chain_chunks >= offer.snapshot.chunks as u64 {
+        bail!(
+            "the snapshot chain has {} chunks out of a total of {}",
+            chain_chunks,
+            offer.snapshot.chunks
+        );
+    }
+
     let manifest = SnapshotManifest {
         block_height: offer.snapshot.height.value(),
         size: metadata.size,
@@ -435,6 +560,7 @@ pub fn from_snapshot(
         checksum,
         state_params: metadata.state_params,
         version: offer.snapshot.format,
+        chain: metadata.chain,
     };
 
     Ok(manifest)
@@ -0,0 +1,423 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! ABCI 2.0 vote extensions, carrying parent finality votes and checkpoint signatures
+//! along with the precommits, so that their quorum is available at the next height.
+
+use anyhow::{anyhow, bail, Context};
+use async_stm::{atomically, atomically_or_err};
+use fendermint_vm_interpreter::chain::ChainEnv;
+use fendermint_vm_message::ipc::CheckpointSignature;
+use fendermint_vm_topdown::voting::{Error as VoteError, ValidatorKey};
+use fendermint_vm_topdown::IPCParentFinality;
+use ipc_api::subnet_id::SubnetID;
+use ipc_ipld_resolver::{SignedVoteRecord, VoteRecord};
+use libp2p::identity::Keypair;
+use serde::{Deserialize, Serialize};
+
+use crate::ipc::AppVote;
+
+/// Data a validator attaches to its precommit vote.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppVoteExtension {
+    /// The signed envelope of a [SignedVoteRecord] about the latest parent block the validator considers final.
+    pub parent_finality: Option<Vec<u8>>,
+    /// Checkpoint signatures of the validator which aren't in the ledger yet.
+    pub checkpoint_signatures: Vec<CheckpointSignature>,
+}
+
+impl AppVoteExtension {
+    pub fn is_empty(&self) -> bool {
+        self.parent_finality.is_none() && self.checkpoint_signatures.is_empty()
+    }
+
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        fvm_ipld_encoding::to_vec(self).context("failed to encode vote extension")
+    }
+
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        fvm_ipld_encoding::from_slice(bytes).context("failed to decode vote extension")
+    }
+}
+
+/// Produces the vote extensions of this node and checks the ones of others.
+#[derive(Clone)]
+pub struct VoteExtensions {
+    /// The subnet the votes are valid in.
+    subnet_id: SubnetID,
+    /// Key to sign our own parent finality votes with, if this node is a validator.
+    key: Option<Keypair>,
+}
+
+impl VoteExtensions {
+    pub fn new(subnet_id: SubnetID, key: Option<Keypair>) -> Self {
+        Self { subnet_id, key }
+    }
+
+    /// Produce the vote extension from the latest parent block in the tally and the pending checkpoint signatures.
+    pub async fn extend(&self, env: &ChainEnv) -> anyhow::Result<AppVoteExtension> {
+        let key = match self.key {
+            Some(ref key) => key,
+            None => return Ok(AppVoteExtension::default()),
+        };
+
+        let parent_finality = if env.parent_finality_provider.is_enabled() {
+            let validator_key = ValidatorKey::from(key.public());
+
+            let latest = atomically(|| {
+                let height = env.parent_finality_votes.latest_height()?;
+                if height == 0 || !env.parent_finality_votes.has_power(&validator_key)? {
+                    return Ok(None);
+                }
+                let block_hash = env.parent_finality_votes.block_hash(height)?;
+                Ok(block_hash.map(|block_hash| IPCParentFinality { height, block_hash }))
+            })
+            .await;
+
+            match latest {
+                Some(finality) => {
+                    let vote = VoteRecord::signed(
+                        key,
+                        self.subnet_id.clone(),
+                        AppVote::ParentFinality(finality),
+                    )
+                    .context("failed to sign parent finality vote")?;
+
+                    let envelope = vote.envelope().clone().into_protobuf_encoding();
+                    self.add_vote(env, vote).await;
+
+                    Some(envelope)
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+
+        let checkpoint_signatures = atomically(|| env.checkpoint_signatures.pending()).await;
+
+        Ok(AppVoteExtension {
+            parent_finality,
+            checkpoint_signatures,
+        })
+    }
+
+    /// Check that a vote extension is well formed and that the parent finality vote and the
+    /// checkpoint signatures in it were signed by the validator who sent it at `height`.
+    ///
+    /// Verification has no side effects: the vote only goes into the tally once the extension
+    /// is committed, when the next proposer collects it. Whether the checkpoint signatures are
+    /// over the checkpoints in the ledger is checked by the gateway when they are delivered.
+    pub fn verify(
+        &self,
+        validator_address: &tendermint::account::Id,
+        height: u64,
+        bytes: &[u8],
+    ) -> anyhow::Result<AppVoteExtension> {
+        if bytes.is_empty() {
+            return Ok(AppVoteExtension::default());
+        }
+
+        let ext = AppVoteExtension::decode(bytes)?;
+
+        for signature in ext.checkpoint_signatures.iter() {
+            if signature.height < 0 || signature.height as u64 > height {
+                bail!(
+                    "checkpoint signature for height {} at height {height}",
+                    signature.height
+                );
+            }
+
+            let signer = signature
+                .recover_signer()
+                .context("invalid checkpoint signature")?;
+
+            if to_account_id(&ValidatorKey::from(signer))? != *validator_address {
+                bail!("checkpoint signature signed by a different validator");
+            }
+        }
+
+        if let Some(ref envelope) = ext.parent_finality {
+            let vote = SignedVoteRecord::<AppVote>::from_bytes(envelope)
+                .context("invalid parent finality vote")?;
+
+            let record = vote.record();
+
+            if record.subnet_id != self.subnet_id {
+                bail!("parent finality vote is for a different subnet");
+            }
+
+            if to_account_id(&record.public_key)? != *validator_address {
+                bail!("parent finality vote signed by a different validator");
+            }
+        }
+
+        Ok(ext)
+    }
+
+    /// Go through the vote extensions of the last commit as the proposer of the next block:
+    /// add the parent finality votes to the tally and return the checkpoint signatures to be proposed.
+    ///
+    /// CometBFT has already checked the signatures of the extensions, so malformed ones are only skipped.
+    pub async fn collect<'a>(
+        &self,
+        env: &ChainEnv,
+        exts: impl Iterator<Item = &'a [u8]>,
+    ) -> Vec<CheckpointSignature> {
+        let mut signatures = Vec::new();
+
+        for bytes in exts {
+            let ext = match AppVoteExtension::decode(bytes) {
+                Ok(ext) => ext,
+                Err(e) => {
+                    tracing::warn!(error = format!("{e:#}"), "skipping vote extension");
+                    continue;
+                }
+            };
+
+            if let Some(ref envelope) = ext.parent_finality {
+                match SignedVoteRecord::<AppVote>::from_bytes(envelope) {
+                    Ok(vote) if vote.record().subnet_id == self.subnet_id => {
+                        self.add_vote(env, vote).await
+                    }
+                    Ok(_) => tracing::warn!("skipping vote for a different subnet"),
+                    Err(e) => tracing::warn!(error = format!("{e:#}"), "skipping invalid vote"),
+                }
+            }
+
+            signatures.extend(ext.checkpoint_signatures);
+        }
+
+        signatures
+    }
+
+    /// Add a parent finality vote we extended with, or one coming from a committed vote extension, to the tally.
+    ///
+    /// Failures are only logged, they can happen if the vote is too early or too late.
+    async fn add_vote(&self, env: &ChainEnv, vote: SignedVoteRecord<AppVote>) {
+        if !env.parent_finality_provider.is_enabled() {
+            return;
+        }
+
+        let envelope = vote.envelope().clone().into_protobuf_encoding();
+        let vote = vote.into_record();
+
+        let AppVote::ParentFinality(f) = vote.content;
+
+        let res = atomically_or_err(|| {
+            env.parent_finality_votes.add_signed_vote(
+                vote.public_key.clone(),
+                f.height,
+                f.block_hash.clone(),
+                envelope.clone(),
+            )
+        })
+        .await;
+
+        match res {
+            Err(e @ VoteError::Equivocation(_, _, _, _)) => {
+                tracing::warn!(error = e.to_string(), "failed to handle extension vote");
+            }
+            Err(e) => {
+                tracing::debug!(error = e.to_string(), "failed to handle extension vote");
+            }
+            Ok(_) => {
+                tracing::debug!("extension vote handled");
+            }
+        }
+    }
+}
+
+/// The CometBFT address of a validator is derived from its public key.
+fn to_account_id(key: &ValidatorKey) -> anyhow::Result<tendermint::account::Id> {
+    let pk = libp2p::identity::PublicKey::from(key.clone())
+        .try_into_secp256k1()
+        .map_err(|_| anyhow!("validator key is not secp256k1"))?;
+
+    let pk = tendermint::PublicKey::from_raw_secp256k1(&pk.to_bytes())
+        .ok_or_else(|| anyhow!("invalid secp256k1 public key"))?;
+
+    Ok(tendermint::account::Id::from(pk))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use fendermint_crypto::SecretKey;
+    use fendermint_vm_interpreter::chain::{ChainEnv, CheckpointPool};
+    use fendermint_vm_interpreter::fvm::CheckpointSignaturePool;
+    use fendermint_vm_message::conv::from_fvm;
+    use fendermint_vm_message::ipc::CheckpointSignature;
+    use fendermint_vm_message::signed::sign_secp256k1;
+    use fendermint_vm_topdown::voting::{ValidatorKey, VoteTally};
+    use fendermint_vm_topdown::{IPCParentFinality, Toggle};
+    use ipc_api::subnet_id::SubnetID;
+    use ipc_ipld_resolver::VoteRecord;
+    use libp2p::identity::Keypair;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    use super::{to_account_id, AppVoteExtension, VoteExtensions};
+    use crate::ipc::AppVote;
+
+    fn secret_key(seed: u64) -> SecretKey {
+        SecretKey::random(&mut ChaCha20Rng::seed_from_u64(seed))
+    }
+
+    fn account_id(sk: &SecretKey) -> tendermint::account::Id {
+        to_account_id(&ValidatorKey::from(sk.public_key())).unwrap()
+    }
+
+    fn keypair(sk: &SecretKey) -> Keypair {
+        let mut bz = sk.serialize();
+        let sk = libp2p::identity::secp256k1::SecretKey::try_from_bytes(&mut bz).unwrap();
+        Keypair::from(libp2p::identity::secp256k1::Keypair::from(sk))
+    }
+
+    fn parent_finality_vote(sk: &SecretKey, subnet_id: &SubnetID) -> Vec<u8> {
+        let finality = IPCParentFinality {
+            height: 100,
+            block_hash: vec![1u8; 32],
+        };
+        VoteRecord::signed(
+            &keypair(sk),
+            subnet_id.clone(),
+            AppVote::ParentFinality(finality),
+        )
+        .unwrap()
+        .into_envelope()
+        .into_protobuf_encoding()
+    }
+
+    fn checkpoint_signature(sk: &SecretKey, height: i64) -> CheckpointSignature {
+        let hash = [height as u8; 32];
+        let signature = sign_secp256k1(sk, &hash);
+        let signature = from_fvm::to_eth_signature(&signature, false).unwrap();
+        CheckpointSignature {
+            height,
+            membership_proof: vec![[1u8; 32]],
+            weight: 10,
+            hash,
+            signature: signature.to_vec(),
+        }
+    }
+
+    fn extension(
+        parent_finality: Option<Vec<u8>>,
+        checkpoint_signatures: Vec<CheckpointSignature>,
+    ) -> Vec<u8> {
+        AppVoteExtension {
+            parent_finality,
+            checkpoint_signatures,
+        }
+        .encode()
+        .unwrap()
+    }
+
+    fn chain_env() -> ChainEnv {
+        ChainEnv {
+            checkpoint_pool: CheckpointPool::new(),
+            parent_finality_provider: Arc::new(Toggle::disabled()),
+            parent_finality_votes: VoteTally::empty(),
+            checkpoint_signatures: CheckpointSignaturePool::new(),
+            vote_extensions: true,
+            power_table: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn vote_extension_roundtrip() {
+        let ext = AppVoteExtension {
+            parent_finality: Some(vec![1, 2, 3]),
+            checkpoint_signatures: vec![CheckpointSignature {
+                height: 100,
+                membership_proof: vec![[1u8; 32], [2u8; 32]],
+                weight: 10,
+                hash: [3u8; 32],
+                signature: vec![4u8; 65],
+            }],
+        };
+
+        let bytes = ext.encode().unwrap();
+        let decoded = AppVoteExtension::decode(&bytes).unwrap();
+
+        assert_eq!(ext, decoded);
+        assert!(!decoded.is_empty());
+        assert!(AppVoteExtension::default().is_empty());
+    }
+
+    #[test]
+    fn verify_vote_extension() {
+        let subnet_id = SubnetID::new_root(123);
+        let exts = VoteExtensions::new(subnet_id.clone(), None);
+
+        let sk = secret_key(1);
+        let other = secret_key(2);
+        let addr = account_id(&sk);
+
+        let verify = |bytes: Vec<u8>| exts.verify(&addr, 10, &bytes);
+
+        let ext = extension(
+            Some(parent_finality_vote(&sk, &subnet_id)),
+            vec![checkpoint_signature(&sk, 5), checkpoint_signature(&sk, 10)],
+        );
+        assert!(verify(ext).is_ok(), "well formed extension");
+        assert!(verify(Vec::new()).is_ok(), "empty extension");
+
+        let ext = extension(Some(parent_finality_vote(&other, &subnet_id)), vec![]);
+        assert!(verify(ext).is_err(), "vote of a different validator");
+
+        let ext = extension(None, vec![checkpoint_signature(&other, 5)]);
+        assert!(
+            verify(ext).is_err(),
+            "checkpoint signature of a different validator"
+        );
+
+        let mut vote = parent_finality_vote(&sk, &subnet_id);
+        *vote.last_mut().unwrap() ^= 0xff;
+        let ext = extension(Some(vote), vec![]);
+        assert!(verify(ext).is_err(), "bad vote signature");
+
+        let mut signature = checkpoint_signature(&sk, 5);
+        signature.signature[0] ^= 0xff;
+        let ext = extension(None, vec![signature]);
+        assert!(verify(ext).is_err(), "bad checkpoint signature");
+
+        let ext = extension(None, vec![checkpoint_signature(&sk, 11)]);
+        assert!(
+            verify(ext).is_err(),
+            "checkpoint signature from a future height"
+        );
+
+        let ext = extension(
+            Some(parent_finality_vote(&sk, &SubnetID::new_root(456))),
+            vec![],
+        );
+        assert!(verify(ext).is_err(), "vote for a different subnet");
+
+        assert!(verify(vec![1, 2, 3]).is_err(), "malformed extension");
+    }
+
+    #[tokio::test]
+    async fn collect_vote_extensions() {
+        let subnet_id = SubnetID::new_root(123);
+        let exts = VoteExtensions::new(subnet_id.clone(), None);
+        let env = chain_env();
+
+        let (sk1, sk2) = (secret_key(1), secret_key(2));
+        let sig1 = checkpoint_signature(&sk1, 5);
+        let sig2 = checkpoint_signature(&sk2, 5);
+
+        let bytes = vec![
+            extension(
+                Some(parent_finality_vote(&sk1, &subnet_id)),
+                vec![sig1.clone()],
+            ),
+            vec![1, 2, 3],
+            extension(None, vec![sig2.clone()]),
+        ];
+
+        let signatures = exts.collect(&env, bytes.iter().map(|b| b.as_slice())).await;
+
+        assert_eq!(signatures, vec![sig1, sig2]);
+    }
+}
@@ -24,7 +24,6 @@ use futures::FutureExt;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
-use fvm_shared::crypto::signature::Signature;
 use fvm_shared::{chainid::ChainID, error::ExitCode};
 use jsonrpc_v2::Params;
 use rand::Rng;
@@ -39,9 +38,10 @@ use tendermint_rpc::{
 use fil_actors_evm_shared::uints;
 
 use crate::conv::from_eth::{self, to_fvm_message};
-use crate::conv::from_tm::{self, msg_hash, to_chain_message, to_cumulative, to_eth_block_zero};
+use crate::conv::from_tm::{self, msg_hash, to_cumulative, to_eth_block_zero, to_signed_message};
 use crate::error::{error_with_revert, OutOfSequence};
 use crate::filters::{matches_topics, FilterId, FilterKind, FilterRecords};
+use crate::limiter::limit_exceeded;
 use crate::{
     conv::{
         from_eth::to_fvm_address,
@@ -140,10 +140,7 @@ where
             let txs_results = block_results.txs_results.unwrap_or_default();
 
             for (tx, txres) in block.data().iter().zip(txs_results) {
-                let msg = fvm_ipld_encoding::from_slice::<ChainMessage>(tx)
-                    .context("failed to decode tx as ChainMessage")?;
-
-                if let ChainMessage::Signed(msg) = msg {
+                if let Some(msg) = to_signed_message(tx)? {
                     let premium = crate::gas::effective_gas_premium(&msg.message, base_fee);
                     premiums.push((premium, txres.gas_used));
                 }
@@ -248,10 +245,7 @@ where
 
             let mut premiums = Vec::new();
             for (tx, txres) in block.data().iter().zip(txs_results) {
-                let msg = fvm_ipld_encoding::from_slice::<ChainMessage>(tx)
-                    .context("failed to decode tx as ChainMessage")?;
-
-                if let ChainMessage::Signed(msg) = msg {
+                if let Some(msg) = to_signed_message(tx)? {
                     let premium = crate::gas::effective_gas_premium(&msg.message, &base_fee);
                     premiums.push((premium, txres.gas_used));
                 }
@@ -430,9 +424,7 @@ where
     if let Some(tx) = data.tx_cache.get(&tx_hash) {
         Ok(Some(tx))
     } else if let Some(res) = data.tx_by_hash(tx_hash).await? {
-        let msg = to_chain_message(&res.tx)?;
-
-        if let ChainMessage::Signed(msg) = msg {
+        if let Some(msg) = to_signed_message(&res.tx)? {
             let header: header::Response = data.tm().header(res.height).await?;
             let sp = data
                 .client
@@ -502,8 +494,7 @@ where
         .client
         .state_params(FvmQueryHeight::Height(header.header.height.value()))
         .await?;
-    let msg = to_chain_message(&tx_res.tx)?;
-    if let ChainMessage::Signed(msg) = msg {
+    if let Some(msg) = to_signed_message(&tx_res.tx)? {
         let receipt = to_eth_receipt(
             &msg,
             &tx_res,
@@ -547,8 +538,7 @@ where
         .zip(block_results.txs_results.unwrap_or_default())
         .enumerate()
     {
-        let msg = to_chain_message(&tx)?;
-        if let ChainMessage::Signed(msg) = msg {
+        if let Some(msg) = to_signed_message(&tx)? {
             let result = endpoint::tx::Response {
                 hash: Default::default(), // Shouldn't use this anyway.
                 height,
@@ -628,18 +618,21 @@ where
     let msghash = et::TxHash::from(ethers_core::utils::keccak256(rlp.as_raw()));
     tracing::debug!(?sighash, eth_hash = ?msghash, ?tx, "received raw transaction");
 
-    if let Some(tx) = tx.as_eip1559_ref() {
-        let tx = from_eth::to_eth_transaction(tx.clone(), sig, msghash);
-        data.tx_cache.insert(msghash, tx);
-    }
-
-    let msg = to_fvm_message(tx, false)?;
+    let msg = to_fvm_message(tx.clone())?;
     let sender = msg.from;
     let nonce = msg.sequence;
 
+    let signature = match from_eth::to_fvm_signature(&tx, &sig) {
+        Ok(signature) => signature,
+        Err(e) => return error(ExitCode::USR_ILLEGAL_ARGUMENT, format!("{e:#}")),
+    };
+
+    data.tx_cache
+        .insert(msghash, from_eth::to_eth_transaction(tx, sig, msghash));
+
     let msg = SignedMessage {
         message: msg,
-        signature: Signature::new_secp256k1(sig.to_vec()),
+        signature,
     };
     let msg = ChainMessage::Signed(msg);
     let bz: Vec<u8> = SignedMessageFactory::serialize(&msg)?;
@@ -688,8 +681,16 @@ pub async fn call<C>(
 where
     C: Client + Sync + Send,
 {
-    let msg = to_fvm_message(tx.into(), true)?;
+    let mut msg = to_fvm_message(tx.into())?;
     let is_create = msg.to == EAM_ACTOR_ADDR;
+
+    // Zero would mean the block gas limit.
+    if let Some(max) = data.method_limits.max_call_gas {
+        if msg.gas_limit == 0 || msg.gas_limit > max {
+            msg.gas_limit = max;
+        }
+    }
+
     let height = data.query_height(block_id).await?;
     let response = data.client.call(msg, height).await?;
     let deliver_tx = response.value;
@@ -737,7 +738,14 @@ where
         EstimateGasParams::Two((tx, block_id)) => (tx, block_id),
     };
 
-    let msg = to_fvm_message(tx.into(), true).context("failed to convert to FVM message")?;
+    let mut msg = to_fvm_message(tx.into()).context("failed to convert to FVM message")?;
+
+    // The gas limit of the message is the most the estimation can go up to; zero would mean the block gas limit.
+    if let Some(max) = data.method_limits.max_call_gas {
+        if msg.gas_limit == 0 || msg.gas_limit > max {
+            msg.gas_limit = max;
+        }
+    }
 
     let height = data
         .query_height(block_id)
@@ -940,6 +948,16 @@ where
                 resolve_height(&data, from_block).await?
             };
 
+            if let Some(max) = data.method_limits.max_log_block_range {
+                let range = to_height.value().saturating_sub(from_height.value()) + 1;
+                if from_height <= to_height && range > max {
+                    return limit_exceeded(
+                        "log_block_range",
+                        format!("block range of {range} exceeds the limit of {max}"),
+                    );
+                }
+            }
+
             (from_height, to_height)
         }
         et::FilterBlockOption::AtBlockHash(block_hash) => {
@@ -974,8 +992,8 @@ where
 
                 let mut log_index_start = 0usize;
                 for ((tx_idx, tx_result), tx) in tx_results.iter().enumerate().zip(block.data()) {
-                    let msg = match to_chain_message(tx) {
-                        Ok(ChainMessage::Signed(msg)) => msg,
+                    let msg = match to_signed_message(tx) {
+                        Ok(Some(msg)) => msg,
                         _ => continue,
                     };
 
@@ -1028,6 +1046,9 @@ pub async fn new_filter<C>(
 where
     C: Client + SubscriptionClient + Clone + Sync + Send + 'static,
 {
+    if !data.can_add_filter().await {
+        return limit_exceeded("filters", "too many filters installed");
+    }
     let id = data
         .new_filter(FilterKind::Logs(Box::new(filter)))
         .await
@@ -1041,6 +1062,9 @@ pub async fn new_block_filter<C>(data: JsonRpcData<C>) -> JsonRpcResult<FilterId
 where
     C: Client + SubscriptionClient + Clone + Sync + Send + 'static,
 {
+    if !data.can_add_filter().await {
+        return limit_exceeded("filters", "too many filters installed");
+    }
     let id = data
         .new_filter(FilterKind::NewBlocks)
         .await
@@ -1054,6 +1078,9 @@ pub async fn new_pending_transaction_filter<C>(data: JsonRpcData<C>) -> JsonRpcR
 where
     C: Client + SubscriptionClient + Clone + Sync + Send + 'static,
 {
+    if !data.can_add_filter().await {
+        return limit_exceeded("filters", "too many filters installed");
+    }
     let id = data
         .new_filter(FilterKind::PendingTransactions)
         .await
@@ -1109,22 +1136,28 @@ pub async fn subscribe<C>(
 where
     C: Client + SubscriptionClient + Clone + Sync + Send + 'static,
 {
+    let web_socket_id = match params {
+        SubscribeParams::One((_, id)) | SubscribeParams::Two((_, _, id)) => id,
+    };
+
+    if !data.can_subscribe(&web_socket_id).await {
+        return limit_exceeded("subscriptions", "too many subscriptions");
+    }
+
     match params {
         SubscribeParams::One((tag, web_socket_id)) => match tag.as_str() {
             "newHeads" => {
                 // Subscribe to `Block<TxHash>`
-                let ws_sender = data.get_web_socket(&web_socket_id).await?;
                 let id = data
-                    .new_subscription(FilterKind::NewBlocks, ws_sender)
+                    .new_subscription(FilterKind::NewBlocks, web_socket_id)
                     .await
                     .context("failed to add block subscription")?;
                 Ok(id)
             }
             "newPendingTransactions" => {
                 // Subscribe to `TxHash`
-                let ws_sender = data.get_web_socket(&web_socket_id).await?;
                 let id = data
-                    .new_subscription(FilterKind::PendingTransactions, ws_sender)
+                    .new_subscription(FilterKind::PendingTransactions, web_socket_id)
                     .await
                     .context("failed to add transaction subscription")?;
                 Ok(id)
@@ -1137,9 +1170,8 @@ where
         SubscribeParams::Two((tag, filter, web_socket_id)) => match tag.as_str() {
             "logs" => {
                 // Subscribe to `Log`
-                let ws_sender = data.get_web_socket(&web_socket_id).await?;
                 let id = data
-                    .new_subscription(FilterKind::Logs(Box::new(filter)), ws_sender)
+                    .new_subscription(FilterKind::Logs(Box::new(filter)), web_socket_id)
                     .await
                     .context("failed to add transaction subscription")?;
                 Ok(id)
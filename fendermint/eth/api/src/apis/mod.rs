@@ -7,7 +7,7 @@
 use crate::HybridClient;
 use jsonrpc_v2::{Factory, MapRouter, ServerBuilder};
 use lazy_static::lazy_static;
-use prometheus::{register_histogram_vec, HistogramVec};
+use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
 use std::marker::PhantomData;
 
 mod eth;
@@ -22,6 +22,12 @@ lazy_static! {
         &["method"]
     )
     .unwrap();
+    pub static ref RPC_LIMIT_REJECTIONS: IntCounterVec = register_int_counter_vec!(
+        "rpc_limit_rejections_total",
+        "Number of RPC requests rejected for exceeding a limit",
+        &["reason"]
+    )
+    .unwrap();
 }
 
 /// Middleware to record handler latencies as Prometheus metrics, labelled with the JSON-RPC method name.
@@ -9,20 +9,22 @@ use std::str::FromStr;
 use anyhow::{anyhow, Context};
 use ethers_core::types::{self as et};
 use fendermint_vm_actor_interface::eam::EthAddress;
-use fendermint_vm_message::conv::from_fvm::to_eth_transaction_request;
 use fendermint_vm_message::{chain::ChainMessage, signed::SignedMessage};
 use fvm_shared::address::Address;
 use fvm_shared::bigint::Zero;
 use fvm_shared::chainid::ChainID;
 use fvm_shared::{bigint::BigInt, econ::TokenAmount};
 use lazy_static::lazy_static;
-use tendermint::abci::response::DeliverTx;
+use tendermint::abci::types::ExecTxResult;
 use tendermint::abci::{self, Event, EventAttribute};
 use tendermint::crypto::sha256::Sha256;
 use tendermint_rpc::endpoint;
 
 use super::from_eth;
-use super::from_fvm::{to_eth_address, to_eth_signature, to_eth_tokens};
+use super::from_fvm::{
+    to_eth_access_list, to_eth_address, to_eth_tokens, to_eth_tx_signature, to_eth_tx_type,
+    to_eth_typed_transaction,
+};
 
 // Values taken from https://github.com/filecoin-project/lotus/blob/6e7dc9532abdb3171427347710df4c860f1957a2/chain/types/ethtypes/eth_types.go#L199
 
@@ -155,9 +157,7 @@ pub fn to_eth_block(
         gas_used += et::U256::from(result.gas_used);
         gas_limit += et::U256::from(result.gas_wanted);
 
-        let msg = to_chain_message(data)?;
-
-        if let ChainMessage::Signed(msg) = msg {
+        if let Some(msg) = to_signed_message(data)? {
             let hash = msg_hash(&result.events, data);
 
             let mut tx = to_eth_transaction(msg, chain_id, hash)
@@ -211,11 +211,14 @@ pub fn to_eth_transaction(
     hash: et::TxHash,
 ) -> anyhow::Result<et::Transaction> {
     // Based on https://github.com/filecoin-project/lotus/blob/6cc506f5cf751215be6badc94a960251c6453202/node/impl/full/eth.go#L2048
-    let sig =
-        to_eth_signature(msg.signature(), true).context("failed to convert to eth signature")?;
+    let (tx_type, sig) = to_eth_tx_signature(msg.signature(), &chain_id)
+        .context("failed to convert to eth signature")?;
+
+    let access_list =
+        to_eth_access_list(msg.signature()).context("failed to convert to access list")?;
 
     // Recover the original request; this method has better tests.
-    let tx = to_eth_transaction_request(&msg.message, &chain_id)
+    let tx = to_eth_typed_transaction(&msg.message, &chain_id, tx_type, access_list)
         .context("failed to convert to tx request")?;
 
     let tx = from_eth::to_eth_transaction(tx, sig, hash);
@@ -252,13 +255,14 @@ pub async fn to_eth_receipt(
     let block_number = et::U64::from(result.height.value());
     let transaction_index = et::U64::from(result.index);
     let transaction_hash = msg_hash(&result.tx_result.events, &result.tx);
+    let transaction_type = to_eth_tx_type(msg.signature()).as_u64();
 
     let msg = &msg.message;
     // Lotus effective gas price is based on total spend divided by gas used,
     // for which it recalculates the gas outputs. However, we don't have access
     // to the VM interpreter here to restore those results, and they are discarded
-    // from the [`ApplyRet`] during the conversion to [`DeliverTx`].
-    // We could put it into the [`DeliverTx::info`] field, or we can calculate
+    // from the [`ApplyRet`] during the conversion to [`ExecTxResult`].
+    // We could put it into the [`ExecTxResult::info`] field, or we can calculate
     // something based on the gas fields of the transaction, like Ethermint.
     let effective_gas_price =
         crate::gas::effective_gas_price(msg, base_fee, result.tx_result.gas_used);
@@ -307,7 +311,7 @@ pub async fn to_eth_receipt(
         })),
         root: Some(app_hash_to_root(&header.app_hash)?),
         logs_bloom: et::Bloom::from_slice(&*EMPTY_ETH_BLOOM),
-        transaction_type: Some(et::U64::from(2)), // Value used by Lotus.
+        transaction_type: Some(et::U64::from(transaction_type)),
         effective_gas_price: Some(to_eth_tokens(&effective_gas_price)?),
         other: Default::default(),
     };
@@ -399,7 +403,7 @@ fn app_hash_to_root(app_hash: &tendermint::AppHash) -> anyhow::Result<et::H256>
     }
 }
 
-fn maybe_contract_address(deliver_tx: &DeliverTx) -> Option<EthAddress> {
+fn maybe_contract_address(deliver_tx: &ExecTxResult) -> Option<EthAddress> {
     fendermint_rpc::response::decode_fevm_create(deliver_tx)
         .ok()
         .map(|cr| {
@@ -421,8 +425,10 @@ pub fn to_eth_block_zero(block: tendermint::Block) -> anyhow::Result<et::Block<s
         txs_results: None,
         begin_block_events: None,
         end_block_events: None,
+        finalize_block_events: Vec::new(),
         validator_updates: Vec::new(),
         consensus_param_updates: None,
+        app_hash: Default::default(),
     };
     let block = to_eth_block(&block, block_results, TokenAmount::zero(), ChainID::from(0))
         .context("failed to map block zero to eth")?;
@@ -527,6 +533,18 @@ pub fn to_chain_message(tx: &[u8]) -> anyhow::Result<ChainMessage> {
     fvm_ipld_encoding::from_slice::<ChainMessage>(tx).context("failed to decode tx as ChainMessage")
 }
 
+/// Decode a transaction and return the message signed by the user, if it's a user transaction.
+///
+/// A sponsored message is executed as its sender, so it's presented the same way as the
+/// transaction the sender signed; the sponsor only paid for the gas.
+pub fn to_signed_message(tx: &[u8]) -> anyhow::Result<Option<SignedMessage>> {
+    match to_chain_message(tx)? {
+        ChainMessage::Signed(msg) => Ok(Some(msg)),
+        ChainMessage::Sponsored(msg) => Ok(Some(msg.message)),
+        ChainMessage::Ipc(_) => Ok(None),
+    }
+}
+
 /// Hash the transaction payload the way Tendermint does,
 /// to calculate the transaction hash which is otherwise unavailable.
 ///
@@ -587,9 +605,12 @@ pub fn collect_emitters(events: &[abci::Event]) -> HashSet<Address> {
 
 #[cfg(test)]
 mod tests {
+    use fendermint_vm_message::chain::ChainMessage;
+    use quickcheck_macros::quickcheck;
+
     use crate::conv::from_tm::is_block_zero;
 
-    use super::{to_eth_block_zero, BLOCK_ZERO};
+    use super::{to_eth_block_zero, to_signed_message, BLOCK_ZERO};
 
     #[test]
     fn block_zero_can_be_created() {
@@ -600,4 +621,16 @@ mod tests {
     fn block_zero_can_be_turned_into_eth() {
         let _ = to_eth_block_zero(BLOCK_ZERO.clone()).unwrap();
     }
+
+    #[quickcheck]
+    fn signed_message_of_user_transactions(msg: ChainMessage) {
+        let tx = fvm_ipld_encoding::to_vec(&msg).unwrap();
+        let signed = to_signed_message(&tx).unwrap();
+
+        match msg {
+            ChainMessage::Signed(msg) => assert_eq!(signed, Some(msg)),
+            ChainMessage::Sponsored(msg) => assert_eq!(signed, Some(msg.message)),
+            ChainMessage::Ipc(_) => assert_eq!(signed, None),
+        }
+    }
 }
@@ -12,7 +12,7 @@ use anyhow::{anyhow, Context};
 use ethers_core::types as et;
 use fendermint_rpc::{client::FendermintClient, query::QueryClient};
 use fendermint_vm_actor_interface::eam::EthAddress;
-use fendermint_vm_message::{chain::ChainMessage, query::FvmQueryHeight, signed::DomainHash};
+use fendermint_vm_message::{query::FvmQueryHeight, signed::DomainHash};
 use futures::{Future, StreamExt};
 use fvm_shared::{address::Address, chainid::ChainID, error::ExitCode};
 use lru_time_cache::LruCache;
@@ -28,7 +28,9 @@ use tokio::sync::{
 };
 
 use crate::{
-    conv::from_tm::{self, find_hash_event, map_rpc_block_txs, msg_hash, tx_hash},
+    conv::from_tm::{
+        self, find_hash_event, map_rpc_block_txs, msg_hash, to_signed_message, tx_hash,
+    },
     error::JsonRpcError,
     handlers::ws::{MethodNotification, Notification},
     state::{enrich_block, WebSocketSender},
@@ -256,7 +258,7 @@ where
                 },
             ) => {
                 for tx in &block.data {
-                    if let Ok(ChainMessage::Signed(msg)) = fvm_ipld_encoding::from_slice(tx) {
+                    if let Ok(Some(msg)) = to_signed_message(tx) {
                         if let Ok(Some(DomainHash::Eth(h))) = msg.domain_hash(chain_id) {
                             hashes.push(et::TxHash::from(h))
                         }
@@ -4,11 +4,16 @@
 
 // Based on https://github.com/ChainSafe/forest/blob/v0.8.2/node/rpc/src/rpc_http_handler.rs
 
+use std::net::SocketAddr;
+
+use axum::extract::ConnectInfo;
 use axum::http::{HeaderMap, StatusCode};
-use axum::response::IntoResponse;
+use ipc_observability::propagation::set_parent_from_headers;
 use jsonrpc_v2::{RequestObject, ResponseObjects};
 use serde::Deserialize;
+use tracing::Instrument;
 
+use crate::limiter::{self, LimitError};
 use crate::{apis, AppState};
 
 type ResponseHeaders = [(&'static str, &'static str); 1];
@@ -26,19 +31,41 @@ pub enum RequestKind {
     Many(Vec<RequestObject>),
 }
 
+impl RequestKind {
+    /// Names of the methods called.
+    fn methods(&self) -> Vec<&str> {
+        match self {
+            RequestKind::One(r) => vec![r.method_ref()],
+            RequestKind::Many(rs) => rs.iter().map(|r| r.method_ref()).collect(),
+        }
+    }
+}
+
 /// Handle JSON-RPC calls.
+///
+/// Each call is handled in a span, which continues the trace of the caller if it sent a trace context.
 pub async fn handle(
-    _headers: HeaderMap,
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     axum::extract::State(state): axum::extract::State<AppState>,
     axum::Json(request): axum::Json<RequestKind>,
-) -> impl IntoResponse {
-    // NOTE: Any authorization can come here.
+) -> (StatusCode, ResponseHeaders, std::string::String) {
+    let client = state.rate_limiter.client_id(&headers, addr.ip());
+
+    let span = tracing::info_span!("rpc", method = request.methods().join(","));
+    set_parent_from_headers(&span, &headers);
+
     let response = match request {
         RequestKind::One(request) => {
             if let Err(response) = check_request(&request) {
                 return response;
             }
-            state.rpc_server.handle(request).await
+            if let Err(e) = state.rate_limiter.check(&client, &[request.method_ref()]) {
+                return limit_response(&e, limiter::error_response(&request, &e));
+            }
+            limiter::with_client(client, state.rpc_server.handle(request))
+                .instrument(span)
+                .await
         }
         RequestKind::Many(requests) => {
             for request in requests.iter() {
@@ -46,13 +73,37 @@ pub async fn handle(
                     return response;
                 }
             }
-            state.rpc_server.handle(requests).await
+            let methods = requests.iter().map(|r| r.method_ref()).collect::<Vec<_>>();
+            if let Err(e) = state.rate_limiter.check(&client, &methods) {
+                let responses = requests
+                    .iter()
+                    .map(|r| limiter::error_response(r, &e))
+                    .collect();
+                return limit_response(&e, serde_json::Value::Array(responses));
+            }
+            limiter::with_client(client, state.rpc_server.handle(requests))
+                .instrument(span)
+                .await
         }
     };
     debug_response(&response);
     json_response(&response)
 }
 
+/// Respond with JSON-RPC errors to requests rejected by the rate limiter.
+///
+/// Exceeding the rate is signalled with HTTP 429 as well, which makes some clients back off.
+fn limit_response(
+    error: &LimitError,
+    response: serde_json::Value,
+) -> (StatusCode, ResponseHeaders, std::string::String) {
+    let status = match error {
+        LimitError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        LimitError::BatchTooLarge { .. } => StatusCode::OK,
+    };
+    (status, RESPONSE_HEADERS, response.to_string())
+}
+
 fn debug_response(response: &ResponseObjects) {
     let debug = |r| {
         tracing::debug!(
@@ -100,3 +151,127 @@ fn check_request(
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use axum::extract::{ConnectInfo, State};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::Json;
+    use fvm_shared::econ::TokenAmount;
+
+    use super::{handle, RequestKind};
+    use crate::client::HybridClient;
+    use crate::limiter::{RateLimiter, LIMIT_EXCEEDED_CODE};
+    use crate::state::JsonRpcState;
+    use crate::{make_server, AppState, GasOpt, MethodLimitOpt, RateLimitOpt, TokenBucketOpt};
+
+    /// An application whose client is never called by the methods used in the tests.
+    fn app_state(capacity: u32, max_batch_size: usize) -> AppState {
+        let (client, _driver) = HybridClient::new(
+            "http://127.0.0.1:26657".parse().unwrap(),
+            "ws://127.0.0.1:26657/websocket".parse().unwrap(),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+
+        let gas_opt = GasOpt {
+            min_gas_premium: TokenAmount::from_atto(1),
+            num_blocks_max_prio_fee: 10,
+            max_fee_hist_size: 1024,
+        };
+
+        let rpc_state = Arc::new(JsonRpcState::new(
+            client,
+            Duration::from_secs(60),
+            100,
+            10,
+            gas_opt,
+            MethodLimitOpt::default(),
+        ));
+
+        let rate_limit_opt = RateLimitOpt {
+            per_ip: Some(TokenBucketOpt {
+                capacity,
+                refill_per_sec: 0,
+            }),
+            per_api_key: None,
+            api_key_header: "x-api-key".into(),
+            api_keys: HashSet::new(),
+            default_cost: 1,
+            method_costs: HashMap::new(),
+            max_batch_size: Some(max_batch_size),
+        };
+
+        AppState {
+            rpc_server: make_server(rpc_state.clone()),
+            rpc_state,
+            rate_limiter: Arc::new(RateLimiter::new(rate_limit_opt)),
+        }
+    }
+
+    fn sha3_request(id: usize) -> String {
+        format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"web3_sha3","params":["0x00"]}}"#)
+    }
+
+    async fn call(state: &AppState, request: &str) -> (StatusCode, serde_json::Value) {
+        let request: RequestKind = serde_json::from_str(request).unwrap();
+        let addr: SocketAddr = "127.0.0.1:12345".parse().unwrap();
+        let (status, _, body) = handle(
+            HeaderMap::new(),
+            ConnectInfo(addr),
+            State(state.clone()),
+            Json(request),
+        )
+        .await;
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    fn error_code(response: &serde_json::Value) -> Option<i64> {
+        response["error"]["code"].as_i64()
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_get_429() {
+        let state = app_state(2, 10);
+
+        for id in 0..2 {
+            let (status, response) = call(&state, &sha3_request(id)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(response["result"].is_string(), "{response}");
+        }
+
+        let (status, response) = call(&state, &sha3_request(2)).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(error_code(&response), Some(LIMIT_EXCEEDED_CODE));
+        assert_eq!(response["id"], 2);
+    }
+
+    #[tokio::test]
+    async fn oversized_batches_are_rejected() {
+        let state = app_state(100, 2);
+
+        let batch = |n: usize| {
+            let requests = (0..n).map(sha3_request).collect::<Vec<_>>();
+            format!("[{}]", requests.join(","))
+        };
+
+        let (status, response) = call(&state, &batch(2)).await;
+        assert_eq!(status, StatusCode::OK);
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert!(responses.iter().all(|r| r["result"].is_string()));
+
+        let (status, response) = call(&state, &batch(3)).await;
+        assert_eq!(status, StatusCode::OK);
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert!(responses
+            .iter()
+            .all(|r| error_code(r) == Some(LIMIT_EXCEEDED_CODE)));
+    }
+}
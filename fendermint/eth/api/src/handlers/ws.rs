@@ -4,10 +4,12 @@
 
 // Based on https://github.com/ChainSafe/forest/blob/v0.8.2/node/rpc/src/rpc_ws_handler.rs
 
+use std::net::SocketAddr;
+
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        WebSocketUpgrade,
+        ConnectInfo, WebSocketUpgrade,
     },
     http::HeaderMap,
     response::IntoResponse,
@@ -15,7 +17,9 @@ use axum::{
 use futures::{stream::SplitSink, SinkExt, StreamExt};
 use jsonrpc_v2::{RequestObject, ResponseObject, ResponseObjects, V2};
 use serde_json::json;
+use tracing::Instrument;
 
+use crate::limiter::{self, ClientId, RateLimiter};
 use crate::{apis, state::WebSocketId, AppState, JsonRpcServer};
 
 /// Mirroring [ethers_providers::rpc::transports::ws::types::Notification], which is what the library
@@ -34,18 +38,20 @@ pub struct MethodNotification {
 }
 
 pub async fn handle(
-    _headers: HeaderMap,
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     axum::extract::State(state): axum::extract::State<AppState>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| async { rpc_ws_handler_inner(state, socket).await })
+    let client = state.rate_limiter.client_id(&headers, addr.ip());
+    ws.on_upgrade(move |socket| async { rpc_ws_handler_inner(state, client, socket).await })
 }
 
 /// Handle requests in a loop, interpreting each message as a JSON-RPC request.
 ///
 /// Messages are evaluated one by one. We could spawn tasks like Forest,
-/// but there should be some rate limiting applied to avoid DoS attacks.
-async fn rpc_ws_handler_inner(state: AppState, socket: WebSocket) {
+/// but each request is charged to the rate limit of the client that opened the socket.
+async fn rpc_ws_handler_inner(state: AppState, client: ClientId, socket: WebSocket) {
     tracing::debug!("Accepted WS connection!");
     let (mut sender, mut receiver) = socket.split();
 
@@ -57,7 +63,7 @@ async fn rpc_ws_handler_inner(state: AppState, socket: WebSocket) {
     loop {
         let keep = tokio::select! {
             Some(Ok(message)) = receiver.next() => {
-                handle_incoming(web_socket_id, &state.rpc_server, &mut sender, message).await
+                handle_incoming(web_socket_id, &state.rpc_server, &state.rate_limiter, &client, &mut sender, message).await
             },
             Some(notif) = notif_rx.recv() => {
                 handle_outgoing(web_socket_id, &mut sender, notif).await
@@ -79,6 +85,8 @@ async fn rpc_ws_handler_inner(state: AppState, socket: WebSocket) {
 async fn handle_incoming(
     web_socket_id: WebSocketId,
     rpc_server: &JsonRpcServer,
+    rate_limiter: &RateLimiter,
+    client: &ClientId,
     sender: &mut SplitSink<WebSocket, Message>,
     message: Message,
 ) -> bool {
@@ -92,7 +100,15 @@ async fn handle_incoming(
 
             match serde_json::from_str::<RequestObject>(&request_text) {
                 Ok(req) => {
-                    return send_call_result(web_socket_id, rpc_server, sender, req).await;
+                    if let Err(e) = rate_limiter.check(client, &[req.method_ref()]) {
+                        let json = limiter::error_response(&req, &e).to_string();
+                        return send_json(web_socket_id, sender, json).await;
+                    }
+                    let span = tracing::info_span!("rpc", method = req.method_ref(), web_socket_id);
+                    let call = send_call_result(web_socket_id, rpc_server, sender, req);
+                    return limiter::with_client(client.clone(), call)
+                        .instrument(span)
+                        .await;
                 }
                 Err(e) => {
                     deserialization_error("RequestObject", e);
@@ -214,15 +230,22 @@ async fn send_response(
     match response {
         Err(e) => {
             tracing::error!(error=?e, "failed to serialize response to JSON");
+            true
         }
-        Ok(json) => {
-            tracing::debug!(web_socket_id, json, "sending response to WS");
-            if let Err(e) = sender.send(Message::Text(json)).await {
-                tracing::warn!(web_socket_id, error=?e, "failed to send response to WS");
-                if is_closed_connection(e) {
-                    return false;
-                }
-            }
+        Ok(json) => send_json(web_socket_id, sender, json).await,
+    }
+}
+
+async fn send_json(
+    web_socket_id: WebSocketId,
+    sender: &mut SplitSink<WebSocket, Message>,
+    json: String,
+) -> bool {
+    tracing::debug!(web_socket_id, json, "sending response to WS");
+    if let Err(e) = sender.send(Message::Text(json)).await {
+        tracing::warn!(web_socket_id, error=?e, "failed to send response to WS");
+        if is_closed_connection(e) {
+            return false;
         }
     }
     true
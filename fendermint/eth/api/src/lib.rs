@@ -5,7 +5,12 @@ use anyhow::anyhow;
 use axum::routing::{get, post};
 use fvm_shared::econ::TokenAmount;
 use jsonrpc_v2::Data;
-use std::{net::ToSocketAddrs, sync::Arc, time::Duration};
+use std::collections::{HashMap, HashSet};
+use std::{
+    net::{SocketAddr, ToSocketAddrs},
+    sync::Arc,
+    time::Duration,
+};
 use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
 
 pub mod apis;
@@ -16,12 +21,14 @@ mod error;
 mod filters;
 mod gas;
 mod handlers;
+mod limiter;
 mod mpool;
 mod state;
 
 pub use client::{HybridClient, HybridClientDriver};
 
 use error::{error, JsonRpcError};
+use limiter::RateLimiter;
 use state::{JsonRpcState, Nonce};
 
 /// This is passed to every method handler. It's generic in the client type to facilitate testing with mocks.
@@ -34,6 +41,7 @@ type JsonRpcResult<T> = Result<T, JsonRpcError>;
 pub struct AppState {
     pub rpc_server: JsonRpcServer,
     pub rpc_state: Arc<JsonRpcState<HybridClient>>,
+    pub rate_limiter: Arc<RateLimiter>,
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +58,45 @@ pub struct CorsOpt {
     pub allowed_headers: AllowHeaders,
 }
 
+/// A token bucket, which allows bursts up to its capacity, then limits the rate to its refill speed.
+#[derive(Debug, Clone)]
+pub struct TokenBucketOpt {
+    pub capacity: u32,
+    pub refill_per_sec: u32,
+}
+
+/// Limits applied to clients before their requests are handled.
+#[derive(Debug, Clone)]
+pub struct RateLimitOpt {
+    /// Bucket of each anonymous client IP address; unlimited if `None`.
+    pub per_ip: Option<TokenBucketOpt>,
+    /// Bucket of each API key; unlimited if `None`.
+    pub per_api_key: Option<TokenBucketOpt>,
+    /// The HTTP header clients send their API key in.
+    pub api_key_header: String,
+    /// The known API keys; requests with any other key are treated as anonymous.
+    pub api_keys: HashSet<String>,
+    /// The number of tokens a method call costs unless it's in `method_costs`.
+    pub default_cost: u32,
+    /// Cost of expensive methods.
+    pub method_costs: HashMap<String, u32>,
+    /// Maximum number of requests in a batch.
+    pub max_batch_size: Option<usize>,
+}
+
+/// Limits applied by the method handlers to the work a request can cause.
+#[derive(Debug, Clone, Default)]
+pub struct MethodLimitOpt {
+    /// Maximum number of blocks `eth_getLogs` can scan.
+    pub max_log_block_range: Option<u64>,
+    /// Maximum gas limit for `eth_call` and `eth_estimateGas`; higher limits are capped.
+    pub max_call_gas: Option<u64>,
+    /// Maximum number of filters and subscriptions a client can have installed.
+    pub max_filters: Option<usize>,
+    /// Maximum number of subscriptions a WebSocket connection can have.
+    pub max_subscriptions_per_socket: Option<usize>,
+}
+
 /// Start listening to JSON-RPC requests.
 pub async fn listen<A: ToSocketAddrs>(
     listen_addr: A,
@@ -59,6 +106,8 @@ pub async fn listen<A: ToSocketAddrs>(
     max_nonce_gap: Nonce,
     gas_opt: GasOpt,
     cors_opt: CorsOpt,
+    rate_limit_opt: RateLimitOpt,
+    method_limit_opt: MethodLimitOpt,
 ) -> anyhow::Result<()> {
     if let Some(listen_addr) = listen_addr.to_socket_addrs()?.next() {
         let rpc_state = Arc::new(JsonRpcState::new(
@@ -67,6 +116,7 @@ pub async fn listen<A: ToSocketAddrs>(
             cache_capacity,
             max_nonce_gap,
             gas_opt,
+            method_limit_opt,
         ));
 
         // Start the transaction cache pruning subscription.
@@ -80,9 +130,12 @@ pub async fn listen<A: ToSocketAddrs>(
         let app_state = AppState {
             rpc_server,
             rpc_state,
+            rate_limiter: Arc::new(RateLimiter::new(rate_limit_opt)),
         };
         let router = make_router(app_state, cors_opt);
-        let server = axum::Server::try_bind(&listen_addr)?.serve(router.into_make_service());
+        // The peer address is needed to limit the rate of requests per IP.
+        let server = axum::Server::try_bind(&listen_addr)?
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>());
         tracing::info!(?listen_addr, "bound Ethereum API");
         server.await?;
         Ok(())
@@ -0,0 +1,314 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Rate limiting of JSON-RPC requests, applied before they reach the method handlers.
+
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use jsonrpc_v2::RequestObject;
+use lru_time_cache::LruCache;
+use serde_json::json;
+
+use crate::apis::RPC_LIMIT_REJECTIONS;
+use crate::{JsonRpcError, JsonRpcResult, RateLimitOpt, TokenBucketOpt};
+
+/// JSON-RPC error code for exceeded limits, as proposed in EIP-1474.
+pub const LIMIT_EXCEEDED_CODE: i64 = -32005;
+
+/// Maximum number of clients we keep track of.
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// Minimum time an idle client is remembered for.
+const MIN_CLIENT_TTL: Duration = Duration::from_secs(60);
+
+/// The identity requests are charged to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClientId {
+    /// Requests carrying one of the configured API keys.
+    ApiKey(String),
+    /// Anonymous requests, by the address of the peer.
+    Ip(IpAddr),
+}
+
+tokio::task_local! {
+    /// The client whose request is being handled, for the limits enforced by the method handlers.
+    static CURRENT_CLIENT: ClientId;
+}
+
+/// Handle requests on behalf of a client, which the method handlers can look up with [current_client].
+pub async fn with_client<F: Future>(client: ClientId, f: F) -> F::Output {
+    CURRENT_CLIENT.scope(client, f).await
+}
+
+/// The client whose request is being handled, if it's known.
+pub fn current_client() -> Option<ClientId> {
+    CURRENT_CLIENT.try_with(|client| client.clone()).ok()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitError {
+    BatchTooLarge { size: usize, max: usize },
+    RateLimited,
+}
+
+impl LimitError {
+    /// Label of the rejection in the metrics.
+    fn reason(&self) -> &'static str {
+        match self {
+            LimitError::BatchTooLarge { .. } => "batch_size",
+            LimitError::RateLimited => "rate",
+        }
+    }
+}
+
+impl std::fmt::Display for LimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitError::BatchTooLarge { size, max } => {
+                write!(f, "batch of {size} requests exceeds the limit of {max}")
+            }
+            LimitError::RateLimited => write!(f, "request rate limit exceeded"),
+        }
+    }
+}
+
+/// Tokens available to a client, refilled continuously up to the capacity of the bucket.
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(opt: &TokenBucketOpt, now: Instant) -> Self {
+        Self {
+            tokens: opt.capacity as f64,
+            updated: now,
+        }
+    }
+
+    fn try_take(&mut self, opt: &TokenBucketOpt, cost: u32, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * opt.refill_per_sec as f64).min(opt.capacity as f64);
+        self.updated = now;
+
+        if self.tokens >= cost as f64 {
+            self.tokens -= cost as f64;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Token buckets of the clients seen recently.
+pub struct RateLimiter {
+    opt: RateLimitOpt,
+    buckets: Mutex<LruCache<ClientId, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(opt: RateLimitOpt) -> Self {
+        // Forgetting a bucket resets it to full, so keep them at least until they would have been refilled.
+        let ttl = [opt.per_ip.as_ref(), opt.per_api_key.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|b| Duration::from_secs((b.capacity / b.refill_per_sec.max(1)) as u64 + 1))
+            .fold(MIN_CLIENT_TTL, |a, b| a.max(b));
+
+        Self {
+            opt,
+            buckets: Mutex::new(LruCache::with_expiry_duration_and_capacity(
+                ttl,
+                MAX_TRACKED_CLIENTS,
+            )),
+        }
+    }
+
+    /// Identify the client by its API key if it sent a known one, otherwise by its address.
+    pub fn client_id(&self, headers: &HeaderMap, ip: IpAddr) -> ClientId {
+        headers
+            .get(self.opt.api_key_header.as_str())
+            .and_then(|v| v.to_str().ok())
+            .filter(|key| self.opt.api_keys.contains(*key))
+            .map(|key| ClientId::ApiKey(key.to_string()))
+            .unwrap_or(ClientId::Ip(ip))
+    }
+
+    /// The cost of calling a method.
+    fn cost(&self, method: &str) -> u32 {
+        self.opt
+            .method_costs
+            .get(method)
+            .cloned()
+            .unwrap_or(self.opt.default_cost)
+    }
+
+    /// Check whether the client can call the methods, which may be a batch, and charge for them if so.
+    ///
+    /// Rejections are counted in the metrics.
+    pub fn check(&self, client: &ClientId, methods: &[&str]) -> Result<(), LimitError> {
+        let res = self.check_at(client, methods, Instant::now());
+        if let Err(ref e) = res {
+            RPC_LIMIT_REJECTIONS.with_label_values(&[e.reason()]).inc();
+            tracing::debug!(?client, ?methods, error = %e, "rejected RPC request");
+        }
+        res
+    }
+
+    fn check_at(
+        &self,
+        client: &ClientId,
+        methods: &[&str],
+        now: Instant,
+    ) -> Result<(), LimitError> {
+        if let Some(max) = self.opt.max_batch_size {
+            if methods.len() > max {
+                return Err(LimitError::BatchTooLarge {
+                    size: methods.len(),
+                    max,
+                });
+            }
+        }
+
+        let opt = match client {
+            ClientId::ApiKey(_) => self.opt.per_api_key.as_ref(),
+            ClientId::Ip(_) => self.opt.per_ip.as_ref(),
+        };
+
+        let Some(opt) = opt else {
+            return Ok(());
+        };
+
+        let cost = methods.iter().map(|m| self.cost(m)).sum();
+
+        let mut buckets = self.buckets.lock().expect("buckets poisoned");
+        let bucket = buckets
+            .entry(client.clone())
+            .or_insert_with(|| TokenBucket::new(opt, now));
+
+        if bucket.try_take(opt, cost, now) {
+            Ok(())
+        } else {
+            Err(LimitError::RateLimited)
+        }
+    }
+}
+
+/// Reject a request which would exceed a limit enforced by a method handler.
+///
+/// The rejection is counted in the metrics under `reason`.
+pub fn limit_exceeded<T>(reason: &'static str, msg: impl ToString) -> JsonRpcResult<T> {
+    RPC_LIMIT_REJECTIONS.with_label_values(&[reason]).inc();
+    Err(JsonRpcError {
+        code: LIMIT_EXCEEDED_CODE,
+        message: msg.to_string(),
+        data: None,
+    })
+}
+
+/// The JSON-RPC error response to a rejected request.
+pub fn error_response(request: &RequestObject, error: &LimitError) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": LIMIT_EXCEEDED_CODE,
+            "message": error.to_string(),
+        },
+        "id": request.id_ref(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, Instant};
+
+    use axum::http::HeaderMap;
+
+    use super::{ClientId, LimitError, RateLimiter};
+    use crate::{RateLimitOpt, TokenBucketOpt};
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(RateLimitOpt {
+            per_ip: Some(TokenBucketOpt {
+                capacity: 10,
+                refill_per_sec: 2,
+            }),
+            per_api_key: None,
+            api_key_header: "x-api-key".into(),
+            api_keys: HashSet::from(["secret".to_string()]),
+            default_cost: 1,
+            method_costs: HashMap::from([("eth_getLogs".to_string(), 5)]),
+            max_batch_size: Some(3),
+        })
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = limiter();
+        let client = ClientId::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let other = ClientId::Ip(IpAddr::V4(Ipv4Addr::BROADCAST));
+        let now = Instant::now();
+
+        // 10 - 5 - 1 - 1 = 3 tokens left
+        assert!(limiter.check_at(&client, &["eth_getLogs"], now).is_ok());
+        assert!(limiter
+            .check_at(&client, &["eth_chainId", "eth_chainId"], now)
+            .is_ok());
+        assert_eq!(
+            limiter.check_at(&client, &["eth_getLogs"], now),
+            Err(LimitError::RateLimited)
+        );
+        // Other clients have their own buckets.
+        assert!(limiter.check_at(&other, &["eth_getLogs"], now).is_ok());
+
+        // The bucket refills with time: 3 + 2 * 2 = 7 tokens.
+        let later = now + Duration::from_secs(2);
+        assert!(limiter.check_at(&client, &["eth_getLogs"], later).is_ok());
+        assert!(limiter
+            .check_at(&client, &["eth_chainId", "eth_chainId"], later)
+            .is_ok());
+        assert!(limiter.check_at(&client, &["eth_chainId"], later).is_err());
+
+        // The capacity caps the refill.
+        let much_later = later + Duration::from_secs(60);
+        assert!(limiter
+            .check_at(&client, &["eth_getLogs", "eth_getLogs"], much_later)
+            .is_ok());
+        assert!(limiter
+            .check_at(&client, &["eth_chainId"], much_later)
+            .is_err());
+
+        assert_eq!(
+            limiter.check_at(&other, &["eth_chainId"; 4], much_later),
+            Err(LimitError::BatchTooLarge { size: 4, max: 3 })
+        );
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let limiter = limiter();
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(limiter.client_id(&headers, ip), ClientId::Ip(ip));
+
+        headers.insert("x-api-key", "unknown".parse().unwrap());
+        assert_eq!(limiter.client_id(&headers, ip), ClientId::Ip(ip));
+
+        headers.insert("x-api-key", "secret".parse().unwrap());
+        let client = limiter.client_id(&headers, ip);
+        assert_eq!(client, ClientId::ApiKey("secret".into()));
+
+        // Keys without a bucket are not limited by rate.
+        for _ in 0..100 {
+            assert!(limiter.check(&client, &["eth_getLogs"]).is_ok());
+        }
+    }
+}
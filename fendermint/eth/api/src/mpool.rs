@@ -17,7 +17,7 @@ use tendermint_rpc::{
     Client, SubscriptionClient,
 };
 
-use crate::{cache::Cache, state::Nonce, HybridClient};
+use crate::{cache::Cache, conv::from_tm::to_signed_message, state::Nonce, HybridClient};
 
 const RETRY_SLEEP_SECS: u64 = 5;
 
@@ -152,7 +152,7 @@ async fn tx_cache_clearing_loop<C>(
 fn collect_txs(block: &Block, chain_id: &ChainID) -> Vec<(et::TxHash, Address, Nonce)> {
     let mut txs = Vec::new();
     for tx in &block.data {
-        if let Ok(ChainMessage::Signed(msg)) = fvm_ipld_encoding::from_slice(tx) {
+        if let Ok(Some(msg)) = to_signed_message(tx) {
             if let Ok(Some(DomainHash::Eth(h))) = msg.domain_hash(chain_id) {
                 txs.push((et::TxHash::from(h), msg.message.from, msg.message.sequence))
             }
@@ -4,7 +4,7 @@
 //! Tendermint RPC helper methods for the implementation of the APIs.
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
@@ -14,9 +14,9 @@ use ethers_core::types::{self as et};
 use fendermint_rpc::client::{FendermintClient, TendermintClient};
 use fendermint_rpc::query::QueryClient;
 use fendermint_vm_actor_interface::{evm, system};
+use fendermint_vm_message::conv::from_eth::to_fvm_address;
 use fendermint_vm_message::query::{ActorState, FvmQueryHeight};
 use fendermint_vm_message::signed::DomainHash;
-use fendermint_vm_message::{chain::ChainMessage, conv::from_eth::to_fvm_address};
 use fvm_ipld_encoding::{de::DeserializeOwned, RawBytes};
 use fvm_shared::{chainid::ChainID, econ::TokenAmount, error::ExitCode, message::Message};
 use rand::Rng;
@@ -37,12 +37,13 @@ use crate::filters::{
     FilterRecords,
 };
 use crate::handlers::ws::MethodNotification;
+use crate::limiter::{self, ClientId};
 use crate::mpool::{TransactionBuffer, TransactionCache};
-use crate::GasOpt;
 use crate::{
-    conv::from_tm::{map_rpc_block_txs, to_chain_message, to_eth_block, to_eth_transaction},
+    conv::from_tm::{map_rpc_block_txs, to_eth_block, to_eth_transaction, to_signed_message},
     error, JsonRpcResult,
 };
+use crate::{GasOpt, MethodLimitOpt};
 
 /// How long to keep transactions in the caches.
 const TX_CACHE_TTL_SECS: u64 = 5 * 60;
@@ -66,8 +67,13 @@ pub struct JsonRpcState<C> {
     filters: FilterMap,
     next_web_socket_id: AtomicUsize,
     web_sockets: RwLock<HashMap<WebSocketId, WebSocketSender>>,
+    /// Subscriptions opened by each web socket, some of which may have been removed since.
+    web_socket_subscriptions: RwLock<HashMap<WebSocketId, HashSet<FilterId>>>,
+    /// Filters and subscriptions installed by each client, some of which may have been removed since.
+    client_filters: RwLock<HashMap<Option<ClientId>, HashSet<FilterId>>>,
     pub max_nonce_gap: Nonce,
     pub gas_opt: GasOpt,
+    pub method_limits: MethodLimitOpt,
 }
 
 impl<C> JsonRpcState<C>
@@ -80,6 +86,7 @@ where
         cache_capacity: usize,
         max_nonce_gap: Nonce,
         gas_opt: GasOpt,
+        method_limits: MethodLimitOpt,
     ) -> Self {
         let client = FendermintClient::new(client);
         let addr_cache = AddressCache::new(client.clone(), cache_capacity);
@@ -97,8 +104,11 @@ where
             filters: Default::default(),
             next_web_socket_id: Default::default(),
             web_sockets: Default::default(),
+            web_socket_subscriptions: Default::default(),
+            client_filters: Default::default(),
             gas_opt,
             max_nonce_gap,
+            method_limits,
         }
     }
 }
@@ -121,6 +131,7 @@ impl<C> JsonRpcState<C> {
     pub async fn remove_web_socket(&self, id: &WebSocketId) {
         let mut guard = self.web_sockets.write().await;
         guard.remove(id);
+        self.web_socket_subscriptions.write().await.remove(id);
     }
 
     /// Get the sender of a web socket.
@@ -341,9 +352,7 @@ where
         index: et::U64,
     ) -> JsonRpcResult<Option<et::Transaction>> {
         if let Some(msg) = block.data().get(index.as_usize()) {
-            let msg = to_chain_message(msg)?;
-
-            if let ChainMessage::Signed(msg) = msg {
+            if let Some(msg) = to_signed_message(msg)? {
                 let sp = self
                     .client
                     .state_params(FvmQueryHeight::from(index.as_u64()))
@@ -561,6 +570,14 @@ where
 
         let (state, tx) = self.insert_filter_driver(kind, ws_sender).await;
         let id = state.id();
+
+        self.client_filters
+            .write()
+            .await
+            .entry(limiter::current_client())
+            .or_default()
+            .insert(id);
+
         let filters = self.filters.clone();
         let client = self.client.clone();
 
@@ -583,13 +600,55 @@ where
     pub async fn new_subscription(
         &self,
         kind: FilterKind,
-        ws_sender: WebSocketSender,
+        web_socket_id: WebSocketId,
     ) -> anyhow::Result<FilterId> {
-        self.new_filter_driver(kind, Some(ws_sender)).await
+        let ws_sender = self.get_web_socket(&web_socket_id).await?;
+        let id = self.new_filter_driver(kind, Some(ws_sender)).await?;
+
+        self.web_socket_subscriptions
+            .write()
+            .await
+            .entry(web_socket_id)
+            .or_default()
+            .insert(id);
+
+        Ok(id)
     }
 }
 
 impl<C> JsonRpcState<C> {
+    /// Check if the client of the current request can install another filter.
+    pub async fn can_add_filter(&self) -> bool {
+        let Some(max) = self.method_limits.max_filters else {
+            return true;
+        };
+        let client = limiter::current_client();
+        let filters = self.filters.read().await;
+        let mut owned = self.client_filters.write().await;
+        // Forget the ones which have been uninstalled or timed out.
+        owned.retain(|_, ids| {
+            ids.retain(|id| filters.contains_key(id));
+            !ids.is_empty()
+        });
+        owned.get(&client).map(|ids| ids.len()).unwrap_or_default() < max
+    }
+
+    /// Check if the web socket can open another subscription.
+    pub async fn can_subscribe(&self, web_socket_id: &WebSocketId) -> bool {
+        if !self.can_add_filter().await {
+            return false;
+        }
+        let Some(max) = self.method_limits.max_subscriptions_per_socket else {
+            return true;
+        };
+        let filters = self.filters.read().await;
+        let mut subs = self.web_socket_subscriptions.write().await;
+        let ids = subs.entry(*web_socket_id).or_default();
+        // Forget the ones which have been unsubscribed.
+        ids.retain(|id| filters.contains_key(id));
+        ids.len() < max
+    }
+
     pub async fn uninstall_filter(&self, filter_id: FilterId) -> anyhow::Result<bool> {
         let filters = self.filters.read().await;
 
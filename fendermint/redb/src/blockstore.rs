@@ -0,0 +1,89 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+use redb::{Database, ReadableTable};
+
+use crate::db::table_def;
+use crate::Redb;
+
+/// A [`Blockstore`] implementation that writes to a specific namespace.
+#[derive(Clone)]
+pub struct NamespaceBlockstore {
+    db: Arc<Database>,
+    ns: String,
+}
+
+impl NamespaceBlockstore {
+    pub fn new(db: Redb, ns: String) -> anyhow::Result<Self> {
+        // All namespaces are pre-created during open.
+        if !db.has_ns(&ns)? {
+            Err(anyhow!("namespace {ns} does not exist!"))
+        } else {
+            Ok(Self { db: db.db, ns })
+        }
+    }
+}
+
+impl Blockstore for NamespaceBlockstore {
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        let tx = self.db.begin_read()?;
+        let table = tx.open_table(table_def(&self.ns))?;
+        let res = table.get(k.to_bytes().as_slice())?;
+        Ok(res.map(|v| v.value().to_vec()))
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+        self.put_many_keyed([(*k, block)])
+    }
+
+    // Called by the BufferedBlockstore during flush.
+    fn put_many_keyed<D, I>(&self, blocks: I) -> anyhow::Result<()>
+    where
+        Self: Sized,
+        D: AsRef<[u8]>,
+        I: IntoIterator<Item = (Cid, D)>,
+    {
+        let tx = self.db.begin_write()?;
+        {
+            let mut table = tx.open_table(table_def(&self.ns))?;
+            for (cid, v) in blocks.into_iter() {
+                let k = cid.to_bytes();
+                table.insert(k.as_slice(), v.as_ref())?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cid::{multihash, multihash::MultihashDigest, Cid};
+    use fvm_ipld_blockstore::Blockstore;
+    use fvm_ipld_encoding::{to_vec, DAG_CBOR};
+
+    use crate::Redb;
+
+    use super::NamespaceBlockstore;
+
+    #[test]
+    fn put_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Redb::open_ns(dir.path().join("redb"), ["blocks"].iter()).unwrap();
+
+        assert!(NamespaceBlockstore::new(db.clone(), "missing".into()).is_err());
+
+        let bs = NamespaceBlockstore::new(db, "blocks".into()).unwrap();
+
+        let data = to_vec(&"hello world").unwrap();
+        let cid = Cid::new_v1(DAG_CBOR, multihash::Code::Blake2b256.digest(&data));
+
+        assert!(!bs.has(&cid).unwrap());
+        bs.put_keyed(&cid, &data).unwrap();
+        assert_eq!(bs.get(&cid).unwrap(), Some(data));
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::path::Path;
+use std::sync::Arc;
+
+use redb::{Database, TableDefinition, TableHandle};
+use thiserror::Error;
+
+/// Keys and values are stored as raw bytes, the encoding is up to the users.
+pub(crate) type Bytes = &'static [u8];
+
+/// Database error
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Database(#[from] redb::Error),
+    #[error("{0}")]
+    Other(String),
+}
+
+/// Convert any of the specific `redb` errors into our own.
+pub(crate) fn to_error(e: impl Into<redb::Error>) -> Error {
+    Error::Database(e.into())
+}
+
+/// Definition of the table storing a namespace.
+pub(crate) fn table_def(name: &str) -> TableDefinition<'_, Bytes, Bytes> {
+    TableDefinition::new(name)
+}
+
+/// `Redb` is a pure-Rust alternative to `RocksDb`, storing everything in a single file.
+///
+/// Namespaces are mapped to tables, the same way `RocksDb` maps them to column families.
+/// Unlike `RocksDb`, it only allows a single write transaction at a time; others block
+/// until it is committed or rolled back, so write transactions never conflict.
+///
+/// Usage:
+/// ```no_run
+/// use fendermint_redb::Redb;
+///
+/// let db = Redb::open_ns("test.redb", ["foo", "bar"].iter()).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Redb {
+    pub db: Arc<Database>,
+}
+
+impl Redb {
+    /// Open an existing database or create a new one.
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let ns: Vec<String> = Vec::new();
+        Self::open_ns(path, ns.iter())
+    }
+
+    /// Open a database and create any of the namespaces which don't exist yet.
+    ///
+    /// Read transactions can only see tables which exist, so all namespaces should be created here.
+    pub fn open_ns<P, I, N>(path: P, namespaces: I) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        I: Iterator<Item = N>,
+        N: AsRef<str>,
+    {
+        let db = Database::create(path).map_err(to_error)?;
+        let db = Self { db: Arc::new(db) };
+
+        let tx = db.db.begin_write().map_err(to_error)?;
+        for ns in namespaces {
+            tx.open_table(table_def(ns.as_ref())).map_err(to_error)?;
+        }
+        tx.commit().map_err(to_error)?;
+
+        Ok(db)
+    }
+
+    /// List the namespaces in the database.
+    pub fn list_ns(&self) -> Result<Vec<String>, Error> {
+        let tx = self.db.begin_read().map_err(to_error)?;
+        let tables = tx.list_tables().map_err(to_error)?;
+        Ok(tables.map(|t| t.name().to_owned()).collect())
+    }
+
+    /// Check if a namespace exists.
+    pub fn has_ns(&self, name: &str) -> Result<bool, Error> {
+        Ok(self.list_ns()?.iter().any(|ns| ns == name))
+    }
+
+    /// Create a new namespace.
+    ///
+    /// Returns error if it already exists.
+    pub fn new_ns<'a>(&self, name: &'a str) -> Result<&'a str, Error> {
+        if self.has_ns(name)? {
+            return Err(Error::Other(format!("namespace '{name}' already exists")));
+        }
+        let tx = self.db.begin_write().map_err(to_error)?;
+        tx.open_table(table_def(name)).map_err(to_error)?;
+        tx.commit().map_err(to_error)?;
+        Ok(name)
+    }
+}
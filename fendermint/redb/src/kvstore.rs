@@ -0,0 +1,472 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+use std::collections::VecDeque;
+use std::ops::Bound;
+use std::thread;
+
+use fendermint_storage::Decode;
+use fendermint_storage::Encode;
+use fendermint_storage::KVResult;
+use fendermint_storage::KVTransaction;
+use fendermint_storage::KVWritable;
+use fendermint_storage::KVWrite;
+use fendermint_storage::{KVError, KVRead, KVReadable, KVStore};
+use redb::{ReadTransaction, ReadableTable, StorageError, WriteTransaction};
+
+use crate::db::{table_def, to_error, Bytes};
+use crate::Redb;
+
+/// Number of entries to load from a table at a time during iteration.
+const ITER_BATCH_SIZE: usize = 1000;
+
+type Entry = (Vec<u8>, Vec<u8>);
+
+/// For reads we use a read transaction, which sees a snapshot of the database.
+pub struct RedbReadTx {
+    tx: ReadTransaction,
+}
+
+/// For writes, we use a transaction which we'll either commit or roll back at the end.
+pub struct RedbWriteTx {
+    tx: Option<WriteTransaction>,
+}
+
+impl RedbWriteTx {
+    // This method takes the transaction, so the panicky destructor knows it's been finished.
+    fn take_tx(mut self) -> WriteTransaction {
+        self.tx.take().expect("transaction is only taken once")
+    }
+
+    fn tx(&self) -> &WriteTransaction {
+        self.tx
+            .as_ref()
+            .expect("transaction is only taken at the end")
+    }
+}
+
+impl<S> KVReadable<S> for Redb
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    type Tx<'a>
+        = RedbReadTx
+    where
+        Self: 'a;
+
+    fn read(&self) -> Self::Tx<'_> {
+        let tx = self
+            .db
+            .begin_read()
+            .expect("failed to begin read transaction");
+
+        RedbReadTx { tx }
+    }
+}
+
+impl<S> KVWritable<S> for Redb
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    type Tx<'a>
+        = RedbWriteTx
+    where
+        Self: 'a;
+
+    /// Start a write transaction, blocking until any other write transaction finishes.
+    fn write(&self) -> Self::Tx<'_> {
+        let tx = self
+            .db
+            .begin_write()
+            .expect("failed to begin write transaction");
+
+        RedbWriteTx { tx: Some(tx) }
+    }
+}
+
+impl<S> KVRead<S> for RedbReadTx
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    fn get<K, V>(&self, ns: &S::Namespace, k: &K) -> KVResult<Option<V>>
+    where
+        S: Encode<K> + Decode<V>,
+    {
+        let key = S::to_repr(k)?;
+
+        let table = self
+            .tx
+            .open_table(table_def(ns.as_ref()))
+            .map_err(to_kv_error)?;
+
+        let res = table.get(key.as_slice()).map_err(to_kv_error)?;
+
+        match res {
+            Some(bz) => Ok(Some(S::from_repr(&bz.value().to_vec())?)),
+            None => Ok(None),
+        }
+    }
+
+    fn iterate<K, V>(&self, ns: &S::Namespace) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        S: Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        let it = BatchIter::new(move |after| {
+            let table = self
+                .tx
+                .open_table(table_def(ns.as_ref()))
+                .map_err(to_kv_error)?;
+
+            load_batch(&table, None, after).map_err(to_kv_error)
+        });
+
+        it.map(decode_entry::<S, K, V>)
+    }
+
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        let (from, err) = match S::to_repr(from) {
+            Ok(from) => (Some(from.into_owned()), None),
+            Err(e) => (None, Some(Err(e))),
+        };
+
+        let it = from.map(|from| {
+            BatchIter::new(move |after| {
+                let table = self
+                    .tx
+                    .open_table(table_def(ns.as_ref()))
+                    .map_err(to_kv_error)?;
+
+                load_batch(&table, Some(from.as_slice()), after).map_err(to_kv_error)
+            })
+        });
+
+        err.into_iter()
+            .chain(it.into_iter().flatten())
+            .map(decode_entry::<S, K, V>)
+    }
+}
+
+impl<S> KVRead<S> for RedbWriteTx
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    fn get<K, V>(&self, ns: &S::Namespace, k: &K) -> KVResult<Option<V>>
+    where
+        S: Encode<K> + Decode<V>,
+    {
+        let key = S::to_repr(k)?;
+
+        let table = self
+            .tx()
+            .open_table(table_def(ns.as_ref()))
+            .map_err(to_kv_error)?;
+
+        let res = table.get(key.as_slice()).map_err(to_kv_error)?;
+
+        match res {
+            Some(bz) => Ok(Some(S::from_repr(&bz.value().to_vec())?)),
+            None => Ok(None),
+        }
+    }
+
+    fn iterate<K, V>(&self, ns: &S::Namespace) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        S: Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        // A table can only be open once in a write transaction, so we can't
+        // hold on to it while the caller might be doing other operations.
+        let it = BatchIter::new(move |after| {
+            let table = self
+                .tx()
+                .open_table(table_def(ns.as_ref()))
+                .map_err(to_kv_error)?;
+
+            load_batch(&table, None, after).map_err(to_kv_error)
+        });
+
+        it.map(decode_entry::<S, K, V>)
+    }
+
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        let (from, err) = match S::to_repr(from) {
+            Ok(from) => (Some(from.into_owned()), None),
+            Err(e) => (None, Some(Err(e))),
+        };
+
+        let it = from.map(|from| {
+            BatchIter::new(move |after| {
+                let table = self
+                    .tx()
+                    .open_table(table_def(ns.as_ref()))
+                    .map_err(to_kv_error)?;
+
+                load_batch(&table, Some(from.as_slice()), after).map_err(to_kv_error)
+            })
+        });
+
+        err.into_iter()
+            .chain(it.into_iter().flatten())
+            .map(decode_entry::<S, K, V>)
+    }
+}
+
+impl<S> KVWrite<S> for RedbWriteTx
+where
+    S: KVStore<Repr = Vec<u8>>,
+    S::Namespace: AsRef<str>,
+{
+    fn put<K, V>(&mut self, ns: &S::Namespace, k: &K, v: &V) -> KVResult<()>
+    where
+        S: Encode<K> + Encode<V>,
+    {
+        let k = S::to_repr(k)?;
+        let v = S::to_repr(v)?;
+
+        let mut table = self
+            .tx()
+            .open_table(table_def(ns.as_ref()))
+            .map_err(to_kv_error)?;
+
+        table
+            .insert(k.as_slice(), v.as_slice())
+            .map_err(to_kv_error)?;
+
+        Ok(())
+    }
+
+    fn delete<K>(&mut self, ns: &S::Namespace, k: &K) -> KVResult<()>
+    where
+        S: Encode<K>,
+    {
+        let k = S::to_repr(k)?;
+
+        let mut table = self
+            .tx()
+            .open_table(table_def(ns.as_ref()))
+            .map_err(to_kv_error)?;
+
+        table.remove(k.as_slice()).map_err(to_kv_error)?;
+
+        Ok(())
+    }
+}
+
+impl KVTransaction for RedbWriteTx {
+    fn commit(self) -> KVResult<()> {
+        let tx = self.take_tx();
+        tx.commit().map_err(to_kv_error)
+    }
+
+    fn rollback(self) -> KVResult<()> {
+        let tx = self.take_tx();
+        tx.abort().map_err(to_kv_error)
+    }
+}
+
+impl Drop for RedbWriteTx {
+    fn drop(&mut self) {
+        if self.tx.is_some() && !thread::panicking() {
+            panic!("Transaction prematurely dropped. Must call `.commit()` or `.rollback()`.");
+        }
+    }
+}
+
+/// Iterate a table in batches, loading the entries after the last key seen so far.
+///
+/// This way we don't have to keep a table open between calls to `next`.
+struct BatchIter<F> {
+    load: F,
+    batch: VecDeque<Entry>,
+    last_key: Option<Vec<u8>>,
+    done: bool,
+}
+
+impl<F> BatchIter<F>
+where
+    F: Fn(Option<&[u8]>) -> KVResult<Vec<Entry>>,
+{
+    fn new(load: F) -> Self {
+        Self {
+            load,
+            batch: VecDeque::new(),
+            last_key: None,
+            done: false,
+        }
+    }
+}
+
+impl<F> Iterator for BatchIter<F>
+where
+    F: Fn(Option<&[u8]>) -> KVResult<Vec<Entry>>,
+{
+    type Item = KVResult<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.batch.is_empty() && !self.done {
+            match (self.load)(self.last_key.as_deref()) {
+                Ok(batch) => {
+                    self.done = batch.len() < ITER_BATCH_SIZE;
+                    self.batch.extend(batch);
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        let (k, v) = self.batch.pop_front()?;
+        self.last_key = Some(k.clone());
+        Some(Ok((k, v)))
+    }
+}
+
+/// Load the next batch of entries from a table, in the order of their keys,
+/// starting at `from` if it's the first batch.
+fn load_batch<T>(
+    table: &T,
+    from: Option<&[u8]>,
+    after: Option<&[u8]>,
+) -> Result<Vec<Entry>, StorageError>
+where
+    T: ReadableTable<Bytes, Bytes>,
+{
+    let range = match (after, from) {
+        (Some(key), _) => table.range::<&[u8]>((Bound::Excluded(key), Bound::Unbounded))?,
+        (None, Some(key)) => table.range::<&[u8]>((Bound::Included(key), Bound::Unbounded))?,
+        (None, None) => table.range::<&[u8]>(..)?,
+    };
+
+    range
+        .take(ITER_BATCH_SIZE)
+        .map(|res| res.map(|(k, v)| (k.value().to_vec(), v.value().to_vec())))
+        .collect()
+}
+
+fn decode_entry<S, K, V>(res: KVResult<Entry>) -> KVResult<(K, V)>
+where
+    S: KVStore<Repr = Vec<u8>> + Decode<K> + Decode<V>,
+{
+    let (k, v) = res?;
+    let k: K = S::from_repr(&k)?;
+    let v: V = S::from_repr(&v)?;
+    Ok((k, v))
+}
+
+fn to_kv_error(e: impl Into<redb::Error>) -> KVError {
+    KVError::Unexpected(Box::new(to_error(e)))
+}
+
+#[cfg(all(feature = "kvstore", test))]
+mod tests {
+    use std::borrow::Cow;
+
+    use quickcheck::{QuickCheck, Testable};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use fendermint_storage::{testing::*, Codec, Decode, Encode, KVError, KVResult, KVStore};
+
+    use crate::Redb;
+
+    const TEST_COUNT: u64 = 20;
+
+    #[derive(Clone)]
+    struct TestKVStore;
+
+    impl KVStore for TestKVStore {
+        type Namespace = TestNamespace;
+        type Repr = Vec<u8>;
+    }
+
+    impl<T: Serialize> Encode<T> for TestKVStore {
+        fn to_repr(value: &T) -> KVResult<Cow<Self::Repr>> {
+            fvm_ipld_encoding::to_vec(value)
+                .map_err(|e| KVError::Codec(Box::new(e)))
+                .map(Cow::Owned)
+        }
+    }
+    impl<T: DeserializeOwned> Decode<T> for TestKVStore {
+        fn from_repr(repr: &Self::Repr) -> KVResult<T> {
+            fvm_ipld_encoding::from_slice(repr).map_err(|e| KVError::Codec(Box::new(e)))
+        }
+    }
+
+    impl<T> Codec<T> for TestKVStore where TestKVStore: Encode<T> + Decode<T> {}
+
+    fn new_backend() -> Redb {
+        let dir = tempfile::Builder::new()
+            .tempdir()
+            .expect("error creating temporary path for db");
+        let path = dir.path().join("redb");
+
+        // Create the tables the test will use.
+        Redb::open_ns(path, test_namespaces().iter()).expect("error creating redb")
+    }
+
+    fn run_quickcheck<F: Testable>(f: F) {
+        QuickCheck::new().tests(TEST_COUNT).quickcheck(f)
+    }
+
+    #[test]
+    fn writable() {
+        run_quickcheck(
+            (|data| {
+                let backend = new_backend();
+                check_writable::<TestKVStore>(&backend, data)
+            }) as fn(TestData) -> bool,
+        )
+    }
+
+    // Not running `check_write_isolation` because two write transactions on the
+    // same thread would deadlock, as redb only allows one of them at a time.
+
+    #[test]
+    fn write_isolation_concurrent() {
+        run_quickcheck(
+            (|data1, data2| {
+                let backend = new_backend();
+                check_write_isolation_concurrent::<TestKVStore, _>(&backend, data1, data2)
+            }) as fn(TestData, TestData) -> bool,
+        )
+    }
+
+    #[test]
+    fn write_serialization_concurrent() {
+        run_quickcheck(
+            (|data1, data2| {
+                let backend = new_backend();
+                check_write_serialization_concurrent::<TestKVStore, _>(&backend, data1, data2)
+            }) as fn(TestData, TestData) -> bool,
+        )
+    }
+
+    #[test]
+    fn read_isolation() {
+        run_quickcheck(
+            (|data| {
+                let backend = new_backend();
+                check_read_isolation::<TestKVStore, _>(&backend, data)
+            }) as fn(TestData) -> bool,
+        )
+    }
+}
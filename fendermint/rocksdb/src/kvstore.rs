@@ -162,6 +162,42 @@ where
             })
             .expect("just wrapped into ok")
     }
+
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        let (from, err) = match S::to_repr(from) {
+            Ok(from) => (Some(from.into_owned()), None),
+            Err(e) => (None, Some(Err(e))),
+        };
+
+        let it = from.map(|from| {
+            self.cache
+                .with_cf_handle(ns.as_ref(), |cf| {
+                    let mode = rocksdb::IteratorMode::From(&from, rocksdb::Direction::Forward);
+                    let it = self.snapshot.iterator_cf(cf, mode);
+
+                    let it = it.map(|res| res.map_err(to_kv_error)).map(|res| {
+                        res.and_then(|(k, v)| {
+                            let k: K = S::from_repr(&k.to_vec())?;
+                            let v: V = S::from_repr(&v.to_vec())?;
+                            Ok((k, v))
+                        })
+                    });
+
+                    Ok(it)
+                })
+                .expect("just wrapped into ok")
+        });
+
+        err.into_iter().chain(it.into_iter().flatten())
+    }
 }
 
 impl<'a, S> KVRead<S> for RocksDbWriteTx<'a>
@@ -206,6 +242,42 @@ where
             })
             .expect("just wrapped into ok")
     }
+
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+    {
+        let (from, err) = match S::to_repr(from) {
+            Ok(from) => (Some(from.into_owned()), None),
+            Err(e) => (None, Some(Err(e))),
+        };
+
+        let it = from.map(|from| {
+            self.cache
+                .with_cf_handle(ns.as_ref(), |cf| {
+                    let mode = rocksdb::IteratorMode::From(&from, rocksdb::Direction::Forward);
+                    let it = self.tx.iterator_cf(cf, mode);
+
+                    let it = it.map(|res| res.map_err(to_kv_error)).map(|res| {
+                        res.and_then(|(k, v)| {
+                            let k: K = S::from_repr(&k.to_vec())?;
+                            let v: V = S::from_repr(&v.to_vec())?;
+                            Ok((k, v))
+                        })
+                    });
+
+                    Ok(it)
+                })
+                .expect("just wrapped into ok")
+        });
+
+        err.into_iter().chain(it.into_iter().flatten())
+    }
 }
 
 impl<'a, S> KVWrite<S> for RocksDbWriteTx<'a>
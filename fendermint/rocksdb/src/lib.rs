@@ -9,4 +9,6 @@ mod kvstore;
 
 pub mod namespaces;
 
+#[cfg(feature = "kvstore")]
+pub use kvstore::{RocksDbReadTx, RocksDbWriteTx};
 pub use rocks::{Error as RocksDbError, RocksDb, RocksDbConfig};
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use rocksdb::{
-    ColumnFamilyDescriptor, ErrorKind, OptimisticTransactionDB, Options, WriteBatchWithTransaction,
+    checkpoint::Checkpoint, ColumnFamilyDescriptor, ErrorKind, OptimisticTransactionDB, Options,
+    WriteBatchWithTransaction,
 };
 use std::{path::Path, sync::Arc};
 
@@ -136,6 +137,19 @@ impl RocksDb {
         self.db.flush().map_err(|e| Error::Other(e.to_string()))
     }
 
+    /// Create a consistent copy of all column families in a directory which must not exist yet.
+    ///
+    /// The memtables are flushed first, then the SST files are hard linked if the target is
+    /// on the same file system, otherwise copied. Writes are not blocked for longer than the flush.
+    pub fn create_checkpoint<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let checkpoint = Checkpoint::new(&*self.db)?;
+        checkpoint.create_checkpoint(path)?;
+        Ok(())
+    }
+
     /// Check if a column family exists
     pub fn has_cf_handle(&self, name: &str) -> bool {
         self.db.cf_handle(name).is_some()
@@ -154,3 +168,35 @@ impl RocksDb {
         Ok(name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{RocksDb, RocksDbConfig};
+
+    #[test]
+    fn checkpoint_has_all_column_families() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RocksDbConfig::default();
+
+        let db = RocksDb::open_cf(dir.path().join("db"), &config, ["foo"].iter()).unwrap();
+        db.write("spam", "eggs").unwrap();
+        db.db
+            .put_cf(&db.db.cf_handle("foo").unwrap(), "fizz", "buzz")
+            .unwrap();
+
+        db.create_checkpoint(dir.path().join("checkpoint")).unwrap();
+
+        // Writes after the checkpoint don't show up in it.
+        db.write("spam", "ham").unwrap();
+
+        let cp = RocksDb::open(dir.path().join("checkpoint"), &config).unwrap();
+        assert!(cp.has_cf_handle("foo"));
+        assert_eq!(cp.read("spam").unwrap(), Some(b"eggs".to_vec()));
+        assert_eq!(
+            cp.db
+                .get_cf(&cp.db.cf_handle("foo").unwrap(), "fizz")
+                .unwrap(),
+            Some(b"buzz".to_vec())
+        );
+    }
+}
@@ -7,7 +7,7 @@ use std::marker::PhantomData;
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use fendermint_vm_message::chain::ChainMessage;
-use tendermint::abci::response::DeliverTx;
+use tendermint::abci::types::ExecTxResult;
 use tendermint::block::Height;
 use tendermint_rpc::{endpoint::abci_query::AbciQuery, Client, HttpClient, Scheme, Url};
 use tendermint_rpc::{WebSocketClient, WebSocketClientDriver, WebSocketClientUrl};
@@ -134,6 +134,15 @@ where
     async fn perform(&self, query: FvmQuery, height: FvmQueryHeight) -> anyhow::Result<AbciQuery> {
         perform_query(&self.inner, query, height).await
     }
+
+    async fn perform_path(
+        &self,
+        path: &str,
+        data: Vec<u8>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<AbciQuery> {
+        perform_abci_query(&self.inner, Some(path.to_owned()), data, height).await
+    }
 }
 
 /// Fendermint client capable of signing transactions.
@@ -174,6 +183,15 @@ where
     async fn perform(&self, query: FvmQuery, height: FvmQueryHeight) -> anyhow::Result<AbciQuery> {
         perform_query(&self.inner, query, height).await
     }
+
+    async fn perform_path(
+        &self,
+        path: &str,
+        data: Vec<u8>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<AbciQuery> {
+        perform_abci_query(&self.inner, Some(path.to_owned()), data, height).await
+    }
 }
 
 #[async_trait]
@@ -183,7 +201,7 @@ where
 {
     async fn perform<F, T>(&self, msg: ChainMessage, _f: F) -> anyhow::Result<AsyncResponse<T>>
     where
-        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        F: FnOnce(&ExecTxResult) -> anyhow::Result<T> + Sync + Send,
     {
         let data = SignedMessageFactory::serialize(&msg)?;
         let response = self
@@ -210,7 +228,7 @@ where
         _f: F,
     ) -> anyhow::Result<crate::tx::SyncResponse<T>>
     where
-        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        F: FnOnce(&ExecTxResult) -> anyhow::Result<T> + Sync + Send,
     {
         let data = SignedMessageFactory::serialize(&msg)?;
         let response = self
@@ -237,7 +255,7 @@ where
         f: F,
     ) -> anyhow::Result<crate::tx::CommitResponse<T>>
     where
-        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        F: FnOnce(&ExecTxResult) -> anyhow::Result<T> + Sync + Send,
     {
         let data = SignedMessageFactory::serialize(&msg)?;
         let response = self
@@ -245,12 +263,12 @@ where
             .broadcast_tx_commit(data)
             .await
             .context("broadcast_tx_commit failed")?;
-        // We have a fully `DeliverTx` with default fields even if `CheckTx` indicates failure.
-        let return_data = if response.check_tx.code.is_err() || response.deliver_tx.code.is_err() {
+        // We have a fully `ExecTxResult` with default fields even if `CheckTx` indicates failure.
+        let return_data = if response.check_tx.code.is_err() || response.tx_result.code.is_err() {
             None
         } else {
             let return_data =
-                f(&response.deliver_tx).context("error decoding data from deliver_tx in commit")?;
+                f(&response.tx_result).context("error decoding data from deliver_tx in commit")?;
             Some(return_data)
         };
         let response = CommitResponse {
@@ -271,6 +289,18 @@ where
 {
     tracing::debug!(?query, ?height, "perform ABCI query");
     let data = fvm_ipld_encoding::to_vec(&query).context("failed to encode query")?;
+    perform_abci_query(client, None, data, height).await
+}
+
+async fn perform_abci_query<C>(
+    client: &C,
+    path: Option<String>,
+    data: Vec<u8>,
+    height: FvmQueryHeight,
+) -> anyhow::Result<AbciQuery>
+where
+    C: Client + Sync + Send,
+{
     let height: u64 = height.into();
     let height = Height::try_from(height).context("failed to conver to Height")?;
 
@@ -278,11 +308,11 @@ where
     // the `perform` method below with a request that prints the response if it fails
     // to deserialize for any reason.
     // let res = client
-    //     .abci_query(None, data, Some(height), false)
+    //     .abci_query(path, data, Some(height), false)
     //     .await
     //     .context("abci query failed")?;
 
-    let req = tendermint_rpc::endpoint::abci_query::Request::new(None, data, Some(height), false);
+    let req = tendermint_rpc::endpoint::abci_query::Request::new(path, data, Some(height), false);
 
     let res = client
         .perform(debug::DebugRequest(req))
@@ -8,7 +8,9 @@ use base64::Engine;
 use bytes::Bytes;
 use fendermint_crypto::SecretKey;
 use fendermint_vm_actor_interface::{eam, evm};
-use fendermint_vm_message::{chain::ChainMessage, signed::SignedMessage};
+use fendermint_vm_message::{
+    chain::ChainMessage, signed::SignedMessage, sponsored::SponsoredMessage,
+};
 use fvm_ipld_encoding::{BytesSer, RawBytes};
 use fvm_shared::{
     address::Address, chainid::ChainID, econ::TokenAmount, message::Message, MethodNum, METHOD_SEND,
@@ -181,12 +183,41 @@ impl SignedMessageFactory {
         value: TokenAmount,
         gas_params: GasParams,
     ) -> anyhow::Result<ChainMessage> {
+        let signed = self.signed_transaction(to, method_num, params, value, gas_params)?;
+        let chain = ChainMessage::Signed(signed);
+        Ok(chain)
+    }
+
+    /// Sign a message to an actor without wrapping it for sending, so it can be
+    /// passed on to a sponsor, who pays for the gas.
+    pub fn signed_transaction(
+        &mut self,
+        to: Address,
+        method_num: MethodNum,
+        params: RawBytes,
+        value: TokenAmount,
+        gas_params: GasParams,
+    ) -> anyhow::Result<SignedMessage> {
         let message = self
             .inner
             .transaction(to, method_num, params, value, gas_params);
         let signed = SignedMessage::new_secp256k1(message, &self.sk, &self.chain_id)?;
-        let chain = ChainMessage::Signed(signed);
-        Ok(chain)
+        Ok(signed)
+    }
+
+    /// Pay for the gas of a message signed by another account, which is executed as that account.
+    ///
+    /// It uses up a sequence of the sponsor, the same way sending a transaction would.
+    pub fn sponsor(&mut self, message: SignedMessage) -> anyhow::Result<ChainMessage> {
+        let sponsored = SponsoredMessage::new_secp256k1(
+            message,
+            self.inner.addr,
+            self.inner.sequence,
+            &self.sk,
+            &self.chain_id,
+        )?;
+        self.inner.sequence += 1;
+        Ok(ChainMessage::Sponsored(sponsored))
     }
 
     /// Deploy a FEVM contract.
@@ -6,20 +6,28 @@ use async_trait::async_trait;
 use fvm_ipld_encoding::serde::Serialize;
 use fvm_shared::message::Message;
 use prost::Message as ProstMessage;
-use tendermint::abci::response::DeliverTx;
+use tendermint::abci::types::ExecTxResult;
 use tendermint::block::Height;
-use tendermint::v0_37::abci::response;
 use tendermint_rpc::endpoint::abci_query::AbciQuery;
 
 use cid::Cid;
+use fendermint_actor_chainmetadata::BlockHashEntry;
+use fendermint_actor_eam::{ExtraMethods, PermissionModeParams};
+use fendermint_actor_vesting::{State as VestingState, VestingInfo};
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::ActorID;
 use fvm_shared::{address::Address, error::ExitCode};
+use num_traits::Zero;
 
+use fendermint_vm_actor_interface::{eam, system};
 use fendermint_vm_message::query::{
     ActorState, BuiltinActors, FvmQuery, FvmQueryHeight, GasEstimate, StateParams,
+    PARENT_FINALITY_CERTIFICATE_PATH, PARENT_VIEW_PATH,
 };
 
-use crate::response::encode_data;
+use crate::response::{decode_data, encode_data};
 
 #[derive(Serialize, Debug, Clone)]
 /// The parsed value from a query, along with the height at which the query was performed.
@@ -55,12 +63,68 @@ pub trait QueryClient: Sync {
         Ok(QueryResponse { height, value })
     }
 
+    /// Query the vested and unvested balances of a vesting actor at the height of the query.
+    async fn vesting_info(
+        &self,
+        address: &Address,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<QueryResponse<Option<VestingInfo>>> {
+        let res = self.actor_state(address, height).await?;
+        let height = res.height;
+        let value = match res.value {
+            None => None,
+            Some((_, actor)) => {
+                // Query the state at the same height the actor was found at.
+                let bz = self
+                    .ipld(&actor.state, FvmQueryHeight::Height(height.value()))
+                    .await?
+                    .ok_or_else(|| anyhow!("cannot find the state of actor {address}"))?;
+                let state: VestingState = fvm_ipld_encoding::from_slice(&bz)
+                    .with_context(|| format!("actor {address} is not a vesting actor"))?;
+                let epoch = height.value() as ChainEpoch;
+                Some(VestingInfo::new(&state, &actor.balance, epoch))
+            }
+        };
+        Ok(QueryResponse { height, value })
+    }
+
+    /// Query the permission mode of the EAM actor, which lists the deployers in allow-list mode.
+    async fn eam_permission_mode(
+        &self,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<QueryResponse<PermissionModeParams>> {
+        // Calls from the system actor are executed implicitly, so no account is needed.
+        let message = Message {
+            version: Default::default(),
+            from: system::SYSTEM_ACTOR_ADDR,
+            to: eam::EAM_ACTOR_ADDR,
+            sequence: 0,
+            value: TokenAmount::zero(),
+            method_num: ExtraMethods::ListDeployers as u64,
+            params: RawBytes::default(),
+            gas_limit: 0,
+            gas_fee_cap: TokenAmount::zero(),
+            gas_premium: TokenAmount::zero(),
+        };
+        let res = self.call(message, height).await?;
+        if res.value.code.is_err() {
+            return Err(anyhow!("failed to list deployers: {}", res.value.info));
+        }
+        let data = decode_data(&res.value.data)?;
+        let value = fvm_ipld_encoding::from_slice(&data)
+            .context("failed to decode PermissionModeParams from call")?;
+        Ok(QueryResponse {
+            height: res.height,
+            value,
+        })
+    }
+
     /// Run a message in a read-only fashion.
     async fn call(
         &self,
         message: Message,
         height: FvmQueryHeight,
-    ) -> anyhow::Result<QueryResponse<response::DeliverTx>> {
+    ) -> anyhow::Result<QueryResponse<ExecTxResult>> {
         let res = self
             .perform(FvmQuery::Call(Box::new(message)), height)
             .await
@@ -128,8 +192,71 @@ pub trait QueryClient: Sync {
         Ok(QueryResponse { height, value })
     }
 
+    /// Query the block hashes kept by the chain metadata actor in an inclusive range of heights.
+    ///
+    /// Heights which are not in the lookback window of the actor, or were null rounds, are missing.
+    async fn block_hashes(
+        &self,
+        from: ChainEpoch,
+        to: ChainEpoch,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<QueryResponse<Vec<BlockHashEntry>>> {
+        let res = self
+            .perform(FvmQuery::BlockHashes { from, to }, height)
+            .await
+            .context("block hashes query failed")?;
+        let height = res.height;
+        let value = extract(res, |res| {
+            fvm_ipld_encoding::from_slice(&res.value)
+                .context("failed to decode block hashes from query")
+        })?;
+        Ok(QueryResponse { height, value })
+    }
+
+    /// Query the quorum certificate of the parent finality committed at a parent block height.
+    ///
+    /// The response is the IPLD encoded `fendermint_vm_topdown::certificate::QuorumCertificate`,
+    /// or `None` if this node doesn't have one for that height.
+    async fn parent_finality_certificate(
+        &self,
+        parent_height: u64,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let data = fvm_ipld_encoding::to_vec(&parent_height).context("failed to encode height")?;
+        let res = self
+            .perform_path(
+                PARENT_FINALITY_CERTIFICATE_PATH,
+                data,
+                FvmQueryHeight::Committed,
+            )
+            .await
+            .context("parent finality certificate query failed")?;
+        extract_opt(res, |res| Ok(res.value))
+    }
+
+    /// Query the parent blocks the node has cached for top-down finality.
+    ///
+    /// The response is the IPLD encoded list of `fendermint_vm_topdown::store::ParentViewEntry`,
+    /// along with the height of the last committed block.
+    async fn parent_view(&self) -> anyhow::Result<QueryResponse<Vec<u8>>> {
+        let res = self
+            .perform_path(PARENT_VIEW_PATH, Vec::new(), FvmQueryHeight::Committed)
+            .await
+            .context("parent view query failed")?;
+        let height = res.height;
+        let value = extract(res, |res| Ok(res.value))?;
+        Ok(QueryResponse { height, value })
+    }
+
     /// Run an ABCI query.
     async fn perform(&self, query: FvmQuery, height: FvmQueryHeight) -> anyhow::Result<AbciQuery>;
+
+    /// Run an ABCI query on a path answered by the application itself, rather than the FVM.
+    async fn perform_path(
+        &self,
+        path: &str,
+        data: Vec<u8>,
+        height: FvmQueryHeight,
+    ) -> anyhow::Result<AbciQuery>;
 }
 
 /// Extract some value from the query result, unless it's not found or other error.
@@ -174,20 +301,29 @@ fn is_not_found(res: &AbciQuery) -> bool {
     res.code.value() == ExitCode::USR_NOT_FOUND.value()
 }
 
-fn parse_deliver_tx(res: AbciQuery) -> anyhow::Result<DeliverTx> {
+/// Parse the result of a read-only call, which the application sends back as a `DeliverTx`,
+/// into the [`ExecTxResult`] which replaced it in ABCI 2.0, so there is one representation for both.
+fn parse_deliver_tx(res: AbciQuery) -> anyhow::Result<ExecTxResult> {
     let bz: Vec<u8> =
         fvm_ipld_encoding::from_slice(&res.value).context("failed to decode IPLD as bytes")?;
 
-    let deliver_tx = tendermint_proto::abci::ResponseDeliverTx::decode(bz.as_ref())
+    let deliver_tx = tendermint_proto::v0_37::abci::ResponseDeliverTx::decode(bz.as_ref())
         .context("failed to deserialize ResponseDeliverTx from proto bytes")?;
 
-    let mut deliver_tx = tendermint::abci::response::DeliverTx::try_from(deliver_tx)
+    let deliver_tx = tendermint::abci::response::DeliverTx::try_from(deliver_tx)
         .context("failed to create DeliverTx from proto response")?;
 
-    // Mimic the Base64 encoding of the value that Tendermint does.
-    deliver_tx.data = encode_data(&deliver_tx.data);
-
-    Ok(deliver_tx)
+    Ok(ExecTxResult {
+        code: deliver_tx.code,
+        // Mimic the Base64 encoding of the value that Tendermint does.
+        data: encode_data(&deliver_tx.data),
+        log: deliver_tx.log,
+        info: deliver_tx.info,
+        gas_wanted: deliver_tx.gas_wanted,
+        gas_used: deliver_tx.gas_used,
+        events: deliver_tx.events,
+        codespace: deliver_tx.codespace,
+    })
 }
 
 #[cfg(test)]
@@ -5,9 +5,9 @@ use base64::Engine;
 use bytes::Bytes;
 use fendermint_vm_actor_interface::eam::{self, CreateReturn};
 use fvm_ipld_encoding::{BytesDe, RawBytes};
-use tendermint::abci::response::DeliverTx;
+use tendermint::abci::types::ExecTxResult;
 
-/// Parse what Tendermint returns in the `data` field of [`DeliverTx`] into bytes.
+/// Parse what Tendermint returns in the `data` field of [`ExecTxResult`] into bytes.
 /// Somewhere along the way it replaces them with the bytes of a Base64 encoded string,
 /// and `tendermint_rpc` does not undo that wrapping.
 pub fn decode_data(data: &Bytes) -> anyhow::Result<RawBytes> {
@@ -18,29 +18,29 @@ pub fn decode_data(data: &Bytes) -> anyhow::Result<RawBytes> {
     Ok(RawBytes::from(data))
 }
 
-/// Apply the encoding that Tendermint does to the bytes inside [`DeliverTx`].
+/// Apply the encoding that Tendermint does to the bytes inside [`ExecTxResult`].
 pub fn encode_data(data: &[u8]) -> Bytes {
     let b64 = base64::engine::general_purpose::STANDARD.encode(data);
     let bz = b64.as_bytes();
     Bytes::copy_from_slice(bz)
 }
 
-/// Parse what Tendermint returns in the `data` field of [`DeliverTx`] as raw bytes.
+/// Parse what Tendermint returns in the `data` field of [`ExecTxResult`] as raw bytes.
 ///
-/// Only call this after the `code` of both [`DeliverTx`] and [`CheckTx`] have been inspected!
-pub fn decode_bytes(deliver_tx: &DeliverTx) -> anyhow::Result<RawBytes> {
+/// Only call this after the `code` of both [`ExecTxResult`] and [`CheckTx`] have been inspected!
+pub fn decode_bytes(deliver_tx: &ExecTxResult) -> anyhow::Result<RawBytes> {
     decode_data(&deliver_tx.data)
 }
 
-/// Parse what Tendermint returns in the `data` field of [`DeliverTx`] as [`CreateReturn`].
-pub fn decode_fevm_create(deliver_tx: &DeliverTx) -> anyhow::Result<CreateReturn> {
+/// Parse what Tendermint returns in the `data` field of [`ExecTxResult`] as [`CreateReturn`].
+pub fn decode_fevm_create(deliver_tx: &ExecTxResult) -> anyhow::Result<CreateReturn> {
     let data = decode_data(&deliver_tx.data)?;
     fvm_ipld_encoding::from_slice::<eam::CreateReturn>(&data)
         .map_err(|e| anyhow!("error parsing as CreateReturn: {e}"))
 }
 
-/// Parse what Tendermint returns in the `data` field of [`DeliverTx`] as raw ABI return value.
-pub fn decode_fevm_invoke(deliver_tx: &DeliverTx) -> anyhow::Result<Vec<u8>> {
+/// Parse what Tendermint returns in the `data` field of [`ExecTxResult`] as raw ABI return value.
+pub fn decode_fevm_invoke(deliver_tx: &ExecTxResult) -> anyhow::Result<Vec<u8>> {
     let data = decode_data(&deliver_tx.data)?;
     decode_fevm_return_data(data)
 }
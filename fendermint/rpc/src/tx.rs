@@ -7,15 +7,17 @@ use anyhow::Context;
 use async_trait::async_trait;
 use bytes::Bytes;
 use fendermint_vm_message::query::{FvmQueryHeight, GasEstimate};
-use tendermint::abci::response::DeliverTx;
+use tendermint::abci::types::ExecTxResult;
 use tendermint_rpc::endpoint::broadcast::{tx_async, tx_commit, tx_sync};
 
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::MethodNum;
+use num_traits::Zero;
 
-use fendermint_vm_actor_interface::eam::CreateReturn;
+use fendermint_actor_eam::{ExtraMethods, PermissionModeParams};
+use fendermint_vm_actor_interface::eam::{self, CreateReturn};
 use fendermint_vm_message::chain::ChainMessage;
 
 use crate::message::{GasParams, SignedMessageFactory};
@@ -99,9 +101,89 @@ pub trait TxClient<M: BroadcastMode = TxCommit>: BoundClient + Send + Sync {
         Ok(res)
     }
 
+    /// Add deployers to the allow-list of the EAM actor; only its admin can do this.
+    async fn eam_add_deployers(
+        &mut self,
+        deployers: Vec<Address>,
+        gas_params: GasParams,
+    ) -> anyhow::Result<M::Response<()>> {
+        let params = RawBytes::serialize(deployers)?;
+        let mf = self.message_factory_mut();
+        let msg = mf.transaction(
+            eam::EAM_ACTOR_ADDR,
+            ExtraMethods::AddDeployers as u64,
+            params,
+            TokenAmount::zero(),
+            gas_params,
+        )?;
+        let fut = self.perform(msg, |_| Ok(()));
+        let res = fut.await?;
+        Ok(res)
+    }
+
+    /// Remove deployers from the allow-list of the EAM actor; only its admin can do this.
+    async fn eam_remove_deployers(
+        &mut self,
+        deployers: Vec<Address>,
+        gas_params: GasParams,
+    ) -> anyhow::Result<M::Response<()>> {
+        let params = RawBytes::serialize(deployers)?;
+        let mf = self.message_factory_mut();
+        let msg = mf.transaction(
+            eam::EAM_ACTOR_ADDR,
+            ExtraMethods::RemoveDeployers as u64,
+            params,
+            TokenAmount::zero(),
+            gas_params,
+        )?;
+        let fut = self.perform(msg, |_| Ok(()));
+        let res = fut.await?;
+        Ok(res)
+    }
+
+    /// Switch the EAM actor between unrestricted and allow-list modes; only its admin can do this.
+    async fn eam_set_permission_mode(
+        &mut self,
+        mode: PermissionModeParams,
+        gas_params: GasParams,
+    ) -> anyhow::Result<M::Response<()>> {
+        let params = RawBytes::serialize(mode)?;
+        let mf = self.message_factory_mut();
+        let msg = mf.transaction(
+            eam::EAM_ACTOR_ADDR,
+            ExtraMethods::SetPermissionMode as u64,
+            params,
+            TokenAmount::zero(),
+            gas_params,
+        )?;
+        let fut = self.perform(msg, |_| Ok(()));
+        let res = fut.await?;
+        Ok(res)
+    }
+
+    /// Hand over the administration of the EAM actor, or renounce it with `None`; only its admin can do this.
+    async fn eam_change_admin(
+        &mut self,
+        admin: Option<Address>,
+        gas_params: GasParams,
+    ) -> anyhow::Result<M::Response<()>> {
+        let params = RawBytes::serialize(admin)?;
+        let mf = self.message_factory_mut();
+        let msg = mf.transaction(
+            eam::EAM_ACTOR_ADDR,
+            ExtraMethods::ChangeAdmin as u64,
+            params,
+            TokenAmount::zero(),
+            gas_params,
+        )?;
+        let fut = self.perform(msg, |_| Ok(()));
+        let res = fut.await?;
+        Ok(res)
+    }
+
     async fn perform<F, T>(&self, msg: ChainMessage, f: F) -> anyhow::Result<M::Response<T>>
     where
-        F: FnOnce(&DeliverTx) -> anyhow::Result<T> + Sync + Send,
+        F: FnOnce(&ExecTxResult) -> anyhow::Result<T> + Sync + Send,
         T: Sync + Send;
 }
 
@@ -187,7 +269,7 @@ pub struct CommitResponse<T> {
 
 pub struct CallResponse<T> {
     /// Response from Tendermint.
-    pub response: QueryResponse<tendermint::abci::response::DeliverTx>,
+    pub response: QueryResponse<ExecTxResult>,
     /// Parsed return data, if the response indicates success.
     pub return_data: Option<T>,
 }
@@ -175,6 +175,35 @@ where
             KVIter::empty()
         }
     }
+
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static,
+        K: 'static,
+        V: 'static,
+    {
+        let from = match S::to_repr(from) {
+            Ok(from) => from.into_owned(),
+            Err(e) => return KVIter::failed(e),
+        };
+        if let Some(m) = self.data.get(ns) {
+            let mut items = m
+                .iter()
+                .filter(|(k, _)| **k >= from)
+                .map(|(k, v)| (k, v.as_ref()))
+                .collect::<Vec<_>>();
+            items.sort_by(|a, b| a.0.cmp(b.0));
+
+            KVIter::<S, K, V>::new(items)
+        } else {
+            KVIter::empty()
+        }
+    }
 }
 
 impl<'a, S: KVStore> KVWrite<S> for Transaction<'a, S, Write>
@@ -209,6 +238,8 @@ where
 struct KVIter<'a, S: KVStore, K, V> {
     items: Vec<(&'a S::Repr, &'a S::Repr)>,
     next: usize,
+    /// Error to return before anything else, if the iteration could not be started.
+    error: Option<KVError>,
     phantom_v: PhantomData<V>,
     phantom_k: PhantomData<K>,
 }
@@ -221,6 +252,7 @@ where
         KVIter {
             items,
             next: 0,
+            error: None,
             phantom_v: PhantomData,
             phantom_k: PhantomData,
         }
@@ -229,6 +261,13 @@ where
     pub fn empty() -> Self {
         Self::new(vec![])
     }
+
+    pub fn failed(error: KVError) -> Self {
+        KVIter {
+            error: Some(error),
+            ..Self::empty()
+        }
+    }
 }
 
 impl<'a, S, K, V> Iterator for KVIter<'a, S, K, V>
@@ -238,6 +277,9 @@ where
     type Item = Result<(K, V), KVError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
         if let Some((k, v)) = self.items.get(self.next) {
             self.next += 1;
             let kv = S::from_repr(k).and_then(|k| S::from_repr(v).map(|v| (k, v)));
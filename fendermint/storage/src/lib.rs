@@ -76,6 +76,19 @@ pub trait KVRead<S: KVStore> {
         V: 'static,
         S: Decode<K> + Decode<V>,
         <S as KVStore>::Repr: Ord + 'static;
+
+    /// Iterate items in the namespace ordered by their representation,
+    /// starting at the first key which is not less than `from`.
+    fn iterate_from<K, V>(
+        &self,
+        ns: &S::Namespace,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>>
+    where
+        K: 'static,
+        V: 'static,
+        S: Encode<K> + Decode<K> + Decode<V>,
+        <S as KVStore>::Repr: Ord + 'static;
 }
 
 /// Operations available on a write transaction.
@@ -180,4 +193,19 @@ where
     {
         kv.iterate::<K, V>(&self.ns)
     }
+
+    pub fn iterate_from<'a, 'b>(
+        &'a self,
+        kv: &'b impl KVRead<S>,
+        from: &K,
+    ) -> impl Iterator<Item = KVResult<(K, V)>> + 'b
+    where
+        S::Repr: Ord + 'static,
+        S: Decode<K>,
+        K: 'static,
+        V: 'static,
+        'a: 'b,
+    {
+        kv.iterate_from::<K, V>(&self.ns, from)
+    }
 }
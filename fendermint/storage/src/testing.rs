@@ -26,6 +26,7 @@ pub enum TestOpKV<K, V> {
     Put(K, V),
     Del(K),
     Iter,
+    IterFrom(K),
 }
 
 #[derive(Clone, Debug)]
@@ -52,20 +53,22 @@ impl Arbitrary for TestOpNs {
             i if i < 47 => {
                 let ns = g.choose(&["spam", "eggs"]).unwrap();
                 let k = *g.choose(&["foo", "bar", "baz"]).unwrap();
-                match u8::arbitrary(g) % 10 {
+                match u8::arbitrary(g) % 11 {
                     i if i < 3 => S2I(ns, Get(k.to_owned())),
                     i if i < 4 => S2I(ns, Iter),
-                    i if i < 9 => S2I(ns, Put(k.to_owned(), Arbitrary::arbitrary(g))),
+                    i if i < 5 => S2I(ns, IterFrom(k.to_owned())),
+                    i if i < 10 => S2I(ns, Put(k.to_owned(), Arbitrary::arbitrary(g))),
                     _ => S2I(ns, Del(k.to_owned())),
                 }
             }
             i if i < 94 => {
                 let ns = g.choose(&["fizz", "buzz"]).unwrap();
                 let k = u8::arbitrary(g) % 3;
-                match u8::arbitrary(g) % 10 {
+                match u8::arbitrary(g) % 11 {
                     i if i < 3 => I2S(ns, Get(k)),
                     i if i < 4 => I2S(ns, Iter),
-                    i if i < 9 => {
+                    i if i < 5 => I2S(ns, IterFrom(k)),
+                    i if i < 10 => {
                         let sz = u8::arbitrary(g) % 5;
                         let s = (0..sz).map(|_| char::arbitrary(g)).collect();
                         I2S(ns, Put(k, s))
@@ -416,12 +419,23 @@ where
             coll.delete(tx, &k).unwrap();
             model.entry(ns).or_default().remove(&k);
         }
-        TestOpKV::Iter => {
-            let found = coll.iterate(tx).collect::<Result<Vec<_>, _>>().unwrap();
+        TestOpKV::Iter | TestOpKV::IterFrom(_) => {
+            let (found, from) = match op {
+                TestOpKV::IterFrom(k) => (
+                    coll.iterate_from(tx, &k).collect::<Result<Vec<_>, _>>(),
+                    Some(S::to_repr(&k).unwrap().into_owned()),
+                ),
+                _ => (coll.iterate(tx).collect::<Result<Vec<_>, _>>(), None),
+            };
+            let found = found.unwrap();
 
             let expected = if let Some(m) = model.get(ns) {
                 let mut expected = m
                     .iter()
+                    .filter(|(k, _)| match from {
+                        Some(ref from) => *S::to_repr(k).unwrap() >= *from,
+                        None => true,
+                    })
                     .map(|(k, v)| (k.clone(), v.clone()))
                     .collect::<Vec<_>>();
 
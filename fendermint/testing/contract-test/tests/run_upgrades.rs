@@ -16,17 +16,24 @@ use ethers::contract::abigen;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::Zero;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
 use fvm_shared::version::NetworkVersion;
 use tendermint_rpc::Client;
 
+use fendermint_actor_chainmetadata::{BlockHashEntry, GetBlockHashesParams};
 use fendermint_crypto::SecretKey;
 use fendermint_vm_actor_interface::eam;
 use fendermint_vm_actor_interface::eam::EthAddress;
+use fendermint_vm_actor_interface::{chainmetadata, system};
 use fendermint_vm_core::Timestamp;
 use fendermint_vm_genesis::{Account, Actor, ActorMeta, Genesis, PermissionMode, SignerAddr};
+use fendermint_vm_interpreter::fvm::state::FvmExecState;
 use fendermint_vm_interpreter::fvm::store::memory::MemoryBlockstore;
-use fendermint_vm_interpreter::fvm::upgrades::{Upgrade, UpgradeScheduler};
-use fendermint_vm_interpreter::fvm::FvmMessageInterpreter;
+use fendermint_vm_interpreter::fvm::upgrades::{
+    set_chainmetadata_lookback_len, set_eam_admin, Upgrade, UpgradeScheduler,
+};
+use fendermint_vm_interpreter::fvm::{FvmMessage, FvmMessageInterpreter};
+use fvm_ipld_encoding::RawBytes;
 
 // returns a seeded secret key which is guaranteed to be the same every time
 fn my_secret_key() -> SecretKey {
@@ -194,6 +201,91 @@ async fn test_applying_upgrades() {
         )
         .unwrap();
 
+    upgrade_scheduler
+        .add(
+            Upgrade::new(CHAIN_NAME, 4, None, |state| {
+                println!(
+                    "[Upgrade at height {}] Shrinks the block hash lookback",
+                    state.block_height()
+                );
+
+                set_chainmetadata_lookback_len(state, 2).unwrap();
+
+                // the hashes of the blocks before this one have been pushed, only the last 2 are kept
+                let params = RawBytes::serialize(GetBlockHashesParams { from: 0, to: 10 }).unwrap();
+                let message = FvmMessage {
+                    from: system::SYSTEM_ACTOR_ADDR,
+                    to: chainmetadata::CHAINMETADATA_ACTOR_ADDR,
+                    sequence: state.block_height() as u64,
+                    gas_limit: fvm_shared::BLOCK_GAS_LIMIT,
+                    method_num: fendermint_actor_chainmetadata::Method::GetBlockHashes as u64,
+                    params,
+                    value: Default::default(),
+                    version: Default::default(),
+                    gas_fee_cap: Default::default(),
+                    gas_premium: Default::default(),
+                };
+
+                let (res, _) = state.execute_implicit(message).unwrap();
+                assert!(
+                    res.msg_receipt.exit_code.is_success(),
+                    "{:?}",
+                    res.failure_info
+                );
+
+                let entries = fvm_ipld_encoding::from_slice::<Vec<BlockHashEntry>>(
+                    &res.msg_receipt.return_data,
+                )
+                .unwrap();
+                let epochs = entries.iter().map(|e| e.epoch).collect::<Vec<_>>();
+                assert_eq!(epochs, vec![2, 3]);
+
+                Ok(())
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+    upgrade_scheduler
+        .add(
+            Upgrade::new(CHAIN_NAME, 5, None, |state| {
+                println!(
+                    "[Upgrade at height {}] Installs an EAM admin",
+                    state.block_height()
+                );
+
+                // an account changing the admin of the EAM, which only the current admin can do
+                let change_admin = |state: &mut FvmExecState<MemoryBlockstore>| {
+                    let message = FvmMessage {
+                        from: *ADDR,
+                        to: eam::EAM_ACTOR_ADDR,
+                        sequence: 1,
+                        gas_limit: GAS_PARAMS.gas_limit,
+                        method_num: fendermint_actor_eam::ExtraMethods::ChangeAdmin as u64,
+                        params: RawBytes::serialize(Some(*ADDR)).unwrap(),
+                        value: Default::default(),
+                        version: Default::default(),
+                        gas_fee_cap: Default::default(),
+                        gas_premium: Default::default(),
+                    };
+                    let (res, _) = state.execute_implicit(message).unwrap();
+                    res.msg_receipt.exit_code
+                };
+
+                // the genesis has no admin, so nobody can take over
+                assert_eq!(change_admin(state), ExitCode::USR_FORBIDDEN);
+
+                set_eam_admin(state, Some(*ADDR)).unwrap();
+
+                // now the account is the admin
+                assert!(change_admin(state).is_success());
+
+                Ok(())
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
     let interpreter: FvmMessageInterpreter<MemoryBlockstore, _> =
         FvmMessageInterpreter::new(NeverCallClient, None, 1.05, 1.05, false, upgrade_scheduler);
 
@@ -211,7 +303,9 @@ async fn test_applying_upgrades() {
             balance: TokenAmount::from_atto(0),
         }],
         eam_permission_mode: PermissionMode::Unrestricted,
+        eam_admin: None,
         ipc: None,
+        contracts: Vec::new(),
     };
 
     let mut tester = Tester::new(interpreter, genesis).await.unwrap();
@@ -220,7 +314,7 @@ async fn test_applying_upgrades() {
     assert_eq!(tester.state_params().app_version, 0);
 
     // iterate over all the upgrades
-    for block_height in 1..=3 {
+    for block_height in 1..=5 {
         tester.begin_block(block_height).await.unwrap();
         tester.end_block(block_height).await.unwrap();
         tester.commit().await.unwrap();
@@ -585,7 +585,9 @@ impl arbitrary::Arbitrary<'_> for StakingState {
             validators: parent_validators,
             accounts: parent_actors,
             eam_permission_mode: PermissionMode::Unrestricted,
+            eam_admin: None,
             ipc: Some(parent_ipc),
+            contracts: Vec::new(),
         };
 
         let child_ipc = IpcParams {
@@ -606,7 +608,9 @@ impl arbitrary::Arbitrary<'_> for StakingState {
             validators: current_configuration,
             accounts: Vec::new(),
             eam_permission_mode: PermissionMode::Unrestricted,
+            eam_admin: None,
             ipc: Some(child_ipc),
+            contracts: Vec::new(),
         };
 
         Ok(StakingState::new(accounts, parent_genesis, child_genesis))
@@ -315,6 +315,7 @@ impl DockerMaterializer {
         let mut config = if !file_name.exists() {
             IpcCliConfig {
                 keystore_path: Some("~/.ipc".to_string()),
+                encrypted_evm_keystore: false,
                 subnets: Default::default(),
             }
         } else {
@@ -687,6 +688,7 @@ impl Materializer<DockerMaterials> for DockerMaterializer {
                     })
                     .collect(),
                 eam_permission_mode: fendermint_vm_genesis::PermissionMode::Unrestricted,
+                eam_admin: None,
                 ipc: Some(IpcParams {
                     gateway: GatewayParams {
                         subnet_id: SubnetID::new_root(chain_id.into()),
@@ -696,6 +698,7 @@ impl Materializer<DockerMaterials> for DockerMaterializer {
                         active_validators_limit: 100,
                     },
                 }),
+                contracts: Vec::new(),
             };
             Ok(genesis)
         })
@@ -1067,6 +1070,7 @@ mod tests {
     fn test_ipc_cli_config_toml_roundtrip() {
         let mut config0 = IpcCliConfig {
             keystore_path: Some("~/.ipc".to_string()),
+            encrypted_evm_keystore: false,
             subnets: Default::default(),
         };
 
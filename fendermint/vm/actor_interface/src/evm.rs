@@ -1,9 +1,13 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::borrow::Cow;
+
 use cid::Cid;
-use fvm_ipld_encoding::RawBytes;
-use fvm_shared::METHOD_CONSTRUCTOR;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::{strict_bytes, RawBytes};
+use fvm_ipld_kamt::{AsHashedKey, Config as KamtConfig, Kamt};
+use fvm_shared::{ActorID, METHOD_CONSTRUCTOR};
 use serde_tuple::{Deserialize_tuple, Serialize_tuple};
 
 pub use fil_actors_evm_shared::uints;
@@ -46,6 +50,65 @@ pub struct GetStorageAtReturn {
     pub storage: uints::U256,
 }
 
+/// The state of the EVM actor, copied from `fil_actor_evm` v12.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// The EVM contract bytecode resulting from calling the
+    /// initialization code by the constructor.
+    pub bytecode: Cid,
+    /// The EVM contract bytecode hash keccak256(bytecode).
+    #[serde(with = "strict_bytes")]
+    pub bytecode_hash: Vec<u8>,
+    /// The EVM contract state dictionary: a KAMT of 256 bit keys to 256 bit values.
+    pub contract_state: Cid,
+    /// The EVM nonce used to track how many times CREATE or CREATE2 have been called.
+    pub nonce: u64,
+    /// Possibly a tombstone if this actor has been self-destructed.
+    pub tombstone: Option<Tombstone>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tombstone {
+    /// The message origin when this actor was self-destructed.
+    pub origin: ActorID,
+    /// The message nonce when this actor was self-destructed.
+    pub nonce: u64,
+}
+
+/// The storage of a contract, with the same configuration the EVM actor uses.
+pub type StorageKamt<BS> = Kamt<BS, uints::U256, uints::U256, StorageHashAlgorithm>;
+
+/// Keys are stored as-is, without hashing.
+pub struct StorageHashAlgorithm;
+
+impl AsHashedKey<uints::U256, 32> for StorageHashAlgorithm {
+    fn as_hashed_key(key: &uints::U256) -> Cow<[u8; 32]> {
+        let mut k = [0u8; 32];
+        key.to_big_endian(&mut k);
+        Cow::Owned(k)
+    }
+}
+
+/// Configuration of the contract storage KAMT in the EVM actor.
+pub fn storage_kamt_config() -> KamtConfig {
+    KamtConfig {
+        min_data_depth: 0,
+        bit_width: 5,
+        max_array_width: 1,
+    }
+}
+
+impl State {
+    /// Load the storage of the contract.
+    pub fn storage<BS: Blockstore>(&self, store: BS) -> anyhow::Result<StorageKamt<BS>> {
+        Ok(Kamt::load_with_config(
+            &self.contract_state,
+            store,
+            storage_kamt_config(),
+        )?)
+    }
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ConstructorParams {
     /// The actor's "creator" (specified by the EAM).
@@ -60,6 +60,8 @@ impl State {
         eth_builtin_ids: &BTreeSet<ActorID>,
         // Number of dynamically deployed EVM library contracts.
         eth_library_count: u64,
+        // Contracts from the Genesis file, deployed after the libraries, with their optional fixed address.
+        eth_contract_addrs: &[Option<EthAddress>],
     ) -> anyhow::Result<(Self, AddressMap)> {
         // Returning only the addreses that belong to user accounts.
         let mut allocated_ids = AddressMap::new();
@@ -80,6 +82,7 @@ impl State {
                 vec![acc.owner.0]
             }
             ActorMeta::Multisig(ms) => ms.signers.iter().map(|a| a.0).collect(),
+            ActorMeta::Vesting(v) => v.signers().map(|a| a.0).collect(),
         });
 
         let mut next_id = FIRST_NON_SINGLETON_ADDR;
@@ -93,12 +96,12 @@ impl State {
             next_id += 1;
         }
 
-        // We will need to allocate an ID for each multisig account, however,
+        // We will need to allocate an ID for each multisig and vesting account, however,
         // these do not have to be recorded in the map, because their addr->ID
         // mapping is trivial (it's an ID type address). To avoid the init actor
         // using the same ID for something else, give it a higher ID to use next.
         for a in accounts.iter() {
-            if let ActorMeta::Multisig { .. } = a.meta {
+            if let ActorMeta::Multisig(_) | ActorMeta::Vesting(_) = a.meta {
                 next_id += 1;
             }
         }
@@ -116,6 +119,14 @@ impl State {
             next_id += 1;
         }
 
+        // Insert EVM contracts from the Genesis file.
+        for eth_addr in eth_contract_addrs {
+            let eth_addr = eth_addr.unwrap_or_else(|| builtin_actor_eth_addr(next_id));
+            set_address(Address::from(eth_addr), next_id)
+                .context("cannot set ID of eth contract address")?;
+            next_id += 1;
+        }
+
         // Insert the null-Ethereum address to equal the system actor,
         // so the system actor can be identified by 0xff00..00 as well as 0x00..00
         set_address(*system::SYSTEM_ACTOR_ETH_ADDR, system::SYSTEM_ACTOR_ID)
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 use crate::{
     ipc, Account, Actor, ActorMeta, Collateral, Genesis, Multisig, PermissionMode, Power,
-    SignerAddr, Validator, ValidatorKey,
+    SignerAddr, Validator, ValidatorKey, Vesting,
 };
 use cid::multihash::MultihashDigest;
 use fendermint_crypto::SecretKey;
@@ -30,6 +30,23 @@ impl Arbitrary for ActorMeta {
             ActorMeta::Account(Account {
                 owner: SignerAddr(addr),
             })
+        } else if bool::arbitrary(g) {
+            let signer = |g: &mut Gen| {
+                let pk = ValidatorKey::arbitrary(g).0;
+                SignerAddr(Address::new_secp256k1(&pk.serialize()).unwrap())
+            };
+            let duration = u64::arbitrary(g) % 1_000_000;
+            ActorMeta::Vesting(Vesting {
+                owner: signer(g),
+                revoker: if bool::arbitrary(g) {
+                    Some(signer(g))
+                } else {
+                    None
+                },
+                start: u64::arbitrary(g) % 1_000_000,
+                cliff: u64::arbitrary(g) % (duration + 1),
+                duration,
+            })
         } else {
             let n = u64::arbitrary(g) % 4 + 2;
             let signers = (0..n)
@@ -109,11 +126,14 @@ impl Arbitrary for Genesis {
             validators: (0..nv).map(|_| Arbitrary::arbitrary(g)).collect(),
             accounts: (0..na).map(|_| Arbitrary::arbitrary(g)).collect(),
             eam_permission_mode: PermissionMode::Unrestricted,
+            eam_admin: None,
             ipc: if bool::arbitrary(g) {
                 Some(ipc::IpcParams::arbitrary(g))
             } else {
                 None
             },
+            // Contracts can only be sealed with their build artifacts on disk.
+            contracts: Vec::new(),
         }
     }
 }
@@ -3,10 +3,13 @@
 //! A Genesis data structure similar to [genesis.Template](https://github.com/filecoin-project/lotus/blob/v1.20.4/genesis/types.go)
 //! in Lotus, which is used to [initialize](https://github.com/filecoin-project/lotus/blob/v1.20.4/chain/gen/genesis/genesis.go) the state tree.
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use anyhow::anyhow;
 use fvm_shared::bigint::{BigInt, Integer};
 use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+use serde_with::{hex::Hex, serde_as};
 
 use fendermint_actor_eam::PermissionModeParams;
 use fvm_shared::version::NetworkVersion;
@@ -44,9 +47,15 @@ pub struct Genesis {
     pub accounts: Vec<Actor>,
     /// The custom eam permission mode that controls who can deploy contracts
     pub eam_permission_mode: PermissionMode,
+    /// The account allowed to manage the EAM deployers after launch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eam_admin: Option<SignerAddr>,
     /// IPC related configuration, if enabled.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ipc: Option<ipc::IpcParams>,
+    /// EVM contracts to deploy when the genesis is sealed, in the order they are listed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contracts: Vec<Contract>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -81,10 +90,32 @@ pub struct Multisig {
     pub vesting_start: u64,
 }
 
+/// An account whose balance is released to the owner gradually.
+///
+/// Nothing can be withdrawn before `start + cliff`, after which the balance vests
+/// linearly until `start + duration`. Setting the cliff equal to the duration makes
+/// a timelocked account. If there is a revoker, they can take back the unvested part.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Vesting {
+    pub owner: SignerAddr,
+    pub revoker: Option<SignerAddr>,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+impl Vesting {
+    /// The accounts which have to exist for the schedule to be usable.
+    pub fn signers(&self) -> impl Iterator<Item = &SignerAddr> {
+        std::iter::once(&self.owner).chain(self.revoker.iter())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ActorMeta {
     Account(Account),
     Multisig(Multisig),
+    Vesting(Vesting),
 }
 
 #[serde_as]
@@ -95,6 +126,42 @@ pub struct Actor {
     pub balance: TokenAmount,
 }
 
+/// An EVM contract deployed from its Hardhat or Foundry build artifact when the genesis is sealed.
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Contract {
+    /// Unique name of the contract, which other contracts can use to link it as a library,
+    /// and under which its address is reported after deployment.
+    pub name: String,
+    /// Path to the JSON artifact, e.g. `out/Multicall3.sol/Multicall3.json`.
+    ///
+    /// Both tools put the artifacts in a directory named after the Solidity source file.
+    /// A relative path is resolved against the directory of the genesis file.
+    pub artifact: PathBuf,
+    /// ABI encoded constructor arguments, appended to the bytecode.
+    #[serde_as(as = "Hex")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constructor_args: Vec<u8>,
+    /// Libraries referenced by the bytecode, by their fully qualified or plain contract name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub libraries: BTreeMap<String, LibraryLink>,
+    /// Fixed `f410` address to deploy the contract at; otherwise one is derived from its actor ID.
+    #[serde_as(as = "Option<IsHumanReadable>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegated_address: Option<Address>,
+}
+
+/// The address to link in place of a library reference.
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LibraryLink {
+    /// A contract listed earlier in the genesis file.
+    Contract(String),
+    /// An actor which exists at genesis, given by its ID or `f410` address.
+    Address(#[serde_as(as = "IsHumanReadable")] Address),
+}
+
 /// Total amount of tokens delegated to a validator.
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -248,11 +315,11 @@ pub mod ipc {
 
 #[cfg(test)]
 mod tests {
-    use fvm_shared::{bigint::BigInt, econ::TokenAmount};
+    use fvm_shared::{address::Address, bigint::BigInt, econ::TokenAmount};
     use num_traits::Num;
     use quickcheck_macros::quickcheck;
 
-    use crate::{Collateral, Genesis};
+    use crate::{Collateral, Contract, Genesis, LibraryLink};
 
     #[quickcheck]
     fn genesis_json(value0: Genesis) {
@@ -272,6 +339,42 @@ mod tests {
         assert_eq!(value1, value0)
     }
 
+    #[quickcheck]
+    fn genesis_contracts(mut value0: Genesis) {
+        value0.contracts = vec![
+            Contract {
+                name: "MathLib".into(),
+                artifact: "out/MathLib.sol/MathLib.json".into(),
+                constructor_args: Vec::new(),
+                libraries: Default::default(),
+                delegated_address: None,
+            },
+            Contract {
+                name: "Token".into(),
+                artifact: "out/Token.sol/Token.json".into(),
+                constructor_args: vec![0, 1, 2, 255],
+                libraries: [
+                    ("MathLib".into(), LibraryLink::Contract("MathLib".into())),
+                    (
+                        "src/Util.sol:Util".into(),
+                        LibraryLink::Address(Address::new_id(64)),
+                    ),
+                ]
+                .into(),
+                delegated_address: Some(Address::new_delegated(10, &[1u8; 20]).unwrap()),
+            },
+        ];
+
+        let repr = serde_json::to_string(&value0).expect("failed to encode");
+        assert!(repr.contains("\"constructor_args\":\"000102ff\""));
+        let value1: Genesis = serde_json::from_str(&repr).expect("failed to decode JSON");
+        assert_eq!(value1, value0);
+
+        let repr = fvm_ipld_encoding::to_vec(&value0).expect("failed to encode");
+        let value1: Genesis = fvm_ipld_encoding::from_slice(&repr).expect("failed to decode");
+        assert_eq!(value1, value0);
+    }
+
     #[test]
     fn tokens_to_power() {
         // Collateral given in atto (18 digits after the decimal)
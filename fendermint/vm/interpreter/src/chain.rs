@@ -1,23 +1,33 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 use crate::fvm::state::ipc::GatewayCaller;
-use crate::fvm::{topdown, FvmApplyRet, PowerUpdates};
+use crate::fvm::store::ReadOnlyBlockstore;
+use crate::fvm::{
+    access_list, sponsor, topdown, CheckpointSignaturePool, FvmApplyRet, PowerUpdates,
+};
 use crate::{
     fvm::state::FvmExecState,
     fvm::FvmMessage,
-    signed::{SignedMessageApplyRes, SignedMessageCheckRes, SyntheticMessage, VerifiableMessage},
+    signed::{
+        InvalidSignature, SignedMessageApplyRes, SignedMessageApplyRet, SignedMessageCheckRes,
+        SyntheticMessage, VerifiableMessage,
+    },
     CheckInterpreter, ExecInterpreter, ProposalInterpreter, QueryInterpreter,
 };
-use anyhow::{bail, Context};
+use anyhow::{anyhow, bail, Context};
 use async_stm::atomically;
 use async_trait::async_trait;
 use fendermint_tracing::emit;
 use fendermint_vm_actor_interface::ipc;
 use fendermint_vm_event::ParentFinalityMissingQuorum;
+use fendermint_vm_genesis::{Power, Validator};
 use fendermint_vm_message::ipc::ParentFinality;
 use fendermint_vm_message::{
     chain::ChainMessage,
-    ipc::{BottomUpCheckpoint, CertifiedMessage, IpcMessage, SignedRelayedMessage},
+    ipc::{
+        BottomUpCheckpoint, CertifiedMessage, CheckpointSignature, IpcMessage, SignedRelayedMessage,
+    },
+    signed::SignedMessageError,
 };
 use fendermint_vm_resolver::pool::{ResolveKey, ResolvePool};
 use fendermint_vm_topdown::proxy::IPCProviderProxyWithLatency;
@@ -30,6 +40,7 @@ use fvm_ipld_encoding::RawBytes;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use num_traits::Zero;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// A resolution pool for bottom-up and top-down checkpoints.
@@ -45,6 +56,15 @@ pub struct ChainEnv {
     /// The parent finality provider for top down checkpoint
     pub parent_finality_provider: TopDownFinalityProvider,
     pub parent_finality_votes: VoteTally,
+    /// Checkpoint signatures of this validator waiting to be sent as vote extensions.
+    pub checkpoint_signatures: CheckpointSignaturePool,
+    /// Whether vote extensions are enabled (ABCI 2.0), which is the only way checkpoint
+    /// signatures can legitimately end up in a proposal.
+    pub vote_extensions: bool,
+    /// The power table of the gateway in the last committed state, which checkpoint signatures
+    /// in a proposal are checked against. Set by the application before processing a proposal,
+    /// so that every validator decides based on the ledger rather than what it heard so far.
+    pub power_table: Vec<Validator<Power>>,
 }
 
 #[derive(Clone, Hash, PartialEq, Eq)]
@@ -74,6 +94,12 @@ pub enum ChainMessageApplyRet {
     Signed(SignedMessageApplyRes),
     /// The IPC chain message execution result
     Ipc(FvmApplyRet),
+    /// Checkpoint signatures added to the gateway implicitly; the ones
+    /// the gateway rejected are counted, but don't fail the message.
+    CheckpointSignatures {
+        accepted: usize,
+        rejected: usize,
+    },
 }
 
 /// We only allow signed messages into the mempool.
@@ -211,6 +237,18 @@ where
                         return Ok(false);
                     }
                 }
+                ChainMessage::Ipc(IpcMessage::CheckpointSignatures(signatures)) => {
+                    if !env.vote_extensions {
+                        return Ok(false);
+                    }
+                    if let Err(e) = check_checkpoint_signatures(&env.power_table, &signatures) {
+                        tracing::warn!(
+                            error = format!("{e:#}"),
+                            "rejecting proposed checkpoint signatures"
+                        );
+                        return Ok(false);
+                    }
+                }
                 _ => {}
             };
         }
@@ -246,12 +284,90 @@ where
     ) -> anyhow::Result<(Self::State, Self::DeliverOutput)> {
         match msg {
             ChainMessage::Signed(msg) => {
-                let (state, ret) = self
+                let sender = msg.message.from;
+                let access_list_gas = msg.access_list_gas();
+
+                let (mut state, mut ret) = self
                     .inner
                     .deliver(state, VerifiableMessage::Signed(msg))
                     .await?;
+
+                if let Ok(ref mut ret) = ret {
+                    access_list::charge_access_list(
+                        &mut state,
+                        sender,
+                        access_list_gas,
+                        &mut ret.fvm.apply_ret,
+                    )
+                    .context("failed to charge access list gas")?;
+                }
+
                 Ok(((env, state), ChainMessageApplyRet::Signed(ret)))
             }
+            ChainMessage::Sponsored(msg) => {
+                // Verify both signatures before moving any funds.
+                match msg.verify(&state.chain_id()) {
+                    Err(SignedMessageError::Ipld(e)) => Err(anyhow!(e)),
+                    Err(SignedMessageError::Ethereum(e)) => Ok((
+                        (env, state),
+                        ChainMessageApplyRet::Signed(Err(InvalidSignature(e.to_string()))),
+                    )),
+                    Err(SignedMessageError::InvalidSignature(s)) => Ok((
+                        (env, state),
+                        ChainMessageApplyRet::Signed(Err(InvalidSignature(s))),
+                    )),
+                    Ok(()) => {
+                        let sponsorship = sponsor::deposit_gas(&mut state, &msg)
+                            .context("failed to deposit sponsored gas")?;
+
+                        let deposit = match sponsorship.deposit {
+                            Ok(deposit) => deposit,
+                            Err(reason) => {
+                                let mut ret = SignedMessageApplyRet {
+                                    fvm: sponsor::rejected_apply_ret(&msg, reason),
+                                    domain_hash: None,
+                                };
+                                sponsor::charge_sponsorship(
+                                    &mut ret.fvm.apply_ret,
+                                    sponsorship.fee.as_ref(),
+                                );
+                                return Ok(((env, state), ChainMessageApplyRet::Signed(Ok(ret))));
+                            }
+                        };
+
+                        // Execute as the user, who now has the funds to pay for the gas.
+                        let (mut state, mut ret) = self
+                            .inner
+                            .deliver(state, VerifiableMessage::Signed(msg.message.clone()))
+                            .await?;
+
+                        // The access list is paid from the deposit like the rest of the gas.
+                        if let Ok(ref mut ret) = ret {
+                            access_list::charge_access_list(
+                                &mut state,
+                                msg.message().from,
+                                msg.message.access_list_gas(),
+                                &mut ret.fvm.apply_ret,
+                            )
+                            .context("failed to charge access list gas")?;
+                        }
+
+                        let apply_ret = ret.as_ref().ok().map(|ret| &ret.fvm.apply_ret);
+
+                        sponsor::refund_gas(&mut state, &msg, &deposit, apply_ret)
+                            .context("failed to refund sponsored gas")?;
+
+                        if let Ok(ref mut ret) = ret {
+                            sponsor::charge_sponsorship(
+                                &mut ret.fvm.apply_ret,
+                                sponsorship.fee.as_ref(),
+                            );
+                        }
+
+                        Ok(((env, state), ChainMessageApplyRet::Signed(ret)))
+                    }
+                }
+            }
             ChainMessage::Ipc(msg) => match msg {
                 IpcMessage::BottomUpResolve(msg) => {
                     let smsg = relayed_bottom_up_ckpt_to_fvm(&msg)
@@ -393,6 +509,38 @@ where
 
                     Ok(((env, state), ChainMessageApplyRet::Ipc(ret)))
                 }
+                IpcMessage::CheckpointSignatures(signatures) => {
+                    let mut accepted = 0;
+                    let mut rejected = 0;
+
+                    for signature in signatures.iter().cloned() {
+                        let height = signature.height;
+                        match self
+                            .gateway_caller
+                            .add_checkpoint_signature(&mut state, signature)
+                            .context("failed to add checkpoint signature")?
+                        {
+                            Ok(()) => accepted += 1,
+                            Err(e) => {
+                                // Signatures can legitimately arrive after the quorum has been reached.
+                                tracing::debug!(
+                                    height,
+                                    exit_code = e.exit_code.value(),
+                                    error = ?e.error,
+                                    "checkpoint signature rejected"
+                                );
+                                rejected += 1;
+                            }
+                        }
+                    }
+
+                    atomically(|| env.checkpoint_signatures.remove_delivered(&signatures)).await;
+
+                    Ok((
+                        (env, state),
+                        ChainMessageApplyRet::CheckpointSignatures { accepted, rejected },
+                    ))
+                }
             },
         }
     }
@@ -438,7 +586,11 @@ where
 impl<I, DB> CheckInterpreter for ChainMessageInterpreter<I, DB>
 where
     DB: Blockstore + Clone + 'static + Send + Sync,
-    I: CheckInterpreter<Message = VerifiableMessage, Output = SignedMessageCheckRes>,
+    I: CheckInterpreter<
+        Message = VerifiableMessage,
+        Output = SignedMessageCheckRes,
+        State = FvmExecState<ReadOnlyBlockstore<DB>>,
+    >,
 {
     type State = I::State;
     type Message = ChainMessage;
@@ -446,7 +598,7 @@ where
 
     async fn check(
         &self,
-        state: Self::State,
+        mut state: Self::State,
         msg: Self::Message,
         is_recheck: bool,
     ) -> anyhow::Result<(Self::State, Self::Output)> {
@@ -459,6 +611,47 @@ where
 
                 Ok((state, Ok(ret)))
             }
+            ChainMessage::Sponsored(msg) => {
+                let verify_result = if is_recheck {
+                    Ok(())
+                } else {
+                    msg.verify(&state.chain_id())
+                };
+
+                match verify_result {
+                    Err(SignedMessageError::Ipld(e)) => Err(anyhow!(e)),
+                    Err(SignedMessageError::Ethereum(e)) => {
+                        Ok((state, Ok(Err(InvalidSignature(e.to_string())))))
+                    }
+                    Err(SignedMessageError::InvalidSignature(s)) => {
+                        Ok((state, Ok(Err(InvalidSignature(s)))))
+                    }
+                    Ok(()) => {
+                        // Move the deposit in the pending state too, so the sender passes the balance check,
+                        // and the sponsor can't have more messages in the mempool than it can pay for.
+                        match sponsor::deposit_gas(&mut state, &msg)
+                            .context("failed to deposit sponsored gas")?
+                            .deposit
+                        {
+                            Err(reason) => {
+                                Ok((state, Ok(Ok(sponsor::rejected_check_ret(&msg, reason)))))
+                            }
+                            Ok(_) => {
+                                let (state, ret) = self
+                                    .inner
+                                    .check(
+                                        state,
+                                        VerifiableMessage::Signed(msg.message),
+                                        is_recheck,
+                                    )
+                                    .await?;
+
+                                Ok((state, Ok(ret)))
+                            }
+                        }
+                    }
+                }
+            }
             ChainMessage::Ipc(msg) => {
                 match msg {
                     IpcMessage::BottomUpResolve(msg) => {
@@ -473,7 +666,9 @@ where
 
                         Ok((state, Ok(ret)))
                     }
-                    IpcMessage::TopDownExec(_) | IpcMessage::BottomUpExec(_) => {
+                    IpcMessage::TopDownExec(_)
+                    | IpcMessage::BottomUpExec(_)
+                    | IpcMessage::CheckpointSignatures(_) => {
                         // Users cannot send these messages, only validators can propose them in blocks.
                         Ok((state, Err(IllegalMessage)))
                     }
@@ -502,6 +697,60 @@ where
     }
 }
 
+/// Check the checkpoint signatures a proposer collected from the vote extensions of the previous height.
+///
+/// Because they are executed implicitly, without anyone paying for gas, each signature has to come
+/// from a validator in the power table, and there can be at most one per validator for each checkpoint.
+/// Whether the signed hash is that of the checkpoint in the ledger is up to the gateway to check.
+fn check_checkpoint_signatures(
+    power_table: &[Validator<Power>],
+    signatures: &[CheckpointSignature],
+) -> anyhow::Result<()> {
+    let validators = power_table
+        .iter()
+        .filter(|v| v.power.0 > 0)
+        .map(|v| v.public_key.0.serialize())
+        .collect::<HashSet<_>>();
+
+    // Cheap bound on the number of signatures before recovering the signatories.
+    let mut counts = HashMap::<ChainEpoch, usize>::new();
+    for signature in signatures {
+        let count = counts.entry(signature.height).or_default();
+        *count += 1;
+        if *count > validators.len() {
+            bail!(
+                "more checkpoint signatures at height {} than validators",
+                signature.height
+            );
+        }
+    }
+
+    let mut signers = HashSet::new();
+    for signature in signatures {
+        let signer = signature
+            .recover_signer()
+            .context("invalid checkpoint signature")?;
+
+        let signer = signer.serialize();
+
+        if !validators.contains(&signer) {
+            bail!(
+                "checkpoint signature at height {} from a validator without power",
+                signature.height
+            );
+        }
+
+        if !signers.insert((signature.height, signer)) {
+            bail!(
+                "duplicate checkpoint signature of a validator at height {}",
+                signature.height
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Convert a signed relayed bottom-up checkpoint to a syntetic message we can send to the FVM.
 ///
 /// By mapping to an FVM message we invoke the right contract to validate the checkpoint,
@@ -531,3 +780,497 @@ fn relayed_bottom_up_ckpt_to_fvm(
 
     Ok(msg)
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use fendermint_crypto::SecretKey;
+    use fendermint_vm_genesis::{
+        Account, Actor, ActorMeta, Genesis, Power, SignerAddr, Validator, ValidatorKey,
+    };
+    use fendermint_vm_message::chain::ChainMessage;
+    use fendermint_vm_message::conv::from_fvm;
+    use fendermint_vm_message::ipc::{CheckpointSignature, IpcMessage};
+    use fendermint_vm_message::signed::{sign_secp256k1, SignedMessage};
+    use fendermint_vm_message::sponsored::SponsoredMessage;
+    use fendermint_vm_topdown::voting::VoteTally;
+    use fendermint_vm_topdown::Toggle;
+    use fvm::engine::MultiEngine;
+    use fvm::state_tree::ActorState;
+    use fvm_ipld_blockstore::Blockstore;
+    use fvm_ipld_encoding::RawBytes;
+    use fvm_shared::address::Address;
+    use fvm_shared::chainid::ChainID;
+    use fvm_shared::clock::ChainEpoch;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use fvm_shared::message::Message;
+    use fvm_shared::METHOD_SEND;
+    use num_traits::Zero;
+    use quickcheck::Arbitrary;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::sync::Arc;
+
+    use crate::fvm::bundle::{bundle_path, custom_actors_bundle_path};
+    use crate::fvm::sponsor::SPONSORSHIP_GAS;
+    use crate::fvm::state::{FvmExecState, FvmStateParams};
+    use crate::fvm::store::memory::MemoryBlockstore;
+    use crate::fvm::store::ReadOnlyBlockstore;
+    use crate::fvm::upgrades::UpgradeScheduler;
+    use crate::fvm::{CheckpointSignaturePool, FvmMessageInterpreter};
+    use crate::genesis::create_test_genesis_state;
+    use crate::signed::SignedMessageInterpreter;
+    use crate::{CheckInterpreter, ExecInterpreter, ProposalInterpreter};
+
+    use super::{
+        check_checkpoint_signatures, ChainEnv, ChainMessageApplyRet, ChainMessageInterpreter,
+        CheckpointPool,
+    };
+
+    fn sign(sk: &SecretKey, height: ChainEpoch, hash: [u8; 32]) -> CheckpointSignature {
+        let signature = sign_secp256k1(sk, &hash);
+        let signature = from_fvm::to_eth_signature(&signature, false).unwrap();
+        CheckpointSignature {
+            height,
+            membership_proof: Vec::new(),
+            weight: 1,
+            hash,
+            signature: signature.to_vec(),
+        }
+    }
+
+    fn validators(n: usize) -> (Vec<SecretKey>, Vec<Validator<Power>>) {
+        let mut rng = StdRng::seed_from_u64(n as u64);
+        let sks = (0..n)
+            .map(|_| SecretKey::random(&mut rng))
+            .collect::<Vec<_>>();
+        let power_table = sks
+            .iter()
+            .map(|sk| Validator {
+                public_key: ValidatorKey::new(sk.public_key()),
+                power: Power(1),
+            })
+            .collect();
+        (sks, power_table)
+    }
+
+    fn chain_env(power_table: Vec<Validator<Power>>, vote_extensions: bool) -> ChainEnv {
+        ChainEnv {
+            checkpoint_pool: CheckpointPool::new(),
+            parent_finality_provider: Arc::new(Toggle::disabled()),
+            parent_finality_votes: VoteTally::empty(),
+            checkpoint_signatures: CheckpointSignaturePool::new(),
+            vote_extensions,
+            power_table,
+        }
+    }
+
+    #[test]
+    fn test_check_checkpoint_signatures() {
+        let (sks, mut power_table) = validators(3);
+        let outsider = SecretKey::random(&mut StdRng::seed_from_u64(100));
+
+        let sigs = vec![
+            sign(&sks[0], 10, [1; 32]),
+            sign(&sks[1], 10, [1; 32]),
+            sign(&sks[0], 20, [2; 32]),
+        ];
+        assert!(check_checkpoint_signatures(&power_table, &sigs).is_ok());
+        assert!(check_checkpoint_signatures(&power_table, &[]).is_ok());
+
+        // Not in the power table.
+        let sigs = vec![sign(&outsider, 10, [1; 32])];
+        assert!(check_checkpoint_signatures(&power_table, &sigs).is_err());
+
+        // The same validator twice for the same checkpoint.
+        let sigs = vec![sign(&sks[0], 10, [1; 32]), sign(&sks[0], 10, [3; 32])];
+        assert!(check_checkpoint_signatures(&power_table, &sigs).is_err());
+
+        // More signatures for a checkpoint than there are validators.
+        let sigs = vec![sign(&sks[0], 10, [1; 32]); 4];
+        assert!(check_checkpoint_signatures(&power_table, &sigs).is_err());
+
+        // Not a recoverable signature.
+        let mut sig = sign(&sks[0], 10, [1; 32]);
+        sig.signature.truncate(64);
+        assert!(check_checkpoint_signatures(&power_table, &[sig]).is_err());
+
+        // Nothing is accepted without a power table.
+        let sigs = vec![sign(&sks[0], 10, [1; 32])];
+        assert!(check_checkpoint_signatures(&[], &sigs).is_err());
+
+        // A validator without power doesn't count.
+        power_table[0].power = Power(0);
+        assert!(check_checkpoint_signatures(&power_table, &sigs).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_process_checkpoint_signatures() {
+        let interpreter = ChainMessageInterpreter::<(), MemoryBlockstore>::new(());
+        let (sks, power_table) = validators(2);
+
+        let msgs = vec![ChainMessage::Ipc(IpcMessage::CheckpointSignatures(vec![
+            sign(&sks[0], 10, [1; 32]),
+            sign(&sks[1], 10, [1; 32]),
+        ]))];
+
+        let accept = interpreter
+            .process(chain_env(power_table.clone(), true), msgs.clone())
+            .await
+            .unwrap();
+        assert!(accept, "signatures of validators are accepted");
+
+        let accept = interpreter
+            .process(chain_env(power_table.clone(), false), msgs)
+            .await
+            .unwrap();
+        assert!(!accept, "signatures without vote extensions are rejected");
+
+        let msgs = vec![ChainMessage::Ipc(IpcMessage::CheckpointSignatures(vec![
+            sign(&sks[0], 10, [1; 32]),
+            sign(
+                &SecretKey::random(&mut StdRng::seed_from_u64(100)),
+                10,
+                [1; 32],
+            ),
+        ]))];
+
+        let accept = interpreter
+            .process(chain_env(power_table, true), msgs)
+            .await
+            .unwrap();
+        assert!(!accept, "signatures of non-validators are rejected");
+    }
+
+    const SPONSOR_BALANCE: u64 = 1_000_000_000_000_000_000;
+    const POOR_SPONSOR_BALANCE: u64 = 1000;
+
+    type TestInterpreter = ChainMessageInterpreter<
+        SignedMessageInterpreter<FvmMessageInterpreter<MemoryBlockstore, NeverCallClient>>,
+        MemoryBlockstore,
+    >;
+
+    #[derive(Clone)]
+    struct NeverCallClient;
+
+    #[async_trait]
+    impl tendermint_rpc::Client for NeverCallClient {
+        async fn perform<R>(&self, _request: R) -> Result<R::Output, tendermint_rpc::Error>
+        where
+            R: tendermint_rpc::SimpleRequest,
+        {
+            todo!()
+        }
+    }
+
+    /// A user without funds, a sponsor, and a sponsor who can't pay for anything.
+    struct Sponsorship {
+        store: MemoryBlockstore,
+        params: FvmStateParams,
+        multi_engine: MultiEngine,
+        interpreter: TestInterpreter,
+        user: (SecretKey, Address),
+        sponsor: (SecretKey, Address),
+        poor_sponsor: (SecretKey, Address),
+    }
+
+    impl Sponsorship {
+        async fn new() -> Self {
+            let key = |seed| {
+                let sk = SecretKey::random(&mut StdRng::seed_from_u64(seed));
+                let addr = Address::new_secp256k1(&sk.public_key().serialize()).unwrap();
+                (sk, addr)
+            };
+            let (user, sponsor, poor_sponsor) = (key(1), key(2), key(3));
+
+            let mut genesis = Genesis::arbitrary(&mut quickcheck::Gen::new(5));
+            genesis.ipc = None;
+            genesis.base_fee = TokenAmount::from_atto(100);
+            genesis.accounts = [
+                (user.1, 0),
+                (sponsor.1, SPONSOR_BALANCE),
+                (poor_sponsor.1, POOR_SPONSOR_BALANCE),
+            ]
+            .into_iter()
+            .map(|(addr, balance)| Actor {
+                meta: ActorMeta::Account(Account {
+                    owner: SignerAddr(addr),
+                }),
+                balance: TokenAmount::from_atto(balance),
+            })
+            .collect();
+
+            let (state, out) = create_test_genesis_state(
+                bundle_path(),
+                custom_actors_bundle_path(),
+                genesis,
+                None,
+            )
+            .await
+            .expect("failed to create genesis");
+
+            let (state_root, store) = state.finalize().expect("failed to finalize genesis");
+
+            let params = FvmStateParams {
+                state_root,
+                timestamp: out.timestamp,
+                network_version: out.network_version,
+                base_fee: out.base_fee,
+                circ_supply: out.circ_supply,
+                chain_id: out.chain_id.into(),
+                power_scale: out.power_scale,
+                app_version: 0,
+            };
+
+            let interpreter = FvmMessageInterpreter::new(
+                NeverCallClient,
+                None,
+                1.25,
+                1.25,
+                false,
+                UpgradeScheduler::new(),
+            );
+            let interpreter =
+                ChainMessageInterpreter::new(SignedMessageInterpreter::new(interpreter));
+
+            Self {
+                store,
+                params,
+                multi_engine: MultiEngine::new(1),
+                interpreter,
+                user,
+                sponsor,
+                poor_sponsor,
+            }
+        }
+
+        fn chain_id(&self) -> ChainID {
+            ChainID::from(self.params.chain_id)
+        }
+
+        fn exec_state(&self) -> FvmExecState<MemoryBlockstore> {
+            FvmExecState::new(
+                self.store.clone(),
+                &self.multi_engine,
+                1,
+                self.params.clone(),
+            )
+            .expect("failed to create exec state")
+        }
+
+        fn check_state(&self) -> FvmExecState<ReadOnlyBlockstore<MemoryBlockstore>> {
+            FvmExecState::new(
+                ReadOnlyBlockstore::new(self.store.clone()),
+                &self.multi_engine,
+                1,
+                self.params.clone(),
+            )
+            .expect("failed to create check state")
+        }
+
+        /// A transfer of nothing from the user, signed by `user_sk`, paid for by `sponsor`,
+        /// signed by `sponsor_sk`.
+        fn sponsored(
+            &self,
+            user_sk: &SecretKey,
+            sponsor: &(SecretKey, Address),
+            sponsor_sk: &SecretKey,
+        ) -> ChainMessage {
+            self.sponsored_at(user_sk, 0, sponsor, sponsor_sk)
+        }
+
+        /// Same as [Sponsorship::sponsored] with a given sequence of the user.
+        fn sponsored_at(
+            &self,
+            user_sk: &SecretKey,
+            sequence: u64,
+            sponsor: &(SecretKey, Address),
+            sponsor_sk: &SecretKey,
+        ) -> ChainMessage {
+            let msg = Message {
+                version: Default::default(),
+                from: self.user.1,
+                to: sponsor.1,
+                sequence,
+                value: TokenAmount::zero(),
+                method_num: METHOD_SEND,
+                params: RawBytes::default(),
+                gas_limit: 10_000_000,
+                gas_fee_cap: TokenAmount::from_atto(200),
+                gas_premium: TokenAmount::from_atto(10),
+            };
+            let signed = SignedMessage::new_secp256k1(msg, user_sk, &self.chain_id()).unwrap();
+            let sponsored =
+                SponsoredMessage::new_secp256k1(signed, sponsor.1, 0, sponsor_sk, &self.chain_id())
+                    .unwrap();
+            ChainMessage::Sponsored(sponsored)
+        }
+    }
+
+    fn actor<DB: Blockstore + Clone + 'static>(
+        state: &FvmExecState<DB>,
+        addr: &Address,
+    ) -> ActorState {
+        let id = state
+            .state_tree()
+            .lookup_id(addr)
+            .unwrap()
+            .expect("actor exists");
+        state
+            .state_tree()
+            .get_actor(id)
+            .unwrap()
+            .expect("actor exists")
+    }
+
+    #[tokio::test]
+    async fn test_deliver_sponsored() {
+        let s = Sponsorship::new().await;
+        let (user_sk, user) = &s.user;
+        let (sponsor_sk, sponsor) = &s.sponsor;
+        let deliver = |state, msg| {
+            s.interpreter
+                .deliver((chain_env(Vec::new(), false), state), msg)
+        };
+
+        // The sponsor pays for the gas, the user executes the message.
+        let msg = s.sponsored(user_sk, &s.sponsor, sponsor_sk);
+        let ((_, state), ret) = deliver(s.exec_state(), msg.clone()).await.unwrap();
+        let ret = match ret {
+            ChainMessageApplyRet::Signed(Ok(ret)) => ret.fvm.apply_ret,
+            _ => panic!("expected the message to be executed"),
+        };
+        assert_eq!(ret.msg_receipt.exit_code, ExitCode::OK);
+
+        let charged = ret.base_fee_burn + ret.over_estimation_burn + ret.miner_tip;
+        assert!(charged.is_positive());
+
+        let sponsor_actor = actor(&state, sponsor);
+        assert_eq!(
+            sponsor_actor.balance,
+            TokenAmount::from_atto(SPONSOR_BALANCE) - charged
+        );
+        assert_eq!(sponsor_actor.sequence, 1);
+
+        let user_actor = actor(&state, user);
+        assert!(user_actor.balance.is_zero());
+        assert_eq!(user_actor.sequence, 1);
+
+        // The same message can't be delivered again.
+        let (_, ret) = deliver(state, msg).await.unwrap();
+        let ret = match ret {
+            ChainMessageApplyRet::Signed(Ok(ret)) => ret.fvm.apply_ret,
+            _ => panic!("expected the message to be rejected before execution"),
+        };
+        assert_eq!(
+            ret.msg_receipt.exit_code,
+            ExitCode::SYS_SENDER_STATE_INVALID
+        );
+
+        // The sponsor can't pay for the gas.
+        let msg = s.sponsored(user_sk, &s.poor_sponsor, &s.poor_sponsor.0);
+        let ((_, state), ret) = deliver(s.exec_state(), msg).await.unwrap();
+        let ret = match ret {
+            ChainMessageApplyRet::Signed(Ok(ret)) => ret.fvm.apply_ret,
+            _ => panic!("expected the message to be rejected before execution"),
+        };
+        assert_eq!(
+            ret.msg_receipt.exit_code,
+            ExitCode::SYS_SENDER_STATE_INVALID
+        );
+
+        let poor_actor = actor(&state, &s.poor_sponsor.1);
+        assert_eq!(
+            poor_actor.balance,
+            TokenAmount::from_atto(POOR_SPONSOR_BALANCE)
+        );
+        assert_eq!(poor_actor.sequence, 0);
+        assert_eq!(actor(&state, user).sequence, 0);
+
+        // The user signed the wrong sequence, but the sponsor still pays for the sponsorship.
+        let msg = s.sponsored_at(user_sk, 1, &s.sponsor, sponsor_sk);
+        let ((_, state), ret) = deliver(s.exec_state(), msg).await.unwrap();
+        let ret = match ret {
+            ChainMessageApplyRet::Signed(Ok(ret)) => ret.fvm.apply_ret,
+            _ => panic!("expected the message to be rejected before execution"),
+        };
+        assert_eq!(
+            ret.msg_receipt.exit_code,
+            ExitCode::SYS_SENDER_STATE_INVALID
+        );
+
+        let fee = TokenAmount::from_atto(100) * SPONSORSHIP_GAS;
+        assert_eq!(ret.msg_receipt.gas_used, SPONSORSHIP_GAS);
+        assert_eq!(ret.base_fee_burn, fee);
+
+        let sponsor_actor = actor(&state, sponsor);
+        assert_eq!(
+            sponsor_actor.balance,
+            TokenAmount::from_atto(SPONSOR_BALANCE) - fee
+        );
+        assert_eq!(sponsor_actor.sequence, 1);
+
+        // Signed by someone else than the sponsor.
+        let msg = s.sponsored(user_sk, &s.sponsor, user_sk);
+        let ((_, state), ret) = deliver(s.exec_state(), msg).await.unwrap();
+        assert!(matches!(ret, ChainMessageApplyRet::Signed(Err(_))));
+        assert_eq!(actor(&state, sponsor).sequence, 0);
+
+        // Signed by someone else than the user.
+        let msg = s.sponsored(sponsor_sk, &s.sponsor, sponsor_sk);
+        let ((_, state), ret) = deliver(s.exec_state(), msg).await.unwrap();
+        assert!(matches!(ret, ChainMessageApplyRet::Signed(Err(_))));
+        assert_eq!(actor(&state, sponsor).sequence, 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_sponsored() {
+        let s = Sponsorship::new().await;
+        let (user_sk, user) = &s.user;
+        let (sponsor_sk, sponsor) = &s.sponsor;
+        let check = |msg| s.interpreter.check(s.check_state(), msg, false);
+
+        // The deposit moves to the user in the pending state, then the user pays for the gas.
+        let msg = s.sponsored(user_sk, &s.sponsor, sponsor_sk);
+        let (state, ret) = check(msg).await.unwrap();
+        let ret = match ret {
+            Ok(Ok(ret)) => ret,
+            _ => panic!("expected the signatures to be valid"),
+        };
+        assert_eq!(ret.exit_code, ExitCode::OK);
+
+        let deposit = TokenAmount::from_atto(200) * 10_000_000u64;
+        let fee = TokenAmount::from_atto(100) * SPONSORSHIP_GAS;
+        let sponsor_actor = actor(&state, sponsor);
+        assert_eq!(
+            sponsor_actor.balance,
+            TokenAmount::from_atto(SPONSOR_BALANCE) - deposit - fee
+        );
+        assert_eq!(sponsor_actor.sequence, 1);
+
+        let user_actor = actor(&state, user);
+        assert!(user_actor.balance.is_zero());
+        assert_eq!(user_actor.sequence, 1);
+
+        // The sponsor can't pay for the gas.
+        let msg = s.sponsored(user_sk, &s.poor_sponsor, &s.poor_sponsor.0);
+        let (state, ret) = check(msg).await.unwrap();
+        let ret = match ret {
+            Ok(Ok(ret)) => ret,
+            _ => panic!("expected the signatures to be valid"),
+        };
+        assert_eq!(ret.exit_code, ExitCode::SYS_SENDER_STATE_INVALID);
+        assert_eq!(actor(&state, &s.poor_sponsor.1).sequence, 0);
+
+        // Signed by someone else than the sponsor.
+        let msg = s.sponsored(user_sk, &s.sponsor, user_sk);
+        let (_, ret) = check(msg).await.unwrap();
+        assert!(matches!(ret, Ok(Err(_))));
+
+        // Signed by someone else than the user.
+        let msg = s.sponsored(sponsor_sk, &s.sponsor, sponsor_sk);
+        let (_, ret) = check(msg).await.unwrap();
+        assert!(matches!(ret, Ok(Err(_))));
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Ethereum transactions can declare the accounts and storage slots they are going to access,
+//! paying for them up front as per EIP-2930. The FVM doesn't know about access lists, so the
+//! gas for them is charged to the sender after the message is executed.
+
+use anyhow::Context;
+use fendermint_vm_actor_interface::burntfunds::BURNT_FUNDS_ACTOR_ADDR;
+use fvm::executor::{ApplyFailure, ApplyRet};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_shared::{address::Address, econ::TokenAmount};
+use num_traits::Zero;
+
+use super::{sponsor::transfer, state::FvmExecState};
+
+/// Burn the access list gas of an executed message at the base fee, and account for it in the result.
+///
+/// Messages rejected before execution are not charged, because their sequence doesn't change,
+/// so the same message could be charged again and again. The fee is capped to the balance
+/// the sender has left after execution.
+pub fn charge_access_list<DB>(
+    state: &mut FvmExecState<DB>,
+    sender: Address,
+    gas: u64,
+    apply_ret: &mut ApplyRet,
+) -> anyhow::Result<()>
+where
+    DB: Blockstore + Clone + 'static,
+{
+    if gas == 0 || matches!(apply_ret.failure_info, Some(ApplyFailure::PreValidation(_))) {
+        return Ok(());
+    }
+
+    let state_tree = state.state_tree_mut();
+    let balance = match state_tree.lookup_id(&sender)? {
+        Some(id) => state_tree
+            .get_actor(id)?
+            .map(|actor| actor.balance)
+            .unwrap_or_default(),
+        None => TokenAmount::zero(),
+    };
+
+    let mut fee = (state.base_fee().clone() * gas).min(balance);
+
+    if fee.is_positive() {
+        let ret = transfer(state, sender, BURNT_FUNDS_ACTOR_ADDR, fee.clone())
+            .context("failed to charge access list gas")?;
+
+        if !ret.msg_receipt.exit_code.is_success() {
+            fee = TokenAmount::zero();
+        }
+    }
+
+    apply_ret.msg_receipt.gas_used += gas;
+    apply_ret.base_fee_burn += fee;
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 use anyhow::{anyhow, Context};
+use async_stm::{atomically, Stm, TVar};
 use ethers::abi::Tokenizable;
 use tendermint::block::Height;
 use tendermint_rpc::endpoint::commit;
@@ -18,6 +19,7 @@ use fendermint_crypto::SecretKey;
 use fendermint_vm_actor_interface::eam::EthAddress;
 use fendermint_vm_actor_interface::ipc::BottomUpCheckpoint;
 use fendermint_vm_genesis::{Power, Validator, ValidatorKey};
+use fendermint_vm_message::ipc::CheckpointSignature;
 
 use ipc_actors_abis::checkpointing_facet as checkpoint;
 use ipc_actors_abis::gateway_getter_facet as getter;
@@ -42,6 +44,47 @@ pub struct PowerTable(pub Vec<Validator<Power>>);
 #[derive(Debug, Clone, Default)]
 pub struct PowerUpdates(pub Vec<Validator<Power>>);
 
+/// Checkpoint signatures of this validator which haven't made it into the ledger yet.
+///
+/// With ABCI 2.0 the signatures are attached to the precommit votes as vote extensions,
+/// instead of being broadcast as transactions. Entries are removed once they are delivered.
+#[derive(Clone, Default)]
+pub struct CheckpointSignaturePool {
+    signatures: TVar<BTreeMap<u64, CheckpointSignature>>,
+}
+
+impl CheckpointSignaturePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a signature, replacing any previous one at the same checkpoint height.
+    pub fn add(&self, signature: CheckpointSignature) -> Stm<()> {
+        self.signatures.update(|mut sigs| {
+            sigs.insert(signature.height as u64, signature);
+            sigs
+        })
+    }
+
+    /// Signatures waiting to be included, in ascending order of checkpoint height.
+    pub fn pending(&self) -> Stm<Vec<CheckpointSignature>> {
+        let sigs = self.signatures.read()?;
+        Ok(sigs.values().cloned().collect())
+    }
+
+    /// Remove the signatures which have been delivered.
+    pub fn remove_delivered(&self, delivered: &[CheckpointSignature]) -> Stm<()> {
+        self.signatures.update(|mut sigs| {
+            for sig in delivered {
+                if sigs.get(&(sig.height as u64)) == Some(sig) {
+                    sigs.remove(&(sig.height as u64));
+                }
+            }
+            sigs
+        })
+    }
+}
+
 /// Construct and store a checkpoint if this is the end of the checkpoint period.
 /// Perform end-of-checkpoint-period transitions in the ledger.
 ///
@@ -193,6 +236,9 @@ where
 }
 
 /// Sign the current and any incomplete checkpoints.
+///
+/// The signatures are either broadcast as transactions or, if the validator has a
+/// signature pool, added to the pool to be sent as vote extensions.
 pub async fn broadcast_incomplete_signatures<C, DB>(
     client: &C,
     validator_ctx: &ValidatorContext<C>,
@@ -244,18 +290,31 @@ where
                 msgs: convert_tokenizables(cp.msgs)?,
             };
 
-            // We mustn't do these in parallel because of how nonces are fetched.
-            broadcast_signature(
-                &validator_ctx.broadcaster,
-                gateway,
-                checkpoint,
-                &power_table,
-                &validator,
-                &validator_ctx.secret_key,
-                chain_id,
-            )
-            .await
-            .context("failed to broadcast checkpoint signature")?;
+            if let Some(ref pool) = validator_ctx.signature_pool {
+                let signature = gateway
+                    .checkpoint_signature(
+                        checkpoint,
+                        &power_table.0,
+                        &validator,
+                        &validator_ctx.secret_key,
+                    )
+                    .context("failed to sign checkpoint")?;
+
+                atomically(|| pool.add(signature.clone())).await;
+            } else {
+                // We mustn't do these in parallel because of how nonces are fetched.
+                broadcast_signature(
+                    &validator_ctx.broadcaster,
+                    gateway,
+                    checkpoint,
+                    &power_table,
+                    &validator,
+                    &validator_ctx.secret_key,
+                    chain_id,
+                )
+                .await
+                .context("failed to broadcast checkpoint signature")?;
+            }
 
             emit(CheckpointSigned {
                 role: CheckpointSignedRole::Own,
@@ -146,6 +146,12 @@ where
         Ok((state, ret))
     }
 
+    #[tracing::instrument(skip_all, fields(
+        height = state.block_height(),
+        from = %msg.from,
+        to = %msg.to,
+        method_num = msg.method_num,
+    ))]
     async fn deliver(
         &self,
         mut state: Self::State,
@@ -1,6 +1,7 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+pub(crate) mod access_list;
 mod broadcast;
 mod check;
 mod checkpoint;
@@ -8,6 +9,7 @@ mod exec;
 mod externs;
 pub mod observe;
 mod query;
+pub(crate) mod sponsor;
 pub mod state;
 pub mod store;
 pub mod upgrades;
@@ -17,7 +19,7 @@ pub mod bundle;
 pub(crate) mod topdown;
 
 pub use check::FvmCheckRet;
-pub use checkpoint::PowerUpdates;
+pub use checkpoint::{CheckpointSignaturePool, PowerUpdates};
 pub use exec::FvmApplyRet;
 use fendermint_crypto::{PublicKey, SecretKey};
 pub use fendermint_vm_message::query::FvmQuery;
@@ -39,6 +41,9 @@ pub struct ValidatorContext<C> {
     /// Used to broadcast transactions. It might use a different secret key for
     /// signing transactions than the validator's block producing key.
     broadcaster: Broadcaster<C>,
+    /// With ABCI 2.0 checkpoint signatures are collected here to be sent as vote extensions,
+    /// rather than broadcast as transactions.
+    signature_pool: Option<CheckpointSignaturePool>,
 }
 
 impl<C> ValidatorContext<C> {
@@ -49,8 +54,14 @@ impl<C> ValidatorContext<C> {
             secret_key,
             public_key,
             broadcaster,
+            signature_pool: None,
         }
     }
+
+    pub fn with_signature_pool(mut self, signature_pool: CheckpointSignaturePool) -> Self {
+        self.signature_pool = Some(signature_pool);
+        self
+    }
 }
 
 /// Interpreter working on already verified unsigned messages.
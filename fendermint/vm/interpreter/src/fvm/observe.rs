@@ -12,6 +12,8 @@ use prometheus::{
 };
 
 use fendermint_crypto::PublicKey;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::message::Message;
 
 register_metrics! {
@@ -23,6 +25,8 @@ register_metrics! {
         = register_histogram!("exec_fvm_apply_execution_time_secs", "Execution time of FVM apply in seconds");
     EXEC_FVM_CALL_EXECUTION_TIME_SECS: Histogram
         = register_histogram!("exec_fvm_call_execution_time_secs", "Execution time of FVM call in seconds");
+    EXEC_SPONSORED_REFUND_SHORTFALL_TOTAL: IntCounter
+        = register_int_counter!("exec_sponsored_refund_shortfall_total", "Sponsored messages whose unspent gas could not be fully refunded");
     BOTTOMUP_CHECKPOINT_CREATED_TOTAL: IntCounter
         = register_int_counter!("bottomup_checkpoint_created_total", "Bottom-up checkpoint produced");
     BOTTOMUP_CHECKPOINT_CREATED_HEIGHT: IntGauge
@@ -74,6 +78,26 @@ impl Recordable for MsgExec {
     }
 }
 
+impl_traceables!(TraceLevel::Warn, "Execution", SponsoredRefundShortfall);
+
+/// The sender of a sponsored message didn't have enough left to return the unspent deposit;
+/// the sponsor got back what was available and the rest stays with the sender.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct SponsoredRefundShortfall {
+    pub sponsor: Address,
+    pub sender: Address,
+    pub height: i64,
+    pub refund: TokenAmount,
+    pub refunded: TokenAmount,
+}
+
+impl Recordable for SponsoredRefundShortfall {
+    fn record_metrics(&self) {
+        EXEC_SPONSORED_REFUND_SHORTFALL_TOTAL.inc();
+    }
+}
+
 impl_traceables!(
     TraceLevel::Info,
     "Bottomup",
@@ -190,5 +214,13 @@ mod tests {
             hash: HexEncodableBlockHash(hash.clone()),
             validator: secret_key.public_key(),
         });
+
+        emit(SponsoredRefundShortfall {
+            sponsor: Address::new_id(1),
+            sender: Address::new_id(2),
+            height: 1,
+            refund: TokenAmount::from_atto(2),
+            refunded: TokenAmount::from_atto(1),
+        });
     }
 }
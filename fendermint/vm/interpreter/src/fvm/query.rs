@@ -4,6 +4,7 @@ use std::time::Instant;
 
 use async_trait::async_trait;
 use cid::Cid;
+use fendermint_actor_chainmetadata::BlockHashEntry;
 use fendermint_vm_message::query::{ActorState, FvmQuery, GasEstimate, StateParams};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::RawBytes;
@@ -35,6 +36,8 @@ pub enum FvmQueryRet {
     StateParams(StateParams),
     /// Builtin actors known by the system.
     BuiltinActors(Vec<(String, Cid)>),
+    /// Block hashes kept by the chain metadata actor.
+    BlockHashes(Vec<BlockHashEntry>),
 }
 
 #[async_trait]
@@ -118,9 +121,14 @@ where
                     method_num = msg.method_num,
                     "query estimate gas"
                 );
-                // Populate gas message parameters.
+                // A gas limit in the message is the most the estimate can be, e.g. a cap set by the API.
+                let max_gas = match msg.gas_limit {
+                    0 => BLOCK_GAS_LIMIT,
+                    limit => limit.min(BLOCK_GAS_LIMIT),
+                };
 
-                match self.estimate_gassed_msg(state, &mut msg).await? {
+                // Populate gas message parameters.
+                match self.estimate_gassed_msg(state, &mut msg, max_gas).await? {
                     (state, Some(est)) => {
                         // return immediately if something is returned,
                         // it means that the message failed to execute so there's
@@ -129,12 +137,13 @@ where
                     }
                     (state, None) => {
                         // perform a gas search for an accurate value
-                        let (state, mut est) = self.gas_search(state, &msg).await?;
+                        let (state, mut est) = self.gas_search(state, &msg, max_gas).await?;
                         // we need an additional overestimation for the case where
                         // the exact value is returned as part of the gas search
                         // (for some reason with subsequent calls sometimes this is the case).
-                        est.gas_limit =
-                            (est.gas_limit as f64 * self.gas_overestimation_rate) as u64;
+                        est.gas_limit = ((est.gas_limit as f64 * self.gas_overestimation_rate)
+                            as u64)
+                            .min(max_gas);
 
                         Ok((state, FvmQueryRet::EstimateGas(est)))
                     }
@@ -147,6 +156,7 @@ where
                     circ_supply: state_params.circ_supply.clone(),
                     chain_id: state_params.chain_id,
                     network_version: state_params.network_version,
+                    state_root: Some(state_params.state_root),
                 };
                 Ok((state, FvmQueryRet::StateParams(state_params)))
             }
@@ -154,6 +164,18 @@ where
                 let (state, ret) = state.builtin_actors().await?;
                 Ok((state, FvmQueryRet::BuiltinActors(ret)))
             }
+            FvmQuery::BlockHashes { from, to } => {
+                let (state, ret) = state.block_hashes(from, to).await?;
+                tracing::info!(
+                    height = state.block_height(),
+                    pending = state.pending(),
+                    from,
+                    to,
+                    found = ret.len(),
+                    "query block hashes"
+                );
+                Ok((state, FvmQueryRet::BlockHashes(ret)))
+            }
         }
     }
 }
@@ -166,9 +188,10 @@ where
         &self,
         state: FvmQueryState<DB>,
         msg: &mut Message,
+        max_gas: u64,
     ) -> anyhow::Result<(FvmQueryState<DB>, Option<GasEstimate>)> {
-        // Setting BlockGasLimit as initial limit for gas estimation
-        msg.gas_limit = BLOCK_GAS_LIMIT;
+        // Setting the most gas the message can use as initial limit for gas estimation
+        msg.gas_limit = max_gas;
 
         // With unlimited gas we are probably better off setting the prices to zero.
         let gas_premium = msg.gas_premium.clone();
@@ -238,8 +261,9 @@ where
         &self,
         mut state: FvmQueryState<DB>,
         msg: &Message,
+        max_gas: u64,
     ) -> anyhow::Result<(FvmQueryState<DB>, GasEstimate)> {
-        let mut curr_limit = msg.gas_limit;
+        let mut curr_limit = msg.gas_limit.min(max_gas);
 
         loop {
             let (st, est) = self
@@ -253,12 +277,12 @@ where
             }
 
             curr_limit = (curr_limit as f64 * self.gas_search_step) as u64;
-            if curr_limit > BLOCK_GAS_LIMIT {
+            if curr_limit > max_gas {
                 let est = GasEstimate {
                     exit_code: ExitCode::OK,
                     info: "".to_string(),
                     return_data: RawBytes::default(),
-                    gas_limit: BLOCK_GAS_LIMIT,
+                    gas_limit: max_gas,
                 };
                 return Ok((state, est));
             }
@@ -0,0 +1,319 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Gas sponsorship: before a sponsored message is executed, the sponsor deposits the most the
+//! message can cost with the sender, which then pays for the gas as usual; afterwards the
+//! sender returns whatever wasn't spent.
+
+use anyhow::Context;
+use fendermint_vm_actor_interface::burntfunds::BURNT_FUNDS_ACTOR_ADDR;
+use fendermint_vm_message::sponsored::SponsoredMessage;
+use fvm::executor::{ApplyFailure, ApplyRet};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{
+    address::Address, econ::TokenAmount, error::ExitCode, receipt::Receipt, BLOCK_GAS_LIMIT,
+    METHOD_SEND,
+};
+use ipc_observability::emit;
+use num_traits::Zero;
+
+use super::{
+    observe::SponsoredRefundShortfall, state::FvmExecState, FvmApplyRet, FvmCheckRet, FvmMessage,
+};
+
+/// Gas charged to the sponsor for the implicit transfers of the deposit and the refund,
+/// which the FVM doesn't account for.
+pub const SPONSORSHIP_GAS: u64 = 1_000_000;
+
+/// The outcome of asking the sponsor to pay for a message.
+pub struct Sponsorship {
+    /// The fee burned for [SPONSORSHIP_GAS] at the base fee, if the sponsor was charged.
+    ///
+    /// Once the sponsor's signature and sequence check out it pays even if the message is
+    /// rejected, otherwise sponsored messages could fill blocks for free.
+    pub fee: Option<TokenAmount>,
+    /// The deposit, or the reason the message is rejected.
+    pub deposit: Result<TokenAmount, String>,
+}
+
+/// Check that the sponsor can pay for the message, then charge the sponsorship fee, increment
+/// the sequence of the sponsor and move the maximum gas fee from the sponsor to the sender.
+///
+/// Nothing changes if the message is rejected before the sponsor is charged.
+pub fn deposit_gas<DB>(
+    state: &mut FvmExecState<DB>,
+    msg: &SponsoredMessage,
+) -> anyhow::Result<Sponsorship>
+where
+    DB: Blockstore + Clone + 'static,
+{
+    let message = msg.message();
+    let fee = state.base_fee().clone() * SPONSORSHIP_GAS;
+
+    let reject = |fee: Option<TokenAmount>, reason: String| -> anyhow::Result<Sponsorship> {
+        Ok(Sponsorship {
+            fee,
+            deposit: Err(reason),
+        })
+    };
+
+    let state_tree = state.state_tree_mut();
+
+    let sponsor_id = match state_tree.lookup_id(&msg.sponsor)? {
+        Some(id) => id,
+        None => return reject(None, format!("cannot find sponsor {}", msg.sponsor)),
+    };
+
+    let sponsor = match state_tree.get_actor(sponsor_id)? {
+        Some(actor) => actor,
+        None => return reject(None, format!("cannot find sponsor {}", msg.sponsor)),
+    };
+
+    if sponsor.sequence != msg.sponsor_sequence {
+        return reject(
+            None,
+            format!(
+                "expected sponsor sequence {}, got {}",
+                sponsor.sequence, msg.sponsor_sequence
+            ),
+        );
+    }
+    if sponsor.balance < fee {
+        return reject(
+            None,
+            format!(
+                "sponsor balance {} less than the sponsorship fee {}",
+                sponsor.balance, fee
+            ),
+        );
+    }
+
+    if !fee.is_zero() {
+        let ret = transfer(state, msg.sponsor, BURNT_FUNDS_ACTOR_ADDR, fee.clone())
+            .context("failed to charge sponsorship fee")?;
+
+        if !ret.msg_receipt.exit_code.is_success() {
+            return reject(
+                None,
+                format!(
+                    "failed to charge sponsorship fee: {}",
+                    ret.failure_info.map(|i| i.to_string()).unwrap_or_default()
+                ),
+            );
+        }
+    }
+
+    // Implicit messages don't touch the sequence, so this is what protects the sponsor from replays.
+    let state_tree = state.state_tree_mut();
+    let mut sponsor = state_tree
+        .get_actor(sponsor_id)?
+        .context("sponsor disappeared")?;
+    sponsor.sequence += 1;
+    state_tree.set_actor(sponsor_id, sponsor.clone());
+
+    // From here on the sponsor has paid for the message, whether it gets executed or not.
+    let fee = Some(fee);
+
+    // Reject messages the FVM would not execute, as they wouldn't pay for any gas.
+    if let Err(e) = message.check() {
+        return reject(fee, format!("pre-check failure: {:#}", e));
+    }
+
+    // The sender might not exist yet, in which case the deposit will create it.
+    let sender_sequence = match state_tree.lookup_id(&message.from)? {
+        Some(id) if id == sponsor_id => {
+            return reject(fee, "the sponsor cannot be the sender".to_string())
+        }
+        Some(id) => state_tree
+            .get_actor(id)?
+            .map(|actor| actor.sequence)
+            .unwrap_or_default(),
+        None => 0,
+    };
+
+    if sender_sequence != message.sequence {
+        return reject(
+            fee,
+            format!(
+                "expected sequence {}, got {}",
+                sender_sequence, message.sequence
+            ),
+        );
+    }
+
+    let deposit = msg.max_gas_fee();
+
+    if sponsor.balance < deposit {
+        return reject(
+            fee,
+            format!(
+                "sponsor balance {} less than needed {}",
+                sponsor.balance, deposit
+            ),
+        );
+    }
+
+    if !deposit.is_zero() {
+        let ret = transfer(state, msg.sponsor, message.from, deposit.clone())
+            .context("failed to deposit gas")?;
+
+        if !ret.msg_receipt.exit_code.is_success() {
+            return reject(
+                fee,
+                format!(
+                    "failed to deposit gas: {}",
+                    ret.failure_info.map(|i| i.to_string()).unwrap_or_default()
+                ),
+            );
+        }
+    }
+
+    Ok(Sponsorship {
+        fee,
+        deposit: Ok(deposit),
+    })
+}
+
+/// Return the part of the deposit that wasn't spent on gas to the sponsor.
+///
+/// If the message didn't get executed at all, the whole deposit is returned. The sender is
+/// not supposed to be able to spend the deposit, but if its balance falls short anyway, the
+/// sponsor gets back what is there and the shortfall is reported, rather than failing the block.
+pub fn refund_gas<DB>(
+    state: &mut FvmExecState<DB>,
+    msg: &SponsoredMessage,
+    deposit: &TokenAmount,
+    apply_ret: Option<&ApplyRet>,
+) -> anyhow::Result<()>
+where
+    DB: Blockstore + Clone + 'static,
+{
+    let charged = match apply_ret {
+        Some(ret) => {
+            ret.base_fee_burn.clone() + ret.over_estimation_burn.clone() + ret.miner_tip.clone()
+        }
+        None => TokenAmount::zero(),
+    };
+
+    let refund = deposit.clone() - charged;
+
+    if !refund.is_positive() {
+        return Ok(());
+    }
+
+    let sender = msg.message().from;
+    let state_tree = state.state_tree_mut();
+    let balance = match state_tree.lookup_id(&sender)? {
+        Some(id) => state_tree
+            .get_actor(id)?
+            .map(|actor| actor.balance)
+            .unwrap_or_default(),
+        None => TokenAmount::zero(),
+    };
+
+    let mut refunded = refund.clone().min(balance);
+
+    if refunded.is_positive() {
+        let ret = transfer(state, sender, msg.sponsor, refunded.clone())
+            .context("failed to refund gas")?;
+
+        if !ret.msg_receipt.exit_code.is_success() {
+            refunded = TokenAmount::zero();
+        }
+    }
+
+    if refunded < refund {
+        emit(SponsoredRefundShortfall {
+            sponsor: msg.sponsor,
+            sender,
+            height: state.block_height(),
+            refund,
+            refunded,
+        });
+    }
+
+    Ok(())
+}
+
+/// Account for the sponsorship in the result of a sponsored message, so the gas of the
+/// implicit transfers counts towards the gas used in the block, and the fee towards the burn.
+pub fn charge_sponsorship(apply_ret: &mut ApplyRet, fee: Option<&TokenAmount>) {
+    if let Some(fee) = fee {
+        apply_ret.msg_receipt.gas_used += SPONSORSHIP_GAS;
+        apply_ret.base_fee_burn += fee.clone();
+    }
+}
+
+/// The result of delivering a sponsored message that was rejected before execution.
+pub fn rejected_apply_ret(msg: &SponsoredMessage, reason: String) -> FvmApplyRet {
+    let message = msg.message();
+    let zero = TokenAmount::zero();
+    let apply_ret = ApplyRet {
+        msg_receipt: Receipt {
+            exit_code: ExitCode::SYS_SENDER_STATE_INVALID,
+            return_data: RawBytes::default(),
+            gas_used: 0,
+            events_root: None,
+        },
+        penalty: zero.clone(),
+        miner_tip: zero.clone(),
+        base_fee_burn: zero.clone(),
+        over_estimation_burn: zero.clone(),
+        refund: zero,
+        gas_refund: 0,
+        gas_burned: 0,
+        failure_info: Some(ApplyFailure::PreValidation(reason)),
+        exec_trace: Vec::new(),
+        events: Vec::new(),
+    };
+    FvmApplyRet {
+        apply_ret,
+        from: message.from,
+        to: message.to,
+        method_num: message.method_num,
+        gas_limit: message.gas_limit,
+        emitters: Default::default(),
+    }
+}
+
+/// The result of checking a sponsored message that was rejected before execution.
+pub fn rejected_check_ret(msg: &SponsoredMessage, reason: String) -> FvmCheckRet {
+    let message = msg.message();
+    FvmCheckRet {
+        sender: message.from,
+        gas_limit: message.gas_limit,
+        exit_code: ExitCode::SYS_SENDER_STATE_INVALID,
+        return_data: None,
+        info: Some(reason),
+        message: message.clone(),
+    }
+}
+
+/// Move funds between accounts without charging gas.
+pub(super) fn transfer<DB>(
+    state: &mut FvmExecState<DB>,
+    from: Address,
+    to: Address,
+    value: TokenAmount,
+) -> anyhow::Result<ApplyRet>
+where
+    DB: Blockstore + Clone + 'static,
+{
+    let msg = FvmMessage {
+        version: Default::default(),
+        from,
+        to,
+        sequence: 0,
+        value,
+        method_num: METHOD_SEND,
+        params: RawBytes::default(),
+        gas_limit: BLOCK_GAS_LIMIT,
+        gas_fee_cap: Default::default(),
+        gas_premium: Default::default(),
+    };
+
+    let (ret, _) = state.execute_implicit(msg)?;
+
+    Ok(ret)
+}
@@ -128,6 +128,27 @@ where
         multi_engine: &MultiEngine,
         block_height: ChainEpoch,
         params: FvmStateParams,
+    ) -> anyhow::Result<Self> {
+        Self::create(blockstore, multi_engine, block_height, params, false)
+    }
+
+    /// Create a new FVM execution environment which records the execution trace of messages,
+    /// including gas charges, in their `ApplyRet`. Meant for debugging, as it slows execution down.
+    pub fn new_traced(
+        blockstore: DB,
+        multi_engine: &MultiEngine,
+        block_height: ChainEpoch,
+        params: FvmStateParams,
+    ) -> anyhow::Result<Self> {
+        Self::create(blockstore, multi_engine, block_height, params, true)
+    }
+
+    fn create(
+        blockstore: DB,
+        multi_engine: &MultiEngine,
+        block_height: ChainEpoch,
+        params: FvmStateParams,
+        tracing: bool,
     ) -> anyhow::Result<Self> {
         let mut nc = NetworkConfig::new(params.network_version);
         nc.chain_id = ChainID::from(params.chain_id);
@@ -139,6 +160,10 @@ where
         mc.set_base_fee(params.base_fee.clone());
         mc.set_circulating_supply(params.circ_supply.clone());
 
+        if tracing {
+            mc.enable_tracing();
+        }
+
         // Creating a new machine every time is prohibitively slow.
         // let ec = EngineConfig::from(&nc);
         // let engine = EnginePool::new_default(ec)?;
@@ -237,6 +262,11 @@ where
         self.params.app_version
     }
 
+    /// The base fee of the currently executing block.
+    pub fn base_fee(&self) -> &TokenAmount {
+        &self.params.base_fee
+    }
+
     /// Get a mutable reference to the underlying [StateTree].
     pub fn state_tree_mut(&mut self) -> &mut StateTree<MachineBlockstore<DB>> {
         self.executor.state_tree_mut()
@@ -17,7 +17,7 @@ use fendermint_vm_actor_interface::{
     system, EMPTY_ARR,
 };
 use fendermint_vm_core::Timestamp;
-use fendermint_vm_genesis::{Account, Multisig, PowerScale};
+use fendermint_vm_genesis::{Account, Multisig, PowerScale, SignerAddr, Vesting};
 use fvm::{
     engine::MultiEngine,
     machine::Manifest,
@@ -358,18 +358,7 @@ where
 
         // Make sure every signer has their own account.
         for signer in ms.signers {
-            let id = ids
-                .get(&signer.0)
-                .ok_or_else(|| anyhow!("can't find ID for signer {}", signer.0))?;
-
-            if self
-                .with_state_tree(|s| s.get_actor(*id), |s| s.get_actor(*id))?
-                .is_none()
-            {
-                self.create_account_actor(Account { owner: signer }, TokenAmount::zero(), ids)?;
-            }
-
-            signers.push(*id)
+            signers.push(self.ensure_account_actor(signer, ids)?)
         }
 
         // Now create a multisig actor that manages group transactions.
@@ -385,6 +374,69 @@ where
         self.create_builtin_actor(MULTISIG_ACTOR_CODE_ID, next_id, &state, balance, None)
     }
 
+    pub fn create_vesting_actor(
+        &mut self,
+        vesting: Vesting,
+        balance: TokenAmount,
+        ids: &init::AddressMap,
+        next_id: ActorID,
+    ) -> anyhow::Result<()> {
+        if vesting.cliff > vesting.duration {
+            bail!(
+                "vesting cliff {} is longer than the duration {}",
+                vesting.cliff,
+                vesting.duration
+            );
+        }
+
+        // Make sure the owner and the revoker have their own accounts.
+        let owner = self.ensure_account_actor(vesting.owner, ids)?;
+        let revoker = match vesting.revoker {
+            Some(revoker) => Some(self.ensure_account_actor(revoker, ids)?),
+            None => None,
+        };
+
+        let state = fendermint_actor_vesting::State {
+            owner: Address::new_id(owner),
+            revoker: revoker.map(Address::new_id),
+            initial_balance: balance.clone(),
+            start_epoch: vesting.start as ChainEpoch,
+            cliff: vesting.cliff as ChainEpoch,
+            duration: vesting.duration as ChainEpoch,
+            revoked_at: None,
+        };
+
+        self.create_custom_actor(
+            fendermint_actor_vesting::VESTING_ACTOR_NAME,
+            next_id,
+            &state,
+            balance,
+            None,
+        )
+    }
+
+    /// Create an account actor with zero balance for a signer, unless it already exists.
+    ///
+    /// Returns the ID of the account.
+    fn ensure_account_actor(
+        &mut self,
+        signer: SignerAddr,
+        ids: &init::AddressMap,
+    ) -> anyhow::Result<ActorID> {
+        let id = *ids
+            .get(&signer.0)
+            .ok_or_else(|| anyhow!("can't find ID for signer {}", signer.0))?;
+
+        if self
+            .with_state_tree(|s| s.get_actor(id), |s| s.get_actor(id))?
+            .is_none()
+        {
+            self.create_account_actor(Account { owner: signer }, TokenAmount::zero(), ids)?;
+        }
+
+        Ok(id)
+    }
+
     /// Deploy an EVM contract with a fixed ID and some constructor arguments.
     ///
     /// Returns the hashed Ethereum address we can use to invoke the contract.
@@ -412,6 +464,18 @@ where
         &mut self,
         id: ActorID,
         initcode: Vec<u8>,
+    ) -> anyhow::Result<EthAddress> {
+        self.create_evm_actor_at(id, builtin_actor_eth_addr(id), initcode)
+    }
+
+    /// Deploy an EVM contract with a given delegated address.
+    ///
+    /// The address has to be registered with the `Init` actor under the same ID.
+    pub fn create_evm_actor_at(
+        &mut self,
+        id: ActorID,
+        eth_addr: EthAddress,
+        initcode: Vec<u8>,
     ) -> anyhow::Result<EthAddress> {
         // Here we are circumventing the normal way of creating an actor through the EAM and jump ahead to what the `Init` actor would do:
         // https://github.com/filecoin-project/builtin-actors/blob/421855a7b968114ac59422c1faeca968482eccf4/actors/init/src/lib.rs#L97-L107
@@ -427,7 +491,7 @@ where
         // When a contract is constructed the EVM actor verifies that it has an Ethereum delegated address.
         // This has been inserted into the Init actor state as well.
         let f0_addr = Address::new_id(id);
-        let f4_addr = Address::from(builtin_actor_eth_addr(id));
+        let f4_addr = Address::from(eth_addr);
 
         let msg = Message {
             version: 0,
@@ -488,12 +552,7 @@ where
             );
         }
 
-        let addr: [u8; 20] = match f4_addr.payload() {
-            Payload::Delegated(addr) => addr.subaddress().try_into().expect("hash is 20 bytes"),
-            other => panic!("not an f4 address: {other:?}"),
-        };
-
-        Ok(EthAddress(addr))
+        Ok(eth_addr)
     }
 
     pub fn store(&self) -> &DB {
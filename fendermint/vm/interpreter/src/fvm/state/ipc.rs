@@ -5,6 +5,7 @@ use anyhow::{anyhow, Context};
 use ethers::types as et;
 
 use fvm_ipld_blockstore::Blockstore;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::ActorID;
 
@@ -17,6 +18,7 @@ use fendermint_vm_actor_interface::{
 };
 use fendermint_vm_genesis::{Collateral, Power, PowerScale, Validator, ValidatorKey};
 use fendermint_vm_message::conv::{from_eth, from_fvm};
+use fendermint_vm_message::ipc::CheckpointSignature;
 use fendermint_vm_message::signed::sign_secp256k1;
 use fendermint_vm_topdown::IPCParentFinality;
 
@@ -30,7 +32,7 @@ use ipc_api::cross::IpcEnvelope;
 use ipc_api::staking::{ConfigurationNumber, StakingChangeRequest};
 
 use super::{
-    fevm::{ContractCaller, MockProvider, NoRevert},
+    fevm::{ContractCaller, ContractResult, MockContractCall, MockProvider, NoRevert},
     FvmExecState,
 };
 use crate::fvm::FvmApplyRet;
@@ -184,27 +186,21 @@ impl<DB: Blockstore + Clone> GatewayCaller<DB> {
         Ok((membership.configuration_number, power_table))
     }
 
-    /// Construct the input parameters for adding a signature to the checkpoint.
-    ///
-    /// This will need to be broadcasted as a transaction.
-    pub fn add_checkpoint_signature_calldata(
+    /// Sign the checkpoint as a validator and construct the Merkle proof of its membership in the power table.
+    pub fn checkpoint_signature(
         &self,
         checkpoint: checkpointing_facet::BottomUpCheckpoint,
         power_table: &[Validator<Power>],
         validator: &Validator<Power>,
         secret_key: &SecretKey,
-    ) -> anyhow::Result<et::Bytes> {
+    ) -> anyhow::Result<CheckpointSignature> {
         debug_assert_eq!(validator.public_key.0, secret_key.public_key());
 
-        let height = checkpoint.block_height;
-        let weight = et::U256::from(validator.power.0);
-
         let hash = checkpoint.abi_hash();
 
         let signature = sign_secp256k1(secret_key, &hash);
         let signature =
             from_fvm::to_eth_signature(&signature, false).context("invalid signature")?;
-        let signature = et::Bytes::from(signature.to_vec());
 
         let tree =
             ValidatorMerkleTree::new(power_table).context("failed to construct Merkle tree")?;
@@ -216,12 +212,29 @@ impl<DB: Blockstore + Clone> GatewayCaller<DB> {
             .map(|p| p.into())
             .collect();
 
-        let call = self.checkpointing.contract().add_checkpoint_signature(
-            height,
+        Ok(CheckpointSignature {
+            height: checkpoint.block_height.as_u64() as ChainEpoch,
             membership_proof,
-            weight,
-            signature,
-        );
+            weight: validator.power.0,
+            hash,
+            signature: signature.to_vec(),
+        })
+    }
+
+    /// Construct the input parameters for adding a signature to the checkpoint.
+    ///
+    /// This will need to be broadcasted as a transaction.
+    pub fn add_checkpoint_signature_calldata(
+        &self,
+        checkpoint: checkpointing_facet::BottomUpCheckpoint,
+        power_table: &[Validator<Power>],
+        validator: &Validator<Power>,
+        secret_key: &SecretKey,
+    ) -> anyhow::Result<et::Bytes> {
+        let signature =
+            self.checkpoint_signature(checkpoint, power_table, validator, secret_key)?;
+
+        let call = self.add_checkpoint_signature_call(signature);
 
         let calldata = call
             .calldata()
@@ -230,6 +243,31 @@ impl<DB: Blockstore + Clone> GatewayCaller<DB> {
         Ok(calldata)
     }
 
+    /// Add a signature to a checkpoint implicitly, as part of a block proposed by a validator,
+    /// rather than a transaction sent by the signatory.
+    ///
+    /// The gateway checks the signature and the membership proof, so it doesn't matter who relays it.
+    pub fn add_checkpoint_signature(
+        &self,
+        state: &mut FvmExecState<DB>,
+        signature: CheckpointSignature,
+    ) -> anyhow::Result<ContractResult<(), checkpointing_facet::CheckpointingFacetErrors>> {
+        self.checkpointing
+            .try_call(state, |_| self.add_checkpoint_signature_call(signature))
+    }
+
+    fn add_checkpoint_signature_call(
+        &self,
+        signature: CheckpointSignature,
+    ) -> MockContractCall<()> {
+        self.checkpointing.contract().add_checkpoint_signature(
+            et::U256::from(signature.height as u64),
+            signature.membership_proof,
+            et::U256::from(signature.weight),
+            et::Bytes::from(signature.signature),
+        )
+    }
+
     /// Commit the parent finality to the gateway and returns the previously committed finality.
     /// None implies there is no previously committed finality.
     pub fn commit_parent_finality(
@@ -7,6 +7,8 @@ use std::{cell::RefCell, sync::Arc};
 use anyhow::{anyhow, Context};
 
 use cid::Cid;
+use fendermint_actor_chainmetadata::BlockHashEntry;
+use fendermint_vm_actor_interface::chainmetadata::CHAINMETADATA_ACTOR_ADDR;
 use fendermint_vm_actor_interface::system::{
     is_system_addr, State as SystemState, SYSTEM_ACTOR_ADDR,
 };
@@ -225,6 +227,25 @@ where
         Ok((s, ret))
     }
 
+    /// Returns the block hashes kept by the chain metadata actor in an inclusive range of heights.
+    pub async fn block_hashes(
+        self,
+        from: ChainEpoch,
+        to: ChainEpoch,
+    ) -> anyhow::Result<(Self, Vec<BlockHashEntry>)> {
+        let (s, actor_state) = {
+            let (s, state) = self.actor_state(&CHAINMETADATA_ACTOR_ADDR).await?;
+            (s, state.ok_or(anyhow!("no chain metadata actor"))?.1)
+        };
+        let state: fendermint_actor_chainmetadata::State = s
+            .store
+            .get_cbor(&actor_state.state)
+            .context("failed to get chain metadata state")?
+            .ok_or(anyhow!("chain metadata actor state not found"))?;
+        let ret = state.get_block_hashes(&s.store, from, to)?;
+        Ok((s, ret))
+    }
+
     pub fn block_height(&self) -> ChainEpoch {
         self.block_height
     }
@@ -13,7 +13,7 @@ use fvm_ipld_car::{load_car, load_car_unchecked, CarHeader};
 use fvm_ipld_encoding::{from_slice, CborStore, DAG_CBOR};
 use libipld::Ipld;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -23,9 +23,15 @@ use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 pub type BlockHeight = u64;
 pub type SnapshotVersion = u32;
 
+/// Version of snapshots containing the complete state.
+pub const FULL_SNAPSHOT_VERSION: SnapshotVersion = 1;
+/// Version of snapshots containing only the blocks missing from the state of a base snapshot.
+pub const DELTA_SNAPSHOT_VERSION: SnapshotVersion = 2;
+
 /// Taking snapshot of the current blockchain state
 pub enum Snapshot<BS> {
     V1(V1Snapshot<BS>),
+    V2(V2Snapshot<BS>),
 }
 
 /// Contains the overall metadata for the snapshot
@@ -54,13 +60,46 @@ where
         )?))
     }
 
+    /// Create a delta snapshot which only contains the blocks that aren't reachable from the
+    /// state root of the base snapshot it is meant to be applied on top of.
+    pub fn new_delta(
+        store: BS,
+        state_params: FvmStateParams,
+        block_height: BlockHeight,
+        base_state_root: Cid,
+    ) -> anyhow::Result<Self> {
+        Ok(Self::V2(V2Snapshot::new(
+            store,
+            state_params,
+            block_height,
+            base_state_root,
+        )?))
+    }
+
     pub fn version(&self) -> SnapshotVersion {
         match self {
-            Snapshot::V1(_) => 1,
+            Snapshot::V1(_) => FULL_SNAPSHOT_VERSION,
+            Snapshot::V2(_) => DELTA_SNAPSHOT_VERSION,
+        }
+    }
+
+    pub fn block_height(&self) -> BlockHeight {
+        match self {
+            Snapshot::V1(inner) => inner.block_height(),
+            Snapshot::V2(inner) => inner.block_height(),
+        }
+    }
+
+    pub fn state_params(&self) -> &FvmStateParams {
+        match self {
+            Snapshot::V1(inner) => inner.state_params(),
+            Snapshot::V2(inner) => inner.state_params(),
         }
     }
 
-    /// Read the snapshot from file and load all the data into the store
+    /// Read the snapshot from file and load all the data into the store.
+    ///
+    /// A delta snapshot can only be read into a store which already contains the state of its base.
     pub async fn read_car(
         path: impl AsRef<Path>,
         store: BS,
@@ -90,6 +129,10 @@ where
                 store,
                 metadata.data_root_cid,
             )?)),
+            2 => Ok(Self::V2(V2Snapshot::from_root(
+                store,
+                metadata.data_root_cid,
+            )?)),
             v => Err(anyhow!("unknown snapshot version: {v}")),
         }
     }
@@ -136,6 +179,16 @@ where
                     streamer,
                 ))
             }
+            Snapshot::V2(inner) => {
+                let (data_root_cid, streamer) = inner.into_streamer()?;
+                Ok((
+                    SnapshotMetadata {
+                        version: 2,
+                        data_root_cid,
+                    },
+                    streamer,
+                ))
+            }
         }
     }
 }
@@ -212,6 +265,100 @@ where
     }
 }
 
+/// The data root of a delta snapshot: the block state parameters and the state root of the base snapshot.
+pub type DeltaStateParams = (FvmStateParams, BlockHeight, Cid);
+
+/// Snapshot of the blocks which are reachable from the current state root,
+/// but not from the state root of a base snapshot.
+pub struct V2Snapshot<BS> {
+    store: BS,
+    state_params: FvmStateParams,
+    block_height: BlockHeight,
+    base_state_root: Cid,
+}
+
+impl<BS> V2Snapshot<BS>
+where
+    BS: Blockstore + 'static + Send + Clone,
+{
+    pub fn new(
+        store: BS,
+        state_params: FvmStateParams,
+        block_height: BlockHeight,
+        base_state_root: Cid,
+    ) -> anyhow::Result<Self> {
+        // Check that both states are available before we start walking them.
+        let _ = StateTree::new_from_root(ReadOnlyBlockstore::new(store.clone()), &base_state_root)?;
+        let _ = StateTree::new_from_root(
+            ReadOnlyBlockstore::new(store.clone()),
+            &state_params.state_root,
+        )?;
+
+        Ok(Self {
+            store,
+            state_params,
+            block_height,
+            base_state_root,
+        })
+    }
+
+    fn from_root(store: BS, root_cid: Cid) -> anyhow::Result<Self> {
+        if let Some((state_params, block_height, base_state_root)) =
+            store.get_cbor::<DeltaStateParams>(&root_cid)?
+        {
+            if !store.has(&base_state_root)? {
+                return Err(anyhow!(
+                    "invalid v2 snapshot, base state root not found: {}",
+                    base_state_root
+                ));
+            }
+            Ok(Self {
+                store,
+                state_params,
+                block_height,
+                base_state_root,
+            })
+        } else {
+            Err(anyhow!(
+                "invalid v2 snapshot, root cid not found: {}",
+                root_cid
+            ))
+        }
+    }
+
+    fn into_streamer(self) -> anyhow::Result<(Cid, SnapshotStreamer)> {
+        let state_tree_root = self.state_params.state_root;
+
+        let delta_state_params = (self.state_params, self.block_height, self.base_state_root);
+        let (root_cid, bytes) = derive_cid(&delta_state_params)?;
+
+        // Everything reachable from the base is in the base snapshot, or the ones it builds on.
+        let base_cids = reachable_cids(&self.store, self.base_state_root)?;
+
+        let state_tree_streamer = StateTreeStreamer::with_exclusions(
+            state_tree_root,
+            ReadOnlyBlockstore::new(self.store),
+            base_cids,
+        );
+        let root_streamer = tokio_stream::iter(vec![(root_cid, bytes)]);
+        let streamer: SnapshotStreamer = Box::new(state_tree_streamer.merge(root_streamer));
+
+        Ok((root_cid, streamer))
+    }
+
+    pub fn block_height(&self) -> BlockHeight {
+        self.block_height
+    }
+
+    pub fn state_params(&self) -> &FvmStateParams {
+        &self.state_params
+    }
+
+    pub fn base_state_root(&self) -> &Cid {
+        &self.base_state_root
+    }
+}
+
 #[pin_project::pin_project]
 pub(crate) struct StateTreeStreamer<BS> {
     /// The list of cids to pull from the blockstore
@@ -219,16 +366,45 @@ pub(crate) struct StateTreeStreamer<BS> {
     dfs: VecDeque<Cid>,
     /// The block store
     bs: BS,
+    /// CIDs which should not be streamed, nor the blocks only reachable through them.
+    exclude: HashSet<Cid>,
 }
 
 impl<BS> StateTreeStreamer<BS> {
     pub fn new(state_root_cid: Cid, bs: BS) -> Self {
+        Self::with_exclusions(state_root_cid, bs, HashSet::new())
+    }
+
+    /// Stream the blocks reachable from the state root, except the excluded ones.
+    ///
+    /// Because blocks are content addressed, if the CIDs excluded are all the ones reachable
+    /// from another root, then the descendants of an excluded CID are all excluded as well.
+    pub fn with_exclusions(state_root_cid: Cid, bs: BS, exclude: HashSet<Cid>) -> Self {
         let mut dfs = VecDeque::new();
         dfs.push_back(state_root_cid);
-        Self { dfs, bs }
+        Self { dfs, bs, exclude }
     }
 }
 
+/// Collect the CIDs of all the blocks reachable from a root.
+fn reachable_cids<BS: Blockstore>(bs: &BS, root: Cid) -> anyhow::Result<HashSet<Cid>> {
+    let mut cids = HashSet::new();
+    let mut dfs = VecDeque::new();
+    dfs.push_back(root);
+
+    while let Some(cid) = dfs.pop_front() {
+        if !cids.insert(cid) || cid.codec() != DAG_CBOR {
+            continue;
+        }
+        if let Some(bytes) = bs.get(&cid)? {
+            let ipld = from_slice::<Ipld>(&bytes)?;
+            walk_ipld_cids(ipld, &mut dfs);
+        }
+    }
+
+    Ok(cids)
+}
+
 impl<BS: Blockstore> Stream for StateTreeStreamer<BS> {
     type Item = (Cid, Vec<u8>);
 
@@ -242,6 +418,10 @@ impl<BS: Blockstore> Stream for StateTreeStreamer<BS> {
                 return Poll::Ready(None);
             };
 
+            if this.exclude.contains(&cid) {
+                continue;
+            }
+
             match this.bs.get(&cid) {
                 Ok(Some(bytes)) => {
                     // Not all data in the blockstore is traversable, e.g.
@@ -349,6 +529,7 @@ mod tests {
         let mut stream = StateTreeStreamer {
             dfs: VecDeque::from(vec![root_cid]),
             bs: bs.clone(),
+            exclude: Default::default(),
         };
 
         let new_bs = MemoryBlockstore::new();
@@ -389,7 +570,10 @@ mod tests {
         let new_store = MemoryBlockstore::new();
         let Snapshot::V1(loaded_snapshot) = Snapshot::read_car(tmp_file.path(), new_store, true)
             .await
-            .unwrap();
+            .unwrap()
+        else {
+            panic!("expected a full snapshot");
+        };
 
         assert_eq!(state_params, loaded_snapshot.state_params);
         assert_eq!(block_height, loaded_snapshot.block_height);
@@ -398,4 +582,80 @@ mod tests {
             &loaded_snapshot.state_tree,
         );
     }
+
+    #[tokio::test]
+    async fn test_delta_car() {
+        let (base_state_root, state_tree) = prepare_state_tree(100);
+        let bs = state_tree.into_store();
+
+        // Change some of the actors and add a few new ones.
+        let mut state_tree = StateTree::new_from_root(bs.clone(), &base_state_root).unwrap();
+        let mut gen = Gen::new(16);
+        for i in 95..=105 {
+            state_tree.set_actor(i, ActorState::arbitrary(&mut gen));
+        }
+        let state_root = state_tree.flush().unwrap();
+
+        let state_params = |state_root| FvmStateParams {
+            state_root,
+            timestamp: Timestamp(100),
+            network_version: NetworkVersion::V1,
+            base_fee: Default::default(),
+            circ_supply: Default::default(),
+            chain_id: 1024,
+            power_scale: 0,
+            app_version: 0,
+        };
+
+        let base_file = tempfile::NamedTempFile::new().unwrap();
+        let delta_file = tempfile::NamedTempFile::new().unwrap();
+
+        Snapshot::new(bs.clone(), state_params(base_state_root), 1024)
+            .unwrap()
+            .write_car(base_file.path())
+            .await
+            .unwrap();
+
+        let delta =
+            Snapshot::new_delta(bs.clone(), state_params(state_root), 2048, base_state_root)
+                .unwrap();
+        assert_eq!(delta.version(), 2);
+        delta.write_car(delta_file.path()).await.unwrap();
+
+        let base_size = std::fs::metadata(base_file.path()).unwrap().len();
+        let delta_size = std::fs::metadata(delta_file.path()).unwrap().len();
+        assert!(
+            delta_size < base_size,
+            "delta should only contain the changes"
+        );
+
+        // The delta cannot be restored on its own.
+        assert!(
+            Snapshot::read_car(delta_file.path(), MemoryBlockstore::new(), true)
+                .await
+                .is_err(),
+            "delta should need the base"
+        );
+
+        let new_store = MemoryBlockstore::new();
+        Snapshot::read_car(base_file.path(), new_store.clone(), true)
+            .await
+            .unwrap();
+        let Snapshot::V2(loaded_snapshot) =
+            Snapshot::read_car(delta_file.path(), new_store.clone(), true)
+                .await
+                .unwrap()
+        else {
+            panic!("expected a delta snapshot");
+        };
+
+        assert_eq!(state_params(state_root), loaded_snapshot.state_params);
+        assert_eq!(2048, loaded_snapshot.block_height);
+        assert_eq!(base_state_root, loaded_snapshot.base_state_root);
+
+        let old_state_tree = StateTree::new_from_root(bs, &state_root).unwrap();
+        let new_state_tree = StateTree::new_from_root(new_store, &state_root).unwrap();
+        assert_tree2_contains_tree1(&old_state_tree, &new_state_tree);
+        assert_tree2_contains_tree1(&new_state_tree, &old_state_tree);
+    }
 }
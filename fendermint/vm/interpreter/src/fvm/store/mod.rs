@@ -5,6 +5,7 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_shared::EMPTY_ARR_CID;
 
 pub mod memory;
+pub mod overlay;
 
 #[derive(Clone)]
 pub struct ReadOnlyBlockstore<DB>(DB);
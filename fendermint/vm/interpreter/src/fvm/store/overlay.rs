@@ -0,0 +1,75 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use anyhow::Result;
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+
+use super::memory::MemoryBlockstore;
+
+/// A blockstore which reads through to an underlying store but keeps all writes in memory,
+/// so that messages can be executed on top of a persisted state without changing it.
+#[derive(Clone)]
+pub struct OverlayBlockstore<DB> {
+    base: DB,
+    overlay: MemoryBlockstore,
+}
+
+impl<DB> OverlayBlockstore<DB> {
+    pub fn new(base: DB) -> Self {
+        Self {
+            base,
+            overlay: MemoryBlockstore::new(),
+        }
+    }
+}
+
+impl<DB> Blockstore for OverlayBlockstore<DB>
+where
+    DB: Blockstore,
+{
+    fn get(&self, k: &Cid) -> Result<Option<Vec<u8>>> {
+        match self.overlay.get(k)? {
+            Some(block) => Ok(Some(block)),
+            None => self.base.get(k),
+        }
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> Result<()> {
+        self.overlay.put_keyed(k, block)
+    }
+
+    fn has(&self, k: &Cid) -> Result<bool> {
+        Ok(self.overlay.has(k)? || self.base.has(k)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cid::multihash::{Code, MultihashDigest};
+    use cid::Cid;
+    use fvm_ipld_blockstore::Blockstore;
+    use fvm_ipld_encoding::DAG_CBOR;
+
+    use super::OverlayBlockstore;
+    use crate::fvm::store::memory::MemoryBlockstore;
+
+    fn cid_of(data: &[u8]) -> Cid {
+        Cid::new_v1(DAG_CBOR, Code::Blake2b256.digest(data))
+    }
+
+    #[test]
+    fn writes_stay_in_overlay() {
+        let base = MemoryBlockstore::new();
+        let existing = b"existing".to_vec();
+        base.put_keyed(&cid_of(&existing), &existing).unwrap();
+
+        let overlay = OverlayBlockstore::new(base.clone());
+        let added = b"added".to_vec();
+        overlay.put_keyed(&cid_of(&added), &added).unwrap();
+
+        assert_eq!(overlay.get(&cid_of(&existing)).unwrap(), Some(existing));
+        assert_eq!(overlay.get(&cid_of(&added)).unwrap(), Some(added.clone()));
+        assert!(!base.has(&cid_of(&added)).unwrap());
+    }
+}
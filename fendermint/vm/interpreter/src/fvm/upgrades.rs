@@ -4,12 +4,16 @@
 use std::collections::BTreeMap;
 
 use anyhow::bail;
+use fendermint_vm_actor_interface::{chainmetadata, eam, system};
 use fendermint_vm_core::chainid;
 use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
 use fvm_shared::chainid::ChainID;
 use std::collections::btree_map::Entry::{Occupied, Vacant};
 
 use super::state::{snapshot::BlockHeight, FvmExecState};
+use super::FvmMessage;
 
 #[derive(PartialEq, Eq, Clone)]
 struct UpgradeKey(ChainID, BlockHeight);
@@ -147,6 +151,67 @@ where
     }
 }
 
+/// Change how many block hashes the chain metadata actor keeps, pruning the oldest ones if it shrinks.
+///
+/// Meant to be called from a [`MigrationFunc`], as only the system actor can change it.
+pub fn set_chainmetadata_lookback_len<DB>(
+    state: &mut FvmExecState<DB>,
+    lookback_len: u64,
+) -> anyhow::Result<()>
+where
+    DB: Blockstore + 'static + Clone,
+{
+    let msg = FvmMessage {
+        from: system::SYSTEM_ACTOR_ADDR,
+        to: chainmetadata::CHAINMETADATA_ACTOR_ADDR,
+        sequence: state.block_height() as u64,
+        gas_limit: fvm_shared::BLOCK_GAS_LIMIT,
+        method_num: fendermint_actor_chainmetadata::Method::SetLookbackLen as u64,
+        params: RawBytes::serialize(lookback_len)?,
+        value: Default::default(),
+        version: Default::default(),
+        gas_fee_cap: Default::default(),
+        gas_premium: Default::default(),
+    };
+
+    let (apply_ret, _) = state.execute_implicit(msg)?;
+
+    if let Some(err) = apply_ret.failure_info {
+        bail!("failed to set chainmetadata lookback length: {}", err);
+    }
+
+    Ok(())
+}
+
+/// Set the account allowed to manage the EAM deployers, or remove it with `None`.
+///
+/// Meant to be called from a [`MigrationFunc`] to install an admin on chains launched without one.
+pub fn set_eam_admin<DB>(state: &mut FvmExecState<DB>, admin: Option<Address>) -> anyhow::Result<()>
+where
+    DB: Blockstore + 'static + Clone,
+{
+    let msg = FvmMessage {
+        from: system::SYSTEM_ACTOR_ADDR,
+        to: eam::EAM_ACTOR_ADDR,
+        sequence: state.block_height() as u64,
+        gas_limit: fvm_shared::BLOCK_GAS_LIMIT,
+        method_num: fendermint_actor_eam::ExtraMethods::ChangeAdmin as u64,
+        params: RawBytes::serialize(admin)?,
+        value: Default::default(),
+        version: Default::default(),
+        gas_fee_cap: Default::default(),
+        gas_premium: Default::default(),
+    };
+
+    let (apply_ret, _) = state.execute_implicit(msg)?;
+
+    if let Some(err) = apply_ret.failure_info {
+        bail!("failed to set the EAM admin: {}", err);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_validate_upgrade_schedule() {
     use crate::fvm::store::memory::MemoryBlockstore;
@@ -1,7 +1,7 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::{Read, Write};
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
@@ -22,15 +22,19 @@ use fendermint_vm_actor_interface::{
     account, burntfunds, chainmetadata, cron, eam, init, ipc, reward, system, EMPTY_ARR,
 };
 use fendermint_vm_core::{chainid, Timestamp};
-use fendermint_vm_genesis::{ActorMeta, Collateral, Genesis, Power, PowerScale, Validator};
+use fendermint_vm_genesis::{
+    ActorMeta, Collateral, Contract, Genesis, LibraryLink, Power, PowerScale, Validator,
+};
 use futures_util::io::Cursor;
 use fvm::engine::MultiEngine;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_car::{load_car, CarHeader};
 use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::{Address, Payload};
 use fvm_shared::chainid::ChainID;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::version::NetworkVersion;
+use fvm_shared::ActorID;
 use ipc_actors_abis::i_diamond::FacetCut;
 use num_traits::Zero;
 
@@ -153,6 +157,8 @@ pub struct GenesisOutput {
     pub power_scale: PowerScale,
     pub circ_supply: TokenAmount,
     pub validators: Vec<Validator<Power>>,
+    /// Ethereum addresses of the contracts deployed from the genesis file, by name.
+    pub contracts: BTreeMap<String, et::Address>,
 }
 
 pub struct GenesisBuilder {
@@ -162,6 +168,8 @@ pub struct GenesisBuilder {
     builtin_actors_path: PathBuf,
     /// The custom actors bundle path
     custom_actors_path: PathBuf,
+    /// The directory relative contract artifact paths in the genesis file are resolved against
+    genesis_dir: Option<PathBuf>,
 
     /// Genesis params
     genesis_params: Genesis,
@@ -177,6 +185,7 @@ impl GenesisBuilder {
             hardhat: None,
             builtin_actors_path,
             custom_actors_path,
+            genesis_dir: None,
             genesis_params,
         }
     }
@@ -186,6 +195,13 @@ impl GenesisBuilder {
         self
     }
 
+    /// Resolve the relative contract artifact paths against the directory of the genesis file,
+    /// rather than the current working directory.
+    pub fn with_genesis_dir(mut self, dir: PathBuf) -> Self {
+        self.genesis_dir = Some(dir);
+        self
+    }
+
     /// Initialize actor states from the Genesis parameters and write the sealed genesis state to
     /// a CAR file specified by `out_path`
     pub async fn write_to(&self, out_path: PathBuf) -> anyhow::Result<()> {
@@ -290,7 +306,7 @@ impl GenesisBuilder {
         // Currently we just pass them back as they are, but later we should
         // store them in the IPC actors; or in case of a snapshot restore them
         // from the state.
-        let out = GenesisOutput {
+        let mut out = GenesisOutput {
             chain_id,
             timestamp: genesis.timestamp,
             network_version: genesis.network_version,
@@ -298,6 +314,7 @@ impl GenesisBuilder {
             base_fee: genesis.base_fee,
             power_scale: genesis.power_scale,
             validators,
+            contracts: BTreeMap::new(),
         };
 
         // STAGE 0: Declare the built-in EVM contracts we'll have to deploy.
@@ -308,6 +325,10 @@ impl GenesisBuilder {
             .transpose()?
             .unwrap_or((Vec::new(), EthContractMap::new()));
 
+        // The contracts listed in the genesis file are deployed after the IPC libraries.
+        let contract_addrs =
+            contract_addrs(&genesis.contracts).context("invalid genesis contracts")?;
+
         // STAGE 1: First we initialize native built-in actors.
         // System actor
         state
@@ -332,9 +353,20 @@ impl GenesisBuilder {
                 .map(|c| c.actor_id)
                 .collect::<BTreeSet<_>>(),
             all_ipc_contracts.len() as u64,
+            &contract_addrs,
         )
         .context("failed to create init state")?;
 
+        if let Some(addr) = contract_addrs
+            .iter()
+            .flatten()
+            .find(|a| addr_to_id.contains_key(&Address::from(**a)))
+        {
+            return Err(anyhow!(
+                "genesis contract address {addr} is already an account"
+            ));
+        }
+
         state
             .create_builtin_actor(
                 init::INIT_ACTOR_CODE_ID,
@@ -418,6 +450,7 @@ impl GenesisBuilder {
         let eam_state = fendermint_actor_eam::State::new(
             state.store(),
             PermissionModeParams::from(genesis.eam_permission_mode),
+            genesis.eam_admin.map(|a| a.0),
         )?;
         state
             .replace_builtin_actor(
@@ -433,7 +466,7 @@ impl GenesisBuilder {
         // STAGE 2: Create non-builtin accounts which do not have a fixed ID.
 
         // The next ID is going to be _after_ the accounts, which have already been assigned an ID by the `Init` actor.
-        // The reason we aren't using the `init_state.next_id` is because that already accounted for the multisig accounts.
+        // The reason we aren't using the `init_state.next_id` is because that already accounted for the multisig and vesting accounts.
         let mut next_id = init::FIRST_NON_SINGLETON_ADDR + addr_to_id.len() as u64;
 
         for a in genesis.accounts {
@@ -450,6 +483,17 @@ impl GenesisBuilder {
                         .context("failed to create multisig actor")?;
                     next_id += 1;
                 }
+                ActorMeta::Vesting(vesting) => {
+                    tracing::info!(
+                        owner = vesting.owner.0.to_string(),
+                        actor_id = next_id,
+                        "creating vesting actor"
+                    );
+                    state
+                        .create_vesting_actor(vesting, balance, &addr_to_id, next_id)
+                        .context("failed to create vesting actor")?;
+                    next_id += 1;
+                }
             }
         }
 
@@ -466,6 +510,23 @@ impl GenesisBuilder {
             )
             .context("failed to init exec state")?;
 
+        let contracts_next_id = next_id + all_ipc_contracts.len() as u64;
+
+        // The IPC contracts and the genesis contracts without a fixed address get an address derived
+        // from their actor ID, which must not be taken by any of the fixed addresses.
+        let reserved_ids = ipc_entrypoints
+            .values()
+            .map(|c| c.actor_id)
+            .chain(next_id..contracts_next_id)
+            .chain(
+                contract_addrs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, a)| a.is_none())
+                    .map(|(i, _)| contracts_next_id + i as u64),
+            );
+        check_reserved_addrs(&contract_addrs, reserved_ids).context("invalid genesis contracts")?;
+
         let maybe_ipc = self.handle_ipc(genesis.ipc.as_ref(), |hardhat, ipc_params| {
             (hardhat, ipc_params)
         })?;
@@ -481,6 +542,13 @@ impl GenesisBuilder {
             )?;
         }
 
+        out.contracts = deploy_genesis_contracts(
+            genesis.contracts,
+            self.genesis_dir.as_deref(),
+            contracts_next_id,
+            state,
+        )?;
+
         Ok(out)
     }
 }
@@ -590,6 +658,165 @@ fn deploy_contracts(
     Ok(())
 }
 
+/// Validate the contracts in the genesis file and collect their fixed addresses, if they have one.
+fn contract_addrs(contracts: &[Contract]) -> anyhow::Result<Vec<Option<EthAddress>>> {
+    let mut names = BTreeSet::new();
+    let mut addrs = BTreeSet::new();
+    let mut fixed_addrs = Vec::new();
+
+    for c in contracts {
+        if !names.insert(c.name.as_str()) {
+            return Err(anyhow!("duplicate contract name: {}", c.name));
+        }
+        let addr = match c.delegated_address {
+            None => None,
+            Some(ref addr) => {
+                let addr = delegated_eth_addr(addr)
+                    .with_context(|| format!("invalid address of contract {}", c.name))?;
+                if !addrs.insert(addr) {
+                    return Err(anyhow!("duplicate contract address: {addr}"));
+                }
+                Some(addr)
+            }
+        };
+        fixed_addrs.push(addr);
+    }
+
+    Ok(fixed_addrs)
+}
+
+/// Check that none of the fixed contract addresses is the same as the address derived from a reserved actor ID.
+fn check_reserved_addrs(
+    contract_addrs: &[Option<EthAddress>],
+    reserved_ids: impl IntoIterator<Item = ActorID>,
+) -> anyhow::Result<()> {
+    let fixed_addrs = contract_addrs.iter().flatten().collect::<BTreeSet<_>>();
+
+    for id in reserved_ids {
+        let addr = init::builtin_actor_eth_addr(id);
+        if fixed_addrs.contains(&addr) {
+            return Err(anyhow!(
+                "genesis contract address {addr} is reserved for actor {id}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Deploy the contracts listed in the genesis file in order, with consecutive actor IDs.
+///
+/// Relative artifact paths are resolved against `genesis_dir`, if given.
+///
+/// Returns the Ethereum address of each contract.
+fn deploy_genesis_contracts(
+    contracts: Vec<Contract>,
+    genesis_dir: Option<&Path>,
+    mut next_id: u64,
+    state: &mut FvmGenesisState<MemoryBlockstore>,
+) -> anyhow::Result<BTreeMap<String, et::Address>> {
+    let mut deployed = BTreeMap::new();
+
+    for c in contracts {
+        let artifact = match genesis_dir {
+            Some(dir) => dir.join(&c.artifact),
+            None => c.artifact.clone(),
+        };
+        let (hardhat, contract_src, contract_name) = artifact_location(&artifact)
+            .with_context(|| format!("invalid artifact of contract {}", c.name))?;
+
+        let mut libraries = HashMap::new();
+        for (lib, link) in c.libraries {
+            let lib_addr = match link {
+                LibraryLink::Contract(name) => *deployed.get(&name).ok_or_else(|| {
+                    anyhow!(
+                        "library {name} has to be deployed before contract {}",
+                        c.name
+                    )
+                })?,
+                LibraryLink::Address(addr) => match addr.payload() {
+                    Payload::ID(id) => et::Address::from(EthAddress::from_id(*id).0),
+                    _ => et::Address::from(delegated_eth_addr(&addr)?.0),
+                },
+            };
+            libraries.insert(lib, lib_addr);
+        }
+
+        let mut initcode = hardhat
+            .bytecode(&contract_src, &contract_name, &libraries)
+            .with_context(|| format!("failed to load {} bytecode", c.name))?;
+
+        initcode.extend(c.constructor_args);
+
+        let eth_addr = match c.delegated_address {
+            Some(ref addr) => delegated_eth_addr(addr)?,
+            None => init::builtin_actor_eth_addr(next_id),
+        };
+
+        let eth_addr = state
+            .create_evm_actor_at(next_id, eth_addr, initcode)
+            .with_context(|| format!("failed to create {} actor", c.name))?;
+
+        let eth_addr = et::Address::from(eth_addr.0);
+
+        tracing::info!(
+            actor_id = next_id,
+            ?eth_addr,
+            name = c.name.as_str(),
+            "deployed genesis contract"
+        );
+
+        deployed.insert(c.name, eth_addr);
+        next_id += 1;
+    }
+
+    Ok(deployed)
+}
+
+/// Split the path of an artifact such as `out/Token.sol/Token.json` into the
+/// artifacts directory, the contract source and the contract name.
+fn artifact_location(artifact: &Path) -> anyhow::Result<(Hardhat, PathBuf, String)> {
+    let contract_name = artifact
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| anyhow!("artifact has no file name: {artifact:?}"))?;
+
+    let source_dir = artifact
+        .parent()
+        .ok_or_else(|| anyhow!("artifact has no source directory: {artifact:?}"))?;
+
+    let contract_src = source_dir
+        .file_name()
+        .ok_or_else(|| anyhow!("artifact has no source directory: {artifact:?}"))?;
+
+    let contracts_dir = source_dir.parent().unwrap_or_else(|| Path::new(""));
+
+    Ok((
+        Hardhat::new(contracts_dir.to_path_buf()),
+        PathBuf::from(contract_src),
+        contract_name.to_string(),
+    ))
+}
+
+/// Ethereum address of an `f410` address which can be given to a contract.
+fn delegated_eth_addr(addr: &Address) -> anyhow::Result<EthAddress> {
+    match addr.payload() {
+        Payload::Delegated(d) if d.namespace() == eam::EAM_ACTOR_ID => {
+            let bytes: [u8; 20] = d
+                .subaddress()
+                .try_into()
+                .map_err(|_| anyhow!("not an Ethereum address: {addr}"))?;
+            let eth_addr = EthAddress(bytes);
+            // The EVM actor would reject a delegated address that looks like an ID address.
+            if eth_addr.is_masked_id() {
+                return Err(anyhow!("masked ID address cannot be delegated: {eth_addr}"));
+            }
+            Ok(eth_addr)
+        }
+        _ => Err(anyhow!("not an f410 address: {addr}")),
+    }
+}
+
 fn contract_src(name: &str) -> PathBuf {
     PathBuf::from(format!("{name}.sol"))
 }
@@ -759,7 +986,188 @@ pub async fn create_test_genesis_state(
 
 #[cfg(test)]
 mod tests {
-    use crate::genesis::GenesisAppState;
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    use ethers::core::types as et;
+    use fendermint_crypto::SecretKey;
+    use fendermint_vm_actor_interface::eam::EthAddress;
+    use fendermint_vm_actor_interface::{evm, init};
+    use fendermint_vm_genesis::{Actor, ActorMeta, Contract, Genesis, SignerAddr, Vesting};
+    use fvm_ipld_blockstore::Blockstore;
+    use fvm_ipld_encoding::CborStore;
+    use fvm_shared::address::Address;
+    use fvm_shared::econ::TokenAmount;
+    use quickcheck::Arbitrary;
+
+    use crate::fvm::bundle::{bundle_path, custom_actors_bundle_path};
+    use crate::genesis::{
+        artifact_location, check_reserved_addrs, delegated_eth_addr, GenesisAppState,
+        GenesisBuilder,
+    };
+
+    #[test]
+    fn test_artifact_location() {
+        let (_, src, name) = artifact_location(Path::new("out/Multicall3.sol/Multicall3.json"))
+            .expect("valid artifact path");
+        assert_eq!(src, PathBuf::from("Multicall3.sol"));
+        assert_eq!(name, "Multicall3");
+
+        assert!(artifact_location(Path::new("Multicall3.json")).is_err());
+    }
+
+    #[test]
+    fn test_delegated_eth_addr() {
+        let eth_addr = EthAddress([1u8; 20]);
+        assert_eq!(
+            delegated_eth_addr(&Address::from(eth_addr)).expect("f410 address"),
+            eth_addr
+        );
+
+        assert!(delegated_eth_addr(&Address::new_id(100)).is_err());
+        let masked_id = Address::new_delegated(10, &EthAddress::from_id(100).0).unwrap();
+        assert!(delegated_eth_addr(&masked_id).is_err());
+    }
+
+    #[test]
+    fn test_check_reserved_addrs() {
+        let fixed = EthAddress([1u8; 20]);
+        let reserved = init::builtin_actor_eth_addr(110);
+
+        assert!(check_reserved_addrs(&[Some(fixed), None], 100..120).is_ok());
+        assert!(check_reserved_addrs(&[None, Some(reserved)], 100..120).is_err());
+        assert!(check_reserved_addrs(&[Some(reserved)], 100..110).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_seal_genesis_contract() {
+        let dir = tempfile::tempdir().expect("temp dir");
+
+        // Write a Hardhat style artifact next to where the genesis file would be.
+        let bytecode = include_str!("../../../testing/contracts/SimpleCoin.bin");
+        let runtime = include_str!("../../../testing/contracts/SimpleCoin.bin-runtime");
+        let artifact = Path::new("out/SimpleCoin.sol/SimpleCoin.json");
+        let artifact_json = serde_json::json!({
+            "bytecode": { "object": bytecode.trim(), "linkReferences": {} }
+        });
+        std::fs::create_dir_all(dir.path().join(artifact.parent().unwrap())).unwrap();
+        std::fs::write(dir.path().join(artifact), artifact_json.to_string()).unwrap();
+
+        let eth_addr = EthAddress([0xab; 20]);
+
+        let mut genesis = Genesis::arbitrary(&mut quickcheck::Gen::new(5));
+        genesis.ipc = None;
+        genesis.contracts = vec![Contract {
+            name: "SimpleCoin".to_string(),
+            artifact: artifact.to_path_buf(),
+            constructor_args: Vec::new(),
+            libraries: BTreeMap::new(),
+            delegated_address: Some(Address::from(eth_addr)),
+        }];
+
+        let builder = GenesisBuilder::new(bundle_path(), custom_actors_bundle_path(), genesis)
+            .with_genesis_dir(dir.path().to_path_buf());
+
+        let mut state = builder.init_state().await.expect("init state");
+        let out = builder
+            .populate_state(&mut state, builder.genesis_params.clone())
+            .expect("genesis contract deployed");
+
+        assert_eq!(
+            out.contracts.get("SimpleCoin"),
+            Some(&et::Address::from(eth_addr.0))
+        );
+
+        let exec = state.exec_state().expect("exec state");
+
+        let id = exec
+            .state_tree()
+            .lookup_id(&Address::from(eth_addr))
+            .expect("lookup works")
+            .expect("contract has an ID");
+
+        let actor = exec
+            .state_tree()
+            .get_actor(id)
+            .expect("get actor works")
+            .expect("contract exists");
+
+        assert_eq!(
+            Some(&actor.code),
+            exec.builtin_actors().code_by_id(evm::EVM_ACTOR_CODE_ID)
+        );
+
+        let store = exec.state_tree().store();
+        let evm_state = store
+            .get_cbor::<evm::State>(&actor.state)
+            .expect("state can be loaded")
+            .expect("state exists");
+        let code = store
+            .get(&evm_state.bytecode)
+            .expect("bytecode can be loaded")
+            .expect("bytecode exists");
+
+        assert_eq!(code, hex::decode(runtime.trim()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_seal_genesis_vesting() {
+        let sk = SecretKey::random(&mut rand::thread_rng());
+        let owner = Address::new_secp256k1(&sk.public_key().serialize()).unwrap();
+        let balance = TokenAmount::from_whole(10);
+
+        let mut genesis = Genesis::arbitrary(&mut quickcheck::Gen::new(5));
+        genesis.ipc = None;
+        genesis.contracts = Vec::new();
+        genesis.accounts = vec![Actor {
+            meta: ActorMeta::Vesting(Vesting {
+                owner: SignerAddr(owner),
+                revoker: None,
+                start: 0,
+                cliff: 100,
+                duration: 100,
+            }),
+            balance: balance.clone(),
+        }];
+
+        let builder = GenesisBuilder::new(bundle_path(), custom_actors_bundle_path(), genesis);
+
+        let mut state = builder.init_state().await.expect("init state");
+        builder
+            .populate_state(&mut state, builder.genesis_params.clone())
+            .expect("vesting actor created");
+
+        let exec = state.exec_state().expect("exec state");
+
+        // The owner gets an account first, then the vesting actor is created after it.
+        let owner_id = exec
+            .state_tree()
+            .lookup_id(&owner)
+            .expect("lookup works")
+            .expect("owner has an ID");
+
+        assert_eq!(owner_id, init::FIRST_NON_SINGLETON_ADDR);
+
+        let actor = exec
+            .state_tree()
+            .get_actor(owner_id + 1)
+            .expect("get actor works")
+            .expect("vesting actor exists");
+
+        assert_eq!(actor.balance, balance);
+
+        let vesting_state = exec
+            .state_tree()
+            .store()
+            .get_cbor::<fendermint_actor_vesting::State>(&actor.state)
+            .expect("state can be loaded")
+            .expect("state exists");
+
+        assert_eq!(vesting_state.owner, Address::new_id(owner_id));
+        assert_eq!(vesting_state.initial_balance, balance);
+        assert_eq!(vesting_state.vested_amount(99), TokenAmount::zero());
+        assert_eq!(vesting_state.vested_amount(100), balance);
+    }
 
     #[test]
     fn test_compression() {
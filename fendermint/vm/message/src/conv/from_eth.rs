@@ -3,7 +3,13 @@
 
 //! Helper methods to convert between Ethereum and FVM data formats.
 
-use ethers_core::types::{Eip1559TransactionRequest, NameOrAddress, H160, U256};
+use anyhow::bail;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::transaction::eip2930::AccessList;
+use ethers_core::types::{
+    self as et, Eip1559TransactionRequest, NameOrAddress, TransactionRequest, H160, U256,
+};
+use ethers_core::utils::rlp;
 use fendermint_vm_actor_interface::{
     eam::{self, EthAddress},
     evm,
@@ -12,10 +18,13 @@ use fvm_ipld_encoding::{BytesSer, RawBytes};
 use fvm_shared::{
     address::Address,
     bigint::{BigInt, Sign},
+    crypto::signature::{Signature, SECP_SIG_LEN, SIGNATURE_MAX_LENGTH},
     econ::TokenAmount,
     message::Message,
 };
 
+use super::EthTxType;
+
 // https://github.com/filecoin-project/lotus/blob/594c52b96537a8c8728389b446482a2d7ea5617c/chain/types/ethtypes/eth_transactions.go#L152
 pub fn to_fvm_message(tx: &Eip1559TransactionRequest) -> anyhow::Result<Message> {
     // FIP-55 says that we should use `InvokeContract` for transfers instead of `METHOD_SEND`,
@@ -63,6 +72,77 @@ pub fn to_fvm_message(tx: &Eip1559TransactionRequest) -> anyhow::Result<Message>
     Ok(msg)
 }
 
+/// Convert any of the supported Ethereum transaction envelopes to an FVM message.
+///
+/// Legacy and EIP-2930 transactions have a single gas price, which is used both as the fee cap
+/// and the premium, so that they pay the price they signed for, like they do in Lotus.
+///
+/// The message has no place for the access list, it goes into the signature instead,
+/// see [to_fvm_signature].
+pub fn to_fvm_message_typed(tx: &TypedTransaction) -> anyhow::Result<Message> {
+    match tx {
+        TypedTransaction::Eip1559(tx) => to_fvm_message(tx),
+        TypedTransaction::Eip2930(tx) => to_fvm_message(&from_legacy_request(&tx.tx)),
+        TypedTransaction::Legacy(tx) => to_fvm_message(&from_legacy_request(tx)),
+    }
+}
+
+fn from_legacy_request(tx: &TransactionRequest) -> Eip1559TransactionRequest {
+    Eip1559TransactionRequest {
+        from: tx.from,
+        to: tx.to.clone(),
+        gas: tx.gas,
+        value: tx.value,
+        data: tx.data.clone(),
+        nonce: tx.nonce,
+        access_list: AccessList::default(),
+        max_priority_fee_per_gas: tx.gas_price,
+        max_fee_per_gas: tx.gas_price,
+        chain_id: tx.chain_id,
+    }
+}
+
+/// Turn the signature of an Ethereum transaction into the FVM signature of the message,
+/// recording the type and the access list of the transaction, which are needed to check
+/// the signature later.
+///
+/// Legacy transactions have to be replay protected by including the chain ID in `v`,
+/// and the access list has to fit into the maximum length of a signature.
+pub fn to_fvm_signature(tx: &TypedTransaction, sig: &et::Signature) -> anyhow::Result<Signature> {
+    let tx_type = EthTxType::from(tx);
+    let access_list = tx.access_list().filter(|al| !al.0.is_empty());
+
+    let rec_id = match (tx_type, sig.v) {
+        (EthTxType::Legacy, 27 | 28) => {
+            bail!("legacy transactions without EIP-155 replay protection are not supported")
+        }
+        (EthTxType::Legacy, v) if v >= 35 => (v - 35) % 2,
+        (EthTxType::Eip2930 | EthTxType::Eip1559, v @ (0 | 1)) => v,
+        (_, v) => bail!("unexpected signature v value for {tx_type:?} transaction: {v}"),
+    };
+
+    let mut bytes = Vec::with_capacity(SECP_SIG_LEN + 1);
+    bytes.extend(tx_type.signature_prefix(access_list.is_some()));
+
+    let mut rs = [0u8; 32];
+    sig.r.to_big_endian(&mut rs);
+    bytes.extend_from_slice(&rs);
+    sig.s.to_big_endian(&mut rs);
+    bytes.extend_from_slice(&rs);
+    bytes.push(rec_id as u8);
+
+    if let Some(access_list) = access_list {
+        bytes.extend_from_slice(&rlp::encode(access_list));
+    }
+
+    // The serialized signature is prefixed by its type.
+    if bytes.len() + 1 > SIGNATURE_MAX_LENGTH as usize {
+        bail!("the access list is too long");
+    }
+
+    Ok(Signature::new_secp256k1(bytes))
+}
+
 pub fn to_fvm_address(addr: H160) -> Address {
     Address::from(EthAddress(addr.0))
 }
@@ -78,7 +158,10 @@ pub fn to_fvm_tokens(value: &U256) -> TokenAmount {
 mod tests {
 
     use ethers_core::{
-        types::{transaction::eip2718::TypedTransaction, Bytes, TxHash},
+        types::{
+            transaction::{eip2718::TypedTransaction, eip2930::AccessList},
+            Bytes, TxHash,
+        },
         utils::rlp,
     };
     use fendermint_testing::arb::ArbTokenAmount;
@@ -86,12 +169,62 @@ mod tests {
     use quickcheck_macros::quickcheck;
 
     use crate::{
-        conv::{from_eth::to_fvm_message, from_fvm::to_eth_tokens},
+        conv::{
+            access_list_gas,
+            from_eth::{to_fvm_message, to_fvm_message_typed, to_fvm_signature},
+            from_fvm::{to_eth_access_list, to_eth_tokens, to_eth_tx_signature, to_eth_tx_type},
+            EthTxType,
+        },
         signed::{DomainHash, SignedMessage},
     };
 
     use super::to_fvm_tokens;
 
+    /// Decode a signed raw transaction, convert it to a signed FVM message, and check that
+    /// it can be verified and hashed the same way as the original.
+    fn check_raw_transaction(raw_tx: &str, tx_type: EthTxType, from: &str) {
+        let raw_tx: Bytes = raw_tx.parse().unwrap();
+        let rlp = rlp::Rlp::new(&raw_tx);
+        let tx_hash = TxHash::from(ethers_core::utils::keccak256(rlp.as_raw()));
+
+        let (tx, sig) = TypedTransaction::decode_signed(&rlp).expect("decode signed tx");
+        let chain_id: ChainID = tx.chain_id().unwrap().as_u64().into();
+
+        let msg = SignedMessage {
+            message: to_fvm_message_typed(&tx).expect("to_fvm_message_typed"),
+            signature: to_fvm_signature(&tx, &sig).expect("to_fvm_signature"),
+        };
+
+        assert_eq!(to_eth_tx_type(msg.signature()), tx_type);
+        assert_eq!(
+            &to_eth_access_list(msg.signature()).expect("to_eth_access_list"),
+            tx.access_list().unwrap_or(&AccessList::default())
+        );
+        assert_eq!(msg.message.gas_fee_cap, msg.message.gas_premium);
+
+        let from: ethers_core::types::Address = from.parse().unwrap();
+        assert_eq!(
+            msg.message.from,
+            super::to_fvm_address(from),
+            "sender recovered by ethers"
+        );
+
+        msg.verify(&chain_id).expect("signature should be valid");
+
+        // The original signature is restored, including the EIP-155 `v`.
+        let (_, sig1) = to_eth_tx_signature(msg.signature(), &chain_id).unwrap();
+        assert_eq!(sig1, sig);
+
+        match msg.domain_hash(&chain_id).expect("domain_hash") {
+            Some(DomainHash::Eth(h)) => assert_eq!(h, tx_hash.0),
+            other => panic!("unexpected domain hash: {other:?}"),
+        }
+
+        // The signature doesn't cover the chain ID of another chain.
+        let other_chain_id = ChainID::from(u64::from(chain_id) + 1);
+        assert!(msg.verify(&other_chain_id).is_err());
+    }
+
     #[quickcheck]
     fn prop_to_token_amount(tokens: ArbTokenAmount) -> bool {
         let tokens0 = tokens.0;
@@ -131,4 +264,93 @@ mod tests {
             other => panic!("unexpected domain hash: {other:?}"),
         }
     }
+
+    #[test]
+    fn test_legacy_eip155_transaction() {
+        // The example transaction from the EIP-155 specification, with chain ID 1.
+        check_raw_transaction(
+            "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+            EthTxType::Legacy,
+            "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+        );
+    }
+
+    #[test]
+    fn test_eip2930_transaction() {
+        // The same transfer as the EIP-155 example, as a type 1 transaction signed by the same key.
+        check_raw_transaction(
+            "0x01f86e01098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080c001a07891ea9edfbabd29bc07e16e2a8c867cb7ed8134d9eb1668f707e76e56d86af6a06a4e88e3128eb06d3fbe2851768f0018f95be6b68bb30a3b48bc25d4e5c59c22",
+            EthTxType::Eip2930,
+            "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+        );
+    }
+
+    #[test]
+    fn test_eip2930_transaction_with_access_list() {
+        // The same transfer signed by the same key, with two addresses and two storage keys
+        // in the access list, paying for them in the gas limit.
+        let raw_tx = "0x01f8e101098504a817c8008273a0943535353535353535353535353535353535353535880de0b6b3a764000080f872f859943535353535353535353535353535353535353535f842a00000000000000000000000000000000000000000000000000000000000000001a00000000000000000000000000000000000000000000000000000000000000002d694de0b295669a9fd93d5f28d9ec85e40f4cb697baec001a0089903fc3ca6a65f79c658d0fd998fa496ef33d01fa3b4f1ff32d20105a07721a022b90975beb508d6341e555391427c341f397c8c92941cd92ad50589cf605de5";
+
+        check_raw_transaction(
+            raw_tx,
+            EthTxType::Eip2930,
+            "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+        );
+
+        let raw_tx: Bytes = raw_tx.parse().unwrap();
+        let (tx, sig) =
+            TypedTransaction::decode_signed(&rlp::Rlp::new(&raw_tx)).expect("decode signed tx");
+
+        let msg = SignedMessage {
+            message: to_fvm_message_typed(&tx).expect("to_fvm_message_typed"),
+            signature: to_fvm_signature(&tx, &sig).expect("to_fvm_signature"),
+        };
+
+        assert_eq!(msg.access_list_gas(), 2 * 2400 + 2 * 1900);
+        assert_eq!(
+            access_list_gas(tx.access_list().unwrap()),
+            msg.access_list_gas()
+        );
+
+        // The access list is covered by the signature.
+        let mut tampered = msg.clone();
+        tampered.signature.bytes.truncate(66);
+        assert!(tampered.verify(&ChainID::from(1)).is_err());
+    }
+
+    #[test]
+    fn test_reject_unsupported_transactions() {
+        use ethers_core::types::Signature as EthSignature;
+
+        // Pre EIP-155 signatures could be replayed on any chain.
+        let sig = EthSignature {
+            v: 27,
+            r: 1.into(),
+            s: 1.into(),
+        };
+        let tx = TypedTransaction::Legacy(Default::default());
+        assert!(to_fvm_signature(&tx, &sig).is_err());
+    }
+
+    #[test]
+    fn test_reject_malleable_access_lists() {
+        let mut bytes = vec![0x01u8];
+        bytes.extend([1u8; 65]);
+
+        // Empty access lists are not appended.
+        let mut empty = bytes.clone();
+        empty.extend_from_slice(&rlp::encode(&AccessList::default()));
+        assert!(to_eth_access_list(&Signature::new_secp256k1(empty)).is_err());
+
+        // Legacy transactions can't have any.
+        let mut legacy = bytes.clone();
+        legacy[0] = 0x00;
+        legacy.push(0xc0);
+        assert!(to_eth_access_list(&Signature::new_secp256k1(legacy)).is_err());
+
+        // EIP-1559 transactions are only prefixed if they have one.
+        let mut eip1559 = bytes;
+        eip1559[0] = 0x02;
+        assert!(to_eth_access_list(&Signature::new_secp256k1(eip1559)).is_err());
+    }
 }
@@ -8,6 +8,9 @@ use std::str::FromStr;
 use anyhow::anyhow;
 use anyhow::bail;
 use ethers_core::types as et;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::transaction::eip2930::AccessList;
+use ethers_core::utils::rlp;
 use fendermint_crypto::{RecoveryId, Signature};
 use fendermint_vm_actor_interface::eam::EthAddress;
 use fendermint_vm_actor_interface::eam::EAM_ACTOR_ID;
@@ -22,6 +25,8 @@ use fvm_shared::message::Message;
 use fvm_shared::{address::Payload, econ::TokenAmount};
 use lazy_static::lazy_static;
 
+use super::EthTxType;
+
 lazy_static! {
     pub static ref MAX_U256: BigInt = BigInt::from_str(&et::U256::MAX.to_string()).unwrap();
 }
@@ -93,6 +98,121 @@ pub fn to_eth_signature(sig: &FvmSignature, normalized: bool) -> anyhow::Result<
     Ok(sig)
 }
 
+/// Tell which Ethereum transaction envelope an FVM signature was made for.
+///
+/// Anything without a known prefix is assumed to be EIP-1559, which will fail to parse
+/// as an Ethereum signature later if it isn't one.
+pub fn to_eth_tx_type(sig: &FvmSignature) -> EthTxType {
+    if sig.sig_type == SignatureType::Secp256k1 && sig.bytes.len() > SECP_SIG_LEN {
+        for tx_type in [EthTxType::Legacy, EthTxType::Eip2930, EthTxType::Eip1559] {
+            if tx_type.signature_prefix(true) == Some(sig.bytes[0]) {
+                return tx_type;
+            }
+        }
+    }
+    EthTxType::Eip1559
+}
+
+/// Recover the access list of the Ethereum transaction an FVM signature was made for.
+///
+/// Only non-empty access lists are appended to the signature, and only in their canonical
+/// RLP encoding, so that there is exactly one valid signature for each transaction.
+pub fn to_eth_access_list(sig: &FvmSignature) -> anyhow::Result<AccessList> {
+    if sig.sig_type != SignatureType::Secp256k1 || sig.bytes.len() <= SECP_SIG_LEN {
+        return Ok(AccessList::default());
+    }
+
+    let bytes = &sig.bytes[SECP_SIG_LEN + 1..];
+
+    match to_eth_tx_type(sig) {
+        EthTxType::Legacy | EthTxType::Eip2930 if bytes.is_empty() => Ok(AccessList::default()),
+        EthTxType::Legacy => bail!("legacy transactions cannot have an access list"),
+        _ => {
+            let access_list = rlp::decode::<AccessList>(bytes)
+                .map_err(|e| anyhow!("failed to decode access list: {e}"))?;
+
+            if access_list.0.is_empty() || rlp::encode(&access_list).as_ref() != bytes {
+                bail!("unexpected access list encoding");
+            }
+
+            Ok(access_list)
+        }
+    }
+}
+
+/// Convert the FVM signature of a message sent by an Ethereum account to the signature of the
+/// original transaction, with `v` in the form its envelope type uses, ie. with the chain ID
+/// incorporated for legacy transactions according to EIP-155.
+pub fn to_eth_tx_signature(
+    sig: &FvmSignature,
+    chain_id: &ChainID,
+) -> anyhow::Result<(EthTxType, et::Signature)> {
+    let tx_type = to_eth_tx_type(sig);
+
+    let sig = if sig.bytes.len() > SECP_SIG_LEN {
+        FvmSignature::new_secp256k1(sig.bytes[1..=SECP_SIG_LEN].to_vec())
+    } else {
+        sig.clone()
+    };
+
+    let mut sig = to_eth_signature(&sig, true)?;
+
+    if tx_type == EthTxType::Legacy {
+        let chain_id: u64 = (*chain_id).into();
+        sig.v = chain_id
+            .checked_mul(2)
+            .and_then(|v| v.checked_add(35 + sig.v))
+            .ok_or_else(|| anyhow!("chain ID too large for EIP-155: {chain_id}"))?;
+    }
+
+    Ok((tx_type, sig))
+}
+
+/// Turn an FVM `Message` back into an Ethereum transaction of the given type.
+///
+/// Legacy and EIP-2930 transactions only have a gas price, so the message must have been
+/// created with the same fee cap and premium, otherwise the premium would not be signed.
+///
+/// The access list is not part of the message, it has to be recovered from the signature.
+pub fn to_eth_typed_transaction(
+    msg: &Message,
+    chain_id: &ChainID,
+    tx_type: EthTxType,
+    access_list: AccessList,
+) -> anyhow::Result<TypedTransaction> {
+    let mut tx = to_eth_transaction_request(msg, chain_id)?;
+
+    if tx_type == EthTxType::Eip1559 {
+        tx.access_list = access_list;
+        return Ok(tx.into());
+    }
+
+    if tx_type == EthTxType::Legacy && !access_list.0.is_empty() {
+        bail!("legacy transactions cannot have an access list");
+    }
+
+    if msg.gas_fee_cap != msg.gas_premium {
+        bail!("{tx_type:?} transactions must have the same gas fee cap and premium");
+    }
+
+    let mut legacy = et::TransactionRequest::new();
+    legacy.from = tx.from;
+    legacy.to = tx.to;
+    legacy.gas = tx.gas;
+    legacy.gas_price = tx.max_fee_per_gas;
+    legacy.value = tx.value;
+    legacy.data = tx.data;
+    legacy.nonce = tx.nonce;
+    legacy.chain_id = tx.chain_id;
+
+    let tx = match tx_type {
+        EthTxType::Legacy => TypedTransaction::Legacy(legacy),
+        _ => TypedTransaction::Eip2930(et::Eip2930TransactionRequest::new(legacy, access_list)),
+    };
+
+    Ok(tx)
+}
+
 /// Turn an FVM `Message` back into an Ethereum transaction request.
 pub fn to_eth_transaction_request(
     msg: &Message,
@@ -142,6 +262,10 @@ pub mod tests {
     use std::str::FromStr;
 
     use ethers::signers::{Signer, Wallet};
+    use ethers_core::types::{
+        self as et,
+        transaction::eip2930::{AccessList, AccessListItem},
+    };
     use ethers_core::utils::rlp;
     use ethers_core::{k256::ecdsa::SigningKey, types::transaction::eip2718::TypedTransaction};
     use fendermint_crypto::SecretKey;
@@ -153,11 +277,14 @@ pub mod tests {
     use rand::{rngs::StdRng, SeedableRng};
 
     use crate::conv::{
-        from_eth::to_fvm_message,
+        from_eth::{to_fvm_message, to_fvm_message_typed, to_fvm_signature},
         tests::{EthMessage, KeyPair},
+        EthTxType,
     };
 
-    use super::{to_eth_signature, to_eth_tokens, to_eth_transaction_request};
+    use super::{
+        to_eth_signature, to_eth_tokens, to_eth_transaction_request, to_eth_typed_transaction,
+    };
 
     #[quickcheck]
     fn prop_to_eth_tokens(tokens: ArbTokenAmount) -> bool {
@@ -251,4 +378,60 @@ pub mod tests {
 
         signed.verify(&chain_id).expect("signature should be valid")
     }
+
+    /// Check that legacy and EIP-2930 transactions signed by a Wallet can be verified after conversion to FVM.
+    #[quickcheck]
+    fn prop_typed_eth_signature(msg: EthMessage, chain_id: u64, key_pair: KeyPair, legacy: bool) {
+        // EIP-155 would overflow `v` if the chain ID is too big.
+        let chain_id = ChainID::from(chain_id / 3);
+        let tx_type = if legacy {
+            EthTxType::Legacy
+        } else {
+            EthTxType::Eip2930
+        };
+
+        // Only one gas price can be signed.
+        let mut msg0 = msg.0;
+        msg0.gas_premium = msg0.gas_fee_cap.clone();
+
+        // Only the newer envelopes can have an access list.
+        let access_list = if legacy {
+            AccessList::default()
+        } else {
+            AccessList(vec![AccessListItem {
+                address: et::H160::from_low_u64_be(u64::from(chain_id)),
+                storage_keys: vec![et::H256::from_low_u64_be(msg0.sequence)],
+            }])
+        };
+
+        let tx = to_eth_typed_transaction(&msg0, &chain_id, tx_type, access_list)
+            .expect("to_eth_typed_transaction failed");
+
+        let wallet: Wallet<SigningKey> = Wallet::from_bytes(key_pair.sk.serialize().as_ref())
+            .expect("failed to create wallet")
+            .with_chain_id(chain_id);
+
+        let sig = wallet.sign_transaction_sync(&tx).expect("failed to sign");
+
+        let bz = tx.rlp_signed(&sig);
+        let rlp = rlp::Rlp::new(bz.as_ref());
+
+        let (tx1, sig) = TypedTransaction::decode_signed(&rlp)
+            .expect("failed to decode RLP as signed TypedTransaction");
+
+        assert_eq!(EthTxType::from(&tx1), tx_type);
+
+        let signed = SignedMessage {
+            message: to_fvm_message_typed(&tx1).expect("to_fvm_message_typed failed"),
+            signature: to_fvm_signature(&tx1, &sig).expect("to_fvm_signature failed"),
+        };
+
+        signed.verify(&chain_id).expect("signature should be valid");
+
+        // Tampering with the unsigned premium is detected.
+        let mut tampered = signed;
+        tampered.message.gas_premium =
+            tampered.message.gas_premium.clone() + TokenAmount::from_atto(1);
+        assert!(tampered.verify(&chain_id).is_err());
+    }
 }
@@ -1,9 +1,74 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use ethers_core::types::transaction::{eip2718::TypedTransaction, eip2930::AccessList};
+
 pub mod from_eth;
 pub mod from_fvm;
 
+/// The Ethereum transaction envelope a message from an Ethereum account was signed as.
+///
+/// The FVM `Message` has no field for it, so it is recorded in the `Signature` instead:
+/// EIP-1559 signatures are the plain 65 bytes they have always been, while the signatures
+/// of the other types are prefixed by a byte identifying the type, similar to Lotus.
+///
+/// The same goes for the access list: if it's not empty, the RLP encoded list is appended to
+/// the signature, which is then prefixed by the type even for EIP-1559.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EthTxType {
+    /// Pre EIP-2718 transaction with EIP-155 replay protection.
+    Legacy,
+    /// EIP-2930 transaction with an access list.
+    Eip2930,
+    /// EIP-1559 transaction with dynamic fees.
+    Eip1559,
+}
+
+impl EthTxType {
+    /// The EIP-2718 transaction type, as it appears in the JSON-RPC API.
+    pub fn as_u64(&self) -> u64 {
+        match self {
+            EthTxType::Legacy => 0,
+            EthTxType::Eip2930 => 1,
+            EthTxType::Eip1559 => 2,
+        }
+    }
+
+    /// The byte the FVM signature is prefixed with, if any.
+    pub(crate) fn signature_prefix(&self, has_access_list: bool) -> Option<u8> {
+        match self {
+            EthTxType::Eip1559 if !has_access_list => None,
+            _ => Some(self.as_u64() as u8),
+        }
+    }
+}
+
+/// Gas charged for each address in an access list, as per EIP-2930.
+pub const ACCESS_LIST_ADDRESS_GAS: u64 = 2400;
+/// Gas charged for each storage key in an access list, as per EIP-2930.
+pub const ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1900;
+
+/// The gas an Ethereum transaction pays for its access list up front.
+///
+/// The FVM doesn't know about access lists, so this is charged on top of the gas used by the message.
+pub fn access_list_gas(access_list: &AccessList) -> u64 {
+    access_list.0.iter().fold(0u64, |gas, item| {
+        gas.saturating_add(ACCESS_LIST_ADDRESS_GAS).saturating_add(
+            ACCESS_LIST_STORAGE_KEY_GAS.saturating_mul(item.storage_keys.len() as u64),
+        )
+    })
+}
+
+impl From<&TypedTransaction> for EthTxType {
+    fn from(tx: &TypedTransaction) -> Self {
+        match tx {
+            TypedTransaction::Legacy(_) => EthTxType::Legacy,
+            TypedTransaction::Eip2930(_) => EthTxType::Eip2930,
+            TypedTransaction::Eip1559(_) => EthTxType::Eip1559,
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use fendermint_crypto::{PublicKey, SecretKey};
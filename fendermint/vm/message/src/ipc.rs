@@ -1,7 +1,10 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use anyhow::anyhow;
 use cid::Cid;
+use fendermint_crypto::PublicKey;
+use fvm_shared::crypto::signature::{ops::recover_secp_public_key, SECP_SIG_LEN};
 use fvm_shared::{
     address::Address, clock::ChainEpoch, crypto::signature::Signature, econ::TokenAmount,
 };
@@ -28,6 +31,11 @@ pub enum IpcMessage {
     /// A top-down checkpoint parent finality proposal. This proposal should contain the latest parent
     /// state that to be checked and voted by validators.
     TopDownExec(ParentFinality),
+
+    /// Signatures of validators over bottom-up checkpoints of the current subnet, which the proposer
+    /// collected from the vote extensions of the previous height, instead of each validator sending
+    /// them as a separate transaction. Only used with ABCI 2.0.
+    CheckpointSignatures(Vec<CheckpointSignature>),
 }
 
 /// A message relayed by a user on the current subnet.
@@ -94,6 +102,43 @@ pub struct BottomUpCheckpoint {
     pub bottom_up_messages: Cid, // TODO: Use TCid
 }
 
+/// A validator signature over a bottom-up checkpoint of the current subnet, along with
+/// the evidence the gateway needs to check that the validator is in the power table.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct CheckpointSignature {
+    /// Block height of the checkpoint.
+    pub height: ChainEpoch,
+    /// Merkle proof of the validator and its weight being part of the power table.
+    pub membership_proof: Vec<[u8; 32]>,
+    /// Power of the validator in the power table of the checkpoint.
+    pub weight: u64,
+    /// ABI hash of the checkpoint, which lets the signatory be recovered before execution.
+    ///
+    /// The gateway checks the signature against its own copy of the checkpoint, not this.
+    pub hash: [u8; 32],
+    /// Recoverable secp256k1 signature over the ABI hash of the checkpoint.
+    pub signature: Vec<u8>,
+}
+
+impl CheckpointSignature {
+    /// Recover the public key of the validator who signed the hash.
+    pub fn recover_signer(&self) -> anyhow::Result<PublicKey> {
+        let mut sig: [u8; SECP_SIG_LEN] = self
+            .signature
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow!("invalid signature length: {}", self.signature.len()))?;
+
+        // The signature is in the Ethereum format, with 27 added to the recovery ID.
+        sig[64] = sig[64]
+            .checked_sub(27)
+            .ok_or_else(|| anyhow!("invalid recovery ID: {}", sig[64]))?;
+
+        recover_secp_public_key(&self.hash, &sig)
+            .map_err(|e| anyhow!("failed to recover signer: {e}"))
+    }
+}
+
 /// A proposal of the parent view that validators will be voting on.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct ParentFinality {
@@ -112,16 +157,17 @@ mod arb {
     use quickcheck::{Arbitrary, Gen};
 
     use super::{
-        BottomUpCheckpoint, CertifiedMessage, IpcMessage, MultiSig, RelayedMessage,
-        SignedRelayedMessage, ValidatorSignature,
+        BottomUpCheckpoint, CertifiedMessage, CheckpointSignature, IpcMessage, MultiSig,
+        RelayedMessage, SignedRelayedMessage, ValidatorSignature,
     };
 
     impl Arbitrary for IpcMessage {
         fn arbitrary(g: &mut Gen) -> Self {
-            match u8::arbitrary(g) % 3 {
+            match u8::arbitrary(g) % 4 {
                 0 => IpcMessage::BottomUpResolve(Arbitrary::arbitrary(g)),
                 1 => IpcMessage::BottomUpExec(Arbitrary::arbitrary(g)),
-                _ => IpcMessage::TopDownExec(Arbitrary::arbitrary(g)),
+                2 => IpcMessage::TopDownExec(Arbitrary::arbitrary(g)),
+                _ => IpcMessage::CheckpointSignatures(Arbitrary::arbitrary(g)),
             }
         }
     }
@@ -187,6 +233,29 @@ mod arb {
         }
     }
 
+    impl Arbitrary for CheckpointSignature {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let mut hash = [0u8; 32];
+            hash.fill_with(|| u8::arbitrary(g));
+            let mut signature = [0u8; 65];
+            signature.fill_with(|| u8::arbitrary(g));
+            Self {
+                height: u32::arbitrary(g).into(),
+                membership_proof: Vec::<u64>::arbitrary(g)
+                    .into_iter()
+                    .map(|x| {
+                        let mut p = [0u8; 32];
+                        p[..8].copy_from_slice(&x.to_be_bytes());
+                        p
+                    })
+                    .collect(),
+                weight: u64::arbitrary(g),
+                hash,
+                signature: signature.to_vec(),
+            }
+        }
+    }
+
     impl Arbitrary for ParentFinality {
         fn arbitrary(g: &mut Gen) -> Self {
             Self {
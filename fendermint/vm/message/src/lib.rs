@@ -9,6 +9,7 @@ pub mod conv;
 pub mod ipc;
 pub mod query;
 pub mod signed;
+pub mod sponsored;
 
 /// Calculate the CID using Blake2b256 digest and DAG_CBOR.
 ///
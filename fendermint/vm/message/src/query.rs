@@ -3,8 +3,8 @@
 use cid::Cid;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::{
-    address::Address, econ::TokenAmount, error::ExitCode, message::Message as FvmMessage,
-    version::NetworkVersion,
+    address::Address, clock::ChainEpoch, econ::TokenAmount, error::ExitCode,
+    message::Message as FvmMessage, version::NetworkVersion,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -49,6 +49,18 @@ impl From<FvmQueryHeight> for u64 {
     }
 }
 
+/// ABCI query path to look up the quorum certificate of the parent finality committed
+/// at the IPLD encoded parent block height sent as the query data.
+///
+/// Unlike [FvmQuery], this is answered from the application's own storage, not the state tree.
+pub const PARENT_FINALITY_CERTIFICATE_PATH: &str = "/topdown/certificate";
+
+/// ABCI query path to list the parent blocks this node has cached for top-down finality,
+/// along with their validator changes and top-down messages which are yet to be executed.
+///
+/// Like [PARENT_FINALITY_CERTIFICATE_PATH], this is answered by the application, and takes no data.
+pub const PARENT_VIEW_PATH: &str = "/topdown/parent-view";
+
 /// Queries over the IPLD blockstore or the state tree.
 ///
 /// Maybe we can have some common queries over the known state of built-in actors,
@@ -76,6 +88,10 @@ pub enum FvmQuery {
     StateParams,
     /// Query the built-in actors known by the System actor.
     BuiltinActors,
+    /// Query the block hashes kept by the chain metadata actor in an inclusive range of heights.
+    ///
+    /// The response is the IPLD encoded list of heights and hashes found in the range.
+    BlockHashes { from: ChainEpoch, to: ChainEpoch },
 }
 
 /// State of all actor implementations.
@@ -142,6 +158,12 @@ pub struct StateParams {
     pub chain_id: u64,
     /// Current network version.
     pub network_version: NetworkVersion,
+    /// Root of the state tree the query was performed on.
+    ///
+    /// It is missing in the responses of nodes running earlier versions.
+    #[serde(default)]
+    #[serde_as(as = "Option<IsHumanReadable>")]
+    pub state_root: Option<Cid>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
@@ -6,7 +6,7 @@ use anyhow::anyhow;
 use cid::multihash::MultihashDigest;
 use cid::Cid;
 use ethers_core::types as et;
-use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::transaction::eip2930::AccessList;
 use fendermint_crypto::{PublicKey, SecretKey};
 use fendermint_vm_actor_interface::eam::EthAddress;
 use fendermint_vm_actor_interface::{eam, evm};
@@ -19,7 +19,7 @@ use fvm_shared::message::Message;
 
 use thiserror::Error;
 
-use crate::conv::from_fvm;
+use crate::conv::{access_list_gas, from_fvm, EthTxType};
 
 enum Signable {
     /// Pair of transaction hash and from.
@@ -79,7 +79,12 @@ impl SignedMessage {
         sk: &SecretKey,
         chain_id: &ChainID,
     ) -> Result<Self, SignedMessageError> {
-        let signature = match Self::signable(&message, chain_id)? {
+        let signature = match Self::signable(
+            &message,
+            chain_id,
+            EthTxType::Eip1559,
+            AccessList::default(),
+        )? {
             Signable::Ethereum((hash, _)) => sign_eth(sk, hash),
             Signable::Regular(data) => sign_regular(sk, &data),
             Signable::RegularFromEth((data, _)) => sign_regular(sk, &data),
@@ -96,7 +101,14 @@ impl SignedMessage {
     ///
     /// The [`ChainID`] is used as a replay attack protection, a variation of
     /// https://github.com/filecoin-project/FIPs/blob/master/FIPS/fip-0039.md
-    fn signable(message: &Message, chain_id: &ChainID) -> Result<Signable, SignedMessageError> {
+    ///
+    /// The [`EthTxType`] and the [`AccessList`] are only used if the message is an Ethereum transaction.
+    fn signable(
+        message: &Message,
+        chain_id: &ChainID,
+        tx_type: EthTxType,
+        access_list: AccessList,
+    ) -> Result<Signable, SignedMessageError> {
         // Here we look at the sender to decide what scheme to use for hashing.
         //
         // This is in contrast to https://github.com/filecoin-project/FIPs/blob/master/FIPS/fip-0055.md#delegated-signature-type
@@ -115,9 +127,9 @@ impl SignedMessage {
         // which should allow messages from ethereum accounts to go to any other type of account, e.g. custom Wasm actors.
         match maybe_eth_address(&message.from) {
             Some(addr) if is_eth_addr_compat(&message.to) => {
-                let tx: TypedTransaction = from_fvm::to_eth_transaction_request(message, chain_id)
-                    .map_err(SignedMessageError::Ethereum)?
-                    .into();
+                let tx =
+                    from_fvm::to_eth_typed_transaction(message, chain_id, tx_type, access_list)
+                        .map_err(SignedMessageError::Ethereum)?;
 
                 Ok(Signable::Ethereum((tx.sighash(), addr)))
             }
@@ -140,11 +152,18 @@ impl SignedMessage {
         signature: &Signature,
         chain_id: &ChainID,
     ) -> Result<(), SignedMessageError> {
-        match Self::signable(message, chain_id)? {
+        let tx_type = from_fvm::to_eth_tx_type(signature);
+        let access_list = if maybe_eth_address(&message.from).is_some() {
+            from_fvm::to_eth_access_list(signature).map_err(SignedMessageError::Ethereum)?
+        } else {
+            AccessList::default()
+        };
+
+        match Self::signable(message, chain_id, tx_type, access_list)? {
             Signable::Ethereum((hash, from)) => {
                 // If the sender is ethereum, recover the public key from the signature (which verifies it),
                 // then turn it into an `EthAddress` and verify it matches the `from` of the message.
-                let sig = from_fvm::to_eth_signature(signature, true)
+                let (_, sig) = from_fvm::to_eth_tx_signature(signature, chain_id)
                     .map_err(SignedMessageError::Ethereum)?;
 
                 let rec = sig
@@ -184,14 +203,16 @@ impl SignedMessage {
         chain_id: &ChainID,
     ) -> Result<Option<DomainHash>, SignedMessageError> {
         if is_eth_addr_deleg(&self.message.from) && is_eth_addr_compat(&self.message.to) {
-            let tx: TypedTransaction =
-                from_fvm::to_eth_transaction_request(self.message(), chain_id)
-                    .map_err(SignedMessageError::Ethereum)?
-                    .into();
+            let (tx_type, sig) = from_fvm::to_eth_tx_signature(self.signature(), chain_id)
+                .map_err(SignedMessageError::Ethereum)?;
 
-            let sig = from_fvm::to_eth_signature(self.signature(), true)
+            let access_list = from_fvm::to_eth_access_list(self.signature())
                 .map_err(SignedMessageError::Ethereum)?;
 
+            let tx =
+                from_fvm::to_eth_typed_transaction(self.message(), chain_id, tx_type, access_list)
+                    .map_err(SignedMessageError::Ethereum)?;
+
             let rlp = tx.rlp_signed(&sig);
 
             let hash = cid::multihash::Code::Keccak256.digest(&rlp);
@@ -204,6 +225,15 @@ impl SignedMessage {
         }
     }
 
+    /// Gas for the access list of an Ethereum transaction, which the FVM doesn't charge for.
+    ///
+    /// Only meaningful once the signature has been verified.
+    pub fn access_list_gas(&self) -> u64 {
+        from_fvm::to_eth_access_list(self.signature())
+            .map(|access_list| access_list_gas(&access_list))
+            .unwrap_or_default()
+    }
+
     /// Verifies that the from address of the message generated the signature.
     pub fn verify(&self, chain_id: &ChainID) -> Result<(), SignedMessageError> {
         Self::verify_signature(&self.message, &self.signature, chain_id)
@@ -236,7 +266,7 @@ impl SignedMessage {
 }
 
 /// Sign a transaction pre-image using Blake2b256, in a way that [Signature::verify] expects it.
-fn sign_regular(sk: &SecretKey, data: &[u8]) -> Signature {
+pub(crate) fn sign_regular(sk: &SecretKey, data: &[u8]) -> Signature {
     let hash: [u8; 32] = blake2b_simd::Params::new()
         .hash_length(32)
         .to_state()
@@ -260,7 +290,7 @@ pub fn chain_id_bytes(chain_id: &ChainID) -> [u8; 8] {
 }
 
 /// Return the 20 byte Ethereum address if the address is that kind of delegated one.
-fn maybe_eth_address(addr: &Address) -> Option<et::H160> {
+pub(crate) fn maybe_eth_address(addr: &Address) -> Option<et::H160> {
     match addr.payload() {
         Payload::Delegated(addr)
             if addr.namespace() == eam::EAM_ACTOR_ID && addr.subaddress().len() == 20 =>
@@ -321,7 +351,7 @@ pub fn sign_secp256k1(sk: &SecretKey, hash: &[u8; 32]) -> Signature {
 /// Recover the public key from a Secp256k1
 ///
 /// Based on how `Signature` does it, but without the final address hashing.
-fn recover_secp256k1(signature: &Signature, data: &[u8]) -> Result<PublicKey, String> {
+pub(crate) fn recover_secp256k1(signature: &Signature, data: &[u8]) -> Result<PublicKey, String> {
     let signature = &signature.bytes;
 
     if signature.len() != SECP_SIG_LEN {
@@ -0,0 +1,211 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use fendermint_crypto::SecretKey;
+use fendermint_vm_actor_interface::eam::EthAddress;
+use fvm_ipld_encoding::tuple::{Deserialize_tuple, Serialize_tuple};
+use fvm_shared::address::Address;
+use fvm_shared::chainid::ChainID;
+use fvm_shared::crypto::signature::Signature;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::message::Message;
+
+use crate::signed::{
+    chain_id_bytes, maybe_eth_address, recover_secp256k1, sign_regular, SignedMessage,
+    SignedMessageError,
+};
+
+/// A message executed as its sender, with the gas paid for by a sponsor.
+///
+/// The user signs the message as usual, and the sponsor signs the CID of the message
+/// together with its own address and sequence. Executing the message increments the
+/// sequence of both parties, so neither signature can be replayed.
+#[derive(PartialEq, Clone, Debug, Serialize_tuple, Deserialize_tuple, Hash, Eq)]
+pub struct SponsoredMessage {
+    /// The message signed by the user, who is the sender.
+    pub message: SignedMessage,
+    /// The account paying for the gas.
+    pub sponsor: Address,
+    /// The expected sequence of the sponsor account.
+    pub sponsor_sequence: u64,
+    /// Signature of the sponsor over the user message, the sponsor and its sequence.
+    pub sponsor_signature: Signature,
+}
+
+impl SponsoredMessage {
+    /// Sponsor a message already signed by the user.
+    ///
+    /// The sponsor address can be an `f1` address, or an `f410` one derived from the same key.
+    pub fn new_secp256k1(
+        message: SignedMessage,
+        sponsor: Address,
+        sponsor_sequence: u64,
+        sk: &SecretKey,
+        chain_id: &ChainID,
+    ) -> Result<Self, SignedMessageError> {
+        let data = Self::signable(message.message(), &sponsor, sponsor_sequence, chain_id)?;
+        let sponsor_signature = sign_regular(sk, &data);
+        Ok(Self {
+            message,
+            sponsor,
+            sponsor_sequence,
+            sponsor_signature,
+        })
+    }
+
+    /// Calculate the bytes that the sponsor needs to sign.
+    ///
+    /// The user message is committed to by its CID, which covers its sequence and gas parameters,
+    /// so the sponsor knows the most it can be charged.
+    fn signable(
+        message: &Message,
+        sponsor: &Address,
+        sponsor_sequence: u64,
+        chain_id: &ChainID,
+    ) -> Result<Vec<u8>, SignedMessageError> {
+        let mut data = SignedMessage::cid(message)?.to_bytes();
+        data.extend(sponsor.to_bytes());
+        data.extend(sponsor_sequence.to_be_bytes());
+        data.extend(chain_id_bytes(chain_id).iter());
+        Ok(data)
+    }
+
+    /// Verify that the sponsor signed this message with its sequence.
+    pub fn verify_sponsor(&self, chain_id: &ChainID) -> Result<(), SignedMessageError> {
+        let data = Self::signable(
+            self.message.message(),
+            &self.sponsor,
+            self.sponsor_sequence,
+            chain_id,
+        )?;
+
+        match maybe_eth_address(&self.sponsor) {
+            Some(addr) => {
+                let rec = recover_secp256k1(&self.sponsor_signature, &data)
+                    .map_err(SignedMessageError::InvalidSignature)?;
+
+                if EthAddress::from(rec).0 == addr.0 {
+                    Ok(())
+                } else {
+                    Err(SignedMessageError::InvalidSignature("the Ethereum delegated address of the sponsor did not match the one recovered from the signature".to_string()))
+                }
+            }
+            None => self
+                .sponsor_signature
+                .verify(&data, &self.sponsor)
+                .map_err(SignedMessageError::InvalidSignature),
+        }
+    }
+
+    /// Verify the signatures of both the user and the sponsor.
+    pub fn verify(&self, chain_id: &ChainID) -> Result<(), SignedMessageError> {
+        self.message.verify(chain_id)?;
+        self.verify_sponsor(chain_id)
+    }
+
+    /// Returns reference to the unsigned message of the user.
+    pub fn message(&self) -> &Message {
+        self.message.message()
+    }
+
+    /// The most the sponsor can be charged for the gas of the message.
+    pub fn max_gas_fee(&self) -> TokenAmount {
+        let message = self.message();
+        message.gas_fee_cap.clone() * message.gas_limit
+    }
+}
+
+/// Sponsored message with invalid random signatures.
+#[cfg(feature = "arb")]
+mod arb {
+    use fendermint_testing::arb::ArbAddress;
+    use fvm_shared::crypto::signature::Signature;
+
+    use super::SponsoredMessage;
+    use crate::signed::SignedMessage;
+
+    impl quickcheck::Arbitrary for SponsoredMessage {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            Self {
+                message: SignedMessage::arbitrary(g),
+                sponsor: ArbAddress::arbitrary(g).0,
+                sponsor_sequence: u64::arbitrary(g),
+                sponsor_signature: Signature::arbitrary(g),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fendermint_vm_actor_interface::eam::EthAddress;
+    use fvm_shared::{address::Address, chainid::ChainID};
+    use quickcheck_macros::quickcheck;
+
+    use crate::conv::tests::KeyPair;
+    use crate::signed::SignedMessage;
+
+    use super::SponsoredMessage;
+
+    #[quickcheck]
+    fn sponsored_message_cbor(value0: SponsoredMessage) {
+        let repr = fvm_ipld_encoding::to_vec(&value0).expect("failed to encode");
+        let value1: SponsoredMessage =
+            fvm_ipld_encoding::from_slice(repr.as_ref()).expect("failed to decode");
+
+        assert_eq!(value1, value0)
+    }
+
+    #[quickcheck]
+    fn sponsor_sign_and_verify(
+        msg: SignedMessage,
+        chain_id: u64,
+        sequence: u64,
+        user: KeyPair,
+        sponsor: KeyPair,
+    ) -> Result<(), String> {
+        let chain_id0 = ChainID::from(chain_id);
+        let chain_id1 = ChainID::from(chain_id.overflowing_add(1).0);
+
+        let mut msg = msg.into_message();
+        msg.from = Address::new_secp256k1(&user.pk.serialize()).map_err(|e| e.to_string())?;
+
+        let signed = SignedMessage::new_secp256k1(msg, &user.sk, &chain_id0)
+            .map_err(|e| format!("signing failed: {e}"))?;
+
+        for sponsor_addr in [
+            Address::new_secp256k1(&sponsor.pk.serialize()).map_err(|e| e.to_string())?,
+            Address::from(EthAddress::from(sponsor.pk)),
+        ] {
+            let sponsored = SponsoredMessage::new_secp256k1(
+                signed.clone(),
+                sponsor_addr,
+                sequence,
+                &sponsor.sk,
+                &chain_id0,
+            )
+            .map_err(|e| format!("sponsoring failed: {e}"))?;
+
+            sponsored
+                .verify(&chain_id0)
+                .map_err(|e| format!("verifying failed: {e}"))?;
+
+            if sponsored.verify_sponsor(&chain_id1).is_ok() {
+                return Err("verifying with a different chain ID should fail".into());
+            }
+
+            let mut replayed = sponsored.clone();
+            replayed.sponsor_sequence = sequence.overflowing_add(1).0;
+            if replayed.verify_sponsor(&chain_id0).is_ok() {
+                return Err("verifying with a different sponsor sequence should fail".into());
+            }
+
+            let mut modified = sponsored.clone();
+            modified.message.message.gas_limit += 1;
+            if modified.verify_sponsor(&chain_id0).is_ok() {
+                return Err("verifying a different user message should fail".into());
+            }
+        }
+        Ok(())
+    }
+}
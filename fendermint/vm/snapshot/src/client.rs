@@ -5,7 +5,7 @@ use std::{path::PathBuf, sync::Arc, time::SystemTime};
 
 use async_stm::{abort, Stm, StmResult, TVar};
 use fendermint_vm_interpreter::fvm::state::{
-    snapshot::{BlockHeight, SnapshotVersion},
+    snapshot::{BlockHeight, SnapshotVersion, DELTA_SNAPSHOT_VERSION, FULL_SNAPSHOT_VERSION},
     FvmStateParams,
 };
 
@@ -80,7 +80,10 @@ impl SnapshotClient {
     /// If the offered snapshot is accepted, we create a temporary directory to hold the chunks
     /// and remember it as our current snapshot being downloaded.
     pub fn offer_snapshot(&self, manifest: SnapshotManifest) -> StmResult<PathBuf, SnapshotError> {
-        if manifest.version != 1 {
+        if !matches!(
+            manifest.version,
+            FULL_SNAPSHOT_VERSION | DELTA_SNAPSHOT_VERSION
+        ) {
             abort(SnapshotError::IncompatibleVersion(manifest.version))
         } else {
             match tempfile::tempdir_in(&self.download_dir) {
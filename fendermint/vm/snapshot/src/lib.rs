@@ -16,8 +16,13 @@ const MANIFEST_FILE_NAME: &str = "manifest.json";
 /// Name of the subdirectory where `{idx}.part` files are stored within a snapshot.
 const PARTS_DIR_NAME: &str = "parts";
 
+/// Name of the directory of a snapshot taken at a given height.
+fn snapshot_dir_name(block_height: u64) -> String {
+    format!("snapshot-{block_height}")
+}
+
 pub use client::SnapshotClient;
 pub use error::SnapshotError;
 pub use manager::{SnapshotManager, SnapshotParams};
-pub use manifest::SnapshotManifest;
+pub use manifest::{SnapshotLink, SnapshotManifest};
 pub use state::SnapshotItem;
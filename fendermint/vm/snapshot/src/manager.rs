@@ -4,9 +4,13 @@
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use crate::manifest::{file_checksum, list_manifests, write_manifest, SnapshotManifest};
+use crate::manifest::{
+    file_checksum, files_checksum, list_manifests, write_manifest, SnapshotLink, SnapshotManifest,
+};
 use crate::state::SnapshotState;
-use crate::{car, SnapshotClient, SnapshotItem, PARTS_DIR_NAME, SNAPSHOT_FILE_NAME};
+use crate::{
+    car, snapshot_dir_name, SnapshotClient, SnapshotItem, PARTS_DIR_NAME, SNAPSHOT_FILE_NAME,
+};
 use anyhow::Context;
 use async_stm::{atomically, retry, TVar};
 use fendermint_vm_interpreter::fvm::state::snapshot::{BlockHeight, Snapshot};
@@ -23,8 +27,14 @@ pub struct SnapshotParams {
     pub chunk_size: usize,
     /// Number of snapshots to keep.
     ///
-    /// 0 means unlimited.
+    /// 0 means unlimited. Snapshots which are part of the chain of a delta snapshot
+    /// are kept until the delta itself can be removed.
     pub hist_size: usize,
+    /// Number of delta snapshots to create on top of a full snapshot,
+    /// before creating the next full one.
+    ///
+    /// 0 means only full snapshots are created.
+    pub max_deltas: usize,
     /// Time to hold on from purging a snapshot after a remote client
     /// asked for a chunk from it.
     pub last_access_hold: Duration,
@@ -38,6 +48,7 @@ pub struct SnapshotManager<BS> {
     snapshots_dir: PathBuf,
     chunk_size: usize,
     hist_size: usize,
+    max_deltas: usize,
     last_access_hold: Duration,
     sync_poll_interval: Duration,
     /// Shared state of snapshots.
@@ -67,6 +78,7 @@ where
             snapshots_dir: params.snapshots_dir,
             chunk_size: params.chunk_size,
             hist_size: params.hist_size,
+            max_deltas: params.max_deltas,
             last_access_hold: params.last_access_hold,
             sync_poll_interval: params.sync_poll_interval,
             state: state.clone(),
@@ -125,6 +137,7 @@ where
                     tracing::info!(
                         snapshot = item.snapshot_dir.to_string_lossy().to_string(),
                         block_height,
+                        version = item.manifest.version,
                         chunks_count = item.manifest.chunks,
                         snapshot_size = item.manifest.size,
                         "exported snapshot"
@@ -167,6 +180,16 @@ where
                             break;
                         }
                     }
+                    // Stop at the first snapshot that a delta snapshot still builds on.
+                    if let Some(head) = snapshots.head() {
+                        let height = head.manifest.block_height;
+                        if snapshots
+                            .iter()
+                            .any(|s| s.manifest.chain.iter().any(|l| l.block_height == height))
+                        {
+                            break;
+                        }
+                    }
                     if let Some(snapshot) = snapshots.pop_front() {
                         removables.push(snapshot);
                     } else {
@@ -188,17 +211,56 @@ where
         }
     }
 
+    /// The latest snapshot, if the next one should be a delta on top of it.
+    async fn delta_base(&self, block_height: BlockHeight) -> Option<SnapshotItem> {
+        if self.max_deltas == 0 {
+            return None;
+        }
+        let snapshots = atomically(|| self.state.snapshots.read_clone()).await;
+
+        snapshots.last().cloned().filter(|base| {
+            base.manifest.block_height < block_height && base.manifest.chain.len() < self.max_deltas
+        })
+    }
+
     /// Export a snapshot to a temporary file, then copy it to the snapshot directory.
+    ///
+    /// Depending on the settings, this is either a full snapshot, or a delta on top of the latest one.
     async fn create_snapshot(
         &self,
         block_height: BlockHeight,
         state_params: FvmStateParams,
     ) -> anyhow::Result<SnapshotItem> {
-        let snapshot = Snapshot::new(self.store.clone(), state_params.clone(), block_height)
-            .context("failed to create snapshot")?;
+        let base = self.delta_base(block_height).await;
+
+        let snapshot = match base {
+            Some(ref base) => Snapshot::new_delta(
+                self.store.clone(),
+                state_params.clone(),
+                block_height,
+                base.manifest.state_params.state_root,
+            ),
+            None => Snapshot::new(self.store.clone(), state_params.clone(), block_height),
+        }
+        .context("failed to create snapshot")?;
+
+        // The chain of snapshots this one has to be applied on top of.
+        let chain = match base {
+            Some(ref base) => {
+                let mut chain = base.manifest.chain.clone();
+                chain.push(SnapshotLink {
+                    block_height: base.manifest.block_height,
+                    chunks: base.manifest.own_chunks(),
+                });
+                chain
+            }
+            None => Vec::new(),
+        };
+        // Chunks are numbered after their position in the logical snapshot including the chain.
+        let chunks_offset = base.as_ref().map(|b| b.manifest.chunks).unwrap_or_default();
 
         let snapshot_version = snapshot.version();
-        let snapshot_name = format!("snapshot-{block_height}");
+        let snapshot_name = snapshot_dir_name(block_height);
         let temp_dir = tempfile::Builder::new()
             .prefix(&snapshot_name)
             .tempdir()
@@ -226,8 +288,16 @@ where
             .context("failed to get snapshot metadata")?
             .len() as usize;
 
-        // Create a checksum over the CAR file.
-        let checksum_bytes = file_checksum(&snapshot_path).context("failed to compute checksum")?;
+        // Create a checksum over the CAR file, preceded by the contents of the chain.
+        let checksum_bytes = match base {
+            Some(ref base) => files_checksum(
+                (0..base.manifest.chunks)
+                    .map(|chunk| base.chunk_path(chunk))
+                    .chain(std::iter::once(snapshot_path.clone())),
+            ),
+            None => file_checksum(&snapshot_path),
+        }
+        .context("failed to compute checksum")?;
 
         std::fs::write(&checksum_path, checksum_bytes.to_string())
             .context("failed to write checksum file")?;
@@ -239,8 +309,8 @@ where
         // They can be listed in the right order with e.g. `ls | sort -n`
         // Alternatively we could pad them with zeroes based on the original file size and the chunk size,
         // but this way it will be easier to return them based on a numeric index.
-        let chunks_count = car::split(&snapshot_path, &parts_path, self.chunk_size, |idx| {
-            format!("{idx}.part")
+        let chunks_count = car::split(&snapshot_path, &parts_path, self.chunk_size, move |idx| {
+            format!("{}.part", idx + chunks_offset as usize)
         })
         .await
         .context("failed to split CAR into chunks")?;
@@ -248,11 +318,12 @@ where
         // Create and export a manifest that we can easily look up.
         let manifest = SnapshotManifest {
             block_height,
-            size: snapshot_size as u64,
-            chunks: chunks_count as u32,
+            size: snapshot_size as u64 + base.as_ref().map(|b| b.manifest.size).unwrap_or_default(),
+            chunks: chunks_count as u32 + chunks_offset,
             checksum: checksum_bytes,
             state_params,
             version: snapshot_version,
+            chain,
         };
         let _ = write_manifest(temp_dir.path(), &manifest).context("failed to export manifest")?;
 
@@ -321,7 +392,7 @@ mod tests {
     use fendermint_vm_interpreter::genesis::create_test_genesis_state;
     use quickcheck::Arbitrary;
 
-    use crate::{manager::SnapshotParams, manifest, PARTS_DIR_NAME};
+    use crate::{manager::SnapshotParams, manifest, SnapshotLink, PARTS_DIR_NAME};
 
     use super::SnapshotManager;
 
@@ -359,6 +430,7 @@ mod tests {
                 block_interval: 1,
                 chunk_size: 10000,
                 hist_size: 1,
+                max_deltas: 0,
                 last_access_hold: Duration::ZERO,
                 sync_poll_interval: never_poll_sync,
             },
@@ -427,6 +499,7 @@ mod tests {
                 block_interval: 1,
                 chunk_size: 10000,
                 hist_size: 1,
+                max_deltas: 0,
                 last_access_hold: Duration::ZERO,
                 sync_poll_interval: never_poll_sync,
             },
@@ -437,6 +510,101 @@ mod tests {
         assert!(!snapshots.is_empty(), "loads manifests on start");
     }
 
+    // Create a full snapshot followed by a delta, then import the delta as a logical snapshot into an empty store.
+    #[tokio::test]
+    async fn create_delta_snapshot_with_manager() {
+        let (state_params, store) = init_genesis().await;
+
+        let snapshots_dir = tempfile::tempdir().expect("failed to create tmp dir");
+        let download_dir = tempfile::tempdir().expect("failed to create tmp dir");
+
+        let (snapshot_manager, snapshot_client) = SnapshotManager::new(
+            store.clone(),
+            SnapshotParams {
+                snapshots_dir: snapshots_dir.path().into(),
+                download_dir: download_dir.path().into(),
+                block_interval: 1,
+                chunk_size: 10000,
+                hist_size: 1,
+                max_deltas: 1,
+                last_access_hold: Duration::ZERO,
+                sync_poll_interval: Duration::ZERO,
+            },
+        )
+        .expect("failed to create snapshot manager");
+
+        tokio::spawn(async move { snapshot_manager.run(mock_client()).await });
+
+        let wait_for_snapshots = |count: usize| {
+            let snapshot_client = snapshot_client.clone();
+            async move {
+                tokio::time::timeout(
+                    Duration::from_secs(10),
+                    atomically(|| {
+                        let snapshots = snapshot_client.list_snapshots()?;
+                        if snapshots.len() < count {
+                            retry()
+                        } else {
+                            Ok(snapshots)
+                        }
+                    }),
+                )
+                .await
+                .expect("failed to export snapshot")
+            }
+        };
+
+        atomically(|| snapshot_client.notify(0, state_params.clone())).await;
+        let _ = wait_for_snapshots(1).await;
+
+        atomically(|| snapshot_client.notify(1, state_params.clone())).await;
+        let snapshots = wait_for_snapshots(2).await;
+
+        // The full snapshot is not pruned while the delta builds on it.
+        assert_eq!(snapshots.len(), 2);
+
+        let full = snapshots[0].clone();
+        let delta = snapshots[1].clone();
+
+        assert_eq!(full.manifest.version, 1);
+        assert!(full.manifest.chain.is_empty());
+        assert_eq!(delta.manifest.version, 2);
+        assert_eq!(delta.manifest.block_height, 1);
+        assert_eq!(
+            delta.manifest.chain,
+            vec![SnapshotLink {
+                block_height: 0,
+                chunks: full.manifest.chunks
+            }]
+        );
+        assert!(delta.manifest.own_chunks() > 0);
+        assert_eq!(
+            delta.manifest.chunks,
+            full.manifest.chunks + delta.manifest.own_chunks()
+        );
+
+        // The delta only stores its own chunks, but serves the chain as well.
+        let delta_parts = manifest::list_parts(delta.snapshot_dir.join(PARTS_DIR_NAME)).unwrap();
+        assert_eq!(delta_parts.len() as u32, delta.manifest.own_chunks());
+
+        let checksum =
+            manifest::files_checksum((0..delta.manifest.chunks).map(|c| delta.chunk_path(c)))
+                .expect("chain checksum can be calculated");
+        assert_eq!(checksum, delta.manifest.checksum);
+
+        for chunk in 0..delta.manifest.chunks {
+            delta.load_chunk(chunk).expect("chunk can be loaded");
+        }
+
+        let imported = delta
+            .import(MemoryBlockstore::new(), true)
+            .await
+            .expect("failed to import the delta with its chain");
+
+        assert_eq!(imported.block_height(), 1);
+        assert_eq!(*imported.state_params(), state_params);
+    }
+
     async fn init_genesis() -> (FvmStateParams, MemoryBlockstore) {
         let mut g = quickcheck::Gen::new(5);
         let genesis = Genesis::arbitrary(&mut g);
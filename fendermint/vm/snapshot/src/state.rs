@@ -1,18 +1,15 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::{fs::File, io, path::PathBuf, sync::Arc, time::SystemTime};
+use std::{fs::File, io, ops::Range, path::PathBuf, sync::Arc, time::SystemTime};
 
 use anyhow::{bail, Context};
 use async_stm::TVar;
-use fendermint_vm_interpreter::fvm::state::snapshot::{BlockStateParams, Snapshot};
+use fendermint_vm_interpreter::fvm::state::snapshot::{BlockHeight, BlockStateParams, Snapshot};
 use fvm_ipld_blockstore::Blockstore;
 use tempfile::TempDir;
 
-use crate::{
-    manifest::{self, SnapshotManifest},
-    PARTS_DIR_NAME, SNAPSHOT_FILE_NAME,
-};
+use crate::{manifest::SnapshotManifest, snapshot_dir_name, PARTS_DIR_NAME, SNAPSHOT_FILE_NAME};
 
 /// State of snapshots, including the list of available completed ones
 /// and the next eligible height.
@@ -62,6 +59,47 @@ impl SnapshotItem {
         self.snapshot_dir.join(PARTS_DIR_NAME)
     }
 
+    /// The block height and the range of chunk indices of each snapshot in the chain,
+    /// ending with this snapshot.
+    fn segments(&self) -> Vec<(BlockHeight, Range<u32>)> {
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for link in self.manifest.chain.iter() {
+            segments.push((link.block_height, start..start + link.chunks));
+            start += link.chunks;
+        }
+        segments.push((self.manifest.block_height, start..self.manifest.chunks));
+        segments
+    }
+
+    /// Path to the file containing a chunk.
+    ///
+    /// Chunks are named after their index in the logical snapshot. Downloaded snapshots have
+    /// all of them in their own directory, while the chunks of the chain of locally created
+    /// delta snapshots are in the directories of the snapshots they belong to.
+    pub fn chunk_path(&self, chunk: u32) -> PathBuf {
+        let file_name = format!("{chunk}.part");
+        let chunk_path = self.parts_dir().join(&file_name);
+        if chunk_path.exists() {
+            return chunk_path;
+        }
+        let link = self
+            .manifest
+            .chain
+            .iter()
+            .zip(self.segments())
+            .find(|(_, (_, range))| range.contains(&chunk))
+            .map(|(link, _)| link);
+
+        match (link, self.snapshot_dir.parent()) {
+            (Some(link), Some(snapshots_dir)) => snapshots_dir
+                .join(snapshot_dir_name(link.block_height))
+                .join(PARTS_DIR_NAME)
+                .join(file_name),
+            _ => chunk_path,
+        }
+    }
+
     /// Load the data from disk.
     ///
     /// Returns an error if the chunk isn't within range or if the file doesn't exist any more.
@@ -72,7 +110,7 @@ impl SnapshotItem {
                 self.manifest.chunks
             );
         }
-        let chunk_file = self.parts_dir().join(format!("{chunk}.part"));
+        let chunk_file = self.chunk_path(chunk);
 
         let content = std::fs::read(&chunk_file)
             .with_context(|| format!("failed to read chunk {}", chunk_file.to_string_lossy()))?;
@@ -81,18 +119,58 @@ impl SnapshotItem {
     }
 
     /// Import a snapshot into the blockstore.
+    ///
+    /// A delta snapshot is imported by importing the snapshots of its chain one by one,
+    /// each on top of the previous one.
     pub async fn import<BS>(&self, store: BS, validate: bool) -> anyhow::Result<Snapshot<BS>>
     where
         BS: Blockstore + Send + Clone + 'static,
     {
-        let parts =
-            manifest::list_parts(self.parts_dir()).context("failed to list snapshot parts")?;
+        let mut snapshot = None;
+
+        for (block_height, chunks) in self.segments() {
+            let imported = self.import_car(store.clone(), chunks, validate).await?;
+
+            if validate && imported.block_height() != block_height {
+                bail!(
+                    "invalid snapshot block height; expected {}, imported {}",
+                    block_height,
+                    imported.block_height()
+                );
+            }
+
+            snapshot = Some(imported);
+        }
 
-        // 1. Restore the snapshots into a complete `snapshot.car` file.
+        let snapshot = snapshot.context("the snapshot chain is empty")?;
+
+        // See if we actually imported what we thought we would.
+        if validate && *snapshot.state_params() != self.manifest.state_params {
+            bail!(
+                "invalid state params; expected {:?}, imported {:?}",
+                self.manifest.state_params,
+                snapshot.state_params()
+            )
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Import the CAR file contained in a range of chunks into the blockstore.
+    async fn import_car<BS>(
+        &self,
+        store: BS,
+        chunks: Range<u32>,
+        validate: bool,
+    ) -> anyhow::Result<Snapshot<BS>>
+    where
+        BS: Blockstore + Send + Clone + 'static,
+    {
+        // 1. Restore the chunks into a complete `snapshot.car` file.
         let car_path = self.snapshot_dir.join(SNAPSHOT_FILE_NAME);
         let mut car_file = File::create(&car_path).context("failed to create CAR file")?;
 
-        for part in parts {
+        for part in chunks.map(|chunk| self.chunk_path(chunk)) {
             let mut part_file = File::open(&part).with_context(|| {
                 format!("failed to open snapshot part {}", part.to_string_lossy())
             })?;
@@ -133,31 +211,7 @@ impl SnapshotItem {
         // Actually a very similar situation arises with garbage collection: since the length of history
         // is configurable, whether some CIDs are (still) present or not depends on how the validator
         // configured their nodes, and cannot be allowed to cause a failure.
-        let snapshot = result.context("failed to import the snapshot into the blockstore")?;
-
-        // 4. See if we actually imported what we thought we would.
-        if validate {
-            match snapshot {
-                Snapshot::V1(ref snapshot) => {
-                    if snapshot.block_height() != self.manifest.block_height {
-                        bail!(
-                            "invalid snapshot block height; expected {}, imported {}",
-                            self.manifest.block_height,
-                            snapshot.block_height()
-                        );
-                    }
-                    if *snapshot.state_params() != self.manifest.state_params {
-                        bail!(
-                            "invalid state params; expected {:?}, imported {:?}",
-                            self.manifest.state_params,
-                            snapshot.state_params()
-                        )
-                    }
-                }
-            }
-        }
-
-        Ok(snapshot)
+        result.context("failed to import the snapshot into the blockstore")
     }
 }
 
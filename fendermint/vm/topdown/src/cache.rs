@@ -134,6 +134,11 @@ impl<K: PrimInt + Debug, V> SequentialKeyCache<K, V> {
         }
     }
 
+    /// Iterate over all the keys and values, in ascending order of keys.
+    pub fn entries(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.data.iter().map(|(k, v)| (k, v))
+    }
+
     /// Removes the all the keys below the target value, exclusive.
     pub fn remove_key_below(&mut self, key: K) {
         while let Some((k, _)) = self.data.front() {
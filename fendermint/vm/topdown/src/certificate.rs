@@ -0,0 +1,261 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Quorum certificates of committed parent finalities.
+//!
+//! A certificate bundles the signed votes gossiped by the validators with the power table
+//! they were tallied against, so that parties outside the subnet, such as bridges, can
+//! check independently why a parent block was accepted as final.
+
+use std::collections::{HashMap, HashSet};
+
+use ipc_api::subnet_id::SubnetID;
+use ipc_ipld_resolver::{SignedVoteRecord, ValidatorKey};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::voting::{quorum_threshold, Weight};
+use crate::{BlockHash, BlockHeight, Bytes, IPCParentFinality};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CertificateError {
+    #[error("the chain does not start at the certified block {0}")]
+    ChainMismatch(BlockHeight),
+
+    #[error("the chain is not contiguous at height {0}")]
+    ChainGap(BlockHeight),
+
+    #[error("invalid signed vote: {0}")]
+    InvalidVote(String),
+
+    #[error("vote from {0} is for subnet {1}")]
+    WrongSubnet(ValidatorKey, SubnetID),
+
+    #[error("vote from {0} is not about parent finality")]
+    UnexpectedContent(ValidatorKey),
+
+    #[error("vote from {0} is for a block at height {1} which is not on the chain")]
+    NotOnChain(ValidatorKey, BlockHeight),
+
+    #[error("multiple votes from {0}")]
+    DuplicateVoter(ValidatorKey),
+
+    #[error("validator unknown or has no power: {0}")]
+    UnpoweredValidator(ValidatorKey),
+
+    #[error("not enough weight for a quorum: {0} < {1}")]
+    NoQuorum(Weight, Weight),
+}
+
+/// Evidence that a quorum of validators voted for a parent block, or one of its descendants.
+///
+/// A vote for a block implies that all its ancestors are final as well, so the certificate
+/// carries the segment of the parent chain between the certified block and the highest block
+/// voted on, with null rounds having no hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuorumCertificate<K = ValidatorKey, V = BlockHash> {
+    /// Height of the certified block on the parent chain.
+    pub block_height: BlockHeight,
+    /// Hash of the certified block.
+    pub block_hash: V,
+    /// The parent chain from the certified block up to the highest block voted on.
+    pub chain: Vec<(BlockHeight, Option<V>)>,
+    /// The validator weights the votes were tallied with.
+    pub power_table: Vec<(K, Weight)>,
+    /// Protobuf encoded signed envelopes of the votes, one per validator.
+    pub votes: Vec<Bytes>,
+}
+
+impl QuorumCertificate {
+    /// Check that the votes in the certificate are correctly signed by validators in the
+    /// power table, are about blocks on the chain of the certificate, and add up to a quorum.
+    ///
+    /// `to_finality` extracts the parent finality from the content of the votes.
+    ///
+    /// Returns the total weight of the votes. It is up to the caller to check that the power
+    /// table matches the membership of the subnet, and that the chain is part of the parent
+    /// chain, which the certificate cannot attest to on its own.
+    pub fn verify<C, F>(
+        &self,
+        subnet_id: &SubnetID,
+        to_finality: F,
+    ) -> Result<Weight, CertificateError>
+    where
+        C: Serialize + DeserializeOwned,
+        F: Fn(C) -> Option<IPCParentFinality>,
+    {
+        match self.chain.first() {
+            Some((h, Some(bh))) if *h == self.block_height && *bh == self.block_hash => {}
+            _ => return Err(CertificateError::ChainMismatch(self.block_height)),
+        }
+
+        for ((h0, _), (h1, _)) in self.chain.iter().zip(self.chain.iter().skip(1)) {
+            if *h1 != h0 + 1 {
+                return Err(CertificateError::ChainGap(*h1));
+            }
+        }
+
+        let chain: HashMap<_, _> = self.chain.iter().cloned().collect();
+        let power_table: HashMap<_, _> = self.power_table.iter().cloned().collect();
+        let threshold = quorum_threshold(power_table.values().sum());
+
+        let mut weight = 0;
+        let mut voters = HashSet::new();
+
+        for bz in &self.votes {
+            let record = SignedVoteRecord::<C>::from_bytes(bz)
+                .map_err(|e| CertificateError::InvalidVote(e.to_string()))?
+                .into_record();
+
+            let vk = record.public_key;
+
+            if record.subnet_id != *subnet_id {
+                return Err(CertificateError::WrongSubnet(vk, record.subnet_id));
+            }
+
+            let Some(finality) = to_finality(record.content) else {
+                return Err(CertificateError::UnexpectedContent(vk));
+            };
+
+            match chain.get(&finality.height) {
+                Some(Some(bh)) if *bh == finality.block_hash => {}
+                _ => return Err(CertificateError::NotOnChain(vk, finality.height)),
+            }
+
+            let w = match power_table.get(&vk) {
+                Some(w) if *w > 0 => *w,
+                _ => return Err(CertificateError::UnpoweredValidator(vk)),
+            };
+
+            if !voters.insert(vk.clone()) {
+                return Err(CertificateError::DuplicateVoter(vk));
+            }
+
+            weight += w;
+        }
+
+        if weight < threshold {
+            return Err(CertificateError::NoQuorum(weight, threshold));
+        }
+
+        Ok(weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_stm::{atomically, atomically_or_err};
+    use ipc_api::subnet_id::SubnetID;
+    use ipc_ipld_resolver::{ValidatorKey, VoteRecord};
+    use libp2p::identity::Keypair;
+
+    use super::{CertificateError, QuorumCertificate};
+    use crate::voting::VoteTally;
+    use crate::IPCParentFinality;
+
+    fn signed_vote(key: &Keypair, subnet_id: &SubnetID, height: u64, hash: u8) -> Vec<u8> {
+        let content = IPCParentFinality {
+            height,
+            block_hash: vec![hash],
+        };
+        VoteRecord::signed(key, subnet_id.clone(), content)
+            .unwrap()
+            .envelope()
+            .clone()
+            .into_protobuf_encoding()
+    }
+
+    #[tokio::test]
+    async fn test_certificate_from_tally() {
+        let subnet_id = SubnetID::new_root(123);
+        let keys = (0..3)
+            .map(|_| Keypair::generate_secp256k1())
+            .collect::<Vec<_>>();
+        let vks = keys
+            .iter()
+            .map(|k| ValidatorKey::from(k.public()))
+            .collect::<Vec<_>>();
+
+        // The threshold is 4, so it takes the first two validators to form a quorum.
+        let power_table = vks.iter().cloned().zip([2, 2, 1]).collect();
+        let tally = VoteTally::new(power_table, (0, vec![0]));
+
+        let votes = [(0, 1, 1), (1, 3, 3), (2, 1, 1)];
+
+        for (h, bh) in [(1, Some(vec![1])), (2, None), (3, Some(vec![3]))] {
+            atomically_or_err(|| tally.add_block(h, bh.clone()))
+                .await
+                .unwrap();
+        }
+
+        // Only the weakest validator has a signed vote so far.
+        let (i, h, bh) = votes[2];
+        let envelope = signed_vote(&keys[i], &subnet_id, h, bh);
+        atomically_or_err(|| tally.add_signed_vote(vks[i].clone(), h, vec![bh], envelope.clone()))
+            .await
+            .unwrap();
+
+        let cert = atomically(|| tally.quorum_certificate(1, &vec![1])).await;
+        assert!(cert.is_none());
+
+        // The others voted too; the second one on a block descending from the certified one.
+        for (i, h, bh) in &votes[..2] {
+            let envelope = signed_vote(&keys[*i], &subnet_id, *h, *bh);
+            atomically_or_err(|| {
+                tally.add_signed_vote(vks[*i].clone(), *h, vec![*bh], envelope.clone())
+            })
+            .await
+            .unwrap();
+        }
+
+        let certs = atomically(|| {
+            tally.set_finalized(1, vec![1], None, None)?;
+            tally.take_certificates()
+        })
+        .await;
+
+        assert_eq!(certs.len(), 1);
+        let cert = certs.into_iter().next().unwrap();
+        assert_eq!(
+            cert.chain,
+            vec![(1, Some(vec![1])), (2, None), (3, Some(vec![3]))]
+        );
+        assert_eq!(cert.votes.len(), 3);
+
+        // The certificate survives being stored.
+        let bz = fvm_ipld_encoding::to_vec(&cert).unwrap();
+        let cert: QuorumCertificate = fvm_ipld_encoding::from_slice(&bz).unwrap();
+
+        assert_eq!(cert.verify(&subnet_id, Some).unwrap(), 5);
+
+        assert!(matches!(
+            cert.verify(&SubnetID::new_root(456), Some),
+            Err(CertificateError::WrongSubnet(_, _))
+        ));
+
+        let mut tampered = cert.clone();
+        tampered.chain[2].1 = Some(vec![4]);
+        assert!(matches!(
+            tampered.verify(&subnet_id, Some),
+            Err(CertificateError::NotOnChain(_, 3))
+        ));
+
+        let mut tampered = cert.clone();
+        tampered.votes.retain(|v| v != &envelope);
+        tampered.votes.pop();
+        assert!(matches!(
+            tampered.verify(&subnet_id, Some),
+            Err(CertificateError::NoQuorum(_, 4))
+        ));
+
+        let mut tampered = cert.clone();
+        tampered.votes.push(tampered.votes[0].clone());
+        assert!(matches!(
+            tampered.verify(&subnet_id, Some),
+            Err(CertificateError::DuplicateVoter(_))
+        ));
+
+        // Nothing left to take.
+        let certs = atomically(|| tally.take_certificates()).await;
+        assert!(certs.is_empty());
+    }
+}
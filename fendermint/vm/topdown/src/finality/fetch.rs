@@ -4,6 +4,7 @@
 use crate::finality::null::FinalityWithNull;
 use crate::finality::ParentViewPayload;
 use crate::proxy::ParentQueryProxy;
+use crate::store::{restorable_entries, ParentViewEntry, ParentViewStore};
 use crate::{
     handle_null_round, BlockHash, BlockHeight, Config, Error, IPCParentFinality,
     ParentFinalityProvider, ParentViewProvider,
@@ -20,6 +21,8 @@ pub struct CachedFinalityProvider<T> {
     config: Config,
     /// The ipc client proxy that works as a back up if cache miss
     parent_client: Arc<T>,
+    /// Optional durable copy of the cache, which survives restarts
+    store: Option<Arc<dyn ParentViewStore>>,
 }
 
 /// Exponential backoff for futures
@@ -196,9 +199,16 @@ impl<T> CachedFinalityProvider<T> {
             inner,
             config,
             parent_client,
+            store: None,
         }
     }
 
+    /// Keep a durable copy of the cached parent view in a store.
+    pub fn with_store(mut self, store: Arc<dyn ParentViewStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
     pub fn block_hash(&self, height: BlockHeight) -> Stm<Option<BlockHash>> {
         self.inner.block_hash_at_height(height)
     }
@@ -237,17 +247,70 @@ impl<T> CachedFinalityProvider<T> {
     pub fn first_non_null_block(&self, height: BlockHeight) -> Stm<Option<BlockHeight>> {
         self.inner.first_non_null_block(height)
     }
+
+    /// Returns all the cached parent heights along with their data.
+    pub fn cached_entries(&self) -> Stm<Vec<ParentViewEntry>> {
+        self.inner.cached_entries()
+    }
+
+    /// Write a parent view that was added to the cache to the store, if there is one.
+    pub fn persist_parent_view(
+        &self,
+        height: BlockHeight,
+        maybe_payload: Option<ParentViewPayload>,
+    ) -> anyhow::Result<()> {
+        match self.store {
+            Some(ref store) => store.put(&ParentViewEntry {
+                height,
+                payload: maybe_payload,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Load the persisted parent views which directly follow the committed finality,
+    /// so they can be added to the cache again without fetching them from the parent.
+    pub fn load_persisted(
+        &self,
+        finality: &IPCParentFinality,
+    ) -> anyhow::Result<Vec<ParentViewEntry>> {
+        match self.store {
+            Some(ref store) => {
+                let entries = store.load_from(finality.height + 1)?;
+                Ok(restorable_entries(finality, entries))
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Remove the persisted parent views below a height, typically the committed finality.
+    pub fn prune_persisted(&self, height: BlockHeight) -> anyhow::Result<()> {
+        match self.store {
+            Some(ref store) => store.prune_below(height),
+            None => Ok(()),
+        }
+    }
+
+    /// Remove all the persisted parent views, after the cache has been reset.
+    pub fn clear_persisted(&self) -> anyhow::Result<()> {
+        match self.store {
+            Some(ref store) => store.clear(),
+            None => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::finality::ParentViewPayload;
     use crate::proxy::ParentQueryProxy;
+    use crate::store::{ParentViewEntry, ParentViewStore};
     use crate::{
-        BlockHeight, CachedFinalityProvider, Config, IPCParentFinality, ParentViewProvider,
+        BlockHeight, CachedFinalityProvider, Config, Error, IPCParentFinality, ParentViewProvider,
         SequentialKeyCache, NULL_ROUND_ERR_MSG,
     };
     use anyhow::anyhow;
+    use async_stm::{atomically, atomically_or_err};
     use async_trait::async_trait;
     use fvm_shared::address::Address;
     use fvm_shared::econ::TokenAmount;
@@ -255,8 +318,9 @@ mod tests {
     use ipc_api::staking::{StakingChange, StakingChangeRequest, StakingOperation};
     use ipc_api::subnet_id::SubnetID;
     use ipc_provider::manager::{GetBlockHashResult, TopDownQueryPayload};
+    use std::collections::BTreeMap;
     use std::sync::atomic::{AtomicUsize, Ordering};
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
     /// Creates a mock of a new parent blockchain view. The key is the height and the value is the
@@ -447,4 +511,94 @@ mod tests {
 
         assert_eq!(messages.len(), 4)
     }
+
+    /// Keeps the parent view in memory, standing in for the database which outlives the provider.
+    #[derive(Default)]
+    struct TestParentViewStore {
+        entries: Mutex<BTreeMap<BlockHeight, Option<ParentViewPayload>>>,
+    }
+
+    impl ParentViewStore for TestParentViewStore {
+        fn put(&self, entry: &ParentViewEntry) -> anyhow::Result<()> {
+            let mut entries = self.entries.lock().unwrap();
+            entries.insert(entry.height, entry.payload.clone());
+            Ok(())
+        }
+
+        fn load_from(&self, height: BlockHeight) -> anyhow::Result<Vec<ParentViewEntry>> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .range(height..)
+                .map(|(height, payload)| ParentViewEntry {
+                    height: *height,
+                    payload: payload.clone(),
+                })
+                .collect())
+        }
+
+        fn prune_below(&self, height: BlockHeight) -> anyhow::Result<()> {
+            self.entries.lock().unwrap().retain(|h, _| *h >= height);
+            Ok(())
+        }
+
+        fn clear(&self) -> anyhow::Result<()> {
+            self.entries.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_persisted_view() {
+        let parent_blocks = new_parent_blocks!(
+            100 => Some((vec![0; 32], vec![], vec![])),   // genesis block
+            101 => Some((vec![1; 32], vec![new_validator_changes(1)], vec![])),
+            102 => None,
+            103 => Some((vec![3; 32], vec![], vec![new_cross_msg(3)])),
+            104 => Some((vec![4; 32], vec![], vec![]))
+        );
+        let store = Arc::new(TestParentViewStore::default());
+
+        let provider = new_provider(parent_blocks.clone()).with_store(store.clone());
+        for h in 101..=104 {
+            let payload = parent_blocks.get_value(h).unwrap().clone();
+            atomically_or_err(|| provider.new_parent_view(h, payload.clone()))
+                .await
+                .unwrap();
+            provider.persist_parent_view(h, payload).unwrap();
+        }
+        let finality = atomically(|| provider.last_committed_finality())
+            .await
+            .unwrap();
+
+        // Start over with an empty cache, as after a restart.
+        let restarted = new_provider(parent_blocks).with_store(store);
+        assert_eq!(atomically(|| restarted.cached_blocks()).await, 0);
+
+        let entries = restarted.load_persisted(&finality).unwrap();
+        atomically_or_err::<_, Error, _>(|| {
+            for e in entries.iter() {
+                restarted.new_parent_view(e.height, e.payload.clone())?;
+            }
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        let summary = |entries: Vec<ParentViewEntry>| {
+            entries
+                .into_iter()
+                .map(|e| (e.height, e.payload.map(|p| (p.0, p.1.len(), p.2))))
+                .collect::<Vec<_>>()
+        };
+
+        let before = atomically(|| provider.cached_entries()).await;
+        let after = atomically(|| restarted.cached_entries()).await;
+        assert_eq!(heights(&after), vec![101, 102, 103, 104]);
+        assert_eq!(summary(before), summary(after));
+        assert_eq!(atomically(|| restarted.latest_height()).await, Some(104));
+    }
+
+    fn heights(entries: &[ParentViewEntry]) -> Vec<BlockHeight> {
+        entries.iter().map(|e| e.height).collect()
+    }
 }
@@ -4,6 +4,7 @@
 use crate::finality::{
     ensure_sequential, topdown_cross_msgs, validator_changes, ParentViewPayload,
 };
+use crate::store::ParentViewEntry;
 use crate::{BlockHash, BlockHeight, Config, Error, IPCParentFinality, SequentialKeyCache};
 use async_stm::{abort, atomically, Stm, StmResult, TVar};
 use ipc_api::cross::IpcEnvelope;
@@ -150,6 +151,18 @@ impl FinalityWithNull {
         self.get_at_height(height, |i| i.0.clone())
     }
 
+    /// Returns all the cached parent heights along with their data.
+    pub(crate) fn cached_entries(&self) -> Stm<Vec<ParentViewEntry>> {
+        let cache = self.cached_data.read()?;
+        Ok(cache
+            .entries()
+            .map(|(height, payload)| ParentViewEntry {
+                height: *height,
+                payload: payload.clone(),
+            })
+            .collect())
+    }
+
     pub(crate) fn latest_height_in_cache(&self) -> Stm<Option<BlockHeight>> {
         let cache = self.cached_data.read()?;
         Ok(cache.upper_bound())
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 mod cache;
+pub mod certificate;
 mod error;
 mod finality;
+pub mod store;
 pub mod sync;
 
 pub mod convert;
@@ -25,7 +27,7 @@ use std::time::Duration;
 
 pub use crate::cache::{SequentialAppendError, SequentialKeyCache, ValueIter};
 pub use crate::error::Error;
-pub use crate::finality::CachedFinalityProvider;
+pub use crate::finality::{CachedFinalityProvider, ParentViewPayload};
 pub use crate::toggle::Toggle;
 
 pub type BlockHeight = u64;
@@ -0,0 +1,92 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! Persistence of the parent view cache, so a node doesn't have to re-fetch
+//! everything from the parent after a restart.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BlockHeight, IPCParentFinality, ParentViewPayload};
+
+/// A parent height along with what the syncer observed there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParentViewEntry {
+    pub height: BlockHeight,
+    /// `None` if the parent height was a null round.
+    pub payload: Option<ParentViewPayload>,
+}
+
+/// Durable storage for the parent view cache.
+///
+/// The syncer writes every block it adds to the in-memory cache here as well,
+/// and removes them once they fall below the committed finality.
+pub trait ParentViewStore: Send + Sync {
+    /// Store the data observed at a parent height, overwriting any previous entry.
+    fn put(&self, entry: &ParentViewEntry) -> anyhow::Result<()>;
+
+    /// Load all the entries at or above a parent height, in ascending order of height.
+    fn load_from(&self, height: BlockHeight) -> anyhow::Result<Vec<ParentViewEntry>>;
+
+    /// Remove all the entries below a parent height.
+    fn prune_below(&self, height: BlockHeight) -> anyhow::Result<()>;
+
+    /// Remove all the entries, for example because the parent chain reorganised.
+    fn clear(&self) -> anyhow::Result<()>;
+}
+
+/// Select the entries which can be appended to the cache after a committed finality:
+/// they have to follow the finality without gaps.
+///
+/// Anything after the first gap is dropped and will be fetched from the parent again.
+pub(crate) fn restorable_entries(
+    finality: &IPCParentFinality,
+    entries: Vec<ParentViewEntry>,
+) -> Vec<ParentViewEntry> {
+    let mut next = finality.height + 1;
+    entries
+        .into_iter()
+        .skip_while(|e| e.height < next)
+        .take_while(|e| {
+            let ok = e.height == next;
+            next += 1;
+            ok
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::IPCParentFinality;
+
+    use super::{restorable_entries, ParentViewEntry};
+
+    fn entries(heights: &[u64]) -> Vec<ParentViewEntry> {
+        heights
+            .iter()
+            .map(|h| ParentViewEntry {
+                height: *h,
+                payload: None,
+            })
+            .collect()
+    }
+
+    fn heights(entries: &[ParentViewEntry]) -> Vec<u64> {
+        entries.iter().map(|e| e.height).collect()
+    }
+
+    #[test]
+    fn restore_after_finality_until_gap() {
+        let finality = IPCParentFinality {
+            height: 10,
+            block_hash: vec![1],
+        };
+
+        let restored = restorable_entries(&finality, entries(&[9, 10, 11, 12, 13, 15, 16]));
+        assert_eq!(heights(&restored), vec![11, 12, 13]);
+
+        let restored = restorable_entries(&finality, entries(&[12, 13]));
+        assert!(restored.is_empty());
+
+        let restored = restorable_entries(&finality, entries(&[]));
+        assert!(restored.is_empty());
+    }
+}
@@ -6,12 +6,14 @@ mod syncer;
 mod tendermint;
 
 use crate::proxy::ParentQueryProxy;
-use crate::sync::syncer::LotusParentSyncer;
+use crate::sync::syncer::{map_voting_err, LotusParentSyncer};
 use crate::sync::tendermint::TendermintAwareSyncer;
 use crate::voting::VoteTally;
-use crate::{CachedFinalityProvider, Config, IPCParentFinality, ParentFinalityProvider, Toggle};
+use crate::{
+    CachedFinalityProvider, Config, Error, IPCParentFinality, ParentFinalityProvider, Toggle,
+};
 use anyhow::anyhow;
-use async_stm::atomically;
+use async_stm::{atomically, atomically_or_err};
 use ethers::utils::hex;
 use ipc_ipld_resolver::ValidatorKey;
 use std::sync::Arc;
@@ -145,6 +147,8 @@ where
     })
     .await;
 
+    restore_parent_view(&view_provider, &vote_tally, &finality).await;
+
     tracing::info!(
         finality = finality.to_string(),
         "launching parent syncer with last committed finality"
@@ -162,6 +166,55 @@ where
     Ok(())
 }
 
+/// Add the parent view persisted before a restart to the cache and the vote tally,
+/// so the syncer can carry on from where it left off instead of fetching it again.
+async fn restore_parent_view<P>(
+    view_provider: &Arc<Toggle<CachedFinalityProvider<P>>>,
+    vote_tally: &VoteTally,
+    finality: &IPCParentFinality,
+) where
+    P: ParentQueryProxy + Send + Sync + 'static,
+{
+    let provider = view_provider.clone();
+    let finality = finality.clone();
+    let res = tokio::task::spawn_blocking(move || provider.load_persisted(&finality)).await;
+
+    let entries = match res.map_err(anyhow::Error::from).and_then(|r| r) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!(error = e.to_string(), "cannot load persisted parent view");
+            return;
+        }
+    };
+
+    if entries.is_empty() {
+        return;
+    }
+
+    let res = atomically_or_err::<_, Error, _>(|| {
+        for entry in entries.iter() {
+            view_provider.new_parent_view(entry.height, entry.payload.clone())?;
+            vote_tally
+                .add_block(entry.height, entry.payload.as_ref().map(|p| p.0.clone()))
+                .map_err(map_voting_err)?;
+        }
+        Ok(())
+    })
+    .await;
+
+    match res {
+        Ok(()) => tracing::info!(
+            from = entries.first().map(|e| e.height),
+            to = entries.last().map(|e| e.height),
+            "restored persisted parent view"
+        ),
+        Err(e) => tracing::warn!(
+            error = e.to_string(),
+            "cannot restore persisted parent view, fetching it from the parent"
+        ),
+    }
+}
+
 /// Start the parent finality listener in the background
 fn start_syncing<T, C, P>(
     config: Config,
@@ -9,7 +9,7 @@ use crate::voting::{self, VoteTally};
 use crate::{
     is_null_round_str, BlockHash, BlockHeight, CachedFinalityProvider, Config, Error, Toggle,
 };
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use async_stm::{atomically, atomically_or_err, StmError};
 use ethers::utils::hex;
 use libp2p::futures::TryFutureExt;
@@ -35,6 +35,8 @@ pub(crate) struct LotusParentSyncer<T, P> {
     /// the polling frequence to where it's impractical after
     /// we have caught up.
     sync_many: bool,
+    /// The committed finality height below which the persisted parent view was last pruned.
+    pruned_height: BlockHeight,
 }
 
 impl<T, P> LotusParentSyncer<T, P>
@@ -56,6 +58,7 @@ where
             vote_tally,
             query,
             sync_many: true,
+            pruned_height: 0,
         })
     }
 
@@ -67,6 +70,8 @@ where
             return Ok(());
         };
 
+        self.prune_persisted().await;
+
         let (mut latest_height_fetched, mut first_non_null_parent_hash) =
             self.latest_cached_data().await;
         tracing::debug!(chain_head, latest_height_fetched, "syncing heights");
@@ -205,6 +210,8 @@ where
                     })
                     .await?;
 
+                    self.persist(height, None).await;
+
                     emit(ParentFinalityAcquired {
                         source: "Parent syncer",
                         is_null: true,
@@ -258,6 +265,8 @@ where
         })
         .await?;
 
+        self.persist(height, Some(data.clone())).await;
+
         emit(ParentFinalityAcquired {
             source: "Parent syncer",
             is_null: false,
@@ -298,11 +307,61 @@ where
     async fn reset(&self) -> anyhow::Result<()> {
         let finality = query_starting_finality(&self.query, &self.parent_proxy).await?;
         atomically(|| self.provider.reset(finality.clone())).await;
+        self.with_store(|p| p.clear_persisted())
+            .await
+            .context("failed to clear the persisted parent view")?;
         Ok(())
     }
+
+    /// Access the store of the parent view on a blocking thread, so disk writes don't hold up the runtime.
+    async fn with_store<F, R>(&self, f: F) -> anyhow::Result<R>
+    where
+        F: FnOnce(&Toggle<CachedFinalityProvider<P>>) -> anyhow::Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let provider = self.provider.clone();
+        tokio::task::spawn_blocking(move || f(&provider))
+            .await
+            .context("parent view store task failed")?
+    }
+
+    /// Write the parent view that was just added to the cache to the store.
+    ///
+    /// Failing to do so is not fatal: the restored cache ends at the first gap,
+    /// and anything after it is fetched from the parent again after a restart.
+    async fn persist(&self, height: BlockHeight, maybe_payload: Option<ParentViewPayload>) {
+        let res = self
+            .with_store(move |p| p.persist_parent_view(height, maybe_payload))
+            .await;
+
+        if let Err(e) = res {
+            tracing::warn!(
+                error = e.to_string(),
+                height,
+                "failed to persist parent view"
+            );
+        }
+    }
+
+    /// Remove the persisted parent view below the committed finality, once it moved on.
+    async fn prune_persisted(&mut self) {
+        let finality = atomically(|| self.provider.last_committed_finality()).await;
+        let height = match finality {
+            Some(f) if f.height > self.pruned_height => f.height,
+            _ => return,
+        };
+        match self.with_store(move |p| p.prune_persisted(height)).await {
+            Ok(()) => self.pruned_height = height,
+            Err(e) => tracing::warn!(
+                error = e.to_string(),
+                height,
+                "failed to prune persisted parent view"
+            ),
+        }
+    }
 }
 
-fn map_voting_err(e: StmError<voting::Error>) -> StmError<Error> {
+pub(crate) fn map_voting_err(e: StmError<voting::Error>) -> StmError<Error> {
     match e {
         StmError::Abort(e) => {
             tracing::error!(
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use crate::finality::ParentViewPayload;
+use crate::store::ParentViewEntry;
 use crate::{
     BlockHash, BlockHeight, CachedFinalityProvider, Error, IPCParentFinality,
     ParentFinalityProvider, ParentViewProvider,
@@ -127,4 +128,31 @@ impl<P> Toggle<CachedFinalityProvider<P>> {
     pub fn first_non_null_block(&self, height: BlockHeight) -> Stm<Option<BlockHeight>> {
         self.perform_or_else(|p| p.first_non_null_block(height), None)
     }
+
+    pub fn cached_entries(&self) -> Stm<Vec<ParentViewEntry>> {
+        self.perform_or_else(|p| p.cached_entries(), Vec::new())
+    }
+
+    pub fn persist_parent_view(
+        &self,
+        height: BlockHeight,
+        maybe_payload: Option<ParentViewPayload>,
+    ) -> anyhow::Result<()> {
+        self.perform_or_else(|p| p.persist_parent_view(height, maybe_payload), ())
+    }
+
+    pub fn load_persisted(
+        &self,
+        finality: &IPCParentFinality,
+    ) -> anyhow::Result<Vec<ParentViewEntry>> {
+        self.perform_or_else(|p| p.load_persisted(finality), Vec::new())
+    }
+
+    pub fn prune_persisted(&self, height: BlockHeight) -> anyhow::Result<()> {
+        self.perform_or_else(|p| p.prune_persisted(height), ())
+    }
+
+    pub fn clear_persisted(&self) -> anyhow::Result<()> {
+        self.perform_or_else(|p| p.clear_persisted(), ())
+    }
 }
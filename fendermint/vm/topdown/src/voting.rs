@@ -7,11 +7,12 @@ use std::fmt::Display;
 use std::hash::Hash;
 use std::{fmt::Debug, time::Duration};
 
+use crate::certificate::QuorumCertificate;
 use crate::observe::{
     ParentFinalityCommitted, ParentFinalityPeerQuorumReached, ParentFinalityPeerVoteReceived,
     ParentFinalityPeerVoteSent,
 };
-use crate::{BlockHash, BlockHeight};
+use crate::{BlockHash, BlockHeight, Bytes};
 use ipc_observability::{emit, serde::HexEncodableBlockHash};
 
 // Usign this type because it's `Hash`, unlike the normal `libsecp256k1::PublicKey`.
@@ -20,6 +21,14 @@ use ipc_ipld_resolver::VoteRecord;
 
 pub type Weight = u64;
 
+/// Calculate the minimum weight needed for a proposal to pass given the total weight of the membership.
+///
+/// This is inclusive, that is, if the sum of weight is greater or equal to this, it should pass.
+/// The equivalent formula can be found in CometBFT [here](https://github.com/cometbft/cometbft/blob/a8991d63e5aad8be82b90329b55413e3a4933dc0/types/vote_set.go#L307).
+pub fn quorum_threshold(total_weight: Weight) -> Weight {
+    total_weight * 2 / 3 + 1
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error<K = ValidatorKey, V: AsRef<[u8]> = BlockHash> {
     #[error("the last finalized block has not been set")]
@@ -66,6 +75,13 @@ pub struct VoteTally<K = ValidatorKey, V = BlockHash> {
     /// same height.
     votes: TVar<im::OrdMap<BlockHeight, im::HashMap<V, im::HashSet<K>>>>,
 
+    /// The signed envelopes of the votes we have them for, indexed by height and validator,
+    /// so we can present them as evidence in quorum certificates.
+    signed_votes: TVar<im::OrdMap<BlockHeight, im::HashMap<K, Bytes>>>,
+
+    /// Quorum certificates of the finalities committed since the last time they were taken.
+    certificates: TVar<im::OrdMap<BlockHeight, QuorumCertificate<K, V>>>,
+
     /// Adding votes can be paused if we observe that looking for a quorum takes too long
     /// and is often retried due to votes being added.
     pause_votes: TVar<bool>,
@@ -86,6 +102,8 @@ where
             power_table: TVar::default(),
             chain: TVar::default(),
             votes: TVar::default(),
+            signed_votes: TVar::default(),
+            certificates: TVar::default(),
             pause_votes: TVar::new(false),
         }
     }
@@ -98,6 +116,8 @@ where
             power_table: TVar::new(im::HashMap::from_iter(power_table)),
             chain: TVar::new(im::OrdMap::from_iter([(height, Some(hash))])),
             votes: TVar::default(),
+            signed_votes: TVar::default(),
+            certificates: TVar::default(),
             pause_votes: TVar::new(false),
         }
     }
@@ -112,14 +132,23 @@ where
         }
     }
 
+    /// Return the keys of the validators who currently have power.
+    pub fn validators(&self) -> Stm<im::HashSet<K>> {
+        let pt = self.power_table.read()?;
+        Ok(pt
+            .iter()
+            .filter(|(_, weight)| **weight > 0)
+            .map(|(vk, _)| vk.clone())
+            .collect())
+    }
+
     /// Calculate the minimum weight needed for a proposal to pass with the current membership.
     ///
-    /// This is inclusive, that is, if the sum of weight is greater or equal to this, it should pass.
-    /// The equivalent formula can be found in CometBFT [here](https://github.com/cometbft/cometbft/blob/a8991d63e5aad8be82b90329b55413e3a4933dc0/types/vote_set.go#L307).
+    /// See [quorum_threshold].
     pub fn quorum_threshold(&self) -> Stm<Weight> {
         let total_weight: Weight = self.power_table.read().map(|pt| pt.values().sum())?;
 
-        Ok(total_weight * 2 / 3 + 1)
+        Ok(quorum_threshold(total_weight))
     }
 
     /// Return the height of the first entry in the chain.
@@ -237,6 +266,33 @@ where
         Ok(true)
     }
 
+    /// Add a vote along with the signed envelope it arrived in.
+    ///
+    /// Works like [VoteTally::add_vote], but also keeps the envelope around so that the vote
+    /// can be included in the quorum certificate of the block once it's finalized. Adding
+    /// the envelope of a vote which was already added without one is not a duplicate.
+    pub fn add_signed_vote(
+        &self,
+        validator_key: K,
+        block_height: BlockHeight,
+        block_hash: V,
+        envelope: Bytes,
+    ) -> StmResult<bool, Error<K, V>> {
+        let added = self.add_vote(validator_key.clone(), block_height, block_hash)?;
+
+        if block_height >= self.last_finalized_height()? {
+            self.signed_votes.update_mut(|signed_votes| {
+                signed_votes
+                    .entry(block_height)
+                    .or_default()
+                    .entry(validator_key)
+                    .or_insert(envelope);
+            })?;
+        }
+
+        Ok(added)
+    }
+
     /// Pause adding more votes until we are finished calling `find_quorum` which
     /// automatically re-enables them.
     pub fn pause_votes_until_find_quorum(&self) -> Stm<()> {
@@ -309,9 +365,91 @@ where
         Ok(None)
     }
 
+    /// Collect the signed votes which make up a quorum for a block on the chain.
+    ///
+    /// Every validator is represented by its vote at the lowest height at or above the block,
+    /// so the chain segment the certificate has to include is as short as possible.
+    ///
+    /// Returns `None` if the block is not on our chain, or we don't have enough signed votes
+    /// for a quorum, which can happen if we didn't receive all the votes that the proposer did.
+    pub fn quorum_certificate(
+        &self,
+        block_height: BlockHeight,
+        block_hash: &V,
+    ) -> Stm<Option<QuorumCertificate<K, V>>> {
+        let chain = self.chain.read()?;
+
+        match chain.get(&block_height) {
+            Some(Some(h)) if h == block_hash => {}
+            _ => return Ok(None),
+        }
+
+        let quorum_threshold = self.quorum_threshold()?;
+        let votes = self.votes.read()?;
+        let signed_votes = self.signed_votes.read()?;
+        let power_table = self.power_table.read()?;
+
+        let mut weight = 0;
+        let mut voters = im::HashSet::new();
+        let mut envelopes = Vec::new();
+        let mut max_height = block_height;
+
+        for (height, hash) in chain.range(block_height..) {
+            let Some(hash) = hash else {
+                continue;
+            };
+            let Some(votes_for_block) = votes.get(height).and_then(|vs| vs.get(hash)) else {
+                continue;
+            };
+            let Some(signed_at_height) = signed_votes.get(height) else {
+                continue;
+            };
+            for vk in votes_for_block {
+                if voters.contains(vk) {
+                    continue;
+                }
+                // Our own vote is added before it's signed, and we might not have its envelope yet.
+                let Some(envelope) = signed_at_height.get(vk) else {
+                    continue;
+                };
+                voters.insert(vk.clone());
+                weight += power_table.get(vk).cloned().unwrap_or_default();
+                envelopes.push(envelope.clone());
+                max_height = *height;
+            }
+        }
+
+        if weight < quorum_threshold {
+            return Ok(None);
+        }
+
+        Ok(Some(QuorumCertificate {
+            block_height,
+            block_hash: block_hash.clone(),
+            chain: chain
+                .range(block_height..=max_height)
+                .map(|(h, bh)| (*h, bh.clone()))
+                .collect(),
+            power_table: power_table.iter().map(|(vk, w)| (vk.clone(), *w)).collect(),
+            votes: envelopes,
+        }))
+    }
+
+    /// Take the quorum certificates of the finalities committed since the last call.
+    ///
+    /// The application is expected to persist these when it commits the block.
+    pub fn take_certificates(&self) -> Stm<Vec<QuorumCertificate<K, V>>> {
+        let certificates = self.certificates.read_clone()?;
+        self.certificates.write(im::OrdMap::new())?;
+        Ok(certificates.into_iter().map(|(_, c)| c).collect())
+    }
+
     /// Call when a new finalized block is added to the ledger, to clear out all preceding blocks.
     ///
     /// After this operation the minimum item in the chain will the new finalized block.
+    ///
+    /// If we have the signed votes for a quorum on the block, a certificate is set aside
+    /// until it's taken with [VoteTally::take_certificates].
     pub fn set_finalized(
         &self,
         parent_block_height: BlockHeight,
@@ -319,6 +457,18 @@ where
         proposer: Option<&str>,
         local_block_height: Option<BlockHeight>,
     ) -> Stm<()> {
+        match self.quorum_certificate(parent_block_height, &parent_block_hash)? {
+            Some(certificate) => self.certificates.update_mut(|certificates| {
+                certificates.insert(parent_block_height, certificate);
+            })?,
+            None => {
+                tracing::debug!(
+                    parent_block_height,
+                    "no quorum of signed votes for the finalized block"
+                );
+            }
+        }
+
         self.chain.update(|chain| {
             let (_, mut chain) = chain.split(&parent_block_height);
             chain.insert(parent_block_height, Some(parent_block_hash.clone()));
@@ -328,6 +478,9 @@ where
         self.votes
             .update(|votes| votes.split(&parent_block_height).1)?;
 
+        self.signed_votes
+            .update(|signed_votes| signed_votes.split(&parent_block_height).1)?;
+
         emit(ParentFinalityCommitted {
             local_height: local_block_height,
             parent_height: parent_block_height,
@@ -453,6 +606,22 @@ pub async fn publish_vote_loop<V, F>(
 
             match VoteRecord::signed(&key, subnet_id.clone(), vote) {
                 Ok(vote) => {
+                    // Keep our own signed vote so it can go into the quorum certificate.
+                    let envelope = vote.envelope().clone().into_protobuf_encoding();
+                    let res = atomically_or_err(|| {
+                        vote_tally.add_signed_vote(
+                            validator_key.clone(),
+                            next_height,
+                            next_hash.clone(),
+                            envelope.clone(),
+                        )
+                    })
+                    .await;
+
+                    if let Err(e) = res {
+                        tracing::error!(error = e.to_string(), "failed to add own signed vote");
+                    }
+
                     if let Err(e) = client.publish_vote(vote) {
                         tracing::error!(error = e.to_string(), "failed to publish vote");
                     }
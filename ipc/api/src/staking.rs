@@ -62,6 +62,20 @@ pub struct ValidatorStakingInfo {
     metadata: Vec<u8>,
 }
 
+impl ValidatorStakingInfo {
+    pub fn confirmed_collateral(&self) -> &TokenAmount {
+        &self.confirmed_collateral
+    }
+
+    pub fn total_collateral(&self) -> &TokenAmount {
+        &self.total_collateral
+    }
+
+    pub fn metadata(&self) -> &[u8] {
+        &self.metadata
+    }
+}
+
 impl Display for ValidatorStakingInfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
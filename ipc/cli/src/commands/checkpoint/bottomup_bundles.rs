@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: MIT
 //! List bottom up bundles
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::clock::ChainEpoch;
+use ipc_api::checkpoint::BottomUpCheckpointBundle;
 use ipc_api::subnet_id::SubnetID;
+use serde::Serialize;
 
 use crate::commands::get_ipc_provider;
 use crate::{CommandLineHandler, GlobalArguments};
@@ -19,22 +21,51 @@ pub(crate) struct GetBottomUpBundles;
 #[async_trait]
 impl CommandLineHandler for GetBottomUpBundles {
     type Arguments = GetBottomUpBundlesArgs;
+    type Output = BottomUpBundlesOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("get bottom up bundles with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
+        let mut bundles = Vec::new();
         for h in arguments.from_epoch..=arguments.to_epoch {
             let Some(bundle) = provider.get_bottom_up_bundle(&subnet, h).await? else {
                 continue;
             };
-
-            println!("bottom up checkpoint bundle at height: {}", h);
-            println!("{}", serde_json::to_string(&bundle)?);
+            bundles.push(BottomUpBundleAtHeight { height: h, bundle });
         }
 
+        Ok(BottomUpBundlesOutput { bundles })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct BottomUpBundleAtHeight {
+    pub height: ChainEpoch,
+    pub bundle: BottomUpCheckpointBundle,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct BottomUpBundlesOutput {
+    pub bundles: Vec<BottomUpBundleAtHeight>,
+}
+
+impl Display for BottomUpBundlesOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, b) in self.bundles.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "bottom up checkpoint bundle at height: {}", b.height)?;
+            let json = serde_json::to_string(&b.bundle).map_err(|_| std::fmt::Error)?;
+            write!(f, "{json}")?;
+        }
         Ok(())
     }
 }
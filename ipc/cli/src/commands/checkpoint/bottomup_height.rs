@@ -1,12 +1,14 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: MIT
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::Args;
+use fvm_shared::clock::ChainEpoch;
 use ipc_api::subnet_id::SubnetID;
+use serde::Serialize;
 
 use crate::commands::get_ipc_provider;
 use crate::{CommandLineHandler, GlobalArguments};
@@ -17,8 +19,12 @@ pub(crate) struct LastBottomUpCheckpointHeight;
 #[async_trait]
 impl CommandLineHandler for LastBottomUpCheckpointHeight {
     type Arguments = LastBottomUpCheckpointHeightArgs;
+    type Output = CheckpointHeightOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!(
             "list bottom up checkpoint height with args: {:?}",
             arguments
@@ -28,9 +34,19 @@ impl CommandLineHandler for LastBottomUpCheckpointHeight {
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
         let height = provider.last_bottom_up_checkpoint_height(&subnet).await?;
-        println!("height: {height}");
 
-        Ok(())
+        Ok(CheckpointHeightOutput { height })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CheckpointHeightOutput {
+    pub height: ChainEpoch,
+}
+
+impl Display for CheckpointHeightOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "height: {}", self.height)
     }
 }
 
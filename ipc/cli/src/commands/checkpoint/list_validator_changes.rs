@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: MIT
 //! List validator change set cli command
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::clock::ChainEpoch;
+use ipc_api::staking::StakingChangeRequest;
 use ipc_api::subnet_id::SubnetID;
+use serde::Serialize;
 
 use crate::commands::get_ipc_provider;
 use crate::{CommandLineHandler, GlobalArguments};
@@ -19,18 +21,50 @@ pub(crate) struct ListValidatorChanges;
 #[async_trait]
 impl CommandLineHandler for ListValidatorChanges {
     type Arguments = ListValidatorChangesArgs;
+    type Output = ValidatorChangesOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("list validator changes with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
+        let mut heights = Vec::new();
         for h in arguments.from_epoch..=arguments.to_epoch {
             let changes = provider.get_validator_changeset(&subnet, h).await?;
-            log::info!("changes at height: {h} are: {:?}", changes.value);
+            heights.push(ValidatorChangesAtHeight {
+                height: h,
+                changes: changes.value,
+            });
         }
 
+        Ok(ValidatorChangesOutput { heights })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ValidatorChangesAtHeight {
+    pub height: ChainEpoch,
+    pub changes: Vec<StakingChangeRequest>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct ValidatorChangesOutput {
+    pub heights: Vec<ValidatorChangesAtHeight>,
+}
+
+impl Display for ValidatorChangesOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, h) in self.heights.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "changes at height: {} are: {:?}", h.height, h.changes)?;
+        }
         Ok(())
     }
 }
@@ -11,7 +11,8 @@ use crate::commands::checkpoint::quorum_reached::{
     GetQuorumReacehdEvents, GetQuorumReachedEventsArgs,
 };
 use crate::commands::checkpoint::relayer::{BottomUpRelayer, BottomUpRelayerArgs};
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::commands::checkpoint::sign_safe_proposal::{SignSafeProposal, SignSafeProposalArgs};
+use crate::{run, GlobalArguments};
 use clap::{Args, Subcommand};
 
 mod bottomup_bundles;
@@ -19,6 +20,7 @@ mod bottomup_height;
 mod list_validator_changes;
 mod quorum_reached;
 mod relayer;
+mod sign_safe_proposal;
 
 #[derive(Debug, Args)]
 #[command(name = "checkpoint", about = "checkpoint related commands")]
@@ -31,17 +33,16 @@ pub(crate) struct CheckpointCommandsArgs {
 impl CheckpointCommandsArgs {
     pub async fn handle(&self, global: &GlobalArguments) -> anyhow::Result<()> {
         match &self.command {
-            Commands::Relayer(args) => BottomUpRelayer::handle(global, args).await,
-            Commands::ListValidatorChanges(args) => {
-                ListValidatorChanges::handle(global, args).await
-            }
-            Commands::ListBottomupBundle(args) => GetBottomUpBundles::handle(global, args).await,
+            Commands::Relayer(args) => run::<BottomUpRelayer>(global, args).await,
+            Commands::ListValidatorChanges(args) => run::<ListValidatorChanges>(global, args).await,
+            Commands::ListBottomupBundle(args) => run::<GetBottomUpBundles>(global, args).await,
             Commands::QuorumReachedEvents(args) => {
-                GetQuorumReacehdEvents::handle(global, args).await
+                run::<GetQuorumReacehdEvents>(global, args).await
             }
             Commands::LastBottomupCheckpointHeight(args) => {
-                LastBottomUpCheckpointHeight::handle(global, args).await
+                run::<LastBottomUpCheckpointHeight>(global, args).await
             }
+            Commands::SignSafeProposal(args) => run::<SignSafeProposal>(global, args).await,
         }
     }
 }
@@ -53,4 +54,5 @@ pub(crate) enum Commands {
     ListBottomupBundle(GetBottomUpBundlesArgs),
     QuorumReachedEvents(GetQuorumReachedEventsArgs),
     LastBottomupCheckpointHeight(LastBottomUpCheckpointHeightArgs),
+    SignSafeProposal(SignSafeProposalArgs),
 }
@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: MIT
 //! List quorum reached events
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::clock::ChainEpoch;
+use ipc_api::checkpoint::QuorumReachedEvent;
 use ipc_api::subnet_id::SubnetID;
+use serde::Serialize;
 
 use crate::commands::get_ipc_provider;
 use crate::{CommandLineHandler, GlobalArguments};
@@ -19,20 +21,40 @@ pub(crate) struct GetQuorumReacehdEvents;
 #[async_trait]
 impl CommandLineHandler for GetQuorumReacehdEvents {
     type Arguments = GetQuorumReachedEventsArgs;
+    type Output = QuorumReachedEventsOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("get quorum reached events with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
+        let mut events = Vec::new();
         for h in arguments.from_epoch..=arguments.to_epoch {
-            let events = provider.quorum_reached_events(&subnet, h).await?;
-            for e in events {
-                println!("{e}");
-            }
+            events.extend(provider.quorum_reached_events(&subnet, h).await?);
         }
 
+        Ok(QuorumReachedEventsOutput { events })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct QuorumReachedEventsOutput {
+    pub events: Vec<QuorumReachedEvent>,
+}
+
+impl Display for QuorumReachedEventsOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, e) in self.events.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{e}")?;
+        }
         Ok(())
     }
 }
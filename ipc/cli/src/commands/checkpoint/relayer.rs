@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: MIT
 
 use crate::commands::get_subnet_config;
-use crate::{require_fil_addr_from_str, CommandLineHandler, GlobalArguments};
+use crate::{require_fil_addr_from_str, CommandLineHandler, GlobalArguments, NoOutput};
 use anyhow::anyhow;
 use anyhow::Context;
 use async_trait::async_trait;
@@ -10,11 +10,14 @@ use clap::Args;
 use fvm_shared::address::Address;
 use fvm_shared::clock::ChainEpoch;
 use ipc_api::subnet_id::SubnetID;
+use ipc_provider::checkpoint::submission::{
+    ContractWalletSubmission, ProposalQueue, SafeSubmission,
+};
 use ipc_provider::checkpoint::BottomUpCheckpointManager;
 use ipc_provider::config::Config;
 use ipc_provider::new_evm_keystore_from_config;
 use ipc_provider::observe::register_metrics as register_checkpoint_metrics;
-use ipc_wallet::EvmKeyStore;
+use ipc_wallet::{EthKeyAddress, EvmKeyStore, PersistentKeyStore};
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
@@ -28,8 +31,12 @@ pub(crate) struct BottomUpRelayer;
 #[async_trait]
 impl CommandLineHandler for BottomUpRelayer {
     type Arguments = BottomUpRelayerArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("start bottom up relayer with args: {:?}", arguments);
 
         // Prometheus metrics
@@ -76,6 +83,20 @@ impl CommandLineHandler for BottomUpRelayer {
         let child = get_subnet_config(&config_path, &subnet)?;
         let parent = get_subnet_config(&config_path, &parent)?;
 
+        let safe = match arguments.safe.as_ref() {
+            Some(safe) => {
+                let mut submission = SafeSubmission::new(ethers::types::Address::from_str(safe)?);
+                for signer in arguments.safe_signers.iter() {
+                    submission = submission.with_signer(&private_key(&keystore, signer)?)?;
+                }
+                if let Some(dir) = arguments.safe_proposals_dir.as_ref() {
+                    submission = submission.with_proposals(ProposalQueue::new(dir)?);
+                }
+                Some(submission)
+            }
+            None => None,
+        };
+
         let mut manager = BottomUpCheckpointManager::new_evm_manager(
             parent.clone(),
             child.clone(),
@@ -88,6 +109,14 @@ impl CommandLineHandler for BottomUpRelayer {
             manager = manager.with_finalization_blocks(v as ChainEpoch);
         }
 
+        if let Some(safe) = safe {
+            manager = manager.with_submission_strategy(Arc::new(safe));
+        } else if let Some(wallet) = arguments.contract_wallet.as_ref() {
+            let wallet = ethers::types::Address::from_str(wallet)?;
+            manager =
+                manager.with_submission_strategy(Arc::new(ContractWalletSubmission::new(wallet)));
+        }
+
         let interval = Duration::from_secs(
             arguments
                 .checkpoint_interval_sec
@@ -95,10 +124,22 @@ impl CommandLineHandler for BottomUpRelayer {
         );
         manager.run(submitter, interval).await;
 
-        Ok(())
+        Ok(NoOutput {})
     }
 }
 
+/// The private key of an address in the EVM keystore.
+fn private_key(
+    keystore: &PersistentKeyStore<EthKeyAddress>,
+    addr: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let addr = ethers::types::Address::from_str(addr)?;
+    let key_info = keystore
+        .get(&addr.into())?
+        .ok_or_else(|| anyhow!("address {addr:?} does not have private key in key store"))?;
+    Ok(key_info.private_key().to_vec())
+}
+
 #[derive(Debug, Args)]
 #[command(about = "Start the bottom up relayer daemon")]
 pub(crate) struct BottomUpRelayerArgs {
@@ -126,4 +167,29 @@ pub(crate) struct BottomUpRelayerArgs {
         help = "Metrics address to listen on. Enables Prometheus metrics if set"
     )]
     pub metrics_address: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "contract_wallet",
+        help = "The hex encoded address of a Safe multisig to submit checkpoints through"
+    )]
+    pub safe: Option<String>,
+    #[arg(
+        long,
+        requires = "safe",
+        value_delimiter = ',',
+        help = "Comma separated hex encoded addresses of Safe owners in the keystore to sign with"
+    )]
+    pub safe_signers: Vec<String>,
+    #[arg(
+        long,
+        requires = "safe",
+        help = "Directory to write Safe proposals to, for the other owners to sign"
+    )]
+    pub safe_proposals_dir: Option<String>,
+    #[arg(
+        long,
+        help = "The hex encoded address of a contract wallet with an `execute(address,uint256,bytes)` function to submit checkpoints through"
+    )]
+    pub contract_wallet: Option<String>,
 }
@@ -0,0 +1,90 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::Args;
+use ipc_provider::checkpoint::submission::ProposalQueue;
+use ipc_provider::config::Config;
+use ipc_provider::new_evm_keystore_from_config;
+use ipc_wallet::EvmKeyStore;
+use serde::Serialize;
+
+use crate::{CommandLineHandler, GlobalArguments};
+
+/// The command to sign a Safe proposal written by a relayer submitting checkpoints through a Safe.
+pub(crate) struct SignSafeProposal;
+
+#[async_trait]
+impl CommandLineHandler for SignSafeProposal {
+    type Arguments = SignSafeProposalArgs;
+    type Output = SignSafeProposalOutput;
+
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
+        log::debug!("sign safe proposal with args: {:?}", arguments);
+
+        let config = Arc::new(Config::from_file(global.config_path())?);
+        let mut keystore = new_evm_keystore_from_config(config)?;
+
+        let signer = match arguments.signer.as_ref() {
+            Some(signer) => ethers::types::Address::from_str(signer)?.into(),
+            None => keystore
+                .get_default()?
+                .ok_or_else(|| anyhow!("no signer address provided"))?,
+        };
+        let key_info = keystore
+            .get(&signer)?
+            .ok_or_else(|| anyhow!("signer does not have private key in key store"))?;
+
+        let signed = ProposalQueue::sign(Path::new(&arguments.proposal), key_info.private_key())?;
+
+        Ok(SignSafeProposalOutput {
+            file: signed.file.to_string_lossy().to_string(),
+            description: signed.description,
+            signer: format!("{:?}", signed.signer),
+            safe_tx_hash: format!("{:?}", signed.safe_tx_hash),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SignSafeProposalOutput {
+    /// The file the signature was written to, next to the proposal.
+    pub file: String,
+    pub description: String,
+    pub signer: String,
+    pub safe_tx_hash: String,
+}
+
+impl Display for SignSafeProposalOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "signed Safe transaction {} to {} by {} written to {:?}",
+            self.safe_tx_hash, self.description, self.signer, self.file
+        )
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(about = "Sign a proposal of a relayer submitting checkpoints through a Safe")]
+pub(crate) struct SignSafeProposalArgs {
+    #[arg(
+        long,
+        help = "The proposal file in the proposals directory of the relayer"
+    )]
+    pub proposal: String,
+    #[arg(
+        long,
+        help = "The hex encoded address of the Safe owner to sign with; the default key if not set"
+    )]
+    pub signer: Option<String>,
+}
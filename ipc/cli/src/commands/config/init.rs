@@ -3,6 +3,8 @@
 use crate::{CommandLineHandler, GlobalArguments};
 use async_trait::async_trait;
 use ipc_provider::config::DEFAULT_CONFIG_TEMPLATE;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
 use std::io::Write;
 
 use clap::Args;
@@ -13,8 +15,12 @@ pub(crate) struct InitConfig;
 #[async_trait]
 impl CommandLineHandler for InitConfig {
     type Arguments = InitConfigArgs;
+    type Output = InitConfigOutput;
 
-    async fn handle(global: &GlobalArguments, _arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        _arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         let path = global.config_path();
         log::debug!("initializing empty config file in {}", path);
 
@@ -30,9 +36,22 @@ impl CommandLineHandler for InitConfig {
                 log::error!("error populating empty config template: {e}");
             })?;
 
-        log::info!("Empty config populated successful in {}", &path);
+        Ok(InitConfigOutput { config_path: path })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct InitConfigOutput {
+    pub config_path: String,
+}
 
-        Ok(())
+impl Display for InitConfigOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Empty config populated successful in {}",
+            self.config_path
+        )
     }
 }
 
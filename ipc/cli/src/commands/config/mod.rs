@@ -8,7 +8,7 @@ use clap::{Args, Subcommand};
 use std::fmt::Debug;
 
 use crate::commands::config::init::{InitConfig, InitConfigArgs};
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::{run, GlobalArguments};
 
 #[derive(Debug, Args)]
 #[command(name = "config", about = "config related commands")]
@@ -21,7 +21,7 @@ pub(crate) struct ConfigCommandsArgs {
 impl ConfigCommandsArgs {
     pub async fn handle(&self, global: &GlobalArguments) -> anyhow::Result<()> {
         match &self.command {
-            Commands::Init(args) => InitConfig::handle(global, args).await,
+            Commands::Init(args) => run::<InitConfig>(global, args).await,
         }
     }
 }
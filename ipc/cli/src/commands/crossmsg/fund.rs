@@ -5,14 +5,19 @@
 use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::bigint::BigInt;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use ipc_api::subnet_id::SubnetID;
+use ipc_provider::offline::TxCall;
 use num_traits::Num;
-use std::{fmt::Debug, str::FromStr};
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
 
+use crate::commands::tx::{write_unsigned_tx, TxOutput};
 use crate::{
     f64_to_token_amount, get_ipc_provider, require_fil_addr_from_str, CommandLineHandler,
-    GlobalArguments,
+    EpochOutput, GlobalArguments, NoOutput,
 };
 
 /// The command to send funds to a subnet from parent
@@ -21,8 +26,12 @@ pub(crate) struct Fund;
 #[async_trait]
 impl CommandLineHandler for Fund {
     type Arguments = FundArgs;
+    type Output = TxOutput<EpochOutput>;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("fund operation with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -40,20 +49,26 @@ impl CommandLineHandler for Fund {
             None => None,
         };
 
-        println!(
-            "fund performed in epoch: {:?}",
-            provider
-                .fund(
-                    subnet,
-                    gateway_addr,
-                    from,
-                    to,
-                    f64_to_token_amount(arguments.amount)?,
-                )
-                .await?,
-        );
-
-        Ok(())
+        let amount = f64_to_token_amount(arguments.amount)?;
+        if let Some(path) = &arguments.unsigned_out {
+            let call = TxCall::Fund {
+                subnet,
+                gateway_addr,
+                to,
+                amount,
+            };
+            let output = write_unsigned_tx(&mut provider, from, call, path).await?;
+            return Ok(TxOutput::Unsigned(output));
+        }
+
+        let epoch = provider
+            .fund(subnet, gateway_addr, from, to, amount)
+            .await?;
+
+        Ok(TxOutput::Sent(EpochOutput::new(
+            "fund performed in epoch",
+            epoch,
+        )))
     }
 }
 
@@ -73,6 +88,11 @@ pub(crate) struct FundArgs {
     pub subnet: String,
     #[arg(help = "The amount to fund in FIL, in whole FIL")]
     pub amount: f64,
+    #[arg(
+        long,
+        help = "Write the unsigned transaction to this file to sign it offline with `tx sign`, instead of sending it"
+    )]
+    pub unsigned_out: Option<String>,
 }
 
 pub struct PreFund;
@@ -80,8 +100,12 @@ pub struct PreFund;
 #[async_trait]
 impl CommandLineHandler for PreFund {
     type Arguments = PreFundArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("pre-fund subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -99,7 +123,7 @@ impl CommandLineHandler for PreFund {
             .await?;
         log::info!("address pre-funded successfully");
 
-        Ok(())
+        Ok(NoOutput {})
     }
 }
 
@@ -123,8 +147,12 @@ pub(crate) struct FundWithToken;
 #[async_trait]
 impl CommandLineHandler for FundWithToken {
     type Arguments = FundWithTokenArgs;
+    type Output = FundWithTokenOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("fund with token operation with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -142,21 +170,38 @@ impl CommandLineHandler for FundWithToken {
             .map_err(|e| anyhow::anyhow!("not a token amount: {e}"))
             .map(TokenAmount::from_atto)?;
 
-        if arguments.approve {
-            println!(
-                "approve token performed in epoch: {:?}",
+        let approve_epoch = if arguments.approve {
+            Some(
                 provider
                     .approve_token(subnet.clone(), from, amount.clone())
                     .await?,
-            );
-        }
+            )
+        } else {
+            None
+        };
+
+        let fund_epoch = provider.fund_with_token(subnet, from, to, amount).await?;
+
+        Ok(FundWithTokenOutput {
+            approve_epoch,
+            fund_epoch,
+        })
+    }
+}
 
-        println!(
-            "fund with token performed in epoch: {:?}",
-            provider.fund_with_token(subnet, from, to, amount).await?,
-        );
+#[derive(Debug, Serialize)]
+pub(crate) struct FundWithTokenOutput {
+    /// Set if the gateway was approved to spend the tokens before funding.
+    pub approve_epoch: Option<ChainEpoch>,
+    pub fund_epoch: ChainEpoch,
+}
 
-        Ok(())
+impl Display for FundWithTokenOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(epoch) = self.approve_epoch {
+            writeln!(f, "approve token performed in epoch: {epoch}")?;
+        }
+        write!(f, "fund with token performed in epoch: {}", self.fund_epoch)
     }
 }
 
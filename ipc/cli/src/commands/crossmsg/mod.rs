@@ -8,7 +8,7 @@ use self::topdown_cross::{
 use crate::commands::crossmsg::fund::Fund;
 use crate::commands::crossmsg::propagate::Propagate;
 use crate::commands::crossmsg::release::Release;
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::{run, GlobalArguments};
 use fund::FundArgs;
 use propagate::PropagateArgs;
 use release::ReleaseArgs;
@@ -31,14 +31,14 @@ pub(crate) struct CrossMsgsCommandsArgs {
 impl CrossMsgsCommandsArgs {
     pub async fn handle(&self, global: &GlobalArguments) -> anyhow::Result<()> {
         match &self.command {
-            Commands::Fund(args) => Fund::handle(global, args).await,
-            Commands::FundWithToken(args) => FundWithToken::handle(global, args).await,
-            Commands::PreFund(args) => PreFund::handle(global, args).await,
-            Commands::Release(args) => Release::handle(global, args).await,
-            Commands::PreRelease(args) => PreRelease::handle(global, args).await,
-            Commands::Propagate(args) => Propagate::handle(global, args).await,
-            Commands::ListTopdownMsgs(args) => ListTopdownMsgs::handle(global, args).await,
-            Commands::ParentFinality(args) => LatestParentFinality::handle(global, args).await,
+            Commands::Fund(args) => run::<Fund>(global, args).await,
+            Commands::FundWithToken(args) => run::<FundWithToken>(global, args).await,
+            Commands::PreFund(args) => run::<PreFund>(global, args).await,
+            Commands::Release(args) => run::<Release>(global, args).await,
+            Commands::PreRelease(args) => run::<PreRelease>(global, args).await,
+            Commands::Propagate(args) => run::<Propagate>(global, args).await,
+            Commands::ListTopdownMsgs(args) => run::<ListTopdownMsgs>(global, args).await,
+            Commands::ParentFinality(args) => run::<LatestParentFinality>(global, args).await,
         }
     }
 }
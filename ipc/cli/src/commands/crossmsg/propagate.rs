@@ -6,7 +6,7 @@ use async_trait::async_trait;
 use clap::Args;
 use std::fmt::Debug;
 
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::{CommandLineHandler, GlobalArguments, NoOutput};
 
 /// The command to propagate a message in the postbox.
 pub(crate) struct Propagate;
@@ -14,8 +14,12 @@ pub(crate) struct Propagate;
 #[async_trait]
 impl CommandLineHandler for Propagate {
     type Arguments = PropagateArgs;
+    type Output = NoOutput;
 
-    async fn handle(_global: &GlobalArguments, _arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        _global: &GlobalArguments,
+        _arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         todo!()
     }
 }
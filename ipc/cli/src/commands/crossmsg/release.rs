@@ -9,7 +9,7 @@ use std::{fmt::Debug, str::FromStr};
 
 use crate::{
     f64_to_token_amount, get_ipc_provider, require_fil_addr_from_str, CommandLineHandler,
-    GlobalArguments,
+    EpochOutput, GlobalArguments, NoOutput,
 };
 
 /// The command to release funds from a child to a parent
@@ -18,8 +18,12 @@ pub(crate) struct Release;
 #[async_trait]
 impl CommandLineHandler for Release {
     type Arguments = ReleaseArgs;
+    type Output = EpochOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("release operation with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -37,20 +41,17 @@ impl CommandLineHandler for Release {
             None => None,
         };
 
-        println!(
-            "release performed in epoch: {:?}",
-            provider
-                .release(
-                    subnet,
-                    gateway_addr,
-                    from,
-                    to,
-                    f64_to_token_amount(arguments.amount)?,
-                )
-                .await?,
-        );
+        let epoch = provider
+            .release(
+                subnet,
+                gateway_addr,
+                from,
+                to,
+                f64_to_token_amount(arguments.amount)?,
+            )
+            .await?;
 
-        Ok(())
+        Ok(EpochOutput::new("release performed in epoch", epoch))
     }
 }
 
@@ -77,8 +78,12 @@ pub struct PreRelease;
 #[async_trait]
 impl CommandLineHandler for PreRelease {
     type Arguments = PreReleaseArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("pre-release subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -92,7 +97,7 @@ impl CommandLineHandler for PreRelease {
             .await?;
         log::info!("address pre-release successfully");
 
-        Ok(())
+        Ok(NoOutput {})
     }
 }
 
@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: MIT
 //! List top down cross messages
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::clock::ChainEpoch;
 use ipc_api::subnet_id::SubnetID;
+use serde::Serialize;
 
 use crate::commands::get_ipc_provider;
 use crate::{CommandLineHandler, GlobalArguments};
@@ -19,32 +20,83 @@ pub(crate) struct ListTopdownMsgs;
 #[async_trait]
 impl CommandLineHandler for ListTopdownMsgs {
     type Arguments = ListTopdownMsgsArgs;
+    type Output = TopdownMsgsOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("list topdown messages with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
+        let mut blocks = Vec::new();
         for h in arguments.from..=arguments.to {
             let result = provider.get_top_down_msgs(&subnet, h).await?;
-            println!(
-                "block height: {}, block hash: {}, number of messages: {}",
-                h,
-                hex::encode(result.block_hash),
-                result.value.len()
-            );
+            let mut messages = Vec::with_capacity(result.value.len());
             for msg in result.value {
-                println!(
-                    "from: {}, to: {}, message: {}, nonce: {} ",
-                    msg.from.to_string()?,
-                    msg.to.to_string()?,
-                    hex::encode(msg.message),
-                    msg.nonce
-                );
+                messages.push(TopdownMsg {
+                    from: msg.from.to_string()?,
+                    to: msg.to.to_string()?,
+                    message: hex::encode(msg.message),
+                    nonce: msg.nonce,
+                });
             }
+            blocks.push(TopdownMsgsAtHeight {
+                height: h,
+                block_hash: hex::encode(result.block_hash),
+                messages,
+            });
         }
 
+        Ok(TopdownMsgsOutput { blocks })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TopdownMsg {
+    pub from: String,
+    pub to: String,
+    /// Hex encoded message payload.
+    pub message: String,
+    pub nonce: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TopdownMsgsAtHeight {
+    pub height: ChainEpoch,
+    pub block_hash: String,
+    pub messages: Vec<TopdownMsg>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct TopdownMsgsOutput {
+    pub blocks: Vec<TopdownMsgsAtHeight>,
+}
+
+impl Display for TopdownMsgsOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, b) in self.blocks.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "block height: {}, block hash: {}, number of messages: {}",
+                b.height,
+                b.block_hash,
+                b.messages.len()
+            )?;
+            for msg in b.messages.iter() {
+                write!(
+                    f,
+                    "\nfrom: {}, to: {}, message: {}, nonce: {} ",
+                    msg.from, msg.to, msg.message, msg.nonce
+                )?;
+            }
+        }
         Ok(())
     }
 }
@@ -65,15 +117,30 @@ pub(crate) struct LatestParentFinality;
 #[async_trait]
 impl CommandLineHandler for LatestParentFinality {
     type Arguments = LatestParentFinalityArgs;
+    type Output = ParentFinalityOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("latest parent finality: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
-        println!("{}", provider.latest_parent_finality(&subnet).await?);
-        Ok(())
+        let height = provider.latest_parent_finality(&subnet).await?;
+        Ok(ParentFinalityOutput { height })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ParentFinalityOutput {
+    pub height: ChainEpoch,
+}
+
+impl Display for ParentFinalityOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.height)
     }
 }
 
@@ -7,13 +7,16 @@ mod config;
 mod crossmsg;
 // mod daemon;
 mod subnet;
+mod tx;
 mod util;
 mod wallet;
 
 use crate::commands::checkpoint::CheckpointCommandsArgs;
 use crate::commands::crossmsg::CrossMsgsCommandsArgs;
+use crate::commands::tx::TxCommandsArgs;
 use crate::commands::util::UtilCommandsArgs;
-use crate::GlobalArguments;
+use crate::output::UsageError;
+use crate::{GlobalArguments, OutputFormat};
 use anyhow::{anyhow, Context, Result};
 
 use clap::{Command, CommandFactory, Parser, Subcommand};
@@ -47,6 +50,7 @@ enum Commands {
     CrossMsg(CrossMsgsCommandsArgs),
     Checkpoint(CheckpointCommandsArgs),
     Util(UtilCommandsArgs),
+    Tx(TxCommandsArgs),
 }
 
 #[derive(Debug, Parser)]
@@ -115,8 +119,14 @@ pub async fn cli() -> anyhow::Result<()> {
     let global = GlobalOptions::parse();
     set_current_network(global.global_params.network());
 
-    // parse the arguments
-    let args = IPCAgentCliCommands::parse();
+    // parse the arguments; in JSON mode usage errors are reported like any other error
+    let args = match IPCAgentCliCommands::try_parse() {
+        Ok(args) => args,
+        Err(e) if e.use_stderr() && global.global_params.output() == OutputFormat::Json => {
+            return Err(UsageError(e.to_string()).into());
+        }
+        Err(e) => e.exit(),
+    };
 
     if let Some(generator) = args.generator {
         let mut cmd = IPCAgentCliCommands::command();
@@ -133,6 +143,7 @@ pub async fn cli() -> anyhow::Result<()> {
                 Commands::Wallet(args) => args.handle(global).await,
                 Commands::Checkpoint(args) => args.handle(global).await,
                 Commands::Util(args) => args.handle(global).await,
+                Commands::Tx(args) => args.handle(global).await,
             };
 
             r.with_context(|| format!("error processing command {:?}", args.command))
@@ -142,6 +153,12 @@ pub async fn cli() -> anyhow::Result<()> {
     }
 }
 
+/// The output format selected on the command line, which is needed to render errors
+/// even when the full set of arguments could not be parsed.
+pub fn output_format() -> OutputFormat {
+    GlobalOptions::parse().global_params.output()
+}
+
 fn print_completions<G: Generator>(gen: G, cmd: &mut Command) {
     generate(gen, cmd, cmd.get_name().to_string(), &mut io::stdout());
 }
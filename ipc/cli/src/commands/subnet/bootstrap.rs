@@ -5,9 +5,13 @@
 use async_trait::async_trait;
 use clap::Args;
 use ipc_api::subnet_id::SubnetID;
-use std::{fmt::Debug, str::FromStr};
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
 
-use crate::{get_ipc_provider, require_fil_addr_from_str, CommandLineHandler, GlobalArguments};
+use crate::{
+    get_ipc_provider, require_fil_addr_from_str, CommandLineHandler, GlobalArguments, NoOutput,
+};
 
 /// The command to add a bootstrap subnet
 pub struct AddBootstrap;
@@ -15,8 +19,12 @@ pub struct AddBootstrap;
 #[async_trait]
 impl CommandLineHandler for AddBootstrap {
     type Arguments = AddBootstrapArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("add subnet bootstrap with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -28,7 +36,9 @@ impl CommandLineHandler for AddBootstrap {
 
         provider
             .add_bootstrap(&subnet, from, arguments.endpoint.clone())
-            .await
+            .await?;
+
+        Ok(NoOutput {})
     }
 }
 
@@ -49,25 +59,32 @@ pub struct ListBootstraps;
 #[async_trait]
 impl CommandLineHandler for ListBootstraps {
     type Arguments = ListBootstrapsArgs;
+    type Output = BootstrapsOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("add subnet bootstrap with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
-        print!(
-            "{}",
-            provider
-                .list_bootstrap_nodes(&subnet)
-                .await?
-                .iter()
-                .as_slice()
-                .join(",")
-        );
-
-        println!();
-        Ok(())
+        let bootstraps = provider.list_bootstrap_nodes(&subnet).await?;
+
+        Ok(BootstrapsOutput { bootstraps })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct BootstrapsOutput {
+    pub bootstraps: Vec<String>,
+}
+
+impl Display for BootstrapsOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.bootstraps.join(","))
     }
 }
 
@@ -2,18 +2,22 @@
 // SPDX-License-Identifier: MIT
 //! Create subnet cli command handler.
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::Args;
+use fvm_shared::address::Address;
 use fvm_shared::clock::ChainEpoch;
 
-use ipc_api::subnet::{Asset, AssetKind, PermissionMode};
+use ipc_api::subnet::{Asset, AssetKind, ConsensusType, ConstructParams, PermissionMode};
 use ipc_api::subnet_id::SubnetID;
+use ipc_provider::offline::TxCall;
+use serde::Serialize;
 
-use crate::commands::get_ipc_provider;
 use crate::commands::subnet::ZERO_ADDRESS;
+use crate::commands::tx::{write_unsigned_tx, TxOutput, UnsignedTxOutput};
+use crate::commands::{get_ipc_provider, get_subnet_config};
 use crate::{f64_to_token_amount, require_fil_addr_from_str, CommandLineHandler, GlobalArguments};
 
 const DEFAULT_ACTIVE_VALIDATORS: u16 = 100;
@@ -37,11 +41,7 @@ impl CreateSubnet {
         let supply_source = parse_supply_source(arguments)?;
         let collateral_source = parse_collateral_source(arguments)?;
 
-        let raw_addr = arguments
-            .validator_gater
-            .clone()
-            .unwrap_or(ZERO_ADDRESS.to_string());
-        let validator_gater = require_fil_addr_from_str(&raw_addr)?;
+        let validator_gater = parse_validator_gater(arguments)?;
         let addr = provider
             .create_subnet(
                 from,
@@ -62,6 +62,48 @@ impl CreateSubnet {
 
         Ok(addr.to_string())
     }
+
+    /// Write the unsigned transaction creating the subnet to `path`, to sign it offline.
+    async fn create_unsigned(
+        global: &GlobalArguments,
+        arguments: &CreateSubnetArgs,
+        path: &str,
+    ) -> anyhow::Result<UnsignedTxOutput> {
+        let mut provider = get_ipc_provider(global)?;
+        let parent = SubnetID::from_str(&arguments.parent)?;
+
+        let from = match &arguments.from {
+            Some(address) => Some(require_fil_addr_from_str(address)?),
+            None => None,
+        };
+
+        let params = ConstructParams {
+            ipc_gateway_addr: get_subnet_config(global.config_path(), &parent)?.gateway_addr(),
+            parent,
+            consensus: ConsensusType::Fendermint,
+            min_validators: arguments.min_validators,
+            min_validator_stake: f64_to_token_amount(arguments.min_validator_stake)?,
+            bottomup_check_period: arguments.bottomup_check_period,
+            active_validators_limit: arguments
+                .active_validators_limit
+                .unwrap_or(DEFAULT_ACTIVE_VALIDATORS),
+            min_cross_msg_fee: f64_to_token_amount(arguments.min_cross_msg_fee)?,
+            permission_mode: arguments.permission_mode,
+            supply_source: parse_supply_source(arguments)?,
+            collateral_source: parse_collateral_source(arguments)?,
+            validator_gater: parse_validator_gater(arguments)?,
+        };
+
+        write_unsigned_tx(&mut provider, from, TxCall::CreateSubnet(params), path).await
+    }
+}
+
+fn parse_validator_gater(arguments: &CreateSubnetArgs) -> anyhow::Result<Address> {
+    let raw_addr = arguments
+        .validator_gater
+        .clone()
+        .unwrap_or(ZERO_ADDRESS.to_string());
+    require_fil_addr_from_str(&raw_addr)
 }
 
 fn parse_supply_source(arguments: &CreateSubnetArgs) -> anyhow::Result<Asset> {
@@ -96,19 +138,35 @@ fn parse_collateral_source(arguments: &CreateSubnetArgs) -> anyhow::Result<Asset
 #[async_trait]
 impl CommandLineHandler for CreateSubnet {
     type Arguments = CreateSubnetArgs;
+    type Output = TxOutput<CreateSubnetOutput>;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("create subnet with args: {:?}", arguments);
 
+        if let Some(path) = &arguments.unsigned_out {
+            let output = CreateSubnet::create_unsigned(global, arguments, path).await?;
+            return Ok(TxOutput::Unsigned(output));
+        }
+
         let address = CreateSubnet::create(global, arguments).await?;
 
-        log::info!(
-            "created subnet actor with id: {}/{}",
-            arguments.parent,
-            address
-        );
+        Ok(TxOutput::Sent(CreateSubnetOutput {
+            subnet_id: format!("{}/{}", arguments.parent, address),
+        }))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateSubnetOutput {
+    pub subnet_id: String,
+}
 
-        Ok(())
+impl Display for CreateSubnetOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "created subnet actor with id: {}", self.subnet_id)
     }
 }
 
@@ -176,4 +234,9 @@ pub struct CreateSubnetArgs {
         help = "The address of collateral source of a subnet on its parent subnet. None if kind is native"
     )]
     pub collateral_source_address: Option<String>,
+    #[arg(
+        long,
+        help = "Write the unsigned transaction to this file to sign it offline with `tx sign`, instead of sending it"
+    )]
+    pub unsigned_out: Option<String>,
 }
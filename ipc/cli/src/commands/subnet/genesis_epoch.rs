@@ -8,7 +8,7 @@ use ipc_api::subnet_id::SubnetID;
 use std::fmt::Debug;
 use std::str::FromStr;
 
-use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
+use crate::{get_ipc_provider, CommandLineHandler, EpochOutput, GlobalArguments};
 
 /// The command to get the genensis epoch.
 pub(crate) struct GenesisEpoch;
@@ -16,17 +16,20 @@ pub(crate) struct GenesisEpoch;
 #[async_trait]
 impl CommandLineHandler for GenesisEpoch {
     type Arguments = GenesisEpochArgs;
+    type Output = EpochOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("get genesis epoch with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
 
-        let ls = provider.genesis_epoch(&subnet).await?;
-        println!("genesis epoch: {}", ls);
+        let epoch = provider.genesis_epoch(&subnet).await?;
 
-        Ok(())
+        Ok(EpochOutput::new("genesis epoch", epoch))
     }
 }
 
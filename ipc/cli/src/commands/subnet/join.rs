@@ -2,15 +2,18 @@
 // SPDX-License-Identifier: MIT
 //! Join subnet cli command handler.
 
+use anyhow::bail;
 use async_trait::async_trait;
 use clap::Args;
 use ipc_api::subnet_id::SubnetID;
+use ipc_provider::offline::TxCall;
 use num_traits::Zero;
 use std::{fmt::Debug, str::FromStr};
 
+use crate::commands::tx::{write_unsigned_tx, TxOutput};
 use crate::{
     f64_to_token_amount, get_ipc_provider, require_fil_addr_from_str, CommandLineHandler,
-    GlobalArguments,
+    EpochOutput, GlobalArguments, NoOutput,
 };
 
 /// The command to join a subnet
@@ -19,8 +22,12 @@ pub struct JoinSubnet;
 #[async_trait]
 impl CommandLineHandler for JoinSubnet {
     type Arguments = JoinSubnetArgs;
+    type Output = TxOutput<EpochOutput>;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("join subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -29,6 +36,24 @@ impl CommandLineHandler for JoinSubnet {
             Some(address) => Some(require_fil_addr_from_str(address)?),
             None => None,
         };
+        if let Some(path) = &arguments.unsigned_out {
+            if arguments.initial_balance.filter(|x| !x.is_zero()).is_some() {
+                bail!(
+                    "--initial-balance cannot be combined with --unsigned-out; pre-fund separately"
+                );
+            }
+            let public_key = match &arguments.public_key {
+                Some(key) => Some(hex::decode(key.trim_start_matches("0x"))?),
+                None => None,
+            };
+            let call = TxCall::JoinSubnet {
+                subnet,
+                collateral: f64_to_token_amount(arguments.collateral)?,
+                public_key,
+            };
+            let output = write_unsigned_tx(&mut provider, from, call, path).await?;
+            return Ok(TxOutput::Unsigned(output));
+        }
         if let Some(initial_balance) = arguments.initial_balance.filter(|x| !x.is_zero()) {
             log::info!("pre-funding address with {initial_balance}");
             provider
@@ -38,9 +63,8 @@ impl CommandLineHandler for JoinSubnet {
         let epoch = provider
             .join_subnet(subnet, from, f64_to_token_amount(arguments.collateral)?)
             .await?;
-        println!("joined at epoch: {epoch}");
 
-        Ok(())
+        Ok(TxOutput::Sent(EpochOutput::new("joined at epoch", epoch)))
     }
 }
 
@@ -61,6 +85,17 @@ pub struct JoinSubnetArgs {
         help = "Optionally add an initial balance to the validator in genesis in the subnet"
     )]
     pub initial_balance: Option<f64>,
+    #[arg(
+        long,
+        help = "Write the unsigned transaction to this file to sign it offline with `tx sign`, instead of sending it"
+    )]
+    pub unsigned_out: Option<String>,
+    #[arg(
+        long,
+        requires = "unsigned_out",
+        help = "The hex encoded uncompressed public key of the validator, if its key is not in the local keystore"
+    )]
+    pub public_key: Option<String>,
 }
 
 /// The command to stake in a subnet from validator
@@ -69,8 +104,12 @@ pub struct StakeSubnet;
 #[async_trait]
 impl CommandLineHandler for StakeSubnet {
     type Arguments = StakeSubnetArgs;
+    type Output = TxOutput<NoOutput>;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("join subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -79,9 +118,15 @@ impl CommandLineHandler for StakeSubnet {
             Some(address) => Some(require_fil_addr_from_str(address)?),
             None => None,
         };
-        provider
-            .stake(subnet, from, f64_to_token_amount(arguments.collateral)?)
-            .await
+        let collateral = f64_to_token_amount(arguments.collateral)?;
+        if let Some(path) = &arguments.unsigned_out {
+            let call = TxCall::Stake { subnet, collateral };
+            let output = write_unsigned_tx(&mut provider, from, call, path).await?;
+            return Ok(TxOutput::Unsigned(output));
+        }
+        provider.stake(subnet, from, collateral).await?;
+
+        Ok(TxOutput::Sent(NoOutput {}))
     }
 }
 
@@ -97,6 +142,11 @@ pub struct StakeSubnetArgs {
         help = "The collateral to stake in the subnet (in whole FIL units)"
     )]
     pub collateral: f64,
+    #[arg(
+        long,
+        help = "Write the unsigned transaction to this file to sign it offline with `tx sign`, instead of sending it"
+    )]
+    pub unsigned_out: Option<String>,
 }
 
 /// The command to unstake in a subnet from validator
@@ -105,8 +155,12 @@ pub struct UnstakeSubnet;
 #[async_trait]
 impl CommandLineHandler for UnstakeSubnet {
     type Arguments = UnstakeSubnetArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("join subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -117,7 +171,9 @@ impl CommandLineHandler for UnstakeSubnet {
         };
         provider
             .unstake(subnet, from, f64_to_token_amount(arguments.collateral)?)
-            .await
+            .await?;
+
+        Ok(NoOutput {})
     }
 }
 
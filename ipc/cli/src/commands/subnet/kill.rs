@@ -7,7 +7,9 @@ use clap::Args;
 use ipc_api::subnet_id::SubnetID;
 use std::{fmt::Debug, str::FromStr};
 
-use crate::{get_ipc_provider, require_fil_addr_from_str, CommandLineHandler, GlobalArguments};
+use crate::{
+    get_ipc_provider, require_fil_addr_from_str, CommandLineHandler, GlobalArguments, NoOutput,
+};
 
 /// The command to kill an existing subnet.
 pub struct KillSubnet;
@@ -15,8 +17,12 @@ pub struct KillSubnet;
 #[async_trait]
 impl CommandLineHandler for KillSubnet {
     type Arguments = KillSubnetArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("kill subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -26,7 +32,9 @@ impl CommandLineHandler for KillSubnet {
             None => None,
         };
 
-        provider.kill_subnet(subnet, from).await
+        provider.kill_subnet(subnet, from).await?;
+
+        Ok(NoOutput {})
     }
 }
 
@@ -7,7 +7,9 @@ use clap::Args;
 use ipc_api::subnet_id::SubnetID;
 use std::{fmt::Debug, str::FromStr};
 
-use crate::{get_ipc_provider, require_fil_addr_from_str, CommandLineHandler, GlobalArguments};
+use crate::{
+    get_ipc_provider, require_fil_addr_from_str, CommandLineHandler, GlobalArguments, NoOutput,
+};
 
 /// The command to leave a new subnet.
 pub struct LeaveSubnet;
@@ -15,8 +17,12 @@ pub struct LeaveSubnet;
 #[async_trait]
 impl CommandLineHandler for LeaveSubnet {
     type Arguments = LeaveSubnetArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("leave subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -25,7 +31,9 @@ impl CommandLineHandler for LeaveSubnet {
             Some(address) => Some(require_fil_addr_from_str(address)?),
             None => None,
         };
-        provider.leave_subnet(subnet, from).await
+        provider.leave_subnet(subnet, from).await?;
+
+        Ok(NoOutput {})
     }
 }
 
@@ -44,8 +52,12 @@ pub struct Claim;
 #[async_trait]
 impl CommandLineHandler for Claim {
     type Arguments = ClaimArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("leave subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -54,7 +66,9 @@ impl CommandLineHandler for Claim {
             Some(address) => Some(require_fil_addr_from_str(address)?),
             None => None,
         };
-        provider.claim_collateral(subnet, from).await
+        provider.claim_collateral(subnet, from).await?;
+
+        Ok(NoOutput {})
     }
 }
 
@@ -4,8 +4,10 @@
 
 use async_trait::async_trait;
 use clap::Args;
+use fvm_shared::clock::ChainEpoch;
 use ipc_api::subnet_id::SubnetID;
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use crate::{get_ipc_provider, require_fil_addr_from_str, CommandLineHandler, GlobalArguments};
@@ -16,8 +18,12 @@ pub(crate) struct ListSubnets;
 #[async_trait]
 impl CommandLineHandler for ListSubnets {
     type Arguments = ListSubnetsArgs;
+    type Output = ListSubnetsOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("list subnets with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -30,13 +36,48 @@ impl CommandLineHandler for ListSubnets {
 
         let ls = provider.list_child_subnets(gateway_addr, &subnet).await?;
 
-        for (_, s) in ls.iter() {
-            println!(
+        let subnets = ls
+            .values()
+            .map(|s| SubnetSummary {
+                id: s.id.to_string(),
+                collateral: s.stake.to_string(),
+                circ_supply: s.circ_supply.to_string(),
+                genesis_epoch: s.genesis_epoch,
+            })
+            .collect();
+
+        Ok(ListSubnetsOutput { subnets })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SubnetSummary {
+    pub id: String,
+    /// Collateral in FIL.
+    pub collateral: String,
+    /// Circulating supply in FIL.
+    pub circ_supply: String,
+    pub genesis_epoch: ChainEpoch,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct ListSubnetsOutput {
+    pub subnets: Vec<SubnetSummary>,
+}
+
+impl Display for ListSubnetsOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, s) in self.subnets.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
                 "{} - collateral: {} FIL, circ.supply: {} FIL, genesis: {}",
-                s.id, s.stake, s.circ_supply, s.genesis_epoch
-            );
+                s.id, s.collateral, s.circ_supply, s.genesis_epoch
+            )?;
         }
-
         Ok(())
     }
 }
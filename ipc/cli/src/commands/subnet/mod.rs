@@ -14,7 +14,7 @@ use crate::commands::subnet::show_gateway_contract_commit_sha::{
     ShowGatewayContractCommitSha, ShowGatewayContractCommitShaArgs,
 };
 use crate::commands::subnet::validator::{ValidatorInfo, ValidatorInfoArgs};
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::{run, GlobalArguments};
 use clap::{Args, Subcommand};
 
 use self::bootstrap::{AddBootstrap, AddBootstrapArgs, ListBootstraps, ListBootstrapsArgs};
@@ -51,25 +51,25 @@ pub(crate) struct SubnetCommandsArgs {
 impl SubnetCommandsArgs {
     pub async fn handle(&self, global: &GlobalArguments) -> anyhow::Result<()> {
         match &self.command {
-            Commands::Create(args) => CreateSubnet::handle(global, args).await,
-            Commands::List(args) => ListSubnets::handle(global, args).await,
-            Commands::Join(args) => JoinSubnet::handle(global, args).await,
-            Commands::Rpc(args) => RPCSubnet::handle(global, args).await,
-            Commands::ChainId(args) => ChainIdSubnet::handle(global, args).await,
-            Commands::Leave(args) => LeaveSubnet::handle(global, args).await,
-            Commands::Kill(args) => KillSubnet::handle(global, args).await,
-            Commands::SendValue(args) => SendValue::handle(global, args).await,
-            Commands::Stake(args) => StakeSubnet::handle(global, args).await,
-            Commands::Unstake(args) => UnstakeSubnet::handle(global, args).await,
-            Commands::Claim(args) => Claim::handle(global, args).await,
-            Commands::AddBootstrap(args) => AddBootstrap::handle(global, args).await,
-            Commands::ListBootstraps(args) => ListBootstraps::handle(global, args).await,
-            Commands::GenesisEpoch(args) => GenesisEpoch::handle(global, args).await,
-            Commands::GetValidator(args) => ValidatorInfo::handle(global, args).await,
+            Commands::Create(args) => run::<CreateSubnet>(global, args).await,
+            Commands::List(args) => run::<ListSubnets>(global, args).await,
+            Commands::Join(args) => run::<JoinSubnet>(global, args).await,
+            Commands::Rpc(args) => run::<RPCSubnet>(global, args).await,
+            Commands::ChainId(args) => run::<ChainIdSubnet>(global, args).await,
+            Commands::Leave(args) => run::<LeaveSubnet>(global, args).await,
+            Commands::Kill(args) => run::<KillSubnet>(global, args).await,
+            Commands::SendValue(args) => run::<SendValue>(global, args).await,
+            Commands::Stake(args) => run::<StakeSubnet>(global, args).await,
+            Commands::Unstake(args) => run::<UnstakeSubnet>(global, args).await,
+            Commands::Claim(args) => run::<Claim>(global, args).await,
+            Commands::AddBootstrap(args) => run::<AddBootstrap>(global, args).await,
+            Commands::ListBootstraps(args) => run::<ListBootstraps>(global, args).await,
+            Commands::GenesisEpoch(args) => run::<GenesisEpoch>(global, args).await,
+            Commands::GetValidator(args) => run::<ValidatorInfo>(global, args).await,
             Commands::ShowGatewayContractCommitSha(args) => {
-                ShowGatewayContractCommitSha::handle(global, args).await
+                run::<ShowGatewayContractCommitSha>(global, args).await
             }
-            Commands::SetFederatedPower(args) => SetFederatedPower::handle(global, args).await,
+            Commands::SetFederatedPower(args) => run::<SetFederatedPower>(global, args).await,
         }
     }
 }
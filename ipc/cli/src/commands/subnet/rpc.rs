@@ -5,7 +5,8 @@
 use async_trait::async_trait;
 use clap::Args;
 use ipc_api::subnet_id::SubnetID;
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
@@ -16,8 +17,12 @@ pub struct RPCSubnet;
 #[async_trait]
 impl CommandLineHandler for RPCSubnet {
     type Arguments = RPCSubnetArgs;
+    type Output = RPCSubnetOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("get rpc for subnet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -27,9 +32,23 @@ impl CommandLineHandler for RPCSubnet {
             Some(conn) => conn,
         };
 
-        println!("rpc: {:?}", conn.subnet().rpc_http().to_string());
-        println!("chainID: {:?}", conn.manager().get_chain_id().await?);
-        Ok(())
+        Ok(RPCSubnetOutput {
+            rpc: conn.subnet().rpc_http().to_string(),
+            chain_id: conn.manager().get_chain_id().await?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RPCSubnetOutput {
+    pub rpc: String,
+    pub chain_id: String,
+}
+
+impl Display for RPCSubnetOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "rpc: {:?}", self.rpc)?;
+        write!(f, "chainID: {:?}", self.chain_id)
     }
 }
 
@@ -46,8 +65,12 @@ pub struct ChainIdSubnet;
 #[async_trait]
 impl CommandLineHandler for ChainIdSubnet {
     type Arguments = ChainIdSubnetArgs;
+    type Output = ChainIdOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("get chain-id for subnet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -57,8 +80,20 @@ impl CommandLineHandler for ChainIdSubnet {
             Some(conn) => conn,
         };
 
-        println!("{:}", conn.manager().get_chain_id().await?);
-        Ok(())
+        Ok(ChainIdOutput {
+            chain_id: conn.manager().get_chain_id().await?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChainIdOutput {
+    pub chain_id: String,
+}
+
+impl Display for ChainIdOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.chain_id)
     }
 }
 
@@ -9,7 +9,7 @@ use std::{fmt::Debug, str::FromStr};
 
 use crate::{
     f64_to_token_amount, get_ipc_provider, require_fil_addr_from_str, CommandLineHandler,
-    GlobalArguments,
+    GlobalArguments, NoOutput,
 };
 
 pub(crate) struct SendValue;
@@ -17,8 +17,12 @@ pub(crate) struct SendValue;
 #[async_trait]
 impl CommandLineHandler for SendValue {
     type Arguments = SendValueArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("send value in subnet with args: {:?}", arguments);
 
         let mut provider = get_ipc_provider(global)?;
@@ -35,7 +39,9 @@ impl CommandLineHandler for SendValue {
                 require_fil_addr_from_str(&arguments.to)?,
                 f64_to_token_amount(arguments.amount)?,
             )
-            .await
+            .await?;
+
+        Ok(NoOutput {})
     }
 }
 
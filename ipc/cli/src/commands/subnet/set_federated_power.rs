@@ -3,7 +3,7 @@
 //! Set federated power cli handler
 
 use crate::commands::{get_ipc_provider, require_fil_addr_from_str};
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::{CommandLineHandler, EpochOutput, GlobalArguments};
 use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::address::Address;
@@ -16,8 +16,12 @@ pub struct SetFederatedPower;
 #[async_trait]
 impl CommandLineHandler for crate::commands::subnet::SetFederatedPower {
     type Arguments = crate::commands::subnet::SetFederatedPowerArgs;
+    type Output = EpochOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("set federated power with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -46,9 +50,11 @@ impl CommandLineHandler for crate::commands::subnet::SetFederatedPower {
                 &arguments.validator_power,
             )
             .await?;
-        println!("New federated power is set at epoch {chain_epoch}");
 
-        Ok(())
+        Ok(EpochOutput::new(
+            "New federated power is set at epoch",
+            chain_epoch,
+        ))
     }
 }
 
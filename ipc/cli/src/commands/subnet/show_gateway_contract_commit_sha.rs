@@ -4,7 +4,8 @@
 use async_trait::async_trait;
 use clap::Args;
 use ipc_api::subnet_id::SubnetID;
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::from_utf8;
 use std::str::FromStr;
 
@@ -15,8 +16,12 @@ pub(crate) struct ShowGatewayContractCommitSha;
 #[async_trait]
 impl CommandLineHandler for ShowGatewayContractCommitSha {
     type Arguments = ShowGatewayContractCommitShaArgs;
+    type Output = CommitShaOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("show contract commit sha with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -25,12 +30,26 @@ impl CommandLineHandler for ShowGatewayContractCommitSha {
         let commit_sha = provider.get_commit_sha(&subnet).await?;
         let commit_sha_str = from_utf8(&commit_sha).unwrap();
 
-        println!(
-            "Using commit SHA {} for contracts in subnet {}",
-            commit_sha_str, subnet
-        );
+        Ok(CommitShaOutput {
+            commit_sha: commit_sha_str.to_string(),
+            subnet: subnet.to_string(),
+        })
+    }
+}
 
-        Ok(())
+#[derive(Debug, Serialize)]
+pub(crate) struct CommitShaOutput {
+    pub commit_sha: String,
+    pub subnet: String,
+}
+
+impl Display for CommitShaOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Using commit SHA {} for contracts in subnet {}",
+            self.commit_sha, self.subnet
+        )
     }
 }
 
@@ -8,7 +8,8 @@ use clap::Args;
 use fvm_shared::address::Address;
 use ipc_api::subnet_id::SubnetID;
 use ipc_types::EthAddress;
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 /// The command to get the validator information
@@ -17,8 +18,12 @@ pub(crate) struct ValidatorInfo;
 #[async_trait]
 impl CommandLineHandler for ValidatorInfo {
     type Arguments = ValidatorInfoArgs;
+    type Output = ValidatorInfoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("get validator info with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -29,10 +34,36 @@ impl CommandLineHandler for ValidatorInfo {
             .map(EthAddress::into)
             .or_else(|_| Address::from_str(&arguments.validator))?;
 
-        let validator_info = provider.get_validator_info(&subnet, &validator).await?;
-        println!("{}", validator_info);
+        let info = provider.get_validator_info(&subnet, &validator).await?;
 
-        Ok(())
+        Ok(ValidatorInfoOutput {
+            confirmed_collateral: info.staking.confirmed_collateral().to_string(),
+            total_collateral: info.staking.total_collateral().to_string(),
+            metadata: hex::encode(info.staking.metadata()),
+            is_active: info.is_active,
+            is_waiting: info.is_waiting,
+            text: info.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ValidatorInfoOutput {
+    /// Collateral amounts in FIL.
+    pub confirmed_collateral: String,
+    pub total_collateral: String,
+    /// Hex encoded validator metadata.
+    pub metadata: String,
+    pub is_active: bool,
+    pub is_waiting: bool,
+    /// The rendering of the validator info used in text mode.
+    #[serde(skip)]
+    pub text: String,
+}
+
+impl Display for ValidatorInfoOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
     }
 }
 
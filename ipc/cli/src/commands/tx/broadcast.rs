@@ -0,0 +1,81 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Broadcast transaction cli handler
+
+use async_trait::async_trait;
+use clap::Args;
+use fvm_shared::clock::ChainEpoch;
+use ipc_api::subnet_id::SubnetID;
+use ipc_provider::offline::SignedTransaction;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use super::read_json;
+use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
+
+/// The command to submit a transaction signed with `tx sign` and wait for its receipt.
+pub(crate) struct BroadcastTx;
+
+#[async_trait]
+impl CommandLineHandler for BroadcastTx {
+    type Arguments = BroadcastTxArgs;
+    type Output = BroadcastTxOutput;
+
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
+        log::debug!("broadcast transaction with args: {:?}", arguments);
+
+        let provider = get_ipc_provider(global)?;
+        let subnet = SubnetID::from_str(&arguments.subnet)?;
+        let signed: SignedTransaction = read_json(&arguments.input)?;
+
+        let receipt = provider.broadcast_transaction(&subnet, &signed).await?;
+
+        Ok(BroadcastTxOutput {
+            hash: format!("{:?}", signed.hash),
+            epoch: receipt.epoch,
+            subnet_id: receipt
+                .subnet_addr
+                .map(|addr| format!("{}/{}", subnet, addr)),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct BroadcastTxOutput {
+    pub hash: String,
+    /// The epoch the transaction was executed in.
+    pub epoch: ChainEpoch,
+    /// The ID of the subnet created by the transaction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_id: Option<String>,
+}
+
+impl Display for BroadcastTxOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "transaction {} executed in epoch: {}",
+            self.hash, self.epoch
+        )?;
+        if let Some(subnet_id) = &self.subnet_id {
+            write!(f, "\ncreated subnet actor with id: {subnet_id}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(about = "Broadcast a transaction signed with `tx sign` and wait for its receipt")]
+pub(crate) struct BroadcastTxArgs {
+    #[arg(
+        long,
+        help = "The subnet to send the transaction to, i.e. the parent of the subnet it acts on"
+    )]
+    pub subnet: String,
+    #[arg(long, help = "The file with the signed transaction")]
+    pub input: String,
+}
@@ -0,0 +1,113 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Commands to sign transactions offline and broadcast them, and the shared handling of the
+//! `--unsigned-out` option of the state-changing commands.
+
+use crate::{run, GlobalArguments};
+
+use clap::{Args, Subcommand};
+use fvm_shared::address::Address;
+use ipc_provider::offline::{TxCall, UnsignedTransaction};
+use ipc_provider::IpcProvider;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use self::broadcast::{BroadcastTx, BroadcastTxArgs};
+use self::sign::{SignTx, SignTxArgs};
+
+mod broadcast;
+mod sign;
+
+#[derive(Debug, Args)]
+#[command(name = "tx", about = "offline transaction signing and broadcast")]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct TxCommandsArgs {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+impl TxCommandsArgs {
+    pub async fn handle(&self, global: &GlobalArguments) -> anyhow::Result<()> {
+        match &self.command {
+            Commands::Sign(args) => run::<SignTx>(global, args).await,
+            Commands::Broadcast(args) => run::<BroadcastTx>(global, args).await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Commands {
+    Sign(SignTxArgs),
+    Broadcast(BroadcastTxArgs),
+}
+
+/// The result of commands which either send a transaction, or with `--unsigned-out` write
+/// it to a file to be signed offline.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TxOutput<T> {
+    Sent(T),
+    Unsigned(UnsignedTxOutput),
+}
+
+impl<T: Display> Display for TxOutput<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxOutput::Sent(output) => output.fmt(f),
+            TxOutput::Unsigned(output) => output.fmt(f),
+        }
+    }
+}
+
+/// An unsigned transaction written to a file.
+#[derive(Debug, Serialize)]
+pub struct UnsignedTxOutput {
+    pub file: String,
+    pub description: String,
+    /// The address which has to sign the transaction.
+    pub from: String,
+    pub nonce: Option<u64>,
+}
+
+impl Display for UnsignedTxOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unsigned transaction to {} from {} written to {:?}; sign it with `ipc-cli tx sign`",
+            self.description, self.from, self.file
+        )
+    }
+}
+
+/// Build the unsigned transaction of `call` and write it to `path`.
+pub(crate) async fn write_unsigned_tx(
+    provider: &mut IpcProvider,
+    from: Option<Address>,
+    call: TxCall,
+    path: &str,
+) -> anyhow::Result<UnsignedTxOutput> {
+    let tx = provider.unsigned_transaction(from, call).await?;
+    write_json(path, &tx)?;
+
+    Ok(UnsignedTxOutput {
+        file: path.to_string(),
+        description: tx.description.clone(),
+        from: format!("{:?}", tx.from()?),
+        nonce: tx.tx.nonce().map(|n| n.as_u64()),
+    })
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: impl AsRef<Path>) -> anyhow::Result<T> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn write_json<T: Serialize>(path: impl AsRef<Path>, value: &T) -> anyhow::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+fn read_unsigned_tx(path: impl AsRef<Path>) -> anyhow::Result<UnsignedTransaction> {
+    read_json(path)
+}
@@ -0,0 +1,70 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Sign transaction cli handler
+
+use async_trait::async_trait;
+use clap::Args;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
+
+use super::{read_unsigned_tx, write_json};
+use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
+
+/// The command to sign a transaction written with `--unsigned-out`, without connecting to
+/// any subnet.
+pub(crate) struct SignTx;
+
+#[async_trait]
+impl CommandLineHandler for SignTx {
+    type Arguments = SignTxArgs;
+    type Output = SignTxOutput;
+
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
+        log::debug!("sign transaction with args: {:?}", arguments);
+
+        let provider = get_ipc_provider(global)?;
+        let unsigned = read_unsigned_tx(&arguments.input)?;
+        log::info!("signing transaction to {}", unsigned.description);
+
+        let signed = provider.sign_transaction(&unsigned)?;
+        write_json(&arguments.output, &signed)?;
+
+        Ok(SignTxOutput {
+            file: arguments.output.clone(),
+            description: signed.description,
+            from: format!("{:?}", signed.from),
+            hash: format!("{:?}", signed.hash),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SignTxOutput {
+    pub file: String,
+    pub description: String,
+    pub from: String,
+    /// The hash the transaction will have on chain.
+    pub hash: String,
+}
+
+impl Display for SignTxOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "signed transaction {} to {} from {} written to {:?}",
+            self.hash, self.description, self.from, self.file
+        )
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(about = "Sign a transaction written with --unsigned-out using the local keystore")]
+pub(crate) struct SignTxArgs {
+    #[arg(long, help = "The file with the unsigned transaction")]
+    pub input: String,
+    #[arg(long, help = "The file to write the signed transaction to")]
+    pub output: String,
+}
@@ -6,22 +6,39 @@ use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::address::Address;
 use ipc_api::evm::payload_to_evm_address;
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use crate::{CommandLineHandler, GlobalArguments};
 
+#[derive(Debug, Serialize)]
+pub(crate) struct EthAddrOutput {
+    pub eth_address: String,
+}
+
+impl Display for EthAddrOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "eth address: {}", self.eth_address)
+    }
+}
+
 pub(crate) struct F4ToEthAddr;
 
 #[async_trait]
 impl CommandLineHandler for F4ToEthAddr {
     type Arguments = F4ToEthAddrArgs;
+    type Output = EthAddrOutput;
 
-    async fn handle(_global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        _global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         let addr = Address::from_str(&arguments.addr)?;
         let eth_addr = payload_to_evm_address(addr.payload())?;
-        log::info!("eth address: {:?}", eth_addr);
-        Ok(())
+        Ok(EthAddrOutput {
+            eth_address: format!("{:?}", eth_addr),
+        })
     }
 }
 
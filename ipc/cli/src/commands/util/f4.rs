@@ -6,21 +6,38 @@ use async_trait::async_trait;
 use clap::Args;
 use fvm_shared::address::Address;
 use ipc_types::EthAddress;
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use crate::{CommandLineHandler, GlobalArguments};
 
+#[derive(Debug, Serialize)]
+pub(crate) struct F4AddrOutput {
+    pub f4_address: String,
+}
+
+impl Display for F4AddrOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "f4 address: {}", self.f4_address)
+    }
+}
+
 pub(crate) struct EthToF4Addr;
 
 #[async_trait]
 impl CommandLineHandler for EthToF4Addr {
     type Arguments = EthToF4AddrArgs;
+    type Output = F4AddrOutput;
 
-    async fn handle(_global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        _global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         let eth_addr = EthAddress::from_str(&arguments.addr)?;
-        log::info!("f4 address: {}", Address::from(eth_addr));
-        Ok(())
+        Ok(F4AddrOutput {
+            f4_address: Address::from(eth_addr).to_string(),
+        })
     }
 }
 
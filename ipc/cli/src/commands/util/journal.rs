@@ -0,0 +1,68 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Journal analyzer util
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::Args;
+use ipc_observability::journal::{analyze, read_journal, JournalReport};
+
+use crate::{CommandLineHandler, GlobalArguments};
+
+pub(crate) struct AnalyzeJournal;
+
+#[async_trait]
+impl CommandLineHandler for AnalyzeJournal {
+    type Arguments = AnalyzeJournalArgs;
+    type Output = JournalReport;
+
+    async fn handle(
+        _global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
+        let mut events = Vec::new();
+
+        for (node, path) in arguments.journal.iter() {
+            let journal = read_journal(node, path)?;
+            if journal.skipped_lines > 0 {
+                log::warn!(
+                    "skipped {} lines which are not events in the journal of {node}",
+                    journal.skipped_lines
+                );
+            }
+            events.extend(journal.events);
+        }
+
+        Ok(analyze(&events, arguments.lag_threshold_ms))
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(
+    about = "Reconstruct the top-down finality and checkpointing timelines from the traces logs of several nodes"
+)]
+pub(crate) struct AnalyzeJournalArgs {
+    #[arg(
+        long,
+        required = true,
+        value_parser = parse_node_journal,
+        help = "Traces log of a node as <node>=<path>; the path can be a file or a directory of rolling traces.log files"
+    )]
+    pub journal: Vec<(String, PathBuf)>,
+    #[arg(
+        long,
+        default_value = "1000",
+        help = "Average delay in milliseconds to vote or sign above which a node is reported as lagging"
+    )]
+    pub lag_threshold_ms: i64,
+}
+
+fn parse_node_journal(s: &str) -> anyhow::Result<(String, PathBuf)> {
+    let (node, path) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected <node>=<path>, got {s}"))?;
+
+    Ok((node.to_string(), PathBuf::from(path)))
+}
@@ -1,14 +1,16 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: MIT
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::{run, GlobalArguments};
 
 use clap::{Args, Subcommand};
 
 use self::eth::{F4ToEthAddr, F4ToEthAddrArgs};
 use self::f4::{EthToF4Addr, EthToF4AddrArgs};
+use self::journal::{AnalyzeJournal, AnalyzeJournalArgs};
 
 mod eth;
 mod f4;
+mod journal;
 
 #[derive(Debug, Args)]
 #[command(name = "util", about = "util commands")]
@@ -21,8 +23,9 @@ pub(crate) struct UtilCommandsArgs {
 impl UtilCommandsArgs {
     pub async fn handle(&self, global: &GlobalArguments) -> anyhow::Result<()> {
         match &self.command {
-            Commands::EthToF4Addr(args) => EthToF4Addr::handle(global, args).await,
-            Commands::F4ToEthAddr(args) => F4ToEthAddr::handle(global, args).await,
+            Commands::EthToF4Addr(args) => run::<EthToF4Addr>(global, args).await,
+            Commands::F4ToEthAddr(args) => run::<F4ToEthAddr>(global, args).await,
+            Commands::AnalyzeJournal(args) => run::<AnalyzeJournal>(global, args).await,
         }
     }
 }
@@ -31,4 +34,5 @@ impl UtilCommandsArgs {
 pub(crate) enum Commands {
     EthToF4Addr(EthToF4AddrArgs),
     F4ToEthAddr(F4ToEthAddrArgs),
+    AnalyzeJournal(AnalyzeJournalArgs),
 }
@@ -5,11 +5,13 @@
 use async_trait::async_trait;
 use clap::Args;
 use futures_util::future::join_all;
-use fvm_shared::{address::Address, econ::TokenAmount};
+use fvm_shared::econ::TokenAmount;
 use ipc_api::ethers_address_to_fil_address;
 use ipc_api::subnet_id::SubnetID;
-use ipc_wallet::{EthKeyAddress, EvmKeyStore, WalletType};
-use std::{fmt::Debug, str::FromStr};
+use ipc_wallet::{EvmKeyStore, WalletType};
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
 
 use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
 
@@ -18,61 +20,43 @@ pub(crate) struct WalletBalances;
 #[async_trait]
 impl CommandLineHandler for WalletBalances {
     type Arguments = WalletBalancesArgs;
+    type Output = WalletBalancesOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("list wallets with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
 
         let wallet_type = WalletType::from_str(&arguments.wallet_type)?;
         let subnet = SubnetID::from_str(&arguments.subnet)?;
-        let mut errors = Vec::new();
-
-        match wallet_type {
+        // A failed lookup is reported next to the address, so the other balances are still shown.
+        let balances = match wallet_type {
             WalletType::Evm => {
                 let wallet = provider.evm_wallet()?;
                 let addresses = wallet.read().unwrap().list()?;
                 let r = addresses
                     .iter()
+                    .filter(|addr| addr.to_string() != "default-key")
                     .map(|addr| {
                         let provider = provider.clone();
                         let subnet = subnet.clone();
                         async move {
-                            provider
-                                .wallet_balance(
-                                    &subnet,
-                                    &ethers_address_to_fil_address(&(addr.clone()).into())?,
-                                )
-                                .await
-                                .map(|balance| (balance, addr))
+                            let balance =
+                                match ethers_address_to_fil_address(&(addr.clone()).into()) {
+                                    Ok(fil_addr) => {
+                                        provider.wallet_balance(&subnet, &fil_addr).await
+                                    }
+                                    Err(e) => Err(e),
+                                };
+                            WalletBalance::new(addr.to_string(), balance)
                         }
                     })
                     .collect::<Vec<_>>();
 
-                let v: Vec<anyhow::Result<(TokenAmount, &EthKeyAddress)>> = join_all(r).await;
-
-                for r in v.into_iter() {
-                    match r {
-                        Ok(i) => {
-                            let (balance, addr) = i;
-                            if addr.to_string() != "default-key" {
-                                println!("{} - Balance: {}", addr, balance);
-                            }
-                        }
-                        Err(e) => {
-                            errors.push(e);
-                        }
-                    }
-                }
-
-                if !errors.is_empty() {
-                    let error = errors
-                        .into_iter()
-                        .fold(anyhow::anyhow!("Error fetching balances"), |acc, err| {
-                            acc.context(err)
-                        });
-                    return Err(error);
-                }
+                join_all(r).await
             }
             WalletType::Fvm => {
                 let wallet = provider.fvm_wallet()?;
@@ -83,24 +67,70 @@ impl CommandLineHandler for WalletBalances {
                         let provider = provider.clone();
                         let subnet = subnet.clone();
                         async move {
-                            provider
-                                .wallet_balance(&subnet, addr)
-                                .await
-                                .map(|balance| (balance, addr))
+                            let balance = provider.wallet_balance(&subnet, addr).await;
+                            WalletBalance::new(addr.to_string(), balance)
                         }
                     })
                     .collect::<Vec<_>>();
 
-                let r = join_all(r)
-                    .await
-                    .into_iter()
-                    .collect::<anyhow::Result<Vec<(TokenAmount, &Address)>>>()?;
-                for (balance, addr) in r {
-                    println!("{:?} - Balance: {}", addr, balance);
-                }
+                join_all(r).await
             }
         };
 
+        Ok(WalletBalancesOutput { balances })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WalletBalance {
+    pub address: String,
+    /// Balance in FIL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<String>,
+    /// Why the balance could not be fetched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WalletBalance {
+    fn new(address: String, balance: anyhow::Result<TokenAmount>) -> Self {
+        match balance {
+            Ok(balance) => Self {
+                address,
+                balance: Some(balance.to_string()),
+                error: None,
+            },
+            Err(e) => Self {
+                address,
+                balance: None,
+                error: Some(format!("{e:#}")),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct WalletBalancesOutput {
+    pub balances: Vec<WalletBalance>,
+}
+
+impl Display for WalletBalancesOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, b) in self.balances.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match (&b.balance, &b.error) {
+                (Some(balance), _) => write!(f, "{} - Balance: {}", b.address, balance)?,
+                (None, error) => write!(
+                    f,
+                    "{} - Error: {}",
+                    b.address,
+                    error.as_deref().unwrap_or_default()
+                )?,
+            }
+        }
         Ok(())
     }
 }
@@ -113,3 +143,27 @@ pub(crate) struct WalletBalancesArgs {
     #[arg(long, help = "The type of the wallet, i.e. fvm, evm")]
     pub wallet_type: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_balance_is_reported_per_address() {
+        let output = WalletBalancesOutput {
+            balances: vec![
+                WalletBalance::new("t1a".into(), Ok(TokenAmount::from_whole(1))),
+                WalletBalance::new("t1b".into(), Err(anyhow::anyhow!("connection refused"))),
+            ],
+        };
+
+        assert_eq!(
+            output.to_string(),
+            "t1a - Balance: 1 FIL\nt1b - Error: connection refused"
+        );
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"[{"address":"t1a","balance":"1 FIL"},{"address":"t1b","error":"connection refused"}]"#
+        );
+    }
+}
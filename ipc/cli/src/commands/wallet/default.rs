@@ -8,15 +8,20 @@ use ipc_wallet::{EvmKeyStore, WalletType};
 use std::fmt::Debug;
 use std::str::FromStr;
 
-use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
+use super::WalletAddressOutput;
+use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments, NoOutput};
 
 pub(crate) struct WalletSetDefault;
 
 #[async_trait]
 impl CommandLineHandler for WalletSetDefault {
     type Arguments = WalletSetDefaultArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("remove wallet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -34,7 +39,7 @@ impl CommandLineHandler for WalletSetDefault {
                 wallet.write().unwrap().set_default(addr)?;
             }
         }
-        Ok(())
+        Ok(NoOutput {})
     }
 }
 
@@ -52,28 +57,30 @@ pub(crate) struct WalletGetDefault;
 #[async_trait]
 impl CommandLineHandler for WalletGetDefault {
     type Arguments = WalletGetDefaultArgs;
+    type Output = WalletAddressOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("remove wallet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let wallet_type = WalletType::from_str(&arguments.wallet_type)?;
 
-        match wallet_type {
+        let address = match wallet_type {
             WalletType::Evm => {
                 let wallet = provider.evm_wallet()?;
                 let mut wallet = wallet.write().unwrap();
-                match wallet.get_default()? {
-                    None => println!("No default account set"),
-                    Some(addr) => println!("{:?}", addr.to_string()),
-                }
+                wallet.get_default()?.map(|addr| addr.to_string())
             }
             WalletType::Fvm => {
                 let wallet = provider.fvm_wallet()?;
-                println!("{:?}", wallet.write().unwrap().get_default()?);
+                let addr = wallet.write().unwrap().get_default()?;
+                Some(addr.to_string())
             }
-        }
-        Ok(())
+        };
+        Ok(WalletAddressOutput { address })
     }
 }
 
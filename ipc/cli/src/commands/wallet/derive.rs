@@ -0,0 +1,54 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Wallet derive cli handler
+
+use async_trait::async_trait;
+use clap::Args;
+use ipc_wallet::WalletType;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use super::WalletAddressOutput;
+use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
+
+pub(crate) struct WalletDerive;
+
+#[async_trait]
+impl CommandLineHandler for WalletDerive {
+    type Arguments = WalletDeriveArgs;
+    type Output = WalletAddressOutput;
+
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
+        log::debug!("derive wallet with args: {:?}", arguments);
+
+        let provider = get_ipc_provider(global)?;
+        let wallet_type = WalletType::from_str(&arguments.wallet_type)?;
+
+        let path = match (&arguments.path, arguments.index) {
+            (Some(path), _) => Some(path.clone()),
+            (None, Some(index)) => Some(ipc_wallet::derivation_path(&wallet_type, index)),
+            (None, None) => None,
+        };
+
+        let address = provider.derive_key_from_seed(&wallet_type, path.as_deref())?;
+        Ok(WalletAddressOutput::new(address))
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(about = "Derive a new account from the HD seed stored in the wallet")]
+pub(crate) struct WalletDeriveArgs {
+    #[arg(long, help = "The type of the wallet, i.e. fvm, evm")]
+    pub wallet_type: String,
+    #[arg(
+        long,
+        conflicts_with = "path",
+        help = "Index of the account in the standard derivation path of the wallet type; defaults to the first one not in the wallet yet"
+    )]
+    pub index: Option<u32>,
+    #[arg(long, help = "Full BIP-32 derivation path of the account to derive")]
+    pub path: Option<String>,
+}
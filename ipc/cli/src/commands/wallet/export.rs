@@ -1,17 +1,19 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: MIT
 //! Wallet export cli handler
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use async_trait::async_trait;
 use base64::{prelude::BASE64_STANDARD, Engine};
 use clap::Args;
 use fvm_shared::address::Address;
 use ipc_provider::{lotus::message::wallet::WalletKeyType, IpcProvider, LotusJsonKeyType};
-use ipc_wallet::{EvmKeyStore, PersistentKeyInfo, WalletType};
-use std::fmt::Debug;
+use ipc_wallet::{EvmKeyStore, PersistentKeyInfo, WalletType, KEYSTORE_FILE_PASSPHRASE_ENV};
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::fs::Permissions;
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
@@ -44,6 +46,40 @@ impl WalletExport {
         Ok(serde_json::to_string(&info)?)
     }
 
+    /// Write the key as an encrypted v3 keystore file to the `--output` path.
+    fn export_evm_keystore(
+        provider: &IpcProvider,
+        arguments: &WalletExportArgs,
+    ) -> anyhow::Result<PathBuf> {
+        let keystore = provider.evm_wallet()?;
+        let address = ethers::types::Address::from_str(&arguments.address)?;
+
+        let key_info = keystore
+            .read()
+            .unwrap()
+            .get(&address.into())?
+            .ok_or_else(|| anyhow!("key does not exists"))?;
+
+        let output = Path::new(
+            arguments
+                .output
+                .as_ref()
+                .ok_or_else(|| anyhow!("--keystore requires --output"))?,
+        );
+        let dir = output.parent().unwrap_or_else(|| Path::new("."));
+        let name = output
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("invalid output file name: {output:?}"))?;
+
+        let passphrase = ipc_wallet::read_passphrase(
+            KEYSTORE_FILE_PASSPHRASE_ENV,
+            "Passphrase to encrypt the keystore file with",
+        )?;
+
+        ipc_wallet::encrypt_evm_key_file(dir, name, &key_info, &passphrase)
+    }
+
     fn export_fvm(provider: &IpcProvider, arguments: &WalletExportArgs) -> anyhow::Result<String> {
         let wallet = provider.fvm_wallet()?;
 
@@ -68,13 +104,30 @@ impl WalletExport {
 #[async_trait]
 impl CommandLineHandler for WalletExport {
     type Arguments = WalletExportArgs;
+    type Output = WalletExportOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("export wallet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
 
         let wallet_type = WalletType::from_str(&arguments.wallet_type)?;
+
+        if arguments.keystore {
+            if !matches!(wallet_type, WalletType::Evm) {
+                bail!("--keystore only supported by --wallet-type=evm");
+            }
+            let file = WalletExport::export_evm_keystore(&provider, arguments)?;
+            return Ok(WalletExportOutput {
+                address: arguments.address.clone(),
+                file: Some(file.to_string_lossy().to_string()),
+                key: None,
+            });
+        }
+
         let v = match wallet_type {
             WalletType::Evm => WalletExport::export_evm(&provider, arguments),
             WalletType::Fvm => WalletExport::export_fvm(&provider, arguments),
@@ -85,17 +138,43 @@ impl CommandLineHandler for WalletExport {
                 let mut file = std::fs::File::create(p)?;
                 file.set_permissions(Permissions::from_mode(0o600))?;
                 file.write_all(v.as_bytes())?;
-                println!(
-                    "exported new wallet with address {:?} in file {:?}",
-                    arguments.address, p
-                );
-            }
-            None => {
-                println!("{}", v);
+                Ok(WalletExportOutput {
+                    address: arguments.address.clone(),
+                    file: Some(p.clone()),
+                    key: None,
+                })
             }
+            None => Ok(WalletExportOutput {
+                address: arguments.address.clone(),
+                file: None,
+                key: Some(v),
+            }),
         }
+    }
+}
 
-        Ok(())
+#[derive(Debug, Serialize)]
+pub(crate) struct WalletExportOutput {
+    pub address: String,
+    /// The file the key was written to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The exported key, if it was not written to a file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+impl Display for WalletExportOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, &self.key) {
+            (Some(p), _) => write!(
+                f,
+                "exported new wallet with address {:?} in file {:?}",
+                self.address, p
+            ),
+            (None, Some(v)) => write!(f, "{v}"),
+            (None, None) => Ok(()),
+        }
     }
 }
 
@@ -118,6 +197,13 @@ pub(crate) struct WalletExportArgs {
     pub fendermint: bool,
     #[arg(long, help = "Export the hex encoded secret key")]
     pub hex: bool,
+    #[arg(
+        long,
+        requires = "output",
+        conflicts_with_all = ["fendermint", "hex"],
+        help = "Export the key as an encrypted Ethereum v3 keystore file (evm only); the passphrase is read from IPC_KEYSTORE_FILE_PASSPHRASE or prompted for"
+    )]
+    pub keystore: bool,
 }
 
 pub(crate) struct WalletPublicKey;
@@ -157,8 +243,12 @@ impl WalletPublicKey {
 #[async_trait]
 impl CommandLineHandler for WalletPublicKey {
     type Arguments = WalletPublicKeyArgs;
+    type Output = WalletPublicKeyOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("export wallet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -168,8 +258,19 @@ impl CommandLineHandler for WalletPublicKey {
             WalletType::Evm => WalletPublicKey::pubkey_evm(&provider, arguments),
             WalletType::Fvm => WalletPublicKey::pubkey_fvm(&provider, arguments),
         }?;
-        println!("{v}");
-        Ok(())
+        Ok(WalletPublicKeyOutput { pub_key: v })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WalletPublicKeyOutput {
+    /// Hex encoded uncompressed public key.
+    pub pub_key: String,
+}
+
+impl Display for WalletPublicKeyOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pub_key)
     }
 }
 
@@ -5,10 +5,11 @@
 use anyhow::bail;
 use async_trait::async_trait;
 use clap::{ArgGroup, Args};
-use ipc_wallet::WalletType;
+use ipc_wallet::{WalletType, KEYSTORE_FILE_PASSPHRASE_ENV};
 use std::fmt::Debug;
 use std::str::FromStr;
 
+use super::WalletAddressOutput;
 use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
 
 pub(crate) struct WalletImport;
@@ -16,22 +17,46 @@ pub(crate) struct WalletImport;
 #[async_trait]
 impl CommandLineHandler for WalletImport {
     type Arguments = WalletImportArgs;
+    type Output = WalletAddressOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("import wallet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
         let wallet_type = WalletType::from_str(&arguments.wallet_type)?;
 
-        if let Some(key) = &arguments.private_key {
+        if arguments.mnemonic {
+            let phrase = ipc_wallet::read_passphrase(ipc_wallet::MNEMONIC_ENV, "Mnemonic phrase")?;
+            let path = match &arguments.path {
+                Some(path) => path.clone(),
+                None => ipc_wallet::derivation_path(&wallet_type, 0),
+            };
+            let address = provider.import_key_from_mnemonic(&wallet_type, &phrase, &path)?;
+            if provider.import_hd_seed(&phrase)? {
+                log::info!("stored the mnemonic as the HD seed of the wallet");
+            } else {
+                log::info!("an HD seed is already stored in the wallet; keeping it");
+            }
+            Ok(WalletAddressOutput::new(address))
+        } else if let Some(key) = &arguments.private_key {
             if !matches!(wallet_type, WalletType::Evm) {
                 bail!("--private-key only supported by --wallet-type=evm");
             }
-            println!(
-                "{:?}",
-                provider.import_evm_key_from_privkey(key)?.to_string()
-            );
-            Ok(())
+            let key = provider.import_evm_key_from_privkey(key)?;
+            Ok(WalletAddressOutput::new(key))
+        } else if let Some(path) = &arguments.keystore_file {
+            if !matches!(wallet_type, WalletType::Evm) {
+                bail!("--keystore-file only supported by --wallet-type=evm");
+            }
+            let passphrase = ipc_wallet::read_passphrase(
+                KEYSTORE_FILE_PASSPHRASE_ENV,
+                "Passphrase of the keystore file",
+            )?;
+            let key = provider.import_evm_key_from_keystore_file(path, &passphrase)?;
+            Ok(WalletAddressOutput::new(key))
         } else {
             // Get keyinfo from file or stdin
             let keyinfo = if arguments.path.is_some() {
@@ -41,17 +66,17 @@ impl CommandLineHandler for WalletImport {
                 bail!("stdin not supported yet")
             };
 
-            match wallet_type {
-                WalletType::Fvm => println!("{:?}", provider.import_fvm_key(&keyinfo)?),
+            let address = match wallet_type {
+                WalletType::Fvm => provider.import_fvm_key(&keyinfo)?.to_string(),
                 WalletType::Evm => {
                     let key = provider
                         .import_evm_key_from_privkey(&keyinfo)
                         .or_else(|_| provider.import_evm_key_from_json(&keyinfo))?;
 
-                    println!("{:?}", key.to_string())
+                    key.to_string()
                 }
             };
-            Ok(())
+            Ok(WalletAddressOutput::new(address))
         }
     }
 }
@@ -60,8 +85,8 @@ impl CommandLineHandler for WalletImport {
 #[command(about = "Import a key into the agent's wallet")]
 #[clap(group(ArgGroup::new("key_source")
 .required(true)
-.multiple(false)
-.args(&["path", "private_key"]),
+.multiple(true)
+.args(&["path", "private_key", "keystore_file", "mnemonic"]),
 ))]
 pub(crate) struct WalletImportArgs {
     #[arg(long, help = "The type of the wallet, i.e. fvm, evm")]
@@ -69,13 +94,27 @@ pub(crate) struct WalletImportArgs {
     #[arg(
         long,
         group = "key_source",
-        help = "Path of key info file for the key to import"
+        help = "Path of key info file for the key to import, or with --mnemonic the BIP-32 derivation path (default m/44'/60'/0'/0/0 for evm, m/44'/461'/0'/0/0 for fvm)"
     )]
     pub path: Option<String>,
     #[arg(
         long,
         group = "key_source",
+        conflicts_with_all = ["path", "keystore_file", "mnemonic"],
         help = "The evm private key to import if path is not specified"
     )]
     pub private_key: Option<String>,
+    #[arg(
+        long,
+        group = "key_source",
+        conflicts_with_all = ["path", "mnemonic"],
+        help = "Path of an encrypted Ethereum v3 keystore file to import (evm only); the passphrase is read from IPC_KEYSTORE_FILE_PASSPHRASE or prompted for"
+    )]
+    pub keystore_file: Option<String>,
+    #[arg(
+        long,
+        group = "key_source",
+        help = "Derive the key from a BIP-39 mnemonic read from IPC_WALLET_MNEMONIC or prompted for, and store it as the HD seed of the wallet if it has none"
+    )]
+    pub mnemonic: bool,
 }
@@ -5,7 +5,8 @@
 use async_trait::async_trait;
 use clap::Args;
 use ipc_wallet::{EthKeyAddress, EvmKeyStore, WalletType};
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
@@ -15,10 +16,15 @@ pub(crate) struct WalletList;
 #[async_trait]
 impl CommandLineHandler for WalletList {
     type Arguments = WalletListArgs;
+    type Output = WalletListOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         let provider = get_ipc_provider(global)?;
         let wallet_type = WalletType::from_str(&arguments.wallet_type)?;
+        let mut keys = Vec::new();
         match wallet_type {
             WalletType::Evm => {
                 let wallet = provider.evm_wallet()?;
@@ -27,34 +33,67 @@ impl CommandLineHandler for WalletList {
                     if *address == EthKeyAddress::default() {
                         continue;
                     }
-                    print!("Address: {}", address);
-
                     let key_info = wallet.read().unwrap().get(address)?.unwrap();
                     let sk = libsecp256k1::SecretKey::parse_slice(key_info.private_key())?;
                     let pub_key =
                         hex::encode(libsecp256k1::PublicKey::from_secret_key(&sk).serialize())
                             .to_string();
-                    println!("\tPubKey: {}", pub_key);
+                    keys.push(WalletKey {
+                        address: address.to_string(),
+                        pub_key,
+                        key_type: None,
+                    });
                 }
-                Ok(())
             }
             WalletType::Fvm => {
                 let wallet = provider.fvm_wallet()?;
                 let addresses = wallet.read().unwrap().list_addrs()?;
                 for address in addresses.iter() {
-                    print!("Address: {}", address);
-
                     let key_info = wallet.write().unwrap().export(address)?;
                     let sk = libsecp256k1::SecretKey::parse_slice(key_info.private_key())?;
                     let pub_key =
                         hex::encode(libsecp256k1::PublicKey::from_secret_key(&sk).serialize())
                             .to_string();
-                    print!("\tPubKey: {}", pub_key);
-                    println!("\tKeyType: {:?}", key_info.key_type());
+                    keys.push(WalletKey {
+                        address: address.to_string(),
+                        pub_key,
+                        key_type: Some(format!("{:?}", key_info.key_type())),
+                    });
                 }
-                Ok(())
             }
         }
+        Ok(WalletListOutput { keys })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WalletKey {
+    pub address: String,
+    /// Hex encoded uncompressed public key.
+    pub pub_key: String,
+    /// Only reported for fvm wallets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct WalletListOutput {
+    pub keys: Vec<WalletKey>,
+}
+
+impl Display for WalletListOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, k) in self.keys.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "Address: {}\tPubKey: {}", k.address, k.pub_key)?;
+            if let Some(key_type) = &k.key_type {
+                write!(f, "\tKeyType: {}", key_type)?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -1,14 +1,17 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: MIT
-use crate::{CommandLineHandler, GlobalArguments};
+use crate::{run, GlobalArguments};
 
 use crate::commands::wallet::balances::{WalletBalances, WalletBalancesArgs};
 use crate::commands::wallet::new::{WalletNew, WalletNewArgs};
 use clap::{Args, Subcommand};
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
 
 use self::default::{
     WalletGetDefault, WalletGetDefaultArgs, WalletSetDefault, WalletSetDefaultArgs,
 };
+use self::derive::{WalletDerive, WalletDeriveArgs};
 use self::export::{WalletExport, WalletExportArgs, WalletPublicKey, WalletPublicKeyArgs};
 use self::import::{WalletImport, WalletImportArgs};
 use self::list::{WalletList, WalletListArgs};
@@ -16,6 +19,7 @@ use self::remove::{WalletRemove, WalletRemoveArgs};
 
 mod balances;
 mod default;
+mod derive;
 mod export;
 mod import;
 mod list;
@@ -33,15 +37,39 @@ pub(crate) struct WalletCommandsArgs {
 impl WalletCommandsArgs {
     pub async fn handle(&self, global: &GlobalArguments) -> anyhow::Result<()> {
         match &self.command {
-            Commands::New(args) => WalletNew::handle(global, args).await,
-            Commands::Balances(args) => WalletBalances::handle(global, args).await,
-            Commands::Import(args) => WalletImport::handle(global, args).await,
-            Commands::Export(args) => WalletExport::handle(global, args).await,
-            Commands::Remove(args) => WalletRemove::handle(global, args).await,
-            Commands::SetDefault(args) => WalletSetDefault::handle(global, args).await,
-            Commands::GetDefault(args) => WalletGetDefault::handle(global, args).await,
-            Commands::PubKey(args) => WalletPublicKey::handle(global, args).await,
-            Commands::List(args) => WalletList::handle(global, args).await,
+            Commands::New(args) => run::<WalletNew>(global, args).await,
+            Commands::Balances(args) => run::<WalletBalances>(global, args).await,
+            Commands::Import(args) => run::<WalletImport>(global, args).await,
+            Commands::Export(args) => run::<WalletExport>(global, args).await,
+            Commands::Remove(args) => run::<WalletRemove>(global, args).await,
+            Commands::SetDefault(args) => run::<WalletSetDefault>(global, args).await,
+            Commands::GetDefault(args) => run::<WalletGetDefault>(global, args).await,
+            Commands::PubKey(args) => run::<WalletPublicKey>(global, args).await,
+            Commands::List(args) => run::<WalletList>(global, args).await,
+            Commands::Derive(args) => run::<WalletDerive>(global, args).await,
+        }
+    }
+}
+
+/// The address of a key created, imported or looked up in a wallet.
+#[derive(Debug, Serialize)]
+pub(crate) struct WalletAddressOutput {
+    pub address: Option<String>,
+}
+
+impl WalletAddressOutput {
+    pub fn new(address: impl ToString) -> Self {
+        Self {
+            address: Some(address.to_string()),
+        }
+    }
+}
+
+impl Display for WalletAddressOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.address {
+            None => write!(f, "No default account set"),
+            Some(addr) => write!(f, "{:?}", addr),
         }
     }
 }
@@ -57,4 +85,5 @@ pub(crate) enum Commands {
     GetDefault(WalletGetDefaultArgs),
     PubKey(WalletPublicKeyArgs),
     List(WalletListArgs),
+    Derive(WalletDeriveArgs),
 }
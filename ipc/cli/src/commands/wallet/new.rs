@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: MIT
 //! Wallet new cli handler
 
+use anyhow::bail;
 use async_trait::async_trait;
 use clap::Args;
 use ipc_provider::lotus::message::wallet::WalletKeyType;
 use ipc_wallet::WalletType;
-use std::fmt::Debug;
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
 use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
@@ -16,17 +18,36 @@ pub(crate) struct WalletNew;
 #[async_trait]
 impl CommandLineHandler for WalletNew {
     type Arguments = WalletNewArgs;
+    type Output = WalletNewOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("create new wallet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
 
         let wallet_type = WalletType::from_str(&arguments.wallet_type)?;
-        match wallet_type {
-            WalletType::Evm => {
-                println!("{:?}", provider.new_evm_key()?.to_string());
+
+        if arguments.mnemonic {
+            if let WalletType::Fvm = wallet_type {
+                if let Some(key_type) = &arguments.key_type {
+                    if WalletKeyType::from_str(key_type)? != WalletKeyType::Secp256k1 {
+                        bail!("--mnemonic only supports secp256k1 keys");
+                    }
+                }
             }
+            let mnemonic = provider.new_hd_seed()?;
+            let address = provider.derive_key_from_seed(&wallet_type, None)?;
+            return Ok(WalletNewOutput {
+                address,
+                mnemonic: Some(mnemonic.to_string()),
+            });
+        }
+
+        let address = match wallet_type {
+            WalletType::Evm => provider.new_evm_key()?.to_string(),
             WalletType::Fvm => {
                 let tp = WalletKeyType::from_str(
                     &arguments
@@ -34,10 +55,34 @@ impl CommandLineHandler for WalletNew {
                         .clone()
                         .expect("fvm key type not specified"),
                 )?;
-                println!("{:?}", provider.new_fvm_key(tp)?)
+                provider.new_fvm_key(tp)?.to_string()
             }
         };
 
+        Ok(WalletNewOutput {
+            address,
+            mnemonic: None,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WalletNewOutput {
+    pub address: String,
+    /// The generated mnemonic, if the key was derived from a new HD seed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mnemonic: Option<String>,
+}
+
+impl Display for WalletNewOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.address)?;
+        if let Some(mnemonic) = &self.mnemonic {
+            write!(
+                f,
+                "\n\nmnemonic: {mnemonic}\n\nWrite down the mnemonic and keep it safe, it is the only way to recover the keys derived from it."
+            )?;
+        }
         Ok(())
     }
 }
@@ -52,4 +97,9 @@ pub(crate) struct WalletNewArgs {
     pub key_type: Option<String>,
     #[arg(long, help = "The type of the wallet, i.e. fvm, evm")]
     pub wallet_type: String,
+    #[arg(
+        long,
+        help = "Generate a new BIP-39 mnemonic, store it as the HD seed of the wallet and derive the first account from it"
+    )]
+    pub mnemonic: bool,
 }
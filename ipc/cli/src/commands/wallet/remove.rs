@@ -8,15 +8,19 @@ use ipc_wallet::{EvmKeyStore, WalletType};
 use std::fmt::Debug;
 use std::str::FromStr;
 
-use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments};
+use crate::{get_ipc_provider, CommandLineHandler, GlobalArguments, NoOutput};
 
 pub(crate) struct WalletRemove;
 
 #[async_trait]
 impl CommandLineHandler for WalletRemove {
     type Arguments = WalletRemoveArgs;
+    type Output = NoOutput;
 
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()> {
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output> {
         log::debug!("remove wallet with args: {:?}", arguments);
 
         let provider = get_ipc_provider(global)?;
@@ -34,7 +38,7 @@ impl CommandLineHandler for WalletRemove {
                 wallet.write().unwrap().remove(&addr)?;
             }
         }
-        Ok(())
+        Ok(NoOutput {})
     }
 }
 
@@ -7,9 +7,11 @@ use fvm_shared::address::Network;
 use num_traits::cast::FromPrimitive;
 
 mod commands;
+mod output;
 
 pub use commands::*;
 use ipc_provider::config::Config;
+pub use output::{EpochOutput, NoOutput, OutputFormat};
 
 /// The trait that represents the abstraction of a command line handler. To implement a new command
 /// line operation, implement this trait and register it.
@@ -25,8 +27,24 @@ pub trait CommandLineHandler {
     /// implementation to abstract away external crates. But this should be good for now.
     type Arguments: std::fmt::Debug + Args;
 
-    /// Handles the request with the provided arguments. Dev should handle the content to print and how
-    async fn handle(global: &GlobalArguments, arguments: &Self::Arguments) -> anyhow::Result<()>;
+    /// The result of the command. It is printed by the caller in the format chosen with the
+    /// global `--output` argument, so handlers should not print their results themselves.
+    type Output: serde::Serialize + std::fmt::Display + Send;
+
+    /// Handles the request with the provided arguments and returns the result to be rendered.
+    async fn handle(
+        global: &GlobalArguments,
+        arguments: &Self::Arguments,
+    ) -> anyhow::Result<Self::Output>;
+}
+
+/// Run a command handler and render its result in the format selected by the global arguments.
+pub(crate) async fn run<H: CommandLineHandler>(
+    global: &GlobalArguments,
+    arguments: &H::Arguments,
+) -> anyhow::Result<()> {
+    let output = H::handle(global, arguments).await?;
+    global.output().render(&output)
 }
 
 /// The global arguments that will be shared by all cli commands.
@@ -46,6 +64,10 @@ pub struct GlobalArguments {
     /// Legacy env var for network
     #[arg(long = "__network", hide = true, env = "NETWORK", value_parser = parse_network)]
     __network: Option<Network>,
+
+    /// Set the format in which command results and errors are printed.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, env = "IPC_CLI_OUTPUT")]
+    output: OutputFormat,
 }
 
 impl GlobalArguments {
@@ -63,6 +85,10 @@ impl GlobalArguments {
     pub fn network(&self) -> Network {
         self.__network.unwrap_or(self._network)
     }
+
+    pub fn output(&self) -> OutputFormat {
+        self.output
+    }
 }
 
 /// Parse the FVM network and set the global value.
@@ -1,18 +1,43 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: MIT
 
+use ipc_observability::config::OtlpLayerSettings;
+use ipc_observability::traces::global_otlp_layer;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, EnvFilter};
 
+/// Setting this to the traces endpoint of an OpenTelemetry collector exports the spans,
+/// e.g. to follow checkpoints submitted by the relayer into the parent subnet.
+const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+
 #[tokio::main]
 async fn main() {
+    let (otlp_layer, otlp_guard) = match std::env::var(OTLP_ENDPOINT_ENV) {
+        Ok(endpoint) => {
+            let settings = OtlpLayerSettings {
+                enabled: true,
+                endpoint: Some(endpoint),
+                service_name: Some("ipc-cli".into()),
+                ..Default::default()
+            };
+            let (layer, guard) = global_otlp_layer(&settings);
+            (Some(layer), Some(guard))
+        }
+        Err(_) => (None, None),
+    };
+
+    // Logs go to stderr so that stdout only carries the command output.
     tracing_subscriber::registry()
-        .with(fmt::layer())
+        .with(otlp_layer)
+        .with(fmt::layer().with_writer(std::io::stderr))
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
         .init();
 
+    let output = ipc_cli::output_format();
+
     if let Err(e) = ipc_cli::cli().await {
         log::error!("main process failed: {e:#}");
-        std::process::exit(1);
+        drop(otlp_guard);
+        std::process::exit(output.render_error(&e));
     }
 }
@@ -0,0 +1,177 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Rendering of command results and errors in the format selected with `--output`.
+
+use std::fmt::{Display, Formatter};
+
+use clap::ValueEnum;
+use fvm_shared::clock::ChainEpoch;
+use serde::Serialize;
+
+/// Exit code used when a command fails while being executed.
+pub const EXIT_CODE_FAILURE: i32 = 1;
+/// Exit code used when the command line arguments could not be parsed.
+/// This is the same exit code `clap` uses for its own usage errors.
+pub const EXIT_CODE_USAGE: i32 = 2;
+
+/// The format in which command results and errors are printed to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Text,
+    /// A single JSON document per invocation, meant to be consumed by scripts.
+    Json,
+}
+
+impl OutputFormat {
+    /// Print the result of a successful command to stdout.
+    ///
+    /// In text mode nothing is printed if the result renders as an empty string.
+    pub fn render<T: Serialize + Display>(&self, output: &T) -> anyhow::Result<()> {
+        match self {
+            OutputFormat::Text => {
+                let text = output.to_string();
+                if !text.is_empty() {
+                    println!("{text}");
+                }
+            }
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(output)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Print an error to stdout (in JSON mode) and return the exit code the process should use.
+    ///
+    /// In text mode the error is expected to have been logged already, so nothing is printed.
+    pub fn render_error(&self, error: &anyhow::Error) -> i32 {
+        let output = ErrorOutput::from(error);
+        if *self == OutputFormat::Json {
+            match serde_json::to_string_pretty(&output) {
+                Ok(json) => println!("{json}"),
+                Err(e) => log::error!("failed to serialize error output: {e}"),
+            }
+        }
+        output.error.code
+    }
+}
+
+/// Marker error for command line arguments that failed to parse, so that they are reported
+/// with [EXIT_CODE_USAGE] rather than as a command failure.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct UsageError(pub String);
+
+/// The JSON object printed when a command fails.
+///
+/// The shape of this object is part of the public interface of the CLI: fields may be added,
+/// but existing ones should not be renamed or removed.
+#[derive(Debug, Serialize)]
+pub struct ErrorOutput {
+    pub error: ErrorDetails,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorDetails {
+    /// The exit code the process terminates with.
+    pub code: i32,
+    /// Coarse classification of the error: `usage` or `command`.
+    pub kind: &'static str,
+    /// The outermost error message.
+    pub message: String,
+    /// The chain of underlying causes, outermost first, excluding `message`.
+    pub causes: Vec<String>,
+}
+
+impl From<&anyhow::Error> for ErrorOutput {
+    fn from(error: &anyhow::Error) -> Self {
+        let (code, kind) = if error.downcast_ref::<UsageError>().is_some() {
+            (EXIT_CODE_USAGE, "usage")
+        } else {
+            (EXIT_CODE_FAILURE, "command")
+        };
+
+        Self {
+            error: ErrorDetails {
+                code,
+                kind,
+                message: error.to_string(),
+                causes: error.chain().skip(1).map(|e| e.to_string()).collect(),
+            },
+        }
+    }
+}
+
+/// The result of commands which have nothing to report beyond their success.
+///
+/// Renders as an empty object in JSON, and as nothing at all in text mode.
+#[derive(Debug, Default, Serialize)]
+pub struct NoOutput {}
+
+impl Display for NoOutput {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+/// The result of commands which submit a transaction and report the epoch it was executed in.
+#[derive(Debug, Serialize)]
+pub struct EpochOutput {
+    /// Human readable description of what happened, only used in text mode.
+    #[serde(skip)]
+    pub message: &'static str,
+    pub epoch: ChainEpoch,
+}
+
+impl EpochOutput {
+    pub fn new(message: &'static str, epoch: ChainEpoch) -> Self {
+        Self { message, epoch }
+    }
+}
+
+impl Display for EpochOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.message, self.epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_error_output() {
+        let err = anyhow::Error::from(UsageError("unexpected argument".into()));
+        let output = ErrorOutput::from(&err);
+        assert_eq!(output.error.code, EXIT_CODE_USAGE);
+        assert_eq!(output.error.kind, "usage");
+        assert!(output.error.causes.is_empty());
+    }
+
+    #[test]
+    fn test_command_error_output() {
+        let err = anyhow::anyhow!("connection refused").context("error processing command");
+        let output = ErrorOutput::from(&err);
+        assert_eq!(output.error.code, EXIT_CODE_FAILURE);
+        assert_eq!(output.error.message, "error processing command");
+        assert_eq!(output.error.causes, vec!["connection refused".to_string()]);
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json["error"]["kind"], "command");
+    }
+
+    #[test]
+    fn test_epoch_output() {
+        let output = EpochOutput::new("joined at epoch", 10);
+        assert_eq!(output.to_string(), "joined at epoch: 10");
+        assert_eq!(serde_json::to_string(&output).unwrap(), r#"{"epoch":10}"#);
+    }
+
+    #[test]
+    fn test_no_output() {
+        assert_eq!(NoOutput {}.to_string(), "");
+        assert_eq!(serde_json::to_string(&NoOutput {}).unwrap(), "{}");
+    }
+}
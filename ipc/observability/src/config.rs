@@ -46,6 +46,7 @@ impl RotationKind {
 pub struct TracingSettings {
     pub console: Option<ConsoleLayerSettings>,
     pub file: Option<FileLayerSettings>,
+    pub otlp: Option<OtlpLayerSettings>,
 }
 
 #[serde_as]
@@ -65,3 +66,19 @@ pub struct FileLayerSettings {
     pub domain_filter: Option<Vec<String>>,
     pub events_filter: Option<Vec<String>>,
 }
+
+/// Export spans to an OpenTelemetry collector over OTLP/HTTP.
+#[serde_as]
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct OtlpLayerSettings {
+    pub enabled: bool,
+    pub level: Option<String>,
+    /// Full URL of the collector's traces endpoint, e.g. `http://localhost:4318/v1/traces`.
+    pub endpoint: Option<String>,
+    /// Name of the service the spans are reported under, e.g. `fendermint` or `eth-api`.
+    pub service_name: Option<String>,
+    /// Fraction of the traces to sample, between 0.0 and 1.0; all of them by default.
+    pub sample_ratio: Option<f64>,
+    /// Timeout of the export requests, in seconds.
+    pub timeout_secs: Option<u64>,
+}
@@ -0,0 +1,199 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// A structured event read back from the traces log of a node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEvent {
+    /// Name of the node whose log the event was read from.
+    pub node: String,
+    /// Time of the event in milliseconds since the UNIX epoch.
+    pub timestamp_ms: i64,
+    /// The domain the event was emitted under, e.g. `topdown`.
+    pub domain: Option<String>,
+    /// Name of the event type, e.g. `ParentFinalityAcquired`.
+    pub name: String,
+    /// The fields of the event, with `Some` unwrapped, `None` left out and strings unquoted.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl JournalEvent {
+    /// Parse a line written by the JSON file layer of the traces log.
+    ///
+    /// The events are recorded with their `Debug` format, e.g.
+    /// `ParentFinalityAcquired { source: "Exec", block_height: 10, block_hash: Some(0abc) }`
+    pub fn parse_line(node: &str, line: &str) -> anyhow::Result<Self> {
+        let json: serde_json::Value =
+            serde_json::from_str(line).context("failed to parse log line as JSON")?;
+
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| anyhow!("timestamp missing"))?;
+
+        let timestamp = OffsetDateTime::parse(timestamp, &Rfc3339)
+            .with_context(|| format!("invalid timestamp: {timestamp}"))?;
+
+        let fields = json
+            .get("fields")
+            .ok_or_else(|| anyhow!("fields missing"))?;
+
+        let event = fields
+            .get("event")
+            .and_then(|e| e.as_str())
+            .ok_or_else(|| anyhow!("event missing"))?;
+
+        let (name, fields_map) = parse_debug_struct(event)?;
+
+        Ok(Self {
+            node: node.to_string(),
+            timestamp_ms: (timestamp.unix_timestamp_nanos() / 1_000_000) as i64,
+            domain: fields
+                .get("domain")
+                .and_then(|d| d.as_str())
+                .map(|d| d.to_string()),
+            name,
+            fields: fields_map,
+        })
+    }
+
+    /// Look up a field.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(|f| f.as_str())
+    }
+
+    /// Look up a field and parse it as a number.
+    pub fn height(&self, name: &str) -> Option<u64> {
+        self.field(name).and_then(|h| h.parse().ok())
+    }
+}
+
+/// Parse the `Debug` format of a struct into its name and its top level fields.
+fn parse_debug_struct(s: &str) -> anyhow::Result<(String, BTreeMap<String, String>)> {
+    let s = s.trim();
+
+    let (name, body) = match s.find('{') {
+        None => return Ok((s.to_string(), BTreeMap::new())),
+        Some(idx) => (s[..idx].trim(), s[idx..].trim()),
+    };
+
+    let body = body
+        .strip_prefix('{')
+        .and_then(|b| b.strip_suffix('}'))
+        .ok_or_else(|| anyhow!("unbalanced braces in event: {s}"))?;
+
+    let mut fields = BTreeMap::new();
+    for field in split_top_level(body) {
+        let field = field.trim();
+        if field.is_empty() {
+            continue;
+        }
+        let (key, value) = field
+            .split_once(':')
+            .ok_or_else(|| anyhow!("invalid field in event: {field}"))?;
+
+        if let Some(value) = normalize_value(value) {
+            fields.insert(key.trim().to_string(), value);
+        }
+    }
+
+    Ok((name.to_string(), fields))
+}
+
+/// Split a string at the commas which are not nested in brackets or quotes.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (idx, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&s[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Unwrap options and quoted strings; returns `None` for `None`.
+fn normalize_value(value: &str) -> Option<String> {
+    let mut value = value.trim();
+    loop {
+        if value == "None" {
+            return None;
+        }
+        match value
+            .strip_prefix("Some(")
+            .and_then(|v| v.strip_suffix(')'))
+        {
+            Some(inner) => value = inner.trim(),
+            None => break,
+        }
+    }
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(quoted) => Some(quoted.replace("\\\"", "\"").replace("\\\\", "\\")),
+        None => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_debug_struct, JournalEvent};
+
+    #[test]
+    fn test_parse_debug_struct() {
+        let (name, fields) = parse_debug_struct(
+            r#"ParentFinalityAcquired { source: "Exec, \"quoted\"", is_null: false, block_height: 10, block_hash: Some(0a0b), commitment_hash: None, validator: PublicKey(Affine { x: 1, y: 2 }) }"#,
+        )
+        .unwrap();
+
+        assert_eq!(name, "ParentFinalityAcquired");
+        assert_eq!(fields["source"], "Exec, \"quoted\"");
+        assert_eq!(fields["is_null"], "false");
+        assert_eq!(fields["block_height"], "10");
+        assert_eq!(fields["block_hash"], "0a0b");
+        assert!(!fields.contains_key("commitment_hash"));
+        assert_eq!(fields["validator"], "PublicKey(Affine { x: 1, y: 2 })");
+
+        let (name, fields) = parse_debug_struct("Unit").unwrap();
+        assert_eq!(name, "Unit");
+        assert!(fields.is_empty());
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let line = r#"{"timestamp":"2024-07-10T12:34:56.789012Z","level":"INFO","fields":{"domain":"bottomup","event":"CheckpointCreated { height: 20, hash: 0102, msg_count: 0, config_number: 1 }"}}"#;
+
+        let event = JournalEvent::parse_line("node-0", line).unwrap();
+
+        assert_eq!(event.node, "node-0");
+        assert_eq!(event.timestamp_ms, 1720614896789);
+        assert_eq!(event.domain.as_deref(), Some("bottomup"));
+        assert_eq!(event.name, "CheckpointCreated");
+        assert_eq!(event.height("height"), Some(20));
+        assert_eq!(event.field("hash"), Some("0102"));
+    }
+}
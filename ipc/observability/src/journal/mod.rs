@@ -0,0 +1,79 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! Read back the structured events written to the traces log by the file layer,
+//! and reconstruct the timelines of the protocols across several nodes.
+
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+mod event;
+mod timeline;
+
+pub use event::JournalEvent;
+pub use timeline::{analyze, CheckpointTimeline, JournalReport, NodeLag, ParentFinalityTimeline};
+
+/// Suffix of the rolling files the traces are written to.
+const TRACES_FILE_SUFFIX: &str = "traces.log";
+
+/// Events read from the traces of a node.
+#[derive(Debug, Default)]
+pub struct NodeJournal {
+    pub events: Vec<JournalEvent>,
+    /// Number of lines which could not be parsed as events.
+    pub skipped_lines: usize,
+}
+
+/// Read the events of a node from a traces log file, or from all the
+/// rolling traces log files in a directory.
+pub fn read_journal(node: &str, path: &Path) -> anyhow::Result<NodeJournal> {
+    let mut journal = NodeJournal::default();
+
+    for file in journal_files(path)? {
+        let reader = std::fs::File::open(&file)
+            .map(BufReader::new)
+            .with_context(|| format!("failed to open {}", file.to_string_lossy()))?;
+
+        for line in reader.lines() {
+            let line =
+                line.with_context(|| format!("failed to read {}", file.to_string_lossy()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match JournalEvent::parse_line(node, &line) {
+                Ok(event) => journal.events.push(event),
+                Err(e) => {
+                    tracing::debug!(node, error = e.to_string(), "skipping journal line");
+                    journal.skipped_lines += 1;
+                }
+            }
+        }
+    }
+
+    journal.events.sort_by_key(|e| e.timestamp_ms);
+
+    Ok(journal)
+}
+
+fn journal_files(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = std::fs::read_dir(path)
+        .with_context(|| format!("failed to read directory {}", path.to_string_lossy()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.file_name()
+                    .map(|n| n.to_string_lossy().ends_with(TRACES_FILE_SUFFIX))
+                    .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    // The rolling appender prefixes the files with the date, so they sort chronologically.
+    files.sort();
+
+    Ok(files)
+}
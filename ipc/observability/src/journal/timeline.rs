@@ -0,0 +1,526 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use super::JournalEvent;
+
+/// The path of a parent block to finality in the subnet.
+///
+/// Timestamps are milliseconds since the UNIX epoch, delays are in milliseconds.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ParentFinalityTimeline {
+    pub height: u64,
+    pub block_hash: Option<String>,
+    /// When the block was first acquired by any of the nodes.
+    pub seen_ms: Option<i64>,
+    /// When a node first observed a quorum of votes.
+    pub quorum_ms: Option<i64>,
+    /// When the finality was first committed.
+    pub committed_ms: Option<i64>,
+    pub seen_to_quorum_ms: Option<i64>,
+    pub seen_to_committed_ms: Option<i64>,
+    /// Delay of the vote of each node since the block was first seen.
+    pub votes: BTreeMap<String, i64>,
+    /// Nodes which voted around this height, but not on this block.
+    pub missing_votes: Vec<String>,
+    /// Validators whose votes were received over gossip.
+    pub peer_votes: BTreeSet<String>,
+    /// Validators who voted on other heights, but whose vote on this one was never received.
+    pub missing_peer_votes: Vec<String>,
+}
+
+/// The path of a bottom-up checkpoint from its creation to its submission to the parent.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CheckpointTimeline {
+    pub height: u64,
+    pub hash: String,
+    /// When the checkpoint was first created by any of the nodes.
+    pub created_ms: Option<i64>,
+    /// When the checkpoint was first seen to be finalized with a quorum of signatures.
+    pub finalized_ms: Option<i64>,
+    /// When the checkpoint was first submitted to the parent by a relayer.
+    pub submitted_ms: Option<i64>,
+    pub created_to_finalized_ms: Option<i64>,
+    pub created_to_submitted_ms: Option<i64>,
+    /// Delay of the signature of each node since the checkpoint was created.
+    pub signatures: BTreeMap<String, i64>,
+    /// Nodes which signed checkpoints around this height, but not this one.
+    pub missing_signatures: Vec<String>,
+}
+
+/// How much a node lags behind the others in voting and signing.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct NodeLag {
+    pub node: String,
+    pub votes: usize,
+    pub missed_votes: usize,
+    pub avg_vote_delay_ms: Option<i64>,
+    pub max_vote_delay_ms: Option<i64>,
+    pub signatures: usize,
+    pub missed_signatures: usize,
+    pub avg_signature_delay_ms: Option<i64>,
+    pub max_signature_delay_ms: Option<i64>,
+    /// Set if the node missed a vote or signature, or its average delay is above the threshold.
+    pub lagging: bool,
+}
+
+/// Protocol timelines reconstructed from the journals of several nodes.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct JournalReport {
+    pub parent_finality: Vec<ParentFinalityTimeline>,
+    pub checkpoints: Vec<CheckpointTimeline>,
+    pub nodes: Vec<NodeLag>,
+}
+
+/// Join the events of all nodes by height and checkpoint hash.
+///
+/// A node is considered lagging if its average delay to vote or sign is above `lag_threshold_ms`.
+pub fn analyze<'a>(
+    events: impl IntoIterator<Item = &'a JournalEvent>,
+    lag_threshold_ms: i64,
+) -> JournalReport {
+    let mut parents = BTreeMap::<u64, ParentFinalityTimeline>::new();
+    let mut checkpoints = BTreeMap::<String, CheckpointTimeline>::new();
+    // Vote and signature timestamps of each node, to be turned into delays at the end.
+    let mut votes = BTreeMap::<u64, BTreeMap<String, i64>>::new();
+    let mut signatures = BTreeMap::<String, BTreeMap<String, i64>>::new();
+
+    for e in events {
+        let ts = e.timestamp_ms;
+        match e.name.as_str() {
+            "ParentFinalityAcquired" => {
+                if let Some(height) = e.height("block_height") {
+                    let p = parent(&mut parents, height);
+                    set_min(&mut p.seen_ms, ts);
+                    set_hash(&mut p.block_hash, e.field("block_hash"));
+                }
+            }
+            "ParentFinalityPeerVoteSent" => {
+                if let Some(height) = e.height("block_height") {
+                    set_hash(
+                        &mut parent(&mut parents, height).block_hash,
+                        e.field("block_hash"),
+                    );
+                    min_entry(votes.entry(height).or_default(), &e.node, ts);
+                }
+            }
+            "ParentFinalityPeerVoteReceived" => {
+                if let (Some(height), Some(validator)) =
+                    (e.height("block_height"), e.field("validator"))
+                {
+                    let p = parent(&mut parents, height);
+                    p.peer_votes.insert(validator.to_string());
+                }
+            }
+            "ParentFinalityPeerQuorumReached" => {
+                if let Some(height) = e.height("block_height") {
+                    let p = parent(&mut parents, height);
+                    set_min(&mut p.quorum_ms, ts);
+                    set_hash(&mut p.block_hash, e.field("block_hash"));
+                }
+            }
+            "ParentFinalityCommitted" => {
+                if let Some(height) = e.height("parent_height") {
+                    let p = parent(&mut parents, height);
+                    set_min(&mut p.committed_ms, ts);
+                    set_hash(&mut p.block_hash, e.field("block_hash"));
+                }
+            }
+            "CheckpointCreated" => {
+                if let Some(c) = checkpoint(&mut checkpoints, e) {
+                    set_min(&mut c.created_ms, ts);
+                }
+            }
+            "CheckpointSigned" => {
+                // Only the own signatures are attributed to the node.
+                if e.field("role") == Some("Own") {
+                    if let Some(c) = checkpoint(&mut checkpoints, e) {
+                        let hash = c.hash.clone();
+                        min_entry(signatures.entry(hash).or_default(), &e.node, ts);
+                    }
+                }
+            }
+            "CheckpointFinalized" => {
+                if let Some(c) = checkpoint(&mut checkpoints, e) {
+                    set_min(&mut c.finalized_ms, ts);
+                }
+            }
+            "CheckpointSubmitted" => {
+                if let Some(c) = checkpoint(&mut checkpoints, e) {
+                    set_min(&mut c.submitted_ms, ts);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Parent finality.
+    let voter_ranges = height_ranges(
+        votes
+            .iter()
+            .flat_map(|(h, vs)| vs.keys().map(move |n| (n.clone(), *h))),
+    );
+    let peer_voters = parents
+        .values()
+        .flat_map(|p| p.peer_votes.iter().cloned())
+        .collect::<BTreeSet<_>>();
+
+    for (height, p) in parents.iter_mut() {
+        let node_votes = votes.remove(height).unwrap_or_default();
+        let first_vote = node_votes.values().min().cloned();
+        let start = p.seen_ms.or(first_vote);
+
+        p.votes = node_votes
+            .into_iter()
+            .map(|(n, ts)| (n, start.map(|s| ts - s).unwrap_or_default()))
+            .collect();
+
+        p.seen_to_quorum_ms = diff(p.seen_ms, p.quorum_ms);
+        p.seen_to_committed_ms = diff(p.seen_ms, p.committed_ms);
+
+        // Only look for missing votes once the block is known to have been voted on.
+        if p.quorum_ms.is_some() || p.committed_ms.is_some() {
+            p.missing_votes = missing(&voter_ranges, *height, &p.votes);
+            if !p.peer_votes.is_empty() {
+                p.missing_peer_votes = peer_voters.difference(&p.peer_votes).cloned().collect();
+            }
+        }
+    }
+
+    // Checkpoints.
+    let signer_ranges = height_ranges(checkpoints.values().flat_map(|c| {
+        signatures
+            .get(&c.hash)
+            .into_iter()
+            .flat_map(|s| s.keys().map(|n| (n.clone(), c.height)))
+    }));
+
+    for c in checkpoints.values_mut() {
+        let node_signatures = signatures.remove(&c.hash).unwrap_or_default();
+        let first_signature = node_signatures.values().min().cloned();
+        let start = c.created_ms.or(first_signature);
+
+        c.signatures = node_signatures
+            .into_iter()
+            .map(|(n, ts)| (n, start.map(|s| ts - s).unwrap_or_default()))
+            .collect();
+
+        c.created_to_finalized_ms = diff(c.created_ms, c.finalized_ms);
+        c.created_to_submitted_ms = diff(c.created_ms, c.submitted_ms);
+        c.missing_signatures = missing(&signer_ranges, c.height, &c.signatures);
+    }
+
+    let mut checkpoints = checkpoints.into_values().collect::<Vec<_>>();
+    checkpoints.sort_by_key(|c| c.height);
+
+    let parent_finality = parents.into_values().collect::<Vec<_>>();
+    let nodes = node_lags(&parent_finality, &checkpoints, lag_threshold_ms);
+
+    JournalReport {
+        parent_finality,
+        checkpoints,
+        nodes,
+    }
+}
+
+fn node_lags(
+    parents: &[ParentFinalityTimeline],
+    checkpoints: &[CheckpointTimeline],
+    lag_threshold_ms: i64,
+) -> Vec<NodeLag> {
+    // The lag of each node with the vote and signature delays.
+    type Delays = (NodeLag, Vec<i64>, Vec<i64>);
+    let mut lags = BTreeMap::<String, Delays>::new();
+
+    fn lag<'a>(lags: &'a mut BTreeMap<String, Delays>, node: &str) -> &'a mut Delays {
+        lags.entry(node.to_string()).or_insert_with(|| {
+            let lag = NodeLag {
+                node: node.to_string(),
+                ..Default::default()
+            };
+            (lag, Vec::new(), Vec::new())
+        })
+    }
+
+    for p in parents {
+        for (node, delay) in p.votes.iter() {
+            lag(&mut lags, node).1.push(*delay);
+        }
+        for node in p.missing_votes.iter() {
+            lag(&mut lags, node).0.missed_votes += 1;
+        }
+    }
+    for c in checkpoints {
+        for (node, delay) in c.signatures.iter() {
+            lag(&mut lags, node).2.push(*delay);
+        }
+        for node in c.missing_signatures.iter() {
+            lag(&mut lags, node).0.missed_signatures += 1;
+        }
+    }
+
+    lags.into_values()
+        .map(|(mut lag, vote_delays, signature_delays)| {
+            lag.votes = vote_delays.len();
+            lag.avg_vote_delay_ms = avg(&vote_delays);
+            lag.max_vote_delay_ms = vote_delays.iter().max().cloned();
+            lag.signatures = signature_delays.len();
+            lag.avg_signature_delay_ms = avg(&signature_delays);
+            lag.max_signature_delay_ms = signature_delays.iter().max().cloned();
+            lag.lagging = lag.missed_votes > 0
+                || lag.missed_signatures > 0
+                || lag.avg_vote_delay_ms.unwrap_or_default() > lag_threshold_ms
+                || lag.avg_signature_delay_ms.unwrap_or_default() > lag_threshold_ms;
+            lag
+        })
+        .collect()
+}
+
+fn parent(
+    parents: &mut BTreeMap<u64, ParentFinalityTimeline>,
+    height: u64,
+) -> &mut ParentFinalityTimeline {
+    parents
+        .entry(height)
+        .or_insert_with(|| ParentFinalityTimeline {
+            height,
+            ..Default::default()
+        })
+}
+
+/// Checkpoints are joined by their hash.
+fn checkpoint<'a>(
+    checkpoints: &'a mut BTreeMap<String, CheckpointTimeline>,
+    e: &JournalEvent,
+) -> Option<&'a mut CheckpointTimeline> {
+    let height = e.height("height")?;
+    let hash = e.field("hash")?.to_string();
+    Some(
+        checkpoints
+            .entry(hash.clone())
+            .or_insert_with(|| CheckpointTimeline {
+                height,
+                hash,
+                ..Default::default()
+            }),
+    )
+}
+
+fn set_min(slot: &mut Option<i64>, ts: i64) {
+    *slot = Some(slot.map_or(ts, |s| s.min(ts)));
+}
+
+fn set_hash(slot: &mut Option<String>, hash: Option<&str>) {
+    if slot.is_none() {
+        *slot = hash.map(|h| h.to_string());
+    }
+}
+
+fn min_entry(map: &mut BTreeMap<String, i64>, node: &str, ts: i64) {
+    let entry = map.entry(node.to_string()).or_insert(ts);
+    *entry = (*entry).min(ts);
+}
+
+fn diff(from: Option<i64>, to: Option<i64>) -> Option<i64> {
+    Some(to? - from?)
+}
+
+fn avg(xs: &[i64]) -> Option<i64> {
+    if xs.is_empty() {
+        None
+    } else {
+        Some(xs.iter().sum::<i64>() / xs.len() as i64)
+    }
+}
+
+/// The lowest and highest heights each node participated at.
+fn height_ranges(items: impl Iterator<Item = (String, u64)>) -> BTreeMap<String, (u64, u64)> {
+    let mut ranges = BTreeMap::<String, (u64, u64)>::new();
+    for (node, height) in items {
+        let range = ranges.entry(node).or_insert((height, height));
+        range.0 = range.0.min(height);
+        range.1 = range.1.max(height);
+    }
+    ranges
+}
+
+/// Nodes which participated before and after a height, but not at it.
+///
+/// Nodes which only joined later, or whose logs end earlier, are not considered missing.
+fn missing(
+    ranges: &BTreeMap<String, (u64, u64)>,
+    height: u64,
+    present: &BTreeMap<String, i64>,
+) -> Vec<String> {
+    ranges
+        .iter()
+        .filter(|(node, (lo, hi))| *lo <= height && height <= *hi && !present.contains_key(*node))
+        .map(|(node, _)| node.clone())
+        .collect()
+}
+
+impl Display for JournalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |x: Option<i64>| x.map(|x| format!("{x}ms")).unwrap_or_else(|| "-".into());
+
+        writeln!(f, "parent finality:")?;
+        writeln!(
+            f,
+            "{:>10} {:>14} {:>16} {:>6}  missing votes",
+            "height", "seen->quorum", "seen->committed", "votes"
+        )?;
+        for p in self.parent_finality.iter() {
+            let mut missing = p.missing_votes.clone();
+            missing.extend(p.missing_peer_votes.iter().cloned());
+            writeln!(
+                f,
+                "{:>10} {:>14} {:>16} {:>6}  {}",
+                p.height,
+                ms(p.seen_to_quorum_ms),
+                ms(p.seen_to_committed_ms),
+                p.votes.len().max(p.peer_votes.len()),
+                missing.join(", ")
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "checkpoints:")?;
+        writeln!(
+            f,
+            "{:>10} {:>18} {:>19} {:>10}  missing signatures",
+            "height", "created->finalized", "created->submitted", "signatures"
+        )?;
+        for c in self.checkpoints.iter() {
+            writeln!(
+                f,
+                "{:>10} {:>18} {:>19} {:>10}  {}",
+                c.height,
+                ms(c.created_to_finalized_ms),
+                ms(c.created_to_submitted_ms),
+                c.signatures.len(),
+                c.missing_signatures.join(", ")
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "nodes:")?;
+        for n in self.nodes.iter() {
+            writeln!(
+                f,
+                "{}{}: votes {} (missed {}, avg {}, max {}), signatures {} (missed {}, avg {}, max {})",
+                n.node,
+                if n.lagging { " [LAGGING]" } else { "" },
+                n.votes,
+                n.missed_votes,
+                ms(n.avg_vote_delay_ms),
+                ms(n.max_vote_delay_ms),
+                n.signatures,
+                n.missed_signatures,
+                ms(n.avg_signature_delay_ms),
+                ms(n.max_signature_delay_ms),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::analyze;
+    use crate::journal::JournalEvent;
+
+    fn event(node: &str, ts: i64, name: &str, fields: &[(&str, &str)]) -> JournalEvent {
+        JournalEvent {
+            node: node.to_string(),
+            timestamp_ms: ts,
+            domain: None,
+            name: name.to_string(),
+            fields: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_parent_finality_timeline() {
+        let mut events = Vec::new();
+        for height in 10..=12i64 {
+            let h = height.to_string();
+            let t = |x: i64| height * 1000 + x;
+            let fields = [("block_height", h.as_str()), ("block_hash", "aa")];
+            events.push(event("n0", t(0), "ParentFinalityAcquired", &fields));
+            events.push(event("n1", t(50), "ParentFinalityAcquired", &fields));
+            events.push(event("n0", t(10), "ParentFinalityPeerVoteSent", &fields));
+            // n1 doesn't vote on the middle height.
+            if h != "11" {
+                events.push(event("n1", t(2000), "ParentFinalityPeerVoteSent", &fields));
+            }
+            events.push(event(
+                "n0",
+                t(2100),
+                "ParentFinalityPeerQuorumReached",
+                &fields,
+            ));
+            events.push(event(
+                "n0",
+                t(3000),
+                "ParentFinalityCommitted",
+                &[("parent_height", h.as_str()), ("block_hash", "aa")],
+            ));
+        }
+
+        let report = analyze(&events, 1000);
+
+        assert_eq!(report.parent_finality.len(), 3);
+        let p = &report.parent_finality[1];
+        assert_eq!(p.height, 11);
+        assert_eq!(p.block_hash.as_deref(), Some("aa"));
+        assert_eq!(p.seen_to_quorum_ms, Some(2100));
+        assert_eq!(p.seen_to_committed_ms, Some(3000));
+        assert_eq!(p.votes.get("n0"), Some(&10));
+        assert_eq!(p.missing_votes, vec!["n1".to_string()]);
+
+        let n0 = report.nodes.iter().find(|n| n.node == "n0").unwrap();
+        let n1 = report.nodes.iter().find(|n| n.node == "n1").unwrap();
+        assert!(!n0.lagging);
+        assert!(n1.lagging);
+        assert_eq!(n1.votes, 2);
+        assert_eq!(n1.missed_votes, 1);
+        assert_eq!(n1.avg_vote_delay_ms, Some(2000));
+    }
+
+    #[test]
+    fn test_checkpoint_timeline() {
+        let cp = [("height", "20"), ("hash", "0102")];
+        let signed = [("height", "20"), ("hash", "0102"), ("role", "Own")];
+        let events = vec![
+            event("n0", 1000, "CheckpointCreated", &cp),
+            event("n1", 1010, "CheckpointCreated", &cp),
+            event("n0", 1100, "CheckpointSigned", &signed),
+            event("n1", 1200, "CheckpointSigned", &signed),
+            event("n0", 3000, "CheckpointFinalized", &cp),
+            event("relayer", 5000, "CheckpointSubmitted", &cp),
+        ];
+
+        let report = analyze(&events, 1000);
+
+        assert_eq!(report.checkpoints.len(), 1);
+        let c = &report.checkpoints[0];
+        assert_eq!(c.height, 20);
+        assert_eq!(c.created_to_finalized_ms, Some(2000));
+        assert_eq!(c.created_to_submitted_ms, Some(4000));
+        assert_eq!(c.signatures.get("n1"), Some(&200));
+        assert!(c.missing_signatures.is_empty());
+
+        // The relayer doesn't vote or sign, so it's not among the nodes.
+        assert_eq!(report.nodes.len(), 2);
+        assert!(report.nodes.iter().all(|n| !n.lagging));
+    }
+}
@@ -6,7 +6,9 @@ pub mod traces;
 mod tracing_layers;
 pub use lazy_static::lazy_static;
 pub mod config;
+pub mod journal;
 pub mod observe;
+pub mod propagation;
 pub mod serde;
 
 use std::fmt::Debug;
@@ -0,0 +1,93 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Propagation of the W3C trace context in HTTP headers, so that spans of
+//! different processes handling the same request end up in the same trace.
+//!
+//! Nothing is propagated unless the OTLP exporter is enabled in the tracing settings.
+
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use opentelemetry::propagation::{Extractor, Injector};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Make the span a child of the remote span in the headers of an incoming request, if there is one.
+pub fn set_parent_from_headers(span: &tracing::Span, headers: &HeaderMap) {
+    let cx =
+        opentelemetry::global::get_text_map_propagator(|p| p.extract(&HeaderExtractor(headers)));
+    span.set_parent(cx);
+}
+
+/// Add the trace context of the current span to the headers of an outgoing request.
+pub fn inject_trace_context(headers: &mut HeaderMap) {
+    let cx = tracing::Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|p| {
+        p.inject_context(&cx, &mut HeaderInjector(headers))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::{inject_trace_context, set_parent_from_headers};
+
+    #[test]
+    fn test_trace_context_roundtrip() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let provider = TracerProvider::builder().build();
+        let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("test"));
+
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            let mut headers = HeaderMap::new();
+
+            let client_span = tracing::info_span!("client");
+            let trace_id = client_span.context().span().span_context().trace_id();
+            client_span.in_scope(|| inject_trace_context(&mut headers));
+
+            let traceparent = headers
+                .get("traceparent")
+                .expect("traceparent is injected")
+                .to_str()
+                .unwrap();
+            assert!(traceparent.contains(&trace_id.to_string()));
+
+            let server_span = tracing::info_span!("server");
+            set_parent_from_headers(&server_span, &headers);
+            assert_eq!(
+                server_span.context().span().span_context().trace_id(),
+                trace_id
+            );
+        });
+    }
+}
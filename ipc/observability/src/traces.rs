@@ -1,17 +1,52 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use crate::config::{FileLayerSettings, TracingSettings};
+use crate::config::{FileLayerSettings, OtlpLayerSettings, TracingSettings};
 use crate::tracing_layers::DomainEventFilterLayer;
+use anyhow::Context;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{self as sdktrace, Sampler};
+use opentelemetry_sdk::{runtime, Resource};
 use std::num::NonZeroUsize;
+use std::time::Duration;
 use tracing::Level;
 pub use tracing_appender::non_blocking;
 pub use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling::RollingFileAppender;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::{fmt, fmt::Subscriber, layer::SubscriberExt, EnvFilter, Layer, Registry};
 
 pub const TRACING_TARGET: &str = "tracing_event";
 
+/// Default OTLP/HTTP endpoint of a collector running on the same host.
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318/v1/traces";
+const DEFAULT_OTLP_SERVICE_NAME: &str = "ipc";
+const DEFAULT_OTLP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Keeps the file writers and the span exporter running until dropped.
+#[derive(Default)]
+pub struct TracingGuard {
+    _file_guards: Vec<WorkerGuard>,
+    tracer_provider: Option<sdktrace::TracerProvider>,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.tracer_provider.take() {
+            // Export whatever is left in the batch before the process exits.
+            for result in provider.force_flush() {
+                if let Err(e) = result {
+                    eprintln!("failed to flush spans: {e}");
+                }
+            }
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
 // Creates a temporary subscriber that logs all traces to stderr. Useful when global tracing is not set yet.
 pub fn create_temporary_subscriber() -> Subscriber {
     tracing_subscriber::FmtSubscriber::builder()
@@ -29,8 +64,10 @@ pub fn create_temporary_subscriber() -> Subscriber {
 // This subscriber bifurcates tracing events into two individual sinks: one for logs and one for
 // structured traces. We also set up the console sink, if requested by the configuration.
 //
+// Spans are also exported to an OpenTelemetry collector, if enabled.
+//
 // Returns a guard that can be used to drop the subscriber.
-pub fn set_global_tracing_subscriber(config: &TracingSettings) -> Vec<WorkerGuard> {
+pub fn set_global_tracing_subscriber(config: &TracingSettings) -> TracingGuard {
     let console_layer = {
         let filter: EnvFilter = config
             .console
@@ -120,8 +157,17 @@ pub fn set_global_tracing_subscriber(config: &TracingSettings) -> Vec<WorkerGuar
         (None, None, Vec::new())
     };
 
+    let (otlp_layer, mut guard) = match config.otlp.as_ref().filter(|s| s.enabled) {
+        Some(otlp_settings) => {
+            let (layer, guard) = global_otlp_layer(otlp_settings);
+            (Some(layer), guard)
+        }
+        None => (None, TracingGuard::default()),
+    };
+
     // Start with the base registry
     let subscriber = Registry::default()
+        .with(otlp_layer)
         .with(console_layer)
         .with(traces_layer)
         .with(logs_layer);
@@ -130,7 +176,91 @@ pub fn set_global_tracing_subscriber(config: &TracingSettings) -> Vec<WorkerGuar
     tracing::subscriber::set_global_default(subscriber)
         .expect("Unable to set global tracing subscriber");
 
-    guards
+    guard._file_guards = guards;
+    guard
+}
+
+/// Create a layer which exports spans to an OTLP collector, and make it the global tracer provider,
+/// along with the W3C trace context propagator, so traces can continue across processes.
+///
+/// The returned guard flushes the remaining spans when dropped.
+pub fn global_otlp_layer<S>(settings: &OtlpLayerSettings) -> (impl Layer<S>, TracingGuard)
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    let (layer, provider) = create_otlp_layer(settings).expect("failed to set up OTLP exporter");
+
+    opentelemetry::global::set_tracer_provider(provider.clone());
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let guard = TracingGuard {
+        _file_guards: Vec::new(),
+        tracer_provider: Some(provider),
+    };
+
+    (layer, guard)
+}
+
+/// Create a layer which exports spans, and the events in them, to an OTLP/HTTP collector in batches.
+///
+/// Must be called within a Tokio runtime, which runs the batches.
+pub fn create_otlp_layer<S>(
+    settings: &OtlpLayerSettings,
+) -> anyhow::Result<(impl Layer<S>, sdktrace::TracerProvider)>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    let endpoint = settings
+        .endpoint
+        .clone()
+        .unwrap_or_else(|| DEFAULT_OTLP_ENDPOINT.to_string());
+
+    let service_name = settings
+        .service_name
+        .clone()
+        .unwrap_or_else(|| DEFAULT_OTLP_SERVICE_NAME.to_string());
+
+    let timeout = settings
+        .timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_OTLP_TIMEOUT);
+
+    // Follow the sampling decision of the caller, so a trace isn't broken up between components.
+    let sampler = match settings.sample_ratio {
+        Some(ratio) if ratio < 1.0 => Sampler::TraceIdRatioBased(ratio),
+        _ => Sampler::AlwaysOn,
+    };
+
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(endpoint)
+        .with_timeout(timeout);
+
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(
+            sdktrace::config()
+                .with_sampler(Sampler::ParentBased(Box::new(sampler)))
+                .with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    service_name,
+                )])),
+        )
+        .install_batch(runtime::Tokio)
+        .context("failed to install OTLP pipeline")?;
+
+    let filter: EnvFilter = settings
+        .level
+        .clone()
+        .unwrap_or_else(|| "info".to_string())
+        .into();
+
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer("ipc"))
+        .with_filter(filter);
+
+    Ok((layer, provider))
 }
 
 fn create_file_appender(settings: &FileLayerSettings, suffix: &str) -> RollingFileAppender {
@@ -159,3 +289,73 @@ fn create_file_appender(settings: &FileLayerSettings, suffix: &str) -> RollingFi
         .build(directory)
         .expect("failed to create traces appender")
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::create_otlp_layer;
+    use crate::config::OtlpLayerSettings;
+
+    /// Run a stand-in for an OTLP collector which reports the request line of every request.
+    async fn run_collector() -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 64 * 1024];
+                    let n = socket.read(&mut buf).await.unwrap_or_default();
+                    let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                    let _ = tx.send(request.lines().next().unwrap_or_default().to_string());
+                    let _ = socket
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                        .await;
+                });
+            }
+        });
+
+        (endpoint, rx)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_otlp_export() {
+        let (endpoint, mut requests) = run_collector().await;
+
+        let settings = OtlpLayerSettings {
+            enabled: true,
+            level: Some("info".into()),
+            endpoint: Some(endpoint),
+            service_name: Some("test".into()),
+            sample_ratio: None,
+            timeout_secs: Some(5),
+        };
+
+        let (layer, provider) = create_otlp_layer(&settings).expect("failed to create layer");
+
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            let span = tracing::info_span!("test_span");
+            let _guard = span.enter();
+            tracing::info!("inside the span");
+        });
+
+        // Flushing blocks until the batch has been exported.
+        for result in provider.force_flush() {
+            result.expect("failed to export spans");
+        }
+
+        let request = tokio::time::timeout(Duration::from_secs(5), requests.recv())
+            .await
+            .expect("collector received no request")
+            .expect("collector stopped");
+
+        assert_eq!(request, "POST /v1/traces HTTP/1.1");
+    }
+}
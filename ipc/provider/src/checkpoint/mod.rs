@@ -16,7 +16,11 @@ use std::cmp::max;
 use std::fmt::{Display, Formatter};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
+use submission::{DirectSubmission, SubmissionStrategy};
 use tokio::sync::Semaphore;
+use tracing::Instrument;
+
+pub mod submission;
 
 /// Tracks the config required for bottom up checkpoint submissions
 /// parent/child subnet and checkpoint period.
@@ -36,9 +40,11 @@ pub struct BottomUpCheckpointManager<T> {
     /// The number of blocks away from the chain head that is considered final
     finalization_blocks: ChainEpoch,
     submission_semaphore: Arc<Semaphore>,
+    /// How checkpoints get into the parent, directly from the submitter by default.
+    submission_strategy: Arc<dyn SubmissionStrategy<T>>,
 }
 
-impl<T: BottomUpCheckpointRelayer> BottomUpCheckpointManager<T> {
+impl<T: BottomUpCheckpointRelayer + 'static> BottomUpCheckpointManager<T> {
     pub async fn new(
         parent: Subnet,
         child: Subnet,
@@ -60,6 +66,7 @@ impl<T: BottomUpCheckpointRelayer> BottomUpCheckpointManager<T> {
             child_handler,
             finalization_blocks: 0,
             submission_semaphore: Arc::new(Semaphore::new(max_parallelism)),
+            submission_strategy: Arc::new(DirectSubmission),
         })
     }
 
@@ -67,6 +74,14 @@ impl<T: BottomUpCheckpointRelayer> BottomUpCheckpointManager<T> {
         self.finalization_blocks = finalization_blocks;
         self
     }
+
+    pub fn with_submission_strategy(
+        mut self,
+        submission_strategy: Arc<dyn SubmissionStrategy<T>>,
+    ) -> Self {
+        self.submission_strategy = submission_strategy;
+        self
+    }
 }
 
 impl BottomUpCheckpointManager<EthSubnetManager> {
@@ -119,7 +134,10 @@ impl<T: BottomUpCheckpointRelayer + Send + Sync + 'static> BottomUpCheckpointMan
 
     /// Run the bottom up checkpoint submission daemon in the foreground
     pub async fn run(self, submitter: Address, submission_interval: Duration) {
-        tracing::info!("launching {self} for {submitter}");
+        tracing::info!(
+            "launching {self} for {submitter} with {}",
+            self.submission_strategy
+        );
 
         loop {
             if let Err(e) = self.submit_next_epoch(submitter).await {
@@ -130,6 +148,7 @@ impl<T: BottomUpCheckpointRelayer + Send + Sync + 'static> BottomUpCheckpointMan
     }
 
     /// Checks if the relayer has already submitted at the next submission epoch, if not it submits it.
+    #[tracing::instrument(skip(self), fields(subnet = %self.metadata.child.id))]
     async fn submit_next_epoch(&self, submitter: Address) -> Result<()> {
         let last_checkpoint_epoch = self
             .parent_handler
@@ -194,34 +213,42 @@ impl<T: BottomUpCheckpointRelayer + Send + Sync + 'static> BottomUpCheckpointMan
                 // We need to acquire a permit (from a limited permit pool) before submitting a checkpoint.
                 // We may wait here until a permit is available.
                 let parent_handler_clone = Arc::clone(&self.parent_handler);
+                let submission_strategy = Arc::clone(&self.submission_strategy);
                 let submission_permit = self
                     .submission_semaphore
                     .clone()
                     .acquire_owned()
                     .await
                     .unwrap();
-                all_submit_tasks.push(tokio::task::spawn(async move {
-                    let height = event.height;
-                    let hash = bundle.checkpoint.block_hash.clone();
-
-                    let result =
-                        Self::submit_checkpoint(parent_handler_clone, submitter, bundle, event)
-                            .await
-                            .inspect(|_| {
-                                emit(CheckpointSubmitted {
-                                    height,
-                                    hash: HexEncodableBlockHash(hash),
-                                });
-                            })
-                            .inspect_err(|err| {
-                                tracing::error!(
-                                    "Fail to submit checkpoint at height {height}: {err}"
-                                );
+                let span = tracing::info_span!("submit_checkpoint", height = event.height);
+                all_submit_tasks.push(tokio::task::spawn(
+                    async move {
+                        let height = event.height;
+                        let hash = bundle.checkpoint.block_hash.clone();
+
+                        let result = Self::submit_checkpoint(
+                            parent_handler_clone,
+                            submission_strategy,
+                            submitter,
+                            bundle,
+                            event,
+                        )
+                        .await
+                        .inspect(|_| {
+                            emit(CheckpointSubmitted {
+                                height,
+                                hash: HexEncodableBlockHash(hash),
                             });
+                        })
+                        .inspect_err(|err| {
+                            tracing::error!("Fail to submit checkpoint at height {height}: {err}");
+                        });
 
-                    drop(submission_permit);
-                    result
-                }));
+                        drop(submission_permit);
+                        result
+                    }
+                    .instrument(span),
+                ));
 
                 count += 1;
                 tracing::debug!("This round has asynchronously submitted {count} checkpoints",);
@@ -237,17 +264,13 @@ impl<T: BottomUpCheckpointRelayer + Send + Sync + 'static> BottomUpCheckpointMan
 
     async fn submit_checkpoint(
         parent_handler: Arc<T>,
+        submission_strategy: Arc<dyn SubmissionStrategy<T>>,
         submitter: Address,
         bundle: BottomUpCheckpointBundle,
         event: QuorumReachedEvent,
     ) -> Result<(), anyhow::Error> {
-        let epoch = parent_handler
-            .submit_checkpoint(
-                &submitter,
-                bundle.checkpoint,
-                bundle.signatures,
-                bundle.signatories,
-            )
+        let epoch = submission_strategy
+            .submit(&parent_handler, &submitter, bundle)
             .await
             .map_err(|e| {
                 anyhow!(
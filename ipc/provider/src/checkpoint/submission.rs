@@ -0,0 +1,975 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Strategies to get bottom-up checkpoints executed in the parent: either sent directly by the
+//! account of the relayer, or wrapped in a call to a contract wallet, like a Safe multisig or a
+//! relayer contract refunding the fees, which the subnet actor then sees as the submitter.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use ethers::abi::{AbiDecode, AbiEncode, Token};
+use ethers::contract::abigen;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Bytes, TransactionReceipt, H256, U256};
+use ethers::utils::keccak256;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use ipc_api::checkpoint::{BottomUpCheckpoint, BottomUpCheckpointBundle, Signature};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::manager::BottomUpCheckpointRelayer;
+
+pub type EthAddress = ethers::types::Address;
+type EthSignature = ethers::types::Signature;
+
+abigen!(
+    SafeWallet,
+    r#"[
+        function nonce() external view returns (uint256)
+        function getThreshold() external view returns (uint256)
+        function getOwners() external view returns (address[])
+        function execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures) external payable returns (bool)
+        event ExecutionSuccess(bytes32 txHash, uint256 payment)
+        event ExecutionFailure(bytes32 txHash, uint256 payment)
+    ]"#,
+);
+
+abigen!(
+    ContractWallet,
+    r#"[
+        function execute(address to, uint256 value, bytes data) external payable returns (bytes)
+    ]"#,
+);
+
+/// The EIP-712 domain of a Safe, which binds signatures to the chain and the Safe itself.
+const SAFE_DOMAIN_TYPE: &str = "EIP712Domain(uint256 chainId,address verifyingContract)";
+/// The EIP-712 type of the transactions a Safe executes.
+const SAFE_TX_TYPE: &str = "SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)";
+
+/// Access to the parent needed to go through a contract wallet, on top of what the relayer
+/// needs for direct submissions.
+#[async_trait]
+pub trait ContractWalletClient: BottomUpCheckpointRelayer {
+    /// The chain ID of the parent, which Safe transaction hashes commit to.
+    fn chain_id(&self) -> u64;
+
+    /// The `submitCheckpoint` call to the subnet actor, without sending it.
+    /// Returns the address of the subnet actor and the ABI encoded call.
+    fn checkpoint_submission_call(
+        &self,
+        checkpoint: BottomUpCheckpoint,
+        signatures: Vec<Signature>,
+        signatories: Vec<Address>,
+    ) -> Result<(EthAddress, Bytes)>;
+
+    /// Execute a read-only call to a contract on the latest state.
+    async fn call_contract(&self, to: EthAddress, data: Bytes) -> Result<Bytes>;
+
+    /// Send a call to a contract from `from` and wait for it to be executed.
+    /// Fails if the transaction reverted.
+    async fn send_contract_call(
+        &self,
+        from: &Address,
+        to: EthAddress,
+        data: Bytes,
+    ) -> Result<TransactionReceipt>;
+}
+
+/// How the relayer gets a checkpoint executed in the parent.
+#[async_trait]
+pub trait SubmissionStrategy<T>: Display + Send + Sync {
+    /// Submit the checkpoint with the account of `submitter` paying for the transaction.
+    /// Returns the epoch the checkpoint was executed in.
+    async fn submit(
+        &self,
+        parent: &T,
+        submitter: &Address,
+        bundle: BottomUpCheckpointBundle,
+    ) -> Result<ChainEpoch>;
+}
+
+/// Submit checkpoints from the account of the relayer.
+pub struct DirectSubmission;
+
+impl Display for DirectSubmission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "direct submission")
+    }
+}
+
+#[async_trait]
+impl<T: BottomUpCheckpointRelayer> SubmissionStrategy<T> for DirectSubmission {
+    async fn submit(
+        &self,
+        parent: &T,
+        submitter: &Address,
+        bundle: BottomUpCheckpointBundle,
+    ) -> Result<ChainEpoch> {
+        parent
+            .submit_checkpoint(
+                submitter,
+                bundle.checkpoint,
+                bundle.signatures,
+                bundle.signatories,
+            )
+            .await
+    }
+}
+
+/// Submit checkpoints through a contract wallet with an `execute(address,uint256,bytes)`
+/// entry point, such as a relayer contract which refunds the fees of the sender.
+pub struct ContractWalletSubmission {
+    wallet: EthAddress,
+}
+
+impl ContractWalletSubmission {
+    pub fn new(wallet: EthAddress) -> Self {
+        Self { wallet }
+    }
+}
+
+impl Display for ContractWalletSubmission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "submission through contract wallet {:?}", self.wallet)
+    }
+}
+
+#[async_trait]
+impl<T: ContractWalletClient> SubmissionStrategy<T> for ContractWalletSubmission {
+    async fn submit(
+        &self,
+        parent: &T,
+        submitter: &Address,
+        bundle: BottomUpCheckpointBundle,
+    ) -> Result<ChainEpoch> {
+        let (to, data) = parent.checkpoint_submission_call(
+            bundle.checkpoint,
+            bundle.signatures,
+            bundle.signatories,
+        )?;
+
+        let call = ExecuteCall {
+            to,
+            value: U256::zero(),
+            data,
+        };
+
+        let receipt = parent
+            .send_contract_call(submitter, self.wallet, call.encode().into())
+            .await?;
+
+        epoch_from_receipt(&receipt)
+    }
+}
+
+/// Submit checkpoints through a Safe multisig: the relayer proposes the transaction, collects
+/// the signatures of the owners, and executes it once the threshold of the Safe is reached.
+///
+/// Owners sign with keys on this machine, or, if a proposal queue is configured, by adding
+/// their signatures to the proposals the relayer writes there; in the latter case a
+/// checkpoint is executed in a later round, once enough signatures have been collected.
+pub struct SafeSubmission {
+    safe: EthAddress,
+    signers: Vec<LocalWallet>,
+    proposals: Option<ProposalQueue>,
+    /// Proposals are ordered by the nonce of the Safe, so only one can be made at a time.
+    lock: Mutex<()>,
+}
+
+impl SafeSubmission {
+    pub fn new(safe: EthAddress) -> Self {
+        Self {
+            safe,
+            signers: Vec::new(),
+            proposals: None,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Sign proposals with the key of an owner of the Safe.
+    pub fn with_signer(mut self, private_key: &[u8]) -> Result<Self> {
+        self.signers.push(LocalWallet::from_bytes(private_key)?);
+        Ok(self)
+    }
+
+    /// Collect the signatures of owners without keys on this machine through a directory.
+    pub fn with_proposals(mut self, proposals: ProposalQueue) -> Self {
+        self.proposals = Some(proposals);
+        self
+    }
+
+    async fn query<T: ContractWalletClient, C: AbiEncode, R: AbiDecode>(
+        &self,
+        parent: &T,
+        call: C,
+    ) -> Result<R> {
+        let ret = parent
+            .call_contract(self.safe, call.encode().into())
+            .await?;
+        Ok(R::decode(ret)?)
+    }
+}
+
+impl Display for SafeSubmission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "submission through Safe {:?}", self.safe)
+    }
+}
+
+#[async_trait]
+impl<T: ContractWalletClient> SubmissionStrategy<T> for SafeSubmission {
+    async fn submit(
+        &self,
+        parent: &T,
+        submitter: &Address,
+        bundle: BottomUpCheckpointBundle,
+    ) -> Result<ChainEpoch> {
+        let _guard = self.lock.lock().await;
+
+        let height = bundle.checkpoint.block_height;
+        let subnet = bundle.checkpoint.subnet_id.clone();
+
+        let (to, data) = parent.checkpoint_submission_call(
+            bundle.checkpoint,
+            bundle.signatures,
+            bundle.signatories,
+        )?;
+
+        let NonceReturn(nonce) = self.query(parent, NonceCall).await?;
+        let GetThresholdReturn(threshold) = self.query(parent, GetThresholdCall).await?;
+        let GetOwnersReturn(owners) = self.query(parent, GetOwnersCall).await?;
+
+        let proposal = SafeProposal::new(
+            format!("submit checkpoint of {subnet} at height {height}"),
+            parent.chain_id(),
+            self.safe,
+            height,
+            SafeTransaction::call(to, data, nonce),
+        );
+
+        let mut signatures = BTreeMap::new();
+
+        for signer in self.signers.iter() {
+            let signature = signer.sign_hash(proposal.safe_tx_hash)?;
+            signatures.insert(signer.address(), signature);
+        }
+
+        if let Some(ref proposals) = self.proposals {
+            // Only one checkpoint can be waiting for the other owners with the same nonce.
+            if let Some(pending) = proposals
+                .pending()?
+                .into_iter()
+                .find(|p| p.tx.nonce == nonce && p.safe_tx_hash != proposal.safe_tx_hash)
+            {
+                bail!(
+                    "Safe nonce {nonce} is taken by the proposal to {}; waiting for it to be executed",
+                    pending.description
+                );
+            }
+            if proposals.load(&proposal.safe_tx_hash)?.is_none() {
+                let path = proposals.save(&proposal)?;
+                tracing::info!(
+                    "proposed Safe transaction to {} in {path:?}",
+                    proposal.description
+                );
+            }
+            signatures.extend(proposals.signatures(&proposal.safe_tx_hash)?);
+        }
+
+        signatures.retain(|signer, _| owners.contains(signer));
+
+        if U256::from(signatures.len()) < threshold {
+            bail!(
+                "Safe transaction {:?} has {} of the {threshold} required signatures; waiting for the other owners",
+                proposal.safe_tx_hash,
+                signatures.len()
+            );
+        }
+
+        let tx = &proposal.tx;
+        let call = ExecTransactionCall {
+            to: tx.to,
+            value: tx.value,
+            data: tx.data.clone(),
+            operation: tx.operation,
+            safe_tx_gas: tx.safe_tx_gas,
+            base_gas: tx.base_gas,
+            gas_price: tx.gas_price,
+            gas_token: tx.gas_token,
+            refund_receiver: tx.refund_receiver,
+            signatures: encode_signatures(&signatures),
+        };
+
+        let receipt = parent
+            .send_contract_call(submitter, self.safe, call.encode().into())
+            .await?;
+
+        let failed = receipt.logs.iter().any(|log| {
+            log.address == self.safe
+                && ethers_contract::parse_log::<ExecutionFailureFilter>(log.clone()).is_ok()
+        });
+        if failed {
+            bail!(
+                "Safe transaction {:?} failed in {:?}",
+                proposal.safe_tx_hash,
+                receipt.transaction_hash
+            );
+        }
+
+        let epoch = epoch_from_receipt(&receipt)?;
+
+        if let Some(ref proposals) = self.proposals {
+            let mut proposal = proposal;
+            proposal.executed = Some(SafeExecution {
+                tx_hash: receipt.transaction_hash,
+                epoch,
+            });
+            proposals.save(&proposal)?;
+        }
+
+        Ok(epoch)
+    }
+}
+
+/// A transaction to be executed by a Safe, in the form its owners sign.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafeTransaction {
+    pub to: EthAddress,
+    pub value: U256,
+    pub data: Bytes,
+    /// 0 for a call, 1 for a delegate call.
+    pub operation: u8,
+    pub safe_tx_gas: U256,
+    pub base_gas: U256,
+    pub gas_price: U256,
+    pub gas_token: EthAddress,
+    pub refund_receiver: EthAddress,
+    pub nonce: U256,
+}
+
+impl SafeTransaction {
+    /// A plain call, with the gas paid by the sender instead of refunded by the Safe.
+    pub fn call(to: EthAddress, data: Bytes, nonce: U256) -> Self {
+        Self {
+            to,
+            value: U256::zero(),
+            data,
+            operation: 0,
+            safe_tx_gas: U256::zero(),
+            base_gas: U256::zero(),
+            gas_price: U256::zero(),
+            gas_token: EthAddress::zero(),
+            refund_receiver: EthAddress::zero(),
+            nonce,
+        }
+    }
+
+    /// The EIP-712 hash the owners of `safe` sign, the same as `getTransactionHash` returns.
+    pub fn hash(&self, chain_id: u64, safe: EthAddress) -> H256 {
+        let domain_separator = keccak256(ethers::abi::encode(&[
+            Token::FixedBytes(keccak256(SAFE_DOMAIN_TYPE).to_vec()),
+            Token::Uint(U256::from(chain_id)),
+            Token::Address(safe),
+        ]));
+
+        let struct_hash = keccak256(ethers::abi::encode(&[
+            Token::FixedBytes(keccak256(SAFE_TX_TYPE).to_vec()),
+            Token::Address(self.to),
+            Token::Uint(self.value),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Uint(U256::from(self.operation)),
+            Token::Uint(self.safe_tx_gas),
+            Token::Uint(self.base_gas),
+            Token::Uint(self.gas_price),
+            Token::Address(self.gas_token),
+            Token::Address(self.refund_receiver),
+            Token::Uint(self.nonce),
+        ]));
+
+        let mut preimage = vec![0x19, 0x01];
+        preimage.extend_from_slice(&domain_separator);
+        preimage.extend_from_slice(&struct_hash);
+
+        H256::from(keccak256(preimage))
+    }
+}
+
+/// A Safe transaction waiting for the signatures of the owners.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafeProposal {
+    /// Human readable description of the call, to be reviewed before signing.
+    pub description: String,
+    pub chain_id: u64,
+    pub safe: EthAddress,
+    pub checkpoint_height: ChainEpoch,
+    pub tx: SafeTransaction,
+    /// The hash the owners sign; checked against the transaction before signing.
+    pub safe_tx_hash: H256,
+    /// Set once the relayer executed the transaction.
+    pub executed: Option<SafeExecution>,
+}
+
+impl SafeProposal {
+    pub fn new(
+        description: String,
+        chain_id: u64,
+        safe: EthAddress,
+        checkpoint_height: ChainEpoch,
+        tx: SafeTransaction,
+    ) -> Self {
+        Self {
+            description,
+            chain_id,
+            safe,
+            checkpoint_height,
+            safe_tx_hash: tx.hash(chain_id, safe),
+            tx,
+            executed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafeExecution {
+    pub tx_hash: H256,
+    pub epoch: ChainEpoch,
+}
+
+/// A directory of Safe proposals, where the owners without keys on the machine of the relayer
+/// add their signatures, one file each, e.g. with `ipc-cli checkpoint sign-safe-proposal`.
+pub struct ProposalQueue {
+    dir: PathBuf,
+}
+
+impl ProposalQueue {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create proposal directory {dir:?}"))?;
+        Ok(Self { dir })
+    }
+
+    fn proposal_path(&self, hash: &H256) -> PathBuf {
+        self.dir.join(format!("{hash:?}.json"))
+    }
+
+    fn signature_path(&self, hash: &H256, signer: &EthAddress) -> PathBuf {
+        self.dir.join(format!("{hash:?}.{signer:?}.sig"))
+    }
+
+    pub fn load(&self, hash: &H256) -> Result<Option<SafeProposal>> {
+        let path = self.proposal_path(hash);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_proposal(&path).map(Some)
+    }
+
+    pub fn save(&self, proposal: &SafeProposal) -> Result<PathBuf> {
+        let path = self.proposal_path(&proposal.safe_tx_hash);
+        std::fs::write(&path, serde_json::to_string_pretty(proposal)?)?;
+        Ok(path)
+    }
+
+    /// Proposals which haven't been executed yet.
+    pub fn pending(&self) -> Result<Vec<SafeProposal>> {
+        let mut proposals = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |e| e == "json") {
+                let proposal = read_proposal(&path)?;
+                if proposal.executed.is_none() {
+                    proposals.push(proposal);
+                }
+            }
+        }
+        Ok(proposals)
+    }
+
+    /// The signatures added to a proposal, by the addresses they recover to.
+    ///
+    /// Signatures which don't recover to the address in their file name are skipped.
+    pub fn signatures(&self, hash: &H256) -> Result<BTreeMap<EthAddress, EthSignature>> {
+        let prefix = format!("{hash:?}.");
+        let mut signatures = BTreeMap::new();
+
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+
+            let Some(signer) = name
+                .strip_prefix(&prefix)
+                .and_then(|n| n.strip_suffix(".sig"))
+            else {
+                continue;
+            };
+
+            let signature = std::fs::read_to_string(&path)?;
+            let signature = hex::decode(signature.trim().trim_start_matches("0x"))?;
+            let signature = EthSignature::try_from(signature.as_slice())?;
+
+            match signature.recover(*hash) {
+                Ok(recovered) if format!("{recovered:?}") == signer => {
+                    signatures.insert(recovered, signature);
+                }
+                _ => tracing::warn!("ignoring invalid Safe signature in {path:?}"),
+            }
+        }
+
+        Ok(signatures)
+    }
+
+    /// Sign the proposal in `path` with the key of an owner and add the signature to the queue.
+    pub fn sign(path: &Path, private_key: &[u8]) -> Result<SignedProposal> {
+        let proposal = read_proposal(path)?;
+        let dir = path
+            .parent()
+            .ok_or_else(|| anyhow!("proposal {path:?} is not in a directory"))?;
+
+        // Never sign a hash without checking what it commits to.
+        let hash = proposal.tx.hash(proposal.chain_id, proposal.safe);
+        if hash != proposal.safe_tx_hash {
+            bail!(
+                "proposal {path:?} is for hash {:?}, but its transaction hashes to {hash:?}",
+                proposal.safe_tx_hash
+            );
+        }
+        if proposal.executed.is_some() {
+            bail!("proposal {path:?} has already been executed");
+        }
+
+        let signer = LocalWallet::from_bytes(private_key)?;
+        let signature = signer.sign_hash(hash)?;
+
+        let queue = ProposalQueue {
+            dir: dir.to_path_buf(),
+        };
+        let file = queue.signature_path(&hash, &signer.address());
+        std::fs::write(&file, hex::encode(signature.to_vec()))?;
+
+        Ok(SignedProposal {
+            description: proposal.description,
+            signer: signer.address(),
+            safe_tx_hash: hash,
+            file,
+        })
+    }
+}
+
+/// A signature added to a Safe proposal.
+pub struct SignedProposal {
+    pub description: String,
+    pub signer: EthAddress,
+    pub safe_tx_hash: H256,
+    pub file: PathBuf,
+}
+
+fn read_proposal(path: &Path) -> Result<SafeProposal> {
+    let json = std::fs::read_to_string(path)?;
+    serde_json::from_str(&json).with_context(|| format!("invalid Safe proposal in {path:?}"))
+}
+
+/// Concatenate the signatures ordered by the address of their signer, as Safe expects them.
+fn encode_signatures(signatures: &BTreeMap<EthAddress, EthSignature>) -> Bytes {
+    signatures
+        .values()
+        .flat_map(|s| s.to_vec())
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn epoch_from_receipt(receipt: &TransactionReceipt) -> Result<ChainEpoch> {
+    let block_number = receipt
+        .block_number
+        .ok_or_else(|| anyhow!("cannot get block number"))?;
+    Ok(block_number.as_u64() as ChainEpoch)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use ethers::abi::{AbiDecode, AbiEncode, Token};
+    use ethers::contract::EthEvent;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{Bytes, Log, TransactionReceipt, H256, U256};
+    use fvm_shared::address::Address;
+    use fvm_shared::clock::ChainEpoch;
+    use ipc_api::checkpoint::{
+        BottomUpCheckpoint, BottomUpCheckpointBundle, QuorumReachedEvent, Signature,
+    };
+    use ipc_api::subnet_id::SubnetID;
+
+    use crate::manager::BottomUpCheckpointRelayer;
+
+    use super::{
+        encode_signatures, ContractWalletClient, EthAddress, ExecTransactionCall,
+        ExecutionFailureFilter, GetOwnersReturn, GetThresholdReturn, NonceReturn, ProposalQueue,
+        SafeProposal, SafeSubmission, SafeTransaction, SafeWalletCalls, SubmissionStrategy,
+    };
+
+    const CHAIN_ID: u64 = 314159;
+    const EXECUTED_AT: u64 = 42;
+
+    /// A parent with a Safe, which records the calls sent to it instead of executing them.
+    struct MockParent {
+        nonce: U256,
+        threshold: U256,
+        owners: Vec<EthAddress>,
+        /// Emit `ExecutionFailure` from the Safe, as it does when the inner call reverts.
+        fail_execution: bool,
+        sent: std::sync::Mutex<Vec<(EthAddress, Bytes)>>,
+    }
+
+    impl MockParent {
+        fn new(threshold: u64, owners: &[[u8; 32]]) -> Self {
+            Self {
+                nonce: U256::zero(),
+                threshold: U256::from(threshold),
+                owners: owners.iter().map(address).collect(),
+                fail_execution: false,
+                sent: Default::default(),
+            }
+        }
+
+        fn sent(&self) -> Vec<(EthAddress, Bytes)> {
+            self.sent.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl BottomUpCheckpointRelayer for MockParent {
+        async fn submit_checkpoint(
+            &self,
+            _submitter: &Address,
+            _checkpoint: BottomUpCheckpoint,
+            _signatures: Vec<Signature>,
+            _signatories: Vec<Address>,
+        ) -> Result<ChainEpoch> {
+            unimplemented!("checkpoints go through the Safe")
+        }
+
+        async fn last_bottom_up_checkpoint_height(
+            &self,
+            _subnet_id: &SubnetID,
+        ) -> Result<ChainEpoch> {
+            unimplemented!()
+        }
+
+        async fn checkpoint_period(&self, _subnet_id: &SubnetID) -> Result<ChainEpoch> {
+            unimplemented!()
+        }
+
+        async fn checkpoint_bundle_at(
+            &self,
+            _height: ChainEpoch,
+        ) -> Result<Option<BottomUpCheckpointBundle>> {
+            unimplemented!()
+        }
+
+        async fn quorum_reached_events(
+            &self,
+            _height: ChainEpoch,
+        ) -> Result<Vec<QuorumReachedEvent>> {
+            unimplemented!()
+        }
+
+        async fn current_epoch(&self) -> Result<ChainEpoch> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl ContractWalletClient for MockParent {
+        fn chain_id(&self) -> u64 {
+            CHAIN_ID
+        }
+
+        fn checkpoint_submission_call(
+            &self,
+            checkpoint: BottomUpCheckpoint,
+            _signatures: Vec<Signature>,
+            _signatories: Vec<Address>,
+        ) -> Result<(EthAddress, Bytes)> {
+            let data = checkpoint.block_height.to_be_bytes().to_vec();
+            Ok((EthAddress::repeat_byte(1), Bytes::from(data)))
+        }
+
+        async fn call_contract(&self, to: EthAddress, data: Bytes) -> Result<Bytes> {
+            assert_eq!(to, safe());
+            let ret = match SafeWalletCalls::decode(data)? {
+                SafeWalletCalls::Nonce(_) => NonceReturn(self.nonce).encode(),
+                SafeWalletCalls::GetThreshold(_) => GetThresholdReturn(self.threshold).encode(),
+                SafeWalletCalls::GetOwners(_) => GetOwnersReturn(self.owners.clone()).encode(),
+                call => panic!("unexpected query: {call:?}"),
+            };
+            Ok(ret.into())
+        }
+
+        async fn send_contract_call(
+            &self,
+            _from: &Address,
+            to: EthAddress,
+            data: Bytes,
+        ) -> Result<TransactionReceipt> {
+            self.sent.lock().unwrap().push((to, data));
+
+            let mut logs = Vec::new();
+            if self.fail_execution {
+                logs.push(Log {
+                    address: safe(),
+                    topics: vec![ExecutionFailureFilter::signature()],
+                    data: ethers::abi::encode(&[
+                        Token::FixedBytes(vec![0; 32]),
+                        Token::Uint(U256::zero()),
+                    ])
+                    .into(),
+                    ..Default::default()
+                });
+            }
+
+            Ok(TransactionReceipt {
+                transaction_hash: H256::repeat_byte(9),
+                block_number: Some(EXECUTED_AT.into()),
+                logs,
+                ..Default::default()
+            })
+        }
+    }
+
+    fn safe() -> EthAddress {
+        EthAddress::repeat_byte(2)
+    }
+
+    fn address(key: &[u8; 32]) -> EthAddress {
+        LocalWallet::from_bytes(key).unwrap().address()
+    }
+
+    fn bundle(height: ChainEpoch) -> BottomUpCheckpointBundle {
+        BottomUpCheckpointBundle {
+            checkpoint: BottomUpCheckpoint {
+                subnet_id: SubnetID::new_root(CHAIN_ID),
+                block_height: height,
+                block_hash: vec![0; 32],
+                next_configuration_number: 0,
+                msgs: Vec::new(),
+            },
+            signatures: Vec::new(),
+            signatories: Vec::new(),
+        }
+    }
+
+    fn submission(keys: &[[u8; 32]]) -> SafeSubmission {
+        keys.iter().fold(SafeSubmission::new(safe()), |s, key| {
+            s.with_signer(key).unwrap()
+        })
+    }
+
+    /// The signers of the `execTransaction` call sent to the Safe, in the order of the signatures.
+    fn exec_signers(data: &Bytes) -> Vec<EthAddress> {
+        let call = ExecTransactionCall::decode(data).unwrap();
+        let tx = SafeTransaction::call(call.to, call.data, U256::zero());
+        let hash = tx.hash(CHAIN_ID, safe());
+        call.signatures
+            .chunks(65)
+            .map(|s| {
+                ethers::types::Signature::try_from(s)
+                    .unwrap()
+                    .recover(hash)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn proposal(nonce: u64) -> SafeProposal {
+        let tx = SafeTransaction::call(
+            EthAddress::repeat_byte(1),
+            Bytes::from(vec![1, 2, 3]),
+            U256::from(nonce),
+        );
+        SafeProposal::new(
+            "submit checkpoint".into(),
+            314159,
+            EthAddress::repeat_byte(2),
+            100,
+            tx,
+        )
+    }
+
+    #[test]
+    fn safe_tx_hash_commits_to_nonce_and_chain() {
+        let p = proposal(0);
+        assert_eq!(p.safe_tx_hash, p.tx.hash(p.chain_id, p.safe));
+        assert_ne!(p.safe_tx_hash, proposal(1).safe_tx_hash);
+        assert_ne!(p.safe_tx_hash, p.tx.hash(p.chain_id + 1, p.safe));
+    }
+
+    #[test]
+    fn collect_signatures_through_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = ProposalQueue::new(dir.path()).unwrap();
+        let p = proposal(0);
+        let path = queue.save(&p).unwrap();
+
+        let key = [1u8; 32];
+        let signed = ProposalQueue::sign(&path, &key).unwrap();
+        let owner = LocalWallet::from_bytes(&key).unwrap().address();
+        assert_eq!(signed.signer, owner);
+
+        let signatures = queue.signatures(&p.safe_tx_hash).unwrap();
+        assert_eq!(signatures.len(), 1);
+        assert!(signatures.contains_key(&owner));
+
+        assert_eq!(queue.pending().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn refuse_to_sign_tampered_proposal() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = ProposalQueue::new(dir.path()).unwrap();
+        let mut p = proposal(0);
+        p.tx.to = EthAddress::repeat_byte(3);
+        let path = queue.save(&p).unwrap();
+
+        assert!(ProposalQueue::sign(&path, &[1u8; 32]).is_err());
+    }
+
+    #[test]
+    fn signatures_ordered_by_signer() {
+        let hash = proposal(0).safe_tx_hash;
+        let mut signatures = BTreeMap::new();
+        for key in [[3u8; 32], [1u8; 32], [2u8; 32]] {
+            let wallet = LocalWallet::from_bytes(&key).unwrap();
+            signatures.insert(wallet.address(), wallet.sign_hash(hash).unwrap());
+        }
+
+        let encoded = encode_signatures(&signatures);
+        assert_eq!(encoded.len(), 3 * 65);
+
+        let signers = encoded
+            .chunks(65)
+            .map(|s| {
+                ethers::types::Signature::try_from(s)
+                    .unwrap()
+                    .recover(hash)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let mut sorted = signers.clone();
+        sorted.sort();
+        assert_eq!(signers, sorted);
+    }
+
+    #[tokio::test]
+    async fn safe_submission_with_owner_signatures() {
+        let (owner1, owner2, stranger) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let parent = MockParent::new(2, &[owner1, owner2, [4u8; 32]]);
+
+        let epoch = submission(&[stranger, owner2, owner1])
+            .submit(&parent, &Address::new_id(100), bundle(10))
+            .await
+            .unwrap();
+
+        assert_eq!(epoch, EXECUTED_AT as ChainEpoch);
+
+        let sent = parent.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, safe());
+
+        // Only the owners signed, ordered by their addresses.
+        let mut owners = vec![address(&owner1), address(&owner2)];
+        owners.sort();
+        assert_eq!(exec_signers(&sent[0].1), owners);
+    }
+
+    #[tokio::test]
+    async fn safe_submission_below_threshold() {
+        let (owner, stranger) = ([1u8; 32], [3u8; 32]);
+        let parent = MockParent::new(2, &[owner, [2u8; 32]]);
+
+        // The signature of a non-owner doesn't count towards the threshold.
+        let err = submission(&[owner, stranger])
+            .submit(&parent, &Address::new_id(100), bundle(10))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("has 1 of the 2"), "{err}");
+        assert!(parent.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn safe_submission_execution_failure() {
+        let owner = [1u8; 32];
+        let mut parent = MockParent::new(1, &[owner]);
+        parent.fail_execution = true;
+
+        let dir = tempfile::tempdir().unwrap();
+        let submission =
+            submission(&[owner]).with_proposals(ProposalQueue::new(dir.path()).unwrap());
+
+        let err = submission
+            .submit(&parent, &Address::new_id(100), bundle(10))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("failed"), "{err}");
+        assert_eq!(parent.sent().len(), 1);
+
+        // The proposal is still waiting to be executed.
+        let queue = ProposalQueue::new(dir.path()).unwrap();
+        assert_eq!(queue.pending().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn safe_submission_through_proposal_queue() {
+        let (owner1, owner2) = ([1u8; 32], [2u8; 32]);
+        let parent = MockParent::new(2, &[owner1, owner2]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let queue = ProposalQueue::new(dir.path()).unwrap();
+        let submission =
+            submission(&[owner1]).with_proposals(ProposalQueue::new(dir.path()).unwrap());
+
+        // The first round proposes the transaction and waits for the other owner.
+        assert!(submission
+            .submit(&parent, &Address::new_id(100), bundle(10))
+            .await
+            .is_err());
+        assert!(parent.sent().is_empty());
+
+        let pending = queue.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        let hash = pending[0].safe_tx_hash;
+
+        // A later checkpoint can't take the same nonce while the first one is pending.
+        let err = submission
+            .submit(&parent, &Address::new_id(100), bundle(20))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("nonce 0 is taken"), "{err}");
+        assert!(parent.sent().is_empty());
+
+        ProposalQueue::sign(&dir.path().join(format!("{hash:?}.json")), &owner2).unwrap();
+
+        let epoch = submission
+            .submit(&parent, &Address::new_id(100), bundle(10))
+            .await
+            .unwrap();
+        assert_eq!(epoch, EXECUTED_AT as ChainEpoch);
+        assert_eq!(exec_signers(&parent.sent()[0].1).len(), 2);
+
+        let executed = queue.load(&hash).unwrap().unwrap().executed.unwrap();
+        assert_eq!(executed.epoch, epoch);
+        assert!(queue.pending().unwrap().is_empty());
+    }
+}
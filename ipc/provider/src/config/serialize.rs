@@ -121,6 +121,7 @@ mod tests {
     fn test_serialization() {
         let mut config = Config {
             keystore_path: Some(String::from("~/.ipc")),
+            encrypted_evm_keystore: false,
             subnets: Default::default(),
         };
 
@@ -4,7 +4,8 @@ use anyhow::{anyhow, Result};
 use async_channel::{Receiver, Sender};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
-use reqwest::header::HeaderValue;
+use ipc_observability::propagation::inject_trace_context;
+use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
@@ -67,7 +68,15 @@ impl JsonRpcClientImpl {
 impl JsonRpcClient for JsonRpcClientImpl {
     async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
         let request_body = build_jsonrpc_request(method, params)?;
-        let mut builder = self.http_client.post(self.url.as_str()).json(&request_body);
+
+        let mut headers = HeaderMap::new();
+        inject_trace_context(&mut headers);
+
+        let mut builder = self
+            .http_client
+            .post(self.url.as_str())
+            .headers(headers)
+            .json(&request_body);
         builder = builder.timeout(DEFAULT_REQ_TIMEOUT);
 
         // Add the authorization bearer token if present
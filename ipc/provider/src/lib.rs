@@ -19,10 +19,11 @@ use ipc_api::{
     subnet_id::SubnetID,
 };
 use ipc_wallet::{
-    EthKeyAddress, EvmKeyStore, KeyStore, KeyStoreConfig, PersistentKeyStore, Wallet,
+    EthKeyAddress, EvmKeyStore, KeyStore, KeyStoreConfig, PersistentKeyStore, Wallet, WalletType,
 };
 use lotus::message::wallet::WalletKeyType;
 use manager::{EthSubnetManager, SubnetGenesisInfo, SubnetInfo, SubnetManager};
+use offline::{BroadcastReceipt, SignedTransaction, TxCall, UnsignedTransaction};
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::Borrow,
@@ -31,7 +32,7 @@ use std::{
     str::FromStr,
     sync::{Arc, RwLock},
 };
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
 
 pub mod checkpoint;
 pub mod config;
@@ -39,6 +40,7 @@ pub mod jsonrpc;
 pub mod lotus;
 pub mod manager;
 pub mod observe;
+pub mod offline;
 
 const DEFAULT_REPO_PATH: &str = ".ipc";
 const DEFAULT_CONFIG_NAME: &str = "config.toml";
@@ -293,7 +295,18 @@ impl IpcProvider {
 
         let subnet_config = conn.subnet();
         let sender = self.check_sender(subnet_config, from)?;
-        let addr = payload_to_evm_address(sender.payload())?;
+        let public_key = self.validator_public_key(&sender)?;
+        let hex_public_key = hex::encode(&public_key);
+        log::info!("joining subnet with public key: {hex_public_key:?}");
+
+        conn.manager()
+            .join_subnet(subnet, sender, collateral, public_key)
+            .await
+    }
+
+    /// The uncompressed public key of an address in the EVM wallet, which validators join with.
+    fn validator_public_key(&self, addr: &Address) -> anyhow::Result<Vec<u8>> {
+        let addr = payload_to_evm_address(addr.payload())?;
         let keystore = self.evm_wallet()?;
         let key_info = keystore
             .read()
@@ -301,13 +314,9 @@ impl IpcProvider {
             .get(&addr.into())?
             .ok_or_else(|| anyhow!("key does not exists"))?;
         let sk = libsecp256k1::SecretKey::parse_slice(key_info.private_key())?;
-        let public_key = libsecp256k1::PublicKey::from_secret_key(&sk).serialize();
-        let hex_public_key = hex::encode(public_key);
-        log::info!("joining subnet with public key: {hex_public_key:?}");
-
-        conn.manager()
-            .join_subnet(subnet, sender, collateral, public_key.into())
-            .await
+        Ok(libsecp256k1::PublicKey::from_secret_key(&sk)
+            .serialize()
+            .to_vec())
     }
 
     pub async fn pre_fund(
@@ -743,6 +752,52 @@ impl IpcProvider {
             .set_federated_power(from, subnet, validators, public_keys, federated_power)
             .await
     }
+
+    /// Builds the transaction of `call` from `from`, or the default sender, on the subnet it
+    /// targets, without signing or sending it. It is populated with the nonce, gas and chain ID,
+    /// so that it can be signed offline with [IpcProvider::sign_transaction].
+    pub async fn unsigned_transaction(
+        &mut self,
+        from: Option<Address>,
+        mut call: TxCall,
+    ) -> anyhow::Result<UnsignedTransaction> {
+        let conn = self.get_connection(&call.target_subnet()?)?;
+
+        let subnet_config = conn.subnet();
+        let sender = self.check_sender(subnet_config, from)?;
+
+        if let TxCall::JoinSubnet {
+            public_key: public_key @ None,
+            ..
+        } = &mut call
+        {
+            *public_key = Some(self.validator_public_key(&sender)?);
+        }
+
+        conn.manager().unsigned_transaction(sender, call).await
+    }
+
+    /// Signs a transaction built by [IpcProvider::unsigned_transaction] with the key of its
+    /// sender in the EVM wallet. This doesn't need a connection to any subnet.
+    pub fn sign_transaction(&self, tx: &UnsignedTransaction) -> anyhow::Result<SignedTransaction> {
+        let from = tx.from()?;
+        let keystore = self.evm_wallet()?;
+        let key_info =
+            keystore.read().unwrap().get(&from.into())?.ok_or_else(|| {
+                anyhow!("address {from:?} does not have private key in key store")
+            })?;
+        tx.sign(key_info.private_key())
+    }
+
+    /// Submits a signed transaction to the subnet and waits for it to be executed.
+    pub async fn broadcast_transaction(
+        &self,
+        subnet: &SubnetID,
+        tx: &SignedTransaction,
+    ) -> anyhow::Result<BroadcastReceipt> {
+        let conn = self.get_connection(subnet)?;
+        conn.manager().broadcast_transaction(tx).await
+    }
 }
 
 /// Lotus JSON keytype format
@@ -828,6 +883,139 @@ impl IpcProvider {
         let persisted: String = persisted.private_key().parse()?;
         self.import_evm_key_from_privkey(&persisted)
     }
+
+    /// Import a key from an Ethereum v3 keystore file, e.g. one created by geth or foundry.
+    pub fn import_evm_key_from_keystore_file(
+        &self,
+        path: impl AsRef<Path>,
+        passphrase: &str,
+    ) -> anyhow::Result<EthKeyAddress> {
+        let key_info = ipc_wallet::decrypt_evm_key_file(path.as_ref(), passphrase)?;
+        let keystore = self.evm_wallet()?;
+        let mut keystore = keystore.write().unwrap();
+        keystore.put(key_info)
+    }
+
+    /// Generate a new mnemonic and store it as the HD seed of the wallet, so that accounts can
+    /// later be derived from it with [IpcProvider::derive_key_from_seed].
+    ///
+    /// The seed is kept next to the EVM keys, encrypted if they are, but it is used to derive
+    /// the keys of both wallet types. Fails if a seed has already been stored.
+    pub fn new_hd_seed(&self) -> anyhow::Result<Zeroizing<String>> {
+        let keystore = self.evm_wallet()?;
+        let mut keystore = keystore.write().unwrap();
+        if keystore.mnemonic()?.is_some() {
+            return Err(anyhow!(
+                "an HD seed is already stored in the wallet; derive new accounts from it instead"
+            ));
+        }
+        let phrase = ipc_wallet::generate_mnemonic()?;
+        keystore.set_mnemonic(&phrase)?;
+        Ok(phrase)
+    }
+
+    /// Store the mnemonic as the HD seed of the wallet, unless one is already stored.
+    ///
+    /// Returns whether the mnemonic was stored.
+    pub fn import_hd_seed(&self, phrase: &str) -> anyhow::Result<bool> {
+        let keystore = self.evm_wallet()?;
+        let mut keystore = keystore.write().unwrap();
+        if keystore.mnemonic()?.is_some() {
+            return Ok(false);
+        }
+        keystore.set_mnemonic(phrase)?;
+        Ok(true)
+    }
+
+    /// Derive the secp256k1 key at the BIP-32 `path` from the mnemonic and add it to the wallet.
+    pub fn import_key_from_mnemonic(
+        &self,
+        wallet_type: &WalletType,
+        phrase: &str,
+        path: &str,
+    ) -> anyhow::Result<String> {
+        let private_key = ipc_wallet::derive_secp256k1_key(phrase, path)?;
+        self.put_derived_key(wallet_type, &private_key)
+    }
+
+    /// Derive a key from the HD seed stored in the wallet and add it to the wallet.
+    ///
+    /// Without a `path`, the account at the lowest index of the standard derivation path of the
+    /// wallet type which is not in the wallet yet is derived.
+    pub fn derive_key_from_seed(
+        &self,
+        wallet_type: &WalletType,
+        path: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let phrase = self
+            .evm_wallet()?
+            .read()
+            .unwrap()
+            .mnemonic()?
+            .ok_or_else(|| {
+                anyhow!("no HD seed stored in the wallet; create or import a mnemonic first")
+            })?;
+
+        if let Some(path) = path {
+            return self.import_key_from_mnemonic(wallet_type, &phrase, path);
+        }
+
+        let mut index = 0;
+        loop {
+            let path = ipc_wallet::derivation_path(wallet_type, index);
+            let private_key = ipc_wallet::derive_secp256k1_key(&phrase, &path)?;
+            if !self.has_derived_key(wallet_type, &private_key)? {
+                return self.put_derived_key(wallet_type, &private_key);
+            }
+            index += 1;
+        }
+    }
+
+    fn has_derived_key(
+        &self,
+        wallet_type: &WalletType,
+        private_key: &[u8],
+    ) -> anyhow::Result<bool> {
+        match wallet_type {
+            WalletType::Evm => {
+                let addr =
+                    EthKeyAddress::try_from(ipc_wallet::EvmKeyInfo::new(private_key.to_vec()))?;
+                Ok(self.evm_wallet()?.read().unwrap().get(&addr)?.is_some())
+            }
+            WalletType::Fvm => {
+                let key = ipc_wallet::Key::try_from(ipc_wallet::KeyInfo::new(
+                    SignatureType::Secp256k1,
+                    private_key.to_vec(),
+                ))?;
+                Ok(self.fvm_wallet()?.write().unwrap().has_key(&key.address))
+            }
+        }
+    }
+
+    fn put_derived_key(
+        &self,
+        wallet_type: &WalletType,
+        private_key: &[u8],
+    ) -> anyhow::Result<String> {
+        let address = match wallet_type {
+            WalletType::Evm => self
+                .evm_wallet()?
+                .write()
+                .unwrap()
+                .put(ipc_wallet::EvmKeyInfo::new(private_key.to_vec()))?
+                .to_string(),
+            WalletType::Fvm => self
+                .fvm_wallet()?
+                .write()
+                .unwrap()
+                .import(ipc_wallet::KeyInfo::new(
+                    SignatureType::Secp256k1,
+                    private_key.to_vec(),
+                ))?
+                .to_string(),
+        };
+        Ok(address)
+    }
 }
 
 fn new_fvm_wallet_from_config(config: Arc<Config>) -> anyhow::Result<KeyStore> {
@@ -845,10 +1033,16 @@ pub fn new_evm_keystore_from_config(
     config: Arc<Config>,
 ) -> anyhow::Result<PersistentKeyStore<EthKeyAddress>> {
     let repo_str = &config.keystore_path;
-    if let Some(repo_str) = repo_str {
-        new_evm_keystore_from_path(repo_str)
-    } else {
-        Err(anyhow!("No keystore repo found in config"))
+    match repo_str {
+        Some(repo_str) if config.encrypted_evm_keystore => {
+            let passphrase = ipc_wallet::read_passphrase(
+                ipc_wallet::EVM_KEYSTORE_PASSPHRASE_ENV,
+                "EVM keystore passphrase",
+            )?;
+            new_encrypted_evm_keystore_from_path(repo_str, passphrase)
+        }
+        Some(repo_str) => new_evm_keystore_from_path(repo_str),
+        None => Err(anyhow!("No keystore repo found in config")),
     }
 }
 
@@ -860,6 +1054,16 @@ pub fn new_evm_keystore_from_path(
     PersistentKeyStore::new(repo).map_err(|e| anyhow!("Failed to create evm keystore: {}", e))
 }
 
+pub fn new_encrypted_evm_keystore_from_path(
+    repo_str: &str,
+    passphrase: Zeroizing<String>,
+) -> anyhow::Result<PersistentKeyStore<EthKeyAddress>> {
+    let repo = Path::new(&repo_str).join(ipc_wallet::ENCRYPTED_EVM_KEYSTORE_NAME);
+    let repo = expand_tilde(repo);
+    PersistentKeyStore::new_encrypted(repo, passphrase)
+        .map_err(|e| anyhow!("Failed to open encrypted evm keystore: {}", e))
+}
+
 pub fn new_fvm_keystore_from_path(repo_str: &str) -> anyhow::Result<KeyStore> {
     let repo = Path::new(&repo_str);
     let repo = expand_tilde(repo);
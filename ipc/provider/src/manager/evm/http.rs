@@ -0,0 +1,97 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use ethers::providers::{HttpClientError, JsonRpcClient, JsonRpcError};
+use ipc_observability::propagation::inject_trace_context;
+use reqwest::header::HeaderMap;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use url::Url;
+
+/// An HTTP transport like [ethers::providers::Http], which also sends the trace context
+/// of the current span with each request, so the calls show up in the traces of the node.
+#[derive(Debug)]
+pub struct TracedHttp {
+    id: AtomicU64,
+    client: Client,
+    url: Url,
+}
+
+impl TracedHttp {
+    pub fn new_with_client(url: Url, client: Client) -> Self {
+        Self {
+            id: AtomicU64::new(1),
+            client,
+            url,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a, T> {
+    id: u64,
+    jsonrpc: &'a str,
+    method: &'a str,
+    // Methods without parameters are called with `()`, which would be `null`.
+    #[serde(skip_serializing_if = "is_zst")]
+    params: T,
+}
+
+fn is_zst<T>(_: &T) -> bool {
+    std::mem::size_of::<T>() == 0
+}
+
+#[async_trait]
+impl JsonRpcClient for TracedHttp {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, HttpClientError>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let request = Request {
+            id: self.id.fetch_add(1, Ordering::SeqCst),
+            jsonrpc: "2.0",
+            method,
+            params,
+        };
+
+        let mut headers = HeaderMap::new();
+        inject_trace_context(&mut headers);
+
+        let body = self
+            .client
+            .post(self.url.as_ref())
+            .headers(headers)
+            .json(&request)
+            .send()
+            .await?
+            .bytes()
+            .await?;
+
+        let parse_error = |err| HttpClientError::SerdeJson {
+            err,
+            text: String::from_utf8_lossy(&body).to_string(),
+        };
+
+        let mut response: serde_json::Value = serde_json::from_slice(&body).map_err(parse_error)?;
+
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            let error: JsonRpcError = serde_json::from_value(error.clone()).map_err(parse_error)?;
+            return Err(HttpClientError::JsonRpcError(error));
+        }
+
+        let result = response
+            .get_mut("result")
+            .map(serde_json::Value::take)
+            .unwrap_or_default();
+
+        serde_json::from_value(result).map_err(parse_error)
+    }
+}
@@ -22,6 +22,8 @@ use std::net::{IpAddr, SocketAddr};
 use ipc_api::subnet::{Asset, AssetKind, PermissionMode};
 use ipc_api::{eth_to_fil_amount, ethers_address_to_fil_address};
 
+use super::http::TracedHttp;
+use crate::checkpoint::submission::ContractWalletClient;
 use crate::config::subnet::SubnetConfig;
 use crate::config::Subnet;
 use crate::lotus::message::ipc::SubnetInfo;
@@ -30,14 +32,16 @@ use crate::manager::subnet::{
     TopDownQueryPayload,
 };
 use crate::manager::{EthManager, SubnetManager};
+use crate::offline::{BroadcastReceipt, SignedTransaction, TxCall, UnsignedTransaction};
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use ethers::abi::Tokenizable;
 use ethers::contract::abigen;
 use ethers::prelude::k256::ecdsa::SigningKey;
 use ethers::prelude::{Signer, SignerMiddleware};
-use ethers::providers::{Authorization, Http, Middleware, Provider};
+use ethers::providers::{Authorization, Middleware, Provider};
 use ethers::signers::{LocalWallet, Wallet};
+use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::types::{BlockId, Eip1559TransactionRequest, ValueOrArray, I256, U256};
 
 use fvm_shared::clock::ChainEpoch;
@@ -53,7 +57,7 @@ use ipc_wallet::{EthKeyAddress, EvmKeyStore, PersistentKeyStore};
 use num_traits::ToPrimitive;
 use std::result;
 
-pub type DefaultSignerMiddleware = SignerMiddleware<Provider<Http>, Wallet<SigningKey>>;
+pub type DefaultSignerMiddleware = SignerMiddleware<Provider<TracedHttp>, Wallet<SigningKey>>;
 
 /// Default polling time used by the Ethers provider to check for pending
 /// transactions and events. Default is 7, and for our child subnets we
@@ -81,7 +85,7 @@ struct IPCContractInfo {
     gateway_addr: ethers::types::Address,
     registry_addr: ethers::types::Address,
     chain_id: u64,
-    provider: Provider<Http>,
+    provider: Provider<TracedHttp>,
 }
 
 //TODO receive clarity on this implementation
@@ -248,37 +252,7 @@ impl TopDownFinalityQuery for EthSubnetManager {
 #[async_trait]
 impl SubnetManager for EthSubnetManager {
     async fn create_subnet(&self, from: Address, params: ConstructParams) -> Result<Address> {
-        self.ensure_same_gateway(&params.ipc_gateway_addr)?;
-
-        let min_validator_stake = params
-            .min_validator_stake
-            .atto()
-            .to_u128()
-            .ok_or_else(|| anyhow!("invalid min validator stake"))?;
-
-        tracing::debug!("calling create subnet for EVM manager");
-
-        let route = subnet_id_to_evm_addresses(&params.parent)?;
-        tracing::debug!("root SubnetID as Ethereum type: {route:?}");
-
-        let params = register_subnet_facet::ConstructorParams {
-            parent_id: register_subnet_facet::SubnetID {
-                root: params.parent.root_id(),
-                route,
-            },
-            ipc_gateway_addr: self.ipc_contract_info.gateway_addr,
-            consensus: params.consensus as u64 as u8,
-            min_activation_collateral: ethers::types::U256::from(min_validator_stake),
-            min_validators: params.min_validators,
-            bottom_up_check_period: params.bottomup_check_period as u64,
-            majority_percentage: SUBNET_MAJORITY_PERCENTAGE,
-            active_validators_limit: params.active_validators_limit,
-            power_scale: 3,
-            permission_mode: params.permission_mode as u8,
-            supply_source: register_subnet_facet::Asset::try_from(params.supply_source)?,
-            collateral_source: register_subnet_facet::Asset::try_from(params.collateral_source)?,
-            validator_gater: payload_to_evm_address(params.validator_gater.payload())?,
-        };
+        let params = self.register_subnet_params(params)?;
 
         tracing::info!("creating subnet on evm with params: {params:?}");
 
@@ -706,7 +680,7 @@ impl SubnetManager for EthSubnetManager {
     /// Send value between two addresses in a subnet
     async fn send_value(&self, from: Address, to: Address, amount: TokenAmount) -> Result<()> {
         let signer = Arc::new(self.get_signer(&from)?);
-        let (fee, fee_cap) = premium_estimation(signer.clone()).await?;
+        let (fee, fee_cap) = premium_estimation(signer.inner()).await?;
         let tx = Eip1559TransactionRequest::new()
             .to(payload_to_evm_address(to.payload())?)
             .value(fil_to_eth_amount(&amount)?)
@@ -916,6 +890,131 @@ impl SubnetManager for EthSubnetManager {
         let receipt = pending_tx.retries(TRANSACTION_RECEIPT_RETRIES).await?;
         block_number_from_receipt(receipt)
     }
+
+    async fn unsigned_transaction(
+        &self,
+        from: Address,
+        call: TxCall,
+    ) -> Result<UnsignedTransaction> {
+        // The contracts are bound to a client without a signer, which is only used to query the
+        // chain; the sender might not even have its key on this machine.
+        let client = Arc::new(self.ipc_contract_info.provider.clone());
+        let description = call.to_string();
+
+        let tx = match call {
+            TxCall::CreateSubnet(params) => {
+                let params = self.register_subnet_params(params)?;
+                let contract = register_subnet_facet::RegisterSubnetFacet::new(
+                    self.ipc_contract_info.registry_addr,
+                    client,
+                );
+                contract.new_subnet_actor(params).tx
+            }
+            TxCall::JoinSubnet {
+                subnet,
+                collateral,
+                public_key,
+            } => {
+                let public_key = public_key
+                    .ok_or_else(|| anyhow!("the public key of the validator is required"))?;
+                let collateral = collateral
+                    .atto()
+                    .to_u128()
+                    .ok_or_else(|| anyhow!("invalid min validator stake"))?;
+                let address = contract_address_from_subnet(&subnet)?;
+                let contract =
+                    subnet_actor_manager_facet::SubnetActorManagerFacet::new(address, client);
+                let txn = contract.join(
+                    ethers::types::Bytes::from(public_key),
+                    U256::from(collateral),
+                );
+                self.handle_txn_token(&subnet, txn, collateral, 0).await?.tx
+            }
+            TxCall::Stake { subnet, collateral } => {
+                let collateral = collateral
+                    .atto()
+                    .to_u128()
+                    .ok_or_else(|| anyhow!("invalid collateral amount"))?;
+                let address = contract_address_from_subnet(&subnet)?;
+                let contract =
+                    subnet_actor_manager_facet::SubnetActorManagerFacet::new(address, client);
+                let txn = contract.stake(U256::from(collateral));
+                self.handle_txn_token(&subnet, txn, collateral, 0).await?.tx
+            }
+            TxCall::Fund {
+                subnet,
+                gateway_addr,
+                to,
+                amount,
+            } => {
+                if let Some(gateway_addr) = gateway_addr {
+                    self.ensure_same_gateway(&gateway_addr)?;
+                }
+                let value = amount
+                    .atto()
+                    .to_u128()
+                    .ok_or_else(|| anyhow!("invalid value to fund"))?;
+                let evm_subnet_id = gateway_manager_facet::SubnetID::try_from(&subnet)?;
+                let contract = gateway_manager_facet::GatewayManagerFacet::new(
+                    self.ipc_contract_info.gateway_addr,
+                    client,
+                );
+                let mut txn = contract.fund(
+                    evm_subnet_id,
+                    gateway_manager_facet::FvmAddress::try_from(to.unwrap_or(from))?,
+                );
+                txn.tx.set_value(value);
+                txn.tx
+            }
+        };
+
+        self.populate_transaction(from, tx, description).await
+    }
+
+    async fn broadcast_transaction(&self, tx: &SignedTransaction) -> Result<BroadcastReceipt> {
+        if tx.chain_id != self.ipc_contract_info.chain_id {
+            return Err(anyhow!(
+                "transaction signed for chain ID {}, but the subnet has chain ID {}",
+                tx.chain_id,
+                self.ipc_contract_info.chain_id
+            ));
+        }
+
+        tracing::info!("broadcasting transaction {:?}: {}", tx.hash, tx.description);
+
+        let pending_tx = self
+            .ipc_contract_info
+            .provider
+            .send_raw_transaction(tx.raw.clone())
+            .await?;
+        let receipt = pending_tx
+            .retries(TRANSACTION_RECEIPT_RETRIES)
+            .await?
+            .ok_or_else(|| {
+                anyhow!("txn sent to network, but receipt cannot be obtained, please check scanner")
+            })?;
+
+        if receipt.status == Some(0u64.into()) {
+            return Err(anyhow!("transaction {:?} reverted", tx.hash));
+        }
+
+        let subnet_addr = receipt
+            .logs
+            .iter()
+            .find_map(|log| {
+                ethers_contract::parse_log::<register_subnet_facet::SubnetDeployedFilter>(
+                    log.clone(),
+                )
+                .ok()
+            })
+            .map(|deployed| ethers_address_to_fil_address(&deployed.subnet_addr))
+            .transpose()?;
+
+        Ok(BroadcastReceipt {
+            epoch: block_number_from_receipt(Some(receipt))?,
+            subnet_addr,
+        })
+    }
 }
 
 #[async_trait]
@@ -1024,7 +1123,7 @@ impl EthSubnetManager {
         gateway_addr: ethers::types::Address,
         registry_addr: ethers::types::Address,
         chain_id: u64,
-        provider: Provider<Http>,
+        provider: Provider<TracedHttp>,
         keystore: Option<Arc<RwLock<PersistentKeyStore<EthKeyAddress>>>>,
     ) -> Self {
         Self {
@@ -1038,6 +1137,78 @@ impl EthSubnetManager {
         }
     }
 
+    /// Fill in the sender, nonce, gas and chain ID of a transaction, the same way they are
+    /// filled in when the transaction is signed and sent right away.
+    async fn populate_transaction(
+        &self,
+        from: Address,
+        mut tx: TypedTransaction,
+        description: String,
+    ) -> Result<UnsignedTransaction> {
+        let from = payload_to_evm_address(from.payload())?;
+        let provider = &self.ipc_contract_info.provider;
+
+        tx.set_from(from);
+        tx.set_chain_id(self.ipc_contract_info.chain_id);
+
+        // Use the pending state for the nonce, like when joining, so a preceding pre-fund counts.
+        let nonce = provider
+            .get_transaction_count(
+                from,
+                Some(BlockId::Number(ethers::types::BlockNumber::Pending)),
+            )
+            .await?;
+        tx.set_nonce(nonce);
+
+        let (max_priority_fee_per_gas, _) = premium_estimation(provider).await?;
+        tx.set_gas_price(max_priority_fee_per_gas);
+
+        // Estimates the gas limit.
+        provider.fill_transaction(&mut tx, None).await?;
+
+        Ok(UnsignedTransaction { description, tx })
+    }
+
+    /// Convert the subnet construction parameters to the ones of the registry contract.
+    fn register_subnet_params(
+        &self,
+        params: ConstructParams,
+    ) -> Result<register_subnet_facet::ConstructorParams> {
+        self.ensure_same_gateway(&params.ipc_gateway_addr)?;
+
+        let min_validator_stake = params
+            .min_validator_stake
+            .atto()
+            .to_u128()
+            .ok_or_else(|| anyhow!("invalid min validator stake"))?;
+
+        tracing::debug!("calling create subnet for EVM manager");
+
+        let route = subnet_id_to_evm_addresses(&params.parent)?;
+        tracing::debug!("root SubnetID as Ethereum type: {route:?}");
+
+        let params = register_subnet_facet::ConstructorParams {
+            parent_id: register_subnet_facet::SubnetID {
+                root: params.parent.root_id(),
+                route,
+            },
+            ipc_gateway_addr: self.ipc_contract_info.gateway_addr,
+            consensus: params.consensus as u64 as u8,
+            min_activation_collateral: ethers::types::U256::from(min_validator_stake),
+            min_validators: params.min_validators,
+            bottom_up_check_period: params.bottomup_check_period as u64,
+            majority_percentage: SUBNET_MAJORITY_PERCENTAGE,
+            active_validators_limit: params.active_validators_limit,
+            power_scale: 3,
+            permission_mode: params.permission_mode as u8,
+            supply_source: register_subnet_facet::Asset::try_from(params.supply_source)?,
+            collateral_source: register_subnet_facet::Asset::try_from(params.collateral_source)?,
+            validator_gater: payload_to_evm_address(params.validator_gater.payload())?,
+        };
+
+        Ok(params)
+    }
+
     /// This method handles the "msg.value" based on different collateral/supply source
     /// asset kind.
     pub async fn handle_txn_token<B, D, M>(
@@ -1126,7 +1297,7 @@ impl EthSubnetManager {
 
         let client = client.build()?;
 
-        let provider = Http::new_with_client(url, client);
+        let provider = TracedHttp::new_with_client(url, client);
 
         let mut provider = Provider::new(provider);
         // set polling interval for provider to fit fast child subnets block times.
@@ -1146,6 +1317,82 @@ impl EthSubnetManager {
     }
 }
 
+#[async_trait]
+impl ContractWalletClient for EthSubnetManager {
+    fn chain_id(&self) -> u64 {
+        self.ipc_contract_info.chain_id
+    }
+
+    fn checkpoint_submission_call(
+        &self,
+        checkpoint: BottomUpCheckpoint,
+        signatures: Vec<Signature>,
+        signatories: Vec<Address>,
+    ) -> Result<(ethers::types::Address, ethers::types::Bytes)> {
+        let address = contract_address_from_subnet(&checkpoint.subnet_id)?;
+
+        let (checkpoint, signatories, signatures) =
+            submit_checkpoint_args(checkpoint, signatures, signatories)?;
+
+        let contract = subnet_actor_checkpointing_facet::SubnetActorCheckpointingFacet::new(
+            address,
+            Arc::new(self.ipc_contract_info.provider.clone()),
+        );
+        let data = contract
+            .submit_checkpoint(checkpoint, signatories, signatures)
+            .calldata()
+            .ok_or_else(|| anyhow!("no call data for checkpoint submission"))?;
+
+        Ok((address, data))
+    }
+
+    async fn call_contract(
+        &self,
+        to: ethers::types::Address,
+        data: ethers::types::Bytes,
+    ) -> Result<ethers::types::Bytes> {
+        let tx = Eip1559TransactionRequest::new().to(to).data(data);
+        let ret = self
+            .ipc_contract_info
+            .provider
+            .call(&TypedTransaction::Eip1559(tx), None)
+            .await?;
+        Ok(ret)
+    }
+
+    async fn send_contract_call(
+        &self,
+        from: &Address,
+        to: ethers::types::Address,
+        data: ethers::types::Bytes,
+    ) -> Result<ethers::types::TransactionReceipt> {
+        let signer = Arc::new(self.get_signer(from)?);
+        let (fee, fee_cap) = premium_estimation(signer.inner()).await?;
+        let tx = Eip1559TransactionRequest::new()
+            .to(to)
+            .data(data)
+            .max_priority_fee_per_gas(fee)
+            .max_fee_per_gas(fee_cap);
+
+        let pending_tx = signer.send_transaction(tx, None).await?;
+        let tx_hash = pending_tx.tx_hash();
+        tracing::debug!("sent call from {from} to contract {to:?} in tx {tx_hash:?}");
+
+        let receipt = pending_tx
+            .retries(TRANSACTION_RECEIPT_RETRIES)
+            .await?
+            .ok_or_else(|| {
+                anyhow!("txn sent to network, but receipt cannot be obtained, please check scanner")
+            })?;
+
+        if receipt.status == Some(0u64.into()) {
+            return Err(anyhow!("transaction {tx_hash:?} reverted"));
+        }
+
+        Ok(receipt)
+    }
+}
+
 #[async_trait]
 impl BottomUpCheckpointRelayer for EthSubnetManager {
     async fn submit_checkpoint(
@@ -1160,17 +1407,8 @@ impl BottomUpCheckpointRelayer for EthSubnetManager {
             "submit bottom up checkpoint: {checkpoint:?} in evm subnet contract: {address:}"
         );
 
-        let signatures = signatures
-            .into_iter()
-            .map(ethers::types::Bytes::from)
-            .collect::<Vec<_>>();
-        let signatories = signatories
-            .into_iter()
-            .map(|addr| payload_to_evm_address(addr.payload()))
-            .collect::<result::Result<Vec<_>, _>>()?;
-
-        let checkpoint =
-            subnet_actor_checkpointing_facet::BottomUpCheckpoint::try_from(checkpoint)?;
+        let (checkpoint, signatories, signatures) =
+            submit_checkpoint_args(checkpoint, signatures, signatories)?;
 
         let signer = Arc::new(self.get_signer(submitter)?);
         let contract = subnet_actor_checkpointing_facet::SubnetActorCheckpointingFacet::new(
@@ -1288,7 +1526,7 @@ where
     B: std::borrow::Borrow<D>,
     M: ethers::abi::Detokenize,
 {
-    let (max_priority_fee_per_gas, _) = premium_estimation(signer).await?;
+    let (max_priority_fee_per_gas, _) = premium_estimation(signer.inner()).await?;
     Ok(call.gas_price(max_priority_fee_per_gas))
 }
 
@@ -1298,16 +1536,16 @@ where
 /// This is adaptation of ethers' `eip1559_default_estimator`:
 /// https://github.com/gakonst/ethers-rs/blob/5dcd3b7e754174448f9a8cbfc0523896609629f9/ethers-core/src/utils/mod.rs#L476
 async fn premium_estimation(
-    signer: Arc<DefaultSignerMiddleware>,
+    provider: &Provider<TracedHttp>,
 ) -> Result<(ethers::types::U256, ethers::types::U256)> {
-    let base_fee_per_gas = signer
+    let base_fee_per_gas = provider
         .get_block(ethers::types::BlockNumber::Latest)
         .await?
         .ok_or_else(|| anyhow!("Latest block not found"))?
         .base_fee_per_gas
         .ok_or_else(|| anyhow!("EIP-1559 not activated"))?;
 
-    let fee_history = signer
+    let fee_history = provider
         .fee_history(
             ethers::utils::EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
             ethers::types::BlockNumber::Latest,
@@ -1397,6 +1635,30 @@ fn estimate_priority_fee(rewards: Vec<Vec<U256>>) -> U256 {
 }
 
 /// Get the block number from the transaction receipt
+/// Convert the arguments of a checkpoint submission to the types of the subnet actor contract.
+fn submit_checkpoint_args(
+    checkpoint: BottomUpCheckpoint,
+    signatures: Vec<Signature>,
+    signatories: Vec<Address>,
+) -> Result<(
+    subnet_actor_checkpointing_facet::BottomUpCheckpoint,
+    Vec<ethers::types::Address>,
+    Vec<ethers::types::Bytes>,
+)> {
+    let signatures = signatures
+        .into_iter()
+        .map(ethers::types::Bytes::from)
+        .collect::<Vec<_>>();
+    let signatories = signatories
+        .into_iter()
+        .map(|addr| payload_to_evm_address(addr.payload()))
+        .collect::<result::Result<Vec<_>, _>>()?;
+
+    let checkpoint = subnet_actor_checkpointing_facet::BottomUpCheckpoint::try_from(checkpoint)?;
+
+    Ok((checkpoint, signatories, signatures))
+}
+
 fn block_number_from_receipt(
     receipt: Option<ethers::types::TransactionReceipt>,
 ) -> Result<ChainEpoch> {
@@ -17,6 +17,7 @@ use ipc_api::subnet_id::SubnetID;
 use ipc_api::validator::Validator;
 
 use crate::lotus::message::ipc::SubnetInfo;
+use crate::offline::{BroadcastReceipt, SignedTransaction, TxCall, UnsignedTransaction};
 
 /// Trait to interact with a subnet and handle its lifecycle.
 #[async_trait]
@@ -197,6 +198,17 @@ pub trait SubnetManager: Send + Sync + TopDownFinalityQuery + BottomUpCheckpoint
         public_keys: &[Vec<u8>],
         federated_power: &[u128],
     ) -> Result<ChainEpoch>;
+
+    /// Builds the transaction of a state-changing call from `from` without signing or sending
+    /// it, populated with the nonce, gas and chain ID so that it can be signed offline.
+    async fn unsigned_transaction(
+        &self,
+        from: Address,
+        call: TxCall,
+    ) -> Result<UnsignedTransaction>;
+
+    /// Submits a transaction that was signed offline and waits for it to be executed.
+    async fn broadcast_transaction(&self, tx: &SignedTransaction) -> Result<BroadcastReceipt>;
 }
 
 #[derive(Debug)]
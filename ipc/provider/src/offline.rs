@@ -0,0 +1,206 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Transactions which are built on a connected machine, signed offline on a machine holding
+//! the keys, and then broadcast from a connected machine again.
+
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, bail};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Bytes, H256};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::{address::Address, econ::TokenAmount};
+use ipc_api::subnet::ConstructParams;
+use ipc_api::subnet_id::SubnetID;
+use serde::{Deserialize, Serialize};
+
+/// A state-changing call which can be built as an unsigned transaction.
+#[derive(Debug, Clone)]
+pub enum TxCall {
+    /// See [crate::manager::SubnetManager::create_subnet].
+    CreateSubnet(ConstructParams),
+    /// See [crate::manager::SubnetManager::join_subnet].
+    JoinSubnet {
+        subnet: SubnetID,
+        collateral: TokenAmount,
+        /// The uncompressed public key of the validator; looked up in the wallet if not set.
+        public_key: Option<Vec<u8>>,
+    },
+    /// See [crate::manager::SubnetManager::stake].
+    Stake {
+        subnet: SubnetID,
+        collateral: TokenAmount,
+    },
+    /// See [crate::manager::SubnetManager::fund].
+    Fund {
+        subnet: SubnetID,
+        /// The gateway of the parent; the one in the config if not set.
+        gateway_addr: Option<Address>,
+        /// The funded address in the subnet; the sender if not set.
+        to: Option<Address>,
+        amount: TokenAmount,
+    },
+}
+
+impl TxCall {
+    /// The subnet the transaction is sent to.
+    pub fn target_subnet(&self) -> anyhow::Result<SubnetID> {
+        match self {
+            TxCall::CreateSubnet(params) => Ok(params.parent.clone()),
+            TxCall::JoinSubnet { subnet, .. }
+            | TxCall::Stake { subnet, .. }
+            | TxCall::Fund { subnet, .. } => {
+                subnet.parent().ok_or_else(|| anyhow!("no parent found"))
+            }
+        }
+    }
+}
+
+impl Display for TxCall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxCall::CreateSubnet(params) => write!(
+                f,
+                "create subnet in {} with min validator stake {} and {} min validators",
+                params.parent, params.min_validator_stake, params.min_validators
+            ),
+            TxCall::JoinSubnet {
+                subnet, collateral, ..
+            } => write!(f, "join subnet {subnet} with collateral {collateral}"),
+            TxCall::Stake { subnet, collateral } => {
+                write!(f, "stake {collateral} in subnet {subnet}")
+            }
+            TxCall::Fund {
+                subnet, to, amount, ..
+            } => match to {
+                Some(to) => write!(f, "fund {to} in subnet {subnet} with {amount}"),
+                None => write!(f, "fund sender in subnet {subnet} with {amount}"),
+            },
+        }
+    }
+}
+
+/// A transaction populated with the sender, nonce, gas and chain ID, ready to be signed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    /// Human readable description of the call, to be reviewed before signing.
+    pub description: String,
+    pub tx: TypedTransaction,
+}
+
+impl UnsignedTransaction {
+    /// The address the transaction has to be signed by.
+    pub fn from(&self) -> anyhow::Result<ethers::types::Address> {
+        self.tx
+            .from()
+            .copied()
+            .ok_or_else(|| anyhow!("transaction has no sender"))
+    }
+
+    /// Sign the transaction with the private key of its sender.
+    ///
+    /// This doesn't need any connection to the chain.
+    pub fn sign(&self, private_key: &[u8]) -> anyhow::Result<SignedTransaction> {
+        let from = self.from()?;
+        let chain_id = self
+            .tx
+            .chain_id()
+            .ok_or_else(|| anyhow!("transaction has no chain ID"))?
+            .as_u64();
+
+        let wallet = LocalWallet::from_bytes(private_key)?.with_chain_id(chain_id);
+        if wallet.address() != from {
+            bail!(
+                "transaction from {from:?} cannot be signed with the key of {:?}",
+                wallet.address()
+            );
+        }
+
+        let signature = wallet.sign_transaction_sync(&self.tx)?;
+        let raw = self.tx.rlp_signed(&signature);
+
+        Ok(SignedTransaction {
+            description: self.description.clone(),
+            from,
+            chain_id,
+            hash: H256::from(ethers::utils::keccak256(&raw)),
+            raw,
+        })
+    }
+}
+
+/// A transaction signed offline, ready to be broadcast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    /// Human readable description of the call, carried over from the unsigned transaction.
+    pub description: String,
+    pub from: ethers::types::Address,
+    pub chain_id: u64,
+    /// The hash the transaction will have on chain.
+    pub hash: H256,
+    /// The RLP encoded signed transaction.
+    pub raw: Bytes,
+}
+
+/// The result of a broadcast transaction once it has been executed.
+#[derive(Debug, Clone)]
+pub struct BroadcastReceipt {
+    /// The epoch the transaction was executed in.
+    pub epoch: ChainEpoch,
+    /// The address of the subnet actor, if the transaction created a subnet.
+    pub subnet_addr: Option<Address>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnsignedTransaction;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use ethers::types::{Eip1559TransactionRequest, U256};
+    use ethers::utils::rlp::Rlp;
+
+    fn unsigned_tx(from: ethers::types::Address) -> UnsignedTransaction {
+        let tx = Eip1559TransactionRequest::new()
+            .from(from)
+            .to(ethers::types::Address::repeat_byte(1))
+            .value(100)
+            .nonce(7)
+            .gas(21000)
+            .max_fee_per_gas(1000)
+            .max_priority_fee_per_gas(100)
+            .chain_id(314159);
+
+        UnsignedTransaction {
+            description: "send value".into(),
+            tx: TypedTransaction::Eip1559(tx),
+        }
+    }
+
+    #[test]
+    fn test_sign_unsigned_transaction() {
+        let key = [1u8; 32];
+        let wallet = LocalWallet::from_bytes(&key).unwrap();
+        let unsigned = unsigned_tx(wallet.address());
+
+        // The file format round trips.
+        let json = serde_json::to_string(&unsigned).unwrap();
+        let unsigned: UnsignedTransaction = serde_json::from_str(&json).unwrap();
+
+        let signed = unsigned.sign(&key).unwrap();
+        assert_eq!(signed.from, wallet.address());
+        assert_eq!(signed.chain_id, 314159);
+
+        let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(&signed.raw)).unwrap();
+        assert_eq!(tx.nonce(), Some(&U256::from(7)));
+        assert_eq!(signature.recover(tx.sighash()).unwrap(), wallet.address());
+        assert_eq!(tx.hash(&signature), signed.hash);
+    }
+
+    #[test]
+    fn test_sign_with_wrong_key() {
+        let wallet = LocalWallet::from_bytes(&[1u8; 32]).unwrap();
+        let unsigned = unsigned_tx(wallet.address());
+        assert!(unsigned.sign(&[2u8; 32]).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+
+//! Encrypted key files in the Ethereum v3 keystore format (scrypt + aes-128-ctr), which
+//! can be exchanged with geth, foundry and other Ethereum tooling.
+
+use crate::evm::KeyInfo;
+use anyhow::{anyhow, bail, Result};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Name of the directory holding the encrypted EVM key files inside the keystore path.
+pub const ENCRYPTED_EVM_KEYSTORE_NAME: &str = "evm_keystore";
+
+/// Environment variable holding the passphrase of the encrypted EVM keystore.
+pub const EVM_KEYSTORE_PASSPHRASE_ENV: &str = "IPC_KEYSTORE_PASSPHRASE";
+
+/// Environment variable holding the passphrase of a single key file being imported or exported.
+pub const KEYSTORE_FILE_PASSPHRASE_ENV: &str = "IPC_KEYSTORE_FILE_PASSPHRASE";
+
+/// Extension of the key files in an encrypted keystore directory.
+pub(crate) const KEY_FILE_EXTENSION: &str = "json";
+
+/// Encrypt a private key with the passphrase and write it as a v3 keystore file called
+/// `name` in `dir`. Returns the path of the created file.
+pub fn encrypt_key_file(
+    dir: &Path,
+    name: &str,
+    key_info: &KeyInfo,
+    passphrase: &str,
+) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+
+    eth_keystore::encrypt_key(
+        dir,
+        &mut rand::thread_rng(),
+        key_info.private_key(),
+        passphrase,
+        Some(name),
+    )
+    .map_err(|e| anyhow!("failed to encrypt key file {name}: {e}"))?;
+
+    let path = dir.join(name);
+
+    #[cfg(unix)]
+    crate::set_user_perm(&std::fs::File::open(&path)?)?;
+
+    Ok(path)
+}
+
+/// Read a v3 keystore file and decrypt the private key in it with the passphrase.
+pub fn decrypt_key_file(path: &Path, passphrase: &str) -> Result<KeyInfo> {
+    let private_key = eth_keystore::decrypt_key(path, passphrase).map_err(|e| {
+        anyhow!("failed to decrypt key file {path:?}, possibly incorrect passphrase: {e}")
+    })?;
+    Ok(KeyInfo::new(private_key))
+}
+
+/// Read a passphrase from the environment variable, or if it's not set, prompt for it on
+/// the terminal without echoing the input.
+pub fn read_passphrase(env_var: &str, prompt: &str) -> Result<Zeroizing<String>> {
+    if let Ok(passphrase) = std::env::var(env_var) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    prompt_passphrase(prompt)
+        .map_err(|e| anyhow!("cannot read passphrase; set it in {env_var} instead: {e}"))
+}
+
+#[cfg(unix)]
+fn prompt_passphrase(prompt: &str) -> Result<Zeroizing<String>> {
+    let fd = libc::STDIN_FILENO;
+
+    // Safety: `isatty`, `tcgetattr` and `tcsetattr` only access the termios struct we own.
+    if unsafe { libc::isatty(fd) } != 1 {
+        bail!("stdin is not a terminal");
+    }
+
+    let mut term: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut term) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let original = term;
+    term.c_lflag &= !libc::ECHO;
+    term.c_lflag |= libc::ECHONL;
+
+    eprint!("{prompt}: ");
+    std::io::stderr().flush()?;
+
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &term) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let mut line = Zeroizing::new(String::new());
+    let read = std::io::stdin().lock().read_line(&mut line);
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    read?;
+
+    Ok(Zeroizing::new(
+        line.trim_end_matches(['\r', '\n']).to_string(),
+    ))
+}
+
+#[cfg(not(unix))]
+fn prompt_passphrase(_prompt: &str) -> Result<Zeroizing<String>> {
+    bail!("passphrase prompt is only supported on unix")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt_key_file, encrypt_key_file};
+    use crate::evm::KeyInfo;
+
+    #[test]
+    fn test_encrypt_decrypt_key_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_info = KeyInfo::new(vec![1; 32]);
+
+        let path = encrypt_key_file(dir.path(), "key.json", &key_info, "secret").unwrap();
+
+        // The file is a v3 keystore, not the hex encoded key.
+        let content = std::fs::read_to_string(&path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["version"], 3);
+        assert!(!content.contains(&hex::encode(key_info.private_key())));
+
+        assert_eq!(decrypt_key_file(&path, "secret").unwrap(), key_info);
+        assert!(decrypt_key_file(&path, "wrong").is_err());
+    }
+}
@@ -3,6 +3,7 @@
 
 //! Ethereum wallet key store.
 
+mod encrypted;
 mod memory;
 mod persistent;
 
@@ -14,6 +15,10 @@ use zeroize::Zeroize;
 #[cfg(feature = "with-ethers")]
 use std::str::FromStr;
 
+pub use crate::evm::encrypted::{
+    decrypt_key_file, encrypt_key_file, read_passphrase, ENCRYPTED_EVM_KEYSTORE_NAME,
+    EVM_KEYSTORE_PASSPHRASE_ENV, KEYSTORE_FILE_PASSPHRASE_ENV,
+};
 pub use crate::evm::persistent::{PersistentKeyInfo, PersistentKeyStore};
 
 pub const DEFAULT_KEYSTORE_NAME: &str = "evm_keystore.json";
@@ -3,8 +3,10 @@
 
 //! Persistent file key store
 
+use crate::evm::encrypted::{decrypt_key_file, encrypt_key_file, KEY_FILE_EXTENSION};
 use crate::evm::memory::MemoryKeyStore;
 use crate::evm::{KeyInfo, KeyStore};
+use crate::hd::{normalize_mnemonic, read_mnemonic_file, write_mnemonic_file};
 use anyhow::anyhow;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -13,13 +15,25 @@ use std::fs;
 use std::fs::File;
 use std::hash::Hash;
 use std::io::{BufReader, BufWriter, ErrorKind};
-use std::path::PathBuf;
-use zeroize::Zeroize;
+use std::path::{Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Name of the file in an encrypted keystore directory which holds the default address.
+const DEFAULT_KEY_FILE: &str = "default";
+
+/// Name of the file next to the keys which holds the mnemonic of the HD seed.
+const MNEMONIC_FILE: &str = "hd_seed";
 
 #[derive(Default)]
 pub struct PersistentKeyStore<T> {
     memory: MemoryKeyStore<T>,
+    /// The JSON file with the keys, or the directory of key files if the store is encrypted.
     file_path: PathBuf,
+    /// Set if the keys are stored as v3 keystore files encrypted with this passphrase.
+    encryption: Option<Zeroizing<String>>,
+    /// The name of the file each key of an encrypted store is kept in. Keys created here are
+    /// named after their address, but imported files, e.g. from geth, keep their original name.
+    key_files: HashMap<T, String>,
 }
 
 /// The persistent key information written to disk
@@ -66,23 +80,23 @@ impl<T: Clone + Eq + Hash + TryFrom<KeyInfo> + Default + ToString> KeyStore
 
     fn put(&mut self, info: KeyInfo) -> Result<Self::Key> {
         let addr = self.memory.put(info)?;
-        self.flush_no_encryption()?;
+        self.flush()?;
         Ok(addr)
     }
 
     fn remove(&mut self, addr: &Self::Key) -> Result<()> {
         self.memory.remove(addr)?;
-        self.flush_no_encryption()
+        self.flush()
     }
 
     fn set_default(&mut self, addr: &Self::Key) -> Result<()> {
         self.memory.set_default(addr)?;
-        self.flush_no_encryption()
+        self.flush()
     }
 
     fn get_default(&mut self) -> Result<Option<Self::Key>> {
         let default = self.memory.get_default()?;
-        self.flush_no_encryption()?;
+        self.flush()?;
         Ok(default)
     }
 }
@@ -106,6 +120,8 @@ impl<T: Clone + Eq + Hash + TryFrom<KeyInfo> + Default + ToString> PersistentKey
                             default: None,
                         },
                         file_path: path,
+                        encryption: None,
+                        key_files: Default::default(),
                     })
                 } else {
                     Err(anyhow!("cannot create key store: {e:}"))
@@ -151,9 +167,157 @@ impl<T: Clone + Eq + Hash + TryFrom<KeyInfo> + Default + ToString> PersistentKey
                 default,
             },
             file_path: path,
+            encryption: None,
+            key_files: Default::default(),
+        })
+    }
+
+    /// Open a key store that keeps every key in its own Ethereum v3 keystore file in the `dir`
+    /// directory, encrypted with the passphrase. Key files created by other Ethereum tools can
+    /// be dropped into the directory, as long as they use the same passphrase. Files which
+    /// are not keystores are skipped.
+    pub fn new_encrypted(dir: PathBuf, passphrase: Zeroizing<String>) -> Result<Self> {
+        let mut key_infos = HashMap::new();
+        let mut key_files = HashMap::new();
+        let mut default = None;
+
+        if dir.exists() {
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                let file_name = match path.file_name().and_then(|n| n.to_str()) {
+                    Some(n) if path.is_file() && n != DEFAULT_KEY_FILE && n != MNEMONIC_FILE => {
+                        n.to_string()
+                    }
+                    _ => continue,
+                };
+                if !is_keystore_file(&path) {
+                    log::warn!("skipping {path:?} in the key store: not a v3 keystore file");
+                    continue;
+                }
+                let key_info = decrypt_key_file(&path, &passphrase)?;
+                let addr = T::try_from(key_info.clone())
+                    .map_err(|_| anyhow!("cannot convert private key to address"))?;
+                key_infos.insert(addr.clone(), key_info);
+                key_files.insert(addr, file_name);
+            }
+
+            let default_path = dir.join(DEFAULT_KEY_FILE);
+            if default_path.exists() {
+                let default_addr = fs::read_to_string(&default_path)?;
+                let default_addr = default_addr.trim();
+                let (addr, key_info) = key_infos
+                    .iter()
+                    .find(|(addr, _)| addr.to_string() == default_addr)
+                    .map(|(addr, info)| (addr.clone(), info.clone()))
+                    .ok_or_else(|| anyhow!("default key {default_addr} not found in key store"))?;
+                key_infos.insert(T::default(), key_info);
+                default = Some(addr);
+            }
+        } else {
+            log::info!("key store does not exist, initialized to empty encrypted key store");
+        }
+
+        Ok(Self {
+            memory: MemoryKeyStore {
+                data: key_infos,
+                default,
+            },
+            file_path: dir,
+            encryption: Some(passphrase),
+            key_files,
         })
     }
 
+    /// The mnemonic of the HD seed keys can be derived from, if one has been stored.
+    pub fn mnemonic(&self) -> Result<Option<Zeroizing<String>>> {
+        read_mnemonic_file(
+            &self.mnemonic_path(),
+            self.encryption.as_deref().map(|p| p.as_str()),
+        )
+    }
+
+    /// Store the mnemonic of the HD seed, encrypted the same way as the keys.
+    ///
+    /// A different mnemonic cannot replace the stored one, because the keys derived from it
+    /// could no longer be recovered from the backup of the phrase.
+    pub fn set_mnemonic(&mut self, phrase: &str) -> Result<()> {
+        let phrase = normalize_mnemonic(phrase);
+        match self.mnemonic()? {
+            Some(stored) if *stored == *phrase => Ok(()),
+            Some(_) => Err(anyhow!(
+                "a different mnemonic is already stored in the key store"
+            )),
+            None => write_mnemonic_file(
+                &self.mnemonic_path(),
+                &phrase,
+                self.encryption.as_deref().map(|p| p.as_str()),
+            ),
+        }
+    }
+
+    fn mnemonic_path(&self) -> PathBuf {
+        match &self.encryption {
+            Some(_) => self.file_path.join(MNEMONIC_FILE),
+            None => self
+                .file_path
+                .with_file_name(format!("{MNEMONIC_FILE}.{KEY_FILE_EXTENSION}")),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self.encryption.clone() {
+            None => self.flush_no_encryption(),
+            Some(passphrase) => self.flush_encrypted(&passphrase),
+        }
+    }
+
+    /// Write the keys which are not on disk yet to their own encrypted files and remove the
+    /// files of the keys which have been removed.
+    ///
+    /// Keys already on disk are not rewritten because the key derivation is slow on purpose.
+    fn flush_encrypted(&mut self, passphrase: &str) -> Result<()> {
+        let dir = self.file_path.clone();
+        fs::create_dir_all(&dir)?;
+
+        for (key, val) in self.memory.data.iter() {
+            // The default key is a copy of another key; only its address is persisted.
+            if *key == T::default() || self.key_files.contains_key(key) {
+                continue;
+            }
+            let file_name = format!("{}.{KEY_FILE_EXTENSION}", key.to_string());
+            if !dir.join(&file_name).exists() {
+                encrypt_key_file(&dir, &file_name, val, passphrase)?;
+            }
+            self.key_files.insert(key.clone(), file_name);
+        }
+
+        // Only delete files we know hold a removed key, whatever their name.
+        let removed = self
+            .key_files
+            .keys()
+            .filter(|key| !self.memory.data.contains_key(key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in removed {
+            if let Some(file_name) = self.key_files.remove(&key) {
+                let path = dir.join(file_name);
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+
+        let default_path = dir.join(DEFAULT_KEY_FILE);
+        match &self.memory.default {
+            Some(addr) => fs::write(default_path, addr.to_string())?,
+            None if default_path.exists() => fs::remove_file(default_path)?,
+            None => {}
+        }
+
+        Ok(())
+    }
+
     /// Write all keys to file without any encryption.
     fn flush_no_encryption(&self) -> Result<()> {
         let dir = self
@@ -190,11 +354,25 @@ impl<T: Clone + Eq + Hash + TryFrom<KeyInfo> + Default + ToString> PersistentKey
     }
 }
 
+/// Check whether a file looks like a v3 keystore, as opposed to some other file which ended up
+/// in the directory, without trying to decrypt it.
+fn is_keystore_file(path: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    match serde_json::from_str::<serde_json::Value>(&content) {
+        // geth writes `crypto`, some older tools `Crypto`.
+        Ok(json) => json.get("crypto").or_else(|| json.get("Crypto")).is_some(),
+        Err(_) => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::evm::KeyInfo;
+    use crate::evm::{encrypt_key_file, KeyInfo};
     use crate::{EvmKeyStore, PersistentKeyStore};
     use std::fmt::{Display, Formatter};
+    use zeroize::Zeroizing;
 
     #[derive(Clone, Eq, PartialEq, Hash, Debug)]
     struct Key {
@@ -250,6 +428,113 @@ mod tests {
         assert_eq!(key_from_store.unwrap(), key_info);
     }
 
+    #[test]
+    fn test_read_write_encrypted_keystore() {
+        let keystore_folder = tempfile::tempdir().unwrap().into_path();
+        let keystore_location = keystore_folder.join("evm_keystore");
+        let passphrase = || Zeroizing::new(String::from("secret"));
+
+        let mut ks =
+            PersistentKeyStore::new_encrypted(keystore_location.clone(), passphrase()).unwrap();
+
+        let key_info = KeyInfo {
+            private_key: vec![0, 1, 2],
+        };
+        let other_key_info = KeyInfo {
+            private_key: vec![0, 1, 3],
+        };
+        let addr = Key::try_from(key_info.clone()).unwrap();
+        let other_addr = Key::try_from(other_key_info.clone()).unwrap();
+
+        ks.put(key_info.clone()).unwrap();
+        ks.put(other_key_info.clone()).unwrap();
+        ks.set_default(&addr).unwrap();
+        ks.remove(&other_addr).unwrap();
+
+        // The private key is not stored in clear text.
+        let key_file = keystore_location.join(format!("{addr}.json"));
+        let content = std::fs::read_to_string(key_file).unwrap();
+        assert!(!content.contains(&hex::encode(&key_info.private_key)));
+
+        // Create the key store again
+        let mut ks =
+            PersistentKeyStore::new_encrypted(keystore_location.clone(), passphrase()).unwrap();
+        assert_eq!(ks.get(&addr).unwrap(), Some(key_info));
+        assert_eq!(ks.get(&other_addr).unwrap(), None);
+        assert_eq!(ks.get_default().unwrap(), Some(addr));
+
+        // The wrong passphrase cannot open it.
+        let wrong = Zeroizing::new(String::from("wrong"));
+        assert!(PersistentKeyStore::<Key>::new_encrypted(keystore_location, wrong).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_keystore_with_foreign_files() {
+        let keystore_location = tempfile::tempdir()
+            .unwrap()
+            .into_path()
+            .join("evm_keystore");
+        let passphrase = || Zeroizing::new(String::from("secret"));
+
+        // A key file named the way geth names them, and a file which isn't a key at all.
+        let key_info = KeyInfo {
+            private_key: vec![0, 1, 2],
+        };
+        let addr = Key::try_from(key_info.clone()).unwrap();
+        let geth_file = "UTC--2024-01-01T00-00-00.000000000Z--0102";
+        encrypt_key_file(&keystore_location, geth_file, &key_info, "secret").unwrap();
+        std::fs::write(keystore_location.join("README"), "my keys").unwrap();
+
+        let mut ks =
+            PersistentKeyStore::new_encrypted(keystore_location.clone(), passphrase()).unwrap();
+        assert_eq!(ks.get(&addr).unwrap(), Some(key_info.clone()));
+
+        // Flushing doesn't write a copy of the imported key under another name.
+        ks.put(KeyInfo {
+            private_key: vec![0, 1, 3],
+        })
+        .unwrap();
+        assert!(!keystore_location.join(format!("{addr}.json")).exists());
+
+        // Removing the key deletes the file it came from, so it doesn't come back.
+        ks.remove(&addr).unwrap();
+        assert!(!keystore_location.join(geth_file).exists());
+        assert!(keystore_location.join("README").exists());
+
+        let ks = PersistentKeyStore::new_encrypted(keystore_location, passphrase()).unwrap();
+        assert_eq!(ks.get(&addr).unwrap(), None);
+        assert_eq!(ks.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_read_write_mnemonic() {
+        let phrase = "test test test test test test test test test test test junk";
+        let keystore_folder = tempfile::tempdir().unwrap().into_path();
+
+        let mut ks = PersistentKeyStore::<Key>::new(keystore_folder.join("eth_keystore")).unwrap();
+        assert!(ks.mnemonic().unwrap().is_none());
+        ks.set_mnemonic(phrase).unwrap();
+        assert_eq!(ks.mnemonic().unwrap().unwrap().as_str(), phrase);
+        // Storing the same phrase again is fine, but it cannot be replaced.
+        ks.set_mnemonic(&format!(" {phrase} ")).unwrap();
+        assert!(ks.set_mnemonic("other words").is_err());
+
+        let dir = keystore_folder.join("evm_keystore");
+        let passphrase = Zeroizing::new(String::from("secret"));
+        let mut ks =
+            PersistentKeyStore::<Key>::new_encrypted(dir.clone(), passphrase.clone()).unwrap();
+        ks.set_mnemonic(phrase).unwrap();
+        ks.put(KeyInfo {
+            private_key: vec![0, 1, 2],
+        })
+        .unwrap();
+
+        // The mnemonic file is not mistaken for a key when the store is opened again.
+        let ks = PersistentKeyStore::<Key>::new_encrypted(dir, passphrase).unwrap();
+        assert_eq!(ks.list().unwrap().len(), 1);
+        assert_eq!(ks.mnemonic().unwrap().unwrap().as_str(), phrase);
+    }
+
     #[test]
     fn test_default() {
         let keystore_folder = tempfile::tempdir().unwrap().into_path();
@@ -0,0 +1,213 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+
+//! Hierarchical deterministic wallets: secp256k1 keys derived with BIP-32 from the seed of a
+//! BIP-39 mnemonic, so that a single phrase is enough to back up every account.
+
+use crate::WalletType;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Derivation path of Ethereum accounts (BIP-44 coin type 60), without the account index.
+pub const ETH_DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0";
+/// Derivation path of Filecoin accounts (SLIP-44 coin type 461), without the account index.
+pub const FIL_DERIVATION_PATH_PREFIX: &str = "m/44'/461'/0'/0";
+
+/// Environment variable a mnemonic phrase is read from instead of prompting for it.
+pub const MNEMONIC_ENV: &str = "IPC_WALLET_MNEMONIC";
+
+/// Number of words in the mnemonics we generate, i.e. 256 bits of entropy.
+#[cfg(feature = "with-ethers")]
+const MNEMONIC_WORD_COUNT: usize = 24;
+
+/// The standard derivation path of the account at `index` for the wallet type.
+pub fn derivation_path(wallet_type: &WalletType, index: u32) -> String {
+    let prefix = match wallet_type {
+        WalletType::Evm => ETH_DERIVATION_PATH_PREFIX,
+        WalletType::Fvm => FIL_DERIVATION_PATH_PREFIX,
+    };
+    format!("{prefix}/{index}")
+}
+
+/// Generate a new random mnemonic phrase in English.
+#[cfg(feature = "with-ethers")]
+pub fn generate_mnemonic() -> Result<Zeroizing<String>> {
+    use ethers::signers::coins_bip39::{English, Mnemonic};
+
+    let mnemonic =
+        Mnemonic::<English>::new_with_count(&mut rand::thread_rng(), MNEMONIC_WORD_COUNT)?;
+    Ok(Zeroizing::new(mnemonic.to_phrase()))
+}
+
+/// Derive the secp256k1 private key at the BIP-32 derivation `path` from a mnemonic phrase.
+#[cfg(feature = "with-ethers")]
+pub fn derive_secp256k1_key(phrase: &str, path: &str) -> Result<Zeroizing<Vec<u8>>> {
+    use ethers::signers::coins_bip39::{English, Mnemonic};
+
+    let phrase = normalize_mnemonic(phrase);
+    let mnemonic = Mnemonic::<English>::new_from_phrase(&phrase)
+        .map_err(|e| anyhow!("invalid mnemonic: {e}"))?;
+    let xpriv = mnemonic
+        .derive_key(path, None)
+        .map_err(|e| anyhow!("failed to derive key at {path}: {e}"))?;
+    let key: &ethers::core::k256::ecdsa::SigningKey = xpriv.as_ref();
+
+    Ok(Zeroizing::new(key.to_bytes().to_vec()))
+}
+
+/// Collapse the whitespace between the words, so phrases copied from a backup still match.
+pub fn normalize_mnemonic(phrase: &str) -> Zeroizing<String> {
+    Zeroizing::new(phrase.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// The mnemonic written to disk when the key store is not encrypted.
+#[derive(Serialize, Deserialize)]
+struct PersistentMnemonic {
+    mnemonic: String,
+}
+
+impl Drop for PersistentMnemonic {
+    fn drop(&mut self) {
+        self.mnemonic.zeroize();
+    }
+}
+
+/// Write the mnemonic to `path`, either as plain JSON or, with a passphrase, as a v3 keystore
+/// file holding the phrase instead of a private key.
+pub(crate) fn write_mnemonic_file(
+    path: &Path,
+    phrase: &str,
+    passphrase: Option<&str>,
+) -> Result<()> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow!("mnemonic file has no parent directory: {path:?}"))?;
+    fs::create_dir_all(dir)?;
+
+    match passphrase {
+        Some(passphrase) => {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| anyhow!("invalid mnemonic file name: {path:?}"))?;
+            eth_keystore::encrypt_key(
+                dir,
+                &mut rand::thread_rng(),
+                phrase.as_bytes(),
+                passphrase,
+                Some(name),
+            )
+            .map_err(|e| anyhow!("failed to encrypt mnemonic: {e}"))?;
+        }
+        None => {
+            let persisted = PersistentMnemonic {
+                mnemonic: phrase.to_string(),
+            };
+            fs::write(path, serde_json::to_string_pretty(&persisted)?)?;
+        }
+    }
+
+    #[cfg(unix)]
+    crate::set_user_perm(&fs::File::open(path)?)?;
+
+    Ok(())
+}
+
+/// Read the mnemonic written by [write_mnemonic_file], if the file exists.
+pub(crate) fn read_mnemonic_file(
+    path: &Path,
+    passphrase: Option<&str>,
+) -> Result<Option<Zeroizing<String>>> {
+    if let Err(e) = fs::metadata(path) {
+        return if e.kind() == ErrorKind::NotFound {
+            Ok(None)
+        } else {
+            Err(anyhow!("cannot read mnemonic file {path:?}: {e}"))
+        };
+    }
+
+    let phrase = match passphrase {
+        Some(passphrase) => {
+            let bytes = Zeroizing::new(
+                eth_keystore::decrypt_key(path, passphrase)
+                    .map_err(|e| anyhow!("failed to decrypt mnemonic file {path:?}: {e}"))?,
+            );
+            String::from_utf8(bytes.to_vec()).map_err(|_| anyhow!("mnemonic is not valid utf8"))?
+        }
+        None => {
+            let content = Zeroizing::new(fs::read_to_string(path)?);
+            let persisted: PersistentMnemonic = serde_json::from_str(&content)
+                .map_err(|e| anyhow!("failed to deserialize mnemonic file {path:?}: {e}"))?;
+            persisted.mnemonic.clone()
+        }
+    };
+
+    Ok(Some(Zeroizing::new(phrase)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{derivation_path, read_mnemonic_file, write_mnemonic_file};
+    use crate::WalletType;
+
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[cfg(feature = "with-ethers")]
+    #[test]
+    fn test_derive_known_account() {
+        use super::{derive_secp256k1_key, generate_mnemonic};
+
+        // The first development account of anvil and hardhat.
+        let path = derivation_path(&WalletType::Evm, 0);
+        let key = derive_secp256k1_key(PHRASE, &path).unwrap();
+        assert_eq!(
+            hex::encode(key.as_slice()),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+
+        // Whitespace differences don't matter.
+        let spaced = format!("  {}\n", PHRASE.replace(' ', "   "));
+        assert_eq!(derive_secp256k1_key(&spaced, &path).unwrap(), key);
+
+        // Filecoin accounts use a different path.
+        let fil_path = derivation_path(&WalletType::Fvm, 0);
+        assert_eq!(fil_path, "m/44'/461'/0'/0/0");
+        assert_ne!(derive_secp256k1_key(PHRASE, &fil_path).unwrap(), key);
+
+        // A typo fails the checksum.
+        assert!(derive_secp256k1_key(&PHRASE.replace("junk", "test"), &path).is_err());
+
+        let phrase = generate_mnemonic().unwrap();
+        assert_eq!(phrase.split(' ').count(), 24);
+        assert!(derive_secp256k1_key(&phrase, &path).is_ok());
+    }
+
+    #[test]
+    fn test_read_write_mnemonic_file() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("plain.json");
+        assert!(read_mnemonic_file(&path, None).unwrap().is_none());
+        write_mnemonic_file(&path, PHRASE, None).unwrap();
+        assert_eq!(
+            read_mnemonic_file(&path, None).unwrap().unwrap().as_str(),
+            PHRASE
+        );
+
+        let path = dir.path().join("encrypted");
+        write_mnemonic_file(&path, PHRASE, Some("secret")).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("junk"));
+        assert_eq!(
+            read_mnemonic_file(&path, Some("secret"))
+                .unwrap()
+                .unwrap()
+                .as_str(),
+            PHRASE
+        );
+        assert!(read_mnemonic_file(&path, Some("wrong")).is_err());
+    }
+}
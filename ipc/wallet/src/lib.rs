@@ -8,14 +8,23 @@ use serde::{Deserialize, Serialize};
 
 mod evm;
 mod fvm;
+mod hd;
 
-#[cfg(feature = "with-ethers")]
-pub use crate::evm::{random_eth_key_info, EthKeyAddress};
 pub use crate::evm::{
-    KeyInfo as EvmKeyInfo, KeyStore as EvmKeyStore, PersistentKeyInfo, PersistentKeyStore,
-    DEFAULT_KEYSTORE_NAME,
+    decrypt_key_file as decrypt_evm_key_file, encrypt_key_file as encrypt_evm_key_file,
+    read_passphrase, KeyInfo as EvmKeyInfo, KeyStore as EvmKeyStore, PersistentKeyInfo,
+    PersistentKeyStore, DEFAULT_KEYSTORE_NAME, ENCRYPTED_EVM_KEYSTORE_NAME,
+    EVM_KEYSTORE_PASSPHRASE_ENV, KEYSTORE_FILE_PASSPHRASE_ENV,
 };
+#[cfg(feature = "with-ethers")]
+pub use crate::evm::{random_eth_key_info, EthKeyAddress};
 pub use crate::fvm::*;
+pub use crate::hd::{
+    derivation_path, normalize_mnemonic, ETH_DERIVATION_PATH_PREFIX, FIL_DERIVATION_PATH_PREFIX,
+    MNEMONIC_ENV,
+};
+#[cfg(feature = "with-ethers")]
+pub use crate::hd::{derive_secp256k1_key, generate_mnemonic};
 
 /// WalletType determines the kind of keys and wallets
 /// supported in the keystore
@@ -4,7 +4,7 @@
 use std::{
     collections::{HashMap, VecDeque},
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use libipld::{store::StoreParams, Cid};
@@ -86,6 +86,11 @@ pub struct Behaviour<P: StoreParams> {
     rate_limiter: RateLimiter<Multiaddr>,
     rate_limit_period: Duration,
     rate_limit: Option<RateLimit>,
+    /// The number of bytes each address can consume in a period; 0 means no limit.
+    rate_limit_bytes: u32,
+    /// Bytes served to each address since the start of the current period, for reporting.
+    rate_limit_usage: HashMap<Multiaddr, u64>,
+    rate_limit_usage_since: Instant,
     outbox: VecDeque<Event>,
 }
 
@@ -109,6 +114,9 @@ impl<P: StoreParams> Behaviour<P> {
             rate_limiter: RateLimiter::new(config.rate_limit_period),
             rate_limit_period: config.rate_limit_period,
             rate_limit,
+            rate_limit_bytes: config.rate_limit_bytes,
+            rate_limit_usage: Default::default(),
+            rate_limit_usage_since: Instant::now(),
             outbox: Default::default(),
         }
     }
@@ -161,23 +169,59 @@ impl<P: StoreParams> Behaviour<P> {
     }
 
     /// Callback by the service after [`Event::BitswapForward`].
-    pub fn rate_limit_used(&mut self, peer_id: PeerId, bytes: usize) {
+    ///
+    /// Returns `false` if the peer has exceeded its rate limit.
+    pub fn rate_limit_used(&mut self, peer_id: PeerId, bytes: usize) -> bool {
         if let Some(ref rate_limit) = self.rate_limit {
             if let Some(addr) = self.peer_addresses.get(&peer_id).cloned() {
+                if self.rate_limit_usage_since.elapsed() >= self.rate_limit_period {
+                    self.rate_limit_usage.clear();
+                    self.rate_limit_usage_since = Instant::now();
+                }
+                *self.rate_limit_usage.entry(addr.clone()).or_default() += bytes as u64;
+
                 let bytes = bytes.try_into().unwrap_or(u32::MAX);
-                let _ = self.rate_limiter.add(rate_limit, addr, bytes);
+                if !self.rate_limiter.add(rate_limit, addr, bytes) {
+                    stats::CONTENT_RATE_LIMITED.inc();
+                    return false;
+                }
             }
         }
+        true
     }
 
     /// Update the rate limit to a new value, keeping the period as-is.
     pub fn update_rate_limit(&mut self, bytes: u32) {
+        self.rate_limit_bytes = bytes;
         if bytes == 0 || self.rate_limit_period.is_zero() {
             self.rate_limit = None;
         } else {
             self.rate_limit = Some(RateLimit::new(bytes, self.rate_limit_period))
         }
     }
+
+    /// The address a connected peer is rate limited by.
+    pub fn peer_address(&self, peer_id: &PeerId) -> Option<&Multiaddr> {
+        self.peer_addresses.get(peer_id)
+    }
+
+    /// The number of bytes each address can consume in a period, and the length of the period.
+    pub fn rate_limit(&self) -> (u32, Duration) {
+        (self.rate_limit_bytes, self.rate_limit_period)
+    }
+
+    /// Bytes served to the address of a peer in the current period.
+    pub fn rate_limit_usage(&self, peer_id: &PeerId) -> u64 {
+        self.peer_address(peer_id)
+            .and_then(|addr| self.rate_limit_usage.get(addr))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Bytes served to all addresses in the current period.
+    pub fn total_rate_limit_usage(&self) -> u64 {
+        self.rate_limit_usage.values().sum()
+    }
 }
 
 impl<P: StoreParams> NetworkBehaviour for Behaviour<P> {
@@ -0,0 +1,470 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use libp2p::core::Endpoint;
+use libp2p::identity::PublicKey;
+use libp2p::swarm::derive_prelude::FromSwarm;
+use libp2p::swarm::{
+    dummy, CloseConnection, ConnectionDenied, ConnectionId, NetworkBehaviour, THandler,
+    THandlerInEvent, THandlerOutEvent, ToSwarm,
+};
+use libp2p::{Multiaddr, PeerId};
+use log::{debug, info};
+
+use crate::stats;
+
+/// Events emitted by the [`gating::Behaviour`] behaviour.
+#[derive(Debug)]
+pub enum Event {
+    /// A peer has exhausted its reputation and is banned until the given time.
+    Banned(PeerId, Instant),
+}
+
+/// Configuration for [`gating::Behaviour`].
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Peers which are always allowed to connect, even in validator-only mode,
+    /// e.g. bootstrap nodes. They can still be banned for misbehaving.
+    pub allow_list: Vec<PeerId>,
+    /// Peers which are never allowed to connect.
+    pub deny_list: Vec<PeerId>,
+    /// Only allow connections from peers whose key is in the current validator set, or in the allow list.
+    pub validator_only: bool,
+    /// Penalty score at which a peer gets banned.
+    pub ban_threshold: u32,
+    /// How long a banned peer is kept from connecting.
+    pub ban_duration: Duration,
+    /// How long it takes for penalties worth the ban threshold to be forgiven;
+    /// penalties decay linearly over time, zero means they never decay.
+    pub penalty_decay: Duration,
+    /// Maximum number of peers to keep penalties for; when full, the least penalized peer is forgotten.
+    pub max_penalized_peers: usize,
+}
+
+/// Reasons for penalizing a peer, decreasing its local reputation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Misbehaviour {
+    /// The peer doesn't support a protocol every participant is expected to.
+    UnsupportedProtocol,
+    /// The peer sent us a message which could not be parsed or had an invalid signature.
+    InvalidMessage,
+    /// The peer exceeded its quota of content served to it.
+    RateLimitExceeded,
+}
+
+impl Misbehaviour {
+    /// Penalty score added to the peer, relative to the default ban threshold of 100.
+    pub fn penalty(&self) -> u32 {
+        match self {
+            Misbehaviour::UnsupportedProtocol => 100,
+            Misbehaviour::InvalidMessage => 20,
+            Misbehaviour::RateLimitExceeded => 10,
+        }
+    }
+}
+
+/// Reasons for denying a connection.
+#[derive(thiserror::Error, Debug)]
+pub enum Denied {
+    #[error("peer {0} is on the deny list")]
+    DenyList(PeerId),
+    #[error("peer {0} is banned")]
+    Banned(PeerId),
+    #[error("peer {0} is not a validator")]
+    NotValidator(PeerId),
+}
+
+/// Decide which peers we keep connections with, based on static allow and deny lists,
+/// the local reputation of peers, and optionally the current validator set.
+///
+/// Other behaviours or the service report misbehaviour with [`Behaviour::penalize`];
+/// once the penalties add up to the threshold the peer is disconnected and banned
+/// for some time, after which it starts with a clean slate.
+pub struct Behaviour {
+    config: Config,
+    /// Peers on the allow list.
+    allowed: HashSet<PeerId>,
+    /// Peers on the deny list.
+    denied: HashSet<PeerId>,
+    /// Peer IDs of the current validators; empty until we learn about them.
+    validators: HashSet<PeerId>,
+    /// Accumulated penalties of peers which haven't been banned (yet), with the time they were last updated.
+    penalties: HashMap<PeerId, (u32, Instant)>,
+    /// Banned peers with the time their ban expires.
+    banned: HashMap<PeerId, Instant>,
+    /// Peers we currently have at least one connection with.
+    connected: HashSet<PeerId>,
+    /// Events to emit and connections to close.
+    outbox: VecDeque<ToSwarm<Event, THandlerInEvent<Self>>>,
+}
+
+impl Behaviour {
+    pub fn new(config: Config) -> Self {
+        Self {
+            allowed: config.allow_list.iter().cloned().collect(),
+            denied: config.deny_list.iter().cloned().collect(),
+            validators: Default::default(),
+            penalties: Default::default(),
+            banned: Default::default(),
+            connected: Default::default(),
+            outbox: Default::default(),
+            config,
+        }
+    }
+
+    /// Replace the current validator set.
+    ///
+    /// In validator-only mode this disconnects every peer which is no longer allowed.
+    /// Until the first non-empty set is given, everyone is allowed to connect, otherwise
+    /// we could not bootstrap before the validators are known.
+    pub fn set_validators(&mut self, validators: Vec<PublicKey>) {
+        self.validators = validators.into_iter().map(|pk| pk.to_peer_id()).collect();
+
+        stats::GATING_VALIDATORS.set(self.validators.len() as i64);
+
+        if !self.config.validator_only {
+            return;
+        }
+
+        let disallowed = self
+            .connected
+            .iter()
+            .filter(|peer_id| self.check(peer_id).is_err())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for peer_id in disallowed {
+            debug!("disconnecting non-validator peer {peer_id}");
+            self.close(peer_id);
+        }
+    }
+
+    /// Add a penalty to the reputation of a peer, banning it if it reaches the threshold.
+    pub fn penalize(&mut self, peer_id: PeerId, misbehaviour: Misbehaviour) {
+        self.penalize_at(peer_id, misbehaviour, Instant::now())
+    }
+
+    /// Same as [`Behaviour::penalize`] but allows passing in the time, for testing.
+    fn penalize_at(&mut self, peer_id: PeerId, misbehaviour: Misbehaviour, at: Instant) {
+        stats::GATING_PENALTIES.inc();
+
+        let penalty = self
+            .penalties
+            .remove(&peer_id)
+            .map(|(penalty, since)| decayed(&self.config, penalty, since, at))
+            .unwrap_or_default()
+            .saturating_add(misbehaviour.penalty());
+
+        debug!("penalized peer {peer_id} for {misbehaviour:?}; total penalty: {penalty}");
+
+        if penalty >= self.config.ban_threshold {
+            self.ban_at(peer_id, at);
+        } else {
+            self.insert_penalty(peer_id, penalty, at);
+        }
+    }
+
+    /// Remember the penalty of a peer, making room for it if we track too many peers already.
+    fn insert_penalty(&mut self, peer_id: PeerId, penalty: u32, at: Instant) {
+        if self.penalties.len() >= self.config.max_penalized_peers {
+            let config = &self.config;
+            self.penalties
+                .retain(|_, (penalty, since)| decayed(config, *penalty, *since, at) > 0);
+
+            if self.penalties.len() >= self.config.max_penalized_peers {
+                let least = self
+                    .penalties
+                    .iter()
+                    .min_by_key(|(_, (penalty, since))| decayed(config, *penalty, *since, at))
+                    .map(|(peer_id, _)| *peer_id);
+
+                if let Some(least) = least {
+                    self.penalties.remove(&least);
+                }
+            }
+        }
+        if self.config.max_penalized_peers > 0 {
+            self.penalties.insert(peer_id, (penalty, at));
+        }
+    }
+
+    /// The current penalty of a peer, for testing.
+    #[cfg(test)]
+    fn penalty_at(&self, peer_id: &PeerId, at: Instant) -> u32 {
+        self.penalties
+            .get(peer_id)
+            .map(|(penalty, since)| decayed(&self.config, *penalty, *since, at))
+            .unwrap_or_default()
+    }
+
+    /// Ban a peer for the configured duration and disconnect it.
+    fn ban_at(&mut self, peer_id: PeerId, at: Instant) {
+        let until = at + self.config.ban_duration;
+
+        info!("banning peer {peer_id} for {:?}", self.config.ban_duration);
+        stats::GATING_BANNED.inc();
+
+        // Forget about expired bans while we're at it.
+        self.banned.retain(|_, expiry| *expiry > at);
+        self.banned.insert(peer_id, until);
+
+        self.outbox
+            .push_back(ToSwarm::GenerateEvent(Event::Banned(peer_id, until)));
+
+        self.close(peer_id);
+    }
+
+    /// Check whether a peer is currently banned.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.is_banned_at(peer_id, Instant::now())
+    }
+
+    fn is_banned_at(&self, peer_id: &PeerId, at: Instant) -> bool {
+        self.banned
+            .get(peer_id)
+            .map(|expiry| *expiry > at)
+            .unwrap_or_default()
+    }
+
+    /// Check whether a peer is allowed to connect.
+    fn check(&self, peer_id: &PeerId) -> Result<(), Denied> {
+        if self.denied.contains(peer_id) {
+            return Err(Denied::DenyList(*peer_id));
+        }
+        if self.is_banned(peer_id) {
+            return Err(Denied::Banned(*peer_id));
+        }
+        if self.config.validator_only
+            && !self.validators.is_empty()
+            && !self.validators.contains(peer_id)
+            && !self.allowed.contains(peer_id)
+        {
+            return Err(Denied::NotValidator(*peer_id));
+        }
+        Ok(())
+    }
+
+    fn deny(&self, peer_id: &PeerId) -> Result<(), ConnectionDenied> {
+        self.check(peer_id).map_err(|e| {
+            stats::GATING_DENIED.inc();
+            debug!("denied connection: {e}");
+            ConnectionDenied::new(e)
+        })
+    }
+
+    fn close(&mut self, peer_id: PeerId) {
+        if self.connected.contains(&peer_id) {
+            self.outbox.push_back(ToSwarm::CloseConnection {
+                peer_id,
+                connection: CloseConnection::All,
+            });
+        }
+    }
+}
+
+/// Decrease a penalty in proportion to the time elapsed since it was last updated.
+fn decayed(config: &Config, penalty: u32, since: Instant, at: Instant) -> u32 {
+    let decay = config.penalty_decay.as_millis();
+    if decay == 0 {
+        return penalty;
+    }
+    let elapsed = at.saturating_duration_since(since).as_millis();
+    let forgiven = elapsed * config.ban_threshold as u128 / decay;
+    penalty.saturating_sub(forgiven.try_into().unwrap_or(u32::MAX))
+}
+
+impl NetworkBehaviour for Behaviour {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Event;
+
+    fn on_swarm_event(&mut self, event: FromSwarm) {
+        match event {
+            FromSwarm::ConnectionEstablished(c) => {
+                self.connected.insert(c.peer_id);
+            }
+            FromSwarm::ConnectionClosed(c) if c.remaining_established == 0 => {
+                self.connected.remove(&c.peer_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        _peer_id: PeerId,
+        _connection_id: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn handle_pending_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        _local_addr: &Multiaddr,
+        _remote_addr: &Multiaddr,
+    ) -> Result<(), ConnectionDenied> {
+        Ok(())
+    }
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        peer: PeerId,
+        _local_addr: &Multiaddr,
+        _remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.deny(&peer)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_pending_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        maybe_peer: Option<PeerId>,
+        _addresses: &[Multiaddr],
+        _effective_role: Endpoint,
+    ) -> Result<Vec<Multiaddr>, ConnectionDenied> {
+        if let Some(peer_id) = maybe_peer {
+            self.deny(&peer_id)?;
+        }
+        Ok(vec![])
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        peer: PeerId,
+        _addr: &Multiaddr,
+        _role_override: Endpoint,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.deny(&peer)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn poll(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        if let Some(ev) = self.outbox.pop_front() {
+            return Poll::Ready(ev);
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use libp2p::identity::Keypair;
+    use libp2p::PeerId;
+
+    use super::{Behaviour, Config, Denied, Misbehaviour};
+
+    fn make_behaviour(validator_only: bool, allow_list: Vec<PeerId>) -> Behaviour {
+        Behaviour::new(Config {
+            allow_list,
+            deny_list: vec![],
+            validator_only,
+            ban_threshold: 100,
+            ban_duration: Duration::from_secs(60),
+            penalty_decay: Duration::from_secs(100),
+            max_penalized_peers: 3,
+        })
+    }
+
+    #[test]
+    fn ban_after_threshold() {
+        let mut gating = make_behaviour(false, vec![]);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            gating.penalize_at(peer_id, Misbehaviour::InvalidMessage, now);
+        }
+        assert!(!gating.is_banned_at(&peer_id, now));
+        assert!(gating.check(&peer_id).is_ok());
+
+        gating.penalize_at(peer_id, Misbehaviour::InvalidMessage, now);
+        assert!(gating.is_banned_at(&peer_id, now));
+        assert!(matches!(gating.check(&peer_id), Err(Denied::Banned(_))));
+
+        assert!(
+            !gating.is_banned_at(&peer_id, now + Duration::from_secs(61)),
+            "bans expire"
+        );
+        assert!(
+            !gating.penalties.contains_key(&peer_id),
+            "starts with a clean slate"
+        );
+    }
+
+    #[test]
+    fn penalties_decay() {
+        let mut gating = make_behaviour(false, vec![]);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            gating.penalize_at(peer_id, Misbehaviour::InvalidMessage, now);
+        }
+        assert_eq!(gating.penalty_at(&peer_id, now), 80);
+        assert_eq!(
+            gating.penalty_at(&peer_id, now + Duration::from_secs(30)),
+            50
+        );
+
+        let later = now + Duration::from_secs(30);
+        gating.penalize_at(peer_id, Misbehaviour::InvalidMessage, later);
+        assert!(
+            !gating.is_banned_at(&peer_id, later),
+            "decayed penalties don't add up to a ban"
+        );
+        assert_eq!(gating.penalty_at(&peer_id, later), 70);
+        assert_eq!(
+            gating.penalty_at(&peer_id, later + Duration::from_secs(100)),
+            0
+        );
+    }
+
+    #[test]
+    fn penalized_peers_capped() {
+        let mut gating = make_behaviour(false, vec![]);
+        let now = Instant::now();
+        let peers = (0..4).map(|_| PeerId::random()).collect::<Vec<_>>();
+
+        gating.penalize_at(peers[0], Misbehaviour::InvalidMessage, now);
+        gating.penalize_at(peers[1], Misbehaviour::RateLimitExceeded, now);
+        gating.penalize_at(peers[2], Misbehaviour::InvalidMessage, now);
+        gating.penalize_at(peers[3], Misbehaviour::InvalidMessage, now);
+
+        assert_eq!(gating.penalties.len(), 3);
+        assert!(
+            !gating.penalties.contains_key(&peers[1]),
+            "the least penalized peer is forgotten"
+        );
+        assert_eq!(gating.penalty_at(&peers[3], now), 20);
+    }
+
+    #[test]
+    fn validator_only() {
+        let validator = Keypair::generate_secp256k1().public();
+        let bootstrap = PeerId::random();
+        let other = PeerId::random();
+
+        let mut gating = make_behaviour(true, vec![bootstrap]);
+        assert!(
+            gating.check(&other).is_ok(),
+            "everyone is allowed until the validators are known"
+        );
+
+        gating.set_validators(vec![validator.clone()]);
+        assert!(gating.check(&validator.to_peer_id()).is_ok());
+        assert!(gating.check(&bootstrap).is_ok());
+        assert!(matches!(gating.check(&other), Err(Denied::NotValidator(_))));
+    }
+}
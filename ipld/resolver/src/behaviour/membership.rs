@@ -9,8 +9,8 @@ use anyhow::anyhow;
 use ipc_api::subnet_id::SubnetID;
 use libp2p::core::Endpoint;
 use libp2p::gossipsub::{
-    self, IdentTopic, MessageAuthenticity, MessageId, PublishError, Sha256Topic, SubscriptionError,
-    Topic, TopicHash,
+    self, IdentTopic, MessageAcceptance, MessageAuthenticity, MessageId, PublishError, Sha256Topic,
+    SubscriptionError, Topic, TopicHash,
 };
 use libp2p::identity::Keypair;
 use libp2p::swarm::derive_prelude::FromSwarm;
@@ -27,7 +27,7 @@ use tokio::time::{Instant, Interval};
 use crate::hash::blake2b_256;
 use crate::provider_cache::{ProviderDelta, SubnetProviderCache};
 use crate::provider_record::{ProviderRecord, SignedProviderRecord};
-use crate::vote_record::{SignedVoteRecord, VoteRecord};
+use crate::vote_record::SignedVoteRecord;
 use crate::{stats, Timestamp};
 
 use super::NetworkConfig;
@@ -53,11 +53,14 @@ pub enum Event<V> {
     /// to trigger a lookup by the discovery module to learn the address.
     Skipped(PeerId),
 
-    /// We received a [`VoteRecord`] in one of the subnets we are providing data for.
-    ReceivedVote(Box<VoteRecord<V>>),
+    /// We received a [`SignedVoteRecord`] in one of the subnets we are providing data for.
+    ReceivedVote(Box<SignedVoteRecord<V>>),
 
     /// We received preemptive data published in a subnet we were interested in.
     ReceivedPreemptive(SubnetID, Vec<u8>),
+
+    /// A peer authored a message which could not be parsed or had an invalid signature.
+    InvalidMessage(PeerId),
 }
 
 /// Configuration for [`membership::Behaviour`].
@@ -139,6 +142,8 @@ where
             let s = blake2b_256(&msg.data);
             MessageId::from(s)
         });
+        // Only forward messages after we have validated them, so honest peers don't spread invalid ones.
+        gossipsub_config.validate_messages();
 
         let gossipsub_config = gossipsub_config
             .build()
@@ -398,42 +403,107 @@ where
         self.provider_cache.providers_of_subnet(subnet_id)
     }
 
+    /// List the subnets a peer is known to provide data for.
+    pub fn subnets_of_provider(&self, peer_id: &PeerId) -> Vec<SubnetID> {
+        self.provider_cache.subnets_of_provider(peer_id)
+    }
+
+    /// Check whether we know the address of a peer.
+    pub fn is_routable(&self, peer_id: &PeerId) -> bool {
+        self.provider_cache.is_routable(peer_id)
+    }
+
+    /// Number of peers we know the address of.
+    pub fn num_routable(&self) -> usize {
+        self.provider_cache.num_routable()
+    }
+
+    /// Number of subnets we know providers of.
+    pub fn num_subnets(&self) -> usize {
+        self.provider_cache.num_subnets()
+    }
+
+    /// The subnets this node provides data for.
+    pub fn provided_subnets(&self) -> Vec<SubnetID> {
+        self.subnet_ids.clone()
+    }
+
+    /// The subnets which will never be pruned from the cache.
+    pub fn pinned_subnets(&self) -> Vec<SubnetID> {
+        self.provider_cache.pinned_subnets()
+    }
+
     /// Parse and handle a [`gossipsub::Message`]. If it's from the expected topic,
     /// then raise domain event to let the rest of the application know about a
     /// provider. Also update all the book keeping in the behaviour that we use
     /// to answer future queries about the topic.
-    fn handle_message(&mut self, msg: gossipsub::Message) {
-        if msg.topic == self.membership_topic.hash() {
+    ///
+    /// Gossipsub only forwards the message once we report it as valid. Invalid messages are
+    /// rejected, which lowers the Gossipsub score of the peer who forwarded it, while the
+    /// signed author of the message is reported to be penalized locally.
+    fn handle_message(
+        &mut self,
+        propagation_source: PeerId,
+        message_id: MessageId,
+        msg: gossipsub::Message,
+    ) {
+        let acceptance = if msg.topic == self.membership_topic.hash() {
             match SignedProviderRecord::from_bytes(&msg.data).map(|r| r.into_record()) {
-                Ok(record) => self.handle_provider_record(record),
+                Ok(record) => {
+                    self.handle_provider_record(record);
+                    MessageAcceptance::Accept
+                }
                 Err(e) => {
                     stats::MEMBERSHIP_INVALID_MESSAGE.inc();
                     warn!(
                         "Gossip message from peer {:?} could not be deserialized as ProviderRecord: {e}",
                         msg.source
                     );
+                    self.report_invalid(msg.source)
                 }
             }
         } else if self.voting_topics.contains(&msg.topic) {
-            match SignedVoteRecord::from_bytes(&msg.data).map(|r| r.into_record()) {
-                Ok(record) => self.handle_vote_record(record),
+            match SignedVoteRecord::from_bytes(&msg.data) {
+                Ok(record) => {
+                    self.handle_vote_record(record);
+                    MessageAcceptance::Accept
+                }
                 Err(e) => {
                     stats::MEMBERSHIP_INVALID_MESSAGE.inc();
                     warn!(
                         "Gossip message from peer {:?} could not be deserialized as VoteRecord: {e}",
                         msg.source
                     );
+                    self.report_invalid(msg.source)
                 }
             }
         } else if let Some(subnet_id) = self.preemptive_topics.get(&msg.topic) {
-            self.handle_preemptive_data(subnet_id.clone(), msg.data)
+            self.handle_preemptive_data(subnet_id.clone(), msg.data);
+            MessageAcceptance::Accept
         } else {
             stats::MEMBERSHIP_UNKNOWN_TOPIC.inc();
             warn!(
                 "unknown gossipsub topic in message from {:?}: {}",
                 msg.source, msg.topic
             );
+            MessageAcceptance::Ignore
+        };
+
+        if let Err(e) = self.inner.report_message_validation_result(
+            &message_id,
+            &propagation_source,
+            acceptance,
+        ) {
+            debug!("failed to report validation result of message {message_id}: {e}");
+        }
+    }
+
+    /// Report the author of an invalid message, if known, and reject the message.
+    fn report_invalid(&mut self, source: Option<PeerId>) -> MessageAcceptance {
+        if let Some(source) = source {
+            self.outbox.push_back(Event::InvalidMessage(source));
         }
+        MessageAcceptance::Reject
     }
 
     /// Try to add a provider record to the cache.
@@ -465,7 +535,7 @@ where
     }
 
     /// Raise an event to tell we received a new vote.
-    fn handle_vote_record(&mut self, record: VoteRecord<V>) {
+    fn handle_vote_record(&mut self, record: SignedVoteRecord<V>) {
         self.outbox.push_back(Event::ReceivedVote(Box::new(record)))
     }
 
@@ -632,8 +702,12 @@ where
                         gossipsub::Event::GossipsubNotSupported { peer_id } => {
                             debug!("peer {peer_id} doesn't support gossipsub");
                         }
-                        gossipsub::Event::Message { message, .. } => {
-                            self.handle_message(message);
+                        gossipsub::Event::Message {
+                            propagation_source,
+                            message_id,
+                            message,
+                        } => {
+                            self.handle_message(propagation_source, message_id, message);
                         }
                     }
                 }
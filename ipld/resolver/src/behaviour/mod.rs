@@ -12,10 +12,12 @@ use libp2p_bitswap::BitswapStore;
 
 pub mod content;
 pub mod discovery;
+pub mod gating;
 pub mod membership;
 
 pub use content::Config as ContentConfig;
 pub use discovery::Config as DiscoveryConfig;
+pub use gating::Config as GatingConfig;
 pub use membership::Config as MembershipConfig;
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -46,6 +48,7 @@ pub enum ConfigError {
 
 /// Libp2p behaviour bundle to manage content resolution from other subnets, using:
 ///
+/// * Connection gating to keep out banned and unwanted peers
 /// * Kademlia for peer discovery
 /// * Gossipsub to advertise subnet membership
 /// * Bitswap to resolve CIDs
@@ -54,6 +57,7 @@ pub struct Behaviour<P, V>
 where
     P: StoreParams,
 {
+    gating: gating::Behaviour,
     ping: ping::Behaviour,
     identify: identify::Behaviour,
     discovery: discovery::Behaviour,
@@ -77,12 +81,14 @@ where
         dc: DiscoveryConfig,
         mc: MembershipConfig,
         cc: ContentConfig,
+        gc: GatingConfig,
         store: S,
     ) -> Result<Self, ConfigError>
     where
         S: BitswapStore<Params = P>,
     {
         Ok(Self {
+            gating: gating::Behaviour::new(gc),
             ping: Default::default(),
             identify: identify::Behaviour::new(identify::Config::new(
                 "ipfs/1.0.0".into(),
@@ -105,4 +111,8 @@ where
     pub fn content_mut(&mut self) -> &mut content::Behaviour<P> {
         &mut self.content
     }
+
+    pub fn gating_mut(&mut self) -> &mut gating::Behaviour {
+        &mut self.gating
+    }
 }
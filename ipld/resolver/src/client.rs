@@ -4,11 +4,13 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use ipc_api::subnet_id::SubnetID;
 use libipld::Cid;
+use libp2p::identity::PublicKey;
+use libp2p::PeerId;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 
 use crate::{
-    service::{Request, ResolveResult},
+    service::{PeerInfo, Request, ResolveResult, ServiceStats},
     vote_record::SignedVoteRecord,
 };
 
@@ -60,6 +62,13 @@ impl<V> Client<V> {
         self.send_request(req)
     }
 
+    /// Set the keys of the current validators, which are the only peers allowed
+    /// to connect in validator-only mode, apart from the ones on the allow list.
+    pub fn set_validators(&self, validators: Vec<PublicKey>) -> anyhow::Result<()> {
+        let req = Request::SetValidators(validators);
+        self.send_request(req)
+    }
+
     /// Update the rate limit based on new projections for the same timeframe
     /// the `content::Behaviour` was originally configured with. This can be
     /// used if we can't come up with a good estimate for the amount of data
@@ -76,6 +85,30 @@ impl<V> Client<V> {
         self.send_request(req)
     }
 
+    /// List the currently connected peers.
+    pub async fn list_peers(&self) -> anyhow::Result<Vec<PeerInfo>> {
+        let (tx, rx) = oneshot::channel();
+        self.send_request(Request::ListPeers(tx))?;
+        let peers = rx.await?;
+        Ok(peers)
+    }
+
+    /// List the known providers of a subnet.
+    pub async fn list_providers(&self, subnet_id: SubnetID) -> anyhow::Result<Vec<PeerId>> {
+        let (tx, rx) = oneshot::channel();
+        self.send_request(Request::ListProviders(subnet_id, tx))?;
+        let providers = rx.await?;
+        Ok(providers)
+    }
+
+    /// Take a snapshot of the state of the [`Service`].
+    pub async fn stats(&self) -> anyhow::Result<ServiceStats> {
+        let (tx, rx) = oneshot::channel();
+        self.send_request(Request::Stats(tx))?;
+        let stats = rx.await?;
+        Ok(stats)
+    }
+
     /// Publish pre-emptively to a subnet that agents in the parent subnet
     /// would be subscribed to if they are interested in receiving data
     /// before they would have to use [`Client::resolve`] instead.
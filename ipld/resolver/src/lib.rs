@@ -7,6 +7,7 @@ mod limiter;
 mod service;
 mod stats;
 mod timestamp;
+mod transport;
 
 mod provider_cache;
 mod provider_record;
@@ -21,6 +22,6 @@ pub mod missing_blocks;
 
 pub use behaviour::{ContentConfig, DiscoveryConfig, MembershipConfig, NetworkConfig};
 pub use client::{Client, Resolver};
-pub use service::{Config, ConnectionConfig, Event, NoKnownPeers, Service};
+pub use service::{Config, ConnectionConfig, Event, NoKnownPeers, PeerInfo, Service, ServiceStats};
 pub use timestamp::Timestamp;
-pub use vote_record::{ValidatorKey, VoteRecord};
+pub use vote_record::{SignedVoteRecord, ValidatorKey, VoteRecord};
@@ -80,7 +80,7 @@ impl SubnetProviderCache {
     }
 
     /// Number of routable peers.
-    pub fn num_routable(&mut self) -> usize {
+    pub fn num_routable(&self) -> usize {
         self.routable_peers.len()
     }
 
@@ -204,6 +204,25 @@ impl SubnetProviderCache {
             .map(|hs| hs.iter().cloned().collect())
             .unwrap_or_default()
     }
+
+    /// List the subnets a peer is known to provide data for.
+    pub fn subnets_of_provider(&self, peer_id: &PeerId) -> Vec<SubnetID> {
+        self.subnet_providers
+            .iter()
+            .filter(|(_, providers)| providers.contains(peer_id))
+            .map(|(subnet_id, _)| subnet_id.clone())
+            .collect()
+    }
+
+    /// List the subnets which will never be pruned.
+    pub fn pinned_subnets(&self) -> Vec<SubnetID> {
+        self.pinned_subnets.iter().cloned().collect()
+    }
+
+    /// Number of subnets we know providers of.
+    pub fn num_subnets(&self) -> usize {
+        self.subnet_providers.len()
+    }
 }
 
 #[cfg(test)]
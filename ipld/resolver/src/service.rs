@@ -12,12 +12,11 @@ use libp2p::futures::StreamExt;
 use libp2p::swarm::SwarmEvent;
 use libp2p::{
     core::{muxing::StreamMuxerBox, transport::Boxed},
-    identity::Keypair,
-    noise, yamux, Multiaddr, PeerId, Swarm, Transport,
+    identity::{Keypair, PublicKey},
+    Multiaddr, PeerId, Swarm,
 };
 use libp2p::{identify, ping};
 use libp2p_bitswap::{BitswapResponse, BitswapStore};
-use libp2p_mplex::MplexConfig;
 use log::{debug, error, info, trace, warn};
 use prometheus::Registry;
 use rand::seq::SliceRandom;
@@ -29,12 +28,13 @@ use tokio::sync::mpsc;
 use tokio::sync::oneshot::{self, Sender};
 
 use crate::behaviour::{
-    self, content, discovery, membership, Behaviour, BehaviourEvent, ConfigError, ContentConfig,
-    DiscoveryConfig, MembershipConfig, NetworkConfig,
+    self, content, discovery, gating, membership, Behaviour, BehaviourEvent, ConfigError,
+    ContentConfig, DiscoveryConfig, GatingConfig, MembershipConfig, NetworkConfig,
 };
 use crate::client::Client;
 use crate::stats;
-use crate::vote_record::{SignedVoteRecord, VoteRecord};
+use crate::transport::{build_transport, transport_label};
+use crate::vote_record::SignedVoteRecord;
 
 /// Result of attempting to resolve a CID.
 pub type ResolveResult = anyhow::Result<()>;
@@ -62,8 +62,9 @@ pub struct NoKnownPeers(SubnetID);
 
 #[derive(Debug, Clone)]
 pub struct ConnectionConfig {
-    /// The address where we will listen to incoming connections.
-    pub listen_addr: Multiaddr,
+    /// The addresses where we will listen to incoming connections,
+    /// e.g. one for TCP and another for QUIC.
+    pub listen_addrs: Vec<Multiaddr>,
     /// A list of known external addresses this node is reachable on.
     pub external_addresses: Vec<Multiaddr>,
     /// Maximum number of incoming connections.
@@ -75,6 +76,21 @@ pub struct ConnectionConfig {
     /// Maximum number of events in the push-based broadcast channel before a slow
     /// consumer gets an error because it's falling behind.
     pub event_buffer_capacity: u32,
+    /// Peers which are always allowed to connect, even in validator-only mode.
+    pub allow_list: Vec<PeerId>,
+    /// Peers which are never allowed to connect.
+    pub deny_list: Vec<PeerId>,
+    /// Only allow connections from peers whose key is in the current validator set,
+    /// which has to be kept up to date with [`Client::set_validators`].
+    pub validator_only: bool,
+    /// Penalty score at which a misbehaving peer gets banned.
+    pub ban_threshold: u32,
+    /// How long a banned peer is kept from connecting.
+    pub ban_duration: Duration,
+    /// How long it takes for penalties worth the ban threshold to decay.
+    pub penalty_decay: Duration,
+    /// Maximum number of peers to keep penalties for.
+    pub max_penalized_peers: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -95,7 +111,11 @@ pub(crate) enum Request<V> {
     PublishPreemptive(SubnetID, Vec<u8>),
     PinSubnet(SubnetID),
     UnpinSubnet(SubnetID),
+    SetValidators(Vec<PublicKey>),
     Resolve(Cid, SubnetID, ResponseChannel),
+    ListPeers(oneshot::Sender<Vec<PeerInfo>>),
+    ListProviders(SubnetID, oneshot::Sender<Vec<PeerId>>),
+    Stats(oneshot::Sender<ServiceStats>),
     RateLimitUsed(PeerId, usize),
     UpdateRateLimit(u32),
 }
@@ -105,11 +125,50 @@ pub(crate) enum Request<V> {
 #[derive(Clone, Debug)]
 pub enum Event<V> {
     /// Received a vote about in a subnet about a CID.
-    ReceivedVote(Box<VoteRecord<V>>),
+    ///
+    /// The signature is kept so that votes can be presented to others as evidence.
+    ReceivedVote(Box<SignedVoteRecord<V>>),
     /// Received raw pre-emptive data published to a pinned subnet.
     ReceivedPreemptive(SubnetID, Vec<u8>),
 }
 
+/// Information about a connected peer.
+#[derive(Clone, Debug)]
+pub struct PeerInfo {
+    pub peer_id: PeerId,
+    /// The address the peer connected from or we dialed, which is used for rate limiting.
+    pub address: Option<Multiaddr>,
+    /// Whether the peer is in the routing table and its provider records are tracked.
+    pub routable: bool,
+    /// Subnets the peer claims to provide data for.
+    pub subnets: Vec<SubnetID>,
+    /// Bytes served to the address of the peer in the current rate limit period.
+    pub rate_limit_used: u64,
+}
+
+/// Snapshot of the state of the [`Service`], for introspection.
+#[derive(Clone, Debug)]
+pub struct ServiceStats {
+    pub peer_id: PeerId,
+    pub listen_addrs: Vec<Multiaddr>,
+    pub external_addrs: Vec<Multiaddr>,
+    pub connected_peers: usize,
+    pub routable_peers: usize,
+    /// Number of subnets we know providers of.
+    pub known_subnets: usize,
+    /// Subnets this node provides data for.
+    pub provided_subnets: Vec<SubnetID>,
+    /// Subnets which are never pruned from the provider cache.
+    pub pinned_subnets: Vec<SubnetID>,
+    /// Number of CID resolutions in progress.
+    pub running_queries: usize,
+    /// Number of bytes each address can consume in a period; 0 means no limit.
+    pub rate_limit_bytes: u32,
+    pub rate_limit_period: Duration,
+    /// Bytes served to all addresses in the current rate limit period.
+    pub rate_limit_used: u64,
+}
+
 /// The `Service` handles P2P communication to resolve IPLD content by wrapping and driving a number of `libp2p` behaviours.
 pub struct Service<P, V>
 where
@@ -117,7 +176,7 @@ where
     V: Serialize + DeserializeOwned + Send + 'static,
 {
     peer_id: PeerId,
-    listen_addr: Multiaddr,
+    listen_addrs: Vec<Multiaddr>,
     swarm: Swarm<Behaviour<P, V>>,
     /// To match finished queries to response channels.
     queries: QueryMap,
@@ -160,11 +219,21 @@ where
     {
         let peer_id = config.network.local_peer_id();
         let transport = transport(config.network.local_key.clone());
+        let gating = GatingConfig {
+            allow_list: config.connection.allow_list,
+            deny_list: config.connection.deny_list,
+            validator_only: config.connection.validator_only,
+            ban_threshold: config.connection.ban_threshold,
+            ban_duration: config.connection.ban_duration,
+            penalty_decay: config.connection.penalty_decay,
+            max_penalized_peers: config.connection.max_penalized_peers,
+        };
         let behaviour = Behaviour::new(
             config.network,
             config.discovery,
             config.membership,
             config.content,
+            gating,
             store,
         )?;
 
@@ -199,7 +268,7 @@ where
 
         let service = Self {
             peer_id,
-            listen_addr: config.connection.listen_addr,
+            listen_addrs: config.connection.listen_addrs,
             swarm,
             queries: Default::default(),
             request_rx,
@@ -266,8 +335,10 @@ where
     /// Start the swarm listening for incoming connections and drive the events forward.
     pub async fn run(mut self) -> anyhow::Result<()> {
         // Start the swarm.
-        info!("running service on {}", self.listen_addr);
-        Swarm::listen_on(&mut self.swarm, self.listen_addr.clone())?;
+        for addr in self.listen_addrs.iter() {
+            info!("running service on {addr}");
+            Swarm::listen_on(&mut self.swarm, addr.clone())?;
+        }
 
         loop {
             select! {
@@ -277,6 +348,15 @@ where
                         self.handle_behaviour_event(event)
                     },
                     // Connection events are handled by the behaviours, passed directly from the Swarm.
+                    // We only observe them here to keep track of the connections per transport.
+                    Some(SwarmEvent::ConnectionEstablished { endpoint, .. }) => {
+                        let label = transport_label(&endpoint);
+                        stats::TRANSPORT_CONNECTIONS_ESTABLISHED.with_label_values(&[label]).inc();
+                        stats::TRANSPORT_CONNECTIONS.with_label_values(&[label]).inc();
+                    },
+                    Some(SwarmEvent::ConnectionClosed { endpoint, .. }) => {
+                        stats::TRANSPORT_CONNECTIONS.with_label_values(&[transport_label(&endpoint)]).dec();
+                    },
                     Some(_) => { },
                     // The connection is closed.
                     None => { break; },
@@ -296,6 +376,7 @@ where
     /// Handle events that the [`NetworkBehaviour`] macro generated for our [`Behaviour`], one for each field.
     fn handle_behaviour_event(&mut self, event: BehaviourEvent<P, V>) {
         match event {
+            BehaviourEvent::Gating(e) => self.handle_gating_event(e),
             BehaviourEvent::Ping(e) => self.handle_ping_event(e),
             BehaviourEvent::Identify(e) => self.handle_identify_event(e),
             BehaviourEvent::Discovery(e) => self.handle_discovery_event(e),
@@ -330,10 +411,18 @@ where
                 );
             }
             Err(ping::Failure::Unsupported) => {
-                warn!("Should ban peer {peer_id} due to protocol error");
-                // TODO: How do we ban peers in 0.53 ?
-                // see https://github.com/libp2p/rust-libp2p/pull/3590/files
-                // self.swarm.ban_peer_id(event.peer);
+                warn!("Banning peer {peer_id} due to protocol error");
+                self.gating_mut()
+                    .penalize(event.peer, gating::Misbehaviour::UnsupportedProtocol);
+            }
+        }
+    }
+
+    fn handle_gating_event(&mut self, event: gating::Event) {
+        match event {
+            gating::Event::Banned(peer_id, _) => {
+                // Don't try to fetch content from them while they are banned.
+                self.membership_mut().set_unroutable(peer_id)
             }
         }
     }
@@ -394,6 +483,9 @@ where
                     debug!("dropped received preemptive data because there are no subscribers")
                 }
             }
+            membership::Event::InvalidMessage(peer_id) => self
+                .gating_mut()
+                .penalize(peer_id, gating::Misbehaviour::InvalidMessage),
         }
     }
 
@@ -467,13 +559,76 @@ where
             Request::Resolve(cid, subnet_id, response_channel) => {
                 self.start_query(cid, subnet_id, response_channel)
             }
+            Request::ListPeers(tx) => {
+                let peers = self.list_peers();
+                let _ = tx.send(peers);
+            }
+            Request::ListProviders(subnet_id, tx) => {
+                let providers = self.membership_mut().providers_of_subnet(&subnet_id);
+                let _ = tx.send(providers);
+            }
+            Request::Stats(tx) => {
+                let stats = self.stats();
+                let _ = tx.send(stats);
+            }
+            Request::SetValidators(validators) => self.gating_mut().set_validators(validators),
             Request::RateLimitUsed(peer_id, bytes) => {
-                self.content_mut().rate_limit_used(peer_id, bytes)
+                if !self.content_mut().rate_limit_used(peer_id, bytes) {
+                    debug!("peer {peer_id} exceeded the rate limit");
+                    self.gating_mut()
+                        .penalize(peer_id, gating::Misbehaviour::RateLimitExceeded)
+                }
             }
             Request::UpdateRateLimit(bytes) => self.content_mut().update_rate_limit(bytes),
         }
     }
 
+    /// Collect information about the currently connected peers.
+    fn list_peers(&mut self) -> Vec<PeerInfo> {
+        let peer_ids = self.swarm.connected_peers().cloned().collect::<Vec<_>>();
+        let mut peers = Vec::with_capacity(peer_ids.len());
+        for peer_id in peer_ids {
+            let address = self.content_mut().peer_address(&peer_id).cloned();
+            let rate_limit_used = self.content_mut().rate_limit_usage(&peer_id);
+            let routable = self.membership_mut().is_routable(&peer_id);
+            let subnets = self.membership_mut().subnets_of_provider(&peer_id);
+            peers.push(PeerInfo {
+                peer_id,
+                address,
+                routable,
+                subnets,
+                rate_limit_used,
+            });
+        }
+        peers
+    }
+
+    /// Take a snapshot of the state of the service.
+    fn stats(&mut self) -> ServiceStats {
+        let (rate_limit_bytes, rate_limit_period) = self.content_mut().rate_limit();
+        let rate_limit_used = self.content_mut().total_rate_limit_usage();
+        let membership = self.membership_mut();
+        let routable_peers = membership.num_routable();
+        let known_subnets = membership.num_subnets();
+        let provided_subnets = membership.provided_subnets();
+        let pinned_subnets = membership.pinned_subnets();
+
+        ServiceStats {
+            peer_id: self.peer_id,
+            listen_addrs: self.swarm.listeners().cloned().collect(),
+            external_addrs: self.swarm.external_addresses().cloned().collect(),
+            connected_peers: self.swarm.connected_peers().count(),
+            routable_peers,
+            known_subnets,
+            provided_subnets,
+            pinned_subnets,
+            running_queries: self.queries.len(),
+            rate_limit_bytes,
+            rate_limit_period,
+            rate_limit_used,
+        }
+    }
+
     /// Start a CID resolution.
     fn start_query(&mut self, cid: Cid, subnet_id: SubnetID, response_channel: ResponseChannel) {
         let mut peers = self.membership_mut().providers_of_subnet(&subnet_id);
@@ -566,6 +721,9 @@ where
     fn content_mut(&mut self) -> &mut behaviour::content::Behaviour<P> {
         self.swarm.behaviour_mut().content_mut()
     }
+    fn gating_mut(&mut self) -> &mut behaviour::gating::Behaviour {
+        self.swarm.behaviour_mut().gating_mut()
+    }
 }
 
 /// Respond to the sender of the query, if they are still listening.
@@ -574,30 +732,3 @@ fn send_resolve_result(tx: Sender<ResolveResult>, res: ResolveResult) {
         error!("error sending resolve result; listener closed")
     }
 }
-
-/// Builds the transport stack that libp2p will communicate over.
-///
-/// Based on the equivalent in Forest.
-pub fn build_transport(local_key: Keypair) -> Boxed<(PeerId, StreamMuxerBox)> {
-    let tcp_transport =
-        || libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::new().nodelay(true));
-    let transport = libp2p::dns::tokio::Transport::system(tcp_transport()).unwrap();
-    let auth_config = noise::Config::new(&local_key).expect("Noise key generation failed");
-
-    let mplex_config = {
-        let mut mplex_config = MplexConfig::new();
-        mplex_config.set_max_buffer_size(usize::MAX);
-
-        // FIXME: Yamux will end up beaing deprecated.
-        let yamux_config = yamux::Config::default();
-        // yamux_config.set_window_update_mode(WindowUpdateMode::OnRead);
-        libp2p::core::upgrade::SelectUpgrade::new(yamux_config, mplex_config)
-    };
-
-    transport
-        .upgrade(libp2p::core::upgrade::Version::V1)
-        .authenticate(auth_config)
-        .multiplex(mplex_config)
-        .timeout(Duration::from_secs(20))
-        .boxed()
-}
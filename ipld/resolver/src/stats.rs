@@ -1,7 +1,9 @@
 // Copyright 2022-2024 Protocol Labs
 // SPDX-License-Identifier: MIT
 use lazy_static::lazy_static;
-use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
+use prometheus::{
+    Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+};
 
 macro_rules! metrics {
     ($($name:ident : $type:ty = $make:expr);* $(;)?) => {
@@ -112,4 +114,43 @@ metrics! {
         "content_rate_limited",
         "Number of rate limited requests"
     );
+
+    GATING_VALIDATORS: IntGauge =
+        IntGauge::new("gating_validators", "Number of known validator peers");
+
+    GATING_PENALTIES: IntCounter = IntCounter::new(
+        "gating_penalties",
+        "Number of penalties given to misbehaving peers"
+    );
+
+    GATING_BANNED: IntCounter =
+        IntCounter::new("gating_banned", "Number of peers banned");
+
+    GATING_DENIED: IntCounter = IntCounter::new(
+        "gating_denied",
+        "Number of connections denied"
+    );
+
+    TRANSPORT_CONNECTIONS: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("transport_connections", "Number of open connections per transport"),
+        &["transport"]
+    );
+
+    TRANSPORT_CONNECTIONS_ESTABLISHED: IntCounterVec = IntCounterVec::new(
+        Opts::new(
+            "transport_connections_established",
+            "Number of connections established per transport"
+        ),
+        &["transport"]
+    );
+
+    TRANSPORT_BYTES_RECEIVED: IntCounterVec = IntCounterVec::new(
+        Opts::new("transport_bytes_received", "Number of bytes received per transport"),
+        &["transport"]
+    );
+
+    TRANSPORT_BYTES_SENT: IntCounterVec = IntCounterVec::new(
+        Opts::new("transport_bytes_sent", "Number of bytes sent per transport"),
+        &["transport"]
+    );
 }
@@ -0,0 +1,194 @@
+// Copyright 2022-2024 Protocol Labs
+// SPDX-License-Identifier: MIT
+//! Transport stack of the resolver, with per-transport bandwidth metrics.
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use libp2p::core::muxing::{
+    StreamMuxer, StreamMuxerBox, StreamMuxerEvent, StreamMuxerExt, SubstreamBox,
+};
+use libp2p::core::transport::Boxed;
+use libp2p::core::ConnectedPoint;
+use libp2p::futures::future::Either;
+use libp2p::futures::{ready, AsyncRead, AsyncWrite};
+use libp2p::multiaddr::Protocol;
+use libp2p::{identity::Keypair, noise, quic, yamux, Multiaddr, PeerId, Transport};
+use prometheus::IntCounter;
+
+use crate::stats;
+
+/// Maximum number of concurrent Yamux streams on a single connection.
+///
+/// Every stream buffers data up to its receive window, so this bounds the memory used per connection.
+const MAX_YAMUX_STREAMS: usize = 256;
+
+/// Builds the transport stack that libp2p will communicate over.
+///
+/// TCP connections are secured with Noise and multiplexed with Yamux; QUIC has both built in.
+/// Which one is used depends on the addresses we listen on and the ones we dial, e.g.
+/// `/ip4/0.0.0.0/tcp/1234` or `/ip4/0.0.0.0/udp/1234/quic-v1`.
+pub fn build_transport(local_key: Keypair) -> Boxed<(PeerId, StreamMuxerBox)> {
+    let auth_config = noise::Config::new(&local_key).expect("Noise key generation failed");
+
+    let mut yamux_config = yamux::Config::default();
+    yamux_config.set_max_num_streams(MAX_YAMUX_STREAMS);
+
+    let tcp_transport =
+        libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::new().nodelay(true))
+            .upgrade(libp2p::core::upgrade::Version::V1)
+            .authenticate(auth_config)
+            .multiplex(yamux_config)
+            .timeout(Duration::from_secs(20));
+
+    let quic_transport = quic::tokio::Transport::new(quic::Config::new(&local_key));
+
+    let transport = quic_transport
+        .or_transport(tcp_transport)
+        .map(|either, _| match either {
+            Either::Left((peer_id, conn)) => (peer_id, StreamMuxerBox::new(conn)),
+            Either::Right((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+        });
+
+    libp2p::dns::tokio::Transport::system(transport)
+        .expect("DNS config failed")
+        .map(|(peer_id, muxer), endpoint| (peer_id, metered(muxer, &endpoint)))
+        .boxed()
+}
+
+/// Name of the transport a connection uses, to label metrics with.
+pub fn transport_label(endpoint: &ConnectedPoint) -> &'static str {
+    let addr: &Multiaddr = endpoint.get_remote_address();
+    for protocol in addr.iter() {
+        match protocol {
+            Protocol::QuicV1 => return "quic",
+            Protocol::Tcp(_) => return "tcp",
+            Protocol::Memory(_) => return "memory",
+            _ => {}
+        }
+    }
+    "other"
+}
+
+/// Wrap a muxer to count the bytes read and written on all of its substreams.
+fn metered(muxer: StreamMuxerBox, endpoint: &ConnectedPoint) -> StreamMuxerBox {
+    let label = transport_label(endpoint);
+    StreamMuxerBox::new(MeteredMuxer {
+        inner: muxer,
+        counters: Counters {
+            received: stats::TRANSPORT_BYTES_RECEIVED.with_label_values(&[label]),
+            sent: stats::TRANSPORT_BYTES_SENT.with_label_values(&[label]),
+        },
+    })
+}
+
+#[derive(Clone)]
+struct Counters {
+    received: IntCounter,
+    sent: IntCounter,
+}
+
+struct MeteredMuxer {
+    inner: StreamMuxerBox,
+    counters: Counters,
+}
+
+impl StreamMuxer for MeteredMuxer {
+    type Substream = MeteredSubstream;
+    type Error = io::Error;
+
+    fn poll_inbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let inner = ready!(self.inner.poll_inbound_unpin(cx))?;
+        Poll::Ready(Ok(MeteredSubstream {
+            inner,
+            counters: self.counters.clone(),
+        }))
+    }
+
+    fn poll_outbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let inner = ready!(self.inner.poll_outbound_unpin(cx))?;
+        Poll::Ready(Ok(MeteredSubstream {
+            inner,
+            counters: self.counters.clone(),
+        }))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_close_unpin(cx)
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        self.inner.poll_unpin(cx)
+    }
+}
+
+struct MeteredSubstream {
+    inner: SubstreamBox,
+    counters: Counters,
+}
+
+impl AsyncRead for MeteredSubstream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        self.counters.received.inc_by(n as u64);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for MeteredSubstream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = ready!(Pin::new(&mut self.inner).poll_write(cx, buf))?;
+        self.counters.sent.inc_by(n as u64);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libp2p::core::{ConnectedPoint, Endpoint};
+
+    use super::transport_label;
+
+    fn dialer(addr: &str) -> ConnectedPoint {
+        ConnectedPoint::Dialer {
+            address: addr.parse().unwrap(),
+            role_override: Endpoint::Dialer,
+        }
+    }
+
+    #[test]
+    fn label_by_address() {
+        assert_eq!(transport_label(&dialer("/ip4/127.0.0.1/tcp/1234")), "tcp");
+        assert_eq!(
+            transport_label(&dialer("/ip4/127.0.0.1/udp/1234/quic-v1")),
+            "quic"
+        );
+        assert_eq!(transport_label(&dialer("/memory/1234")), "memory");
+    }
+}
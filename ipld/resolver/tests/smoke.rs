@@ -18,6 +18,7 @@
 // (although these might be orthogonal).
 
 use std::{
+    net::{Ipv4Addr, UdpSocket},
     sync::atomic::{AtomicU64, Ordering},
     time::Duration,
 };
@@ -109,7 +110,7 @@ impl ClusterBuilder {
         let bootstrap_addr = bootstrap.map(|i| {
             let config = &self.agents[i].config;
             let peer_id = config.network.local_peer_id();
-            let mut addr = config.connection.listen_addr.clone();
+            let mut addr = config.connection.listen_addrs[0].clone();
             addr.push(Protocol::P2p(peer_id));
             addr
         });
@@ -239,7 +240,7 @@ async fn single_bootstrap_publish_receive_vote() {
         .expect("error receiving vote");
 
     if let Event::ReceivedVote(v) = event {
-        assert_eq!(&*v, vote.record());
+        assert_eq!(v.record(), vote.record());
     } else {
         panic!("unexpected {event:?}")
     }
@@ -284,6 +285,107 @@ async fn single_bootstrap_publish_receive_preemptive() {
     }
 }
 
+/// Start two agents, provide a subnet on one and check that the other one knows about it.
+#[tokio::test]
+async fn single_bootstrap_introspection() {
+    init_log();
+
+    let cluster = make_cluster_with_bootstrap(2, 0).await;
+
+    let subnet_id = make_subnet_id(1001);
+    let provider_id = cluster.agents[1].config.network.local_peer_id();
+
+    cluster.agents[1]
+        .client
+        .add_provided_subnet(subnet_id.clone())
+        .expect("failed to add provided subnet");
+
+    // TODO: Wait on some condition instead of sleep.
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let stats = cluster.agents[1]
+        .client
+        .stats()
+        .await
+        .expect("failed to get stats");
+
+    assert_eq!(stats.peer_id, provider_id);
+    assert_eq!(stats.provided_subnets, vec![subnet_id.clone()]);
+    assert!(stats.connected_peers > 0);
+
+    let providers = cluster.agents[0]
+        .client
+        .list_providers(subnet_id.clone())
+        .await
+        .expect("failed to list providers");
+
+    assert_eq!(providers, vec![provider_id]);
+
+    let peers = cluster.agents[0]
+        .client
+        .list_peers()
+        .await
+        .expect("failed to list peers");
+
+    let peer = peers
+        .into_iter()
+        .find(|p| p.peer_id == provider_id)
+        .expect("provider is connected");
+
+    assert!(peer.routable);
+    assert_eq!(peer.subnets, vec![subnet_id]);
+}
+
+/// Start two nodes with the real transport and check that they connect over QUIC,
+/// with the bootstrap node listening on TCP at the same time.
+#[tokio::test]
+async fn can_connect_over_quic() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+    let quic_addr = |port: u16| {
+        Multiaddr::from(Ipv4Addr::LOCALHOST)
+            .with(Protocol::Udp(port))
+            .with(Protocol::QuicV1)
+    };
+
+    let mut config0 = make_config(&mut rng, 2, None);
+    let peer_id0 = config0.network.local_peer_id();
+    let addr0 = quic_addr(free_udp_port());
+    config0.connection.listen_addrs = vec![
+        addr0.clone(),
+        Multiaddr::from(Ipv4Addr::LOCALHOST).with(Protocol::Tcp(0)),
+    ];
+
+    let mut config1 = make_config(&mut rng, 2, Some(addr0.with(Protocol::P2p(peer_id0))));
+    config1.connection.listen_addrs = vec![quic_addr(free_udp_port())];
+
+    let service0 = Service::<TestStoreParams, TestVote>::new(config0, TestBlockstore::default())
+        .expect("failed to create service");
+    let service1 = Service::<TestStoreParams, TestVote>::new(config1, TestBlockstore::default())
+        .expect("failed to create service");
+    let client1 = service1.client();
+
+    for service in [service0, service1] {
+        tokio::task::spawn(async move { service.run().await.expect("error running service") });
+    }
+
+    // TODO: Wait on some condition instead of sleep.
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let peers = client1.list_peers().await.expect("failed to list peers");
+
+    let peer = peers
+        .into_iter()
+        .find(|p| p.peer_id == peer_id0)
+        .expect("bootstrap node is connected");
+
+    let address = peer.address.expect("connected address is known");
+    assert!(
+        address.iter().any(|p| p == Protocol::QuicV1),
+        "not connected over QUIC: {address}"
+    );
+}
+
 #[tokio::test]
 async fn can_register_metrics() {
     let mut rng = rand::rngs::StdRng::seed_from_u64(0);
@@ -317,12 +419,19 @@ fn make_service(config: Config) -> (Service<TestStoreParams, TestVote>, TestBloc
 fn make_config(rng: &mut StdRng, cluster_size: u32, bootstrap_addr: Option<Multiaddr>) -> Config {
     let config = Config {
         connection: ConnectionConfig {
-            listen_addr: Multiaddr::from(Protocol::Memory(rng.gen::<u64>())),
+            listen_addrs: vec![Multiaddr::from(Protocol::Memory(rng.gen::<u64>()))],
             external_addresses: vec![],
             expected_peer_count: cluster_size,
             max_incoming: cluster_size,
             max_peers_per_query: cluster_size,
             event_buffer_capacity: cluster_size,
+            allow_list: vec![],
+            deny_list: vec![],
+            validator_only: false,
+            ban_threshold: 100,
+            ban_duration: Duration::from_secs(60),
+            penalty_decay: Duration::from_secs(60),
+            max_penalized_peers: 1000,
         },
         network: NetworkConfig {
             local_key: Keypair::generate_secp256k1(),
@@ -353,24 +462,21 @@ fn make_config(rng: &mut StdRng, cluster_size: u32, bootstrap_addr: Option<Multi
 fn build_transport(local_key: Keypair) -> Boxed<(PeerId, StreamMuxerBox)> {
     let auth_config = plaintext::Config::new(&local_key);
 
-    let mplex_config = {
-        let mut mplex_config = libp2p_mplex::MplexConfig::new();
-        mplex_config.set_max_buffer_size(usize::MAX);
-
-        let yamux_config = yamux::Config::default();
-        // yamux_config.set_receive_window_size(16 * 1024 * 1024);
-        // yamux_config.set_max_buffer_size(16 * 1024 * 1024);
-        // yamux_config.set_window_update_mode(WindowUpdateMode::OnRead);
-        libp2p::core::upgrade::SelectUpgrade::new(yamux_config, mplex_config)
-    };
-
     MemoryTransport::default()
         .upgrade(libp2p::core::upgrade::Version::V1)
         .authenticate(auth_config)
-        .multiplex(mplex_config)
+        .multiplex(yamux::Config::default())
         .boxed()
 }
 
+/// Find a UDP port on the loopback interface that is free to listen on.
+fn free_udp_port() -> u16 {
+    UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|socket| socket.local_addr())
+        .expect("failed to bind UDP socket")
+        .port()
+}
+
 /// Make a subnet under a rootnet.
 fn make_subnet_id(actor_id: ActorID) -> SubnetID {
     let act = Address::new_id(actor_id);